@@ -13,11 +13,14 @@
 --
 -- Storage: RDF-native with typed columns for performance
 -- - RDF Triple columns: subject, predicate, object (IRI), object_value (literal)
+-- - IRIs interned in the terms table; quads hold their integer IDs, and the
+--   triples view puts the strings back
 -- - Typed columns: object_number, object_integer, object_datetime for range queries
 -- - Full RDF compatibility: Export to Turtle/JSON-LD without transformation
 -- ============================================================================
 
-PRAGMA journal_mode = DELETE;
+-- The journal mode (WAL) is set by eavto::connection::configure, not here:
+-- this file also runs on existing databases during migrations
 PRAGMA foreign_keys = ON;
 PRAGMA synchronous = NORMAL;
 
@@ -30,31 +33,80 @@ PRAGMA synchronous = NORMAL;
 CREATE TABLE IF NOT EXISTS transactions (
   tx INTEGER PRIMARY KEY AUTOINCREMENT,  -- Transaction ID (logical timestamp)
   origin TEXT NOT NULL,                   -- Who initiated this transaction
-  created_at INTEGER NOT NULL             -- Physical timestamp (Unix epoch milliseconds)
+  created_at INTEGER NOT NULL,            -- Physical timestamp (Unix epoch milliseconds)
+  received_at INTEGER,                    -- Local time a transaction made on another device was received
+                                          -- (NULL: made here); its created_at is then the other device's claim
+  message TEXT,                           -- Optional human-readable description ("Imported March bank statement")
+  tags TEXT,                              -- Optional JSON array of tags (["finance", "import"])
+  signature TEXT,                         -- Optional ECDSA signature (hex) of the transaction's canonical
+                                          -- digest, made with the signer's identity key
+  signer TEXT                             -- Public key (hex) of the signer (NULL: unsigned)
 );
 
 CREATE INDEX IF NOT EXISTS idx_tx_created ON transactions(created_at);
 CREATE INDEX IF NOT EXISTS idx_tx_origin ON transactions(origin);
 
 -- ============================================================================
--- Triples Table (Immutable, Append-Only, RDF-Native)
+-- Terms Table (IRI Dictionary)
 -- ============================================================================
--- Core data structure: every piece of information is an RDF triple
--- Triples are NEVER updated or deleted, only retracted and replaced
+-- Every IRI or blank node used as a subject, predicate or IRI object is stored
+-- once here; triples refer to it by integer ID (like origins). Rows and
+-- indices hold integers instead of repeating the strings, and joins compare
+-- integers. Terms are never deleted: an ID, once given, keeps its value.
+
+CREATE TABLE IF NOT EXISTS terms (
+  id INTEGER PRIMARY KEY,
+  value TEXT NOT NULL UNIQUE       -- IRI, blank node or quoted triple (e.g., "foundation:Person", "_:b1",
+                                   -- "<<( <s> <p> "o" )>>", see eavto::statement)
+);
+
+-- ============================================================================
+-- Statements Table
+-- ============================================================================
+-- Quoted triples (RDF-star): facts talked about, e.g. with a confidence or a
+-- note, without being asserted. A quoted triple's term ID is its statement
+-- ID; this table maps it to the IDs of the fact's terms, so the facts quoted
+-- about an entity are found by index.
+
+CREATE TABLE IF NOT EXISTS statements (
+  id INTEGER PRIMARY KEY,          -- The quoted triple's term (terms.id)
+  subject_id INTEGER NOT NULL,     -- terms.id
+  predicate_id INTEGER NOT NULL,   -- terms.id
+  object_id INTEGER,               -- terms.id (NULL: a literal, spelled out in the quoted triple's term)
+
+  FOREIGN KEY (id) REFERENCES terms(id),
+  FOREIGN KEY (subject_id) REFERENCES terms(id),
+  FOREIGN KEY (predicate_id) REFERENCES terms(id),
+  FOREIGN KEY (object_id) REFERENCES terms(id)
+);
+
+CREATE INDEX IF NOT EXISTS idx_statements_subject ON statements(subject_id, predicate_id);
+
+-- ============================================================================
+-- Quads Table (Immutable, Append-Only, RDF-Native)
+-- ============================================================================
+-- Core data structure: every piece of information is an RDF triple, in a
+-- named graph. Triples are NEVER deleted, only retracted and replaced.
+-- Queries read them through the triples view.
+
+CREATE TABLE IF NOT EXISTS quads (
+  id INTEGER PRIMARY KEY,          -- Insertion order (exposed as triples.rowid)
 
-CREATE TABLE IF NOT EXISTS triples (
   -- RDF Triple (core)
-  subject TEXT NOT NULL,           -- IRI or blank node (e.g., "ex:transaction_tx001", "_:b1")
-  predicate TEXT NOT NULL,         -- IRI for property (e.g., "ex:amount", "rdf:type")
+  subject_id INTEGER NOT NULL,     -- IRI or blank node (terms.id)
+  predicate_id INTEGER NOT NULL,   -- IRI for property (terms.id)
 
   -- Object (one of three forms based on object_type)
-  object TEXT,                     -- IRI or blank node (if object_type = 'iri' or 'blank')
+  object_id INTEGER,               -- IRI or blank node (terms.id, if object_type = 'iri' or 'blank')
   object_value TEXT,               -- Literal lexical form (if object_type = 'literal')
   object_datatype TEXT,            -- Datatype IRI (e.g., "xsd:decimal", "xsd:string")
   object_language TEXT,            -- Language tag (e.g., "en", "pt", NULL if not language-tagged)
 
   object_type TEXT NOT NULL CHECK(object_type IN ('iri', 'literal', 'blank')),
 
+  -- Dataset dimension: named graph (imported ontologies, user data, peer data)
+  graph TEXT NOT NULL DEFAULT 'foundation:DefaultGraph',
+
   -- Performance optimization: typed columns (NULL if not applicable)
   object_number REAL,              -- Populated for xsd:decimal, xsd:double, xsd:float
   object_integer INTEGER,          -- Populated for xsd:integer, xsd:int, xsd:long
@@ -66,19 +118,24 @@ CREATE TABLE IF NOT EXISTS triples (
   origin_id INTEGER NOT NULL,      -- Origin ID (references origins.id)
   retracted INTEGER NOT NULL DEFAULT 0,  -- 0 = active, 1 = retracted
   created_at INTEGER NOT NULL,     -- Physical timestamp (Unix epoch milliseconds)
+  retracted_tx INTEGER,            -- Transaction that retracted it (NULL: current, copied from another
+                                   -- database, or retracted before schema version 8)
 
+  FOREIGN KEY (subject_id) REFERENCES terms(id),
+  FOREIGN KEY (predicate_id) REFERENCES terms(id),
+  FOREIGN KEY (object_id) REFERENCES terms(id),
   FOREIGN KEY (origin_id) REFERENCES origins(id),
 
   -- Consistency constraints
   CHECK (
     -- IRI: object must be populated, object_value must be NULL
-    (object_type = 'iri' AND object IS NOT NULL AND object_value IS NULL) OR
+    (object_type = 'iri' AND object_id IS NOT NULL AND object_value IS NULL) OR
 
     -- Literal: object_value and object_datatype must be populated, object must be NULL
-    (object_type = 'literal' AND object_value IS NOT NULL AND object_datatype IS NOT NULL AND object IS NULL) OR
+    (object_type = 'literal' AND object_value IS NOT NULL AND object_datatype IS NOT NULL AND object_id IS NULL) OR
 
     -- Blank node: object must be populated, object_value must be NULL
-    (object_type = 'blank' AND object IS NOT NULL AND object_value IS NULL)
+    (object_type = 'blank' AND object_id IS NOT NULL AND object_value IS NULL)
   ),
 
   -- Typed columns consistency
@@ -94,21 +151,14 @@ CREATE TABLE IF NOT EXISTS triples (
 );
 
 -- ============================================================================
--- SPO Indices (Four Covering Indices for RDF Triple Queries)
+-- SPO Indices
 -- ============================================================================
--- These indices cover all common RDF access patterns without table lookups
-
--- Index 1: SPO (Subject-Predicate-Object) - Find all triples about a subject (most common query)
-CREATE INDEX IF NOT EXISTS idx_spo ON triples(subject, predicate, object, object_value, tx, origin_id);
-
--- Index 2: POS (Predicate-Object-Subject) - Find all subjects with a specific predicate-object
-CREATE INDEX IF NOT EXISTS idx_pos ON triples(predicate, object, object_value, subject, tx, origin_id);
+-- The covering indices for RDF triple queries (SPO, POS, OSP, OPS, and the
+-- same over current quads) are created by eavto::indexes when a database
+-- is opened
 
--- Index 3: OSP (Object-Subject-Predicate) - Find subjects by object (reverse lookup for IRIs)
-CREATE INDEX IF NOT EXISTS idx_osp ON triples(object, subject, predicate, tx, origin_id) WHERE object_type = 'iri';
-
--- Index 4: OPS (Object-Predicate-Subject) - Find all triples referencing an object (backlinks)
-CREATE INDEX IF NOT EXISTS idx_ops ON triples(object, predicate, subject, tx, origin_id) WHERE object_type = 'iri';
+-- Graph: all triples of a named graph
+CREATE INDEX IF NOT EXISTS idx_graph ON quads(graph, subject_id);
 
 -- ============================================================================
 -- Performance Indices for Typed Columns
@@ -116,22 +166,82 @@ CREATE INDEX IF NOT EXISTS idx_ops ON triples(object, predicate, subject, tx, or
 -- Additional indices for range queries on numeric/temporal data
 
 -- Numeric range queries (e.g., amount > 100)
-CREATE INDEX IF NOT EXISTS idx_predicate_number ON triples(predicate, object_number, tx)
+CREATE INDEX IF NOT EXISTS idx_predicate_number ON quads(predicate_id, object_number, tx)
   WHERE object_type = 'literal' AND object_datatype IN ('xsd:decimal', 'xsd:double', 'xsd:float') AND retracted = 0;
 
 -- Integer range queries (e.g., age >= 18)
-CREATE INDEX IF NOT EXISTS idx_predicate_integer ON triples(predicate, object_integer, tx)
+CREATE INDEX IF NOT EXISTS idx_predicate_integer ON quads(predicate_id, object_integer, tx)
   WHERE object_type = 'literal' AND object_datatype IN ('xsd:integer', 'xsd:int', 'xsd:long') AND retracted = 0;
 
 -- Temporal range queries (e.g., created_at >= X AND created_at < Y)
-CREATE INDEX IF NOT EXISTS idx_predicate_datetime ON triples(predicate, object_datetime, tx)
+CREATE INDEX IF NOT EXISTS idx_predicate_datetime ON quads(predicate_id, object_datetime, tx)
   WHERE object_type = 'literal' AND object_datatype = 'xsd:dateTime' AND retracted = 0;
 
 -- Retraction queries (find active triples for a subject)
-CREATE INDEX IF NOT EXISTS idx_subject_retracted ON triples(subject, retracted, tx);
+CREATE INDEX IF NOT EXISTS idx_subject_retracted ON quads(subject_id, retracted, tx);
 
 -- Transaction queries (find all triples in a transaction)
-CREATE INDEX IF NOT EXISTS idx_tx ON triples(tx);
+CREATE INDEX IF NOT EXISTS idx_tx ON quads(tx);
+
+-- Retractions by transaction (diffs between transactions)
+CREATE INDEX IF NOT EXISTS idx_retracted_tx ON quads(retracted_tx) WHERE retracted_tx IS NOT NULL;
+
+-- ============================================================================
+-- Triples View
+-- ============================================================================
+-- The quads with their terms resolved: SQL reads (and writes) triples with
+-- string subjects, predicates and objects, as before interning. SQLite
+-- resolves `WHERE subject = ?` to a terms lookup followed by an integer
+-- index search. Writes go through the triggers below, which intern new
+-- terms; eavto::store writes the quads directly (and gets row counts).
+-- There is no DELETE trigger: triples are never deleted.
+
+CREATE VIEW IF NOT EXISTS triples AS
+SELECT
+  s.value AS subject, p.value AS predicate, o.value AS object,
+  q.object_value, q.object_datatype, q.object_language, q.object_type, q.graph,
+  q.object_number, q.object_integer, q.object_datetime, q.object_boolean,
+  q.tx, q.origin_id, q.retracted, q.created_at, q.retracted_tx,
+  q.id AS rowid
+FROM quads q
+JOIN terms s ON s.id = q.subject_id
+JOIN terms p ON p.id = q.predicate_id
+LEFT JOIN terms o ON o.id = q.object_id;
+
+CREATE TRIGGER IF NOT EXISTS triples_insert INSTEAD OF INSERT ON triples
+BEGIN
+  INSERT OR IGNORE INTO terms (value)
+    SELECT value FROM (SELECT NEW.subject AS value UNION ALL SELECT NEW.predicate UNION ALL SELECT NEW.object)
+    WHERE value IS NOT NULL;
+  INSERT INTO quads (subject_id, predicate_id, object_id, object_value, object_datatype, object_language,
+                     object_type, graph, object_number, object_integer, object_datetime, object_boolean,
+                     tx, origin_id, retracted, created_at, retracted_tx)
+  VALUES ((SELECT id FROM terms WHERE value = NEW.subject),
+          (SELECT id FROM terms WHERE value = NEW.predicate),
+          (SELECT id FROM terms WHERE value = NEW.object),
+          NEW.object_value, NEW.object_datatype, NEW.object_language,
+          NEW.object_type, COALESCE(NEW.graph, 'foundation:DefaultGraph'),
+          NEW.object_number, NEW.object_integer, NEW.object_datetime, NEW.object_boolean,
+          NEW.tx, NEW.origin_id, COALESCE(NEW.retracted, 0), NEW.created_at, NEW.retracted_tx);
+END;
+
+CREATE TRIGGER IF NOT EXISTS triples_update INSTEAD OF UPDATE ON triples
+BEGIN
+  INSERT OR IGNORE INTO terms (value)
+    SELECT value FROM (SELECT NEW.subject AS value UNION ALL SELECT NEW.predicate UNION ALL SELECT NEW.object)
+    WHERE value IS NOT NULL;
+  UPDATE quads SET
+    subject_id = (SELECT id FROM terms WHERE value = NEW.subject),
+    predicate_id = (SELECT id FROM terms WHERE value = NEW.predicate),
+    object_id = (SELECT id FROM terms WHERE value = NEW.object),
+    object_value = NEW.object_value, object_datatype = NEW.object_datatype, object_language = NEW.object_language,
+    object_type = NEW.object_type, graph = NEW.graph,
+    object_number = NEW.object_number, object_integer = NEW.object_integer,
+    object_datetime = NEW.object_datetime, object_boolean = NEW.object_boolean,
+    tx = NEW.tx, origin_id = NEW.origin_id, retracted = NEW.retracted,
+    created_at = NEW.created_at, retracted_tx = NEW.retracted_tx
+  WHERE id = OLD.rowid;
+END;
 
 -- ============================================================================
 -- Namespaces Table
@@ -161,11 +271,16 @@ INSERT OR IGNORE INTO namespaces (prefix, iri) VALUES
 -- Store origin identifiers for triples
 -- Instead of repeating origin strings thousands of times,
 -- we store an integer ID and join when needed
+--
+-- Status: triples from untrusted sources (web fetch, sync from a new peer)
+-- land in a 'quarantined' origin, hidden from normal queries until reviewed.
+-- Approving makes the origin 'active'; rejecting retracts its triples.
 
 CREATE TABLE IF NOT EXISTS origins (
   id INTEGER PRIMARY KEY AUTOINCREMENT,
   name TEXT NOT NULL UNIQUE,
-  description TEXT
+  description TEXT,
+  status TEXT NOT NULL DEFAULT 'active' CHECK(status IN ('active', 'quarantined', 'rejected'))
 );
 
 -- Initialize common origins
@@ -174,6 +289,107 @@ INSERT OR IGNORE INTO origins (id, name, description) VALUES
   (2, 'foundation:CurrentUser', 'Data provided by the current user'),
   (3, 'foundation:FOUNDATION', 'Data collected automatically by FOUNDATION application');
 
+-- ============================================================================
+-- Attachment Text
+-- ============================================================================
+-- Full-text index of the text extracted from attachments (PDF, plain text),
+-- keyed by the foundation:Attachment entity. The text is derived data and
+-- lives outside the triple store: re-indexing an attachment replaces it.
+
+CREATE VIRTUAL TABLE IF NOT EXISTS attachment_text USING fts5(
+  entity UNINDEXED,
+  text,
+  tokenize = 'unicode61 remove_diacritics 2'
+);
+
+-- ============================================================================
+-- API Tokens
+-- ============================================================================
+-- Personal tokens local scripts use to authenticate to the HTTP API. Only a
+-- SHA-256 hash of each token is kept: the token is shown once, when created.
+-- Tokens are credentials of this device, so they live outside the triple
+-- store and never sync.
+
+CREATE TABLE IF NOT EXISTS api_tokens (
+  id INTEGER PRIMARY KEY AUTOINCREMENT,
+  name TEXT NOT NULL,                  -- What the token is for ("backup script")
+  token_hash TEXT NOT NULL UNIQUE,     -- SHA-256 of the token (hex)
+  prefix TEXT NOT NULL,                -- First characters of the token, to tell tokens apart
+  scopes TEXT NOT NULL,                -- JSON array of scopes (["read", "import"])
+  created_at INTEGER NOT NULL,         -- Unix epoch milliseconds
+  expires_at INTEGER,                  -- NULL: never expires
+  revoked_at INTEGER,                  -- NULL: active
+  last_used_at INTEGER
+);
+
+-- ============================================================================
+-- Outbox
+-- ============================================================================
+-- Outbound work (queries to public endpoints, feed fetches, uploads) waiting
+-- for the network. Jobs survive restarts and offline periods: a failed
+-- attempt is retried later, with exponential backoff, until it succeeds or
+-- runs out of attempts. Like API tokens, jobs belong to this device.
+
+CREATE TABLE IF NOT EXISTS outbox (
+  id INTEGER PRIMARY KEY AUTOINCREMENT,
+  kind TEXT NOT NULL,                  -- Handler of the job ("federated_query")
+  payload TEXT NOT NULL,               -- JSON arguments of the handler
+  status TEXT NOT NULL DEFAULT 'pending' CHECK (status IN ('pending', 'done', 'failed', 'cancelled')),
+  attempts INTEGER NOT NULL DEFAULT 0,
+  next_attempt_at INTEGER NOT NULL,    -- Unix epoch milliseconds
+  last_error TEXT,
+  created_at INTEGER NOT NULL,
+  completed_at INTEGER
+);
+
+CREATE INDEX IF NOT EXISTS idx_outbox_due ON outbox(status, next_attempt_at);
+
+-- ============================================================================
+-- Blob Store
+-- ============================================================================
+-- Content of literals over the literal size limit (see eavto::blob). The
+-- triple holds a blob:{hash} reference instead, keeping the triples table
+-- compact. Content-addressed: a value is stored once, however many triples
+-- refer to it.
+
+CREATE TABLE IF NOT EXISTS blobs (
+  hash TEXT PRIMARY KEY,               -- SHA-256 of datatype, language and content (hex)
+  content TEXT NOT NULL,
+  datatype TEXT,
+  language TEXT,
+  size INTEGER NOT NULL,               -- Bytes
+  created_at INTEGER NOT NULL          -- Unix epoch milliseconds
+);
+
+-- ============================================================================
+-- Sync Changesets Table
+-- ============================================================================
+-- Content ids of the changesets exchanged with other instances (see sync),
+-- so a transaction is applied once, whichever device sends it
+
+CREATE TABLE IF NOT EXISTS sync_changesets (
+  id TEXT PRIMARY KEY,             -- Content hash of the changeset
+  tx INTEGER,                      -- Its transaction here (NULL: it had nothing new)
+  peer TEXT,                       -- Sync id of the instance it came from (NULL: made here)
+  recorded_at INTEGER NOT NULL     -- Unix epoch milliseconds
+);
+
+-- ============================================================================
+-- Write Journal Table
+-- ============================================================================
+-- The latest writes and the commands that made them (see eavto::journal), to
+-- trace a corruption report back to the operations that produced it
+
+CREATE TABLE IF NOT EXISTS write_journal (
+  id INTEGER PRIMARY KEY AUTOINCREMENT,
+  command TEXT NOT NULL,           -- Command (or function) that wrote
+  args_hash TEXT,                  -- SHA-256 of its arguments (NULL: unknown)
+  tx INTEGER,                      -- Transaction written (NULL: none, or failed)
+  duration_ms INTEGER NOT NULL,
+  error TEXT,                      -- Error returned (NULL: succeeded)
+  recorded_at INTEGER NOT NULL     -- Unix epoch milliseconds
+);
+
 -- ============================================================================
 -- Metadata Table
 -- ============================================================================
@@ -187,7 +403,7 @@ CREATE TABLE IF NOT EXISTS metadata (
 
 -- Initialize metadata
 INSERT OR IGNORE INTO metadata (key, value, updated_at) VALUES
-  ('schema_version', '3', strftime('%s', 'now') * 1000),
+  ('schema_version', '19', strftime('%s', 'now') * 1000),
   ('created_at', strftime('%s', 'now') * 1000, strftime('%s', 'now') * 1000),
   ('ontology_imported', 'false', strftime('%s', 'now') * 1000);
 
@@ -204,7 +420,9 @@ CREATE TABLE IF NOT EXISTS ontology_files (
   last_modified INTEGER NOT NULL,      -- Unix timestamp (seconds) when file was last modified on disk
   last_imported INTEGER NOT NULL,      -- Unix timestamp (seconds) when file was last imported into DB
   checksum TEXT NOT NULL,              -- SHA-256 hash of file contents for integrity verification
-  triple_count INTEGER NOT NULL        -- Number of triples imported from this file
+  triple_count INTEGER NOT NULL,       -- Number of triples imported from this file
+  source_url TEXT,                     -- URL the file was downloaded from (NULL: local file)
+  etag TEXT                            -- ETag of that download, sent back to skip unchanged ontologies
 );
 
 CREATE INDEX IF NOT EXISTS idx_ontology_files_name ON ontology_files(file_name);
@@ -0,0 +1,26 @@
+// Bundles the core-ontology files into the crate (turtle::bundled), for app
+// shells that can't read them from the source tree (mobile, installed builds)
+
+use std::{env, fs, path::Path};
+
+fn main() {
+    let manifest_dir = env::var("CARGO_MANIFEST_DIR").unwrap();
+    let dir = Path::new(&manifest_dir).join("../../core-ontology").canonicalize()
+        .expect("core-ontology directory not found");
+    println!("cargo:rerun-if-changed={}", dir.display());
+
+    let mut files: Vec<_> = fs::read_dir(&dir).unwrap()
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| path.extension().is_some_and(|ext| ext == "ttl"))
+        .collect();
+    files.sort();
+
+    let mut out = String::from("/// Core ontology files: (file name, content)\npub const FILES: &[(&str, &str)] = &[\n");
+    for path in &files {
+        let name = path.file_name().unwrap().to_string_lossy();
+        out.push_str(&format!("    ({:?}, include_str!({:?})),\n", name, path.display().to_string()));
+    }
+    out.push_str("];\n");
+
+    fs::write(Path::new(&env::var("OUT_DIR").unwrap()).join("bundled_ontologies.rs"), out).unwrap();
+}
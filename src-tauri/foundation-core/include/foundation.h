@@ -0,0 +1,56 @@
+/*
+ * FOUNDATION C ABI (src/ffi.rs)
+ *
+ * Link against libfoundation_core (static or shared). Strings passed in are
+ * NUL-terminated UTF-8, borrowed for the call; strings handed out are JSON
+ * and must be released with foundation_string_free.
+ */
+
+#ifndef FOUNDATION_H
+#define FOUNDATION_H
+
+#include <stdint.h>
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+typedef enum FoundationStatus {
+    FOUNDATION_OK = 0,
+    FOUNDATION_INVALID_ARGUMENT = 1,
+    FOUNDATION_DATABASE = 2,
+    FOUNDATION_SCHEMA = 3,
+    FOUNDATION_IO = 4,
+    FOUNDATION_VALIDATION = 5,
+    FOUNDATION_NOT_FOUND = 6,
+    FOUNDATION_INVALID_OPERATION = 7,
+    FOUNDATION_PANIC = 8,
+} FoundationStatus;
+
+typedef struct FoundationDb FoundationDb;
+
+FoundationStatus foundation_open(const char *path, FoundationDb **out);
+void foundation_close(FoundationDb *db);
+
+/* datatype and language may be NULL (the object is then an IRI); out_tx may be NULL */
+FoundationStatus foundation_assert(const FoundationDb *db,
+                                   const char *subject, const char *predicate, const char *object,
+                                   const char *datatype, const char *language,
+                                   const char *origin, int64_t *out_tx);
+
+/* {"variables": [...], "rows": [[...]], "truncated": bool}; limit 0: the query's own */
+FoundationStatus foundation_query(const FoundationDb *db, const char *query, uint32_t limit, char **out_json);
+
+/* [{"id", "label", "kind", "type"}] */
+FoundationStatus foundation_search(const FoundationDb *db, const char *query, uint32_t limit, char **out_json);
+
+void foundation_string_free(char *s);
+
+/* Message of the last failed call on this thread, or NULL; not to be freed */
+const char *foundation_last_error(void);
+
+#ifdef __cplusplus
+}
+#endif
+
+#endif
@@ -0,0 +1,218 @@
+//! Typed Column Backfill
+//!
+//! Literals with a numeric, boolean or temporal (dateTime, date, time)
+//! datatype keep their value in a typed column too (object_number,
+//! object_integer, object_boolean, object_datetime in epoch milliseconds,
+//! see XsdType::epoch_millis), which range queries and sorting read. Rows written
+//! before a datatype got its column, or by older importers, have the column
+//! NULL; asserting one prints a warning (see store::assert_triples).
+//!
+//! The backfill finds those rows, parses their lexical form and fills the
+//! column. Rows whose value doesn't parse as their datatype are reported
+//! instead, for the user to fix: the store is append-only, so the literal
+//! itself is never rewritten, only the column derived from it.
+//!
+//! Before schema version 18, dateTime columns held seconds and date and time
+//! columns were left empty; `migrate_temporal_columns` brings them to
+//! milliseconds.
+
+use rusqlite::Connection;
+use super::xsd_type::TypedValue;
+
+type Result<T> = std::result::Result<T, Box<dyn std::error::Error>>;
+
+/// Rows with a typed datatype and an empty typed column
+const MISSING: &str = "(object_datatype IN ('xsd:decimal', 'xsd:double', 'xsd:float') AND object_number IS NULL)
+    OR (object_datatype IN ('xsd:integer', 'xsd:int', 'xsd:long') AND object_integer IS NULL)
+    OR (object_datatype = 'xsd:boolean' AND object_boolean IS NULL)
+    OR (object_datatype IN ('xsd:dateTime', 'xsd:date', 'xsd:time') AND object_datetime IS NULL)";
+
+/// Values below which a dateTime column written before version 18 is in
+/// seconds (10^11 s is in the year 5138, 10^11 ms in 1973)
+const SECONDS_BELOW: i64 = 100_000_000_000;
+
+/// A row the backfill couldn't fix: its value isn't valid for its datatype
+#[derive(Debug, Clone, PartialEq)]
+pub struct UnfixableRow {
+    pub subject: String,
+    pub predicate: String,
+    pub value: String,
+    pub datatype: String,
+    pub tx: i64,
+}
+
+/// What a backfill did
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct BackfillReport {
+    pub scanned: usize, // Rows with a missing typed column
+    pub fixed: usize,
+    pub unfixable: Vec<UnfixableRow>,
+}
+
+/// Number of rows with a missing typed column, current and retracted
+pub fn count_missing(conn: &Connection) -> Result<usize> {
+    let count: i64 = conn.query_row(&format!("SELECT COUNT(*) FROM triples WHERE {}", MISSING), [], |row| row.get(0))?;
+    Ok(count as usize)
+}
+
+/// Fill the missing typed columns whose value parses, as one transaction
+pub fn backfill_typed_columns(conn: &mut Connection) -> Result<BackfillReport> {
+    let tx = conn.transaction()?;
+    let rows: Vec<(i64, String, String, String, String, i64)> = tx.prepare(&format!(
+        "SELECT rowid, subject, predicate, COALESCE(object_value, ''), object_datatype, tx
+         FROM triples WHERE {} ORDER BY tx",
+        MISSING
+    ))?
+        .query_map([], |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?, row.get(4)?, row.get(5)?)))?
+        .collect::<std::result::Result<_, _>>()?;
+
+    let mut report = BackfillReport { scanned: rows.len(), ..Default::default() };
+    for (rowid, subject, predicate, value, datatype, tx_id) in rows {
+        match TypedValue::parse(&value, &datatype) {
+            Some(TypedValue::Number(n)) => tx.execute("UPDATE triples SET object_number = ? WHERE rowid = ?", (n, rowid))?,
+            Some(TypedValue::Integer(i)) => tx.execute("UPDATE triples SET object_integer = ? WHERE rowid = ?", (i, rowid))?,
+            Some(TypedValue::Boolean(b)) => tx.execute("UPDATE triples SET object_boolean = ? WHERE rowid = ?", (b as i64, rowid))?,
+            Some(TypedValue::DateTime(ms)) => tx.execute("UPDATE triples SET object_datetime = ? WHERE rowid = ?", (ms, rowid))?,
+            None => {
+                report.unfixable.push(UnfixableRow { subject, predicate, value, datatype, tx: tx_id });
+                continue;
+            }
+        };
+        report.fixed += 1;
+    }
+
+    tx.commit()?;
+    Ok(report)
+}
+
+/// Bring the temporal columns written before schema version 18 to epoch
+/// milliseconds, returning the rows changed:
+/// - dateTime literals in their lexical form get the column parsed again
+///   (it held seconds)
+/// - dateTime values stored as a number (Object::DateTime) held seconds when
+///   the Turtle importer wrote them: small ones get the column scaled (the
+///   value stays as written, Object::DateTime is read from the column)
+/// - date and time literals get the column they didn't have
+pub fn migrate_temporal_columns(conn: &Connection) -> Result<usize> {
+    let tx = conn.unchecked_transaction()?;
+    let rows: Vec<(i64, String, String, Option<i64>)> = tx.prepare(
+        "SELECT id, COALESCE(object_value, ''), object_datatype, object_datetime FROM quads
+         WHERE object_type = 'literal' AND object_datatype IN ('xsd:dateTime', 'xsd:date', 'xsd:time')",
+    )?
+        .query_map([], |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?)))?
+        .collect::<std::result::Result<_, _>>()?;
+
+    let mut changed = 0;
+    for (id, value, datatype, column) in rows {
+        let millis = match (TypedValue::parse(&value, &datatype), value.parse::<i64>()) {
+            (Some(TypedValue::DateTime(millis)), _) => millis,
+            (_, Ok(seconds)) if datatype == "xsd:dateTime" && seconds.abs() < SECONDS_BELOW => seconds * 1000,
+            _ => continue,
+        };
+        if column != Some(millis) {
+            tx.execute("UPDATE quads SET object_datetime = ? WHERE id = ?", (millis, id))?;
+            changed += 1;
+        }
+    }
+
+    tx.commit()?;
+    Ok(changed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::eavto::test_helpers::setup_test_db;
+    use crate::eavto::{store, Object, Triple};
+
+    fn literal(value: &str, datatype: &str) -> Object {
+        Object::Literal { value: value.to_string(), datatype: Some(datatype.to_string()), language: None }
+    }
+
+    #[test]
+    fn test_backfill_fills_parseable_rows_and_reports_the_rest() {
+        let mut conn = setup_test_db();
+        store::assert_triples(&mut conn, &[
+            Triple::new("foundation:Laptop", "foundation:price", literal("1299.90", "xsd:decimal")),
+            Triple::new("foundation:Laptop", "foundation:memorySize", literal("32", "xsd:integer")),
+        ], "test").unwrap();
+        assert_eq!(count_missing(&conn).unwrap(), 0);
+
+        // Legacy rows: typed columns left empty by an older writer
+        conn.execute("UPDATE triples SET object_number = NULL, object_integer = NULL", []).unwrap();
+        for (predicate, value, datatype) in [
+            ("foundation:boughtAt", "2024-05-01T10:00:00Z", "xsd:dateTime"),
+            ("foundation:refurbished", " true ", "xsd:boolean"),
+            ("foundation:weight", "about 2kg", "xsd:decimal"),
+        ] {
+            conn.execute(
+                "INSERT INTO triples (subject, predicate, object_value, object_datatype, object_type, tx, origin_id, created_at)
+                 SELECT 'foundation:Laptop', ?, ?, ?, 'literal', tx, origin_id, created_at FROM triples LIMIT 1",
+                (predicate, value, datatype),
+            ).unwrap();
+        }
+        assert_eq!(count_missing(&conn).unwrap(), 5);
+
+        let report = backfill_typed_columns(&mut conn).unwrap();
+        assert_eq!((report.scanned, report.fixed), (5, 4));
+        assert_eq!(report.unfixable.len(), 1);
+        assert_eq!(report.unfixable[0].value, "about 2kg");
+        assert_eq!(count_missing(&conn).unwrap(), 1);
+
+        let bought: i64 = conn.query_row(
+            "SELECT object_datetime FROM triples WHERE predicate = 'foundation:boughtAt'", [], |row| row.get(0),
+        ).unwrap();
+        assert_eq!(bought, 1_714_557_600_000);
+        let memory: i64 = conn.query_row(
+            "SELECT object_integer FROM triples WHERE predicate = 'foundation:memorySize'", [], |row| row.get(0),
+        ).unwrap();
+        assert_eq!(memory, 32);
+    }
+
+    #[test]
+    fn test_migrate_temporal_columns_to_millis() {
+        let mut conn = setup_test_db();
+        store::assert_triples(&mut conn, &[
+            Triple::new("foundation:Laptop", "foundation:name", Object::Literal { value: "x".into(), datatype: None, language: None }),
+        ], "test").unwrap();
+
+        // As written before version 18
+        for (predicate, value, datatype, column) in [
+            ("foundation:boughtAt", "2024-05-01T10:00:00Z", "xsd:dateTime", Some(1_714_557_600_i64)),
+            ("foundation:soldAt", "1714557600", "xsd:dateTime", Some(1_714_557_600)),
+            ("foundation:modifiedAt", "1714557600000", "xsd:dateTime", Some(1_714_557_600_000)),
+            ("foundation:warrantyEnds", "2026-05-01", "xsd:date", None),
+            ("foundation:opensAt", "09:30:00", "xsd:time", None),
+        ] {
+            conn.execute(
+                "INSERT INTO triples (subject, predicate, object_value, object_datatype, object_type, object_datetime,
+                                      tx, origin_id, created_at)
+                 SELECT 'foundation:Laptop', ?, ?, ?, 'literal', ?, tx, origin_id, created_at FROM triples LIMIT 1",
+                (predicate, value, datatype, column),
+            ).unwrap();
+        }
+        assert_eq!(count_missing(&conn).unwrap(), 2);
+
+        assert_eq!(migrate_temporal_columns(&conn).unwrap(), 4);
+        assert_eq!(count_missing(&conn).unwrap(), 0);
+        let column = |predicate: &str| -> i64 {
+            conn.query_row("SELECT object_datetime FROM triples WHERE predicate = ?", [predicate], |row| row.get(0)).unwrap()
+        };
+        assert_eq!(column("foundation:boughtAt"), 1_714_557_600_000);
+        assert_eq!(column("foundation:soldAt"), 1_714_557_600_000);
+        assert_eq!(column("foundation:modifiedAt"), 1_714_557_600_000);
+        assert_eq!(column("foundation:warrantyEnds"), 1_777_593_600_000);
+        assert_eq!(column("foundation:opensAt"), 34_200_000);
+
+        // Reading it again changes nothing
+        assert_eq!(migrate_temporal_columns(&conn).unwrap(), 0);
+        let sold = crate::eavto::query::get_by_entity_predicate(&conn, "foundation:Laptop", "foundation:soldAt").unwrap();
+        assert_eq!(sold.triples[0].object, Object::DateTime(1_714_557_600_000));
+
+        // Only the column is derived: the literal itself is never rewritten
+        let value: String = conn.query_row(
+            "SELECT object_value FROM triples WHERE predicate = 'foundation:soldAt'", [], |row| row.get(0),
+        ).unwrap();
+        assert_eq!(value, "1714557600");
+    }
+}
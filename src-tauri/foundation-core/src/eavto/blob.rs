@@ -0,0 +1,145 @@
+//! Blob Store
+//!
+//! Literals larger than the literal size limit (extracted document text,
+//! base64 payloads) are not kept in the triples table: the store spills
+//! their content to the blobs table, and the triple points to it with a
+//! `blob:{sha256}` IRI. Blobs are content-addressed, so asserting the same
+//! value twice stores it once, and retracting a large literal by value finds
+//! the triple holding its reference.
+//!
+//! The limit is a metadata value (literal_size_limit, in bytes); "0"
+//! disables spilling.
+
+use rusqlite::{Connection, OptionalExtension};
+use sha2::{Digest, Sha256};
+use std::borrow::Cow;
+use super::object_type::Object;
+use super::triple_type::Triple;
+use super::{query, store};
+
+type Result<T> = std::result::Result<T, Box<dyn std::error::Error>>;
+
+/// Metadata key of the literal size limit
+pub const LITERAL_LIMIT_KEY: &str = "literal_size_limit";
+
+/// Literal size limit when none is configured (64 KiB)
+pub const DEFAULT_LITERAL_LIMIT: usize = 64 * 1024;
+
+/// Prefix of blob reference IRIs
+pub const BLOB_PREFIX: &str = "blob:";
+
+/// Largest literal kept in the triples table, in bytes (None: no limit)
+pub fn literal_limit(conn: &Connection) -> Result<Option<usize>> {
+    let limit = match query::get_metadata(conn, LITERAL_LIMIT_KEY)? {
+        Some(value) => value.parse::<usize>().map_err(|_| format!("Invalid {}: {}", LITERAL_LIMIT_KEY, value))?,
+        None => DEFAULT_LITERAL_LIMIT,
+    };
+    Ok((limit > 0).then_some(limit))
+}
+
+/// Set the literal size limit (0: no limit; None: back to the default)
+/// Literals already stored are left as they are.
+pub fn set_literal_limit(conn: &Connection, limit: Option<usize>) -> Result<()> {
+    store::set_metadata(conn, LITERAL_LIMIT_KEY, limit.map(|l| l.to_string()).as_deref())
+}
+
+/// Whether an IRI is a blob reference
+pub fn is_reference(iri: &str) -> bool {
+    iri.starts_with(BLOB_PREFIX)
+}
+
+/// The triple to store for a triple: literals over the limit are written to
+/// the blob store and replaced by their reference
+pub(crate) fn spill<'a>(conn: &Connection, triple: &'a Triple, limit: Option<usize>) -> Result<Cow<'a, Triple>> {
+    let Some((reference, value, datatype, language)) = oversized(&triple.object, limit) else {
+        return Ok(Cow::Borrowed(triple));
+    };
+
+    conn.prepare_cached(
+        "INSERT OR IGNORE INTO blobs (hash, content, datatype, language, size, created_at) VALUES (?, ?, ?, ?, ?, ?)",
+    )?.execute((&reference[BLOB_PREFIX.len()..], value, datatype, language, value.len() as i64, store::now_millis()))?;
+
+    Ok(Cow::Owned(Triple { object: Object::Iri(reference), ..triple.clone() }))
+}
+
+/// The object a triple holding an object is stored with (without writing
+/// the blob), to match stored triples by value
+pub(crate) fn stored_object<'a>(object: &'a Object, limit: Option<usize>) -> Cow<'a, Object> {
+    match oversized(object, limit) {
+        Some((reference, ..)) => Cow::Owned(Object::Iri(reference)),
+        None => Cow::Borrowed(object),
+    }
+}
+
+/// The literal a blob reference stands for (None if the blob is unknown)
+pub fn get(conn: &Connection, iri: &str) -> Result<Option<Object>> {
+    let Some(hash) = iri.strip_prefix(BLOB_PREFIX) else { return Ok(None) };
+    Ok(conn.query_row(
+        "SELECT content, datatype, language FROM blobs WHERE hash = ?",
+        [hash],
+        |row| Ok(Object::Literal { value: row.get(0)?, datatype: row.get(1)?, language: row.get(2)? }),
+    ).optional()?)
+}
+
+/// An object with blob references replaced by the literals they stand for
+pub fn resolve(conn: &Connection, object: &Object) -> Result<Object> {
+    match object {
+        Object::Iri(iri) if is_reference(iri) => Ok(get(conn, iri)?.unwrap_or_else(|| object.clone())),
+        other => Ok(other.clone()),
+    }
+}
+
+/// Reference and parts of a literal over the limit
+fn oversized(object: &Object, limit: Option<usize>) -> Option<(String, &str, Option<&str>, Option<&str>)> {
+    let Object::Literal { value, datatype, language } = object else { return None };
+    if limit.is_none_or(|limit| value.len() <= limit) {
+        return None;
+    }
+
+    let mut hasher = Sha256::new();
+    hasher.update(datatype.as_deref().unwrap_or_default().as_bytes());
+    hasher.update([0]);
+    hasher.update(language.as_deref().unwrap_or_default().as_bytes());
+    hasher.update([0]);
+    hasher.update(value.as_bytes());
+    let reference = format!("{}{:x}", BLOB_PREFIX, hasher.finalize());
+    Some((reference, value, datatype.as_deref(), language.as_deref()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::eavto::test_helpers::setup_test_db;
+
+    fn text(value: &str) -> Object {
+        Object::Literal { value: value.to_string(), datatype: Some("xsd:string".to_string()), language: None }
+    }
+
+    #[test]
+    fn test_large_literals_spill_to_blobs() {
+        let mut conn = setup_test_db();
+        assert_eq!(literal_limit(&conn).unwrap(), Some(DEFAULT_LITERAL_LIMIT));
+        set_literal_limit(&conn, Some(16)).unwrap();
+
+        let long = text("The full text of a long contract");
+        store::assert_triples(&mut conn, &[
+            Triple::new("foundation:Contract", "foundation:text", long.clone()),
+            Triple::new("foundation:Contract", "rdfs:label", text("Contract")),
+        ], "test").unwrap();
+
+        let stored = query::get_by_entity_predicate(&conn, "foundation:Contract", "foundation:text").unwrap().triples;
+        let reference = stored[0].object.as_iri().unwrap().to_string();
+        assert!(is_reference(&reference));
+        assert_eq!(resolve(&conn, &stored[0].object).unwrap(), long);
+        let label = query::get_by_entity_predicate(&conn, "foundation:Contract", "rdfs:label").unwrap().triples;
+        assert_eq!(label[0].object, text("Contract"));
+
+        // Retracting by value finds the reference
+        store::apply_changes(&mut conn, &[], &[Triple::new("foundation:Contract", "foundation:text", long)], "test").unwrap();
+        assert!(query::get_by_entity_predicate(&conn, "foundation:Contract", "foundation:text").unwrap().triples.is_empty());
+        assert!(get(&conn, &reference).unwrap().is_some());
+
+        set_literal_limit(&conn, Some(0)).unwrap();
+        assert_eq!(literal_limit(&conn).unwrap(), None);
+    }
+}
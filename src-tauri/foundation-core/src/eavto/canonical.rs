@@ -0,0 +1,313 @@
+//! Canonical Serialization
+//!
+//! Stable byte serialization of triples and transactions for content identity
+//!
+//! Everything that hashes content (signatures, merkle trees, dedup hashes,
+//! sync) must serialize through this module so they agree on when two sets
+//! of triples are the same:
+//!
+//! - One N-Triples line per triple, sorted and deduplicated
+//! - IRIs are expanded (`rdfs:label` and its full IRI are the same term)
+//! - Typed objects serialize like the equivalent literal (`Integer(42)` and
+//!   `"42"^^xsd:integer` are the same term); `xsd:string` is implicit
+//! - Blank nodes are relabeled from their structure, so re-parsing a file
+//!   gives the same labels. This is URDNA2015-lite: a blank node's label is
+//!   the hash of its outgoing triples, so structurally identical blank nodes
+//!   share a label. Our data uses blank nodes only for OWL expressions and
+//!   lists, where that is the intended identity anyway.
+//! - Store metadata (tx, origin, created_at, retracted) is not content
+
+use std::collections::HashMap;
+use sha2::{Sha256, Digest};
+use crate::namespaces::expand_iri;
+use super::object_type::Object;
+use super::triple_type::Triple;
+use super::transaction_type::Transaction;
+
+/// Guard against cyclic blank-node structures
+const MAX_DEPTH: usize = 32;
+
+/// Canonical form of a subject or predicate (IRI, blank node label or
+/// quoted triple term)
+pub fn term(iri: &str) -> String {
+    if iri.starts_with("_:") || super::statement::is_statement(iri) {
+        iri.to_string()
+    } else {
+        format!("<{}>", expand_iri(iri))
+    }
+}
+
+/// Canonical form of an object
+pub fn object(object: &Object) -> String {
+    match object {
+        Object::Iri(iri) | Object::Blank(iri) => term(iri),
+        Object::Triple(quoted) => quoted.term(),
+        Object::Literal { value, datatype, language } => {
+            let value = escape(value);
+            match (language, datatype.as_deref()) {
+                (Some(language), _) => format!("\"{}\"@{}", value, language.to_lowercase()),
+                (None, None) => format!("\"{}\"", value),
+                (None, Some(datatype)) if expand_iri(datatype) == expand_iri("xsd:string") => {
+                    format!("\"{}\"", value)
+                }
+                (None, Some(datatype)) => format!("\"{}\"^^{}", value, term(datatype)),
+            }
+        }
+        Object::DateTime(millis) => {
+            let value = chrono::DateTime::from_timestamp_millis(*millis)
+                .map(|dt| dt.to_rfc3339_opts(chrono::SecondsFormat::AutoSi, true))
+                .unwrap_or_else(|| millis.to_string());
+            format!("\"{}\"^^{}", value, term("xsd:dateTime"))
+        }
+        typed => format!(
+            "\"{}\"^^{}",
+            typed.as_literal().unwrap_or_default(),
+            term(typed.datatype().unwrap_or("xsd:string"))
+        ),
+    }
+}
+
+/// N-Triples line for a triple, keeping its blank node labels
+pub fn line(triple: &Triple) -> String {
+    format!("{} {} {} .", term(&triple.subject), term(&triple.predicate), object(&triple.object))
+}
+
+/// Canonical labels for every blank node in `triples` (original id -> `_:c…`)
+pub fn blank_labels(triples: &[Triple]) -> HashMap<String, String> {
+    let mut by_subject: HashMap<&str, Vec<&Triple>> = HashMap::new();
+    for triple in triples {
+        by_subject.entry(triple.subject.as_str()).or_default().push(triple);
+    }
+
+    let mut labels = HashMap::new();
+    for triple in triples {
+        for node in [Some(triple.subject.as_str()), triple_blank_object(triple)].into_iter().flatten() {
+            if node.starts_with("_:") && !labels.contains_key(node) {
+                let digest = sha256_hex(signature(&by_subject, node, 0).as_bytes());
+                labels.insert(node.to_string(), format!("_:c{}", &digest[..16]));
+            }
+        }
+    }
+    labels
+}
+
+/// Copy of `triples` with blank nodes renamed to their canonical labels
+pub fn relabel(triples: &[Triple]) -> Vec<Triple> {
+    let labels = blank_labels(triples);
+    triples.iter()
+        .map(|triple| {
+            let mut triple = triple.clone();
+            if let Some(label) = labels.get(&triple.subject) {
+                triple.subject = label.clone();
+            }
+            if let Object::Blank(id) = &triple.object {
+                if let Some(label) = labels.get(id) {
+                    triple.object = Object::Blank(label.clone());
+                }
+            }
+            triple
+        })
+        .collect()
+}
+
+/// Canonical lines of a set of triples, sorted and deduplicated
+pub fn lines(triples: &[Triple]) -> Vec<String> {
+    let mut lines: Vec<String> = relabel(triples).iter().map(line).collect();
+    lines.sort();
+    lines.dedup();
+    lines
+}
+
+/// Canonical serialization of a set of triples
+pub fn serialize(triples: &[Triple]) -> Vec<u8> {
+    let mut bytes = Vec::new();
+    for line in lines(triples) {
+        bytes.extend_from_slice(line.as_bytes());
+        bytes.push(b'\n');
+    }
+    bytes
+}
+
+/// Content hash of a set of triples (SHA-256 hex of the canonical serialization)
+pub fn hash(triples: &[Triple]) -> String {
+    sha256_hex(&serialize(triples))
+}
+
+/// Canonical serialization of a transaction: its origin and time, then its triples
+///
+/// The transaction ID is local to each store, so it is not part of the content.
+pub fn serialize_transaction(transaction: &Transaction, triples: &[Triple]) -> Vec<u8> {
+    let mut bytes = format!(
+        "# origin {}\n# created_at {}\n",
+        term(&transaction.origin),
+        transaction.created_at
+    ).into_bytes();
+    bytes.extend(serialize(triples));
+    bytes
+}
+
+/// Content hash of a transaction
+pub fn hash_transaction(transaction: &Transaction, triples: &[Triple]) -> String {
+    sha256_hex(&serialize_transaction(transaction, triples))
+}
+
+/// Structural description of a node (blank nodes expand to their contents)
+fn signature(by_subject: &HashMap<&str, Vec<&Triple>>, node: &str, depth: usize) -> String {
+    if !node.starts_with("_:") {
+        return term(node);
+    }
+    if depth > MAX_DEPTH {
+        return "[...]".to_string();
+    }
+
+    let mut parts: Vec<String> = by_subject.get(node)
+        .into_iter()
+        .flatten()
+        .map(|t| {
+            let value = match &t.object {
+                Object::Blank(id) => signature(by_subject, id, depth + 1),
+                other => object(other),
+            };
+            format!("{} {}", term(&t.predicate), value)
+        })
+        .collect();
+    parts.sort();
+    parts.dedup();
+    format!("[{}]", parts.join(" ; "))
+}
+
+fn triple_blank_object(triple: &Triple) -> Option<&str> {
+    match &triple.object {
+        Object::Blank(id) => Some(id),
+        _ => None,
+    }
+}
+
+/// Escape a literal value as in canonical N-Triples (valid in Turtle too,
+/// so the Turtle export writes its literals with it)
+pub(crate) fn escape(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for c in value.chars() {
+        match c {
+            '\\' => escaped.push_str("\\\\"),
+            '"' => escaped.push_str("\\\""),
+            '\n' => escaped.push_str("\\n"),
+            '\r' => escaped.push_str("\\r"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+fn sha256_hex(bytes: &[u8]) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(bytes);
+    format!("{:x}", hasher.finalize())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn string(value: &str) -> Object {
+        Object::Literal {
+            value: value.to_string(),
+            datatype: Some("xsd:string".to_string()),
+            language: None,
+        }
+    }
+
+    fn restriction(node: &str) -> Vec<Triple> {
+        vec![
+            Triple::new("foundation:Computer", "rdfs:subClassOf", Object::Blank(node.to_string())),
+            Triple::new(node, "rdf:type", Object::Iri("owl:Restriction".to_string())),
+            Triple::new(node, "owl:onProperty", Object::Iri("foundation:hasCPU".to_string())),
+        ]
+    }
+
+    #[test]
+    fn test_line_expands_iris() {
+        let triple = Triple::new("foundation:Computer", "rdfs:label", string("Computer"));
+        assert_eq!(
+            line(&triple),
+            "<http://foundation.local/ontology/Computer> <http://www.w3.org/2000/01/rdf-schema#label> \"Computer\" ."
+        );
+
+        let prefixed = Triple::new("foundation:Computer", "rdf:type", Object::Iri("owl:Class".to_string()));
+        let expanded = Triple::new(
+            "http://foundation.local/ontology/Computer",
+            "http://www.w3.org/1999/02/22-rdf-syntax-ns#type",
+            Object::Iri("http://www.w3.org/2002/07/owl#Class".to_string()),
+        );
+        assert_eq!(line(&prefixed), line(&expanded));
+    }
+
+    #[test]
+    fn test_typed_objects_match_literals() {
+        let literal = Object::Literal {
+            value: "42".to_string(),
+            datatype: Some("xsd:integer".to_string()),
+            language: None,
+        };
+        assert_eq!(object(&Object::Integer(42)), object(&literal));
+        assert_eq!(object(&Object::Boolean(true)), "\"true\"^^<http://www.w3.org/2001/XMLSchema#boolean>");
+        assert_eq!(
+            object(&Object::DateTime(0)),
+            "\"1970-01-01T00:00:00Z\"^^<http://www.w3.org/2001/XMLSchema#dateTime>"
+        );
+    }
+
+    #[test]
+    fn test_literal_escaping_and_language() {
+        let literal = Object::Literal {
+            value: "say \"hi\"\nnow".to_string(),
+            datatype: None,
+            language: Some("EN".to_string()),
+        };
+        assert_eq!(object(&literal), "\"say \\\"hi\\\"\\nnow\"@en");
+        assert_eq!(object(&string("plain")), "\"plain\"");
+    }
+
+    #[test]
+    fn test_hash_ignores_order_duplicates_and_metadata() {
+        let mut a = vec![
+            Triple::new("foundation:A", "rdfs:label", string("A")),
+            Triple::new("foundation:A", "rdf:type", Object::Iri("owl:Class".to_string())),
+        ];
+        let mut b: Vec<Triple> = a.iter().rev().cloned().collect();
+        b.push(a[0].clone());
+        b[0].tx = 99;
+        b[0].origin_id = 7;
+
+        assert_eq!(hash(&a), hash(&b));
+
+        a.push(Triple::new("foundation:A", "rdfs:comment", string("changed")));
+        assert_ne!(hash(&a), hash(&b));
+    }
+
+    #[test]
+    fn test_blank_labels_are_structural() {
+        let first = restriction("_:riog1");
+        let second = restriction("_:b42");
+        assert_eq!(serialize(&first), serialize(&second));
+
+        let labels = blank_labels(&first);
+        assert!(labels["_:riog1"].starts_with("_:c"));
+
+        let mut changed = restriction("_:riog1");
+        changed[2].object = Object::Iri("foundation:hasProcessor".to_string());
+        assert_ne!(blank_labels(&changed)["_:riog1"], labels["_:riog1"]);
+    }
+
+    #[test]
+    fn test_transaction_hash_includes_origin() {
+        let triples = restriction("_:x");
+        let a = Transaction::new(1, "import:a.ttl", 1000);
+        let b = Transaction::new(2, "import:a.ttl", 1000);
+        let c = Transaction::new(1, "import:b.ttl", 1000);
+
+        // Local transaction IDs don't change the content
+        assert_eq!(hash_transaction(&a, &triples), hash_transaction(&b, &triples));
+        assert_ne!(hash_transaction(&a, &triples), hash_transaction(&c, &triples));
+    }
+}
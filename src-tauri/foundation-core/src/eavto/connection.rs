@@ -0,0 +1,825 @@
+// ============================================================================
+// EAVTO Connection Module
+// ============================================================================
+// Manages SQLite database connection lifecycle and initialization
+//
+// Database location: ~/Documents/Foundation/FOUNDATION.db on desktop; on
+// Android and iOS, the app sandbox's data directory (see set_data_dir)
+// ============================================================================
+
+use rusqlite::{Connection, OptionalExtension, Result};
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+use std::fs;
+use crate::{ImportObserver, ImportSummary};
+use crate::turtle::pipeline::{self, RdfFormat};
+
+/// Database initialization error types
+#[derive(Debug)]
+pub enum DbError {
+    ConnectionError(rusqlite::Error),
+    SchemaError(String),
+    IoError(std::io::Error),
+}
+
+impl From<rusqlite::Error> for DbError {
+    fn from(err: rusqlite::Error) -> Self {
+        DbError::ConnectionError(err)
+    }
+}
+
+impl From<std::io::Error> for DbError {
+    fn from(err: std::io::Error) -> Self {
+        DbError::IoError(err)
+    }
+}
+
+/// Directory of the databases, when the app shell chooses it
+static DATA_DIR: OnceLock<PathBuf> = OnceLock::new();
+
+/// Keep the databases in `dir` rather than ~/Documents/Foundation
+///
+/// Mobile shells must call this before the database is opened: Android has
+/// no Documents directory, and iOS apps may only write in their sandbox.
+/// Only the first call has an effect.
+pub fn set_data_dir(dir: PathBuf) {
+    let _ = DATA_DIR.set(dir);
+}
+
+/// Get the path to the database file
+/// Uses ~/Documents/Foundation/FOUNDATION.db for both dev and production,
+/// unless the app shell set another directory (set_data_dir)
+pub fn get_db_path() -> Result<PathBuf, DbError> {
+    let foundation_dir = match DATA_DIR.get() {
+        Some(dir) => dir.clone(),
+        None => dirs::document_dir()
+            .ok_or_else(|| DbError::IoError(std::io::Error::new(
+                std::io::ErrorKind::NotFound,
+                "Could not determine Documents directory (mobile shells must call set_data_dir)"
+            )))?
+            .join("Foundation"),
+    };
+
+    // Create Foundation directory if it doesn't exist
+    if !foundation_dir.exists() {
+        println!("Creating Foundation directory at {:?}", foundation_dir);
+        fs::create_dir_all(&foundation_dir)?;
+    }
+
+    let db_path = foundation_dir.join("FOUNDATION.db");
+    println!("Using database at {:?}", db_path);
+
+    Ok(db_path)
+}
+
+/// Get the path to the demo profile database, next to the real one
+/// The demo profile is throwaway: demo__reset deletes it
+pub fn get_demo_db_path() -> Result<PathBuf, DbError> {
+    let db_path = get_db_path()?;
+    Ok(db_path.with_file_name("FOUNDATION-demo.db"))
+}
+
+/// Page cache of each connection, in KiB
+const CACHE_SIZE_KIB: i64 = 64 * 1024;
+
+/// Bytes of the database file mapped in memory (ignored by encrypted databases)
+const MMAP_SIZE: i64 = 256 * 1024 * 1024;
+
+/// Free pages from which a large retraction is followed by an incremental vacuum
+const RECLAIMED_FREE_PAGES: i64 = 1024;
+
+/// Space a vacuum reclaimed
+#[derive(Debug, Clone)]
+pub struct VacuumReport {
+    pub bytes_before: i64,
+    pub bytes_after: i64,
+}
+
+/// Set up a connection to the database file (the writer's):
+/// - WAL journal, so reads run alongside writes (see executor)
+/// - synchronous=NORMAL: with WAL, a power loss can only lose the last
+///   transactions, never corrupt the database
+/// - incremental auto-vacuum for new databases (existing ones switch on
+///   their next full vacuum, see `vacuum`)
+/// - the cache and memory map of `tune`
+pub fn configure(conn: &Connection) -> Result<(), DbError> {
+    conn.pragma_update(None, "auto_vacuum", "INCREMENTAL")?;
+    let mode: String = conn.pragma_update_and_check(None, "journal_mode", "WAL", |row| row.get(0))?;
+    if !mode.eq_ignore_ascii_case("wal") && conn.path().is_some_and(|path| !path.is_empty()) {
+        println!("⚠️  Database journal mode is {}, not WAL", mode);
+    }
+    conn.pragma_update(None, "synchronous", "NORMAL")?;
+    tune(conn)?;
+    Ok(())
+}
+
+/// Set the cache and memory map of a connection (readers' too)
+pub fn tune(conn: &Connection) -> Result<(), DbError> {
+    conn.pragma_update(None, "cache_size", -CACHE_SIZE_KIB)?;
+    conn.pragma_update(None, "mmap_size", MMAP_SIZE)?;
+    conn.pragma_update(None, "temp_store", "MEMORY")?;
+    Ok(())
+}
+
+/// Size of the database file in bytes
+fn database_bytes(conn: &Connection) -> Result<i64, DbError> {
+    let pages: i64 = conn.query_row("PRAGMA page_count", [], |row| row.get(0))?;
+    let page_size: i64 = conn.query_row("PRAGMA page_size", [], |row| row.get(0))?;
+    Ok(pages * page_size)
+}
+
+/// Rebuild the database file, reclaiming the space of deleted rows and
+/// indexes (e.g., after the migration to interned terms), and switch it to
+/// incremental auto-vacuum. Can't run inside a transaction
+pub fn vacuum(conn: &Connection) -> Result<VacuumReport, DbError> {
+    let bytes_before = database_bytes(conn)?;
+    conn.pragma_update(None, "auto_vacuum", "INCREMENTAL")?;
+    conn.execute_batch("VACUUM")?;
+    Ok(VacuumReport { bytes_before, bytes_after: database_bytes(conn)? })
+}
+
+/// Return the free pages of the database file to the file system, when
+/// enough of them built up (large retractions drop index entries of the
+/// current triples); returns the pages reclaimed
+pub fn reclaim_free_pages(conn: &Connection) -> Result<i64, DbError> {
+    let auto_vacuum: i64 = conn.query_row("PRAGMA auto_vacuum", [], |row| row.get(0))?;
+    let free: i64 = conn.query_row("PRAGMA freelist_count", [], |row| row.get(0))?;
+    if auto_vacuum != 2 || free < RECLAIMED_FREE_PAGES {
+        return Ok(0); // 2: incremental
+    }
+    // Each step of the pragma frees a page: step it to the end
+    let mut stmt = conn.prepare("PRAGMA incremental_vacuum")?;
+    let mut steps = stmt.query([])?;
+    while steps.next()?.is_some() {}
+    Ok(free)
+}
+
+/// SQL schema for database initialization
+const SCHEMA_SQL: &str = include_str!("../../../../db/schema.sql");
+
+/// RDF/RDFS/OWL core ontology
+const RDF_CORE_TTL: &str = include_str!("../../../../core-ontology/rdf-rdfs-owl-core.ttl");
+
+/// DTYPE (Datatype Schema) ontology
+const DTYPE_TTL: &str = include_str!("../../../../core-ontology/dtype.ttl");
+
+/// Create database schema
+fn create_schema(conn: &Connection) -> Result<(), DbError> {
+    println!("📋 Creating schema...");
+    conn.execute_batch(SCHEMA_SQL)?;
+    super::indexes::ensure(conn)?;
+    println!("✅ Schema created");
+    Ok(())
+}
+
+/// Bring an existing database up to the current schema
+fn upgrade_schema(conn: &Connection) -> Result<(), DbError> {
+    // Origin status (quarantine), added in schema version 4
+    let has_status: bool = conn.query_row(
+        "SELECT EXISTS(SELECT 1 FROM pragma_table_info('origins') WHERE name = 'status')",
+        [],
+        |row| row.get(0),
+    )?;
+    if !has_status {
+        println!("📋 Adding origin status column...");
+        conn.execute_batch(
+            "ALTER TABLE origins ADD COLUMN status TEXT NOT NULL DEFAULT 'active'
+               CHECK(status IN ('active', 'quarantined', 'rejected'));
+             UPDATE metadata SET value = '4' WHERE key = 'schema_version';"
+        )?;
+    }
+
+    // Attachment text index, added in schema version 5
+    let has_attachment_text: bool = conn.query_row(
+        "SELECT EXISTS(SELECT 1 FROM sqlite_master WHERE name = 'attachment_text')",
+        [],
+        |row| row.get(0),
+    )?;
+    if !has_attachment_text {
+        println!("📋 Adding attachment text index...");
+        conn.execute_batch(
+            "CREATE VIRTUAL TABLE attachment_text USING fts5(
+               entity UNINDEXED, text, tokenize = 'unicode61 remove_diacritics 2'
+             );
+             UPDATE metadata SET value = '5' WHERE key = 'schema_version';"
+        )?;
+    }
+
+    // Receive time of transactions from other devices, added in schema version 6
+    let has_received_at: bool = conn.query_row(
+        "SELECT EXISTS(SELECT 1 FROM pragma_table_info('transactions') WHERE name = 'received_at')",
+        [],
+        |row| row.get(0),
+    )?;
+    if !has_received_at {
+        println!("📋 Adding transaction receive time column...");
+        conn.execute_batch(
+            "ALTER TABLE transactions ADD COLUMN received_at INTEGER;
+             UPDATE metadata SET value = '6' WHERE key = 'schema_version';"
+        )?;
+    }
+
+    // Transaction annotations, added in schema version 7
+    let has_message: bool = conn.query_row(
+        "SELECT EXISTS(SELECT 1 FROM pragma_table_info('transactions') WHERE name = 'message')",
+        [],
+        |row| row.get(0),
+    )?;
+    if !has_message {
+        println!("📋 Adding transaction annotation columns...");
+        conn.execute_batch(
+            "ALTER TABLE transactions ADD COLUMN message TEXT;
+             ALTER TABLE transactions ADD COLUMN tags TEXT;
+             UPDATE metadata SET value = '7' WHERE key = 'schema_version';"
+        )?;
+    }
+
+    // Retracting transaction of triples, added in schema version 8
+    let has_retracted_tx: bool = conn.query_row(
+        "SELECT EXISTS(SELECT 1 FROM pragma_table_info('triples') WHERE name = 'retracted_tx')",
+        [],
+        |row| row.get(0),
+    )?;
+    if !has_retracted_tx {
+        println!("📋 Adding triple retraction transaction column...");
+        conn.execute_batch(
+            "ALTER TABLE triples ADD COLUMN retracted_tx INTEGER;
+             CREATE INDEX IF NOT EXISTS idx_retracted_tx ON triples(retracted_tx) WHERE retracted_tx IS NOT NULL;
+             UPDATE metadata SET value = '8' WHERE key = 'schema_version';"
+        )?;
+    }
+
+    // API tokens, added in schema version 9
+    let has_api_tokens: bool = conn.query_row(
+        "SELECT EXISTS(SELECT 1 FROM sqlite_master WHERE name = 'api_tokens')",
+        [],
+        |row| row.get(0),
+    )?;
+    if !has_api_tokens {
+        println!("📋 Adding API tokens table...");
+        conn.execute_batch(
+            "CREATE TABLE api_tokens (
+               id INTEGER PRIMARY KEY AUTOINCREMENT,
+               name TEXT NOT NULL,
+               token_hash TEXT NOT NULL UNIQUE,
+               prefix TEXT NOT NULL,
+               scopes TEXT NOT NULL,
+               created_at INTEGER NOT NULL,
+               expires_at INTEGER,
+               revoked_at INTEGER,
+               last_used_at INTEGER
+             );
+             UPDATE metadata SET value = '9' WHERE key = 'schema_version';"
+        )?;
+    }
+
+    // Outbox of outbound work, added in schema version 10
+    let has_outbox: bool = conn.query_row(
+        "SELECT EXISTS(SELECT 1 FROM sqlite_master WHERE name = 'outbox')",
+        [],
+        |row| row.get(0),
+    )?;
+    if !has_outbox {
+        println!("📋 Adding outbox table...");
+        conn.execute_batch(
+            "CREATE TABLE outbox (
+               id INTEGER PRIMARY KEY AUTOINCREMENT,
+               kind TEXT NOT NULL,
+               payload TEXT NOT NULL,
+               status TEXT NOT NULL DEFAULT 'pending' CHECK (status IN ('pending', 'done', 'failed', 'cancelled')),
+               attempts INTEGER NOT NULL DEFAULT 0,
+               next_attempt_at INTEGER NOT NULL,
+               last_error TEXT,
+               created_at INTEGER NOT NULL,
+               completed_at INTEGER
+             );
+             CREATE INDEX idx_outbox_due ON outbox(status, next_attempt_at);
+             UPDATE metadata SET value = '10' WHERE key = 'schema_version';"
+        )?;
+    }
+
+    // Blob store of large literals, added in schema version 11
+    let has_blobs: bool = conn.query_row(
+        "SELECT EXISTS(SELECT 1 FROM sqlite_master WHERE name = 'blobs')",
+        [],
+        |row| row.get(0),
+    )?;
+    if !has_blobs {
+        println!("📋 Adding blob store...");
+        conn.execute_batch(
+            "CREATE TABLE blobs (
+               hash TEXT PRIMARY KEY,
+               content TEXT NOT NULL,
+               datatype TEXT,
+               language TEXT,
+               size INTEGER NOT NULL,
+               created_at INTEGER NOT NULL
+             );
+             UPDATE metadata SET value = '11' WHERE key = 'schema_version';"
+        )?;
+    }
+
+    // Named graph of triples, added in schema version 12 (imported
+    // ontologies move to their own graph)
+    let has_graph: bool = conn.query_row(
+        "SELECT EXISTS(SELECT 1 FROM pragma_table_info('triples') WHERE name = 'graph')",
+        [],
+        |row| row.get(0),
+    )?;
+    if !has_graph {
+        println!("📋 Adding triple graph column...");
+        conn.execute_batch(&format!(
+            "ALTER TABLE triples ADD COLUMN graph TEXT NOT NULL DEFAULT '{0}';
+             UPDATE triples SET graph = '{1}'
+               WHERE origin_id IN (SELECT id FROM origins WHERE name IN ('core', 'rdf:core') OR name LIKE 'foundation:ontology:%');
+             CREATE INDEX IF NOT EXISTS idx_graph ON triples(graph, subject);
+             UPDATE metadata SET value = '12' WHERE key = 'schema_version';",
+            super::DEFAULT_GRAPH, super::ONTOLOGY_GRAPH
+        ))?;
+    }
+
+    // Transaction signatures, added in schema version 13
+    let has_signature: bool = conn.query_row(
+        "SELECT EXISTS(SELECT 1 FROM pragma_table_info('transactions') WHERE name = 'signature')",
+        [],
+        |row| row.get(0),
+    )?;
+    if !has_signature {
+        println!("📋 Adding transaction signatures...");
+        conn.execute_batch(
+            "ALTER TABLE transactions ADD COLUMN signature TEXT;
+             ALTER TABLE transactions ADD COLUMN signer TEXT;
+             UPDATE metadata SET value = '13' WHERE key = 'schema_version';"
+        )?;
+    }
+
+    // Changesets exchanged with other instances, added in schema version 14
+    let has_sync: bool = conn.query_row(
+        "SELECT EXISTS(SELECT 1 FROM sqlite_master WHERE name = 'sync_changesets')",
+        [],
+        |row| row.get(0),
+    )?;
+    if !has_sync {
+        println!("📋 Adding sync changesets...");
+        conn.execute_batch(
+            "CREATE TABLE sync_changesets (
+               id TEXT PRIMARY KEY,
+               tx INTEGER,
+               peer TEXT,
+               recorded_at INTEGER NOT NULL
+             );
+             UPDATE metadata SET value = '14' WHERE key = 'schema_version';"
+        )?;
+    }
+
+    // Journal of the latest writes, added in schema version 15
+    let has_journal: bool = conn.query_row(
+        "SELECT EXISTS(SELECT 1 FROM sqlite_master WHERE name = 'write_journal')",
+        [],
+        |row| row.get(0),
+    )?;
+    if !has_journal {
+        println!("📋 Adding write journal...");
+        conn.execute_batch(
+            "CREATE TABLE write_journal (
+               id INTEGER PRIMARY KEY AUTOINCREMENT,
+               command TEXT NOT NULL,
+               args_hash TEXT,
+               tx INTEGER,
+               duration_ms INTEGER NOT NULL,
+               error TEXT,
+               recorded_at INTEGER NOT NULL
+             );
+             UPDATE metadata SET value = '15' WHERE key = 'schema_version';"
+        )?;
+    }
+
+    // Source URL and ETag of downloaded ontologies, added in schema version 16
+    let needs_source_url: bool = conn.query_row(
+        "SELECT EXISTS(SELECT 1 FROM sqlite_master WHERE name = 'ontology_files')
+            AND NOT EXISTS(SELECT 1 FROM pragma_table_info('ontology_files') WHERE name = 'source_url')",
+        [],
+        |row| row.get(0),
+    )?;
+    if needs_source_url {
+        println!("📋 Adding ontology download sources...");
+        conn.execute_batch(
+            "ALTER TABLE ontology_files ADD COLUMN source_url TEXT;
+             ALTER TABLE ontology_files ADD COLUMN etag TEXT;
+             UPDATE metadata SET value = '16' WHERE key = 'schema_version';"
+        )?;
+    }
+
+    // Interned terms, added in schema version 17: the triples table becomes
+    // the quads table (holding term IDs) and a triples view over it
+    let triples_is_table: bool = conn.query_row(
+        "SELECT EXISTS(SELECT 1 FROM sqlite_master WHERE name = 'triples' AND type = 'table')",
+        [],
+        |row| row.get(0),
+    )?;
+    if triples_is_table {
+        println!("📋 Interning triple terms...");
+        // Its views and indices go with it (the schema creates them again)
+        let indices: Vec<String> = conn.prepare(
+            "SELECT name FROM sqlite_master WHERE type = 'index' AND tbl_name = 'triples' AND sql IS NOT NULL"
+        )?.query_map([], |row| row.get(0))?.collect::<Result<_>>()?;
+        let mut batch = String::from(
+            "BEGIN;
+             DROP VIEW IF EXISTS triples_current;
+             DROP VIEW IF EXISTS entities;
+             DROP VIEW IF EXISTS ontology_classes;
+             DROP VIEW IF EXISTS ontology_properties;"
+        );
+        for index in indices {
+            batch.push_str(&format!("DROP INDEX \"{}\";", index));
+        }
+        batch.push_str("ALTER TABLE triples RENAME TO triples_v16; COMMIT;");
+        conn.execute_batch(&batch)?;
+    }
+    let has_v16_triples: bool = conn.query_row(
+        "SELECT EXISTS(SELECT 1 FROM sqlite_master WHERE name = 'triples_v16')",
+        [],
+        |row| row.get(0),
+    )?;
+    if has_v16_triples {
+        // Rows keep their rowid, so their insertion order is kept. Databases
+        // opened before never enforced foreign keys, so rows pointing to a
+        // missing origin are copied as they are
+        conn.execute_batch(SCHEMA_SQL)?;
+        conn.execute_batch(
+            "PRAGMA foreign_keys = OFF;
+             BEGIN;
+             INSERT OR IGNORE INTO terms (value)
+               SELECT subject FROM triples_v16
+               UNION SELECT predicate FROM triples_v16
+               UNION SELECT object FROM triples_v16 WHERE object IS NOT NULL;
+             INSERT INTO quads (id, subject_id, predicate_id, object_id, object_value, object_datatype, object_language,
+                                object_type, graph, object_number, object_integer, object_datetime, object_boolean,
+                                tx, origin_id, retracted, created_at, retracted_tx)
+               SELECT t.rowid, s.id, p.id, o.id, t.object_value, t.object_datatype, t.object_language,
+                      t.object_type, t.graph, t.object_number, t.object_integer, t.object_datetime, t.object_boolean,
+                      t.tx, t.origin_id, t.retracted, t.created_at, t.retracted_tx
+               FROM triples_v16 t
+               JOIN terms s ON s.value = t.subject
+               JOIN terms p ON p.value = t.predicate
+               LEFT JOIN terms o ON o.value = t.object;
+             DROP TABLE triples_v16;
+             UPDATE metadata SET value = '17' WHERE key = 'schema_version';
+             COMMIT;"
+        )?;
+        println!("✅ Triple terms interned (db__vacuum reclaims the space freed)");
+    }
+
+    // Temporal columns in epoch milliseconds, date and time included, since
+    // schema version 18
+    let version: i64 = conn.query_row(
+        "SELECT CAST(value AS INTEGER) FROM metadata WHERE key = 'schema_version'",
+        [],
+        |row| row.get(0),
+    ).optional()?.unwrap_or(0);
+    if version < 18 {
+        println!("📋 Migrating temporal columns to milliseconds...");
+        let changed = super::backfill::migrate_temporal_columns(conn)
+            .map_err(|e| DbError::SchemaError(format!("Temporal column migration failed: {}", e)))?;
+        conn.execute("UPDATE metadata SET value = '18' WHERE key = 'schema_version'", [])?;
+        println!("✅ {} temporal values migrated", changed);
+    }
+
+    // Quoted triples (RDF-star), added in schema version 19 (the interning
+    // above may have created their table with the rest of the schema)
+    if version < 19 {
+        println!("📋 Adding statements...");
+        conn.execute_batch(
+            "CREATE TABLE IF NOT EXISTS statements (
+               id INTEGER PRIMARY KEY,
+               subject_id INTEGER NOT NULL,
+               predicate_id INTEGER NOT NULL,
+               object_id INTEGER,
+               FOREIGN KEY (id) REFERENCES terms(id),
+               FOREIGN KEY (subject_id) REFERENCES terms(id),
+               FOREIGN KEY (predicate_id) REFERENCES terms(id),
+               FOREIGN KEY (object_id) REFERENCES terms(id)
+             );
+             CREATE INDEX IF NOT EXISTS idx_statements_subject ON statements(subject_id, predicate_id);
+             UPDATE metadata SET value = '19' WHERE key = 'schema_version';"
+        )?;
+    }
+
+    // Indexes added since the database was created
+    super::indexes::ensure(conn)?;
+    Ok(())
+}
+
+/// Import RDF/RDFS/OWL core ontology
+fn import_rdf_core(conn: &mut Connection, observer: Option<&dyn ImportObserver>) -> Result<u64, DbError> {
+    println!("\n📚 Importing RDF/RDFS/OWL core ontology...");
+
+    // core + dtype + foundation files
+    pipeline::progress(observer, "core", "rdf-rdfs-owl-core.ttl", 1, 3, 0);
+
+    let stats = pipeline::import(conn, RDF_CORE_TTL.as_bytes(), "rdf-rdfs-owl-core.ttl", RdfFormat::Turtle, "core")
+        .map_err(|e| DbError::SchemaError(format!("RDF core import failed: {:?}", e)))?;
+
+    println!("✅ Imported {} triples from RDF/RDFS/OWL", stats.triples_processed);
+    Ok(stats.triples_processed)
+}
+
+/// Import DTYPE ontology
+fn import_dtype(conn: &mut Connection, observer: Option<&dyn ImportObserver>, total_triples: u64) -> Result<u64, DbError> {
+    println!("\n📚 Importing DTYPE ontology...");
+
+    pipeline::progress(observer, "dtype", "dtype.ttl", 2, 3, total_triples);
+
+    let stats = pipeline::import(conn, DTYPE_TTL.as_bytes(), "dtype.ttl", RdfFormat::Turtle, "core")
+        .map_err(|e| DbError::SchemaError(format!("DTYPE import failed: {:?}", e)))?;
+
+    println!("✅ Imported {} triples from DTYPE", stats.triples_processed);
+    Ok(stats.triples_processed)
+}
+
+/// Initialize database with schema and ontologies
+pub fn initialize_db(db_path: &Path) -> Result<Connection, DbError> {
+    initialize_db_with_progress(db_path, None)
+}
+
+/// Initialize database with progress events
+fn initialize_db_with_progress(db_path: &Path, observer: Option<&dyn ImportObserver>) -> Result<Connection, DbError> {
+    let needs_initialization = !db_path.exists();
+
+    println!("Using database at: {:?}", db_path);
+    let mut conn = super::encryption::open(db_path)?;
+    configure(&conn)?;
+
+    let started = std::time::Instant::now();
+    let mut import = if needs_initialization {
+        println!("\n🚀 Initializing new database...\n");
+
+        create_schema(&conn)?;
+
+        let mut total_triples = 0u64;
+        total_triples += import_rdf_core(&mut conn, observer)?;
+        total_triples += import_dtype(&mut conn, observer, total_triples)?;
+
+        let mut import = crate::turtle::import_all_foundation_ontologies(&mut conn, observer, total_triples)
+            .map_err(|e| DbError::SchemaError(format!("Ontology import failed: {:?}", e)))?;
+        import.files.splice(0..0, ["rdf-rdfs-owl-core.ttl".to_string(), "dtype.ttl".to_string()]);
+        import.triples += total_triples;
+
+        println!("\n⚙️  Setting metadata...");
+        conn.execute(
+            "UPDATE metadata SET value = 'true', updated_at = ? WHERE key = 'ontology_imported'",
+            [std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .unwrap()
+                .as_millis() as i64],
+        )?;
+        println!("✅ Metadata updated");
+        import
+    } else {
+        println!("ℹ️  Database already exists, checking for ontology updates...");
+
+        upgrade_schema(&conn)?;
+
+        // Check for modified ontology files and reimport if needed
+        crate::turtle::import_all_foundation_ontologies(&mut conn, observer, 0)
+            .map_err(|e| DbError::SchemaError(format!("Ontology update check failed: {:?}", e)))?
+    };
+
+    super::indexes::analyze_after_import(&conn, import.triples)?;
+
+    // Summarize the run; startups that imported nothing aren't recorded
+    let duration_ms = started.elapsed().as_millis() as u64;
+    let run = match import.files.is_empty() && import.warnings.is_empty() {
+        true => None,
+        false => match crate::owl::ImportRun::record(
+            &mut conn,
+            import.files.clone(),
+            import.triples as i64,
+            duration_ms as i64,
+            import.warnings.clone(),
+            "foundation:FOUNDATION",
+        ) {
+            Ok(run) => Some(run.iri),
+            Err(e) => {
+                import.warnings.push(format!("Failed to record import run: {}", e));
+                None
+            }
+        },
+    };
+    let message = match import.files.is_empty() {
+        true => "All ontology files up to date".to_string(),
+        false => crate::owl::ImportRun::summarize(
+            import.triples as i64, import.files.len(), duration_ms as i64, import.warnings.len(),
+        ),
+    };
+    println!("\n✅ {}", message);
+
+    if let Some(observer) = observer {
+        observer.summary(ImportSummary {
+            run,
+            files: import.files,
+            facts: import.triples,
+            duration_ms,
+            warnings: import.warnings,
+            message,
+        });
+        if !needs_initialization {
+            observer.complete();
+        }
+    }
+
+    Ok(conn)
+}
+
+/// Initialize the database, reporting import progress to an observer
+pub fn initialize_with_progress(observer: &dyn ImportObserver) -> Result<Connection, DbError> {
+    let db_path = get_db_path()?;
+    initialize_db_with_progress(&db_path, Some(observer))
+}
+
+/// Get or create database connection
+pub fn get_connection() -> Result<Connection, DbError> {
+    let db_path = get_db_path()?;
+    initialize_db(&db_path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_db_error_from_rusqlite() {
+        let rusqlite_err = rusqlite::Error::InvalidQuery;
+        let db_err: DbError = rusqlite_err.into();
+
+        match db_err {
+            DbError::ConnectionError(_) => {},
+            _ => panic!("Expected ConnectionError"),
+        }
+    }
+
+    #[test]
+    fn test_db_error_from_io() {
+        let io_err = std::io::Error::new(std::io::ErrorKind::NotFound, "test error");
+        let db_err: DbError = io_err.into();
+
+        match db_err {
+            DbError::IoError(_) => {},
+            _ => panic!("Expected IoError"),
+        }
+    }
+
+    #[test]
+    fn test_configure_and_reclaim_free_pages() {
+        let path = std::env::temp_dir().join("foundation-configure-test.db");
+        let _ = std::fs::remove_file(&path);
+        let conn = Connection::open(&path).unwrap();
+        configure(&conn).unwrap();
+        let mode: String = conn.query_row("PRAGMA journal_mode", [], |row| row.get(0)).unwrap();
+        assert_eq!(mode, "wal");
+
+        // Deleted rows leave free pages, returned once enough built up
+        conn.execute_batch("CREATE TABLE filler (data BLOB)").unwrap();
+        let page = vec![0u8; 4096];
+        for _ in 0..RECLAIMED_FREE_PAGES + 100 {
+            conn.execute("INSERT INTO filler (data) VALUES (?)", [&page]).unwrap();
+        }
+        conn.execute("DELETE FROM filler", []).unwrap();
+        assert!(reclaim_free_pages(&conn).unwrap() >= RECLAIMED_FREE_PAGES);
+        let free: i64 = conn.query_row("PRAGMA freelist_count", [], |row| row.get(0)).unwrap();
+        assert_eq!(free, 0);
+        assert_eq!(reclaim_free_pages(&conn).unwrap(), 0);
+
+        let report = vacuum(&conn).unwrap();
+        assert!(report.bytes_after <= report.bytes_before);
+        drop(conn);
+        for suffix in ["", "-wal", "-shm"] {
+            let _ = std::fs::remove_file(format!("{}{}", path.display(), suffix));
+        }
+    }
+
+    #[test]
+    fn test_create_schema() {
+        let conn = Connection::open_in_memory().expect("Failed to create in-memory db");
+        let result = create_schema(&conn);
+
+        assert!(result.is_ok());
+
+        // Verify schema was created by checking for tables
+        let count: i64 = conn.query_row(
+            "SELECT COUNT(*) FROM sqlite_master WHERE type='table'",
+            [],
+            |row| row.get(0)
+        ).expect("Failed to query tables");
+
+        assert!(count > 0, "Schema should create tables");
+    }
+
+    #[test]
+    fn test_upgrade_schema_adds_origin_status() {
+        let conn = Connection::open_in_memory().expect("Failed to create in-memory db");
+        conn.execute_batch(
+            "CREATE TABLE origins (id INTEGER PRIMARY KEY AUTOINCREMENT, name TEXT NOT NULL UNIQUE, description TEXT);
+             CREATE TABLE metadata (key TEXT PRIMARY KEY, value TEXT NOT NULL, updated_at INTEGER NOT NULL);
+             CREATE TABLE transactions (tx INTEGER PRIMARY KEY AUTOINCREMENT, origin TEXT NOT NULL, created_at INTEGER NOT NULL);
+             CREATE TABLE triples (subject TEXT NOT NULL, predicate TEXT NOT NULL, object TEXT, object_value TEXT,
+                                   object_datatype TEXT, object_language TEXT, object_type TEXT NOT NULL DEFAULT 'iri',
+                                   object_number REAL, object_integer INTEGER, object_datetime INTEGER, object_boolean INTEGER,
+                                   tx INTEGER NOT NULL, origin_id INTEGER NOT NULL DEFAULT 1, retracted INTEGER NOT NULL DEFAULT 0,
+                                   created_at INTEGER NOT NULL DEFAULT 0);
+             CREATE INDEX idx_spo ON triples(subject, predicate, object);
+             INSERT INTO origins (name) VALUES ('rdf:core');
+             INSERT INTO triples (subject, predicate, object, tx) VALUES ('owl:Thing', 'rdf:type', 'owl:Class', 1);"
+        ).unwrap();
+
+        upgrade_schema(&conn).unwrap();
+        // Idempotent
+        upgrade_schema(&conn).unwrap();
+
+        let status: String = conn.query_row("SELECT status FROM origins WHERE name = 'rdf:core'", [], |row| row.get(0)).unwrap();
+        assert_eq!(status, "active");
+
+        // Later versions: attachment text index, transaction receive time and annotations, retracting transaction, API tokens, outbox, blobs, graphs, terms
+        conn.execute("INSERT INTO attachment_text (entity, text) VALUES ('foundation:A', 'text')", []).unwrap();
+        conn.execute("INSERT INTO transactions (origin, created_at, received_at) VALUES ('peer', 0, 1)", []).unwrap();
+        conn.execute("INSERT INTO transactions (origin, created_at, message, tags) VALUES ('user-edit', 0, 'Note', '[\"a\"]')", []).unwrap();
+        conn.execute(
+            "INSERT INTO triples (subject, predicate, object_value, object_datatype, object_type, tx, origin_id, retracted, created_at, retracted_tx)
+             VALUES ('foundation:A', 'rdfs:label', 'A', 'xsd:string', 'literal', 1, 1, 1, 0, 2)",
+            [],
+        ).unwrap();
+        conn.execute("INSERT INTO api_tokens (name, token_hash, prefix, scopes, created_at) VALUES ('script', 'h', 'fdn_', '[]', 0)", []).unwrap();
+        conn.execute("INSERT INTO outbox (kind, payload, next_attempt_at, created_at) VALUES ('federated_query', '{}', 0, 0)", []).unwrap();
+        conn.execute("INSERT INTO blobs (hash, content, size, created_at) VALUES ('h', 'text', 4, 0)", []).unwrap();
+        let graph: String = conn.query_row("SELECT graph FROM triples WHERE subject = 'owl:Thing'", [], |row| row.get(0)).unwrap();
+        assert_eq!(graph, super::super::ONTOLOGY_GRAPH);
+
+        // Interned terms: the triples table became a view over quads
+        let kind: String = conn.query_row("SELECT type FROM sqlite_master WHERE name = 'triples'", [], |row| row.get(0)).unwrap();
+        assert_eq!(kind, "view");
+        let object: String = conn.query_row("SELECT object FROM triples WHERE subject = 'owl:Thing'", [], |row| row.get(0)).unwrap();
+        assert_eq!(object, "owl:Class");
+        let version: String = conn.query_row("SELECT value FROM metadata WHERE key = 'schema_version'", [], |row| row.get(0)).unwrap();
+        assert_eq!(version, "19");
+    }
+
+    #[test]
+    fn test_initialize_db_creates_new_database() {
+        let temp_dir = TempDir::new().expect("Failed to create temp dir");
+        let db_path = temp_dir.path().join("test.db");
+
+        assert!(!db_path.exists(), "Database should not exist initially");
+
+        let result = initialize_db(&db_path);
+
+        assert!(result.is_ok(), "Database initialization should succeed");
+        assert!(db_path.exists(), "Database file should be created");
+
+        // The schema doesn't undo the WAL journal configure set
+        let mode: String = result.unwrap().query_row("PRAGMA journal_mode", [], |row| row.get(0)).unwrap();
+        assert_eq!(mode, "wal");
+
+        // Verify we can connect to the created database
+        let conn = Connection::open(&db_path).expect("Should open created database");
+        let count: i64 = conn.query_row(
+            "SELECT COUNT(*) FROM sqlite_master WHERE type='table'",
+            [],
+            |row| row.get(0)
+        ).expect("Failed to query tables");
+
+        assert!(count > 0, "Initialized database should have tables");
+    }
+
+    #[test]
+    fn test_initialize_db_reuses_existing_database() {
+        let temp_dir = TempDir::new().expect("Failed to create temp dir");
+        let db_path = temp_dir.path().join("existing.db");
+
+        // Create a database first
+        {
+            let conn = Connection::open(&db_path).expect("Failed to create initial db");
+            conn.execute_batch(SCHEMA_SQL).expect("Failed to create schema");
+        }
+
+        assert!(db_path.exists(), "Database should exist");
+
+        // Initialize should reuse existing database
+        let result = initialize_db(&db_path);
+
+        assert!(result.is_ok(), "Should reuse existing database");
+        let mode: String = result.unwrap().query_row("PRAGMA journal_mode", [], |row| row.get(0)).unwrap();
+        assert_eq!(mode, "wal");
+    }
+
+    #[test]
+    fn test_get_db_path_returns_path() {
+        let result = get_db_path();
+        assert!(result.is_ok(), "get_db_path should return a valid path");
+
+        let path = result.unwrap();
+        assert!(path.to_str().is_some(), "Path should be valid UTF-8");
+        assert!(path.file_name().is_some(), "Path should have a filename");
+    }
+}
@@ -0,0 +1,237 @@
+//! Database Encryption at Rest
+//!
+//! Built with the `encryption` feature, rusqlite links SQLCipher and the
+//! database file can be encrypted with a key:
+//! - derived from a passphrase the user types (SQLCipher's PBKDF2), or
+//! - a random 256-bit key kept in the OS keychain, so the database unlocks
+//!   without asking
+//!
+//! The key in use is process-wide: every connection to the database is
+//! opened through `open`, which applies it. An encrypted database unlocks at
+//! startup from the keychain when it holds the key (`unlock_from_keychain`);
+//! otherwise the app waits for the passphrase (`wait_unlocked`, `unlock`).
+//!
+//! Without the feature, a plaintext database works as before, and setting
+//! encryption fails.
+
+use rand_core::{OsRng, RngCore};
+use rusqlite::{Connection, OpenFlags};
+use std::path::Path;
+use std::sync::{Condvar, Mutex};
+
+type Result<T> = std::result::Result<T, Box<dyn std::error::Error>>;
+
+/// Keychain entry of the database key
+const KEYCHAIN_SERVICE: &str = "FOUNDATION";
+const KEYCHAIN_ACCOUNT: &str = "database-key";
+
+/// Header of a plaintext SQLite file
+const SQLITE_HEADER: &[u8] = b"SQLite format 3\0";
+
+/// Key the database is opened with (None: plaintext), and a signal for the
+/// thread waiting for it
+static KEY: Mutex<Option<String>> = Mutex::new(None);
+static UNLOCKED: Condvar = Condvar::new();
+
+/// Where the key of an encrypted database comes from
+#[derive(Debug, Clone, PartialEq)]
+pub enum KeySource {
+    Passphrase(String),
+    Keychain, // Created on first use
+}
+
+/// Whether this build can encrypt databases
+pub fn is_supported() -> bool {
+    cfg!(feature = "encryption")
+}
+
+/// Whether a database file is encrypted (false if it doesn't exist)
+pub fn is_encrypted(db_path: &Path) -> bool {
+    let mut header = [0u8; 16];
+    match std::fs::File::open(db_path).and_then(|mut file| std::io::Read::read_exact(&mut file, &mut header)) {
+        Ok(()) => header != SQLITE_HEADER,
+        Err(_) => false,
+    }
+}
+
+/// Open a connection to the database with the key in use
+pub fn open(db_path: &Path) -> rusqlite::Result<Connection> {
+    let conn = Connection::open(db_path)?;
+    apply(&conn)?;
+    Ok(conn)
+}
+
+/// Open a read-only connection to the database with the key in use
+pub fn open_read_only(db_path: &Path) -> rusqlite::Result<Connection> {
+    let conn = Connection::open_with_flags(
+        db_path,
+        OpenFlags::SQLITE_OPEN_READ_ONLY | OpenFlags::SQLITE_OPEN_NO_MUTEX | OpenFlags::SQLITE_OPEN_URI,
+    )?;
+    apply(&conn)?;
+    Ok(conn)
+}
+
+/// Apply the key in use to a connection just opened
+pub fn apply(conn: &Connection) -> rusqlite::Result<()> {
+    if let Some(key) = KEY.lock().unwrap().as_deref() {
+        conn.pragma_update(None, "key", key)?;
+    }
+    Ok(())
+}
+
+/// Unlock an encrypted database with a key, checking it opens it
+pub fn unlock(db_path: &Path, source: &KeySource) -> Result<()> {
+    let key = match source {
+        KeySource::Passphrase(passphrase) => passphrase.clone(),
+        KeySource::Keychain => keychain_key()?.ok_or("The keychain holds no database key")?,
+    };
+    verify(db_path, &key)?;
+    *KEY.lock().unwrap() = Some(key);
+    UNLOCKED.notify_all();
+    Ok(())
+}
+
+/// Unlock an encrypted database with the key in the OS keychain, if it
+/// holds one; returns whether the database is now unlocked
+pub fn unlock_from_keychain(db_path: &Path) -> Result<bool> {
+    match keychain_key()? {
+        Some(_) => unlock(db_path, &KeySource::Keychain).map(|_| true),
+        None => Ok(false),
+    }
+}
+
+/// Block until the database is unlocked (`unlock` from another thread)
+pub fn wait_unlocked() {
+    let mut key = KEY.lock().unwrap();
+    while key.is_none() {
+        key = UNLOCKED.wait(key).unwrap();
+    }
+}
+
+/// Encrypt the database (with a key), change its key, or decrypt it (None)
+///
+/// The database is copied to a sibling file with the new key, which then
+/// replaces it; `conn` is reopened on the result. Runs on the writer's
+/// connection, so no other access sees the switch.
+pub fn set_encryption(conn: &mut Connection, db_path: &Path, source: Option<&KeySource>) -> Result<()> {
+    if !is_supported() {
+        return Err("This build can't encrypt databases (built without the encryption feature)".into());
+    }
+    let key = match source {
+        Some(KeySource::Passphrase(passphrase)) if passphrase.is_empty() => return Err("The passphrase is empty".into()),
+        Some(KeySource::Passphrase(passphrase)) => Some(passphrase.clone()),
+        Some(KeySource::Keychain) => Some(match keychain_key()? {
+            Some(key) => key,
+            None => create_keychain_key()?,
+        }),
+        None => None,
+    };
+
+    let target = db_path.with_extension("rekey");
+    if target.exists() {
+        std::fs::remove_file(&target)?;
+    }
+    conn.execute(
+        "ATTACH DATABASE ?1 AS rekeyed KEY ?2",
+        [target.to_string_lossy().as_ref(), key.as_deref().unwrap_or("")],
+    )?;
+    let exported = conn.query_row("SELECT sqlcipher_export('rekeyed')", [], |_| Ok(()));
+    conn.execute("DETACH DATABASE rekeyed", [])?;
+    if let Err(e) = exported {
+        let _ = std::fs::remove_file(&target);
+        return Err(e.into());
+    }
+
+    // Close the connection before the file under it is replaced
+    let previous = std::mem::replace(conn, Connection::open_in_memory()?);
+    previous.close().map_err(|(_, e)| e)?;
+    // Read connections still open keep the write-ahead log of the previous
+    // file (whose content the copy has): it mustn't be replayed into the new one
+    for sidecar in ["-wal", "-shm"] {
+        let mut path = db_path.as_os_str().to_owned();
+        path.push(sidecar);
+        let _ = std::fs::remove_file(path);
+    }
+    std::fs::rename(&target, db_path)?;
+    *KEY.lock().unwrap() = key;
+    *conn = open(db_path)?;
+    Ok(())
+}
+
+/// Check a key opens a database
+fn verify(db_path: &Path, key: &str) -> Result<()> {
+    let conn = Connection::open(db_path)?;
+    conn.pragma_update(None, "key", key)?;
+    conn.query_row("SELECT COUNT(*) FROM sqlite_master", [], |row| row.get::<_, i64>(0))
+        .map_err(|_| "Wrong passphrase or key")?;
+    Ok(())
+}
+
+/// The database key in the OS keychain (a SQLCipher raw key), if any
+fn keychain_key() -> Result<Option<String>> {
+    match keyring::Entry::new(KEYCHAIN_SERVICE, KEYCHAIN_ACCOUNT)?.get_password() {
+        Ok(hex) => Ok(Some(raw_key(&hex))),
+        Err(keyring::Error::NoEntry) => Ok(None),
+        Err(e) => Err(e.into()),
+    }
+}
+
+/// Create a random database key and keep it in the OS keychain
+fn create_keychain_key() -> Result<String> {
+    let mut bytes = [0u8; 32];
+    OsRng.fill_bytes(&mut bytes);
+    let hex = hex::encode(bytes);
+    keyring::Entry::new(KEYCHAIN_SERVICE, KEYCHAIN_ACCOUNT)?.set_password(&hex)?;
+    Ok(raw_key(&hex))
+}
+
+/// SQLCipher key for raw key bytes (used as is, not derived)
+fn raw_key(hex: &str) -> String {
+    format!("x'{}'", hex)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_plaintext_database_is_not_encrypted() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let path = dir.path().join("plain.db");
+        assert!(!is_encrypted(&path));
+
+        Connection::open(&path).unwrap().execute_batch("CREATE TABLE notes (text TEXT)").unwrap();
+        assert!(!is_encrypted(&path));
+
+        std::fs::write(dir.path().join("other.db"), [7u8; 64]).unwrap();
+        assert!(is_encrypted(&dir.path().join("other.db")));
+    }
+
+    #[cfg(not(feature = "encryption"))]
+    #[test]
+    fn test_set_encryption_needs_the_feature() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let path = dir.path().join("plain.db");
+        let mut conn = Connection::open(&path).unwrap();
+        assert!(set_encryption(&mut conn, &path, Some(&KeySource::Passphrase("secret".to_string()))).is_err());
+    }
+
+    #[cfg(feature = "encryption")]
+    #[test]
+    fn test_encrypt_and_decrypt() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let path = dir.path().join("foundation.db");
+        let mut conn = open(&path).unwrap();
+        conn.execute_batch("CREATE TABLE notes (text TEXT); INSERT INTO notes VALUES ('private')").unwrap();
+
+        set_encryption(&mut conn, &path, Some(&KeySource::Passphrase("secret".to_string()))).unwrap();
+        assert!(is_encrypted(&path));
+        let text: String = conn.query_row("SELECT text FROM notes", [], |row| row.get(0)).unwrap();
+        assert_eq!(text, "private");
+        assert!(unlock(&path, &KeySource::Passphrase("wrong".to_string())).is_err());
+        unlock(&path, &KeySource::Passphrase("secret".to_string())).unwrap();
+
+        set_encryption(&mut conn, &path, None).unwrap();
+        assert!(!is_encrypted(&path));
+    }
+}
@@ -0,0 +1,656 @@
+// ============================================================================
+// EAVTO Executor Module
+// ============================================================================
+// Provides async execution for database operations to avoid blocking the UI
+//
+// Architecture:
+// - Single writer thread and connection, with a sequential queue for writes
+// - Reads run on a pool of read-only connections to the same file (WAL
+//   mode, so they don't wait for the writer or each other); an in-memory
+//   database has no file to share, and its reads use the writer's connection
+// - All operations are async to avoid blocking Tauri's event loop
+//
+// Read-after-write consistency:
+// - Writes return the latest committed transaction ID with their result
+// - Reads take an optional `min_tx` and wait until that transaction is
+//   committed, so a read issued after a write always observes it (a pooled
+//   connection sees every transaction committed before its read starts)
+//
+// Buffered writes:
+// - Collectors' small writes held by store::assert_buffered are written
+//   by the writer thread as their intervals end
+//
+// Dry runs:
+// - `write_or_preview` runs a write and rolls it back when asked to, and
+//   returns the triples it would add and retract with its result, so any
+//   mutating command can be previewed the same way
+//
+// Journal:
+// - Every write (dry runs aside) is recorded in the write journal, named
+//   after the command it was written in (see journal)
+//
+// Quotas:
+// - After each write, the origin that made it is checked against its soft
+//   storage quota (see stats), and a warning logged when it is over
+// ============================================================================
+
+use rusqlite::Connection;
+use serde::Serialize;
+use std::path::PathBuf;
+use std::sync::{Arc, Condvar, Mutex};
+use std::time::{Duration, Instant};
+use tokio::sync::{mpsc, oneshot, watch};
+use ts_rs::TS;
+use super::{connection, encryption, journal, query, sandbox, stats, store, Object, Triple};
+
+/// How long a read waits for the transaction it must observe
+const MIN_TX_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Time between checks for buffered assertions to write
+const FLUSH_INTERVAL: Duration = Duration::from_millis(1_000);
+
+/// Triples of each kind listed in a dry run diff
+const DRY_RUN_SAMPLES: usize = 20;
+
+/// Read-only connections open at most (reads beyond wait for one)
+const READERS: usize = 4;
+
+/// Executor for database operations
+/// Ensures writes are sequential while allowing parallel reads
+pub struct DbExecutor {
+    write_tx: mpsc::UnboundedSender<WriteTask>,
+    conn: Arc<Mutex<Connection>>,
+    readers: Arc<ReadPool>,
+    committed: Arc<watch::Sender<i64>>,
+}
+
+/// Read-only connections to the writer's database file
+struct ReadPool {
+    state: Mutex<ReadPoolState>,
+    returned: Condvar,
+}
+
+struct ReadPoolState {
+    path: Option<PathBuf>, // None: in-memory database, reads use the writer's connection
+    idle: Vec<Connection>,
+    open: usize,
+    generation: u64, // Connections of an earlier generation are to a replaced database
+}
+
+/// Connection a read runs on
+enum Reader {
+    Pooled(Connection, u64),
+    Writer,
+}
+
+impl ReadPool {
+    fn new(conn: &Connection) -> Self {
+        let pool = Self {
+            state: Mutex::new(ReadPoolState { path: None, idle: Vec::new(), open: 0, generation: 0 }),
+            returned: Condvar::new(),
+        };
+        pool.reset(conn);
+        pool
+    }
+
+    /// Follow the writer's connection to its database: reads from then on
+    /// open connections to it (once it's configured for WAL), and those to
+    /// the previous database are closed as they're returned
+    fn reset(&self, conn: &Connection) {
+        let path = conn.path().filter(|path| !path.is_empty()).map(PathBuf::from);
+        let path = path.filter(|_| {
+            let configured = connection::configure(conn).map_err(|e| format!("{:?}", e)).and_then(|_| {
+                conn.query_row("PRAGMA journal_mode", [], |row| row.get::<_, String>(0)).map_err(|e| e.to_string())
+            });
+            match configured {
+                Ok(mode) => mode.eq_ignore_ascii_case("wal"),
+                Err(e) => {
+                    println!("⚠️  Database configuration: {}, reads share the writer's connection", e);
+                    false
+                }
+            }
+        });
+
+        let mut state = self.state.lock().unwrap();
+        *state = ReadPoolState { path, idle: Vec::new(), open: 0, generation: state.generation + 1 };
+        self.returned.notify_all();
+    }
+
+    /// A connection for a read: an idle one, a new one while fewer than
+    /// READERS are open, or else the next one returned
+    fn take(&self) -> Result<Reader, String> {
+        let mut state = self.state.lock().map_err(|e| e.to_string())?;
+        loop {
+            let Some(path) = state.path.clone() else {
+                return Ok(Reader::Writer);
+            };
+            if let Some(conn) = state.idle.pop() {
+                return Ok(Reader::Pooled(conn, state.generation));
+            }
+            if state.open < READERS {
+                state.open += 1;
+                let generation = state.generation;
+                drop(state);
+                let opened = encryption::open_read_only(&path).map_err(|e| format!("{}", e))
+                    .and_then(|conn| connection::tune(&conn).map(|_| conn).map_err(|e| format!("{:?}", e)));
+                return match opened {
+                    Ok(conn) => Ok(Reader::Pooled(conn, generation)),
+                    Err(e) => {
+                        self.give_back(None, generation);
+                        Err(format!("Failed to open a read connection: {}", e))
+                    }
+                };
+            }
+            state = self.returned.wait(state).map_err(|e| e.to_string())?;
+        }
+    }
+
+    /// Return a connection taken for a read (None: it failed to open)
+    fn give_back(&self, conn: Option<Connection>, generation: u64) {
+        let mut state = self.state.lock().unwrap();
+        if generation == state.generation {
+            match conn {
+                Some(conn) => state.idle.push(conn),
+                None => state.open -= 1,
+            }
+            self.returned.notify_one();
+        }
+    }
+}
+
+/// Result of a write, with the latest transaction committed when it finished
+/// Pass `tx` as `min_tx` to later reads so they observe the write
+#[derive(Debug, Serialize, TS)]
+#[ts(export)]
+pub struct Committed<T> {
+    #[ts(type = "number")]
+    pub tx: i64,
+    pub value: T,
+    /// Set for dry runs: nothing was committed, and this is what would have been
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub dry_run: Option<DryRunDiff>,
+}
+
+/// Triples a dry run would have added and retracted: counts, plus the first
+/// few of each
+#[derive(Debug, Clone, Serialize, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export)]
+pub struct DryRunDiff {
+    #[ts(type = "number")]
+    pub added: usize,
+    #[ts(type = "number")]
+    pub retracted: usize,
+    pub added_sample: Vec<DiffTriple>,
+    pub retracted_sample: Vec<DiffTriple>,
+}
+
+/// A triple in a dry run diff; object is an IRI or a literal's lexical form
+#[derive(Debug, Clone, Serialize, TS)]
+#[ts(export)]
+pub struct DiffTriple {
+    pub subject: String,
+    pub predicate: String,
+    pub object: String,
+}
+
+impl From<&sandbox::Preview> for DryRunDiff {
+    fn from(preview: &sandbox::Preview) -> Self {
+        let sample = |triples: &[Triple]| triples.iter()
+            .take(DRY_RUN_SAMPLES)
+            .map(|t| DiffTriple {
+                subject: t.subject.clone(),
+                predicate: t.predicate.clone(),
+                object: match &t.object {
+                    Object::Iri(iri) | Object::Blank(iri) => iri.clone(),
+                    other => other.as_literal().unwrap_or_default(),
+                },
+            })
+            .collect();
+        Self {
+            added: preview.added.len(),
+            retracted: preview.retracted.len(),
+            added_sample: sample(&preview.added),
+            retracted_sample: sample(&preview.retracted),
+        }
+    }
+}
+
+/// Warn when the origin of a transaction is over its storage quota (see
+/// stats): the write stays, the user is told which source grows too large
+fn warn_over_quota(conn: &Connection, tx: i64) {
+    if let Ok(Some(usage)) = stats::check_quota(conn, tx) {
+        println!(
+            "⚠️  Origin {} is over its storage quota: ~{} bytes of {} ({} rows)",
+            usage.name, usage.estimated_bytes, usage.quota_bytes.unwrap_or(0), usage.rows
+        );
+    }
+}
+
+/// A write task to be executed sequentially
+/// The operation sends its own result back to the caller
+struct WriteTask {
+    operation: Box<dyn FnOnce(&mut Connection) + Send>,
+}
+
+impl DbExecutor {
+    /// Create a new executor with the given connection
+    pub fn new(conn: Connection) -> Self {
+        let committed = Arc::new(watch::Sender::new(query::get_latest_tx(&conn).unwrap_or(0)));
+        let readers = Arc::new(ReadPool::new(&conn));
+        let conn = Arc::new(Mutex::new(conn));
+        let (write_tx, mut write_rx) = mpsc::unbounded_channel::<WriteTask>();
+
+        // Spawn writer thread that processes writes sequentially
+        let writer_conn = Arc::clone(&conn);
+        std::thread::spawn(move || {
+            while let Some(task) = write_rx.blocking_recv() {
+                let mut conn = writer_conn.lock().unwrap();
+                (task.operation)(&mut conn);
+            }
+        });
+
+        // Write the buffered assertions of collectors as their intervals end
+        // (store::assert_buffered), until the executor is dropped
+        let flusher = write_tx.downgrade();
+        let flush_committed = Arc::clone(&committed);
+        std::thread::spawn(move || loop {
+            std::thread::sleep(FLUSH_INTERVAL);
+            let Some(write_tx) = flusher.upgrade() else { break };
+            if !store::has_buffered() {
+                continue;
+            }
+            let committed = Arc::clone(&flush_committed);
+            let _ = write_tx.send(WriteTask {
+                operation: Box::new(move |conn| match store::flush_due(conn) {
+                    Ok(written) => {
+                        for &tx in &written {
+                            warn_over_quota(conn, tx);
+                        }
+                        if let Some(&tx) = written.iter().max() {
+                            committed.send_if_modified(|latest| {
+                                let newer = tx > *latest;
+                                if newer {
+                                    *latest = tx;
+                                }
+                                newer
+                            });
+                        }
+                    }
+                    Err(e) => println!("⚠️  Buffered writes: {}", e),
+                }),
+            });
+        });
+
+        Self { write_tx, conn, readers, committed }
+    }
+
+    /// Latest committed transaction ID
+    pub fn committed_tx(&self) -> i64 {
+        *self.committed.borrow()
+    }
+
+    /// Follow the latest committed transaction
+    pub fn subscribe(&self) -> watch::Receiver<i64> {
+        self.committed.subscribe()
+    }
+
+    /// Execute a read operation (can run in parallel)
+    /// min_tx: wait until this transaction is committed before reading
+    /// Returns immediately without blocking the event loop
+    pub async fn read<F, R>(&self, min_tx: Option<i64>, operation: F) -> Result<R, String>
+    where
+        F: FnOnce(&Connection) -> Result<R, String> + Send + 'static,
+        R: Send + 'static,
+    {
+        if let Some(min_tx) = min_tx {
+            self.wait_for(min_tx).await?;
+        }
+
+        let conn = Arc::clone(&self.conn);
+        let readers = Arc::clone(&self.readers);
+
+        tokio::task::spawn_blocking(move || match readers.take()? {
+            Reader::Pooled(reader, generation) => {
+                let result = operation(&reader);
+                readers.give_back(Some(reader), generation);
+                result
+            }
+            Reader::Writer => {
+                let conn = conn.lock().map_err(|e| e.to_string())?;
+                operation(&conn)
+            }
+        })
+        .await
+        .map_err(|e| e.to_string())?
+    }
+
+    /// Execute a write operation (sequential, queued)
+    /// Returns the result with the latest committed transaction
+    /// Returns immediately without blocking the event loop
+    pub async fn write<F, R>(&self, operation: F) -> Result<Committed<R>, String>
+    where
+        F: FnOnce(&mut Connection) -> Result<R, String> + Send + 'static,
+        R: Send + 'static,
+    {
+        self.queue_write(Some(journal::command_of::<F>()), operation).await
+    }
+
+    /// Queue a write, journaled under `command` (None: not journaled)
+    async fn queue_write<F, R>(&self, command: Option<String>, operation: F) -> Result<Committed<R>, String>
+    where
+        F: FnOnce(&mut Connection) -> Result<R, String> + Send + 'static,
+        R: Send + 'static,
+    {
+        let (result_tx, result_rx) = oneshot::channel();
+        let committed = Arc::clone(&self.committed);
+        // Take the arguments of the invocation now, while the write is queued in order
+        let journaled = command.map(|command| {
+            let args_hash = journal::take_args_hash(&command);
+            (command, args_hash)
+        });
+
+        let task = WriteTask {
+            operation: Box::new(move |conn| {
+                let started = Instant::now();
+                let before = query::get_latest_tx(conn).unwrap_or(0);
+                let result = operation(conn).and_then(|value| {
+                    let tx = query::get_latest_tx(conn).map_err(|e| e.to_string())?;
+                    Ok(Committed { tx, value, dry_run: None })
+                });
+                let tx = result.as_ref().ok().map(|written| written.tx).filter(|tx| *tx > before);
+                if let Some(tx) = tx {
+                    warn_over_quota(conn, tx);
+                }
+                if let Some((command, args_hash)) = journaled {
+                    let error = result.as_ref().err().map(String::as_str);
+                    if let Err(e) = journal::record(conn, &command, args_hash.as_deref(), tx, started.elapsed(), error) {
+                        println!("⚠️  Write journal: {}", e);
+                    }
+                }
+                // Publish before replying, so reads with this min_tx never wait
+                if let Ok(written) = &result {
+                    committed.send_if_modified(|latest| {
+                        let newer = written.tx > *latest;
+                        if newer {
+                            *latest = written.tx;
+                        }
+                        newer
+                    });
+                }
+                let _ = result_tx.send(result);
+            }),
+        };
+
+        self.write_tx.send(task).map_err(|e| e.to_string())?;
+        result_rx.await.map_err(|e| e.to_string())?
+    }
+
+    /// Execute a write operation, or with dry_run, preview it: the write
+    /// runs and is rolled back, and the result carries the diff it would
+    /// have made (`tx` is then the latest transaction, unchanged)
+    pub async fn write_or_preview<F, R>(&self, dry_run: Option<bool>, operation: F) -> Result<Committed<R>, String>
+    where
+        F: FnOnce(&mut Connection) -> Result<R, String> + Send + 'static,
+        R: Send + 'static,
+    {
+        if !dry_run.unwrap_or(false) {
+            return self.queue_write(Some(journal::command_of::<F>()), operation).await;
+        }
+
+        let previewed = self.queue_write(None, move |conn| {
+            sandbox::dry_run(conn, operation).map_err(|e| e.to_string())
+        }).await?;
+        let (value, preview) = previewed.value;
+        Ok(Committed { tx: previewed.tx, value, dry_run: Some((&preview).into()) })
+    }
+
+    /// Switch to another database, after the writes queued before; returns
+    /// the connection replaced
+    ///
+    /// Reads and writes from then on use the new database (e.g., a demo
+    /// profile), and the committed transaction follows it.
+    pub async fn swap(&self, conn: Connection) -> Result<Connection, String> {
+        let latest = query::get_latest_tx(&conn).unwrap_or(0);
+        let (result_tx, result_rx) = oneshot::channel();
+        let committed = Arc::clone(&self.committed);
+        let readers = Arc::clone(&self.readers);
+
+        let task = WriteTask {
+            operation: Box::new(move |current| {
+                let previous = std::mem::replace(current, conn);
+                readers.reset(current);
+                committed.send_replace(latest);
+                let _ = result_tx.send(previous);
+            }),
+        };
+
+        self.write_tx.send(task).map_err(|e| e.to_string())?;
+        result_rx.await.map_err(|e| e.to_string())
+    }
+
+    /// Reopen the read connections, after the writer's connection was
+    /// reopened on a replaced database file (see encryption::set_encryption)
+    pub async fn reopen_readers(&self) -> Result<(), String> {
+        let (result_tx, result_rx) = oneshot::channel();
+        let readers = Arc::clone(&self.readers);
+
+        let task = WriteTask {
+            operation: Box::new(move |conn| {
+                readers.reset(conn);
+                let _ = result_tx.send(());
+            }),
+        };
+
+        self.write_tx.send(task).map_err(|e| e.to_string())?;
+        result_rx.await.map_err(|e| e.to_string())
+    }
+
+    /// Wait until a transaction is committed
+    async fn wait_for(&self, min_tx: i64) -> Result<(), String> {
+        let mut committed = self.committed.subscribe();
+        let waited = tokio::time::timeout(MIN_TX_TIMEOUT, committed.wait_for(|latest| *latest >= min_tx))
+            .await
+            .map(|result| result.map(|_| ()));
+        match waited {
+            Ok(Ok(())) => Ok(()),
+            Ok(Err(e)) => Err(e.to_string()),
+            Err(_) => Err(format!(
+                "Transaction {} was not committed (latest is {})",
+                min_tx,
+                self.committed_tx()
+            )),
+        }
+    }
+}
+
+// Make DbExecutor cloneable so it can be shared across commands
+impl Clone for DbExecutor {
+    fn clone(&self) -> Self {
+        Self {
+            write_tx: self.write_tx.clone(),
+            conn: Arc::clone(&self.conn),
+            readers: Arc::clone(&self.readers),
+            committed: Arc::clone(&self.committed),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::eavto::{store, test_helpers::{setup_test_db, setup_test_db_file}};
+    use sha2::{Digest, Sha256};
+
+    fn runtime() -> tokio::runtime::Runtime {
+        tokio::runtime::Builder::new_multi_thread().enable_all().build().unwrap()
+    }
+
+    fn assert_label(conn: &mut Connection, subject: &str) -> Result<i64, String> {
+        store::assert_triples(conn, &[
+            Triple::new(subject, "rdfs:label", Object::Literal {
+                value: subject.to_string(),
+                datatype: Some("xsd:string".to_string()),
+                language: None,
+            }),
+        ], "test").map_err(|e| e.to_string())
+    }
+
+    #[test]
+    fn test_write_returns_committed_tx() {
+        runtime().block_on(async {
+            let executor = DbExecutor::new(setup_test_db());
+            assert_eq!(executor.committed_tx(), 0);
+
+            let written = executor.write(|conn| assert_label(conn, "foundation:A")).await.unwrap();
+            assert_eq!(written.tx, written.value);
+            assert_eq!(executor.committed_tx(), written.tx);
+
+            // Reads with min_tx observe the write
+            let count = executor.read(Some(written.tx), |conn| {
+                query::get_by_entity(conn, "foundation:A").map_err(|e| e.to_string())
+            }).await.unwrap().triples.len();
+            assert_eq!(count, 1);
+        });
+    }
+
+    #[test]
+    fn test_read_waits_for_min_tx() {
+        runtime().block_on(async {
+            let executor = DbExecutor::new(setup_test_db());
+
+            let reader = executor.clone();
+            let read = tokio::spawn(async move {
+                reader.read(Some(1), |conn| {
+                    query::get_by_entity(conn, "foundation:B").map_err(|e| e.to_string())
+                }).await
+            });
+
+            executor.write(|conn| assert_label(conn, "foundation:B")).await.unwrap();
+            assert_eq!(read.await.unwrap().unwrap().triples.len(), 1);
+        });
+    }
+
+    #[test]
+    fn test_reads_run_concurrently_on_file_databases() {
+        runtime().block_on(async {
+            let path = std::env::temp_dir().join("foundation-executor-test.db");
+            let executor = DbExecutor::new(setup_test_db_file(&path));
+            let written = executor.write(|conn| assert_label(conn, "foundation:A")).await.unwrap();
+
+            // Each read waits for the other to start: serialized, one would time out
+            let (a_started, a_seen) = std::sync::mpsc::channel();
+            let (b_started, b_seen) = std::sync::mpsc::channel();
+            let read = |started: std::sync::mpsc::Sender<()>, seen: std::sync::mpsc::Receiver<()>| {
+                let reader = executor.clone();
+                tokio::spawn(async move {
+                    reader.read(Some(written.tx), move |conn| {
+                        started.send(()).unwrap();
+                        let overlapped = seen.recv_timeout(Duration::from_secs(5)).is_ok();
+                        let found = query::get_by_entity(conn, "foundation:A").map_err(|e| e.to_string())?.triples.len();
+                        Ok((overlapped, found))
+                    }).await
+                })
+            };
+            let (a, b) = (read(a_started, b_seen), read(b_started, a_seen));
+            assert_eq!(a.await.unwrap().unwrap(), (true, 1));
+            assert_eq!(b.await.unwrap().unwrap(), (true, 1));
+
+            let mode: String = executor.read(None, |conn| {
+                conn.query_row("PRAGMA journal_mode", [], |row| row.get(0)).map_err(|e| e.to_string())
+            }).await.unwrap();
+            assert_eq!(mode, "wal");
+
+            // Pooled connections are read-only
+            let written = executor.read(None, |conn| {
+                conn.execute("INSERT INTO origins (name) VALUES ('other')", []).map_err(|e| e.to_string())
+            }).await;
+            assert!(written.is_err());
+
+            drop(executor);
+            for suffix in ["", "-wal", "-shm"] {
+                let _ = std::fs::remove_file(format!("{}{}", path.display(), suffix));
+            }
+        });
+    }
+
+    #[test]
+    fn test_write_or_preview_dry_run() {
+        runtime().block_on(async {
+            let executor = DbExecutor::new(setup_test_db());
+            let written = executor.write_or_preview(None, |conn| assert_label(conn, "foundation:A")).await.unwrap();
+            assert!(written.dry_run.is_none());
+
+            let previewed = executor.write_or_preview(Some(true), |conn| assert_label(conn, "foundation:B")).await.unwrap();
+            assert_eq!(previewed.tx, written.tx);
+            let diff = previewed.dry_run.unwrap();
+            assert_eq!((diff.added, diff.retracted), (1, 0));
+            assert_eq!(diff.added_sample[0].object, "foundation:B");
+
+            assert_eq!(executor.committed_tx(), written.tx);
+            let count = executor.read(None, |conn| {
+                query::get_by_entity(conn, "foundation:B").map_err(|e| e.to_string())
+            }).await.unwrap().triples.len();
+            assert_eq!(count, 0);
+        });
+    }
+
+    #[test]
+    fn test_swap_switches_database() {
+        runtime().block_on(async {
+            let executor = DbExecutor::new(setup_test_db());
+            executor.write(|conn| assert_label(conn, "foundation:A")).await.unwrap();
+            executor.write(|conn| assert_label(conn, "foundation:B")).await.unwrap();
+
+            let previous = executor.swap(setup_test_db()).await.unwrap();
+            assert_eq!(executor.committed_tx(), 0);
+            let count = executor.read(None, |conn| {
+                query::get_by_entity(conn, "foundation:A").map_err(|e| e.to_string())
+            }).await.unwrap().triples.len();
+            assert_eq!(count, 0);
+
+            executor.swap(previous).await.unwrap();
+            assert_eq!(executor.committed_tx(), 2);
+        });
+    }
+
+    #[test]
+    fn test_writes_are_journaled() {
+        runtime().block_on(async {
+            let executor = DbExecutor::new(setup_test_db());
+            journal::invoked("test_writes_are_journaled", &serde_json::json!({ "subject": "foundation:A" }));
+            let written = executor.write(|conn| assert_label(conn, "foundation:A")).await.unwrap();
+            executor.write_or_preview(Some(true), |conn| assert_label(conn, "foundation:B")).await.unwrap();
+            let failed = executor.write(|_| Err::<(), _>("Invalid".to_string())).await;
+            assert!(failed.is_err());
+
+            let entries = executor.read(None, |conn| journal::recent(conn, 10).map_err(|e| e.to_string())).await.unwrap();
+            assert_eq!(entries.len(), 2);
+            assert_eq!(entries[1].command, "test_writes_are_journaled");
+            assert_eq!(entries[1].tx, Some(written.tx));
+            assert!(entries[1].args_hash.is_some());
+            assert_eq!((entries[0].tx, entries[0].error.as_deref()), (None, Some("Invalid")));
+            assert!(entries[0].args_hash.is_none());
+        });
+    }
+
+    #[test]
+    fn test_dry_run_then_write_is_journaled_with_its_own_arguments() {
+        runtime().block_on(async {
+            let executor = DbExecutor::new(setup_test_db());
+            let preview = serde_json::json!({ "subject": "foundation:A", "dryRun": true });
+            let write = serde_json::json!({ "subject": "foundation:A", "dryRun": false });
+
+            journal::invoked("test_dry_run_then_write_is_journaled_with_its_own_arguments", &preview);
+            executor.write_or_preview(Some(true), |conn| assert_label(conn, "foundation:A")).await.unwrap();
+            journal::invoked("test_dry_run_then_write_is_journaled_with_its_own_arguments", &write);
+            executor.write_or_preview(Some(false), |conn| assert_label(conn, "foundation:A")).await.unwrap();
+
+            let entries = executor.read(None, |conn| journal::recent(conn, 10).map_err(|e| e.to_string())).await.unwrap();
+            assert_eq!(entries.len(), 1);
+            let expected = format!("{:x}", Sha256::digest(write.to_string().as_bytes()));
+            assert_eq!(entries[0].args_hash, Some(expected));
+        });
+    }
+}
@@ -0,0 +1,148 @@
+//! Triple Indexes
+//!
+//! The access paths of the quads table, created when a database is opened:
+//! - SPO, POS, OSP and OPS covering indexes over term IDs, for every quad
+//! - The same lookups over current quads only (partial on `retracted = 0`),
+//!   which normal queries read (see query::visible). Their leading columns
+//!   are the lookup; origin_id and tx follow, so the visibility filter and
+//!   the ordering are read from the index
+//! - Predicate-value indexes over the typed columns, for range queries
+//!   (see query::get_by_predicate_range)
+//!
+//! Bulk imports are followed by an ANALYZE, so SQLite's planner knows how
+//! selective each index is. `explain` shows the plans of the hot lookups.
+
+use rusqlite::Connection;
+use super::query;
+
+type Result<T> = std::result::Result<T, Box<dyn std::error::Error>>;
+
+/// Indexes on quads: (name, definition)
+const INDEXES: &[(&str, &str)] = &[
+    // Triples about a subject (the most common query)
+    ("idx_spo", "quads(subject_id, predicate_id, object_id, object_value, tx, origin_id)"),
+    // Subjects with a predicate-object
+    ("idx_pos", "quads(predicate_id, object_id, object_value, subject_id, tx, origin_id)"),
+    // Subjects by object (reverse lookup for IRIs)
+    ("idx_osp", "quads(object_id, subject_id, predicate_id, tx, origin_id) WHERE object_type = 'iri'"),
+    // Triples referencing an object, by predicate (backlinks)
+    ("idx_ops", "quads(object_id, predicate_id, subject_id, tx, origin_id) WHERE object_type = 'iri'"),
+    // Current quads
+    ("idx_spo_current", "quads(subject_id, predicate_id, object_id, origin_id, tx) WHERE retracted = 0"),
+    ("idx_pos_current", "quads(predicate_id, object_id, subject_id, origin_id, tx) WHERE retracted = 0"),
+    ("idx_osp_current", "quads(object_id, subject_id, predicate_id, origin_id, tx) WHERE retracted = 0 AND object_type = 'iri'"),
+    // Current typed values by predicate, in order (range queries)
+    ("idx_pv_integer", "quads(predicate_id, object_integer) WHERE retracted = 0 AND object_integer IS NOT NULL"),
+    ("idx_pv_number", "quads(predicate_id, object_number) WHERE retracted = 0 AND object_number IS NOT NULL"),
+    ("idx_pv_datetime", "quads(predicate_id, object_datetime) WHERE retracted = 0 AND object_datetime IS NOT NULL"),
+];
+
+/// Triples an import writes from which it is followed by an ANALYZE
+pub const BULK_IMPORT: u64 = 1000;
+
+/// Rows ANALYZE reads per index, so it stays fast on large stores
+const ANALYSIS_LIMIT: u32 = 1000;
+
+/// The lookups behind Class::get and the entity graph: (function, condition)
+const HOT_QUERIES: &[(&str, &str)] = &[
+    ("get_by_entity", query::BY_ENTITY),
+    ("get_by_entity_predicate", query::BY_ENTITY_PREDICATE),
+    ("get_by_predicate_object", query::BY_PREDICATE_OBJECT),
+    ("get_by_object", query::BY_OBJECT),
+];
+
+/// Query plan of a hot lookup
+#[derive(Debug, Clone)]
+pub struct QueryPlan {
+    pub query: String,
+    pub sql: String,
+    pub steps: Vec<String>, // EXPLAIN QUERY PLAN, nested steps indented
+}
+
+/// Create the indexes a database doesn't have yet
+pub fn ensure(conn: &Connection) -> rusqlite::Result<()> {
+    let batch: String = INDEXES
+        .iter()
+        .map(|(name, definition)| format!("CREATE INDEX IF NOT EXISTS {} ON {};\n", name, definition))
+        .collect();
+    conn.execute_batch(&batch)
+}
+
+/// Update the statistics the planner chooses indexes by
+pub fn analyze(conn: &Connection) -> rusqlite::Result<()> {
+    conn.execute_batch(&format!("PRAGMA analysis_limit = {}; ANALYZE quads; ANALYZE terms;", ANALYSIS_LIMIT))
+}
+
+/// Analyze after an import, if it was a bulk one (see BULK_IMPORT)
+pub fn analyze_after_import(conn: &Connection, triples: u64) -> rusqlite::Result<()> {
+    if triples >= BULK_IMPORT {
+        analyze(conn)?;
+    }
+    Ok(())
+}
+
+/// Plans of the hot lookups
+pub fn explain(conn: &Connection) -> Result<Vec<QueryPlan>> {
+    HOT_QUERIES
+        .iter()
+        .map(|(name, condition)| {
+            let sql = query::quads_sql(condition);
+            let mut stmt = conn.prepare(&format!("EXPLAIN QUERY PLAN {}", sql))?;
+            let parameters = vec![rusqlite::types::Null; stmt.parameter_count()];
+            let rows = stmt
+                .query_map(rusqlite::params_from_iter(parameters), |row| {
+                    Ok((row.get::<_, i64>(0)?, row.get::<_, i64>(1)?, row.get::<_, String>(3)?))
+                })?
+                .collect::<std::result::Result<Vec<_>, _>>()?;
+
+            // Steps name their parent step: indent them under it
+            let mut depths = std::collections::HashMap::new();
+            let steps = rows
+                .into_iter()
+                .map(|(id, parent, detail)| {
+                    let depth = depths.get(&parent).map_or(0, |depth| depth + 1);
+                    depths.insert(id, depth);
+                    format!("{}{}", "  ".repeat(depth), detail)
+                })
+                .collect();
+
+            Ok(QueryPlan { query: name.to_string(), sql, steps })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::eavto::test_helpers::setup_test_db;
+    use crate::eavto::{store, Object, Triple};
+
+    #[test]
+    fn test_hot_lookups_use_current_indexes() {
+        let mut conn = setup_test_db();
+        let people: Vec<Triple> = (0..BULK_IMPORT)
+            .flat_map(|i| {
+                let person = format!("foundation:person{}", i);
+                [
+                    Triple::new(&person, "rdf:type", Object::Iri("foundation:Person".to_string())),
+                    Triple::new(&person, "rdfs:label", Object::Literal {
+                        value: format!("Person {}", i),
+                        datatype: None,
+                        language: None,
+                    }),
+                ]
+            })
+            .collect();
+        store::assert_triples(&mut conn, &people, "test").unwrap();
+        analyze(&conn).unwrap();
+
+        let plans = explain(&conn).unwrap();
+        assert_eq!(plans.len(), HOT_QUERIES.len());
+        for plan in plans {
+            let lookup = &plan.steps.iter().find(|step| step.starts_with("SEARCH q")).unwrap_or_else(|| {
+                panic!("{} doesn't search quads by index: {:?}", plan.query, plan.steps)
+            });
+            assert!(lookup.contains("_current"), "{}: {}", plan.query, lookup);
+        }
+    }
+}
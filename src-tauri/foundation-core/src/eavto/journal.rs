@@ -0,0 +1,194 @@
+//! Write Journal
+//!
+//! Every write the executor runs is recorded in the write_journal table:
+//! the command that asked for it, a hash of that command's arguments, the
+//! transaction it made, how long it took and the error it returned. When a
+//! database turns out corrupted, the journal gives the exact sequence of
+//! operations that led there. Only the latest JOURNAL_SIZE writes are kept.
+//!
+//! The executor names a write after the function its operation closure was
+//! written in (a command, for writes asked for by the frontend). The app
+//! shell hashes the arguments of each command as it is invoked (`invoked`,
+//! dry runs aside); the write of that command takes the hash as it is
+//! queued and carries it to the journal, invocations of one command being
+//! served in order. A hash no write takes within PENDING_TTL is dropped:
+//! its command returned without writing (or the write is journaled
+//! without one).
+
+use rusqlite::Connection;
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+use std::collections::{BTreeMap, VecDeque};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use super::store::now_millis;
+
+type Result<T> = std::result::Result<T, Box<dyn std::error::Error>>;
+
+/// Number of writes kept in the journal
+pub const JOURNAL_SIZE: i64 = 1000;
+
+/// Argument hashes kept per command until its write runs (invocations
+/// that never write, like reads, are dropped past this)
+const PENDING_SIZE: usize = 16;
+
+/// How long an argument hash waits for the write of its command
+const PENDING_TTL: Duration = Duration::from_secs(5);
+
+/// Argument hashes of invoked commands whose write hasn't been queued yet,
+/// with when they were invoked
+static PENDING: Mutex<BTreeMap<String, VecDeque<(Instant, String)>>> = Mutex::new(BTreeMap::new());
+
+/// A write, as journaled
+#[derive(Debug, Clone, PartialEq)]
+pub struct JournalEntry {
+    pub id: i64,
+    pub command: String,
+    pub args_hash: Option<String>,
+    pub tx: Option<i64>,
+    pub duration_ms: i64,
+    pub error: Option<String>,
+    pub recorded_at: i64,
+}
+
+/// Note the arguments of an invoked command, for the journal entry of its
+/// write (dry runs write nothing and are skipped)
+pub fn invoked(command: &str, args: &Value) {
+    if args.get("dryRun") == Some(&Value::Bool(true)) {
+        return;
+    }
+    let mut pending = PENDING.lock().unwrap();
+    let queue = pending.entry(command.to_string()).or_default();
+    if queue.len() == PENDING_SIZE {
+        queue.pop_front();
+    }
+    queue.push_back((Instant::now(), args_hash(args)));
+}
+
+/// Argument hash of the oldest invocation of a command not yet written,
+/// dropping those past PENDING_TTL
+pub(crate) fn take_args_hash(command: &str) -> Option<String> {
+    let mut pending = PENDING.lock().unwrap();
+    let queue = pending.get_mut(command)?;
+    while let Some((invoked_at, hash)) = queue.pop_front() {
+        if invoked_at.elapsed() < PENDING_TTL {
+            return Some(hash);
+        }
+    }
+    None
+}
+
+fn args_hash(args: &Value) -> String {
+    format!("{:x}", Sha256::digest(args.to_string().as_bytes()))
+}
+
+/// Name of the function an operation closure was written in
+/// ("bulk__update" for a closure in commands::bulk::bulk__update)
+pub(crate) fn command_of<F>() -> String {
+    let path = std::any::type_name::<F>();
+    let function = path.split("::{{closure}}").next().unwrap_or(path);
+    function.rsplit("::").next().unwrap_or(function).to_string()
+}
+
+/// Record a write, dropping the entries past JOURNAL_SIZE
+pub(crate) fn record(
+    conn: &Connection,
+    command: &str,
+    args_hash: Option<&str>,
+    tx: Option<i64>,
+    duration: Duration,
+    error: Option<&str>,
+) -> Result<()> {
+    conn.execute(
+        "INSERT INTO write_journal (command, args_hash, tx, duration_ms, error, recorded_at)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+        rusqlite::params![command, args_hash, tx, duration.as_millis() as i64, error, now_millis()],
+    )?;
+    conn.execute(
+        "DELETE FROM write_journal WHERE id <= ?",
+        [conn.last_insert_rowid() - JOURNAL_SIZE],
+    )?;
+    Ok(())
+}
+
+/// The latest writes, newest first
+pub fn recent(conn: &Connection, limit: usize) -> Result<Vec<JournalEntry>> {
+    let mut stmt = conn.prepare(
+        "SELECT id, command, args_hash, tx, duration_ms, error, recorded_at
+         FROM write_journal
+         ORDER BY id DESC
+         LIMIT ?"
+    )?;
+    let entries = stmt
+        .query_map([limit as i64], |row| Ok(JournalEntry {
+            id: row.get(0)?,
+            command: row.get(1)?,
+            args_hash: row.get(2)?,
+            tx: row.get(3)?,
+            duration_ms: row.get(4)?,
+            error: row.get(5)?,
+            recorded_at: row.get(6)?,
+        }))?
+        .collect::<std::result::Result<Vec<_>, _>>()?;
+    Ok(entries)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::eavto::test_helpers::setup_test_db;
+
+    #[test]
+    fn test_command_of_closure() {
+        #[allow(non_snake_case)]
+        fn bulk__update() -> String {
+            let operation = |_: &mut Connection| ();
+            fn name<F>(_: &F) -> String { command_of::<F>() }
+            name(&operation)
+        }
+        assert_eq!(bulk__update(), "bulk__update");
+    }
+
+    #[test]
+    fn test_invocations_are_taken_in_order() {
+        invoked("journal_test__write", &serde_json::json!({ "value": 1 }));
+        invoked("journal_test__write", &serde_json::json!({ "value": 2 }));
+
+        let first = take_args_hash("journal_test__write").unwrap();
+        let second = take_args_hash("journal_test__write").unwrap();
+        assert_ne!(first, second);
+        assert!(take_args_hash("journal_test__write").is_none());
+    }
+
+    #[test]
+    fn test_dry_runs_and_stale_invocations_are_not_taken() {
+        invoked("journal_test__preview", &serde_json::json!({ "value": 1, "dryRun": true }));
+        assert!(take_args_hash("journal_test__preview").is_none());
+
+        // An invocation that returned without writing, then one that writes
+        let stale = Instant::now() - PENDING_TTL;
+        PENDING.lock().unwrap().entry("journal_test__stale".to_string()).or_default()
+            .push_back((stale, "stale".to_string()));
+        let args = serde_json::json!({ "value": 2 });
+        invoked("journal_test__stale", &args);
+        assert_eq!(take_args_hash("journal_test__stale"), Some(args_hash(&args)));
+    }
+
+    #[test]
+    fn test_record_keeps_the_latest_writes() {
+        let conn = setup_test_db();
+        for i in 0..JOURNAL_SIZE + 5 {
+            record(&conn, "entity__create", Some("abc"), Some(i), Duration::from_millis(3), None).unwrap();
+        }
+        record(&conn, "entity__retract", None, None, Duration::ZERO, Some("Entity not found")).unwrap();
+
+        let entries = recent(&conn, 2).unwrap();
+        assert_eq!(entries[0].command, "entity__retract");
+        assert_eq!(entries[0].error.as_deref(), Some("Entity not found"));
+        assert_eq!(entries[1].tx, Some(JOURNAL_SIZE + 4));
+        assert_eq!(entries[1].duration_ms, 3);
+
+        let count: i64 = conn.query_row("SELECT COUNT(*) FROM write_journal", [], |row| row.get(0)).unwrap();
+        assert_eq!(count, JOURNAL_SIZE);
+    }
+}
@@ -0,0 +1,439 @@
+//! Import from Another FOUNDATION Database
+//!
+//! Consolidates experiments or old profiles: the other database file is
+//! attached (SQLite ATTACH) and its triples copied into this one, either
+//! whole origins or selected entities.
+//!
+//! Source origins map to origins of the same name (optionally prefixed), and
+//! source transactions to new transactions here, one per source transaction,
+//! keeping their timestamps as claimed by the other device and recording when
+//! they were received (see skew). Triples already current here are skipped, so
+//! importing the same file twice copies no current triples the second time.
+
+use rusqlite::{Connection, OptionalExtension};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::path::Path;
+use super::{blob, query, quarantine, sandbox::{self, Preview}, store};
+
+type Result<T> = std::result::Result<T, Box<dyn std::error::Error>>;
+
+/// Schema name of the attached database
+const SOURCE: &str = "source";
+
+/// What to copy from the other database
+#[derive(Debug, Clone, Default)]
+pub struct MergeOptions {
+    /// Copy these origins whole (default: all except the core ontology)
+    pub origins: Option<Vec<String>>,
+    /// Copy only these entities, with the blank nodes they use
+    pub entities: Option<Vec<String>>,
+    /// Prefix for the names of copied origins (e.g., "old-profile:")
+    pub origin_prefix: Option<String>,
+    /// Also copy retracted triples, preserving their timeline
+    pub include_history: bool,
+    /// Put the copied triples in this named graph (default: keep their graphs)
+    pub graph: Option<String>,
+}
+
+/// What was copied
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct MergeReport {
+    pub origins: Vec<String>, // Origins written here
+    pub transactions: i64,
+    pub copied: i64,
+    pub skipped: i64, // Already current here
+}
+
+/// A triple row of the source database
+struct SourceRow {
+    subject: String,
+    predicate: String,
+    object: Option<String>,
+    object_value: Option<String>,
+    object_datatype: Option<String>,
+    object_language: Option<String>,
+    object_type: String,
+    object_number: Option<f64>,
+    object_integer: Option<i64>,
+    object_datetime: Option<i64>,
+    object_boolean: Option<i64>,
+    graph: String,
+    tx: i64,
+    origin: String,
+    retracted: i64,
+    created_at: i64,
+}
+
+/// Copy triples from another FOUNDATION database file
+pub fn import_db(conn: &mut Connection, path: &Path, options: &MergeOptions) -> Result<MergeReport> {
+    if !path.exists() {
+        return Err(format!("Database {} not found", path.display()).into());
+    }
+
+    conn.execute(&format!("ATTACH DATABASE ?1 AS {}", SOURCE), [path.to_string_lossy()])?;
+    let result = copy(conn, options);
+    // Detaching must happen outside the copy's transaction
+    conn.execute_batch(&format!("DETACH DATABASE {}", SOURCE))?;
+    if let Ok(report) = &result {
+        super::indexes::analyze_after_import(conn, report.copied as u64)?;
+    }
+    result
+}
+
+/// Preview copying from another FOUNDATION database: the copy is made and
+/// rolled back (ATTACH can't run inside the dry run's savepoint, so the
+/// database is attached around it)
+pub fn preview_db(conn: &mut Connection, path: &Path, options: &MergeOptions) -> Result<(MergeReport, Preview)> {
+    if !path.exists() {
+        return Err(format!("Database {} not found", path.display()).into());
+    }
+
+    conn.execute(&format!("ATTACH DATABASE ?1 AS {}", SOURCE), [path.to_string_lossy()])?;
+    let result = sandbox::dry_run(conn, |conn| copy(conn, options));
+    conn.execute_batch(&format!("DETACH DATABASE {}", SOURCE))?;
+    result
+}
+
+fn copy(conn: &mut Connection, options: &MergeOptions) -> Result<MergeReport> {
+    check_schema(conn)?;
+
+    let mut rows = select_rows(conn, options)?;
+    let statuses = source_statuses(conn)?;
+    let target_origin = |origin: &str| format!("{}{}", options.origin_prefix.as_deref().unwrap_or(""), origin);
+
+    let source_blobs: bool = conn.query_row(
+        &format!("SELECT EXISTS(SELECT 1 FROM {}.sqlite_master WHERE name = 'blobs')", SOURCE),
+        [],
+        |row| row.get(0),
+    )?;
+
+    let tx = conn.savepoint()?;
+    let mut report = MergeReport::default();
+    let mut tx_map: HashMap<i64, i64> = HashMap::new();
+    let mut origin_map: BTreeMap<String, i64> = BTreeMap::new();
+    let received_at = store::now_millis();
+
+    for row in &mut rows {
+        if let Some(graph) = &options.graph {
+            row.graph = graph.clone();
+        }
+        if row.retracted == 0 && is_current(&tx, row)? {
+            report.skipped += 1;
+            continue;
+        }
+
+        let origin = target_origin(&row.origin);
+        if !origin_map.contains_key(&origin) {
+            // Unreviewed imports stay in quarantine, unless merged into an existing origin
+            let quarantined = statuses.get(&row.origin).map(String::as_str) == Some("quarantined");
+            if quarantined && query::get_origin_id(&tx, &origin)?.is_none() {
+                quarantine::quarantine(&tx, &origin)?;
+            }
+            origin_map.insert(origin.clone(), store::get_or_create_origin(&tx, &origin)?);
+        }
+
+        let target_tx = match tx_map.get(&row.tx) {
+            Some(target_tx) => *target_tx,
+            None => {
+                let created_at: i64 = tx.query_row(
+                    &format!("SELECT created_at FROM {}.transactions WHERE tx = ?", SOURCE),
+                    [row.tx],
+                    |r| r.get(0),
+                ).optional()?.unwrap_or(row.created_at);
+                tx.execute(
+                    "INSERT INTO main.transactions (origin, created_at, received_at) VALUES (?, ?, ?)",
+                    (&origin, created_at, received_at),
+                )?;
+                let target_tx = tx.last_insert_rowid();
+                tx_map.insert(row.tx, target_tx);
+                report.transactions += 1;
+                target_tx
+            }
+        };
+
+        tx.execute(
+            "INSERT INTO main.triples
+                (subject, predicate, object, object_value, object_datatype, object_language, object_type,
+                 object_number, object_integer, object_datetime, object_boolean,
+                 graph, tx, origin_id, retracted, created_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16)",
+            rusqlite::params![
+                row.subject, row.predicate, row.object, row.object_value, row.object_datatype,
+                row.object_language, row.object_type, row.object_number, row.object_integer,
+                row.object_datetime, row.object_boolean, row.graph, target_tx, origin_map[&origin],
+                row.retracted, row.created_at,
+            ],
+        )?;
+        report.copied += 1;
+
+        // Large literals come with their blob
+        if let Some(hash) = row.object.as_deref().and_then(|o| o.strip_prefix(blob::BLOB_PREFIX)) {
+            if source_blobs {
+                tx.execute(
+                    &format!("INSERT OR IGNORE INTO main.blobs SELECT * FROM {}.blobs WHERE hash = ?", SOURCE),
+                    [hash],
+                )?;
+            }
+        }
+    }
+
+    tx.commit()?;
+    report.origins = origin_map.into_keys().collect();
+    Ok(report)
+}
+
+/// The attached file must be a FOUNDATION database no newer than this one
+fn check_schema(conn: &Connection) -> Result<()> {
+    let tables: i64 = conn.query_row(
+        &format!(
+            "SELECT COUNT(*) FROM {}.sqlite_master
+             WHERE type IN ('table', 'view') AND name IN ('triples', 'transactions', 'origins', 'metadata')",
+            SOURCE
+        ),
+        [],
+        |row| row.get(0),
+    )?;
+    if tables < 4 {
+        return Err("Not a FOUNDATION database".into());
+    }
+
+    let version = |schema: &str| -> Result<i64> {
+        let value: Option<String> = conn.query_row(
+            &format!("SELECT value FROM {}.metadata WHERE key = 'schema_version'", schema),
+            [],
+            |row| row.get(0),
+        ).optional()?;
+        Ok(value.and_then(|v| v.parse().ok()).unwrap_or(0))
+    };
+    let (source, ours) = (version(SOURCE)?, version("main")?);
+    if source > ours {
+        return Err(format!("Database schema version {} is newer than this app's ({})", source, ours).into());
+    }
+    Ok(())
+}
+
+/// Selected source rows, in transaction order
+fn select_rows(conn: &Connection, options: &MergeOptions) -> Result<Vec<SourceRow>> {
+    // Databases before schema version 12 have everything in the default graph
+    let has_graph: bool = conn.query_row(
+        &format!("SELECT EXISTS(SELECT 1 FROM pragma_table_info('triples', '{}') WHERE name = 'graph')", SOURCE),
+        [],
+        |row| row.get(0),
+    )?;
+    let graph = if has_graph { "t.graph".to_string() } else { format!("'{}'", super::DEFAULT_GRAPH) };
+
+    let mut stmt = conn.prepare(&format!(
+        "SELECT t.subject, t.predicate, t.object, t.object_value, t.object_datatype, t.object_language,
+                t.object_type, t.object_number, t.object_integer, t.object_datetime, t.object_boolean,
+                t.tx, o.name, t.retracted, t.created_at, {1}
+         FROM {0}.triples t JOIN {0}.origins o ON o.id = t.origin_id
+         WHERE ?1 OR t.retracted = 0
+         ORDER BY t.tx, t.rowid",
+        SOURCE, graph
+    ))?;
+    let rows = stmt
+        .query_map([options.include_history], |row| Ok(SourceRow {
+            subject: row.get(0)?,
+            predicate: row.get(1)?,
+            object: row.get(2)?,
+            object_value: row.get(3)?,
+            object_datatype: row.get(4)?,
+            object_language: row.get(5)?,
+            object_type: row.get(6)?,
+            object_number: row.get(7)?,
+            object_integer: row.get(8)?,
+            object_datetime: row.get(9)?,
+            object_boolean: row.get(10)?,
+            tx: row.get(11)?,
+            origin: row.get(12)?,
+            retracted: row.get(13)?,
+            created_at: row.get(14)?,
+            graph: row.get(15)?,
+        }))?
+        .collect::<std::result::Result<Vec<_>, _>>()?;
+
+    let rows: Vec<SourceRow> = match &options.origins {
+        Some(origins) => rows.into_iter().filter(|r| origins.contains(&r.origin)).collect(),
+        None => rows.into_iter().filter(|r| !query::is_core_origin(&r.origin)).collect(),
+    };
+
+    match &options.entities {
+        Some(entities) => {
+            let subjects = with_blank_nodes(&rows, entities);
+            Ok(rows.into_iter().filter(|r| subjects.contains(&r.subject)).collect())
+        }
+        None => Ok(rows),
+    }
+}
+
+/// Entities plus the blank nodes they reach (restrictions, lists, ...)
+fn with_blank_nodes(rows: &[SourceRow], entities: &[String]) -> HashSet<String> {
+    let mut subjects: HashSet<String> = entities.iter().cloned().collect();
+    let mut pending: Vec<String> = entities.to_vec();
+
+    while let Some(subject) = pending.pop() {
+        for row in rows.iter().filter(|r| r.subject == subject && r.object_type == "blank") {
+            if let Some(blank) = &row.object {
+                if subjects.insert(blank.clone()) {
+                    pending.push(blank.clone());
+                }
+            }
+        }
+    }
+    subjects
+}
+
+/// Whether the triple is already current here, in the same graph
+fn is_current(conn: &Connection, row: &SourceRow) -> Result<bool> {
+    let exists = conn.query_row(
+        "SELECT EXISTS(
+            SELECT 1 FROM main.triples
+            WHERE subject = ?1 AND predicate = ?2 AND object_type = ?3
+              AND object IS ?4 AND object_value IS ?5
+              AND object_datatype IS ?6 AND object_language IS ?7
+              AND graph = ?8
+              AND retracted = 0)",
+        rusqlite::params![
+            row.subject, row.predicate, row.object_type, row.object, row.object_value,
+            row.object_datatype, row.object_language, row.graph,
+        ],
+        |r| r.get(0),
+    )?;
+    Ok(exists)
+}
+
+/// Review status of source origins (older databases have none)
+fn source_statuses(conn: &Connection) -> Result<HashMap<String, String>> {
+    let has_status: bool = conn.query_row(
+        &format!("SELECT EXISTS(SELECT 1 FROM pragma_table_info('origins', '{}') WHERE name = 'status')", SOURCE),
+        [],
+        |row| row.get(0),
+    )?;
+    if !has_status {
+        return Ok(HashMap::new());
+    }
+
+    let mut stmt = conn.prepare(&format!("SELECT name, status FROM {}.origins", SOURCE))?;
+    let statuses = stmt
+        .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?
+        .collect::<std::result::Result<HashMap<_, _>, _>>()?;
+    Ok(statuses)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::eavto::{test_helpers::{setup_test_db, TRIPLES_SCHEMA}, Object, Triple};
+    use tempfile::TempDir;
+
+    fn label(subject: &str, value: &str) -> Triple {
+        Triple::new(subject, "rdfs:label", Object::Literal {
+            value: value.to_string(),
+            datatype: Some("xsd:string".to_string()),
+            language: None,
+        })
+    }
+
+    /// A source database file with two origins
+    fn source_db(dir: &TempDir) -> std::path::PathBuf {
+        let path = dir.path().join("old.db");
+        let mut source = Connection::open(&path).unwrap();
+        source.execute_batch(
+            "CREATE TABLE origins (id INTEGER PRIMARY KEY AUTOINCREMENT, name TEXT NOT NULL UNIQUE, description TEXT);
+             CREATE TABLE transactions (tx INTEGER PRIMARY KEY AUTOINCREMENT, origin TEXT NOT NULL, created_at INTEGER NOT NULL);
+             CREATE TABLE metadata (key TEXT PRIMARY KEY, value TEXT NOT NULL, updated_at INTEGER NOT NULL);
+             INSERT INTO metadata VALUES ('schema_version', '2', 0);"
+        ).unwrap();
+        source.execute_batch(TRIPLES_SCHEMA).unwrap();
+
+        store::assert_triples(&mut source, &[
+            label("foundation:Trip", "Trip to Lisbon"),
+            Triple::new("foundation:Trip", "foundation:restriction", Object::Blank("_:b1".to_string())),
+            Triple::new("_:b1", "rdf:type", Object::Iri("owl:Restriction".to_string())),
+            label("foundation:Draft", "Draft"),
+        ], "user-edit").unwrap();
+        store::retract_triples(&mut source, &[label("foundation:Draft", "Draft")], "user-edit").unwrap();
+        store::assert_triples(&mut source, &[label("foundation:Experiment", "Experiment")], "lab").unwrap();
+        path
+    }
+
+    fn count(conn: &Connection, subject: &str) -> usize {
+        query::get_by_entity(conn, subject).unwrap().triples.len()
+    }
+
+    #[test]
+    fn test_import_whole_origins() {
+        let dir = TempDir::new().unwrap();
+        let path = source_db(&dir);
+        let mut conn = setup_test_db();
+
+        let options = MergeOptions { origins: Some(vec!["user-edit".to_string()]), ..Default::default() };
+        let (report, preview) = preview_db(&mut conn, &path, &options).unwrap();
+        assert_eq!((report.copied, preview.added.len()), (3, 3));
+        assert_eq!(count(&conn, "foundation:Trip"), 0);
+
+        let report = import_db(&mut conn, &path, &options).unwrap();
+        assert_eq!(report.origins, vec!["user-edit"]);
+        assert_eq!(report.copied, 3);
+        assert_eq!(report.transactions, 1);
+        assert_eq!(count(&conn, "foundation:Trip"), 2);
+        assert_eq!(count(&conn, "foundation:Draft"), 0);
+        assert_eq!(count(&conn, "foundation:Experiment"), 0);
+
+        // Importing again copies nothing
+        let (report, preview) = preview_db(&mut conn, &path, &options).unwrap();
+        assert_eq!((report.copied, preview.added.len()), (0, 0));
+        let report = import_db(&mut conn, &path, &options).unwrap();
+        assert_eq!((report.copied, report.skipped), (0, 3));
+    }
+
+    #[test]
+    fn test_import_selected_entities() {
+        let dir = TempDir::new().unwrap();
+        let path = source_db(&dir);
+        let mut conn = setup_test_db();
+
+        let options = MergeOptions {
+            entities: Some(vec!["foundation:Trip".to_string()]),
+            origin_prefix: Some("old:".to_string()),
+            include_history: true,
+            ..Default::default()
+        };
+        let report = import_db(&mut conn, &path, &options).unwrap();
+        assert_eq!(report.origins, vec!["old:user-edit"]);
+        assert_eq!(count(&conn, "foundation:Trip"), 2);
+        assert_eq!(count(&conn, "_:b1"), 1);
+        assert_eq!(count(&conn, "foundation:Experiment"), 0);
+
+        // The attached database was detached
+        let attached: i64 = conn.query_row("SELECT COUNT(*) FROM pragma_database_list WHERE name = 'source'", [], |r| r.get(0)).unwrap();
+        assert_eq!(attached, 0);
+    }
+
+    #[test]
+    fn test_import_into_graph() {
+        let dir = TempDir::new().unwrap();
+        let path = source_db(&dir);
+        let mut conn = setup_test_db();
+
+        let options = MergeOptions { graph: Some("foundation:PeerGraph".to_string()), ..Default::default() };
+        let report = import_db(&mut conn, &path, &options).unwrap();
+        assert_eq!(report.copied, 4);
+        assert_eq!(query::get_by_graph(&conn, "foundation:PeerGraph").unwrap().triples.len(), 4);
+
+        // The same triples in the default graph are new there
+        let report = import_db(&mut conn, &path, &MergeOptions::default()).unwrap();
+        assert_eq!((report.copied, report.skipped), (4, 0));
+    }
+
+    #[test]
+    fn test_rejects_other_files() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("other.db");
+        Connection::open(&path).unwrap().execute_batch("CREATE TABLE notes (text TEXT)").unwrap();
+
+        let mut conn = setup_test_db();
+        assert!(import_db(&mut conn, &path, &MergeOptions::default()).is_err());
+        assert!(import_db(&mut conn, &dir.path().join("missing.db"), &MergeOptions::default()).is_err());
+    }
+}
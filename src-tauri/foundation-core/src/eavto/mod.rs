@@ -19,6 +19,10 @@
 /// - **V (Value)**: The object (what we're saying about it)
 /// - **T (Time)**: Transaction-based timeline (when it was said)
 /// - **O (Origin)**: Who/what asserted it (provenance)
+///
+/// Each triple also belongs to a named graph (DEFAULT_GRAPH unless given),
+/// so imported ontologies, user data and peer data can be told apart.
+/// Queries see all graphs unless they ask for one.
 
 // Type modules (one file per type)
 mod triple_type;
@@ -32,40 +36,74 @@ mod xsd_type;
 pub mod query;
 pub mod store;
 pub mod connection;
+pub mod encryption;
 pub mod stats;
 pub mod executor;
+pub mod canonical;
+pub mod sandbox;
+pub mod quarantine;
+pub mod origin;
+pub mod journal;
+pub mod merge;
+pub mod pattern;
+pub mod series;
+pub mod skew;
+pub mod blob;
+pub mod backfill;
+pub mod terms;
+pub mod statement;
+pub mod indexes;
 
-// Test helpers (public for use in other module tests)
-#[cfg(test)]
+// Test helpers (public for use in other module tests, and with the
+// test-helpers feature in tests of crates using this one)
+#[cfg(any(test, feature = "test-helpers"))]
 pub mod test_helpers;
 
 // Re-export commonly used types
-pub use triple_type::Triple;
+pub use triple_type::{Triple, QuotedTriple, DEFAULT_GRAPH, ONTOLOGY_GRAPH};
 pub use object_type::Object;
 pub use query_result_type::QueryResult;
-pub use transaction_type::Transaction;
+pub use transaction_type::{Transaction, Annotation};
 pub use origin_type::Origin;
-pub use xsd_type::XsdType;
+pub use xsd_type::{XsdType, TypedValue};
 
 // Re-export main functions
 pub use query::{
     get_by_entity,
     get_by_predicate,
     get_by_entity_predicate,
+    get_by_predicate_range,
+    ValueRange,
     get_at_time,
+    get_as_of,
     get_by_origin,
+    get_by_graph,
+    get_graphs,
     get_history,
+    get_retractions,
+    get_transaction,
 };
 
 pub use store::{
     assert_triples,
+    assert_buffered,
+    flush_buffered,
     retract_triples,
+    retract_by_pattern,
+    retract_origin,
+    apply_changes,
+    apply_annotated_changes,
+    undo_transaction,
+    Assertion,
+    InvalidLiteral,
 };
 
 // Re-export connection and stats functions
 pub use connection::{
     get_connection,
     get_db_path,
+    get_demo_db_path,
+    set_data_dir,
     initialize_db,
     initialize_with_progress,
     DbError,
@@ -76,4 +114,5 @@ pub use stats::{
     DbStats,
 };
 
-pub use executor::DbExecutor;
+pub use executor::{DbExecutor, Committed};
+pub use sandbox::{Sandbox, SandboxRegistry};
@@ -2,6 +2,7 @@
 ///
 /// Represents the object part of an RDF triple
 
+use super::triple_type::QuotedTriple;
 use super::xsd_type::XsdType;
 
 /// RDF Object variants
@@ -25,13 +26,17 @@ pub enum Object {
     Number(f64),
     Boolean(bool),
     DateTime(i64), // Unix epoch milliseconds
+
+    /// Quoted triple (RDF-star), e.g. the fact a note is about
+    Triple(Box<QuotedTriple>),
 }
 
 impl Object {
     /// Get the object type for SQL storage
     pub fn object_type(&self) -> &'static str {
         match self {
-            Object::Iri(_) => "iri",
+            // Quoted triples are terms too, stored like IRIs (see statement)
+            Object::Iri(_) | Object::Triple(_) => "iri",
             Object::Blank(_) => "blank",
             Object::Literal { .. } |
             Object::Integer(_) |
@@ -116,6 +121,7 @@ impl PartialEq for Object {
             (Object::Number(a), Object::Number(b)) => (a - b).abs() < f64::EPSILON,
             (Object::Boolean(a), Object::Boolean(b)) => a == b,
             (Object::DateTime(a), Object::DateTime(b)) => a == b,
+            (Object::Triple(a), Object::Triple(b)) => a == b,
             _ => false,
         }
     }
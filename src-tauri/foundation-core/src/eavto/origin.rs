@@ -0,0 +1,141 @@
+//! Origin Management
+//!
+//! Origins are created on first write (`store::get_or_create_origin`); these
+//! functions list and describe them for provenance views. Triples link to
+//! their origin by ID, so renaming an origin relabels all of its triples.
+//! Transactions keep the origin name they were written under: it is part of
+//! their content hash and signature.
+
+use rusqlite::{Connection, OptionalExtension};
+use super::origin_type::Origin;
+use super::query;
+
+type Result<T> = std::result::Result<T, Box<dyn std::error::Error>>;
+
+/// What an origin holds, and when it wrote
+#[derive(Debug, Clone, PartialEq)]
+pub struct OriginStats {
+    pub origin: Origin,
+    pub status: String,
+    pub triple_count: i64,           // Current (not retracted) triples
+    pub first_tx: Option<i64>,       // None if the origin never wrote
+    pub last_tx: Option<i64>,
+    pub first_at: Option<i64>,       // Unix ms of first_tx
+    pub last_at: Option<i64>,        // Unix ms of last_tx
+}
+
+/// All origins, by name
+pub fn list_origins(conn: &Connection) -> Result<Vec<Origin>> {
+    let mut stmt = conn.prepare("SELECT id, name, description FROM origins ORDER BY name")?;
+    let origins = stmt
+        .query_map([], |row| Ok(Origin::new(row.get(0)?, row.get::<_, String>(1)?).with_description(row.get(2)?)))?
+        .collect::<std::result::Result<Vec<_>, _>>()?;
+    Ok(origins)
+}
+
+/// Statistics of an origin (None if it doesn't exist)
+pub fn get_origin_stats(conn: &Connection, name: &str) -> Result<Option<OriginStats>> {
+    let origin: Option<(Origin, String)> = conn.query_row(
+        "SELECT id, name, description, status FROM origins WHERE name = ?",
+        [name],
+        |row| Ok((Origin::new(row.get(0)?, row.get::<_, String>(1)?).with_description(row.get(2)?), row.get(3)?)),
+    ).optional()?;
+    let Some((origin, status)) = origin else { return Ok(None) };
+
+    let (first_tx, last_tx, first_at, last_at) = conn.query_row(
+        "SELECT MIN(tx), MAX(tx), MIN(created_at), MAX(created_at) FROM triples WHERE origin_id = ?",
+        [origin.id],
+        |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?)),
+    )?;
+    let triple_count = query::count_by_origin(conn, origin.id)?;
+
+    Ok(Some(OriginStats { origin, status, triple_count, first_tx, last_tx, first_at, last_at }))
+}
+
+/// Statistics of every origin, by name
+pub fn list_origin_stats(conn: &Connection) -> Result<Vec<OriginStats>> {
+    list_origins(conn)?
+        .iter()
+        .filter_map(|origin| get_origin_stats(conn, &origin.name).transpose())
+        .collect()
+}
+
+/// Rename an origin
+///
+/// The bundled ontology origins can't be renamed or taken as a new name:
+/// upgrades and exports find them by name.
+pub fn rename(conn: &Connection, name: &str, new_name: &str) -> Result<()> {
+    if query::is_core_origin(name) || query::is_core_origin(new_name) {
+        return Err(format!("Origin {} is reserved for the bundled ontologies", if query::is_core_origin(name) { name } else { new_name }).into());
+    }
+    if new_name.trim().is_empty() {
+        return Err("Origin name can't be empty".into());
+    }
+    if query::get_origin_id(conn, new_name)?.is_some() {
+        return Err(format!("Origin {} already exists", new_name).into());
+    }
+
+    let renamed = conn.execute("UPDATE origins SET name = ?1 WHERE name = ?2", [new_name, name])?;
+    if renamed == 0 {
+        return Err(format!("Origin {} not found", name).into());
+    }
+    Ok(())
+}
+
+/// Set (or clear, with None) the description of an origin
+pub fn describe(conn: &Connection, name: &str, description: Option<&str>) -> Result<()> {
+    let updated = conn.execute(
+        "UPDATE origins SET description = ?1 WHERE name = ?2",
+        rusqlite::params![description, name],
+    )?;
+    if updated == 0 {
+        return Err(format!("Origin {} not found", name).into());
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::eavto::{store, Object, Triple};
+    use crate::eavto::test_helpers::setup_test_db;
+
+    #[test]
+    fn test_stats_track_first_and_last_transaction() {
+        let mut conn = setup_test_db();
+        let first = store::assert_triples(&mut conn, &[Triple::new("ex:a", "ex:p", Object::Integer(1))], "import:csv").unwrap();
+        store::assert_triples(&mut conn, &[Triple::new("ex:b", "ex:p", Object::Integer(2))], "user-edit").unwrap();
+        let last = store::assert_triples(&mut conn, &[Triple::new("ex:c", "ex:p", Object::Integer(3))], "import:csv").unwrap();
+
+        let stats = get_origin_stats(&conn, "import:csv").unwrap().unwrap();
+        assert_eq!(stats.triple_count, 2);
+        assert_eq!((stats.first_tx, stats.last_tx), (Some(first), Some(last)));
+        assert_eq!(stats.status, "active");
+
+        let never = get_origin_stats(&conn, "test").unwrap().unwrap();
+        assert_eq!((never.triple_count, never.first_tx), (0, None));
+        assert!(get_origin_stats(&conn, "missing").unwrap().is_none());
+
+        let names: Vec<String> = list_origin_stats(&conn).unwrap().into_iter().map(|s| s.origin.name).collect();
+        assert_eq!(names, vec!["import:csv", "test", "user-edit"]);
+    }
+
+    #[test]
+    fn test_rename_and_describe() {
+        let mut conn = setup_test_db();
+        store::assert_triples(&mut conn, &[Triple::new("ex:a", "ex:p", Object::Integer(1))], "import:csv").unwrap();
+
+        describe(&conn, "import:csv", Some("Contacts export")).unwrap();
+        rename(&conn, "import:csv", "import:contacts").unwrap();
+
+        let stats = get_origin_stats(&conn, "import:contacts").unwrap().unwrap();
+        assert_eq!(stats.origin.description.as_deref(), Some("Contacts export"));
+        assert_eq!(stats.triple_count, 1);
+        assert!(get_origin_stats(&conn, "import:csv").unwrap().is_none());
+
+        assert!(rename(&conn, "import:contacts", "test").is_err());
+        assert!(rename(&conn, "import:contacts", "core").is_err());
+        assert!(rename(&conn, "missing", "other").is_err());
+        assert!(describe(&conn, "missing", None).is_err());
+    }
+}
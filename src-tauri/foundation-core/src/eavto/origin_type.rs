@@ -3,11 +3,11 @@
 /// Represents the origin/provenance of triples (O dimension in EVTO)
 
 /// Origin metadata
-#[derive(Debug, Clone)]
-#[allow(dead_code)] // Reserved for provenance tracking features
+#[derive(Debug, Clone, PartialEq)]
 pub struct Origin {
     pub id: i64,
     pub name: String,
+    pub description: Option<String>,
 }
 
 impl Origin {
@@ -16,8 +16,15 @@ impl Origin {
         Self {
             id,
             name: name.into(),
+            description: None,
         }
     }
+
+    /// Set the description
+    pub fn with_description(mut self, description: Option<String>) -> Self {
+        self.description = description;
+        self
+    }
 }
 
 #[cfg(test)]
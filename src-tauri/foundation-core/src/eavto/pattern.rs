@@ -0,0 +1,685 @@
+//! EAVTO Pattern Queries
+//!
+//! Basic graph pattern matching over the triple store, for the query console
+//! and other ad-hoc queries. A query is a set of triple patterns whose
+//! variables are joined, written in a SPARQL subset:
+//!
+//! ```text
+//! SELECT ?person ?name WHERE {
+//!     ?person a foundation:Person ;
+//!             rdfs:label ?name .
+//! } LIMIT 10
+//! ```
+//!
+//! Supported: PREFIX, SELECT (variables or *), DISTINCT, WHERE, LIMIT,
+//! prefixed names and <full IRIs>, `a`, the `;` and `,` shorthands, string
+//! literals with @lang or ^^datatype, numbers and booleans. A bare list of
+//! patterns is a query too (SELECT * is implied).
+//!
+//! Patterns are evaluated one at a time (nested-loop join), most selective
+//! first; the chosen order is returned as the query plan.
+
+use rusqlite::Connection;
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::iter::Peekable;
+use std::str::Chars;
+use super::object_type::Object;
+use super::query;
+use crate::namespaces::{compress_iri, prefixes};
+
+type Result<T> = std::result::Result<T, Box<dyn std::error::Error>>;
+
+/// Intermediate solutions beyond this abort the query (it needs more constants)
+const MAX_SOLUTIONS: usize = 100_000;
+
+/// A position in a triple pattern
+#[derive(Debug, Clone, PartialEq)]
+pub enum Term {
+    Var(String),
+    Value(Object),
+}
+
+/// A triple pattern
+#[derive(Debug, Clone, PartialEq)]
+pub struct Pattern {
+    pub subject: Term,
+    pub predicate: Term,
+    pub object: Term,
+}
+
+/// A parsed query
+#[derive(Debug, Clone, PartialEq)]
+pub struct PatternQuery {
+    pub select: Option<Vec<String>>, // None: all variables (SELECT *)
+    pub distinct: bool,
+    pub patterns: Vec<Pattern>,
+    pub limit: Option<usize>,
+}
+
+/// One step of the query plan, in evaluation order
+#[derive(Debug, Clone, PartialEq)]
+pub struct PlanStep {
+    pub pattern: String,
+    pub estimate: i64, // Triples matching the pattern's constants
+    pub joined: Vec<String>, // Variables already bound by earlier steps
+}
+
+/// Query results: one row of values per solution, in variable order
+#[derive(Debug, Clone)]
+pub struct PatternResult {
+    pub variables: Vec<String>,
+    pub rows: Vec<Vec<Object>>,
+    pub plan: Vec<PlanStep>,
+    pub truncated: bool, // More rows matched than the limit
+}
+
+impl Pattern {
+    fn terms(&self) -> [&Term; 3] {
+        [&self.subject, &self.predicate, &self.object]
+    }
+
+    fn variables(&self) -> impl Iterator<Item = &str> {
+        self.terms().into_iter().filter_map(|term| match term {
+            Term::Var(name) => Some(name.as_str()),
+            Term::Value(_) => None,
+        })
+    }
+}
+
+impl fmt::Display for Term {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Term::Var(name) => write!(f, "?{}", name),
+            Term::Value(Object::Iri(iri)) | Term::Value(Object::Blank(iri)) => write!(f, "{}", iri),
+            Term::Value(Object::Literal { value, language: Some(language), .. }) => write!(f, "{:?}@{}", value, language),
+            Term::Value(Object::Literal { value, datatype: Some(datatype), .. }) => write!(f, "{:?}^^{}", value, datatype),
+            Term::Value(Object::Literal { value, .. }) => write!(f, "{:?}", value),
+            Term::Value(Object::Integer(i)) => write!(f, "{}", i),
+            Term::Value(Object::Number(n)) => write!(f, "{}", n),
+            Term::Value(Object::Boolean(b)) => write!(f, "{}", b),
+            Term::Value(Object::DateTime(ms)) => write!(f, "{}", ms),
+            Term::Value(Object::Triple(quoted)) => write!(f, "{}", quoted.term()),
+        }
+    }
+}
+
+impl fmt::Display for Pattern {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {} {}", self.subject, self.predicate, self.object)
+    }
+}
+
+/// Parse and execute a query
+///
+/// `limit` caps the rows returned, on top of the query's own LIMIT.
+pub fn execute(conn: &Connection, text: &str, limit: Option<usize>) -> Result<PatternResult> {
+    let query = parse(text)?;
+    evaluate(conn, &query, limit)
+}
+
+// ============================================================================
+// Parsing
+// ============================================================================
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Var(String),
+    Iri(String), // <full IRI>
+    Name(String), // Keyword, prefixed name, number, boolean or `a`
+    Str { value: String, language: Option<String>, datatype: Option<Box<Token>> },
+    Punct(char),
+}
+
+/// Parse a query (see module docs for the supported syntax)
+pub fn parse(text: &str) -> Result<PatternQuery> {
+    let tokens = tokenize(text)?;
+    let mut parser = Parser { tokens, pos: 0, prefixes: HashMap::new() };
+    parser.query()
+}
+
+fn tokenize(text: &str) -> Result<Vec<Token>> {
+    let mut chars = text.chars().peekable();
+    let mut tokens = Vec::new();
+
+    while let Some(&c) = chars.peek() {
+        match c {
+            c if c.is_whitespace() => { chars.next(); }
+            '#' => {
+                while chars.next_if(|&c| c != '\n').is_some() {}
+            }
+            '{' | '}' | '.' | ';' | ',' | '*' => {
+                chars.next();
+                tokens.push(Token::Punct(c));
+            }
+            '?' | '$' => {
+                chars.next();
+                let name = take_while(&mut chars, |c| c.is_alphanumeric() || c == '_');
+                if name.is_empty() {
+                    return Err("Expected a variable name after '?'".into());
+                }
+                tokens.push(Token::Var(name));
+            }
+            '<' => tokens.push(Token::Iri(read_iri(&mut chars)?)),
+            '"' | '\'' => {
+                let value = read_string(&mut chars)?;
+                let mut language = None;
+                let mut datatype = None;
+                let mut trailing = Vec::new();
+                if chars.next_if_eq(&'@').is_some() {
+                    language = Some(take_while(&mut chars, |c| c.is_alphanumeric() || c == '-'));
+                } else if chars.next_if_eq(&'^').is_some() {
+                    if chars.next_if_eq(&'^').is_none() {
+                        return Err("Expected '^^' before a datatype".into());
+                    }
+                    datatype = Some(Box::new(match chars.peek() {
+                        Some('<') => Token::Iri(read_iri(&mut chars)?),
+                        _ => Token::Name(read_name(&mut chars, &mut trailing)),
+                    }));
+                }
+                tokens.push(Token::Str { value, language, datatype });
+                tokens.extend(trailing);
+            }
+            _ => {
+                let mut trailing = Vec::new();
+                let name = read_name(&mut chars, &mut trailing);
+                if name.is_empty() {
+                    return Err(format!("Unexpected character '{}'", c).into());
+                }
+                tokens.push(Token::Name(name));
+                tokens.extend(trailing);
+            }
+        }
+    }
+    Ok(tokens)
+}
+
+fn take_while(chars: &mut Peekable<Chars>, accept: impl Fn(char) -> bool) -> String {
+    let mut taken = String::new();
+    while let Some(c) = chars.next_if(|&c| accept(c)) {
+        taken.push(c);
+    }
+    taken
+}
+
+/// A bare word; a trailing '.' ends the pattern rather than the word
+fn read_name(chars: &mut Peekable<Chars>, tokens: &mut Vec<Token>) -> String {
+    let mut name = take_while(chars, |c| c.is_alphanumeric() || matches!(c, ':' | '_' | '-' | '.' | '+'));
+    while name.ends_with('.') {
+        name.pop();
+        tokens.push(Token::Punct('.'));
+    }
+    name
+}
+
+fn read_iri(chars: &mut Peekable<Chars>) -> Result<String> {
+    chars.next(); // '<'
+    let iri = take_while(chars, |c| c != '>');
+    if chars.next().is_none() {
+        return Err("Unterminated IRI (missing '>')".into());
+    }
+    Ok(iri)
+}
+
+fn read_string(chars: &mut Peekable<Chars>) -> Result<String> {
+    let quote = chars.next();
+    let mut value = String::new();
+    loop {
+        match chars.next() {
+            None => return Err("Unterminated string literal".into()),
+            Some(c) if Some(c) == quote => return Ok(value),
+            Some('\\') => match chars.next() {
+                Some('n') => value.push('\n'),
+                Some('t') => value.push('\t'),
+                Some('r') => value.push('\r'),
+                Some(c) => value.push(c),
+                None => return Err("Unterminated string literal".into()),
+            },
+            Some(c) => value.push(c),
+        }
+    }
+}
+
+struct Parser {
+    tokens: Vec<Token>,
+    pos: usize,
+    prefixes: HashMap<String, String>, // Declared with PREFIX
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos)
+    }
+
+    fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.pos).cloned();
+        self.pos += 1;
+        token
+    }
+
+    fn at_keyword(&self, keyword: &str) -> bool {
+        matches!(self.peek(), Some(Token::Name(name)) if name.eq_ignore_ascii_case(keyword))
+    }
+
+    fn at_punct(&self, c: char) -> bool {
+        self.peek() == Some(&Token::Punct(c))
+    }
+
+    fn expect_punct(&mut self, c: char) -> Result<()> {
+        if !self.at_punct(c) {
+            return Err(format!("Expected '{}'", c).into());
+        }
+        self.pos += 1;
+        Ok(())
+    }
+
+    fn query(&mut self) -> Result<PatternQuery> {
+        while self.at_keyword("PREFIX") {
+            self.pos += 1;
+            let prefix = match self.next() {
+                Some(Token::Name(name)) if name.ends_with(':') => name.trim_end_matches(':').to_string(),
+                _ => return Err("Expected a prefix name (such as ex:) after PREFIX".into()),
+            };
+            let namespace = match self.next() {
+                Some(Token::Iri(iri)) => iri,
+                _ => return Err(format!("Expected <namespace> for prefix {}:", prefix).into()),
+            };
+            self.prefixes.insert(prefix, namespace);
+        }
+
+        let mut select = None;
+        let mut distinct = false;
+        if self.at_keyword("SELECT") {
+            self.pos += 1;
+            if self.at_keyword("DISTINCT") {
+                self.pos += 1;
+                distinct = true;
+            }
+            if self.at_punct('*') {
+                self.pos += 1;
+            } else {
+                let mut variables = Vec::new();
+                while let Some(Token::Var(name)) = self.peek() {
+                    variables.push(name.clone());
+                    self.pos += 1;
+                }
+                if variables.is_empty() {
+                    return Err("Expected variables or * after SELECT".into());
+                }
+                select = Some(variables);
+            }
+            if self.at_keyword("WHERE") {
+                self.pos += 1;
+            }
+            if !self.at_punct('{') {
+                return Err("Expected '{' before the patterns".into());
+            }
+        }
+
+        let braced = self.at_punct('{');
+        if braced {
+            self.pos += 1;
+        }
+        let patterns = self.patterns()?;
+        if braced {
+            self.expect_punct('}')?;
+        }
+
+        let mut limit = None;
+        if self.at_keyword("LIMIT") {
+            self.pos += 1;
+            limit = match self.next() {
+                Some(Token::Name(n)) => Some(n.parse::<usize>().map_err(|_| format!("Invalid LIMIT: {}", n))?),
+                _ => return Err("Expected a number after LIMIT".into()),
+            };
+        }
+
+        if let Some(token) = self.peek() {
+            return Err(format!("Unexpected {:?} after the query", token).into());
+        }
+        if patterns.is_empty() {
+            return Err("The query has no patterns".into());
+        }
+        Ok(PatternQuery { select, distinct, patterns, limit })
+    }
+
+    fn patterns(&mut self) -> Result<Vec<Pattern>> {
+        let mut patterns = Vec::new();
+
+        while self.peek().is_some() && !self.at_punct('}') && !self.at_keyword("LIMIT") {
+            let subject = self.term()?;
+            if matches!(subject, Term::Value(ref object) if object.is_literal()) {
+                return Err(format!("A literal can't be a subject: {}", subject).into());
+            }
+
+            loop {
+                let predicate = self.term()?;
+                if !matches!(predicate, Term::Var(_) | Term::Value(Object::Iri(_))) {
+                    return Err(format!("A predicate must be an IRI or a variable: {}", predicate).into());
+                }
+
+                loop {
+                    let object = self.term()?;
+                    patterns.push(Pattern { subject: subject.clone(), predicate: predicate.clone(), object });
+                    if !self.at_punct(',') {
+                        break;
+                    }
+                    self.pos += 1;
+                }
+
+                if !self.at_punct(';') {
+                    break;
+                }
+                self.pos += 1;
+                if self.at_punct('.') || self.at_punct('}') || self.peek().is_none() {
+                    break; // Trailing ';'
+                }
+            }
+
+            if self.at_punct('.') {
+                self.pos += 1;
+            } else if self.peek().is_some() && !self.at_punct('}') && !self.at_keyword("LIMIT") {
+                return Err("Expected '.' between patterns".into());
+            }
+        }
+        Ok(patterns)
+    }
+
+    fn term(&mut self) -> Result<Term> {
+        match self.next() {
+            Some(Token::Var(name)) => Ok(Term::Var(name)),
+            Some(Token::Iri(iri)) => Ok(Term::Value(Object::Iri(compress_iri(&iri)))),
+            Some(Token::Str { value, language, datatype }) => {
+                let datatype = match datatype {
+                    Some(token) => match *token {
+                        Token::Iri(iri) => Some(compress_iri(&iri)),
+                        Token::Name(name) => Some(self.resolve(&name)?),
+                        _ => None,
+                    },
+                    None => None,
+                };
+                Ok(Term::Value(Object::Literal { value, datatype, language }))
+            }
+            Some(Token::Name(name)) => self.name(&name),
+            Some(token) => Err(format!("Unexpected {:?} in a pattern", token).into()),
+            None => Err("Unexpected end of query in a pattern".into()),
+        }
+    }
+
+    fn name(&self, name: &str) -> Result<Term> {
+        let value = match name {
+            "a" => Object::Iri("rdf:type".to_string()),
+            "true" => Object::Boolean(true),
+            "false" => Object::Boolean(false),
+            _ if name.starts_with("_:") => Object::Blank(name.to_string()),
+            _ if name.contains(':') => Object::Iri(self.resolve(name)?),
+            _ => {
+                if let Ok(i) = name.parse::<i64>() {
+                    Object::Integer(i)
+                } else if let Ok(n) = name.parse::<f64>() {
+                    Object::Number(n)
+                } else {
+                    return Err(format!("Unknown term: {}", name).into());
+                }
+            }
+        };
+        Ok(Term::Value(value))
+    }
+
+    /// Resolve a prefixed name to the stored (compressed) form
+    fn resolve(&self, name: &str) -> Result<String> {
+        let (prefix, local) = name.split_once(':').ok_or_else(|| format!("Not a prefixed name: {}", name))?;
+        if let Some(namespace) = self.prefixes.get(prefix) {
+            return Ok(compress_iri(&format!("{}{}", namespace, local)));
+        }
+        if prefixes().iter().any(|(known, _)| *known == prefix) {
+            return Ok(name.to_string());
+        }
+        Err(format!("Unknown prefix: {}:", prefix).into())
+    }
+}
+
+// ============================================================================
+// Evaluation
+// ============================================================================
+
+type Solution = HashMap<String, Object>;
+
+/// Evaluate a parsed query
+pub fn evaluate(conn: &Connection, query: &PatternQuery, limit: Option<usize>) -> Result<PatternResult> {
+    let limit = match (query.limit, limit) {
+        (Some(a), Some(b)) => Some(a.min(b)),
+        (a, b) => a.or(b),
+    };
+
+    let mut variables: Vec<String> = Vec::new();
+    for name in query.patterns.iter().flat_map(Pattern::variables) {
+        if !variables.iter().any(|v| v == name) {
+            variables.push(name.to_string());
+        }
+    }
+    if let Some(select) = &query.select {
+        if let Some(unknown) = select.iter().find(|v| !variables.contains(v)) {
+            return Err(format!("?{} is selected but not used in any pattern", unknown).into());
+        }
+        variables = select.clone();
+    }
+
+    let (order, plan) = plan(conn, &query.patterns)?;
+
+    let mut solutions: Vec<Solution> = vec![Solution::new()];
+    for (step, &index) in order.iter().enumerate() {
+        let pattern = &query.patterns[index];
+        let last = step + 1 == order.len();
+        let mut next = Vec::new();
+
+        'solutions: for solution in &solutions {
+            for triple in matches(conn, pattern, solution)? {
+                if let Some(extended) = extend(solution, pattern, triple) {
+                    next.push(extended);
+                    if next.len() > MAX_SOLUTIONS {
+                        return Err(format!(
+                            "The query matches more than {} intermediate results; add constants to narrow it",
+                            MAX_SOLUTIONS
+                        ).into());
+                    }
+                    // One extra row tells whether the results were truncated
+                    if last && !query.distinct && limit.is_some_and(|l| next.len() > l) {
+                        break 'solutions;
+                    }
+                }
+            }
+        }
+        solutions = next;
+    }
+
+    let mut rows: Vec<Vec<Object>> = solutions.into_iter()
+        .map(|mut solution| variables.iter().map(|v| solution.remove(v).unwrap()).collect())
+        .collect();
+
+    if query.distinct {
+        let mut seen = HashSet::new();
+        rows.retain(|row| seen.insert(format!("{:?}", row)));
+    }
+
+    let truncated = limit.is_some_and(|l| rows.len() > l);
+    if let Some(limit) = limit {
+        rows.truncate(limit);
+    }
+
+    Ok(PatternResult { variables, rows, plan, truncated })
+}
+
+/// Order patterns greedily: fewest unbound positions first (so each step
+/// joins with what's already bound), then fewest matching triples
+fn plan(conn: &Connection, patterns: &[Pattern]) -> Result<(Vec<usize>, Vec<PlanStep>)> {
+    let estimates = patterns.iter()
+        .map(|pattern| {
+            let (s, p, o) = constants(pattern, &Solution::new());
+            query::count_by_pattern(conn, s.as_deref(), p.as_deref(), o.as_ref())
+        })
+        .collect::<Result<Vec<i64>>>()?;
+
+    let mut remaining: Vec<usize> = (0..patterns.len()).collect();
+    let mut bound: HashSet<&str> = HashSet::new();
+    let mut order = Vec::new();
+    let mut steps = Vec::new();
+
+    while !remaining.is_empty() {
+        let unbound = |i: usize| patterns[i].variables().filter(|v| !bound.contains(v)).count();
+        let (position, &index) = remaining.iter()
+            .enumerate()
+            .min_by_key(|(_, &i)| (unbound(i), estimates[i]))
+            .unwrap();
+        remaining.remove(position);
+
+        let pattern = &patterns[index];
+        let mut joined: Vec<String> = pattern.variables()
+            .filter(|v| bound.contains(v))
+            .map(String::from)
+            .collect();
+        joined.dedup();
+        steps.push(PlanStep { pattern: pattern.to_string(), estimate: estimates[index], joined });
+
+        bound.extend(pattern.variables());
+        order.push(index);
+    }
+    Ok((order, steps))
+}
+
+/// The pattern's positions with constants and bound variables filled in
+fn constants(pattern: &Pattern, solution: &Solution) -> (Option<String>, Option<String>, Option<Object>) {
+    let value = |term: &Term| match term {
+        Term::Var(name) => solution.get(name).cloned(),
+        Term::Value(object) => Some(object.clone()),
+    };
+    let iri = |term: &Term| value(term).map(|object| object.as_iri().unwrap_or_default().to_string());
+    (iri(&pattern.subject), iri(&pattern.predicate), value(&pattern.object))
+}
+
+fn matches(conn: &Connection, pattern: &Pattern, solution: &Solution) -> Result<Vec<super::Triple>> {
+    let (s, p, o) = constants(pattern, solution);
+    let mut triples = query::get_by_pattern(conn, s.as_deref(), p.as_deref(), o.as_ref())?.triples;
+
+    // A fact asserted by several origins is one match
+    let mut seen = HashSet::new();
+    triples.retain(|t| seen.insert(format!("{:?}", (&t.subject, &t.predicate, &t.object))));
+    Ok(triples)
+}
+
+/// The solution extended with the triple's values, if they agree with it
+fn extend(solution: &Solution, pattern: &Pattern, triple: super::Triple) -> Option<Solution> {
+    let subject = if triple.subject.starts_with("_:") {
+        Object::Blank(triple.subject)
+    } else {
+        Object::Iri(triple.subject)
+    };
+    let values = [subject, Object::Iri(triple.predicate), triple.object];
+
+    let mut extended = solution.clone();
+    for (term, value) in pattern.terms().into_iter().zip(values) {
+        if let Term::Var(name) = term {
+            match extended.get(name) {
+                Some(existing) if *existing != value => return None,
+                Some(_) => {}
+                None => { extended.insert(name.clone(), value); }
+            }
+        }
+    }
+    Some(extended)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::eavto::{store, test_helpers::setup_test_db, Triple};
+
+    fn iri(value: &str) -> Object {
+        Object::Iri(value.to_string())
+    }
+
+    fn text(value: &str) -> Object {
+        Object::Literal { value: value.to_string(), datatype: Some("xsd:string".to_string()), language: None }
+    }
+
+    fn people(conn: &mut Connection) {
+        store::assert_triples(conn, &[
+            Triple::new("foundation:Ana", "rdf:type", iri("foundation:Person")),
+            Triple::new("foundation:Ana", "rdfs:label", text("Ana")),
+            Triple::new("foundation:Ana", "foundation:age", Object::Integer(34)),
+            Triple::new("foundation:Ana", "foundation:knows", iri("foundation:Bruno")),
+            Triple::new("foundation:Bruno", "rdf:type", iri("foundation:Person")),
+            Triple::new("foundation:Bruno", "rdfs:label", text("Bruno")),
+            Triple::new("foundation:Bruno", "foundation:age", Object::Integer(41)),
+            Triple::new("foundation:Acme", "rdf:type", iri("foundation:Organization")),
+            Triple::new("foundation:Acme", "rdfs:label", text("Acme")),
+        ], "test").unwrap();
+    }
+
+    #[test]
+    fn test_parse() {
+        let query = parse(
+            "PREFIX ex: <http://foundation.local/ontology/>
+             SELECT DISTINCT ?p ?name WHERE {
+                 ?p a ex:Person ; rdfs:label ?name , \"Ana\"@pt . # comment
+                 ?p <http://foundation.local/ontology/age> 34.
+             } LIMIT 5"
+        ).unwrap();
+
+        assert_eq!(query.select, Some(vec!["p".to_string(), "name".to_string()]));
+        assert!(query.distinct);
+        assert_eq!(query.limit, Some(5));
+        assert_eq!(query.patterns.len(), 4);
+        assert_eq!(query.patterns[0].predicate, Term::Value(iri("rdf:type")));
+        assert_eq!(query.patterns[0].object, Term::Value(iri("foundation:Person")));
+        assert_eq!(query.patterns[2].object, Term::Value(Object::Literal {
+            value: "Ana".to_string(), datatype: None, language: Some("pt".to_string()),
+        }));
+        assert_eq!(query.patterns[3].predicate, Term::Value(iri("foundation:age")));
+        assert_eq!(query.patterns[3].object, Term::Value(Object::Integer(34)));
+
+        // Bare patterns
+        assert_eq!(parse("?s rdfs:label ?o").unwrap().patterns.len(), 1);
+
+        assert!(parse("SELECT ?s WHERE { ?s ex:p ?o }").is_err()); // Unknown prefix
+        assert!(parse("SELECT ?s WHERE { \"x\" rdfs:label ?o }").is_err());
+        assert!(parse("SELECT ?s WHERE { ?s rdfs:label }").is_err());
+    }
+
+    #[test]
+    fn test_join() {
+        let mut conn = setup_test_db();
+        people(&mut conn);
+
+        let result = execute(&conn, "SELECT ?name ?friend WHERE {
+            ?p a foundation:Person ; rdfs:label ?name ; foundation:knows ?f .
+            ?f rdfs:label ?friend .
+        }", None).unwrap();
+
+        assert_eq!(result.variables, vec!["name", "friend"]);
+        assert_eq!(result.rows, vec![vec![text("Ana"), text("Bruno")]]);
+        assert_eq!(result.plan.len(), 4);
+        assert_eq!(result.plan[0].pattern, "?p rdf:type foundation:Person"); // Most selective first
+        assert_eq!(result.plan[1].pattern, "?p foundation:knows ?f");
+        assert_eq!(result.plan[1].joined, vec!["p"]);
+    }
+
+    #[test]
+    fn test_typed_values_and_limit() {
+        let mut conn = setup_test_db();
+        people(&mut conn);
+
+        let result = execute(&conn, "?p foundation:age 41", None).unwrap();
+        assert_eq!(result.rows, vec![vec![iri("foundation:Bruno")]]);
+
+        let result = execute(&conn, "SELECT * WHERE { ?s rdfs:label ?label }", Some(2)).unwrap();
+        assert_eq!(result.rows.len(), 2);
+        assert!(result.truncated);
+
+        let result = execute(&conn, "SELECT DISTINCT ?class WHERE { ?s a ?class }", None).unwrap();
+        assert_eq!(result.rows.len(), 2);
+        assert!(!result.truncated);
+
+        assert!(execute(&conn, "SELECT ?missing WHERE { ?s a ?class }", None).is_err());
+    }
+}
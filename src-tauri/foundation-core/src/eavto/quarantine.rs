@@ -0,0 +1,226 @@
+//! Import Quarantine
+//!
+//! Review queue for triples from untrusted sources (web fetch, sync from a
+//! new peer). Such an import lands in an origin with status 'quarantined',
+//! which normal queries don't see (see `query::visible`). The user reviews a
+//! summary of the origin and either approves it, making its triples visible,
+//! or rejects it, retracting them.
+
+use rusqlite::{Connection, OptionalExtension};
+use super::triple_type::Triple;
+use super::{query, store};
+
+type Result<T> = std::result::Result<T, Box<dyn std::error::Error>>;
+
+/// Number of sample triples in a review summary
+const SAMPLE_SIZE: usize = 10;
+
+/// Review status of an origin
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OriginStatus {
+    Active,
+    Quarantined,
+    Rejected,
+}
+
+impl OriginStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            OriginStatus::Active => "active",
+            OriginStatus::Quarantined => "quarantined",
+            OriginStatus::Rejected => "rejected",
+        }
+    }
+
+    pub fn parse(status: &str) -> Option<Self> {
+        match status {
+            "active" => Some(OriginStatus::Active),
+            "quarantined" => Some(OriginStatus::Quarantined),
+            "rejected" => Some(OriginStatus::Rejected),
+            _ => None,
+        }
+    }
+}
+
+/// What a quarantined origin would add to the store
+#[derive(Debug, Clone)]
+pub struct QuarantineSummary {
+    pub origin: String,
+    pub triple_count: i64,
+    pub entity_count: i64,
+    pub classes: Vec<String>, // Classes declared, or instantiated by typed entities
+    pub samples: Vec<Triple>,
+    pub received_at: Option<i64>, // Time of the first quarantined transaction
+}
+
+/// Get the status of an origin (None if the origin doesn't exist)
+pub fn get_status(conn: &Connection, origin: &str) -> Result<Option<OriginStatus>> {
+    let status: Option<String> = conn.query_row(
+        "SELECT status FROM origins WHERE name = ?",
+        [origin],
+        |row| row.get(0),
+    ).optional()?;
+
+    Ok(status.as_deref().and_then(OriginStatus::parse))
+}
+
+/// Put an origin in quarantine, creating it if needed
+///
+/// Call before importing from an untrusted source, so its triples are
+/// hidden from the moment they are written.
+pub fn quarantine(conn: &Connection, origin: &str) -> Result<()> {
+    conn.execute(
+        "INSERT INTO origins (name, status) VALUES (?1, 'quarantined')
+         ON CONFLICT(name) DO UPDATE SET status = 'quarantined'",
+        [origin],
+    )?;
+    Ok(())
+}
+
+/// Quarantined origins awaiting review, oldest first
+pub fn review_queue(conn: &Connection) -> Result<Vec<QuarantineSummary>> {
+    let mut stmt = conn.prepare("SELECT name FROM origins WHERE status = 'quarantined'")?;
+    let origins = stmt
+        .query_map([], |row| row.get::<_, String>(0))?
+        .collect::<std::result::Result<Vec<_>, _>>()?;
+
+    let mut summaries = origins.iter()
+        .map(|origin| summarize(conn, origin))
+        .collect::<Result<Vec<_>>>()?;
+    summaries.sort_by_key(|s| s.received_at.unwrap_or(i64::MAX));
+    Ok(summaries)
+}
+
+/// Summary of what an origin holds, for review
+pub fn summarize(conn: &Connection, origin: &str) -> Result<QuarantineSummary> {
+    let origin_id = query::get_origin_id(conn, origin)?
+        .ok_or_else(|| format!("Origin {} not found", origin))?;
+    let triples = query::get_by_origin(conn, origin_id)?.triples;
+
+    let mut entities: Vec<&str> = triples.iter().map(|t| t.subject.as_str()).collect();
+    entities.sort();
+    entities.dedup();
+
+    let mut classes: Vec<String> = triples.iter()
+        .filter(|t| t.predicate == "rdf:type")
+        .filter_map(|t| match t.object.as_iri() {
+            Some("owl:Class") | Some("rdfs:Class") => Some(t.subject.clone()),
+            Some(class) if !class.starts_with("owl:") && !class.starts_with("rdf:") && !class.starts_with("rdfs:") => {
+                Some(class.to_string())
+            }
+            _ => None,
+        })
+        .collect();
+    classes.sort();
+    classes.dedup();
+
+    let received_at = triples.iter().map(|t| t.created_at).min();
+
+    Ok(QuarantineSummary {
+        origin: origin.to_string(),
+        triple_count: triples.len() as i64,
+        entity_count: entities.len() as i64,
+        classes,
+        samples: triples.into_iter().take(SAMPLE_SIZE).collect(),
+        received_at,
+    })
+}
+
+/// Approve a quarantined origin, making its triples visible
+pub fn approve(conn: &Connection, origin: &str) -> Result<()> {
+    set_reviewed(conn, origin, OriginStatus::Active)
+}
+
+/// Reject a quarantined origin, retracting its triples
+///
+/// Returns the retraction transaction (None if the origin held no triples).
+pub fn reject(conn: &mut Connection, origin: &str) -> Result<Option<i64>> {
+    set_reviewed(conn, origin, OriginStatus::Rejected)?;
+
+    let origin_id = query::get_origin_id(conn, origin)?
+        .ok_or_else(|| format!("Origin {} not found", origin))?;
+    let triples = query::get_by_origin(conn, origin_id)?.triples;
+    if triples.is_empty() {
+        return Ok(None);
+    }
+
+    Ok(Some(store::apply_changes(conn, &[], &triples, origin)?))
+}
+
+fn set_reviewed(conn: &Connection, origin: &str, status: OriginStatus) -> Result<()> {
+    match get_status(conn, origin)? {
+        Some(OriginStatus::Quarantined) => {
+            conn.execute("UPDATE origins SET status = ? WHERE name = ?", [status.as_str(), origin])?;
+            Ok(())
+        }
+        Some(other) => Err(format!("Origin {} is not quarantined ({})", origin, other.as_str()).into()),
+        None => Err(format!("Origin {} not found", origin).into()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::eavto::{test_helpers::setup_test_db, Object};
+
+    fn import_untrusted(conn: &mut Connection) {
+        quarantine(conn, "web:example.org").unwrap();
+        store::assert_triples(conn, &[
+            Triple::new("foundation:Gadget", "rdf:type", Object::Iri("owl:Class".to_string())),
+            Triple::new("foundation:Phone1", "rdf:type", Object::Iri("foundation:Phone".to_string())),
+            Triple::new("foundation:Phone1", "rdfs:label", Object::Literal {
+                value: "Phone".to_string(),
+                datatype: Some("xsd:string".to_string()),
+                language: None,
+            }),
+        ], "web:example.org").unwrap();
+    }
+
+    #[test]
+    fn test_quarantined_triples_are_hidden() {
+        let mut conn = setup_test_db();
+        import_untrusted(&mut conn);
+
+        assert_eq!(get_status(&conn, "web:example.org").unwrap(), Some(OriginStatus::Quarantined));
+        assert!(query::get_by_entity(&conn, "foundation:Phone1").unwrap().triples.is_empty());
+        assert!(query::get_all(&conn).unwrap().triples.is_empty());
+        assert!(query::get_active_origins(&conn).unwrap().is_empty());
+
+        approve(&conn, "web:example.org").unwrap();
+        assert_eq!(query::get_by_entity(&conn, "foundation:Phone1").unwrap().triples.len(), 2);
+    }
+
+    #[test]
+    fn test_review_summary() {
+        let mut conn = setup_test_db();
+        import_untrusted(&mut conn);
+
+        let queue = review_queue(&conn).unwrap();
+        assert_eq!(queue.len(), 1);
+        let summary = &queue[0];
+        assert_eq!(summary.origin, "web:example.org");
+        assert_eq!(summary.triple_count, 3);
+        assert_eq!(summary.entity_count, 2);
+        assert_eq!(summary.classes, vec!["foundation:Gadget", "foundation:Phone"]);
+        assert_eq!(summary.samples.len(), 3);
+        assert!(summary.received_at.is_some());
+    }
+
+    #[test]
+    fn test_reject_retracts_triples() {
+        let mut conn = setup_test_db();
+        import_untrusted(&mut conn);
+
+        assert!(reject(&mut conn, "web:example.org").unwrap().is_some());
+        assert_eq!(get_status(&conn, "web:example.org").unwrap(), Some(OriginStatus::Rejected));
+
+        let origin_id = query::get_origin_id(&conn, "web:example.org").unwrap().unwrap();
+        assert!(query::get_by_origin(&conn, origin_id).unwrap().triples.is_empty());
+        assert!(review_queue(&conn).unwrap().is_empty());
+
+        // Only quarantined origins can be reviewed
+        assert!(approve(&conn, "web:example.org").is_err());
+        assert!(approve(&conn, "test").is_err());
+        assert!(approve(&conn, "missing").is_err());
+    }
+}
@@ -0,0 +1,1254 @@
+/// EVTO Query Functions
+///
+/// Pure functions for querying the triple store
+
+use rusqlite::{Connection, Row};
+use super::triple_type::Triple;
+use super::object_type::Object;
+use super::query_result_type::QueryResult;
+use super::transaction_type::Transaction;
+use super::terms;
+use super::statement;
+
+type Result<T> = std::result::Result<T, Box<dyn std::error::Error>>;
+
+/// Metadata key holding the IRI of the active workspace (absent: all workspaces)
+pub const ACTIVE_WORKSPACE_KEY: &str = "active_workspace";
+
+/// Condition for triples visible to normal queries, for a table alias
+/// (use "triples" for unaliased queries over the triples table):
+/// - current (not retracted)
+/// - not from a quarantined origin awaiting review
+/// - about an entity in the active workspace, or in no workspace at all
+///
+/// Queries that address an origin or a transaction explicitly (get_by_origin,
+/// get_by_transaction) see everything current, so quarantined imports can be
+/// reviewed. Raw SQL over `triples` elsewhere should use this too.
+pub fn visible(alias: &str) -> String {
+    format!("{}.retracted = 0 AND {}", alias, shown(alias))
+}
+
+/// `visible` without the condition on retraction, for reads of past states
+/// (get_as_of): not quarantined, and in the active workspace or in none
+fn shown(alias: &str) -> String {
+    format!(
+        "{0}.origin_id NOT IN (SELECT id FROM origins WHERE status = 'quarantined')
+           AND (NOT EXISTS (SELECT 1 FROM metadata WHERE key = '{1}')
+                OR {0}.subject NOT IN (SELECT subject FROM triples WHERE predicate = '{2}' AND retracted = 0)
+                OR {0}.subject IN (SELECT subject FROM triples WHERE predicate = '{2}' AND retracted = 0
+                                   AND object = (SELECT value FROM metadata WHERE key = '{1}')))",
+        alias, ACTIVE_WORKSPACE_KEY, IN_WORKSPACE
+    )
+}
+
+/// Workspace membership of an entity (foundation:inWorkspace)
+pub const IN_WORKSPACE: &str = "foundation:inWorkspace";
+
+/// Columns of a quad `q` with its terms resolved, in row_to_triple's order
+const QUAD_COLUMNS: &str =
+    "s.value, p.value, o.value, q.object_value, q.object_datatype, q.object_language,
+     q.object_type, q.object_number, q.object_integer, q.object_datetime, q.object_boolean,
+     q.tx, q.origin_id, q.retracted, q.created_at, q.graph
+     FROM quads q
+     JOIN terms s ON s.id = q.subject_id
+     JOIN terms p ON p.id = q.predicate_id
+     LEFT JOIN terms o ON o.id = q.object_id";
+
+/// `visible` for a quad alias, comparing term IDs
+fn visible_quad(alias: &str) -> String {
+    format!(
+        "{0}.retracted = 0
+           AND {0}.origin_id NOT IN (SELECT id FROM origins WHERE status = 'quarantined')
+           AND (NOT EXISTS (SELECT 1 FROM metadata WHERE key = '{1}')
+                OR {0}.subject_id NOT IN (SELECT subject_id FROM quads WHERE retracted = 0
+                                          AND predicate_id = (SELECT id FROM terms WHERE value = '{2}'))
+                OR {0}.subject_id IN (SELECT subject_id FROM quads WHERE retracted = 0
+                                      AND predicate_id = (SELECT id FROM terms WHERE value = '{2}')
+                                      AND object_id = (SELECT id FROM terms WHERE value = (SELECT value FROM metadata WHERE key = '{1}'))))",
+        alias, ACTIVE_WORKSPACE_KEY, IN_WORKSPACE
+    )
+}
+
+/// Conditions of the EAV lookups below on term IDs. Class::get and the
+/// entity graph run them for every entity shown (see indexes::explain)
+pub(crate) const BY_ENTITY: &str = "q.subject_id = ?";
+pub(crate) const BY_PREDICATE: &str = "q.predicate_id = ?";
+pub(crate) const BY_ENTITY_PREDICATE: &str = "q.subject_id = ? AND q.predicate_id = ?";
+pub(crate) const BY_PREDICATE_OBJECT: &str = "q.predicate_id = ? AND q.object_id = ?";
+pub(crate) const BY_OBJECT: &str = "q.object_id = ? AND q.object_type = 'iri'";
+
+/// SQL selecting the visible quads matching a condition, latest first
+pub(crate) fn quads_sql(condition: &str) -> String {
+    format!(
+        "SELECT {} WHERE {} AND {} ORDER BY q.tx DESC",
+        QUAD_COLUMNS, condition, visible_quad("q")
+    )
+}
+
+/// Visible quads matching a condition on term IDs, latest first
+///
+/// The statement is cached: the EAV lookups below run for every entity
+/// shown, and their SQL is the same each time.
+fn select_quads(conn: &Connection, condition: &str, ids: &[i64]) -> Result<QueryResult> {
+    let mut stmt = conn.prepare_cached(&quads_sql(condition))?;
+
+    let triples = stmt
+        .query_map(rusqlite::params_from_iter(ids), row_to_triple)?
+        .collect::<std::result::Result<Vec<_>, _>>()?;
+
+    Ok(QueryResult::new(triples))
+}
+
+/// IDs of terms, or None if one was never stored (nothing can match it)
+fn term_ids<const N: usize>(conn: &Connection, values: [&str; N]) -> Result<Option<[i64; N]>> {
+    let mut ids = [0; N];
+    for (id, value) in ids.iter_mut().zip(values) {
+        match terms::lookup(conn, value)? {
+            Some(found) => *id = found,
+            None => return Ok(None),
+        }
+    }
+    Ok(Some(ids))
+}
+
+/// Query triples by entity (E - subject)
+pub fn get_by_entity(conn: &Connection, entity: &str) -> Result<QueryResult> {
+    match term_ids(conn, [entity])? {
+        Some(ids) => select_quads(conn, BY_ENTITY, &ids),
+        None => Ok(QueryResult::empty()),
+    }
+}
+
+/// Query triples by predicate (V - value/property)
+pub fn get_by_predicate(conn: &Connection, predicate: &str) -> Result<QueryResult> {
+    match term_ids(conn, [predicate])? {
+        Some(ids) => select_quads(conn, BY_PREDICATE, &ids),
+        None => Ok(QueryResult::empty()),
+    }
+}
+
+/// Query triples by subject and predicate (EV)
+pub fn get_by_entity_predicate(
+    conn: &Connection,
+    entity: &str,
+    predicate: &str,
+) -> Result<QueryResult> {
+    match term_ids(conn, [entity, predicate])? {
+        Some(ids) => select_quads(conn, BY_ENTITY_PREDICATE, &ids),
+        None => Ok(QueryResult::empty()),
+    }
+}
+
+/// Query by predicate and object (e.g., all properties with a specific domain)
+pub fn get_by_predicate_object(
+    conn: &Connection,
+    predicate: &str,
+    object: &str,
+) -> Result<QueryResult> {
+    match term_ids(conn, [predicate, object])? {
+        Some(ids) => select_quads(conn, BY_PREDICATE_OBJECT, &ids),
+        None => Ok(QueryResult::empty()),
+    }
+}
+
+/// Query by object (e.g., all triples that reference a specific entity)
+pub fn get_by_object(
+    conn: &Connection,
+    object: &str,
+) -> Result<QueryResult> {
+    match term_ids(conn, [object])? {
+        Some(ids) => select_quads(conn, BY_OBJECT, &ids),
+        None => Ok(QueryResult::empty()),
+    }
+}
+
+/// Bounds of a range query on a typed column, both inclusive (None: open)
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ValueRange {
+    Integer(Option<i64>, Option<i64>),
+    Number(Option<f64>, Option<f64>),  // Integers are numbers too
+    DateTime(Option<i64>, Option<i64>), // Epoch ms, of dateTime and date values
+}
+
+/// Query by predicate and a range of its typed values, smallest first
+/// (e.g., memories with capacity >= 16, events between two dates)
+pub fn get_by_predicate_range(conn: &Connection, predicate: &str, range: ValueRange) -> Result<QueryResult> {
+    use rusqlite::types::Value;
+
+    let Some([predicate_id]) = term_ids(conn, [predicate])? else {
+        return Ok(QueryResult::empty());
+    };
+    let mut params = vec![Value::Integer(predicate_id)];
+    let mut between = |column: &str, min: Option<Value>, max: Option<Value>| -> String {
+        let mut bounds = vec![format!("{} IS NOT NULL", column)];
+        for (bound, operator) in [(min, ">="), (max, "<=")] {
+            if let Some(bound) = bound {
+                bounds.push(format!("{} {} ?", column, operator));
+                params.push(bound);
+            }
+        }
+        bounds.join(" AND ")
+    };
+    let (condition, order) = match range {
+        ValueRange::Integer(min, max) => (
+            between("q.object_integer", min.map(Value::Integer), max.map(Value::Integer)),
+            "q.object_integer",
+        ),
+        ValueRange::Number(min, max) => (
+            format!(
+                "(({}) OR ({}))",
+                between("q.object_number", min.map(Value::Real), max.map(Value::Real)),
+                between("q.object_integer", min.map(Value::Real), max.map(Value::Real)),
+            ),
+            "COALESCE(q.object_number, q.object_integer)",
+        ),
+        ValueRange::DateTime(min, max) => (
+            format!(
+                "{} AND q.object_datatype != 'xsd:time'",
+                between("q.object_datetime", min.map(Value::Integer), max.map(Value::Integer)),
+            ),
+            "q.object_datetime",
+        ),
+    };
+
+    let mut stmt = conn.prepare_cached(&format!(
+        "SELECT {} WHERE q.predicate_id = ? AND {} AND {} ORDER BY {}, q.tx DESC",
+        QUAD_COLUMNS, condition, visible_quad("q"), order
+    ))?;
+    let triples = stmt
+        .query_map(rusqlite::params_from_iter(params), row_to_triple)?
+        .collect::<std::result::Result<Vec<_>, _>>()?;
+    Ok(QueryResult::new(triples))
+}
+
+/// Find entities by class and properties in a single query
+///
+/// This performs an efficient SQL JOIN to find entities that match a class and property constraints.
+/// Can be used with one or multiple properties.
+///
+/// Example:
+/// ```ignore
+/// // Single property
+/// let releases = find_by_class_and_properties(
+///     conn,
+///     "foundation:SoftwareRelease",
+///     &[("foundation:versionNumber", "0.1.0")]
+/// )?;
+///
+/// // Multiple properties
+/// let release = find_by_class_and_properties(
+///     conn,
+///     "foundation:SoftwareRelease",
+///     &[
+///         ("foundation:versionNumber", "0.1.0"),
+///         ("foundation:releaseOf", "foundation:FoundationProduct"),
+///     ]
+/// )?;
+/// ```
+pub fn find_by_class_and_properties(
+    conn: &Connection,
+    class_iri: &str,
+    properties: &[(&str, &str)],
+) -> Result<Vec<String>> {
+    if properties.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    // Build dynamic query with multiple JOINs
+    let mut query = String::from(
+        "SELECT DISTINCT t0.subject
+         FROM triples t0"
+    );
+
+    // Add JOIN for each property
+    for (i, _) in properties.iter().enumerate() {
+        let table_num = i + 1;
+        query.push_str(&format!(
+            "\n         INNER JOIN triples t{} ON t0.subject = t{}.subject",
+            table_num, table_num
+        ));
+    }
+
+    // Add WHERE clause for class
+    query.push_str(&format!(
+        "\n         WHERE t0.predicate = 'rdf:type'
+           AND t0.object = '{}'
+           AND {}",
+        class_iri, visible("t0")
+    ));
+
+    // Add WHERE clause for each property
+    for (i, (prop_iri, _)) in properties.iter().enumerate() {
+        let table_num = i + 1;
+        query.push_str(&format!(
+            "\n           AND t{}.predicate = '{}'
+           AND {}",
+            table_num, prop_iri, visible(&format!("t{}", table_num))
+        ));
+    }
+
+    // Add value matching (supports both literal and IRI)
+    for (i, (_, value)) in properties.iter().enumerate() {
+        let table_num = i + 1;
+        query.push_str(&format!(
+            "\n           AND (t{}.object_value = '{}' OR t{}.object = '{}')",
+            table_num, value, table_num, value
+        ));
+    }
+
+    let mut stmt = conn.prepare(&query)?;
+    let entities: Vec<String> = stmt
+        .query_map([], |row| row.get(0))?
+        .collect::<std::result::Result<Vec<_>, _>>()?;
+
+    Ok(entities)
+}
+
+/// Find entities by attribute value (works for any property)
+pub fn find_entities_by_attribute_value(
+    conn: &Connection,
+    attribute: &str,
+    value: &str,
+) -> Result<Vec<String>> {
+    let mut stmt = conn.prepare(&format!(
+        "SELECT DISTINCT subject
+         FROM triples
+         WHERE predicate = ? AND object_value = ? AND {}",
+        visible("triples")
+    ))?;
+
+    let entities: Vec<String> = stmt
+        .query_map([attribute, value], |row| row.get(0))?
+        .collect::<std::result::Result<Vec<_>, _>>()?;
+
+    Ok(entities)
+}
+
+/// Query entity state at specific time (ET - temporal query)
+pub fn get_at_time(conn: &Connection, entity: &str, tx: i64) -> Result<QueryResult> {
+    let mut stmt = conn.prepare(&format!(
+        "SELECT subject, predicate, object, object_value, object_datatype, object_language,
+                object_type, object_number, object_integer, object_datetime, object_boolean,
+                tx, origin_id, retracted, created_at, graph
+         FROM triples
+         WHERE subject = ? AND tx <= ? AND {}
+         ORDER BY predicate, tx DESC",
+        visible("triples")
+    ))?;
+
+    let triples = stmt
+        .query_map([entity, tx.to_string().as_str()], row_to_triple)?
+        .collect::<std::result::Result<Vec<_>, _>>()?;
+
+    // Keep only the latest value for each predicate (temporal snapshot)
+    let mut seen_predicates = std::collections::HashSet::new();
+    let snapshot: Vec<Triple> = triples
+        .into_iter()
+        .filter(|t| seen_predicates.insert(t.predicate.clone()))
+        .collect();
+
+    Ok(QueryResult::new(snapshot))
+}
+
+/// All triples current as of a transaction: asserted by then and not
+/// retracted until after it (the store's state right after the transaction),
+/// except those normal queries don't see (quarantined, other workspaces)
+pub fn get_as_of(conn: &Connection, tx: i64) -> Result<QueryResult> {
+    let mut stmt = conn.prepare(&format!(
+        "SELECT subject, predicate, object, object_value, object_datatype, object_language,
+                object_type, object_number, object_integer, object_datetime, object_boolean,
+                tx, origin_id, retracted, created_at, graph
+         FROM triples
+         WHERE tx <= ?1 AND (retracted = 0 OR retracted_tx > ?1) AND {}
+         ORDER BY tx, rowid",
+        shown("triples")
+    ))?;
+
+    let triples = stmt
+        .query_map([tx], row_to_triple)?
+        .collect::<std::result::Result<Vec<_>, _>>()?;
+
+    Ok(QueryResult::new(triples))
+}
+
+/// Query triples by origin (O)
+pub fn get_by_origin(conn: &Connection, origin_id: i64) -> Result<QueryResult> {
+    let mut stmt = conn.prepare(
+        "SELECT subject, predicate, object, object_value, object_datatype, object_language,
+                object_type, object_number, object_integer, object_datetime, object_boolean,
+                tx, origin_id, retracted, created_at, graph
+         FROM triples
+         WHERE origin_id = ? AND retracted = 0
+         ORDER BY tx DESC"
+    )?;
+
+    let triples = stmt
+        .query_map([origin_id], row_to_triple)?
+        .collect::<std::result::Result<Vec<_>, _>>()?;
+
+    Ok(QueryResult::new(triples))
+}
+
+/// Query the current triples of a named graph
+pub fn get_by_graph(conn: &Connection, graph: &str) -> Result<QueryResult> {
+    let mut stmt = conn.prepare(&format!(
+        "SELECT subject, predicate, object, object_value, object_datatype, object_language,
+                object_type, object_number, object_integer, object_datetime, object_boolean,
+                tx, origin_id, retracted, created_at, graph
+         FROM triples
+         WHERE graph = ? AND {}
+         ORDER BY tx DESC",
+        visible("triples")
+    ))?;
+
+    let triples = stmt
+        .query_map([graph], row_to_triple)?
+        .collect::<std::result::Result<Vec<_>, _>>()?;
+
+    Ok(QueryResult::new(triples))
+}
+
+/// Named graphs holding current triples, with their triple counts
+pub fn get_graphs(conn: &Connection) -> Result<Vec<(String, i64)>> {
+    let mut stmt = conn.prepare(&format!(
+        "SELECT graph, COUNT(*) FROM triples WHERE {} GROUP BY graph ORDER BY graph",
+        visible("triples")
+    ))?;
+    let graphs = stmt
+        .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?
+        .collect::<std::result::Result<Vec<_>, _>>()?;
+    Ok(graphs)
+}
+
+/// Query triples asserted in a transaction (T)
+pub fn get_by_transaction(conn: &Connection, tx: i64) -> Result<QueryResult> {
+    let mut stmt = conn.prepare(
+        "SELECT subject, predicate, object, object_value, object_datatype, object_language,
+                object_type, object_number, object_integer, object_datetime, object_boolean,
+                tx, origin_id, retracted, created_at, graph
+         FROM triples
+         WHERE tx = ? AND retracted = 0
+         ORDER BY subject, predicate"
+    )?;
+
+    let triples = stmt
+        .query_map([tx], row_to_triple)?
+        .collect::<std::result::Result<Vec<_>, _>>()?;
+
+    Ok(QueryResult::new(triples))
+}
+
+/// Query every triple asserted in a transaction, retracted since or not
+/// (what the transaction said, e.g. for its signature)
+pub fn get_asserted_by_transaction(conn: &Connection, tx: i64) -> Result<QueryResult> {
+    let mut stmt = conn.prepare(
+        "SELECT subject, predicate, object, object_value, object_datatype, object_language,
+                object_type, object_number, object_integer, object_datetime, object_boolean,
+                tx, origin_id, retracted, created_at, graph
+         FROM triples
+         WHERE tx = ?
+         ORDER BY rowid"
+    )?;
+
+    let triples = stmt
+        .query_map([tx], row_to_triple)?
+        .collect::<std::result::Result<Vec<_>, _>>()?;
+
+    Ok(QueryResult::new(triples))
+}
+
+/// Query all current triples (ordered by subject for grouped output)
+pub fn get_all(conn: &Connection) -> Result<QueryResult> {
+    let mut stmt = conn.prepare(&format!(
+        "SELECT subject, predicate, object, object_value, object_datatype, object_language,
+                object_type, object_number, object_integer, object_datetime, object_boolean,
+                tx, origin_id, retracted, created_at, graph
+         FROM triples
+         WHERE {}
+         ORDER BY subject, predicate, tx",
+        visible("triples")
+    ))?;
+
+    let triples = stmt
+        .query_map([], row_to_triple)?
+        .collect::<std::result::Result<Vec<_>, _>>()?;
+
+    Ok(QueryResult::new(triples))
+}
+
+/// Query triples matching a pattern (None matches any value in that position)
+pub fn get_by_pattern(
+    conn: &Connection,
+    subject: Option<&str>,
+    predicate: Option<&str>,
+    object: Option<&Object>,
+) -> Result<QueryResult> {
+    let (conditions, params) = pattern_conditions(subject, predicate, object);
+    let mut stmt = conn.prepare(&format!(
+        "SELECT subject, predicate, object, object_value, object_datatype, object_language,
+                object_type, object_number, object_integer, object_datetime, object_boolean,
+                tx, origin_id, retracted, created_at, graph
+         FROM triples
+         WHERE {} AND {}
+         ORDER BY subject, predicate, tx",
+        conditions,
+        visible("triples")
+    ))?;
+
+    let triples = stmt
+        .query_map(rusqlite::params_from_iter(params), row_to_triple)?
+        .collect::<std::result::Result<Vec<_>, _>>()?;
+
+    Ok(QueryResult::new(triples))
+}
+
+/// Count triples matching a pattern (None matches any value in that position)
+pub fn count_by_pattern(
+    conn: &Connection,
+    subject: Option<&str>,
+    predicate: Option<&str>,
+    object: Option<&Object>,
+) -> Result<i64> {
+    let (conditions, params) = pattern_conditions(subject, predicate, object);
+    let count = conn.query_row(
+        &format!("SELECT COUNT(*) FROM triples WHERE {} AND {}", conditions, visible("triples")),
+        rusqlite::params_from_iter(params),
+        |row| row.get(0),
+    )?;
+    Ok(count)
+}
+
+/// WHERE conditions and parameters for a triple pattern
+///
+/// Typed objects match on their typed column, so 5 matches "5"^^xsd:integer
+/// however it was written.
+fn pattern_conditions(
+    subject: Option<&str>,
+    predicate: Option<&str>,
+    object: Option<&Object>,
+) -> (String, Vec<rusqlite::types::Value>) {
+    use rusqlite::types::Value;
+
+    let mut conditions = vec!["1 = 1"];
+    let mut params = Vec::new();
+
+    if let Some(subject) = subject {
+        conditions.push("subject = ?");
+        params.push(Value::Text(subject.to_string()));
+    }
+    if let Some(predicate) = predicate {
+        conditions.push("predicate = ?");
+        params.push(Value::Text(predicate.to_string()));
+    }
+    match object {
+        Some(Object::Iri(iri)) | Some(Object::Blank(iri)) => {
+            conditions.push("object = ?");
+            params.push(Value::Text(iri.clone()));
+        }
+        Some(Object::Triple(quoted)) => {
+            conditions.push("object = ?");
+            params.push(Value::Text(quoted.term()));
+        }
+        Some(Object::Integer(i)) => {
+            conditions.push("object_integer = ?");
+            params.push(Value::Integer(*i));
+        }
+        Some(Object::Number(n)) => {
+            conditions.push("object_number = ?");
+            params.push(Value::Real(*n));
+        }
+        Some(Object::Boolean(b)) => {
+            conditions.push("object_boolean = ?");
+            params.push(Value::Integer(*b as i64));
+        }
+        Some(Object::DateTime(ms)) => {
+            conditions.push("object_datetime = ?");
+            params.push(Value::Integer(*ms));
+        }
+        Some(Object::Literal { value, language, .. }) => {
+            conditions.push("object_type = 'literal' AND object_value = ?");
+            params.push(Value::Text(value.clone()));
+            if let Some(language) = language {
+                conditions.push("object_language = ?");
+                params.push(Value::Text(language.clone()));
+            }
+        }
+        None => {}
+    }
+
+    (conditions.join(" AND "), params)
+}
+
+/// Whether an origin holds the bundled core ontology (core or rdf:core, and
+/// the foundation:ontology:* files) rather than user data
+pub fn is_core_origin(name: &str) -> bool {
+    name == "core" || name == "rdf:core" || name.starts_with("foundation:ontology:")
+}
+
+/// Graph that triples imported under an origin go to: the ontology graph for
+/// the core ontologies, the default graph otherwise
+pub fn graph_of_origin(name: &str) -> &'static str {
+    if is_core_origin(name) { super::ONTOLOGY_GRAPH } else { super::DEFAULT_GRAPH }
+}
+
+/// Look up an origin ID by name
+pub fn get_origin_id(conn: &Connection, name: &str) -> Result<Option<i64>> {
+    let mut stmt = conn.prepare("SELECT id FROM origins WHERE name = ?")?;
+    let mut rows = stmt.query([name])?;
+
+    match rows.next()? {
+        Some(row) => Ok(Some(row.get(0)?)),
+        None => Ok(None),
+    }
+}
+
+/// Look up an origin name by ID
+pub fn get_origin_name(conn: &Connection, id: i64) -> Result<Option<String>> {
+    let mut stmt = conn.prepare("SELECT name FROM origins WHERE id = ?")?;
+    let mut rows = stmt.query([id])?;
+
+    match rows.next()? {
+        Some(row) => Ok(Some(row.get(0)?)),
+        None => Ok(None),
+    }
+}
+
+/// Latest committed transaction ID (0 for an empty store)
+pub fn get_latest_tx(conn: &Connection) -> Result<i64> {
+    let tx = conn.query_row("SELECT COALESCE(MAX(tx), 0) FROM transactions", [], |row| row.get(0))?;
+    Ok(tx)
+}
+
+/// Transactions, newest first
+///
+/// before: only transactions older than this one (for paging)
+/// tag: only transactions annotated with this tag
+pub fn get_transactions(conn: &Connection, before: Option<i64>, tag: Option<&str>, limit: usize) -> Result<Vec<Transaction>> {
+    let mut stmt = conn.prepare(
+        "SELECT tx, origin, created_at, received_at, message, tags
+         FROM transactions
+         WHERE (?1 IS NULL OR tx < ?1)
+           AND (?2 IS NULL OR EXISTS (SELECT 1 FROM json_each(transactions.tags) WHERE value = ?2))
+         ORDER BY tx DESC
+         LIMIT ?3"
+    )?;
+
+    read_transactions(&mut stmt, rusqlite::params![before, tag, limit as i64])
+}
+
+/// Transactions after one, oldest first (for following new commits)
+pub fn get_transactions_after(conn: &Connection, after: i64, limit: usize) -> Result<Vec<Transaction>> {
+    let mut stmt = conn.prepare(
+        "SELECT tx, origin, created_at, received_at, message, tags
+         FROM transactions
+         WHERE tx > ?1
+         ORDER BY tx
+         LIMIT ?2"
+    )?;
+
+    read_transactions(&mut stmt, rusqlite::params![after, limit as i64])
+}
+
+/// Run a transactions query selecting tx, origin, created_at, received_at, message, tags
+fn read_transactions(stmt: &mut rusqlite::Statement, params: impl rusqlite::Params) -> Result<Vec<Transaction>> {
+    let rows = stmt.query_map(params, |row| {
+        Ok((
+            Transaction {
+                tx: row.get(0)?,
+                origin: row.get(1)?,
+                created_at: row.get(2)?,
+                received_at: row.get(3)?,
+                message: row.get(4)?,
+                tags: Vec::new(),
+            },
+            row.get::<_, Option<String>>(5)?,
+        ))
+    })?.collect::<std::result::Result<Vec<_>, _>>()?;
+
+    rows.into_iter()
+        .map(|(mut transaction, tags)| {
+            if let Some(tags) = tags {
+                transaction.tags = serde_json::from_str(&tags)?;
+            }
+            Ok(transaction)
+        })
+        .collect()
+}
+
+/// Count triples asserted in a transaction (retracted since or not)
+pub fn count_by_transaction(conn: &Connection, tx: i64) -> Result<i64> {
+    let count = conn.query_row("SELECT COUNT(*) FROM triples WHERE tx = ?", [tx], |row| row.get(0))?;
+    Ok(count)
+}
+
+/// Entities with current triples written after a transaction
+pub fn get_changed_subjects(conn: &Connection, since_tx: i64) -> Result<Vec<String>> {
+    let mut stmt = conn.prepare(&format!(
+        "SELECT DISTINCT subject FROM triples WHERE tx > ? AND {} ORDER BY subject",
+        visible("triples")
+    ))?;
+
+    let subjects = stmt
+        .query_map([since_tx], |row| row.get(0))?
+        .collect::<std::result::Result<Vec<String>, _>>()?;
+
+    Ok(subjects)
+}
+
+/// Names of origins that have current triples
+pub fn get_active_origins(conn: &Connection) -> Result<Vec<String>> {
+    let mut stmt = conn.prepare(&format!(
+        "SELECT name FROM origins
+         WHERE id IN (SELECT DISTINCT origin_id FROM triples WHERE {})
+         ORDER BY name",
+        visible("triples")
+    ))?;
+
+    let names = stmt
+        .query_map([], |row| row.get(0))?
+        .collect::<std::result::Result<Vec<String>, _>>()?;
+
+    Ok(names)
+}
+
+/// Count current triples of an origin
+pub fn count_by_origin(conn: &Connection, origin_id: i64) -> Result<i64> {
+    let count = conn.query_row(
+        "SELECT COUNT(*) FROM triples WHERE origin_id = ? AND retracted = 0",
+        [origin_id],
+        |row| row.get(0),
+    )?;
+    Ok(count)
+}
+
+/// Read a database metadata value
+pub fn get_metadata(conn: &Connection, key: &str) -> Result<Option<String>> {
+    let mut stmt = conn.prepare("SELECT value FROM metadata WHERE key = ?")?;
+    let mut rows = stmt.query([key])?;
+
+    match rows.next()? {
+        Some(row) => Ok(Some(row.get(0)?)),
+        None => Ok(None),
+    }
+}
+
+/// Query complete history of an entity (all transactions)
+pub fn get_history(conn: &Connection, entity: &str) -> Result<Vec<(i64, Vec<Triple>)>> {
+    let mut stmt = conn.prepare(
+        "SELECT subject, predicate, object, object_value, object_datatype, object_language,
+                object_type, object_number, object_integer, object_datetime, object_boolean,
+                tx, origin_id, retracted, created_at, graph
+         FROM triples
+         WHERE subject = ?
+         ORDER BY tx ASC"
+    )?;
+
+    let all_triples: Vec<Triple> = stmt
+        .query_map([entity], row_to_triple)?
+        .collect::<std::result::Result<Vec<_>, _>>()?;
+
+    // Group by transaction
+    let mut history: std::collections::HashMap<i64, Vec<Triple>> = std::collections::HashMap::new();
+    for triple in all_triples {
+        history.entry(triple.tx).or_insert_with(Vec::new).push(triple);
+    }
+
+    let mut result: Vec<(i64, Vec<Triple>)> = history.into_iter().collect();
+    result.sort_by_key(|(tx, _)| *tx);
+
+    Ok(result)
+}
+
+/// Retractions of an entity's triples, with the transaction that retracted
+/// each, oldest first (get_history lists them under the transaction that
+/// asserted them)
+pub fn get_retractions(conn: &Connection, entity: &str) -> Result<Vec<(i64, Triple)>> {
+    let mut stmt = conn.prepare(
+        "SELECT subject, predicate, object, object_value, object_datatype, object_language,
+                object_type, object_number, object_integer, object_datetime, object_boolean,
+                tx, origin_id, retracted, created_at, graph, retracted_tx
+         FROM triples
+         WHERE subject = ? AND retracted_tx IS NOT NULL
+         ORDER BY retracted_tx, rowid"
+    )?;
+
+    let retractions = stmt
+        .query_map([entity], |row| Ok((row.get(16)?, row_to_triple(row)?)))?
+        .collect::<std::result::Result<Vec<_>, _>>()?;
+    Ok(retractions)
+}
+
+/// A transaction by its ID
+pub fn get_transaction(conn: &Connection, tx: i64) -> Result<Option<Transaction>> {
+    let mut stmt = conn.prepare(
+        "SELECT tx, origin, created_at, received_at, message, tags
+         FROM transactions
+         WHERE tx = ?1"
+    )?;
+
+    Ok(read_transactions(&mut stmt, [tx])?.pop())
+}
+
+/// Changes between two transactions: the triples added and retracted going
+/// from the state after tx_a to the state after tx_b (when tx_b is the
+/// earlier one, the changes that undo them)
+///
+/// Triples both asserted and retracted between the two cancel out.
+/// Triples retracted before schema version 8, or copied already retracted
+/// from another database, have no retracting transaction and are left out.
+pub fn diff_transactions(conn: &Connection, tx_a: i64, tx_b: i64) -> Result<(Vec<Triple>, Vec<Triple>)> {
+    if tx_a > tx_b {
+        let (added, retracted) = diff_transactions(conn, tx_b, tx_a)?;
+        return Ok((retracted, added));
+    }
+
+    let select = |condition: &str| -> Result<Vec<Triple>> {
+        let mut stmt = conn.prepare(&format!(
+            "SELECT subject, predicate, object, object_value, object_datatype, object_language,
+                    object_type, object_number, object_integer, object_datetime, object_boolean,
+                    tx, origin_id, retracted, created_at, graph
+             FROM triples
+             WHERE {}
+             ORDER BY tx, rowid",
+            condition
+        ))?;
+        let triples = stmt
+            .query_map([tx_a, tx_b], row_to_triple)?
+            .collect::<std::result::Result<Vec<_>, _>>()?;
+        Ok(triples)
+    };
+
+    let added = select("tx > ?1 AND tx <= ?2 AND (retracted = 0 OR retracted_tx > ?2)")?;
+    let retracted = select("retracted_tx > ?1 AND retracted_tx <= ?2 AND tx <= ?1")?;
+    Ok((added, retracted))
+}
+
+/// Convert SQLite row to Triple
+fn row_to_triple(row: &Row) -> rusqlite::Result<Triple> {
+    let subject: String = row.get(0)?;
+    let predicate: String = row.get(1)?;
+    let object_opt: Option<String> = row.get(2)?;
+    let object_value: Option<String> = row.get(3)?;
+    let object_datatype: Option<String> = row.get(4)?;
+    let object_language: Option<String> = row.get(5)?;
+    let object_type: String = row.get(6)?;
+    let object_number: Option<f64> = row.get(7)?;
+    let object_integer: Option<i64> = row.get(8)?;
+    let object_datetime: Option<i64> = row.get(9)?;
+    let object_boolean: Option<i64> = row.get(10)?;
+    let tx: i64 = row.get(11)?;
+    let origin_id: i64 = row.get(12)?;
+    let retracted: i64 = row.get(13)?;
+    let created_at: i64 = row.get(14)?;
+    let graph: String = row.get(15)?;
+
+    let object = match object_type.as_str() {
+        "iri" => {
+            let object = object_opt.unwrap();
+            match statement::parse(&object) {
+                Some(quoted) => Object::Triple(Box::new(quoted)),
+                None => Object::Iri(object),
+            }
+        }
+        "blank" => Object::Blank(object_opt.unwrap()),
+        "literal" => {
+            // Check for typed literals
+            if let Some(int) = object_integer {
+                Object::Integer(int)
+            } else if let Some(num) = object_number {
+                Object::Number(num)
+            } else if let Some(dt) = object_datetime.filter(|_| object_datatype.as_deref() == Some("xsd:dateTime")) {
+                // xsd:date and xsd:time keep their lexical form
+                Object::DateTime(dt)
+            } else if let Some(bool_val) = object_boolean {
+                Object::Boolean(bool_val != 0)
+            } else {
+                // Generic literal
+                Object::Literal {
+                    value: object_value.unwrap(),
+                    datatype: object_datatype,
+                    language: object_language,
+                }
+            }
+        }
+        _ => unreachable!("Invalid object_type in database"),
+    };
+
+    Ok(Triple {
+        subject,
+        predicate,
+        object,
+        graph,
+        tx,
+        origin_id,
+        retracted: retracted != 0,
+        created_at,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::eavto::test_helpers::{setup_test_db, create_test_triples};
+    use crate::eavto::store::{assert_triples, apply_annotated_changes};
+    use crate::eavto::transaction_type::Annotation;
+    use crate::eavto::DEFAULT_GRAPH;
+    use crate::eavto::XsdType;
+
+    fn setup_test_data(conn: &mut Connection) -> i64 {
+        let triples = create_test_triples();
+        assert_triples(conn, &triples, "test").unwrap()
+    }
+
+    #[test]
+    fn test_get_by_entity() {
+        let mut conn = setup_test_db();
+        setup_test_data(&mut conn);
+
+        let result = get_by_entity(&conn, "foundation:TestClass").unwrap();
+        assert_eq!(result.triples.len(), 2); // rdf:type and rdfs:label
+    }
+
+    #[test]
+    fn test_get_by_entity_nonexistent() {
+        let mut conn = setup_test_db();
+        setup_test_data(&mut conn);
+
+        let result = get_by_entity(&conn, "foundation:NonExistent").unwrap();
+        assert_eq!(result.triples.len(), 0);
+    }
+
+    #[test]
+    fn test_get_by_predicate() {
+        let mut conn = setup_test_db();
+        setup_test_data(&mut conn);
+
+        let result = get_by_predicate(&conn, "rdf:type").unwrap();
+        assert_eq!(result.triples.len(), 1);
+    }
+
+    #[test]
+    fn test_get_by_entity_predicate() {
+        let mut conn = setup_test_db();
+        setup_test_data(&mut conn);
+
+        let result = get_by_entity_predicate(&conn, "foundation:TestClass", "rdfs:label").unwrap();
+        assert_eq!(result.triples.len(), 1);
+
+        let triple = &result.triples[0];
+        match &triple.object {
+            Object::Literal { value, .. } => assert_eq!(value, "Test Class"),
+            _ => panic!("Expected literal object"),
+        }
+    }
+
+    #[test]
+    fn test_get_at_time() {
+        let mut conn = setup_test_db();
+        let tx_id = setup_test_data(&mut conn);
+
+        let result = get_at_time(&conn, "foundation:TestClass", tx_id).unwrap();
+        assert_eq!(result.triples.len(), 2); // Should have both triples at this tx
+    }
+
+    #[test]
+    fn test_get_at_time_temporal_snapshot() {
+        let mut conn = setup_test_db();
+        let tx1 = setup_test_data(&mut conn);
+
+        // Add new triple with same predicate (update)
+        let updated_triple = vec![Triple {
+            subject: "foundation:TestClass".to_string(),
+            predicate: "rdfs:label".to_string(),
+            object: Object::Literal {
+                value: "Updated Label".to_string(),
+                datatype: Some("xsd:string".to_string()),
+                language: None,
+            },
+            graph: DEFAULT_GRAPH.to_string(),
+            tx: 0,
+            created_at: 2000,
+            origin_id: 1,
+            retracted: false,
+        }];
+        let tx2 = assert_triples(&mut conn, &updated_triple, "test").unwrap();
+
+        // Query at tx2 should give latest label
+        let result = get_at_time(&conn, "foundation:TestClass", tx2).unwrap();
+
+        // Should only have 2 triples (latest rdfs:label + rdf:type)
+        assert_eq!(result.triples.len(), 2);
+
+        // Find the label triple
+        let label_triple = result.triples.iter()
+            .find(|t| t.predicate == "rdfs:label")
+            .expect("Should have label");
+
+        match &label_triple.object {
+            Object::Literal { value, .. } => assert_eq!(value, "Updated Label"),
+            _ => panic!("Expected literal"),
+        }
+    }
+
+    #[test]
+    fn test_get_by_origin() {
+        let mut conn = setup_test_db();
+        setup_test_data(&mut conn);
+
+        // Origin ID 1 is "test" from setup_test_db
+        let result = get_by_origin(&conn, 1).unwrap();
+        assert!(result.triples.len() > 0);
+    }
+
+    #[test]
+    fn test_get_by_graph() {
+        let mut conn = setup_test_db();
+        setup_test_data(&mut conn);
+        assert_triples(&mut conn, &[Triple::new(
+            "foundation:Other",
+            "rdf:type",
+            Object::Iri("owl:Class".to_string()),
+        ).in_graph("foundation:PeerGraph")], "test").unwrap();
+
+        let peer = get_by_graph(&conn, "foundation:PeerGraph").unwrap();
+        assert_eq!(peer.triples.len(), 1);
+        assert_eq!(peer.triples[0].graph, "foundation:PeerGraph");
+        assert_eq!(get_by_graph(&conn, DEFAULT_GRAPH).unwrap().triples.len(), 3);
+        assert_eq!(get_by_entity(&conn, "foundation:Other").unwrap().triples.len(), 1); // Queries see every graph
+        assert_eq!(get_graphs(&conn).unwrap(), vec![
+            (DEFAULT_GRAPH.to_string(), 3),
+            ("foundation:PeerGraph".to_string(), 1),
+        ]);
+    }
+
+    #[test]
+    fn test_get_by_transaction() {
+        let mut conn = setup_test_db();
+        let tx1 = setup_test_data(&mut conn);
+        let tx2 = assert_triples(&mut conn, &[Triple::new(
+            "foundation:Other",
+            "rdf:type",
+            Object::Iri("owl:Class".to_string()),
+        )], "test").unwrap();
+
+        assert_eq!(get_by_transaction(&conn, tx1).unwrap().triples.len(), 3);
+        let second = get_by_transaction(&conn, tx2).unwrap();
+        assert_eq!(second.triples.len(), 1);
+        assert_eq!(second.triples[0].subject, "foundation:Other");
+    }
+
+    #[test]
+    fn test_get_all() {
+        let mut conn = setup_test_db();
+        setup_test_data(&mut conn);
+
+        let result = get_all(&conn).unwrap();
+        assert_eq!(result.triples.len(), 3);
+        assert_eq!(result.triples[0].subject, "foundation:TestClass");
+    }
+
+    #[test]
+    fn test_get_origin_id() {
+        let conn = setup_test_db();
+
+        assert_eq!(get_origin_id(&conn, "test").unwrap(), Some(1));
+        assert_eq!(get_origin_id(&conn, "missing").unwrap(), None);
+    }
+
+    #[test]
+    fn test_get_origin_name() {
+        let conn = setup_test_db();
+
+        assert_eq!(get_origin_name(&conn, 1).unwrap().as_deref(), Some("test"));
+        assert_eq!(get_origin_name(&conn, 99).unwrap(), None);
+    }
+
+    #[test]
+    fn test_get_latest_tx() {
+        let mut conn = setup_test_db();
+        assert_eq!(get_latest_tx(&conn).unwrap(), 0);
+
+        let tx = setup_test_data(&mut conn);
+        assert_eq!(get_latest_tx(&conn).unwrap(), tx);
+    }
+
+    #[test]
+    fn test_get_transactions() {
+        let mut conn = setup_test_db();
+        let tx1 = setup_test_data(&mut conn);
+        let annotation = Annotation { message: Some("Imported March bank statement".to_string()), tags: vec!["finance".to_string()] };
+        let tx2 = apply_annotated_changes(&mut conn, &[Triple::new(
+            "foundation:Statement",
+            "rdf:type",
+            Object::Iri("foundation:Document".to_string()),
+        )], &[], "import:bank", &annotation).unwrap();
+
+        let feed = get_transactions(&conn, None, None, 10).unwrap();
+        assert_eq!(feed.iter().map(|t| t.tx).collect::<Vec<_>>(), vec![tx2, tx1]);
+        assert_eq!(feed[0].message.as_deref(), Some("Imported March bank statement"));
+        assert_eq!(feed[0].tags, vec!["finance"]);
+        assert_eq!(feed[1].message, None);
+        assert!(feed[1].tags.is_empty());
+
+        assert_eq!(get_transactions(&conn, Some(tx2), None, 10).unwrap().len(), 1);
+        assert_eq!(get_transactions(&conn, None, Some("finance"), 10).unwrap().len(), 1);
+        assert!(get_transactions(&conn, None, Some("travel"), 10).unwrap().is_empty());
+        assert_eq!(count_by_transaction(&conn, tx1).unwrap(), 3);
+
+        let after = get_transactions_after(&conn, 0, 10).unwrap();
+        assert_eq!(after.iter().map(|t| t.tx).collect::<Vec<_>>(), vec![tx1, tx2]);
+        assert_eq!(after[1].tags, vec!["finance"]);
+        assert!(get_transactions_after(&conn, tx2, 10).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_diff_transactions() {
+        let mut conn = setup_test_db();
+        let label = |value: &str| Triple::new("foundation:TestClass", "rdfs:label", Object::Literal {
+            value: value.to_string(),
+            datatype: Some("xsd:string".to_string()),
+            language: None,
+        });
+        let tx1 = assert_triples(&mut conn, &[label("First")], "test").unwrap();
+        let tx2 = apply_annotated_changes(&mut conn, &[label("Second")], &[label("First")], "test", &Annotation::default()).unwrap();
+        let tx3 = apply_annotated_changes(&mut conn, &[label("Third")], &[label("Second")], "test", &Annotation::default()).unwrap();
+
+        let value = |triples: &[Triple]| triples.iter().filter_map(|t| t.object.as_literal()).collect::<Vec<_>>();
+
+        let (added, retracted) = diff_transactions(&conn, tx1, tx2).unwrap();
+        assert_eq!((value(&added), value(&retracted)), (vec!["Second".to_string()], vec!["First".to_string()]));
+
+        // Second was added and retracted in between
+        let (added, retracted) = diff_transactions(&conn, tx1, tx3).unwrap();
+        assert_eq!((value(&added), value(&retracted)), (vec!["Third".to_string()], vec!["First".to_string()]));
+
+        let (added, retracted) = diff_transactions(&conn, tx3, tx1).unwrap();
+        assert_eq!((value(&added), value(&retracted)), (vec!["First".to_string()], vec!["Third".to_string()]));
+
+        let (added, retracted) = diff_transactions(&conn, tx2, tx2).unwrap();
+        assert!(added.is_empty() && retracted.is_empty());
+    }
+
+    #[test]
+    fn test_get_active_origins() {
+        let mut conn = setup_test_db();
+        assert!(get_active_origins(&conn).unwrap().is_empty());
+
+        setup_test_data(&mut conn);
+        assert_eq!(get_active_origins(&conn).unwrap(), vec!["test"]);
+    }
+
+    #[test]
+    fn test_count_by_origin() {
+        let mut conn = setup_test_db();
+        setup_test_data(&mut conn);
+
+        assert_eq!(count_by_origin(&conn, 1).unwrap(), 3);
+        assert_eq!(count_by_origin(&conn, 99).unwrap(), 0);
+    }
+
+    #[test]
+    fn test_get_history() {
+        let mut conn = setup_test_db();
+        let tx1 = setup_test_data(&mut conn);
+
+        // Add another triple in a new transaction
+        let new_triple = vec![Triple {
+            subject: "foundation:TestClass".to_string(),
+            predicate: "rdfs:comment".to_string(),
+            object: Object::Literal {
+                value: "A comment".to_string(),
+                datatype: Some("xsd:string".to_string()),
+                language: None,
+            },
+            graph: DEFAULT_GRAPH.to_string(),
+            tx: 0,
+            created_at: 2000,
+            origin_id: 1,
+            retracted: false,
+        }];
+        let tx2 = assert_triples(&mut conn, &new_triple, "test").unwrap();
+
+        let history = get_history(&conn, "foundation:TestClass").unwrap();
+
+        assert_eq!(history.len(), 2); // Two transactions
+        assert_eq!(history[0].0, tx1); // First transaction
+        assert_eq!(history[1].0, tx2); // Second transaction
+        assert_eq!(history[0].1.len(), 2); // First tx has 2 triples
+        assert_eq!(history[1].1.len(), 1); // Second tx has 1 triple
+    }
+
+    #[test]
+    fn test_get_by_predicate_range() {
+        let mut conn = setup_test_db();
+        let literal = |value: &str, datatype: &str| Object::Literal {
+            value: value.to_string(),
+            datatype: Some(datatype.to_string()),
+            language: None,
+        };
+        assert_triples(&mut conn, &[
+            Triple::new("foundation:a", "foundation:capacity", Object::Integer(8)),
+            Triple::new("foundation:b", "foundation:capacity", Object::Integer(32)),
+            Triple::new("foundation:c", "foundation:capacity", Object::Integer(16)),
+            Triple::new("foundation:c", "foundation:weight", Object::Number(1.5)),
+            Triple::new("foundation:d", "foundation:weight", Object::Integer(3)),
+            Triple::new("foundation:a", "foundation:at", literal("2024-01-01T10:00:00Z", "xsd:dateTime")),
+            Triple::new("foundation:b", "foundation:at", literal("2024-06-01", "xsd:date")),
+            Triple::new("foundation:c", "foundation:at", literal("12:00:00", "xsd:time")),
+        ], "test").unwrap();
+        let subjects = |range| -> Vec<String> {
+            get_by_predicate_range(&conn, "foundation:capacity", range).unwrap()
+                .triples.into_iter().map(|t| t.subject).collect()
+        };
+
+        // Inclusive, smallest first, open where a bound is missing
+        assert_eq!(subjects(ValueRange::Integer(Some(16), None)), ["foundation:c", "foundation:b"]);
+        assert_eq!(subjects(ValueRange::Integer(Some(8), Some(16))), ["foundation:a", "foundation:c"]);
+        assert!(subjects(ValueRange::Integer(Some(64), None)).is_empty());
+
+        let weights = get_by_predicate_range(&conn, "foundation:weight", ValueRange::Number(Some(1.0), Some(3.0))).unwrap();
+        assert_eq!(weights.triples.len(), 2); // Integers are numbers too
+
+        // Dates are midnight; times of day are not moments
+        let june = XsdType::DateTime.epoch_millis("2024-05-01T00:00:00Z").unwrap();
+        let after = get_by_predicate_range(&conn, "foundation:at", ValueRange::DateTime(Some(june), None)).unwrap();
+        assert_eq!(after.triples.iter().map(|t| t.subject.as_str()).collect::<Vec<_>>(), ["foundation:b"]);
+        let all = get_by_predicate_range(&conn, "foundation:at", ValueRange::DateTime(None, None)).unwrap();
+        assert_eq!(all.triples.len(), 2);
+
+        let unknown = get_by_predicate_range(&conn, "foundation:unknown", ValueRange::Integer(None, None)).unwrap();
+        assert!(unknown.triples.is_empty());
+    }
+
+    #[test]
+    fn test_row_to_triple_with_iri() {
+        let mut conn = setup_test_db();
+        setup_test_data(&mut conn);
+
+        let result = get_by_entity(&conn, "foundation:TestClass").unwrap();
+        let iri_triple = result.triples.iter()
+            .find(|t| t.predicate == "rdf:type")
+            .expect("Should have rdf:type");
+
+        match &iri_triple.object {
+            Object::Iri(iri) => assert_eq!(iri, "owl:Class"),
+            _ => panic!("Expected IRI object"),
+        }
+    }
+
+    #[test]
+    fn test_row_to_triple_with_integer() {
+        let mut conn = setup_test_db();
+        setup_test_data(&mut conn);
+
+        let result = get_by_entity(&conn, "foundation:TestProperty").unwrap();
+        let int_triple = result.triples.iter()
+            .find(|t| t.predicate == "foundation:someValue")
+            .expect("Should have foundation:someValue");
+
+        match &int_triple.object {
+            Object::Integer(i) => assert_eq!(*i, 42),
+            _ => panic!("Expected Integer object"),
+        }
+    }
+}
@@ -0,0 +1,334 @@
+//! Sandbox Transactions (what-if mode)
+//!
+//! A sandbox collects asserts and retractions without making them permanent,
+//! so the effect of bulk changes can be previewed before committing them.
+//!
+//! Reads run against the store with the sandbox's changes applied inside a
+//! savepoint that is rolled back afterwards. Every existing query (and the
+//! OWL layer on top) sees the overlay without knowing about it, and nothing
+//! outside the read ever does.
+//!
+//! Committing replays the change sets in order, atomically. Change sets are
+//! intents (assert this triple, retract that one), so they apply on top of
+//! whatever was committed since the sandbox began.
+//!
+//! Any other write can be previewed the same way with `dry_run`: it runs
+//! inside a savepoint that is rolled back, and the triples it added and
+//! retracted are read back from the store before the rollback.
+
+use rusqlite::Connection;
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicU64, Ordering};
+use super::triple_type::Triple;
+use super::{query, store};
+use super::transaction_type::Annotation;
+
+type Result<T> = std::result::Result<T, Box<dyn std::error::Error>>;
+
+/// Savepoint holding a sandbox overlay while it is read
+const OVERLAY_SAVEPOINT: &str = "sandbox_overlay";
+
+/// Savepoint making a sandbox commit atomic
+const COMMIT_SAVEPOINT: &str = "sandbox_commit";
+
+/// Savepoint holding a write while it is previewed
+const DRY_RUN_SAVEPOINT: &str = "dry_run";
+
+/// Triples a previewed write would add and retract
+#[derive(Debug, Clone, Default)]
+pub struct Preview {
+    pub added: Vec<Triple>,
+    pub retracted: Vec<Triple>,
+}
+
+/// Pending changes of a what-if session
+#[derive(Debug, Clone)]
+pub struct Sandbox {
+    pub id: String,
+    pub origin: String,
+    pub base_tx: i64, // Latest committed transaction when the sandbox began
+    changes: Vec<ChangeSet>,
+}
+
+/// One batch of changes, applied as one transaction
+#[derive(Debug, Clone)]
+struct ChangeSet {
+    additions: Vec<Triple>,
+    retractions: Vec<Triple>,
+}
+
+impl Sandbox {
+    pub fn new(id: &str, origin: &str, base_tx: i64) -> Self {
+        Self {
+            id: id.to_string(),
+            origin: origin.to_string(),
+            base_tx,
+            changes: Vec::new(),
+        }
+    }
+
+    /// Record a batch of additions and exact retractions
+    pub fn apply(&mut self, additions: Vec<Triple>, retractions: Vec<Triple>) {
+        if additions.is_empty() && retractions.is_empty() {
+            return;
+        }
+        self.changes.push(ChangeSet { additions, retractions });
+    }
+
+    /// Record triples to assert
+    pub fn assert(&mut self, triples: Vec<Triple>) {
+        self.apply(triples, Vec::new());
+    }
+
+    /// Record triples to retract
+    pub fn retract(&mut self, triples: Vec<Triple>) {
+        self.apply(Vec::new(), triples);
+    }
+
+    /// All pending (additions, retractions), in the order they were recorded
+    pub fn pending(&self) -> (Vec<Triple>, Vec<Triple>) {
+        let additions = self.changes.iter().flat_map(|c| c.additions.iter().cloned()).collect();
+        let retractions = self.changes.iter().flat_map(|c| c.retractions.iter().cloned()).collect();
+        (additions, retractions)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.changes.is_empty()
+    }
+
+    /// Run a read against the store as it would be after committing
+    ///
+    /// The overlay is rolled back afterwards, whatever the read returns.
+    pub fn with_overlay<R>(
+        &self,
+        conn: &mut Connection,
+        read: impl FnOnce(&Connection) -> Result<R>,
+    ) -> Result<R> {
+        conn.execute_batch(&format!("SAVEPOINT {}", OVERLAY_SAVEPOINT))?;
+
+        let result = self.replay(conn, &Annotation::default()).and_then(|_| read(conn));
+
+        conn.execute_batch(&format!(
+            "ROLLBACK TO {0}; RELEASE {0}",
+            OVERLAY_SAVEPOINT
+        ))?;
+        result
+    }
+
+    /// Make the changes permanent, annotating each transaction; returns the
+    /// transaction IDs, one per change set
+    pub fn commit(self, conn: &mut Connection, annotation: &Annotation) -> Result<Vec<i64>> {
+        conn.execute_batch(&format!("SAVEPOINT {}", COMMIT_SAVEPOINT))?;
+
+        match self.replay(conn, annotation) {
+            Ok(txs) => {
+                conn.execute_batch(&format!("RELEASE {}", COMMIT_SAVEPOINT))?;
+                Ok(txs)
+            }
+            Err(e) => {
+                conn.execute_batch(&format!("ROLLBACK TO {0}; RELEASE {0}", COMMIT_SAVEPOINT))?;
+                Err(e)
+            }
+        }
+    }
+
+    fn replay(&self, conn: &mut Connection, annotation: &Annotation) -> Result<Vec<i64>> {
+        self.changes.iter()
+            .map(|change| store::apply_annotated_changes(conn, &change.additions, &change.retractions, &self.origin, annotation))
+            .collect()
+    }
+}
+
+/// Run a write and roll it back; returns its result and the triples it
+/// would add and retract
+///
+/// The write must not start a transaction of its own (savepoints nest).
+pub fn dry_run<R, E>(
+    conn: &mut Connection,
+    write: impl FnOnce(&mut Connection) -> std::result::Result<R, E>,
+) -> Result<(R, Preview)>
+where
+    E: Into<Box<dyn std::error::Error>>,
+{
+    conn.execute_batch(&format!("SAVEPOINT {}", DRY_RUN_SAVEPOINT))?;
+
+    let result = query::get_latest_tx(conn).and_then(|before| {
+        let value = write(conn).map_err(Into::into)?;
+        let after = query::get_latest_tx(conn)?;
+        let (added, retracted) = query::diff_transactions(conn, before, after)?;
+        Ok((value, Preview { added, retracted }))
+    });
+
+    conn.execute_batch(&format!(
+        "ROLLBACK TO {0}; RELEASE {0}",
+        DRY_RUN_SAVEPOINT
+    ))?;
+    result
+}
+
+/// Open sandboxes, by handle
+#[derive(Debug, Default)]
+pub struct SandboxRegistry {
+    sandboxes: Mutex<HashMap<String, Sandbox>>,
+    next_id: AtomicU64,
+}
+
+impl SandboxRegistry {
+    /// Open a sandbox and return its handle
+    pub fn begin(&self, origin: &str, base_tx: i64) -> String {
+        let id = format!("sandbox-{}", self.next_id.fetch_add(1, Ordering::Relaxed) + 1);
+        self.sandboxes.lock().unwrap().insert(id.clone(), Sandbox::new(&id, origin, base_tx));
+        id
+    }
+
+    /// Change a sandbox
+    pub fn update<R>(&self, id: &str, change: impl FnOnce(&mut Sandbox) -> R) -> std::result::Result<R, String> {
+        let mut sandboxes = self.sandboxes.lock().unwrap();
+        let sandbox = sandboxes.get_mut(id).ok_or_else(|| format!("Sandbox {} not found", id))?;
+        Ok(change(sandbox))
+    }
+
+    /// Copy of a sandbox, to read through it
+    pub fn get(&self, id: &str) -> std::result::Result<Sandbox, String> {
+        self.update(id, |sandbox| sandbox.clone())
+    }
+
+    /// Close a sandbox, returning it (to commit) or dropping it (to discard)
+    pub fn close(&self, id: &str) -> std::result::Result<Sandbox, String> {
+        self.sandboxes.lock().unwrap()
+            .remove(id)
+            .ok_or_else(|| format!("Sandbox {} not found", id))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::eavto::{query, test_helpers::setup_test_db, Object};
+
+    fn label(subject: &str, value: &str) -> Triple {
+        Triple::new(subject, "rdfs:label", Object::Literal {
+            value: value.to_string(),
+            datatype: Some("xsd:string".to_string()),
+            language: None,
+        })
+    }
+
+    fn labels(conn: &Connection, subject: &str) -> Vec<String> {
+        query::get_by_entity(conn, subject).unwrap()
+            .triples
+            .iter()
+            .filter_map(|t| t.object.as_literal())
+            .collect()
+    }
+
+    #[test]
+    fn test_overlay_is_visible_only_inside_reads() {
+        let mut conn = setup_test_db();
+        store::assert_triples(&mut conn, &[label("foundation:A", "Old")], "test").unwrap();
+        let latest = query::get_latest_tx(&conn).unwrap();
+
+        let mut sandbox = Sandbox::new("s1", "test", latest);
+        sandbox.apply(vec![label("foundation:A", "New")], vec![label("foundation:A", "Old")]);
+        sandbox.assert(vec![label("foundation:B", "Added")]);
+
+        let preview = sandbox.with_overlay(&mut conn, |conn| {
+            Ok((labels(conn, "foundation:A"), labels(conn, "foundation:B")))
+        }).unwrap();
+        assert_eq!(preview, (vec!["New".to_string()], vec!["Added".to_string()]));
+
+        // Nothing leaked into the store, not even transaction IDs
+        assert_eq!(labels(&conn, "foundation:A"), vec!["Old"]);
+        assert!(labels(&conn, "foundation:B").is_empty());
+        assert_eq!(query::get_latest_tx(&conn).unwrap(), latest);
+    }
+
+    #[test]
+    fn test_dry_run_reports_changes_and_rolls_back() {
+        let mut conn = setup_test_db();
+        store::assert_triples(&mut conn, &[label("foundation:A", "Old")], "test").unwrap();
+        let latest = query::get_latest_tx(&conn).unwrap();
+
+        let (tx, preview) = dry_run(&mut conn, |conn| {
+            store::apply_changes(conn, &[label("foundation:A", "New")], &[label("foundation:A", "Old")], "test")?;
+            store::assert_triples(conn, &[label("foundation:B", "Other")], "test")
+        }).unwrap();
+        assert!(tx > latest);
+        assert_eq!(preview.added.len(), 2);
+        assert_eq!(preview.retracted.len(), 1);
+        assert_eq!(preview.retracted[0].object.as_literal().as_deref(), Some("Old"));
+
+        assert_eq!(labels(&conn, "foundation:A"), vec!["Old"]);
+        assert!(labels(&conn, "foundation:B").is_empty());
+        assert_eq!(query::get_latest_tx(&conn).unwrap(), latest);
+
+        let failed: Result<((), Preview)> = dry_run(&mut conn, |conn| {
+            store::assert_triples(conn, &[label("foundation:C", "C")], "test")?;
+            Err::<(), Box<dyn std::error::Error>>("write failed".into())
+        });
+        assert!(failed.is_err());
+        assert!(labels(&conn, "foundation:C").is_empty());
+    }
+
+    #[test]
+    fn test_dry_run_of_retract_by_pattern() {
+        let mut conn = setup_test_db();
+        store::assert_triples(&mut conn, &[label("foundation:A", "Old"), label("foundation:A", "Older")], "test").unwrap();
+
+        let (_, preview) = dry_run(&mut conn, |conn| {
+            store::retract_by_pattern(conn, &[("foundation:A", "rdfs:label")], "test")
+        }).unwrap();
+        assert_eq!(preview.retracted.len(), 2);
+        assert!(preview.added.is_empty());
+
+        let mut current = labels(&conn, "foundation:A");
+        current.sort();
+        assert_eq!(current, vec!["Old", "Older"]);
+    }
+
+    #[test]
+    fn test_overlay_rolls_back_failed_reads() {
+        let mut conn = setup_test_db();
+        let mut sandbox = Sandbox::new("s1", "test", 0);
+        sandbox.assert(vec![label("foundation:A", "New")]);
+
+        let result: Result<()> = sandbox.with_overlay(&mut conn, |_| Err("read failed".into()));
+        assert!(result.is_err());
+        assert!(labels(&conn, "foundation:A").is_empty());
+    }
+
+    #[test]
+    fn test_commit_applies_change_sets_in_order() {
+        let mut conn = setup_test_db();
+        let mut sandbox = Sandbox::new("s1", "test", 0);
+        sandbox.assert(vec![label("foundation:A", "First")]);
+        sandbox.apply(vec![label("foundation:A", "Second")], vec![label("foundation:A", "First")]);
+
+        let (additions, retractions) = sandbox.pending();
+        assert_eq!((additions.len(), retractions.len()), (2, 1));
+
+        let annotation = Annotation { message: Some("Rename A".to_string()), tags: vec!["cleanup".to_string()] };
+        let txs = sandbox.commit(&mut conn, &annotation).unwrap();
+        assert_eq!(txs.len(), 2);
+        assert_eq!(labels(&conn, "foundation:A"), vec!["Second"]);
+
+        let feed = query::get_transactions(&conn, None, Some("cleanup"), 10).unwrap();
+        assert_eq!(feed.iter().map(|t| t.tx).collect::<Vec<_>>(), vec![txs[1], txs[0]]);
+        assert!(feed.iter().all(|t| t.message.as_deref() == Some("Rename A")));
+    }
+
+    #[test]
+    fn test_registry() {
+        let registry = SandboxRegistry::default();
+        let id = registry.begin("user-edit", 7);
+
+        registry.update(&id, |s| s.assert(vec![label("foundation:A", "New")])).unwrap();
+        assert!(!registry.get(&id).unwrap().is_empty());
+        assert_eq!(registry.get(&id).unwrap().base_tx, 7);
+
+        registry.close(&id).unwrap();
+        assert!(registry.get(&id).is_err());
+        assert!(registry.close(&id).is_err());
+    }
+}
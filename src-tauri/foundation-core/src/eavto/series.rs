@@ -0,0 +1,166 @@
+//! EAVTO Time Series
+//!
+//! Aggregates the numeric values a property of an entity has taken over time
+//! into calendar buckets (UTC day, ISO week, month), for charts.
+//!
+//! Every value ever asserted counts at its assertion time, including values
+//! since replaced (retracted): a reading that was later updated is still a
+//! point of the series. Values from quarantined origins are left out, and
+//! values from another device count no later than they were received here
+//! (see skew).
+
+use chrono::{Datelike, NaiveDate, TimeZone, Utc};
+use rusqlite::Connection;
+use std::collections::BTreeMap;
+
+type Result<T> = std::result::Result<T, Box<dyn std::error::Error>>;
+
+/// Calendar bucket width
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Bucket {
+    Day,
+    Week, // ISO week, starting Monday
+    Month,
+}
+
+impl Bucket {
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.to_ascii_lowercase().as_str() {
+            "day" => Some(Bucket::Day),
+            "week" => Some(Bucket::Week),
+            "month" => Some(Bucket::Month),
+            _ => None,
+        }
+    }
+
+    /// Start (Unix ms, UTC) of the bucket containing a timestamp
+    fn start(&self, ms: i64) -> i64 {
+        let date = Utc.timestamp_millis_opt(ms).single()
+            .map(|dt| dt.date_naive())
+            .unwrap_or_default();
+        let start = match self {
+            Bucket::Day => date,
+            Bucket::Week => date - chrono::Duration::days(date.weekday().num_days_from_monday() as i64),
+            Bucket::Month => NaiveDate::from_ymd_opt(date.year(), date.month(), 1).unwrap_or(date),
+        };
+        start.and_hms_opt(0, 0, 0).unwrap_or_default().and_utc().timestamp_millis()
+    }
+}
+
+/// Aggregated series, as parallel arrays (one element per non-empty bucket,
+/// oldest first)
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Series {
+    pub starts: Vec<i64>, // Bucket start (Unix ms, UTC)
+    pub counts: Vec<usize>,
+    pub min: Vec<f64>,
+    pub max: Vec<f64>,
+    pub avg: Vec<f64>,
+    pub sum: Vec<f64>,
+    pub last: Vec<f64>, // Latest value in the bucket
+    pub unit: Option<String>, // QUDT unit of the property (qudt:hasUnit)
+}
+
+/// Numeric values of an entity's property, aggregated per bucket
+pub fn series(conn: &Connection, entity: &str, property: &str, bucket: Bucket) -> Result<Series> {
+    let mut stmt = conn.prepare(&format!(
+        "SELECT {}, COALESCE(t.object_number, t.object_integer)
+         FROM triples t
+         LEFT JOIN transactions x ON x.tx = t.tx
+         WHERE t.subject = ? AND t.predicate = ?
+           AND (t.object_number IS NOT NULL OR t.object_integer IS NOT NULL)
+           AND t.origin_id NOT IN (SELECT id FROM origins WHERE status = 'quarantined')
+         ORDER BY 1, t.tx, t.rowid",
+        super::skew::effective_time("t", "x")
+    ))?;
+    let points = stmt
+        .query_map([entity, property], |row| Ok((row.get::<_, i64>(0)?, row.get::<_, f64>(1)?)))?
+        .collect::<std::result::Result<Vec<_>, _>>()?;
+
+    let mut buckets: BTreeMap<i64, Vec<f64>> = BTreeMap::new();
+    for (at, value) in points {
+        buckets.entry(bucket.start(at)).or_default().push(value);
+    }
+
+    let mut series = Series { unit: unit(conn, property)?, ..Default::default() };
+    for (start, values) in buckets {
+        let sum: f64 = values.iter().sum();
+        series.starts.push(start);
+        series.counts.push(values.len());
+        series.min.push(values.iter().copied().fold(f64::INFINITY, f64::min));
+        series.max.push(values.iter().copied().fold(f64::NEG_INFINITY, f64::max));
+        series.avg.push(sum / values.len() as f64);
+        series.sum.push(sum);
+        series.last.push(values[values.len() - 1]);
+    }
+    Ok(series)
+}
+
+fn unit(conn: &Connection, property: &str) -> Result<Option<String>> {
+    Ok(super::query::get_by_entity_predicate(conn, property, "qudt:hasUnit")?
+        .triples.first()
+        .and_then(|t| t.object.as_iri().map(String::from)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::eavto::{store, test_helpers::setup_test_db, Object, Triple};
+
+    const DAY: i64 = 86_400_000;
+
+    /// Assert a reading, dated `at` (Unix ms)
+    fn reading(conn: &mut Connection, value: Object, at: i64) {
+        let current = crate::eavto::get_by_entity_predicate(conn, "foundation:Sensor", "foundation:temperature").unwrap();
+        let triple = Triple::new("foundation:Sensor", "foundation:temperature", value);
+        let tx = store::apply_changes(conn, &[triple], &current.triples, "test").unwrap();
+        conn.execute("UPDATE triples SET created_at = ? WHERE tx = ?", (at, tx)).unwrap();
+    }
+
+    #[test]
+    fn test_bucket_start() {
+        // 2024-03-14 (a Thursday) 15:30 UTC
+        let ms = Utc.with_ymd_and_hms(2024, 3, 14, 15, 30, 0).unwrap().timestamp_millis();
+        let date = |y, m, d| Utc.with_ymd_and_hms(y, m, d, 0, 0, 0).unwrap().timestamp_millis();
+
+        assert_eq!(Bucket::Day.start(ms), date(2024, 3, 14));
+        assert_eq!(Bucket::Week.start(ms), date(2024, 3, 11));
+        assert_eq!(Bucket::Month.start(ms), date(2024, 3, 1));
+    }
+
+    #[test]
+    fn test_series_includes_replaced_values() {
+        let mut conn = setup_test_db();
+        reading(&mut conn, Object::Number(20.0), 0);
+        reading(&mut conn, Object::Integer(24), DAY / 2);
+        reading(&mut conn, Object::Number(18.5), DAY + 1);
+        store::assert_triples(&mut conn, &[
+            Triple::new("foundation:temperature", "qudt:hasUnit", Object::Iri("unit:DEG_C".to_string())),
+        ], "test").unwrap();
+
+        let daily = series(&conn, "foundation:Sensor", "foundation:temperature", Bucket::Day).unwrap();
+        assert_eq!(daily.starts, vec![0, DAY]);
+        assert_eq!(daily.counts, vec![2, 1]);
+        assert_eq!(daily.min, vec![20.0, 18.5]);
+        assert_eq!(daily.max, vec![24.0, 18.5]);
+        assert_eq!(daily.avg, vec![22.0, 18.5]);
+        assert_eq!(daily.last, vec![24.0, 18.5]);
+        assert_eq!(daily.unit.as_deref(), Some("unit:DEG_C"));
+
+        let monthly = series(&conn, "foundation:Sensor", "foundation:temperature", Bucket::Month).unwrap();
+        assert_eq!(monthly.counts, vec![3]);
+        assert_eq!(monthly.sum, vec![62.5]);
+    }
+
+    #[test]
+    fn test_series_caps_claims_from_fast_clocks() {
+        let mut conn = setup_test_db();
+        reading(&mut conn, Object::Number(20.0), 0);
+        // Received on day 1 from a device claiming day 5
+        reading(&mut conn, Object::Number(22.0), 5 * DAY);
+        conn.execute("UPDATE transactions SET created_at = ?, received_at = ? WHERE tx = (SELECT MAX(tx) FROM transactions)", (5 * DAY, DAY)).unwrap();
+
+        let daily = series(&conn, "foundation:Sensor", "foundation:temperature", Bucket::Day).unwrap();
+        assert_eq!(daily.starts, vec![0, DAY]);
+    }
+}
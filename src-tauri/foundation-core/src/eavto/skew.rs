@@ -0,0 +1,100 @@
+//! EAVTO Clock Skew
+//!
+//! Transactions made on another device (copied from its database) carry the
+//! time that device claimed in created_at, and the local time they arrived in
+//! received_at. A device whose clock runs fast claims times after it was
+//! received, which is impossible; such claims are capped at the receive time
+//! by `effective_time`, so time series and other temporal queries don't place
+//! data in the future.
+//!
+//! Claims in the past can't be told from old data and are kept as they are;
+//! the skew statistics per origin show how far back they go.
+
+use rusqlite::Connection;
+
+type Result<T> = std::result::Result<T, Box<dyn std::error::Error>>;
+
+/// Claims this far past their receive time are counted as ahead (clock
+/// adjustments and transfer delays stay under it)
+pub const TOLERANCE_MS: i64 = 5 * 60 * 1000;
+
+/// Clock skew of the transactions an origin received from another device
+#[derive(Debug, Clone, PartialEq)]
+pub struct OriginSkew {
+    pub origin: String,
+    pub transactions: i64,
+    pub ahead: i64, // Claimed after they were received (beyond TOLERANCE_MS)
+    pub max_ahead_ms: i64, // Furthest claim past its receive time (0: none)
+    pub first_claimed: i64, // Unix ms
+    pub last_claimed: i64,
+    pub last_received: i64,
+}
+
+/// SQL expression for the time of a triple, capped at its transaction's
+/// receive time; `triple` and `tx` are the aliases of the triples and
+/// transactions tables
+pub fn effective_time(triple: &str, tx: &str) -> String {
+    format!("MIN({t}.created_at, COALESCE({x}.received_at, {t}.created_at))", t = triple, x = tx)
+}
+
+/// Skew statistics per origin with received transactions, most ahead first
+pub fn skew_by_origin(conn: &Connection) -> Result<Vec<OriginSkew>> {
+    let mut stmt = conn.prepare(
+        "SELECT origin,
+                COUNT(*),
+                SUM(created_at > received_at + ?1),
+                MAX(MAX(created_at - received_at, 0)),
+                MIN(created_at),
+                MAX(created_at),
+                MAX(received_at)
+         FROM transactions
+         WHERE received_at IS NOT NULL
+         GROUP BY origin
+         ORDER BY 3 DESC, 4 DESC, origin"
+    )?;
+    let skews = stmt.query_map([TOLERANCE_MS], |row| Ok(OriginSkew {
+        origin: row.get(0)?,
+        transactions: row.get(1)?,
+        ahead: row.get(2)?,
+        max_ahead_ms: row.get(3)?,
+        first_claimed: row.get(4)?,
+        last_claimed: row.get(5)?,
+        last_received: row.get(6)?,
+    }))?.collect::<std::result::Result<Vec<_>, _>>()?;
+    Ok(skews)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::eavto::test_helpers::setup_test_db;
+
+    const HOUR: i64 = 3_600_000;
+
+    #[test]
+    fn test_skew_by_origin() {
+        let conn = setup_test_db();
+        conn.execute_batch(&format!(
+            "INSERT INTO transactions (origin, created_at, received_at) VALUES
+                ('phone', {now}, {now}),
+                ('phone', {ahead}, {now}),
+                ('laptop', {old}, {now}),
+                ('laptop', {slightly}, {now});
+             INSERT INTO transactions (origin, created_at) VALUES ('user-edit', {ahead});",
+            now = 100 * HOUR, ahead = 103 * HOUR, old = 10 * HOUR, slightly = 100 * HOUR + 60_000,
+        )).unwrap();
+
+        let skews = skew_by_origin(&conn).unwrap();
+        assert_eq!(skews.len(), 2);
+        assert_eq!(skews[0], OriginSkew {
+            origin: "phone".to_string(),
+            transactions: 2,
+            ahead: 1,
+            max_ahead_ms: 3 * HOUR,
+            first_claimed: 100 * HOUR,
+            last_claimed: 103 * HOUR,
+            last_received: 100 * HOUR,
+        });
+        assert_eq!((skews[1].origin.as_str(), skews[1].ahead, skews[1].first_claimed), ("laptop", 0, 10 * HOUR));
+    }
+}
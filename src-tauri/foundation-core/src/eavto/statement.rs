@@ -0,0 +1,194 @@
+//! Quoted Triples (RDF-star)
+//!
+//! A fact can be talked about without asserting it: its quoted triple is a
+//! term, `<<( s p o )>>` (see QuotedTriple::term), interned like an IRI, so
+//! triples can be about it (`<<( alice age 42 )>> foundation:confidence 0.9`)
+//! or point to it (Object::Triple).
+//!
+//! The term spells the fact out with canonical IRIs and literals, so the
+//! same fact always has the same term, and reads parse it back. Its terms ID
+//! is the statement ID: the statements table maps it to the IDs of the
+//! fact's subject, predicate and object, so the facts quoted about an entity
+//! are an index lookup.
+
+use rusqlite::Connection;
+use crate::namespaces::compress_iri;
+use super::object_type::Object;
+use super::terms;
+use super::triple_type::QuotedTriple;
+use super::xsd_type::XsdType;
+
+type Result<T> = std::result::Result<T, Box<dyn std::error::Error>>;
+
+/// Whether a term is a quoted triple term
+pub fn is_statement(term: &str) -> bool {
+    term.starts_with("<<(") && term.ends_with(")>>")
+}
+
+/// The quoted triple of a term, if it is one
+pub fn parse(term: &str) -> Option<QuotedTriple> {
+    if !is_statement(term) {
+        return None;
+    }
+    let mut rest = term;
+    let quoted = quoted_triple(&mut rest)?;
+    rest.is_empty().then_some(quoted)
+}
+
+/// ID of a term, adding it to the dictionary if it's new; a quoted triple
+/// is registered as a statement, its own terms interned
+pub(crate) fn intern(conn: &Connection, value: &str) -> rusqlite::Result<i64> {
+    match parse(value) {
+        Some(quoted) => register(conn, &quoted),
+        None => terms::intern(conn, value),
+    }
+}
+
+/// Statement ID of a quoted triple, registering it if it's new
+pub(crate) fn register(conn: &Connection, quoted: &QuotedTriple) -> rusqlite::Result<i64> {
+    let id = terms::intern(conn, &quoted.term())?;
+    let subject_id = intern(conn, &quoted.subject)?;
+    let predicate_id = terms::intern(conn, &quoted.predicate)?;
+    let object_id = match &quoted.object {
+        Object::Triple(inner) => Some(register(conn, inner)?),
+        object => object.as_iri().map(|iri| terms::intern(conn, iri)).transpose()?,
+    };
+    conn.prepare_cached(
+        "INSERT OR IGNORE INTO statements (id, subject_id, predicate_id, object_id) VALUES (?, ?, ?, ?)",
+    )?.execute(rusqlite::params![id, subject_id, predicate_id, object_id])?;
+    Ok(id)
+}
+
+/// Quoted triples about an entity (as their subject) that were ever stored
+pub fn about(conn: &Connection, subject: &str) -> Result<Vec<QuotedTriple>> {
+    let mut stmt = conn.prepare_cached(
+        "SELECT t.value FROM statements st
+         JOIN terms t ON t.id = st.id
+         JOIN terms s ON s.id = st.subject_id
+         WHERE s.value = ?
+         ORDER BY st.id",
+    )?;
+    let terms = stmt.query_map([subject], |row| row.get::<_, String>(0))?
+        .collect::<std::result::Result<Vec<_>, _>>()?;
+    Ok(terms.iter().filter_map(|term| parse(term)).collect())
+}
+
+// Parsing: `rest` is what's left of the term, consumed as it's read
+
+fn quoted_triple(rest: &mut &str) -> Option<QuotedTriple> {
+    *rest = rest.strip_prefix("<<(")?.trim_start();
+    let subject = node(rest)?;
+    let predicate = node(rest)?;
+    let object = object(rest)?;
+    *rest = rest.trim_start().strip_prefix(")>>")?;
+    Some(QuotedTriple { subject, predicate, object })
+}
+
+/// An IRI, blank node or quoted triple, as stored (IRIs compressed)
+fn node(rest: &mut &str) -> Option<String> {
+    *rest = rest.trim_start();
+    if rest.starts_with("<<(") {
+        quoted_triple(rest).map(|quoted| quoted.term())
+    } else if let Some(iri) = rest.strip_prefix('<') {
+        let end = iri.find('>')?;
+        *rest = &iri[end + 1..];
+        Some(compress_iri(&iri[..end]))
+    } else if rest.starts_with("_:") {
+        let end = rest.find(char::is_whitespace).unwrap_or(rest.len());
+        let (label, remaining) = rest.split_at(end);
+        *rest = remaining;
+        Some(label.to_string())
+    } else {
+        None
+    }
+}
+
+/// An object, with typed literals read as the store reads them
+fn object(rest: &mut &str) -> Option<Object> {
+    *rest = rest.trim_start();
+    if rest.starts_with("<<(") {
+        return quoted_triple(rest).map(|quoted| Object::Triple(Box::new(quoted)));
+    }
+    let Some(literal) = rest.strip_prefix('"') else {
+        let node = node(rest)?;
+        return Some(if node.starts_with("_:") { Object::Blank(node) } else { Object::Iri(node) });
+    };
+
+    let mut value = String::new();
+    let mut chars = literal.char_indices();
+    let end = loop {
+        match chars.next()? {
+            (i, '"') => break i,
+            (_, '\\') => value.push(match chars.next()?.1 {
+                'n' => '\n',
+                'r' => '\r',
+                c => c,
+            }),
+            (_, c) => value.push(c),
+        }
+    };
+    *rest = &literal[end + 1..];
+
+    if let Some(tagged) = rest.strip_prefix('@') {
+        let end = tagged.find(char::is_whitespace).unwrap_or(tagged.len());
+        let language = tagged[..end].to_string();
+        *rest = &tagged[end..];
+        return Some(Object::Literal { value, datatype: Some("rdf:langString".to_string()), language: Some(language) });
+    }
+    let datatype = match rest.strip_prefix("^^") {
+        Some(typed) => {
+            *rest = typed;
+            node(rest)?
+        }
+        None => "xsd:string".to_string(),
+    };
+    let typed = match XsdType::from_iri(&datatype) {
+        Some(XsdType::Integer | XsdType::Int | XsdType::Long) => value.parse().ok().map(Object::Integer),
+        Some(XsdType::Decimal | XsdType::Float | XsdType::Double) => value.parse().ok().map(Object::Number),
+        Some(XsdType::Boolean) => value.parse().ok().map(Object::Boolean),
+        Some(XsdType::DateTime) => XsdType::DateTime.epoch_millis(&value).map(Object::DateTime),
+        _ => None,
+    };
+    Some(typed.unwrap_or(Object::Literal { value, datatype: Some(datatype), language: None }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::eavto::test_helpers::setup_test_db;
+    use crate::eavto::{query, store, Triple};
+
+    #[test]
+    fn test_quoted_triples_round_trip_through_the_store() {
+        let mut conn = setup_test_db();
+        let age = QuotedTriple::new("foundation:alice", "foundation:age", Object::Integer(42));
+        let said = QuotedTriple::new("foundation:bob", "foundation:said", Object::Triple(Box::new(age.clone())));
+        let note = Object::Literal { value: "From \"her\" ID\ncard".into(), datatype: None, language: Some("en".into()) };
+        let noted = QuotedTriple::new("foundation:alice", "foundation:nickname", note);
+
+        for quoted in [&age, &said, &noted] {
+            assert_eq!(parse(&quoted.term()).map(|q| q.term()), Some(quoted.term()));
+        }
+        assert_eq!(parse(&age.term()), Some(age.clone()));
+        assert!(parse("<<( <a> )>>").is_none());
+
+        store::assert_triples(&mut conn, &[
+            Triple::new(age.term(), "foundation:confidence", Object::Number(0.9)),
+            Triple::new("foundation:bob", "foundation:said", Object::Triple(Box::new(age.clone()))),
+            Triple::new(said.term(), "foundation:note", Object::Literal { value: "Overheard".into(), datatype: None, language: None }),
+        ], "test").unwrap();
+
+        // Quoting a fact doesn't assert it
+        assert!(query::get_by_entity(&conn, "foundation:alice").unwrap().triples.is_empty());
+        let annotation = &query::get_by_entity(&conn, &age.term()).unwrap().triples[0];
+        assert_eq!(annotation.object, Object::Number(0.9));
+        let said_triples = query::get_by_entity(&conn, "foundation:bob").unwrap().triples;
+        assert_eq!(said_triples[0].object, Object::Triple(Box::new(age.clone())));
+
+        // One statement per fact, however often it's quoted
+        assert_eq!(about(&conn, "foundation:alice").unwrap(), vec![age.clone()]);
+        assert_eq!(about(&conn, "foundation:bob").unwrap(), vec![said]);
+        let statements: i64 = conn.query_row("SELECT COUNT(*) FROM statements", [], |row| row.get(0)).unwrap();
+        assert_eq!(statements, 2);
+    }
+}
@@ -0,0 +1,381 @@
+// ============================================================================
+// EAVTO Statistics Module
+// ============================================================================
+// Provides database statistics and metrics
+//
+// Storage accounting:
+// - Rows and estimated bytes per origin and per workspace, so a runaway
+//   data source shows up before it dominates the database
+// - Optional soft quotas per origin (metadata quota:<origin>, in estimated
+//   bytes): writes over them still go through, the executor warns
+//
+// Content: instances per class and uses per property, counted by GROUP BY
+// over the current quads (term IDs, so no string is compared)
+// ============================================================================
+
+use rusqlite::{Connection, OptionalExtension};
+use super::connection::DbError;
+use super::query;
+
+/// Metadata key prefix of origin quotas
+pub const QUOTA_KEY_PREFIX: &str = "quota:";
+
+/// Estimated bytes of a triples row: its text columns, plus the typed
+/// columns and index entries (a fixed overhead)
+const ROW_BYTES: &str = "(length(t.subject) + length(t.predicate) + COALESCE(length(t.object), 0)
+     + COALESCE(length(t.object_value), 0) + COALESCE(length(t.graph), 0) + 64)";
+
+/// Database statistics
+#[derive(Debug, serde::Serialize)]
+pub struct DbStats {
+    pub total_facts: u64,
+    pub active_facts: u64,
+    pub total_transactions: u64,
+    pub entities_count: u64,
+    pub ontology_imported: bool,
+}
+
+/// Get database statistics
+pub fn get_stats(conn: &Connection) -> Result<DbStats, DbError> {
+    let total_facts: u64 = conn.query_row(
+        "SELECT COUNT(*) FROM triples",
+        [],
+        |row| row.get(0)
+    )?;
+
+    let active_facts: u64 = conn.query_row(
+        "SELECT COUNT(*) FROM triples WHERE retracted = 0",
+        [],
+        |row| row.get(0)
+    )?;
+
+    let total_transactions: u64 = conn.query_row(
+        "SELECT COUNT(*) FROM transactions",
+        [],
+        |row| row.get(0)
+    )?;
+
+    let entities_count: u64 = conn.query_row(
+        "SELECT COUNT(DISTINCT subject) FROM triples WHERE retracted = 0",
+        [],
+        |row| row.get(0)
+    )?;
+
+    let ontology_imported_str: String = conn.query_row(
+        "SELECT value FROM metadata WHERE key = 'ontology_imported'",
+        [],
+        |row| row.get(0)
+    ).unwrap_or_else(|_| "false".to_string());
+
+    let ontology_imported = ontology_imported_str == "true";
+
+    Ok(DbStats {
+        total_facts,
+        active_facts,
+        total_transactions,
+        entities_count,
+        ontology_imported,
+    })
+}
+
+/// Storage used by an origin or a workspace
+#[derive(Debug, Clone, PartialEq)]
+pub struct StorageUsage {
+    pub name: String,         // Origin name, or workspace IRI
+    pub rows: u64,            // Including retracted rows, which stay stored
+    pub active_rows: u64,
+    pub estimated_bytes: u64,
+    pub quota_bytes: Option<u64>, // Origins only
+}
+
+impl StorageUsage {
+    /// Whether the usage is over its quota
+    pub fn over_quota(&self) -> bool {
+        self.quota_bytes.is_some_and(|quota| self.estimated_bytes > quota)
+    }
+}
+
+/// Storage used by each origin, largest first
+pub fn origin_usage(conn: &Connection) -> Result<Vec<StorageUsage>, DbError> {
+    let mut stmt = conn.prepare(&format!(
+        "SELECT o.name, COUNT(*), SUM(t.retracted = 0), SUM({})
+         FROM triples t JOIN origins o ON o.id = t.origin_id
+         GROUP BY o.name
+         ORDER BY 4 DESC, o.name",
+        ROW_BYTES
+    ))?;
+    let mut usage = stmt
+        .query_map([], |row| Ok(StorageUsage {
+            name: row.get(0)?,
+            rows: row.get(1)?,
+            active_rows: row.get(2)?,
+            estimated_bytes: row.get(3)?,
+            quota_bytes: None,
+        }))?
+        .collect::<Result<Vec<_>, _>>()?;
+    for entry in &mut usage {
+        entry.quota_bytes = get_quota(conn, &entry.name)?;
+    }
+    Ok(usage)
+}
+
+/// Storage used by the entities of each workspace, largest first
+///
+/// An entity in two workspaces counts in both.
+pub fn workspace_usage(conn: &Connection) -> Result<Vec<StorageUsage>, DbError> {
+    let mut stmt = conn.prepare(&format!(
+        "SELECT m.object, COUNT(*), SUM(t.retracted = 0), SUM({})
+         FROM triples t JOIN triples m ON m.subject = t.subject
+         WHERE m.predicate = ?1 AND m.retracted = 0
+         GROUP BY m.object
+         ORDER BY 4 DESC, m.object",
+        ROW_BYTES
+    ))?;
+    let usage = stmt
+        .query_map([query::IN_WORKSPACE], |row| Ok(StorageUsage {
+            name: row.get(0)?,
+            rows: row.get(1)?,
+            active_rows: row.get(2)?,
+            estimated_bytes: row.get(3)?,
+            quota_bytes: None,
+        }))?
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(usage)
+}
+
+/// Soft quota of an origin, in estimated bytes (None: unlimited)
+pub fn get_quota(conn: &Connection, origin: &str) -> Result<Option<u64>, DbError> {
+    let value: Option<String> = conn.query_row(
+        "SELECT value FROM metadata WHERE key = ?",
+        [format!("{}{}", QUOTA_KEY_PREFIX, origin)],
+        |row| row.get(0),
+    ).optional()?;
+    Ok(value.and_then(|v| v.parse().ok()))
+}
+
+/// Set (or remove, with None) the soft quota of an origin
+pub fn set_quota(conn: &Connection, origin: &str, quota_bytes: Option<u64>) -> Result<(), DbError> {
+    let key = format!("{}{}", QUOTA_KEY_PREFIX, origin);
+    match quota_bytes {
+        Some(quota) => conn.execute(
+            "INSERT INTO metadata (key, value, updated_at) VALUES (?1, ?2, ?3)
+             ON CONFLICT(key) DO UPDATE SET value = ?2, updated_at = ?3",
+            rusqlite::params![key, quota.to_string(), super::store::now_millis()],
+        )?,
+        None => conn.execute("DELETE FROM metadata WHERE key = ?", [key])?,
+    };
+    Ok(())
+}
+
+/// Usage of the origin that wrote a transaction, when it is over its quota
+///
+/// Cheap when the origin has no quota, so it can run after every write.
+pub fn check_quota(conn: &Connection, tx: i64) -> Result<Option<StorageUsage>, DbError> {
+    let origin: Option<String> = conn.query_row(
+        "SELECT origin FROM transactions WHERE tx = ?",
+        [tx],
+        |row| row.get(0),
+    ).optional()?;
+    let Some(origin) = origin else { return Ok(None) };
+    let Some(quota) = get_quota(conn, &origin)? else { return Ok(None) };
+
+    let (rows, active_rows, estimated_bytes): (u64, u64, u64) = conn.query_row(
+        &format!(
+            "SELECT COUNT(*), COALESCE(SUM(t.retracted = 0), 0), COALESCE(SUM({}), 0)
+             FROM triples t JOIN origins o ON o.id = t.origin_id
+             WHERE o.name = ?",
+            ROW_BYTES
+        ),
+        [&origin],
+        |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
+    )?;
+    let usage = StorageUsage { name: origin, rows, active_rows, estimated_bytes, quota_bytes: Some(quota) };
+    Ok(usage.over_quota().then_some(usage))
+}
+
+/// Current instances of a class (rdf:type, asserted only)
+#[derive(Debug, Clone, PartialEq)]
+pub struct ClassCount {
+    pub class: String,
+    pub instances: u64,
+}
+
+/// Current use of a property
+#[derive(Debug, Clone, PartialEq)]
+pub struct PropertyCount {
+    pub property: String,
+    pub uses: u64,            // Statements
+    pub subjects: u64,        // Distinct subjects
+    pub distinct_values: u64, // Distinct objects (an IRI, or a literal value)
+}
+
+/// Instances of each class, most first: classes used by rdf:type, and
+/// declared classes (owl:Class) without instances
+pub fn class_counts(conn: &Connection) -> Result<Vec<ClassCount>, DbError> {
+    let mut stmt = conn.prepare(
+        "WITH type AS (SELECT id FROM terms WHERE value = ?1),
+              classes(id) AS (
+                SELECT object_id FROM quads
+                WHERE predicate_id = (SELECT id FROM type) AND object_type = 'iri' AND retracted = 0
+                UNION
+                SELECT subject_id FROM quads
+                WHERE predicate_id = (SELECT id FROM type) AND retracted = 0
+                  AND object_id = (SELECT id FROM terms WHERE value = ?2)
+              )
+         SELECT c.value, COUNT(DISTINCT i.subject_id)
+         FROM classes k
+         JOIN terms c ON c.id = k.id
+         LEFT JOIN quads i ON i.object_id = k.id AND i.predicate_id = (SELECT id FROM type) AND i.retracted = 0
+         WHERE c.value NOT LIKE '\\_:%' ESCAPE '\\'
+         GROUP BY k.id
+         ORDER BY 2 DESC, 1",
+    )?;
+    let counts = stmt
+        .query_map(["rdf:type", "owl:Class"], |row| Ok(ClassCount {
+            class: row.get(0)?,
+            instances: row.get(1)?,
+        }))?
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(counts)
+}
+
+/// Uses of each property, most first
+pub fn property_counts(conn: &Connection) -> Result<Vec<PropertyCount>, DbError> {
+    let mut stmt = conn.prepare(
+        "SELECT p.value, COUNT(*), COUNT(DISTINCT q.subject_id), COUNT(DISTINCT COALESCE(q.object_id, q.object_value))
+         FROM quads q JOIN terms p ON p.id = q.predicate_id
+         WHERE q.retracted = 0
+         GROUP BY q.predicate_id
+         ORDER BY 2 DESC, 1",
+    )?;
+    let counts = stmt
+        .query_map([], |row| Ok(PropertyCount {
+            property: row.get(0)?,
+            uses: row.get(1)?,
+            subjects: row.get(2)?,
+            distinct_values: row.get(3)?,
+        }))?
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(counts)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::eavto::test_helpers::setup_test_db;
+
+    #[test]
+    fn test_get_stats_empty_db() {
+        let conn = setup_test_db();
+        let stats = get_stats(&conn).expect("Failed to get stats");
+
+        assert_eq!(stats.total_facts, 0);
+        assert_eq!(stats.active_facts, 0);
+        assert_eq!(stats.total_transactions, 0);
+        assert_eq!(stats.entities_count, 0);
+        assert_eq!(stats.ontology_imported, false);
+    }
+
+    #[test]
+    fn test_get_stats_with_data() {
+        let mut conn = setup_test_db();
+
+        // Insert test transaction
+        conn.execute(
+            "INSERT INTO transactions (origin, created_at) VALUES ('test', 1000)",
+            [],
+        )
+        .unwrap();
+        let tx_id = conn.last_insert_rowid();
+
+        // Insert test triples
+        conn.execute(
+            "INSERT INTO triples (subject, predicate, object, object_type, tx, origin_id, created_at, retracted)
+             VALUES ('foundation:TestClass', 'rdf:type', 'owl:Class', 'iri', ?, 1, 1000, 0)",
+            [tx_id],
+        )
+        .unwrap();
+
+        conn.execute(
+            "INSERT INTO triples (subject, predicate, object, object_type, tx, origin_id, created_at, retracted)
+             VALUES ('foundation:TestClass', 'rdfs:label', 'owl:Class', 'iri', ?, 1, 1000, 1)",
+            [tx_id],
+        )
+        .unwrap();
+
+        let stats = get_stats(&conn).expect("Failed to get stats");
+
+        assert_eq!(stats.total_facts, 2);
+        assert_eq!(stats.active_facts, 1); // Only one non-retracted
+        assert_eq!(stats.total_transactions, 1);
+        assert_eq!(stats.entities_count, 1);
+    }
+
+    #[test]
+    fn test_origin_and_workspace_usage() {
+        use crate::eavto::{store, Object, Triple};
+
+        let mut conn = setup_test_db();
+        let label = |value: &str| Object::Literal { value: value.to_string(), datatype: None, language: None };
+        store::assert_triples(&mut conn, &[
+            Triple::new("foundation:Note1", "rdfs:label", label("Groceries")),
+            Triple::new("foundation:Note1", query::IN_WORKSPACE, Object::Iri("foundation:Workspace_Home".to_string())),
+        ], "user-edit").unwrap();
+        let tx = store::assert_triples(&mut conn, &(0..20)
+            .map(|i| Triple::new(format!("foundation:Track{}", i), "rdfs:label", label("A song with a long title")))
+            .collect::<Vec<_>>(), "collector:music").unwrap();
+
+        let usage = origin_usage(&conn).unwrap();
+        assert_eq!(usage[0].name, "collector:music");
+        assert_eq!((usage[0].rows, usage[0].active_rows), (20, 20));
+        assert!(usage[0].estimated_bytes > usage[1].estimated_bytes);
+
+        let workspaces = workspace_usage(&conn).unwrap();
+        assert_eq!(workspaces.len(), 1);
+        assert_eq!((workspaces[0].name.as_str(), workspaces[0].rows), ("foundation:Workspace_Home", 2));
+
+        // Soft quotas
+        assert!(check_quota(&conn, tx).unwrap().is_none());
+        set_quota(&conn, "collector:music", Some(1_000)).unwrap();
+        let over = check_quota(&conn, tx).unwrap().unwrap();
+        assert_eq!(over.name, "collector:music");
+        assert!(origin_usage(&conn).unwrap()[0].over_quota());
+
+        set_quota(&conn, "collector:music", Some(1_000_000)).unwrap();
+        assert!(check_quota(&conn, tx).unwrap().is_none());
+        set_quota(&conn, "collector:music", None).unwrap();
+        assert_eq!(get_quota(&conn, "collector:music").unwrap(), None);
+    }
+
+    #[test]
+    fn test_class_and_property_counts() {
+        use crate::eavto::{store, Object, Triple};
+
+        let mut conn = setup_test_db();
+        let iri = |value: &str| Object::Iri(value.to_string());
+        store::assert_triples(&mut conn, &[
+            Triple::new("foundation:Person", "rdf:type", iri("owl:Class")),
+            Triple::new("foundation:Place", "rdf:type", iri("owl:Class")),
+            Triple::new("_:union", "rdf:type", iri("owl:Class")),
+            Triple::new("foundation:alice", "rdf:type", iri("foundation:Person")),
+            Triple::new("foundation:bob", "rdf:type", iri("foundation:Person")),
+            Triple::new("foundation:alice", "foundation:age", Object::Integer(30)),
+            Triple::new("foundation:bob", "foundation:age", Object::Integer(30)),
+            Triple::new("foundation:carol", "foundation:age", Object::Integer(41)),
+        ], "test").unwrap();
+        store::retract_triples(&mut conn, &[
+            Triple::new("foundation:carol", "foundation:age", Object::Integer(41)),
+        ], "test").unwrap();
+
+        let classes = class_counts(&conn).unwrap();
+        let count = |class: &str| classes.iter().find(|c| c.class == class).map(|c| c.instances);
+        assert_eq!(classes[0], ClassCount { class: "owl:Class".into(), instances: 3 });
+        assert_eq!(count("foundation:Person"), Some(2));
+        assert_eq!(count("foundation:Place"), Some(0));
+        assert_eq!(count("_:union"), None);
+
+        let properties = property_counts(&conn).unwrap();
+        let age = properties.iter().find(|p| p.property == "foundation:age").unwrap();
+        assert_eq!((age.uses, age.subjects, age.distinct_values), (2, 2, 1));
+    }
+}
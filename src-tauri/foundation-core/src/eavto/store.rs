@@ -0,0 +1,1025 @@
+/// EVTO Store Functions
+///
+/// Functions for asserting and retracting triples (append-only, immutable)
+
+use rusqlite::Connection;
+use std::collections::BTreeMap;
+use std::sync::Mutex;
+use super::triple_type::Triple;
+use super::object_type::Object;
+use super::xsd_type::TypedValue;
+use super::transaction_type::Annotation;
+use super::{blob, connection, query, statement, terms};
+
+type Result<T> = std::result::Result<T, Box<dyn std::error::Error>>;
+
+/// A typed literal whose value doesn't parse as its datatype (e.g. "noon"
+/// as xsd:time): the write is refused rather than left without its typed
+/// column
+#[derive(Debug, Clone, PartialEq)]
+pub struct InvalidLiteral {
+    pub subject: String,
+    pub predicate: String,
+    pub value: String,
+    pub datatype: String,
+}
+
+impl std::fmt::Display for InvalidLiteral {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Invalid {} literal '{}' for {} {}", self.datatype, self.value, self.subject, self.predicate)
+    }
+}
+
+impl std::error::Error for InvalidLiteral {}
+
+/// Time buffered assertions of an origin are coalesced over
+pub const BUFFER_INTERVAL_MS: i64 = 5_000;
+
+/// Triples retracted at once from which free pages are reclaimed
+pub const LARGE_RETRACTION: usize = 1000;
+
+/// Assertions waiting to be written, by origin
+static BUFFER: Mutex<BTreeMap<String, Buffered>> = Mutex::new(BTreeMap::new());
+
+/// Triples of an origin waiting in the buffer, and since when
+struct Buffered {
+    triples: Vec<Triple>,
+    since: i64,
+}
+
+/// Assert triples (add new facts to the store)
+///
+/// The transaction is signed with the local identity, if one is set (see
+/// identity::set_local).
+///
+/// Returns the transaction ID of the assertion
+pub fn assert_triples(
+    conn: &mut Connection,
+    triples: &[Triple],
+    origin: &str,
+) -> Result<i64> {
+    let mut assertion = Assertion::begin(conn, origin)?;
+    for triple in triples {
+        assertion.insert(triple)?;
+    }
+    assertion.commit()
+}
+
+/// An assertion being written, for triples streamed in as they are read
+/// (e.g., an import): one transaction, committed once every triple is in
+///
+/// It runs in a savepoint, so it can be nested (e.g., in a dry run);
+/// dropping it without committing writes nothing.
+pub struct Assertion<'conn> {
+    tx: rusqlite::Savepoint<'conn>,
+    tx_id: i64,
+    origin_id: i64,
+    created_at: i64,
+    limit: Option<usize>,
+}
+
+impl<'conn> Assertion<'conn> {
+    /// Open the transaction of an origin (created if new)
+    pub fn begin(conn: &'conn mut Connection, origin: &str) -> Result<Self> {
+        let tx = conn.savepoint()?;
+
+        // Create transaction record (AUTOINCREMENT generates tx_id)
+        let created_at = now_millis();
+        tx.execute(
+            "INSERT INTO transactions (origin, created_at) VALUES (?, ?)",
+            (origin, created_at),
+        )?;
+        let tx_id = tx.last_insert_rowid();
+
+        let origin_id = get_or_create_origin(&tx, origin)?;
+        let limit = blob::literal_limit(&tx)?;
+        Ok(Self { tx, tx_id, origin_id, created_at, limit })
+    }
+
+    /// ID of the origin asserting
+    pub fn origin_id(&self) -> i64 {
+        self.origin_id
+    }
+
+    /// When the triples are asserted (Unix milliseconds)
+    pub fn created_at(&self) -> i64 {
+        self.created_at
+    }
+
+    /// Insert a triple (a large literal goes to the blob store)
+    pub fn insert(&mut self, triple: &Triple) -> Result<()> {
+        insert_triple(&self.tx, &*blob::spill(&self.tx, triple, self.limit)?, self.tx_id, self.origin_id, self.created_at)
+    }
+
+    /// Sign and commit the transaction; returns its ID
+    pub fn commit(self) -> Result<i64> {
+        let Self { tx, tx_id, .. } = self;
+
+        // Before commit, validate numeric literals have typed columns
+        {
+            let mut stmt = tx.prepare(
+                "SELECT subject, predicate, object_datatype, object_number, object_integer
+                 FROM triples
+                 WHERE tx = ?
+                 AND (
+                   (object_datatype IN ('xsd:decimal', 'xsd:double', 'xsd:float') AND object_number IS NULL) OR
+                   (object_datatype IN ('xsd:integer', 'xsd:int', 'xsd:long') AND object_integer IS NULL)
+                 )"
+            )?;
+
+            // subject, predicate, datatype, object_number, object_integer
+            type Untyped = (String, String, String, Option<f64>, Option<i64>);
+            let bad_triples: Vec<Untyped> = stmt.query_map([tx_id], |row| {
+                Ok((
+                    row.get(0)?,
+                    row.get(1)?,
+                    row.get(2)?,
+                    row.get(3)?,
+                    row.get(4)?,
+                ))
+            })?.collect::<std::result::Result<Vec<_>, _>>()?;
+
+            if !bad_triples.is_empty() {
+                eprintln!("\n⚠️  FOUND {} TRIPLES WITH NUMERIC DATATYPE BUT NO TYPED COLUMN:", bad_triples.len());
+                for (idx, (subj, pred, dt, num, int)) in bad_triples.iter().enumerate().take(5) {
+                    eprintln!("  #{}: {} {} (datatype={}, object_number={:?}, object_integer={:?})",
+                             idx + 1, subj, pred, dt, num, int);
+                }
+                if bad_triples.len() > 5 {
+                    eprintln!("  ... and {} more", bad_triples.len() - 5);
+                }
+                eprintln!("  Run storage__backfill_typed_columns to repair them");
+                eprintln!();
+            }
+        } // stmt is dropped here
+
+        // Sign what was asserted with the local identity, when there is one
+        if let Some(identity) = crate::identity::local() {
+            crate::identity::sign_transaction(&tx, tx_id, identity)?;
+        }
+
+        tx.commit()?;
+        Ok(tx_id)
+    }
+}
+
+/// Retract triples (mark as retracted, don't delete)
+///
+/// Each triple retracts the current rows matching its subject, predicate and
+/// object (value, datatype and language), so other values of a multi-valued
+/// property are kept. See `retract_by_pattern` to retract every value.
+/// The triple's graph isn't matched: a fact is retracted from every graph.
+///
+/// Returns the transaction ID of the retraction
+pub fn retract_triples(
+    conn: &mut Connection,
+    triples: &[Triple],
+    origin: &str,
+) -> Result<i64> {
+    let tx = conn.savepoint()?;
+
+    // Create transaction record
+    let now = now_millis();
+    tx.execute(
+        "INSERT INTO transactions (origin, created_at) VALUES (?, ?)",
+        (origin, now),
+    )?;
+
+    let tx_id = tx.last_insert_rowid();
+    get_or_create_origin(&tx, origin)?;
+
+    // Mark matching triples as retracted
+    let limit = blob::literal_limit(&tx)?;
+    let mut retracted = 0;
+    for triple in triples {
+        retracted += retract_by_value(&tx, triple, tx_id, limit)?;
+    }
+
+    tx.commit()?;
+    reclaim_after(conn, retracted);
+    Ok(tx_id)
+}
+
+/// Retract every current value of (subject, predicate) pairs, whatever the
+/// object
+///
+/// Returns the transaction ID of the retraction
+pub fn retract_by_pattern(
+    conn: &mut Connection,
+    patterns: &[(&str, &str)],
+    origin: &str,
+) -> Result<i64> {
+    let tx = conn.savepoint()?;
+
+    tx.execute(
+        "INSERT INTO transactions (origin, created_at) VALUES (?, ?)",
+        (origin, now_millis()),
+    )?;
+    let tx_id = tx.last_insert_rowid();
+    get_or_create_origin(&tx, origin)?;
+
+    let mut retracted = 0;
+    for (subject, predicate) in patterns {
+        retracted += tx.prepare_cached(
+            "UPDATE quads
+             SET retracted = 1, retracted_tx = ?
+             WHERE subject_id = (SELECT id FROM terms WHERE value = ?)
+               AND predicate_id = (SELECT id FROM terms WHERE value = ?)
+               AND retracted = 0",
+        )?.execute((tx_id, subject, predicate))?;
+    }
+
+    tx.commit()?;
+    reclaim_after(conn, retracted);
+    Ok(tx_id)
+}
+
+/// Retract every current triple of an origin (forget an import)
+///
+/// The retraction is recorded under the origin itself, so the history shows
+/// who lost the triples. Returns the transaction ID (None if the origin held
+/// no current triples).
+pub fn retract_origin(conn: &mut Connection, origin_id: i64) -> Result<Option<i64>> {
+    let origin = query::get_origin_name(conn, origin_id)?
+        .ok_or_else(|| format!("Origin {} not found", origin_id))?;
+    let tx = conn.savepoint()?;
+
+    tx.execute(
+        "INSERT INTO transactions (origin, created_at) VALUES (?, ?)",
+        (&origin, now_millis()),
+    )?;
+    let tx_id = tx.last_insert_rowid();
+
+    let retracted = tx.execute(
+        "UPDATE quads
+         SET retracted = 1, retracted_tx = ?
+         WHERE origin_id = ? AND retracted = 0",
+        (tx_id, origin_id),
+    )?;
+    if retracted == 0 {
+        return Ok(None); // Savepoint rolls back on drop
+    }
+
+    tx.commit()?;
+    reclaim_after(conn, retracted);
+    Ok(Some(tx_id))
+}
+
+/// Reclaim the free pages a large retraction left behind (retracted rows
+/// leave the indexes of current triples); failing to is only logged
+fn reclaim_after(conn: &Connection, retracted: usize) {
+    if retracted >= LARGE_RETRACTION {
+        if let Err(e) = connection::reclaim_free_pages(conn) {
+            println!("⚠️  Incremental vacuum: {:?}", e);
+        }
+    }
+}
+
+/// Apply a set of additions and exact retractions in a single transaction
+///
+/// Retractions match subject, predicate and object.
+/// When a retracted triple carries an origin_id (e.g., it was read from the
+/// store), only rows from that origin are retracted.
+///
+/// Runs in a savepoint, so it can be nested in an enclosing transaction
+/// (e.g., a sandbox overlay that is rolled back afterwards).
+///
+/// Returns the transaction ID of the change set
+pub fn apply_changes(
+    conn: &mut Connection,
+    additions: &[Triple],
+    retractions: &[Triple],
+    origin: &str,
+) -> Result<i64> {
+    apply_annotated_changes(conn, additions, retractions, origin, &Annotation::default())
+}
+
+/// Apply a change set like `apply_changes`, recording a message and tags
+/// on its transaction
+pub fn apply_annotated_changes(
+    conn: &mut Connection,
+    additions: &[Triple],
+    retractions: &[Triple],
+    origin: &str,
+    annotation: &Annotation,
+) -> Result<i64> {
+    let tx = conn.savepoint()?;
+
+    let now = now_millis();
+    let tags = (!annotation.tags.is_empty()).then(|| serde_json::to_string(&annotation.tags)).transpose()?;
+    tx.execute(
+        "INSERT INTO transactions (origin, created_at, message, tags) VALUES (?, ?, ?, ?)",
+        (origin, now, &annotation.message, tags),
+    )?;
+
+    let tx_id = tx.last_insert_rowid();
+    let origin_id = get_or_create_origin(&tx, origin)?;
+
+    let limit = blob::literal_limit(&tx)?;
+    let mut retracted = 0;
+    for triple in retractions {
+        retracted += retract_by_value(&tx, triple, tx_id, limit)?;
+    }
+
+    for triple in additions {
+        insert_triple(&tx, &*blob::spill(&tx, triple, limit)?, tx_id, origin_id, now)?;
+    }
+
+    tx.commit()?;
+    reclaim_after(conn, retracted);
+    Ok(tx_id)
+}
+
+/// Undo a transaction with a compensating one: the triples it asserted are
+/// retracted, and the triples it retracted are asserted again (keeping
+/// their origins)
+///
+/// A retracted triple is not asserted again if it is current anyway (e.g.,
+/// asserted again later). Retractions made before schema version 8 were not
+/// recorded with their transaction and can't be undone.
+///
+/// Returns the transaction ID of the undo
+pub fn undo_transaction(conn: &mut Connection, tx_id: i64, origin: &str) -> Result<i64> {
+    let exists: bool = conn.query_row("SELECT EXISTS(SELECT 1 FROM transactions WHERE tx = ?)", [tx_id], |row| row.get(0))?;
+    if !exists {
+        return Err(format!("Transaction {} not found", tx_id).into());
+    }
+    let pending: bool = conn.query_row(
+        "SELECT EXISTS(SELECT 1 FROM triples WHERE (tx = ?1 AND retracted = 0) OR retracted_tx = ?1)",
+        [tx_id],
+        |row| row.get(0),
+    )?;
+    if !pending {
+        return Err(format!("Transaction {} has nothing to undo", tx_id).into());
+    }
+
+    let tx = conn.savepoint()?;
+
+    let now = now_millis();
+    tx.execute(
+        "INSERT INTO transactions (origin, created_at, message) VALUES (?, ?, ?)",
+        (origin, now, format!("Undo transaction {}", tx_id)),
+    )?;
+    let undo_tx = tx.last_insert_rowid();
+    get_or_create_origin(&tx, origin)?;
+
+    tx.execute(
+        "UPDATE quads SET retracted = 1, retracted_tx = ?2 WHERE tx = ?1 AND retracted = 0",
+        [tx_id, undo_tx],
+    )?;
+
+    tx.execute(
+        "INSERT INTO quads (subject_id, predicate_id, object_id, object_value, object_datatype, object_language,
+                            object_type, object_number, object_integer, object_datetime, object_boolean,
+                            graph, tx, origin_id, retracted, created_at)
+         SELECT subject_id, predicate_id, object_id, object_value, object_datatype, object_language,
+                object_type, object_number, object_integer, object_datetime, object_boolean,
+                graph, ?2, origin_id, 0, ?3
+         FROM quads t
+         WHERE retracted_tx = ?1
+           AND NOT EXISTS (
+             SELECT 1 FROM quads c
+             WHERE c.subject_id = t.subject_id AND c.predicate_id = t.predicate_id AND c.object_type = t.object_type
+               AND COALESCE(c.object_id, c.object_value) = COALESCE(t.object_id, t.object_value)
+               AND c.graph = t.graph
+               AND c.retracted = 0
+           )
+         GROUP BY subject_id, predicate_id, object_type, COALESCE(object_id, object_value), graph",
+        [tx_id, undo_tx, now],
+    )?;
+
+    tx.commit()?;
+    Ok(undo_tx)
+}
+
+/// Assert triples through the write buffer
+///
+/// For high-frequency collectors (telemetry, file watchers): their small
+/// writes are held and written as one transaction per origin every
+/// BUFFER_INTERVAL_MS, instead of thousands of tiny transactions bloating
+/// the log and the WAL. Buffered triples aren't visible until written; the
+/// executor writes what is due in the background, and flush_buffered
+/// writes everything at once (e.g. before closing).
+///
+/// Returns the transaction ID if this call wrote the origin's buffer.
+pub fn assert_buffered(conn: &mut Connection, triples: &[Triple], origin: &str) -> Result<Option<i64>> {
+    assert_buffered_at(conn, triples, origin, now_millis())
+}
+
+fn assert_buffered_at(conn: &mut Connection, triples: &[Triple], origin: &str, now: i64) -> Result<Option<i64>> {
+    {
+        let mut buffer = BUFFER.lock().unwrap_or_else(|e| e.into_inner());
+        let buffered = buffer.entry(origin.to_string())
+            .or_insert_with(|| Buffered { triples: Vec::new(), since: now });
+        buffered.triples.extend_from_slice(triples);
+        if now - buffered.since < BUFFER_INTERVAL_MS {
+            return Ok(None);
+        }
+    }
+    Ok(flush(conn, |o, _| o == origin)?.into_iter().next())
+}
+
+/// Whether assertions are waiting in the write buffer
+pub fn has_buffered() -> bool {
+    !BUFFER.lock().unwrap_or_else(|e| e.into_inner()).is_empty()
+}
+
+/// Write the buffered assertions whose interval is over
+///
+/// Returns the transaction IDs written, one per origin.
+pub fn flush_due(conn: &mut Connection) -> Result<Vec<i64>> {
+    let now = now_millis();
+    flush(conn, |_, since| now - since >= BUFFER_INTERVAL_MS)
+}
+
+/// Write every buffered assertion now
+///
+/// Returns the transaction IDs written, one per origin.
+pub fn flush_buffered(conn: &mut Connection) -> Result<Vec<i64>> {
+    flush(conn, |_, _| true)
+}
+
+/// Write the buffers of the origins selected by origin and start time
+///
+/// A buffer that fails to write is dropped, and the first error returned
+/// once the others are written, so one bad triple can't hold writes back
+/// forever.
+fn flush(conn: &mut Connection, select: impl Fn(&str, i64) -> bool) -> Result<Vec<i64>> {
+    let due: Vec<(String, Buffered)> = {
+        let mut buffer = BUFFER.lock().unwrap_or_else(|e| e.into_inner());
+        let origins: Vec<String> = buffer.iter()
+            .filter(|(origin, buffered)| select(origin, buffered.since))
+            .map(|(origin, _)| origin.clone())
+            .collect();
+        origins.into_iter()
+            .filter_map(|origin| buffer.remove(&origin).map(|buffered| (origin, buffered)))
+            .collect()
+    };
+
+    let mut written = Vec::new();
+    let mut failure = None;
+    for (origin, buffered) in due {
+        match assert_triples(conn, &buffered.triples, &origin) {
+            Ok(tx) => written.push(tx),
+            Err(e) => failure = failure.or(Some(e)),
+        }
+    }
+    match failure {
+        Some(e) => Err(e),
+        None => Ok(written),
+    }
+}
+
+/// Set (or, with None, remove) a database metadata value
+pub fn set_metadata(conn: &Connection, key: &str, value: Option<&str>) -> Result<()> {
+    match value {
+        Some(value) => conn.execute(
+            "INSERT INTO metadata (key, value, updated_at) VALUES (?1, ?2, ?3)
+             ON CONFLICT(key) DO UPDATE SET value = ?2, updated_at = ?3",
+            rusqlite::params![key, value, now_millis()],
+        )?,
+        None => conn.execute("DELETE FROM metadata WHERE key = ?", [key])?,
+    };
+    Ok(())
+}
+
+/// Retract the current rows of a triple, matching subject, predicate and
+/// object (and the origin, when the triple carries one)
+///
+/// Returns the number of rows retracted
+fn retract_by_value(tx: &Connection, triple: &Triple, tx_id: i64, limit: Option<usize>) -> Result<usize> {
+    let object = blob::stored_object(&triple.object, limit);
+    let object_key = match object.as_ref() {
+        Object::Iri(iri) | Object::Blank(iri) => iri.clone(),
+        other => other.as_literal().unwrap_or_default(),
+    };
+    // Typed values match the datatypes stored in the same typed column
+    let datatypes = match object.as_ref() {
+        Object::Integer(_) => ["xsd:integer", "xsd:int", "xsd:long"],
+        Object::Number(_) => ["xsd:decimal", "xsd:double", "xsd:float"],
+        other => [other.datatype().unwrap_or_default(); 3],
+    };
+    let language = match object.as_ref() {
+        Object::Literal { language, .. } => language.as_deref(),
+        _ => None,
+    };
+
+    let retracted = tx.prepare_cached(
+        "UPDATE quads
+         SET retracted = 1, retracted_tx = ?6
+         WHERE subject_id = (SELECT id FROM terms WHERE value = ?1)
+           AND predicate_id = (SELECT id FROM terms WHERE value = ?2)
+           AND object_type = ?3
+           AND CASE object_type
+                 WHEN 'literal' THEN object_value = ?4
+                 ELSE object_id = (SELECT id FROM terms WHERE value = ?4)
+               END
+           AND COALESCE(object_datatype, '') IN (?7, ?8, ?9)
+           AND object_language IS ?10
+           AND (?5 = 0 OR origin_id = ?5)
+           AND retracted = 0",
+    )?.execute(
+        rusqlite::params![
+            &triple.subject,
+            &triple.predicate,
+            object.object_type(),
+            object_key,
+            triple.origin_id,
+            tx_id,
+            datatypes[0],
+            datatypes[1],
+            datatypes[2],
+            language,
+        ],
+    )?;
+    Ok(retracted)
+}
+
+/// Insert a single triple into the database
+fn insert_triple(
+    tx: &Connection,
+    triple: &Triple,
+    tx_id: i64,
+    origin_id: i64,
+    created_at: i64,
+) -> Result<()> {
+    // Convert object to SQL columns
+    // Need to compute everything together to ensure datatype matches typed columns
+    let int_str;
+    let num_str;
+    let bool_str;
+    let dt_str;
+    let quoted_str;
+
+    let (object, object_value, object_datatype, object_language, object_number, object_integer, object_datetime, object_boolean) = match &triple.object {
+        Object::Iri(iri) => (Some(iri.as_str()), None, None, None, None, None, None, None),
+        Object::Blank(blank) => (Some(blank.as_str()), None, None, None, None, None, None, None),
+        Object::Triple(quoted) => {
+            quoted_str = quoted.term();
+            (Some(quoted_str.as_str()), None, None, None, None, None, None, None)
+        }
+
+        Object::Integer(i) => {
+            int_str = i.to_string();
+            (None, Some(int_str.as_str()), Some("xsd:integer"), None, None, Some(*i), None, None)
+        }
+        Object::Number(n) => {
+            num_str = n.to_string();
+            (None, Some(num_str.as_str()), Some("xsd:decimal"), None, Some(*n), None, None, None)
+        }
+        Object::Boolean(b) => {
+            bool_str = b.to_string();
+            (None, Some(bool_str.as_str()), Some("xsd:boolean"), None, None, None, None, Some(if *b { 1 } else { 0 }))
+        }
+        Object::DateTime(dt) => {
+            dt_str = dt.to_string();
+            (None, Some(dt_str.as_str()), Some("xsd:dateTime"), None, None, None, Some(*dt), None)
+        }
+
+        Object::Literal { value, datatype, language } => {
+            // Parse typed literals and populate typed columns; a value that
+            // doesn't parse as its datatype is refused, never written
+            let typed = match datatype.as_deref() {
+                Some(dt) if TypedValue::has_column(dt) => Some(TypedValue::parse(value, dt).ok_or_else(|| InvalidLiteral {
+                    subject: triple.subject.clone(),
+                    predicate: triple.predicate.clone(),
+                    value: value.clone(),
+                    datatype: dt.to_string(),
+                })?),
+                _ => None,
+            };
+            let (number, integer, datetime, boolean) = match typed {
+                Some(TypedValue::Number(n)) => (Some(n), None, None, None),
+                Some(TypedValue::Integer(i)) => (None, Some(i), None, None),
+                Some(TypedValue::DateTime(ms)) => (None, None, Some(ms), None),
+                Some(TypedValue::Boolean(b)) => (None, None, None, Some(if b { 1 } else { 0 })),
+                None => (None, None, None, None),
+            };
+            (None, Some(value.as_str()), datatype.as_deref(), language.as_deref(), number, integer, datetime, boolean)
+        }
+    };
+
+    let object_type = triple.object.object_type();
+    let subject_id = statement::intern(tx, &triple.subject)?;
+    let predicate_id = terms::intern(tx, &triple.predicate)?;
+    let object_id = object.map(|object| statement::intern(tx, object)).transpose()?;
+
+    // Cached, so bulk inserts (ontology imports) parse the statement once
+    let mut stmt = tx.prepare_cached(
+        "INSERT INTO quads (
+            subject_id, predicate_id, object_id, object_value, object_datatype, object_language,
+            object_type, object_number, object_integer, object_datetime, object_boolean,
+            graph, tx, origin_id, retracted, created_at
+        ) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, 0, ?)",
+    )?;
+    let result = stmt.execute(
+        rusqlite::params![
+            subject_id,
+            predicate_id,
+            object_id,
+            object_value,
+            object_datatype,
+            object_language,
+            object_type,
+            object_number,
+            object_integer,
+            object_datetime,
+            object_boolean,
+            &triple.graph,
+            tx_id,
+            origin_id,
+            created_at,
+        ],
+    );
+
+    if let Err(e) = result {
+        eprintln!("\n❌ INSERT FAILED:");
+        eprintln!("   Subject: {}", triple.subject);
+        eprintln!("   Predicate: {}", triple.predicate);
+        eprintln!("   Object: {:?}", triple.object);
+        eprintln!("   object_datatype: {:?}", object_datatype);
+        eprintln!("   object_number: {:?}", object_number);
+        eprintln!("   object_integer: {:?}", object_integer);
+        eprintln!("   object_boolean: {:?}", object_boolean);
+        eprintln!("   Error: {}\n", e);
+        return Err(e.into());
+    }
+
+    Ok(())
+}
+
+/// Get or create origin ID
+pub(crate) fn get_or_create_origin(tx: &Connection, origin: &str) -> rusqlite::Result<i64> {
+    // Try to get existing origin
+    match tx.query_row(
+        "SELECT id FROM origins WHERE name = ?",
+        [origin],
+        |row| row.get(0),
+    ) {
+        Ok(id) => Ok(id),
+        Err(rusqlite::Error::QueryReturnedNoRows) => {
+            // Create new origin
+            tx.execute("INSERT INTO origins (name) VALUES (?)", [origin])?;
+            Ok(tx.last_insert_rowid())
+        }
+        Err(e) => Err(e),
+    }
+}
+
+/// Get current Unix time in milliseconds
+pub fn now_millis() -> i64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap()
+        .as_millis() as i64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::eavto::test_helpers::{setup_test_db, create_test_triples, assert_triple_exists, get_active_triple_count};
+
+    #[test]
+    fn test_assert_buffered_coalesces_writes() {
+        let mut conn = setup_test_db();
+        let triples = create_test_triples();
+        let origin = "test:buffered";
+
+        assert_eq!(assert_buffered_at(&mut conn, &triples[..1], origin, 1_000).unwrap(), None);
+        assert_eq!(assert_buffered_at(&mut conn, &triples[1..2], origin, 2_000).unwrap(), None);
+        assert_eq!(get_active_triple_count(&conn), 0);
+
+        let tx = assert_buffered_at(&mut conn, &triples[2..], origin, 1_000 + BUFFER_INTERVAL_MS)
+            .unwrap()
+            .expect("the interval is over");
+        assert_eq!(query::count_by_transaction(&conn, tx).unwrap(), 3);
+        assert_eq!(query::get_latest_tx(&conn).unwrap(), tx);
+
+        // The next write starts a new interval
+        assert_eq!(assert_buffered_at(&mut conn, &triples[..1], origin, 1_000 + BUFFER_INTERVAL_MS + 1).unwrap(), None);
+        assert_eq!(flush(&mut conn, |o, _| o == origin).unwrap().len(), 1);
+        assert_eq!(get_active_triple_count(&conn), 4);
+    }
+
+    #[test]
+    fn test_assert_triples_basic() {
+        let mut conn = setup_test_db();
+        let triples = create_test_triples();
+
+        let tx_id = assert_triples(&mut conn, &triples, "test_origin")
+            .expect("Failed to assert triples");
+
+        assert!(tx_id > 0);
+        assert_eq!(get_active_triple_count(&conn), 3);
+    }
+
+    #[test]
+    fn test_assert_triples_creates_transaction() {
+        let mut conn = setup_test_db();
+        let triples = create_test_triples();
+
+        let tx_id = assert_triples(&mut conn, &triples, "test_origin").unwrap();
+
+        let tx_count: i64 = conn
+            .query_row("SELECT COUNT(*) FROM transactions WHERE tx = ?", [tx_id], |row| row.get(0))
+            .unwrap();
+
+        assert_eq!(tx_count, 1);
+    }
+
+    #[test]
+    fn test_assert_triples_creates_origin() {
+        let mut conn = setup_test_db();
+        let triples = create_test_triples();
+
+        assert_triples(&mut conn, &triples, "new_origin").unwrap();
+
+        let origin_exists: bool = conn
+            .query_row(
+                "SELECT COUNT(*) > 0 FROM origins WHERE name = 'new_origin'",
+                [],
+                |row| row.get(0),
+            )
+            .unwrap();
+
+        assert!(origin_exists);
+    }
+
+    #[test]
+    fn test_assert_triples_with_different_object_types() {
+        let mut conn = setup_test_db();
+
+        let triples = vec![
+            Triple {
+                subject: "test:Subject1".to_string(),
+                predicate: "test:hasIri".to_string(),
+                object: Object::Iri("test:Object1".to_string()),
+                graph: crate::eavto::DEFAULT_GRAPH.to_string(),
+                tx: 0,
+                created_at: 1000,
+                origin_id: 1,
+                retracted: false,
+            },
+            Triple {
+                subject: "test:Subject2".to_string(),
+                predicate: "test:hasInteger".to_string(),
+                object: Object::Integer(42),
+                graph: crate::eavto::DEFAULT_GRAPH.to_string(),
+                tx: 0,
+                created_at: 1000,
+                origin_id: 1,
+                retracted: false,
+            },
+            Triple {
+                subject: "test:Subject3".to_string(),
+                predicate: "test:hasNumber".to_string(),
+                object: Object::Number(3.14),
+                graph: crate::eavto::DEFAULT_GRAPH.to_string(),
+                tx: 0,
+                created_at: 1000,
+                origin_id: 1,
+                retracted: false,
+            },
+            Triple {
+                subject: "test:Subject4".to_string(),
+                predicate: "test:hasBoolean".to_string(),
+                object: Object::Boolean(true),
+                graph: crate::eavto::DEFAULT_GRAPH.to_string(),
+                tx: 0,
+                created_at: 1000,
+                origin_id: 1,
+                retracted: false,
+            },
+        ];
+
+        assert_triples(&mut conn, &triples, "test").unwrap();
+
+        assert_triple_exists(&conn, "test:Subject1", "test:hasIri");
+        assert_triple_exists(&conn, "test:Subject2", "test:hasInteger");
+        assert_triple_exists(&conn, "test:Subject3", "test:hasNumber");
+        assert_triple_exists(&conn, "test:Subject4", "test:hasBoolean");
+    }
+
+    #[test]
+    fn test_retract_triples() {
+        let mut conn = setup_test_db();
+        let triples = create_test_triples();
+
+        // Assert triples first
+        assert_triples(&mut conn, &triples, "test").unwrap();
+        assert_eq!(get_active_triple_count(&conn), 3);
+
+        // Retract one triple
+        let to_retract = vec![triples[0].clone()];
+        let retract_tx_id = retract_triples(&mut conn, &to_retract, "test").unwrap();
+
+        assert!(retract_tx_id > 0);
+        assert_eq!(get_active_triple_count(&conn), 2); // One should be retracted
+    }
+
+    #[test]
+    fn test_retract_triples_multiple() {
+        let mut conn = setup_test_db();
+        let triples = create_test_triples();
+
+        assert_triples(&mut conn, &triples, "test").unwrap();
+        assert_eq!(get_active_triple_count(&conn), 3);
+
+        // Retract all triples
+        retract_triples(&mut conn, &triples, "test").unwrap();
+        assert_eq!(get_active_triple_count(&conn), 0);
+    }
+
+    #[test]
+    fn test_retract_triples_keeps_other_values() {
+        let mut conn = setup_test_db();
+        let tag = |value: &str, language: Option<&str>| Triple::new("foundation:Note", "foundation:tag", Object::Literal {
+            value: value.to_string(),
+            datatype: None,
+            language: language.map(|l| l.to_string()),
+        });
+        assert_triples(&mut conn, &[
+            tag("work", None),
+            tag("home", None),
+            tag("home", Some("en")),
+            Triple::new("foundation:Note", "foundation:size", Object::Integer(3)),
+        ], "test").unwrap();
+
+        retract_triples(&mut conn, &[tag("home", None), Triple::new("foundation:Note", "foundation:size", Object::Integer(4))], "test").unwrap();
+        assert_eq!(get_active_triple_count(&conn), 3);
+        retract_triples(&mut conn, &[Triple::new("foundation:Note", "foundation:size", Object::Integer(3))], "test").unwrap();
+        assert_eq!(get_active_triple_count(&conn), 2);
+
+        retract_by_pattern(&mut conn, &[("foundation:Note", "foundation:tag")], "test").unwrap();
+        assert_eq!(get_active_triple_count(&conn), 0);
+    }
+
+    #[test]
+    fn test_retract_nonexistent_triple_does_not_error() {
+        let mut conn = setup_test_db();
+
+        let triples = vec![Triple {
+            subject: "nonexistent:Subject".to_string(),
+            predicate: "nonexistent:predicate".to_string(),
+            object: Object::Iri("nonexistent:Object".to_string()),
+            graph: crate::eavto::DEFAULT_GRAPH.to_string(),
+            tx: 0,
+            created_at: 1000,
+            origin_id: 1,
+            retracted: false,
+        }];
+
+        // Should not error even though triple doesn't exist
+        let result = retract_triples(&mut conn, &triples, "test");
+        assert!(result.is_ok());
+    }
+
+    #[test]
+    fn test_retract_origin() {
+        let mut conn = setup_test_db();
+        assert_triples(&mut conn, &[
+            Triple::new("ex:a", "ex:p", Object::Integer(1)),
+            Triple::new("ex:b", "ex:p", Object::Integer(2)),
+        ], "import:bad").unwrap();
+        assert_triples(&mut conn, &[Triple::new("ex:a", "ex:q", Object::Integer(3))], "user-edit").unwrap();
+        let bad = query::get_origin_id(&conn, "import:bad").unwrap().unwrap();
+
+        let tx = retract_origin(&mut conn, bad).unwrap().unwrap();
+        assert_eq!(query::count_by_origin(&conn, bad).unwrap(), 0);
+        assert_eq!(query::get_by_entity(&conn, "ex:a").unwrap().triples.len(), 1);
+        assert_eq!(query::get_transactions(&conn, None, None, 1).unwrap()[0].tx, tx);
+
+        assert!(retract_origin(&mut conn, bad).unwrap().is_none());
+        assert!(retract_origin(&mut conn, 9999).is_err());
+    }
+
+    #[test]
+    fn test_apply_changes_retracts_exact_object() {
+        let mut conn = setup_test_db();
+        let tags = vec![
+            Triple::new("foundation:Note", "foundation:tag", Object::Iri("foundation:Red".to_string())),
+            Triple::new("foundation:Note", "foundation:tag", Object::Iri("foundation:Blue".to_string())),
+        ];
+        assert_triples(&mut conn, &tags, "test").unwrap();
+
+        let added = Triple::new("foundation:Note", "foundation:tag", Object::Iri("foundation:Green".to_string()));
+        let tx_id = apply_changes(&mut conn, &[added], &tags[..1], "test").unwrap();
+
+        assert!(tx_id > 0);
+        let remaining: Vec<String> = conn
+            .prepare("SELECT object FROM triples WHERE predicate = 'foundation:tag' AND retracted = 0 ORDER BY object")
+            .unwrap()
+            .query_map([], |row| row.get(0))
+            .unwrap()
+            .collect::<std::result::Result<_, _>>()
+            .unwrap();
+        assert_eq!(remaining, vec!["foundation:Blue", "foundation:Green"]);
+    }
+
+    #[test]
+    fn test_malformed_typed_literals_are_refused() {
+        let mut conn = setup_test_db();
+        let typed = |predicate: &str, value: &str, datatype: &str| Triple::new("foundation:Shop", predicate, Object::Literal {
+            value: value.to_string(),
+            datatype: Some(datatype.to_string()),
+            language: None,
+        });
+        assert_triples(&mut conn, &[typed("foundation:opensAt", "09:30:00", "xsd:time")], "test").unwrap();
+
+        for bad in [
+            typed("foundation:openedOn", "soon", "xsd:date"),
+            typed("foundation:closesAt", "noon", "xsd:time"),
+            typed("foundation:employees", "abc", "xsd:integer"),
+        ] {
+            let err = apply_changes(&mut conn, std::slice::from_ref(&bad), &[], "test").unwrap_err();
+            let invalid = err.downcast_ref::<InvalidLiteral>().expect("typed error");
+            assert_eq!((invalid.value.as_str(), invalid.predicate.as_str()), (bad.object.as_literal().unwrap().as_str(), bad.predicate.as_str()));
+        }
+
+        // Nothing of the refused change sets was written, and writes go on
+        assert_eq!(get_active_triple_count(&conn), 1);
+        assert_triples(&mut conn, &[typed("foundation:employees", "12", "xsd:integer")], "test").unwrap();
+        assert_eq!(get_active_triple_count(&conn), 2);
+    }
+
+    #[test]
+    fn test_apply_changes_respects_origin() {
+        let mut conn = setup_test_db();
+        let label = Triple::new("foundation:Note", "rdfs:label", Object::Literal {
+            value: "Note".to_string(),
+            datatype: Some("xsd:string".to_string()),
+            language: None,
+        });
+        assert_triples(&mut conn, std::slice::from_ref(&label), "ontology").unwrap();
+        assert_triples(&mut conn, std::slice::from_ref(&label), "user").unwrap();
+
+        let mut from_ontology = label;
+        from_ontology.origin_id = conn
+            .query_row("SELECT id FROM origins WHERE name = 'ontology'", [], |row| row.get(0))
+            .unwrap();
+        apply_changes(&mut conn, &[], &[from_ontology], "ontology").unwrap();
+
+        // The user's copy of the same fact survives
+        assert_eq!(get_active_triple_count(&conn), 1);
+    }
+
+    #[test]
+    fn test_undo_transaction() {
+        let mut conn = setup_test_db();
+        let label = |value: &str| Triple::new("foundation:A", "rdfs:label", Object::Literal {
+            value: value.to_string(),
+            datatype: Some("xsd:string".to_string()),
+            language: None,
+        });
+        let labels = |conn: &Connection| query::get_by_entity(conn, "foundation:A").unwrap().triples.iter()
+            .filter_map(|t| t.object.as_literal())
+            .collect::<Vec<_>>();
+
+        assert_triples(&mut conn, &[label("Old")], "import").unwrap();
+        let edit = apply_changes(&mut conn, &[label("New")], &[label("Old")], "user-edit").unwrap();
+        assert_eq!(labels(&conn), vec!["New"]);
+
+        let undo = undo_transaction(&mut conn, edit, "user-edit").unwrap();
+        assert_eq!(labels(&conn), vec!["Old"]);
+        let restored = query::get_by_entity(&conn, "foundation:A").unwrap().triples;
+        assert_eq!((restored[0].tx, query::get_origin_name(&conn, restored[0].origin_id).unwrap().as_deref()), (undo, Some("import")));
+
+        // Undoing the undo redoes the edit
+        undo_transaction(&mut conn, undo, "user-edit").unwrap();
+        assert_eq!(labels(&conn), vec!["New"]);
+
+        assert!(undo_transaction(&mut conn, 99, "user-edit").is_err());
+        let empty = assert_triples(&mut conn, &[], "user-edit").unwrap();
+        assert!(undo_transaction(&mut conn, empty, "user-edit").is_err());
+    }
+
+    #[test]
+    fn test_get_or_create_origin_existing() {
+        let mut conn = setup_test_db();
+        let tx = conn.transaction().unwrap();
+
+        // Origin "test" should already exist from setup_test_db
+        let id1 = get_or_create_origin(&tx, "test").unwrap();
+        let id2 = get_or_create_origin(&tx, "test").unwrap();
+
+        assert_eq!(id1, id2); // Should return same ID
+    }
+
+    #[test]
+    fn test_get_or_create_origin_new() {
+        let mut conn = setup_test_db();
+        let tx = conn.transaction().unwrap();
+
+        let id = get_or_create_origin(&tx, "brand_new_origin").unwrap();
+        assert!(id > 0);
+    }
+
+    #[test]
+    fn test_now_millis() {
+        let ts = now_millis();
+        assert!(ts > 0);
+
+        // Should be a reasonable timestamp (after 2020)
+        assert!(ts > 1577836800000); // Jan 1, 2020 in milliseconds
+    }
+}
@@ -0,0 +1,52 @@
+//! IRI Dictionary
+//!
+//! Subjects, predicates and IRI (or blank node) objects are interned in the
+//! terms table: a row of the quads table holds their integer IDs instead of
+//! the strings. Reads go through the triples view, which puts the strings
+//! back; `store` interns the terms of what it writes.
+
+use rusqlite::{Connection, OptionalExtension};
+
+/// ID of a term, adding it to the dictionary if it's new
+pub fn intern(conn: &Connection, value: &str) -> rusqlite::Result<i64> {
+    if let Some(id) = lookup(conn, value)? {
+        return Ok(id);
+    }
+    conn.prepare_cached("INSERT INTO terms (value) VALUES (?)")?.execute([value])?;
+    Ok(conn.last_insert_rowid())
+}
+
+/// ID of a term, if any triple ever used it
+pub fn lookup(conn: &Connection, value: &str) -> rusqlite::Result<Option<i64>> {
+    conn.prepare_cached("SELECT id FROM terms WHERE value = ?")?
+        .query_row([value], |row| row.get(0))
+        .optional()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::eavto::test_helpers::setup_test_db;
+    use crate::eavto::{query, store, Object, Triple};
+
+    #[test]
+    fn test_terms_are_stored_once() {
+        let mut conn = setup_test_db();
+        assert!(lookup(&conn, "foundation:Person").unwrap().is_none());
+
+        let person = intern(&conn, "foundation:Person").unwrap();
+        assert_eq!(intern(&conn, "foundation:Person").unwrap(), person);
+
+        store::assert_triples(&mut conn, &[
+            Triple::new("foundation:alice", "rdf:type", Object::Iri("foundation:Person".to_string())),
+            Triple::new("foundation:bob", "rdf:type", Object::Iri("foundation:Person".to_string())),
+        ], "test").unwrap();
+        let terms: i64 = conn.query_row("SELECT COUNT(*) FROM terms", [], |row| row.get(0)).unwrap();
+        assert_eq!(terms, 4);
+        assert_eq!(lookup(&conn, "foundation:Person").unwrap(), Some(person));
+
+        let people = query::get_by_predicate_object(&conn, "rdf:type", "foundation:Person").unwrap().triples;
+        assert_eq!(people.len(), 2);
+        assert_eq!(people[0].object.as_iri(), Some("foundation:Person"));
+    }
+}
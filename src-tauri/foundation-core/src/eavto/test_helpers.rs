@@ -0,0 +1,281 @@
+// ============================================================================
+// EAVTO Test Helpers
+// ============================================================================
+// Common utilities for EAVTO module tests
+// ============================================================================
+
+use rusqlite::Connection;
+
+use super::{Triple, Object};
+
+/// The triples view over interned quads, as in schema.sql (without its
+/// constraints; setup_test_db adds the indexes of `indexes`)
+pub const TRIPLES_SCHEMA: &str = r#"
+    CREATE TABLE IF NOT EXISTS terms (
+        id INTEGER PRIMARY KEY,
+        value TEXT NOT NULL UNIQUE
+    );
+
+    CREATE TABLE IF NOT EXISTS statements (
+        id INTEGER PRIMARY KEY,
+        subject_id INTEGER NOT NULL,
+        predicate_id INTEGER NOT NULL,
+        object_id INTEGER
+    );
+
+    CREATE TABLE IF NOT EXISTS quads (
+        id INTEGER PRIMARY KEY,
+        subject_id INTEGER NOT NULL,
+        predicate_id INTEGER NOT NULL,
+        object_id INTEGER,
+        object_value TEXT,
+        object_type TEXT NOT NULL CHECK(object_type IN ('iri', 'literal', 'blank')),
+        graph TEXT NOT NULL DEFAULT 'foundation:DefaultGraph',
+        object_datatype TEXT,
+        object_language TEXT,
+        object_number REAL,
+        object_integer INTEGER,
+        object_datetime INTEGER,
+        object_boolean INTEGER,
+        tx INTEGER NOT NULL,
+        origin_id INTEGER NOT NULL,
+        created_at INTEGER NOT NULL,
+        retracted INTEGER NOT NULL DEFAULT 0,
+        retracted_tx INTEGER,
+        FOREIGN KEY (tx) REFERENCES transactions(tx),
+        FOREIGN KEY (origin_id) REFERENCES origins(id)
+    );
+
+    CREATE INDEX IF NOT EXISTS idx_quads_tx ON quads(tx);
+
+    CREATE VIEW IF NOT EXISTS triples AS
+    SELECT s.value AS subject, p.value AS predicate, o.value AS object,
+           q.object_value, q.object_datatype, q.object_language, q.object_type, q.graph,
+           q.object_number, q.object_integer, q.object_datetime, q.object_boolean,
+           q.tx, q.origin_id, q.retracted, q.created_at, q.retracted_tx, q.id AS rowid
+    FROM quads q
+    JOIN terms s ON s.id = q.subject_id
+    JOIN terms p ON p.id = q.predicate_id
+    LEFT JOIN terms o ON o.id = q.object_id;
+
+    CREATE TRIGGER IF NOT EXISTS triples_insert INSTEAD OF INSERT ON triples
+    BEGIN
+        INSERT OR IGNORE INTO terms (value)
+            SELECT value FROM (SELECT NEW.subject AS value UNION ALL SELECT NEW.predicate UNION ALL SELECT NEW.object)
+            WHERE value IS NOT NULL;
+        INSERT INTO quads (subject_id, predicate_id, object_id, object_value, object_datatype, object_language,
+                           object_type, graph, object_number, object_integer, object_datetime, object_boolean,
+                           tx, origin_id, retracted, created_at, retracted_tx)
+        VALUES ((SELECT id FROM terms WHERE value = NEW.subject),
+                (SELECT id FROM terms WHERE value = NEW.predicate),
+                (SELECT id FROM terms WHERE value = NEW.object),
+                NEW.object_value, NEW.object_datatype, NEW.object_language,
+                NEW.object_type, COALESCE(NEW.graph, 'foundation:DefaultGraph'),
+                NEW.object_number, NEW.object_integer, NEW.object_datetime, NEW.object_boolean,
+                NEW.tx, NEW.origin_id, COALESCE(NEW.retracted, 0), NEW.created_at, NEW.retracted_tx);
+    END;
+
+    CREATE TRIGGER IF NOT EXISTS triples_update INSTEAD OF UPDATE ON triples
+    BEGIN
+        INSERT OR IGNORE INTO terms (value)
+            SELECT value FROM (SELECT NEW.subject AS value UNION ALL SELECT NEW.predicate UNION ALL SELECT NEW.object)
+            WHERE value IS NOT NULL;
+        UPDATE quads SET
+            subject_id = (SELECT id FROM terms WHERE value = NEW.subject),
+            predicate_id = (SELECT id FROM terms WHERE value = NEW.predicate),
+            object_id = (SELECT id FROM terms WHERE value = NEW.object),
+            object_value = NEW.object_value, object_datatype = NEW.object_datatype, object_language = NEW.object_language,
+            object_type = NEW.object_type, graph = NEW.graph,
+            object_number = NEW.object_number, object_integer = NEW.object_integer,
+            object_datetime = NEW.object_datetime, object_boolean = NEW.object_boolean,
+            tx = NEW.tx, origin_id = NEW.origin_id, retracted = NEW.retracted,
+            created_at = NEW.created_at, retracted_tx = NEW.retracted_tx
+        WHERE id = OLD.rowid;
+    END;
+"#;
+
+/// Create an in-memory test database with schema
+pub fn setup_test_db() -> Connection {
+    let conn = Connection::open_in_memory().expect("Failed to open in-memory database");
+    create_test_schema(&conn);
+    conn
+}
+
+/// Create a test database with schema in a file (replaced if it exists), for
+/// tests that need several connections to it
+pub fn setup_test_db_file(path: &std::path::Path) -> Connection {
+    let _ = std::fs::remove_file(path);
+    let conn = Connection::open(path).expect("Failed to open test database file");
+    create_test_schema(&conn);
+    conn
+}
+
+fn create_test_schema(conn: &Connection) {
+    // Create minimal schema for testing
+    conn.execute_batch(
+        r#"
+        CREATE TABLE IF NOT EXISTS origins (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            name TEXT UNIQUE NOT NULL,
+            description TEXT,
+            status TEXT NOT NULL DEFAULT 'active'
+        );
+
+        CREATE TABLE IF NOT EXISTS transactions (
+            tx INTEGER PRIMARY KEY AUTOINCREMENT,
+            origin TEXT NOT NULL,
+            created_at INTEGER NOT NULL,
+            received_at INTEGER,
+            message TEXT,
+            tags TEXT,
+            signature TEXT,
+            signer TEXT
+        );
+
+        CREATE TABLE IF NOT EXISTS metadata (
+            key TEXT PRIMARY KEY,
+            value TEXT NOT NULL,
+            updated_at INTEGER NOT NULL
+        );
+
+        CREATE VIRTUAL TABLE IF NOT EXISTS attachment_text USING fts5(
+            entity UNINDEXED,
+            text,
+            tokenize = 'unicode61 remove_diacritics 2'
+        );
+
+        CREATE TABLE IF NOT EXISTS api_tokens (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            name TEXT NOT NULL,
+            token_hash TEXT NOT NULL UNIQUE,
+            prefix TEXT NOT NULL,
+            scopes TEXT NOT NULL,
+            created_at INTEGER NOT NULL,
+            expires_at INTEGER,
+            revoked_at INTEGER,
+            last_used_at INTEGER
+        );
+
+        CREATE TABLE IF NOT EXISTS outbox (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            kind TEXT NOT NULL,
+            payload TEXT NOT NULL,
+            status TEXT NOT NULL DEFAULT 'pending' CHECK (status IN ('pending', 'done', 'failed', 'cancelled')),
+            attempts INTEGER NOT NULL DEFAULT 0,
+            next_attempt_at INTEGER NOT NULL,
+            last_error TEXT,
+            created_at INTEGER NOT NULL,
+            completed_at INTEGER
+        );
+
+        CREATE TABLE IF NOT EXISTS blobs (
+            hash TEXT PRIMARY KEY,
+            content TEXT NOT NULL,
+            datatype TEXT,
+            language TEXT,
+            size INTEGER NOT NULL,
+            created_at INTEGER NOT NULL
+        );
+
+        CREATE TABLE IF NOT EXISTS sync_changesets (
+            id TEXT PRIMARY KEY,
+            tx INTEGER,
+            peer TEXT,
+            recorded_at INTEGER NOT NULL
+        );
+
+        CREATE TABLE IF NOT EXISTS write_journal (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            command TEXT NOT NULL,
+            args_hash TEXT,
+            tx INTEGER,
+            duration_ms INTEGER NOT NULL,
+            error TEXT,
+            recorded_at INTEGER NOT NULL
+        );
+
+        CREATE TABLE IF NOT EXISTS ontology_files (
+            file_path TEXT PRIMARY KEY,
+            file_name TEXT NOT NULL,
+            last_modified INTEGER NOT NULL,
+            last_imported INTEGER NOT NULL,
+            checksum TEXT NOT NULL,
+            triple_count INTEGER NOT NULL,
+            source_url TEXT,
+            etag TEXT
+        );
+
+        INSERT INTO metadata (key, value, updated_at) VALUES
+            ('schema_version', '2', 0),
+            ('ontology_imported', 'false', 0);
+
+        INSERT INTO origins (name, description) VALUES
+            ('test', 'Test origin for unit tests');
+        "#
+    ).expect("Failed to create test schema");
+    conn.execute_batch(TRIPLES_SCHEMA).expect("Failed to create test triples");
+    super::indexes::ensure(conn).expect("Failed to create test indexes");
+}
+
+/// Create sample test triples
+pub fn create_test_triples() -> Vec<Triple> {
+    vec![
+        Triple {
+            subject: "foundation:TestClass".to_string(),
+            predicate: "rdf:type".to_string(),
+            object: Object::Iri("owl:Class".to_string()),
+            graph: super::DEFAULT_GRAPH.to_string(),
+            tx: 0,
+            created_at: 1000,
+            origin_id: 1,
+            retracted: false,
+        },
+        Triple {
+            subject: "foundation:TestClass".to_string(),
+            predicate: "rdfs:label".to_string(),
+            object: Object::Literal {
+                value: "Test Class".to_string(),
+                datatype: Some("xsd:string".to_string()),
+                language: None,
+            },
+            graph: super::DEFAULT_GRAPH.to_string(),
+            tx: 0,
+            created_at: 1000,
+            origin_id: 1,
+            retracted: false,
+        },
+        Triple {
+            subject: "foundation:TestProperty".to_string(),
+            predicate: "foundation:someValue".to_string(),
+            object: Object::Integer(42),
+            graph: super::DEFAULT_GRAPH.to_string(),
+            tx: 0,
+            created_at: 1000,
+            origin_id: 1,
+            retracted: false,
+        },
+    ]
+}
+
+/// Assert that a triple exists in the database
+pub fn assert_triple_exists(conn: &Connection, subject: &str, predicate: &str) {
+    let count: i64 = conn
+        .query_row(
+            "SELECT COUNT(*) FROM triples WHERE subject = ? AND predicate = ? AND retracted = 0",
+            [subject, predicate],
+            |row| row.get(0),
+        )
+        .expect("Failed to query triple");
+
+    assert!(count > 0, "Triple not found: {} {}", subject, predicate);
+}
+
+/// Get the count of active triples in the database
+pub fn get_active_triple_count(conn: &Connection) -> i64 {
+    conn.query_row(
+        "SELECT COUNT(*) FROM triples WHERE retracted = 0",
+        [],
+        |row| row.get(0),
+    )
+    .expect("Failed to count triples")
+}
@@ -9,6 +9,30 @@ pub struct Transaction {
     pub tx: i64,
     pub origin: String,
     pub created_at: i64,
+    pub received_at: Option<i64>, // Set when made on another device (created_at is its claim)
+    pub message: Option<String>,
+    pub tags: Vec<String>,
+}
+
+/// Human-readable description of a transaction, given by whoever writes it
+/// ("Imported March bank statement", tagged "finance")
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Annotation {
+    pub message: Option<String>,
+    pub tags: Vec<String>,
+}
+
+impl Annotation {
+    /// Annotation from optional command parameters; blank messages and tags are dropped
+    pub fn new(message: Option<String>, tags: Option<Vec<String>>) -> Self {
+        Self {
+            message: message.map(|m| m.trim().to_string()).filter(|m| !m.is_empty()),
+            tags: tags.unwrap_or_default().into_iter()
+                .map(|t| t.trim().to_string())
+                .filter(|t| !t.is_empty())
+                .collect(),
+        }
+    }
 }
 
 impl Transaction {
@@ -18,6 +42,9 @@ impl Transaction {
             tx,
             origin: origin.into(),
             created_at,
+            received_at: None,
+            message: None,
+            tags: Vec::new(),
         }
     }
 }
@@ -52,6 +79,13 @@ mod tests {
         assert_eq!(cloned.created_at, 999);
     }
 
+    #[test]
+    fn test_annotation_drops_blanks() {
+        let annotation = Annotation::new(Some("  ".to_string()), Some(vec!["finance".to_string(), " ".to_string()]));
+        assert_eq!(annotation, Annotation { message: None, tags: vec!["finance".to_string()] });
+        assert_eq!(Annotation::new(None, None), Annotation::default());
+    }
+
     #[test]
     fn test_transaction_with_negative_timestamp() {
         let transaction = Transaction::new(5, "retracted", -1);
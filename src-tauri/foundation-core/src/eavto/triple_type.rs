@@ -4,6 +4,12 @@
 
 use super::object_type::Object;
 
+/// Graph of triples asserted without one (user data)
+pub const DEFAULT_GRAPH: &str = "foundation:DefaultGraph";
+
+/// Graph of the core ontologies (see query::is_core_origin)
+pub const ONTOLOGY_GRAPH: &str = "foundation:OntologyGraph";
+
 /// A single RDF triple with EVTO metadata
 #[derive(Debug, Clone)]
 pub struct Triple {
@@ -12,6 +18,9 @@ pub struct Triple {
     pub predicate: String,
     pub object: Object,
 
+    // Dataset dimension (named graph)
+    pub graph: String,
+
     // Time dimension
     pub tx: i64,
     pub created_at: i64,
@@ -34,6 +43,7 @@ impl Triple {
             subject: subject.into(),
             predicate: predicate.into(),
             object,
+            graph: DEFAULT_GRAPH.to_string(),
             tx: 0,
             created_at: 0,
             origin_id: 0,
@@ -41,12 +51,51 @@ impl Triple {
         }
     }
 
+    /// The same triple in another named graph
+    pub fn in_graph(mut self, graph: impl Into<String>) -> Self {
+        self.graph = graph.into();
+        self
+    }
+
     /// Check if this triple is currently active (not retracted)
     pub fn is_active(&self) -> bool {
         !self.retracted
     }
 }
 
+/// A quoted triple (RDF-star): a fact talked about, not asserted by quoting it
+///
+/// Its term (see `term`) is stored like an IRI, so triples can have it as
+/// subject or object: `<<( alice age 42 )>> foundation:confidence 0.9`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct QuotedTriple {
+    pub subject: String, // An IRI, blank node or quoted triple term
+    pub predicate: String,
+    pub object: Object,
+}
+
+impl QuotedTriple {
+    pub fn new(subject: impl Into<String>, predicate: impl Into<String>, object: Object) -> Self {
+        Self { subject: subject.into(), predicate: predicate.into(), object }
+    }
+
+    /// The fact of a triple, without its store metadata
+    pub fn of(triple: &Triple) -> Self {
+        Self::new(&triple.subject, &triple.predicate, triple.object.clone())
+    }
+
+    /// Its term: `<<( s p o )>>` with the canonical forms of s, p and o
+    /// (see canonical), so the same fact always has the same term
+    pub fn term(&self) -> String {
+        format!(
+            "<<( {} {} {} )>>",
+            super::canonical::term(&self.subject),
+            super::canonical::term(&self.predicate),
+            super::canonical::object(&self.object)
+        )
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -61,6 +110,7 @@ mod tests {
 
         assert_eq!(triple.subject, "foundation:Class");
         assert_eq!(triple.predicate, "rdf:type");
+        assert_eq!(triple.graph, DEFAULT_GRAPH);
         assert_eq!(triple.tx, 0);
         assert_eq!(triple.created_at, 0);
         assert_eq!(triple.origin_id, 0);
@@ -204,6 +204,99 @@ impl XsdType {
             XsdType::DateTime | XsdType::Date | XsdType::Time | XsdType::Duration
         )
     }
+
+    /// Value of a dateTime, date or time lexical form in the object_datetime
+    /// column: Unix epoch milliseconds, UTC unless the form has a timezone
+    /// - dateTime: the instant ("2024-05-01T10:00:00Z")
+    /// - date: its midnight ("2024-05-01")
+    /// - time: milliseconds since midnight ("10:00:00.5")
+    pub fn epoch_millis(&self, value: &str) -> Option<i64> {
+        use chrono::{NaiveDate, NaiveDateTime, NaiveTime, Timelike};
+
+        let value = value.trim();
+        match self {
+            XsdType::DateTime => chrono::DateTime::parse_from_rfc3339(value)
+                .map(|dt| dt.timestamp_millis())
+                .or_else(|_| NaiveDateTime::parse_from_str(value, "%Y-%m-%dT%H:%M:%S%.f").map(|dt| dt.and_utc().timestamp_millis()))
+                .ok(),
+            XsdType::Date => {
+                let (date, offset) = split_timezone(value)?;
+                let midnight = NaiveDate::parse_from_str(date, "%Y-%m-%d").ok()?.and_hms_opt(0, 0, 0)?;
+                Some(midnight.and_utc().timestamp_millis() - offset)
+            }
+            XsdType::Time => {
+                let (time, offset) = split_timezone(value)?;
+                let time = NaiveTime::parse_from_str(time, "%H:%M:%S%.f").ok()?;
+                let millis = time.num_seconds_from_midnight() as i64 * 1000 + (time.nanosecond() / 1_000_000) as i64;
+                Some((millis - offset).rem_euclid(86_400_000))
+            }
+            _ => None,
+        }
+    }
+}
+
+/// A date or time form without its timezone ("Z", "+02:00" or "-05:00"),
+/// and the timezone's offset from UTC in milliseconds (0 if none)
+fn split_timezone(value: &str) -> Option<(&str, i64)> {
+    if let Some(local) = value.strip_suffix('Z') {
+        return Some((local, 0));
+    }
+    let at = value.len().saturating_sub(6);
+    match value.get(at..).map(|zone| (at, zone)) {
+        Some((at, zone)) if zone.len() == 6 && (zone.starts_with('+') || zone.starts_with('-')) && zone.as_bytes()[3] == b':' => {
+            let hours: i64 = zone[1..3].parse().ok()?;
+            let minutes: i64 = zone[4..6].parse().ok()?;
+            let offset = (hours * 60 + minutes) * 60_000;
+            Some((&value[..at], if zone.starts_with('-') { -offset } else { offset }))
+        }
+        _ => Some((value, 0)),
+    }
+}
+
+/// Value of a literal in its typed column (object_number, object_integer,
+/// object_boolean or object_datetime), which range queries and sorting read
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TypedValue {
+    Number(f64),
+    Integer(i64),
+    Boolean(bool),
+    DateTime(i64), // Unix ms, see XsdType::epoch_millis
+}
+
+impl TypedValue {
+    /// Check if literals of a datatype keep their value in a typed column
+    pub fn has_column(datatype: &str) -> bool {
+        matches!(
+            datatype,
+            "xsd:decimal" | "xsd:double" | "xsd:float" |
+            "xsd:integer" | "xsd:int" | "xsd:long" |
+            "xsd:boolean" |
+            "xsd:dateTime" | "xsd:date" | "xsd:time"
+        )
+    }
+
+    /// Typed value of a lexical form, if valid for its datatype (None also
+    /// for datatypes without a typed column)
+    ///
+    /// Surrounding whitespace is ignored, as xsd's whitespace facet collapses it.
+    pub fn parse(value: &str, datatype: &str) -> Option<Self> {
+        let value = value.trim();
+        match datatype {
+            "xsd:decimal" | "xsd:double" | "xsd:float" => value.parse::<f64>().ok()
+                .filter(|n| n.is_finite() || datatype != "xsd:decimal")
+                .map(TypedValue::Number),
+            "xsd:integer" | "xsd:int" | "xsd:long" => value.strip_prefix('+').unwrap_or(value).parse::<i64>().ok()
+                .map(TypedValue::Integer),
+            "xsd:boolean" => match value {
+                "true" | "1" => Some(TypedValue::Boolean(true)),
+                "false" | "0" => Some(TypedValue::Boolean(false)),
+                _ => None,
+            },
+            "xsd:dateTime" | "xsd:date" | "xsd:time" => XsdType::from_iri(datatype)?.epoch_millis(value)
+                .map(TypedValue::DateTime),
+            _ => None,
+        }
+    }
 }
 
 impl std::fmt::Display for XsdType {
@@ -343,6 +436,17 @@ mod tests {
         assert_eq!(XsdType::from_iri("xsd:boolean"), Some(XsdType::Boolean));
     }
 
+    #[test]
+    fn test_typed_value_parse() {
+        assert_eq!(TypedValue::parse(" +32 ", "xsd:integer"), Some(TypedValue::Integer(32)));
+        assert_eq!(TypedValue::parse("1", "xsd:boolean"), Some(TypedValue::Boolean(true)));
+        assert_eq!(TypedValue::parse("09:30:00", "xsd:time"), Some(TypedValue::DateTime(34_200_000)));
+        assert_eq!(TypedValue::parse("abc", "xsd:integer"), None);
+        assert_eq!(TypedValue::parse("noon", "xsd:time"), None);
+        assert_eq!(TypedValue::parse("NaN", "xsd:decimal"), None);
+        assert!(TypedValue::has_column("xsd:date") && !TypedValue::has_column("xsd:duration"));
+    }
+
     #[test]
     fn test_from_iri_temporal_types() {
         assert_eq!(XsdType::from_iri("dateTime"), Some(XsdType::DateTime));
@@ -403,4 +507,18 @@ mod tests {
         assert_eq!(format!("{}", XsdType::DateTime), "xsd:dateTime");
         assert_eq!(format!("{}", XsdType::Boolean), "xsd:boolean");
     }
+
+    #[test]
+    fn test_epoch_millis_of_temporal_forms() {
+        let may_first = 1_714_521_600_000; // 2024-05-01T00:00:00Z
+        assert_eq!(XsdType::DateTime.epoch_millis("2024-05-01T10:00:00Z"), Some(may_first + 36_000_000));
+        assert_eq!(XsdType::DateTime.epoch_millis("2024-05-01T12:00:00+02:00"), Some(may_first + 36_000_000));
+        assert_eq!(XsdType::DateTime.epoch_millis("2024-05-01T10:00:00.250"), Some(may_first + 36_000_250));
+        assert_eq!(XsdType::Date.epoch_millis("2024-05-01"), Some(may_first));
+        assert_eq!(XsdType::Date.epoch_millis("2024-05-01+02:00"), Some(may_first - 7_200_000));
+        assert_eq!(XsdType::Time.epoch_millis("10:00:00.5"), Some(36_000_500));
+        assert_eq!(XsdType::Time.epoch_millis("01:00:00+02:00"), Some(82_800_000));
+        assert_eq!(XsdType::Date.epoch_millis("May 1st"), None);
+        assert_eq!(XsdType::Integer.epoch_millis("2024"), None);
+    }
 }
@@ -0,0 +1,17 @@
+// ============================================================================
+// Export Module
+// ============================================================================
+// Serializes triples from the EAVTO store into interchange formats
+// ============================================================================
+
+pub mod nquads;
+pub mod rdfxml;
+pub mod results;
+pub mod snapshot;
+pub mod turtle;
+
+pub use nquads::{export_nquads, GraphMode, NQuadsExport};
+pub use rdfxml::{export_rdfxml, OntologyHeader};
+pub use results::{export_query_results, ResultFormat, ResultsExport};
+pub use snapshot::{export_snapshot, SnapshotFormat, SnapshotExport};
+pub use turtle::{export_origin_as_ontology, OntologyMetadata, OntologyFile};
@@ -0,0 +1,227 @@
+// ============================================================================
+// N-Quads Export
+// ============================================================================
+// Writes current triples as N-Quads, keeping the provenance other triple
+// stores would otherwise lose:
+// - Each triple goes in a named graph for its origin
+//   (http://foundation.local/origin/{name}), for its transaction
+//   (http://foundation.local/tx/{tx}), or in the graph it is stored in
+// - The default graph describes those graphs: origin graphs are labeled with
+//   the origin name; transaction graphs have their origin graph as
+//   dcterms:source and their time as dcterms:created
+//
+// Terms are written as in canonical N-Triples (eavto::canonical): full
+// IRIs, typed literals for numbers and dates.
+// ============================================================================
+
+use rusqlite::Connection;
+use std::collections::{hash_map::Entry, BTreeMap, BTreeSet, HashMap};
+use crate::eavto::{canonical, query, Object, Triple};
+use crate::owl::vocabulary::{rdfs, dcterms};
+
+type Result<T> = std::result::Result<T, Box<dyn std::error::Error>>;
+
+/// Base IRI of origin graphs
+pub const ORIGIN_GRAPH: &str = "http://foundation.local/origin/";
+
+/// Base IRI of transaction graphs
+pub const TRANSACTION_GRAPH: &str = "http://foundation.local/tx/";
+
+/// What the named graph of a quad stands for
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GraphMode {
+    Origin,
+    Transaction,
+    Stored, // The triple's own named graph
+}
+
+impl GraphMode {
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.to_ascii_lowercase().as_str() {
+            "origin" => Some(GraphMode::Origin),
+            "transaction" | "tx" => Some(GraphMode::Transaction),
+            "stored" | "graph" => Some(GraphMode::Stored),
+            _ => None,
+        }
+    }
+}
+
+/// An N-Quads document
+#[derive(Debug, Clone)]
+pub struct NQuadsExport {
+    pub content: String,
+    pub quads: usize, // Data quads (graph descriptions not counted)
+    pub graphs: Vec<String>,
+}
+
+/// Export the current triples, or those of one origin, as N-Quads
+pub fn export_nquads(conn: &Connection, origin: Option<&str>, mode: GraphMode) -> Result<NQuadsExport> {
+    let triples = match origin {
+        Some(origin) => {
+            let origin_id = query::get_origin_id(conn, origin)?
+                .ok_or_else(|| format!("Origin {} not found", origin))?;
+            query::get_by_origin(conn, origin_id)?.triples
+        }
+        None => query::get_all(conn)?.triples,
+    };
+
+    let mut origin_names = HashMap::new();
+    for triple in &triples {
+        if let Entry::Vacant(entry) = origin_names.entry(triple.origin_id) {
+            let name = query::get_origin_name(conn, triple.origin_id)?
+                .unwrap_or_else(|| triple.origin_id.to_string());
+            entry.insert(name);
+        }
+    }
+
+    Ok(write_nquads(&triples, &origin_names, mode))
+}
+
+/// N-Quads for triples, given the names of their origins (by origin ID)
+pub fn write_nquads(triples: &[Triple], origin_names: &HashMap<i64, String>, mode: GraphMode) -> NQuadsExport {
+    let graph_of = |triple: &Triple| origin_names.get(&triple.origin_id)
+        .map_or_else(|| format!("{}{}", ORIGIN_GRAPH, triple.origin_id), |name| origin_graph(name));
+
+    let mut by_graph: BTreeMap<String, BTreeSet<String>> = BTreeMap::new();
+    let mut descriptions = BTreeSet::new();
+    for triple in triples {
+        let graph = match mode {
+            GraphMode::Origin => {
+                let graph = graph_of(triple);
+                if let Some(name) = origin_names.get(&triple.origin_id) {
+                    descriptions.insert(description(&graph, rdfs::LABEL, &string(name)));
+                }
+                graph
+            }
+            GraphMode::Transaction => {
+                let graph = format!("{}{}", TRANSACTION_GRAPH, triple.tx);
+                descriptions.insert(description(&graph, dcterms::SOURCE, &canonical::term(&graph_of(triple))));
+                descriptions.insert(description(&graph, dcterms::CREATED, &canonical::object(&Object::DateTime(triple.created_at))));
+                graph
+            }
+            GraphMode::Stored => crate::namespaces::expand_iri(&triple.graph),
+        };
+        by_graph.entry(graph).or_default().insert(format!(
+            "{} {} {}",
+            canonical::term(&triple.subject),
+            canonical::term(&triple.predicate),
+            canonical::object(&triple.object)
+        ));
+    }
+
+    let mut content = String::new();
+    for line in &descriptions {
+        content.push_str(line);
+        content.push('\n');
+    }
+    let mut quads = 0;
+    for (graph, lines) in &by_graph {
+        for line in lines {
+            content.push_str(&format!("{} <{}> .\n", line, graph));
+            quads += 1;
+        }
+    }
+
+    NQuadsExport { content, quads, graphs: by_graph.into_keys().collect() }
+}
+
+/// Graph IRI of an origin
+pub fn origin_graph(name: &str) -> String {
+    let mut iri = String::from(ORIGIN_GRAPH);
+    for byte in name.bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' => iri.push(byte as char),
+            other => iri.push_str(&format!("%{:02X}", other)),
+        }
+    }
+    iri
+}
+
+/// A default-graph quad about a named graph
+fn description(graph: &str, predicate: &str, object: &str) -> String {
+    format!("<{}> {} {} .", graph, canonical::term(predicate), object)
+}
+
+fn string(value: &str) -> String {
+    canonical::object(&Object::Literal {
+        value: value.to_string(),
+        datatype: Some("xsd:string".to_string()),
+        language: None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::eavto::{store, test_helpers::setup_test_db};
+
+    fn setup(conn: &mut Connection) -> (i64, i64) {
+        let tx1 = store::assert_triples(conn, &[
+            Triple::new("foundation:Laptop", "rdf:type", Object::Iri("foundation:Computer".to_string())),
+            Triple::new("foundation:Laptop", "foundation:memorySize", Object::Integer(16)),
+        ], "import:devices.ttl").unwrap();
+        let tx2 = store::assert_triples(conn, &[
+            Triple::new("foundation:Laptop", "rdfs:label", Object::Literal {
+                value: "Work \"laptop\"".to_string(),
+                datatype: Some("xsd:string".to_string()),
+                language: None,
+            }),
+        ], "user-edit").unwrap();
+        (tx1, tx2)
+    }
+
+    #[test]
+    fn test_origins_as_named_graphs() {
+        let mut conn = setup_test_db();
+        setup(&mut conn);
+
+        let export = export_nquads(&conn, None, GraphMode::Origin).unwrap();
+        assert_eq!(export.quads, 3);
+        assert_eq!(export.graphs, vec![
+            "http://foundation.local/origin/import%3Adevices.ttl",
+            "http://foundation.local/origin/user-edit",
+        ]);
+        assert!(export.content.contains(
+            "<http://foundation.local/ontology/Laptop> <http://foundation.local/ontology/memorySize> \"16\"^^<http://www.w3.org/2001/XMLSchema#integer> <http://foundation.local/origin/import%3Adevices.ttl> .\n"
+        ));
+        assert!(export.content.contains("\"Work \\\"laptop\\\"\" <http://foundation.local/origin/user-edit> .\n"));
+        assert!(export.content.contains(
+            "<http://foundation.local/origin/user-edit> <http://www.w3.org/2000/01/rdf-schema#label> \"user-edit\" .\n"
+        ));
+
+        let one = export_nquads(&conn, Some("user-edit"), GraphMode::Origin).unwrap();
+        assert_eq!(one.quads, 1);
+        assert!(export_nquads(&conn, Some("nowhere"), GraphMode::Origin).is_err());
+    }
+
+    #[test]
+    fn test_transactions_as_named_graphs() {
+        let mut conn = setup_test_db();
+        let (tx1, tx2) = setup(&mut conn);
+
+        let export = export_nquads(&conn, None, GraphMode::Transaction).unwrap();
+        assert_eq!(export.graphs, vec![format!("{}{}", TRANSACTION_GRAPH, tx1), format!("{}{}", TRANSACTION_GRAPH, tx2)]);
+        assert!(export.content.contains(&format!(
+            "<{}{}> <http://purl.org/dc/terms/source> <http://foundation.local/origin/user-edit> .\n",
+            TRANSACTION_GRAPH, tx2
+        )));
+        assert!(export.content.contains(&format!("<{}{}> <http://purl.org/dc/terms/created> \"", TRANSACTION_GRAPH, tx1)));
+    }
+
+    #[test]
+    fn test_stored_named_graphs() {
+        let mut conn = setup_test_db();
+        setup(&mut conn);
+        store::assert_triples(&mut conn, &[
+            Triple::new("foundation:Phone", "rdf:type", Object::Iri("foundation:Computer".to_string()))
+                .in_graph("foundation:PeerGraph"),
+        ], "sync").unwrap();
+
+        let export = export_nquads(&conn, None, GraphMode::Stored).unwrap();
+        assert_eq!(export.quads, 4);
+        assert_eq!(export.graphs, vec![
+            "http://foundation.local/ontology/DefaultGraph",
+            "http://foundation.local/ontology/PeerGraph",
+        ]);
+    }
+}
@@ -0,0 +1,476 @@
+// ============================================================================
+// RDF/XML Export (Protégé profile)
+// ============================================================================
+// Writes triples as RDF/XML that Protégé opens without warnings:
+// - A single owl:Ontology header (IRI, version IRI, owl:imports)
+// - Explicit declarations for every class, property and individual used
+// - Foreign ontology headers are left out (they belong to their own files)
+// - The licenses of the exported sources are listed on the header
+//
+// Compressed IRIs (e.g., "foundation:Computer") are expanded on output.
+// ============================================================================
+
+use rusqlite::Connection;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use crate::eavto::{query, statement, Triple, Object};
+use crate::namespaces::{expand_iri, prefixes};
+use crate::owl::license::{self, SourceLicense};
+use crate::owl::vocabulary::{rdf, rdfs, owl, dcterms};
+
+type Result<T> = std::result::Result<T, Box<dyn std::error::Error>>;
+
+/// Ontology header written at the top of the document
+#[derive(Debug, Clone)]
+pub struct OntologyHeader {
+    pub iri: String,
+    pub version_iri: Option<String>,
+    pub imports: Vec<String>,
+    /// Sources included in the export, with their declared licenses
+    pub sources: Vec<SourceLicense>,
+}
+
+/// Export current triples (optionally limited to one origin) as RDF/XML
+pub fn export_rdfxml(conn: &Connection, origin: Option<&str>, header: &OntologyHeader) -> Result<String> {
+    let result = match origin {
+        Some(name) => {
+            let origin_id = query::get_origin_id(conn, name)?
+                .ok_or_else(|| format!("Origin {} not found", name))?;
+            query::get_by_origin(conn, origin_id)?
+        }
+        None => query::get_all(conn)?,
+    };
+
+    Ok(write_rdfxml(&result.triples, header))
+}
+
+/// Entity kinds that need an explicit declaration
+const DECLARATION_TYPES: &[&str] = &[
+    owl::CLASS,
+    rdfs::CLASS,
+    owl::OBJECT_PROPERTY,
+    owl::DATATYPE_PROPERTY,
+    owl::ANNOTATION_PROPERTY,
+    rdfs::DATATYPE,
+    "owl:NamedIndividual",
+];
+
+/// Serialize triples as RDF/XML
+pub fn write_rdfxml(triples: &[Triple], header: &OntologyHeader) -> String {
+    // Leave out foreign ontology headers
+    let foreign_ontologies: BTreeSet<&str> = triples.iter()
+        .filter(|t| t.predicate == rdf::TYPE && t.object.as_iri() == Some(owl::ONTOLOGY))
+        .map(|t| t.subject.as_str())
+        .filter(|s| expand_iri(s) != header.iri)
+        .collect();
+    let triples: Vec<&Triple> = triples.iter()
+        .filter(|t| !foreign_ontologies.contains(t.subject.as_str()))
+        .collect();
+
+    let declarations = collect_declarations(&triples);
+    let mut namespaces = Namespaces::new(&triples);
+    let licenses = distinct_licenses(&header.sources);
+    if !licenses.is_empty() {
+        namespaces.ensure("dcterms", "http://purl.org/dc/terms/");
+    }
+
+    let mut out = String::new();
+    out.push_str("<?xml version=\"1.0\"?>\n");
+    out.push_str(&format!("<rdf:RDF xml:base=\"{}\"", escape(&header.iri)));
+    for (prefix, namespace) in &namespaces.used {
+        out.push_str(&format!("\n     xmlns:{}=\"{}\"", prefix, escape(namespace)));
+    }
+    out.push_str(">\n");
+
+    // Ontology header
+    out.push_str(&format!("    <owl:Ontology rdf:about=\"{}\">\n", escape(&header.iri)));
+    if let Some(version_iri) = &header.version_iri {
+        out.push_str(&format!("        <owl:versionIRI rdf:resource=\"{}\"/>\n", escape(&expand_iri(version_iri))));
+    }
+    for import in &header.imports {
+        out.push_str(&format!("        <owl:imports rdf:resource=\"{}\"/>\n", escape(&expand_iri(import))));
+    }
+    if !header.sources.is_empty() {
+        out.push_str("        <!-- Sources:\n");
+        for source in &header.sources {
+            let license = source.license.as_deref()
+                .map(license::short_name)
+                .unwrap_or_else(|| "no license declared".to_string());
+            out.push_str(&format!("             {} ({})\n", comment(&source.source), comment(&license)));
+        }
+        out.push_str("        -->\n");
+    }
+    for license in licenses {
+        let (predicate, object) = if license.contains("://") {
+            (dcterms::LICENSE, Object::Iri(license))
+        } else {
+            (dcterms::RIGHTS, Object::Literal { value: license, datatype: None, language: None })
+        };
+        if let Some(element) = namespaces.qname(predicate) {
+            out.push_str(&format!("        {}\n", property_element(&element, &object)));
+        }
+    }
+    out.push_str("    </owl:Ontology>\n");
+
+    // Declarations for entities that are used without being typed
+    if !declarations.is_empty() {
+        out.push_str("\n    <!-- Declarations -->\n");
+        for (entity, kind) in &declarations {
+            out.push_str(&format!(
+                "    <rdf:Description rdf:about=\"{}\">\n        <rdf:type rdf:resource=\"{}\"/>\n    </rdf:Description>\n",
+                escape(&expand_iri(entity)),
+                escape(&expand_iri(kind))
+            ));
+        }
+    }
+
+    // Descriptions grouped by subject
+    let mut by_subject: BTreeMap<&str, Vec<&Triple>> = BTreeMap::new();
+    for triple in &triples {
+        by_subject.entry(triple.subject.as_str()).or_default().push(triple);
+    }
+
+    for (subject, triples) in by_subject {
+        out.push('\n');
+        if statement::is_statement(subject) {
+            out.push_str(&format!("    <!-- Skipped {} triples about a quoted triple, which RDF/XML can't express -->\n", triples.len()));
+            continue;
+        }
+        match subject.strip_prefix("_:") {
+            Some(node_id) => out.push_str(&format!("    <rdf:Description rdf:nodeID=\"{}\">\n", escape(node_id))),
+            None => out.push_str(&format!("    <rdf:Description rdf:about=\"{}\">\n", escape(&expand_iri(subject)))),
+        }
+
+        for triple in triples {
+            let Some(element) = namespaces.qname(&triple.predicate) else {
+                out.push_str(&format!("        <!-- Skipped predicate without a valid XML name: {} -->\n", escape(&triple.predicate)));
+                continue;
+            };
+            out.push_str(&format!("        {}\n", property_element(&element, &triple.object)));
+        }
+
+        out.push_str("    </rdf:Description>\n");
+    }
+
+    out.push_str("</rdf:RDF>\n");
+    out
+}
+
+/// Declared licenses of the sources, without duplicates, in source order
+fn distinct_licenses(sources: &[SourceLicense]) -> Vec<String> {
+    let mut licenses: Vec<String> = Vec::new();
+    for license in sources.iter().filter_map(|s| s.license.as_deref()) {
+        let license = if license.contains("://") { expand_iri(license) } else { license.to_string() };
+        if !licenses.contains(&license) {
+            licenses.push(license);
+        }
+    }
+    licenses
+}
+
+/// Find entities that Protégé would otherwise see as undeclared
+fn collect_declarations<'a>(triples: &[&'a Triple]) -> BTreeMap<&'a str, &'static str> {
+    let mut typed: HashMap<&str, BTreeSet<&str>> = HashMap::new();
+    for triple in triples {
+        if triple.predicate == rdf::TYPE {
+            if let Some(type_iri) = triple.object.as_iri() {
+                typed.entry(triple.subject.as_str()).or_default().insert(type_iri);
+            }
+        }
+    }
+
+    let is_declared = |entity: &str| -> bool {
+        typed.get(entity)
+            .map(|types| types.iter().any(|t| DECLARATION_TYPES.contains(t)))
+            .unwrap_or(false)
+    };
+
+    let mut declarations = BTreeMap::new();
+
+    // Properties: object-valued ones become object properties, others annotation properties
+    let mut property_objects: BTreeMap<&str, (bool, bool)> = BTreeMap::new();
+    for triple in triples {
+        if is_builtin(&triple.predicate) {
+            continue;
+        }
+        let entry = property_objects.entry(triple.predicate.as_str()).or_default();
+        if triple.object.is_literal() {
+            entry.0 = true;
+        } else {
+            entry.1 = true;
+        }
+    }
+    for (property, (has_literals, has_resources)) in property_objects {
+        if is_declared(property) {
+            continue;
+        }
+        let kind = if has_resources && !has_literals {
+            owl::OBJECT_PROPERTY
+        } else {
+            owl::ANNOTATION_PROPERTY
+        };
+        declarations.insert(property, kind);
+    }
+
+    // Classes referenced by typing and hierarchy axioms
+    for triple in triples {
+        let class = match triple.predicate.as_str() {
+            p if p == rdf::TYPE || p == rdfs::SUB_CLASS_OF || p == rdfs::DOMAIN => triple.object.as_iri(),
+            _ => None,
+        };
+        if let Some(class) = class {
+            if !class.starts_with("_:") && !is_builtin(class) && !is_declared(class) {
+                declarations.insert(class, owl::CLASS);
+            }
+        }
+        if triple.predicate == rdfs::SUB_CLASS_OF && !triple.subject.starts_with("_:") && !is_declared(&triple.subject) {
+            declarations.insert(triple.subject.as_str(), owl::CLASS);
+        }
+    }
+
+    // Individuals: typed with a non-builtin class
+    for (entity, types) in &typed {
+        if entity.starts_with("_:") || is_declared(entity) || declarations.contains_key(entity) {
+            continue;
+        }
+        if types.iter().any(|t| !is_builtin(t)) {
+            declarations.insert(*entity, "owl:NamedIndividual");
+        }
+    }
+
+    declarations
+}
+
+/// Vocabulary that Protégé already knows about
+fn is_builtin(iri: &str) -> bool {
+    ["rdf:", "rdfs:", "owl:", "xsd:"].iter().any(|prefix| iri.starts_with(prefix))
+}
+
+/// XML namespace bookkeeping for property elements
+struct Namespaces {
+    /// prefix -> namespace, for every namespace used in property elements
+    used: BTreeMap<String, String>,
+}
+
+impl Namespaces {
+    fn new(triples: &[&Triple]) -> Self {
+        let mut used: BTreeMap<String, String> = BTreeMap::new();
+        for (prefix, namespace) in [
+            ("rdf", "http://www.w3.org/1999/02/22-rdf-syntax-ns#"),
+            ("rdfs", "http://www.w3.org/2000/01/rdf-schema#"),
+            ("owl", "http://www.w3.org/2002/07/owl#"),
+            ("xsd", "http://www.w3.org/2001/XMLSchema#"),
+        ] {
+            used.insert(prefix.to_string(), namespace.to_string());
+        }
+
+        let known = prefixes();
+        let mut generated = 0;
+        for triple in triples {
+            let Some((namespace, _)) = split_iri(&expand_iri(&triple.predicate)) else {
+                continue;
+            };
+            if used.values().any(|n| *n == namespace) {
+                continue;
+            }
+            let prefix = match known.iter().find(|(_, n)| *n == namespace) {
+                Some((prefix, _)) => prefix.to_string(),
+                None => {
+                    generated += 1;
+                    format!("ns{}", generated)
+                }
+            };
+            used.insert(prefix, namespace);
+        }
+
+        Self { used }
+    }
+
+    /// Declare a namespace unless it is already declared
+    fn ensure(&mut self, prefix: &str, namespace: &str) {
+        if !self.used.values().any(|n| n == namespace) {
+            self.used.insert(prefix.to_string(), namespace.to_string());
+        }
+    }
+
+    /// Qualified XML element name for a predicate
+    fn qname(&self, predicate: &str) -> Option<String> {
+        let (namespace, local) = split_iri(&expand_iri(predicate))?;
+        self.used.iter()
+            .find(|(_, n)| **n == namespace)
+            .map(|(prefix, _)| format!("{}:{}", prefix, local))
+    }
+}
+
+/// Split an IRI into namespace and an XML-safe local name
+fn split_iri(iri: &str) -> Option<(String, String)> {
+    let split = iri.rfind(['#', '/'])?;
+    let (namespace, local) = iri.split_at(split + 1);
+
+    let mut chars = local.chars();
+    let valid_start = chars.next().map(|c| c.is_alphabetic() || c == '_').unwrap_or(false);
+    let valid_rest = chars.all(|c| c.is_alphanumeric() || matches!(c, '_' | '-' | '.'));
+
+    if valid_start && valid_rest {
+        Some((namespace.to_string(), local.to_string()))
+    } else {
+        None
+    }
+}
+
+/// Render a single property element
+fn property_element(element: &str, object: &Object) -> String {
+    let typed = |value: String, datatype: &str| {
+        format!(
+            "<{} rdf:datatype=\"{}\">{}</{}>",
+            element, escape(&expand_iri(datatype)), escape(&value), element
+        )
+    };
+
+    match object {
+        Object::Iri(iri) => format!("<{} rdf:resource=\"{}\"/>", element, escape(&expand_iri(iri))),
+        Object::Blank(id) => format!("<{} rdf:nodeID=\"{}\"/>", element, escape(id.trim_start_matches("_:"))),
+        Object::Literal { value, language: Some(lang), .. } => {
+            format!("<{} xml:lang=\"{}\">{}</{}>", element, escape(lang), escape(value), element)
+        }
+        Object::Literal { value, datatype: Some(dt), .. } if dt != "xsd:string" => typed(value.clone(), dt),
+        Object::Literal { value, .. } => format!("<{}>{}</{}>", element, escape(value), element),
+        Object::Integer(i) => typed(i.to_string(), "xsd:integer"),
+        Object::Number(n) => typed(n.to_string(), "xsd:decimal"),
+        Object::Boolean(b) => typed(b.to_string(), "xsd:boolean"),
+        Object::DateTime(ms) => {
+            let value = chrono::DateTime::from_timestamp_millis(*ms)
+                .map(|dt| dt.to_rfc3339())
+                .unwrap_or_else(|| ms.to_string());
+            typed(value, "xsd:dateTime")
+        }
+        Object::Triple(quoted) => format!("<!-- Skipped quoted triple, which RDF/XML can't express: {} -->", comment(&quoted.term())),
+    }
+}
+
+/// Text that is safe inside an XML comment
+fn comment(value: &str) -> String {
+    value.replace("--", "- -")
+}
+
+fn escape(value: &str) -> String {
+    value
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::eavto::{store, test_helpers::setup_test_db};
+
+    fn header() -> OntologyHeader {
+        OntologyHeader {
+            iri: "http://foundation.local/ontology".to_string(),
+            version_iri: Some("http://foundation.local/ontology/1.0".to_string()),
+            imports: vec!["http://www.w3.org/2004/02/skos/core".to_string()],
+            sources: Vec::new(),
+        }
+    }
+
+    fn literal(value: &str) -> Object {
+        Object::Literal { value: value.to_string(), datatype: Some("xsd:string".to_string()), language: None }
+    }
+
+    #[test]
+    fn test_header_and_imports() {
+        let xml = write_rdfxml(&[], &header());
+        assert!(xml.contains("<owl:Ontology rdf:about=\"http://foundation.local/ontology\">"));
+        assert!(xml.contains("<owl:versionIRI rdf:resource=\"http://foundation.local/ontology/1.0\"/>"));
+        assert!(xml.contains("<owl:imports rdf:resource=\"http://www.w3.org/2004/02/skos/core\"/>"));
+        assert!(xml.trim_end().ends_with("</rdf:RDF>"));
+    }
+
+    #[test]
+    fn test_header_lists_source_licenses() {
+        let mut header = header();
+        header.sources = vec![
+            SourceLicense {
+                origin: "import:bfo.owl".to_string(),
+                source: "BFO".to_string(),
+                license: Some("http://creativecommons.org/licenses/by/4.0/".to_string()),
+            },
+            SourceLicense {
+                origin: "import:units.ttl".to_string(),
+                source: "Units".to_string(),
+                license: Some("CC0-1.0".to_string()),
+            },
+            SourceLicense { origin: "user-edit".to_string(), source: "user-edit".to_string(), license: None },
+        ];
+
+        let xml = write_rdfxml(&[], &header);
+        assert!(xml.contains("xmlns:dcterms=\"http://purl.org/dc/terms/\""));
+        assert!(xml.contains("BFO (CC-BY)"));
+        assert!(xml.contains("user-edit (no license declared)"));
+        assert!(xml.contains("<dcterms:license rdf:resource=\"http://creativecommons.org/licenses/by/4.0/\"/>"));
+        assert!(xml.contains("<dcterms:rights>CC0-1.0</dcterms:rights>"));
+    }
+
+    #[test]
+    fn test_declares_undeclared_entities() {
+        let triples = [
+            Triple::new("foundation:Computer", rdf::TYPE, Object::Iri(owl::CLASS.to_string())),
+            Triple::new("foundation:Laptop", rdfs::SUB_CLASS_OF, Object::Iri("foundation:Computer".to_string())),
+            Triple::new("foundation:MyLaptop", rdf::TYPE, Object::Iri("foundation:Laptop".to_string())),
+            Triple::new("foundation:MyLaptop", "foundation:owner", Object::Iri("foundation:Alice".to_string())),
+            Triple::new("foundation:MyLaptop", "foundation:icon", literal("laptop")),
+        ];
+
+        let declarations = collect_declarations(&triples.iter().collect::<Vec<_>>());
+        assert_eq!(declarations.get("foundation:Laptop"), Some(&owl::CLASS));
+        assert_eq!(declarations.get("foundation:owner"), Some(&owl::OBJECT_PROPERTY));
+        assert_eq!(declarations.get("foundation:icon"), Some(&owl::ANNOTATION_PROPERTY));
+        assert_eq!(declarations.get("foundation:MyLaptop"), Some(&"owl:NamedIndividual"));
+        assert!(!declarations.contains_key("foundation:Computer"));
+    }
+
+    #[test]
+    fn test_property_elements_and_escaping() {
+        let triples = vec![
+            Triple::new("foundation:Note", rdfs::LABEL, literal("Fish & <Chips>")),
+            Triple::new("foundation:Note", "foundation:count", Object::Integer(3)),
+            Triple::new("foundation:Note", "foundation:part", Object::Blank("_:b1".to_string())),
+        ];
+
+        let xml = write_rdfxml(&triples, &header());
+        assert!(xml.contains("xmlns:foundation=\"http://foundation.local/ontology/\""));
+        assert!(xml.contains("<rdfs:label>Fish &amp; &lt;Chips&gt;</rdfs:label>"));
+        assert!(xml.contains("<foundation:count rdf:datatype=\"http://www.w3.org/2001/XMLSchema#integer\">3</foundation:count>"));
+        assert!(xml.contains("<foundation:part rdf:nodeID=\"b1\"/>"));
+    }
+
+    #[test]
+    fn test_skips_foreign_ontology_headers() {
+        let triples = vec![
+            Triple::new("http://qudt.org/2.1/vocab/unit", rdf::TYPE, Object::Iri(owl::ONTOLOGY.to_string())),
+            Triple::new("http://qudt.org/2.1/vocab/unit", rdfs::LABEL, literal("QUDT Units")),
+        ];
+
+        let xml = write_rdfxml(&triples, &header());
+        assert_eq!(xml.matches("owl:Ontology").count(), 2); // open + close tag of our header
+        assert!(!xml.contains("QUDT Units"));
+    }
+
+    #[test]
+    fn test_export_by_origin() {
+        let mut conn = setup_test_db();
+        store::assert_triples(&mut conn, &[
+            Triple::new("foundation:A", rdfs::LABEL, literal("From test")),
+        ], "test").unwrap();
+        store::assert_triples(&mut conn, &[
+            Triple::new("foundation:B", rdfs::LABEL, literal("From other")),
+        ], "other").unwrap();
+
+        let xml = export_rdfxml(&conn, Some("test"), &header()).unwrap();
+        assert!(xml.contains("From test"));
+        assert!(!xml.contains("From other"));
+
+        assert!(export_rdfxml(&conn, Some("missing"), &header()).is_err());
+    }
+}
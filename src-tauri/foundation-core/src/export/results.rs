@@ -0,0 +1,225 @@
+// ============================================================================
+// Query Results Export (CSV / JSON)
+// ============================================================================
+// Writes the results of a pattern query (eavto::pattern) as a table for
+// spreadsheets and analysis tools:
+// - One column per variable, values formatted by type (numbers as numbers,
+//   dates as ISO 8601, IRIs in prefixed form)
+// - A "<variable>_unit" column after each variable bound through a property
+//   with a QUDT unit (qudt:hasUnit), so quantities keep their meaning
+//
+// Rows are written to the file as they are formatted, not built in memory
+// first.
+// ============================================================================
+
+use rusqlite::Connection;
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::Path;
+use crate::eavto::{pattern::{self, PatternQuery, Term}, query, Object};
+
+type Result<T> = std::result::Result<T, Box<dyn std::error::Error>>;
+
+const HAS_UNIT: &str = "qudt:hasUnit";
+
+/// Output format
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ResultFormat {
+    Csv,
+    Json, // Array of objects keyed by column
+}
+
+impl ResultFormat {
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.to_ascii_lowercase().as_str() {
+            "csv" => Some(ResultFormat::Csv),
+            "json" => Some(ResultFormat::Json),
+            _ => None,
+        }
+    }
+}
+
+/// Summary of a written export
+#[derive(Debug, Clone, PartialEq)]
+pub struct ResultsExport {
+    pub columns: Vec<String>,
+    pub rows: usize,
+}
+
+/// Run a query and write its results to a file
+pub fn export_query_results(conn: &Connection, text: &str, format: ResultFormat, path: &Path) -> Result<ResultsExport> {
+    let query = pattern::parse(text)?;
+    let result = pattern::evaluate(conn, &query, None)?;
+    let units = units(conn, &query)?;
+
+    let mut columns = Vec::new();
+    for variable in &result.variables {
+        columns.push(variable.clone());
+        if units.contains_key(variable) {
+            columns.push(format!("{}_unit", variable));
+        }
+    }
+
+    // Cells per row, in column order
+    let rows = result.rows.iter().map(|row| {
+        let mut cells = Vec::with_capacity(columns.len());
+        for (variable, value) in result.variables.iter().zip(row) {
+            cells.push(value.clone());
+            if let Some(unit) = units.get(variable) {
+                cells.push(Object::Iri(unit.clone()));
+            }
+        }
+        cells
+    });
+
+    let mut out = BufWriter::new(File::create(path)?);
+    match format {
+        ResultFormat::Csv => write_csv(&mut out, &columns, rows)?,
+        ResultFormat::Json => write_json(&mut out, &columns, rows)?,
+    }
+    out.flush()?;
+
+    Ok(ResultsExport { columns, rows: result.rows.len() })
+}
+
+/// Units of variables bound as values of a property with a QUDT unit
+fn units(conn: &Connection, query: &PatternQuery) -> Result<HashMap<String, String>> {
+    let mut units = HashMap::new();
+    for pattern in &query.patterns {
+        if let (Term::Value(Object::Iri(property)), Term::Var(variable)) = (&pattern.predicate, &pattern.object) {
+            if units.contains_key(variable) {
+                continue;
+            }
+            let unit = query::get_by_entity_predicate(conn, property, HAS_UNIT)?
+                .triples.first()
+                .and_then(|t| t.object.as_iri().map(String::from));
+            if let Some(unit) = unit {
+                units.insert(variable.clone(), unit);
+            }
+        }
+    }
+    Ok(units)
+}
+
+/// Text of a value in a cell
+fn text(value: &Object) -> String {
+    match value {
+        Object::DateTime(ms) => chrono::DateTime::from_timestamp_millis(*ms)
+            .map(|dt| dt.to_rfc3339_opts(chrono::SecondsFormat::AutoSi, true))
+            .unwrap_or_else(|| ms.to_string()),
+        other => other.as_iri().map(String::from)
+            .or_else(|| other.as_literal())
+            .unwrap_or_default(),
+    }
+}
+
+/// CSV (RFC 4180): a header row, then one row per result
+fn write_csv(
+    out: &mut impl Write,
+    columns: &[String],
+    rows: impl Iterator<Item = Vec<Object>>,
+) -> std::io::Result<()> {
+    let line = |cells: Vec<String>| cells.iter().map(|c| csv_field(c)).collect::<Vec<_>>().join(",");
+
+    writeln!(out, "{}", line(columns.to_vec()))?;
+    for row in rows {
+        let cells = row.iter().map(text).collect();
+        writeln!(out, "{}", line(cells))?;
+    }
+    Ok(())
+}
+
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+/// JSON: an array with one object per result, keyed by column
+fn write_json(
+    out: &mut impl Write,
+    columns: &[String],
+    rows: impl Iterator<Item = Vec<Object>>,
+) -> std::io::Result<()> {
+    write!(out, "[")?;
+    for (i, row) in rows.enumerate() {
+        let object: serde_json::Map<String, serde_json::Value> = columns.iter()
+            .zip(row)
+            .map(|(column, cell)| (column.clone(), json(&cell)))
+            .collect();
+        write!(out, "{}\n  ", if i == 0 { "" } else { "," })?;
+        serde_json::to_writer(&mut *out, &object)?;
+    }
+    writeln!(out, "\n]")?;
+    Ok(())
+}
+
+/// JSON value of an object: numbers and booleans native, other values as text
+pub fn json(value: &Object) -> serde_json::Value {
+    match value {
+        Object::Integer(i) => (*i).into(),
+        Object::Number(n) => serde_json::Number::from_f64(*n)
+            .map(serde_json::Value::Number)
+            .unwrap_or(serde_json::Value::Null),
+        Object::Boolean(b) => (*b).into(),
+        other => text(other).into(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::eavto::{store, test_helpers::setup_test_db, Triple};
+
+    fn setup(conn: &mut Connection) {
+        store::assert_triples(conn, &[
+            Triple::new("foundation:memorySize", HAS_UNIT, Object::Iri("unit:GigaBYTE".to_string())),
+            Triple::new("foundation:Laptop", "rdfs:label", Object::Literal {
+                value: "Laptop, \"work\"".to_string(), datatype: Some("xsd:string".to_string()), language: None,
+            }),
+            Triple::new("foundation:Laptop", "foundation:memorySize", Object::Number(16.5)),
+            Triple::new("foundation:Laptop", "foundation:purchased", Object::DateTime(0)),
+        ], "test").unwrap();
+    }
+
+    const QUERY: &str = "SELECT ?label ?memory ?date WHERE {
+        ?c rdfs:label ?label ; foundation:memorySize ?memory ; foundation:purchased ?date .
+    }";
+
+    #[test]
+    fn test_csv_with_unit_columns() {
+        let mut conn = setup_test_db();
+        setup(&mut conn);
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("results.csv");
+
+        let export = export_query_results(&conn, QUERY, ResultFormat::Csv, &path).unwrap();
+        assert_eq!(export.columns, vec!["label", "memory", "memory_unit", "date"]);
+        assert_eq!(export.rows, 1);
+
+        let csv = std::fs::read_to_string(&path).unwrap();
+        assert_eq!(csv, "label,memory,memory_unit,date\n\
+                         \"Laptop, \"\"work\"\"\",16.5,unit:GigaBYTE,1970-01-01T00:00:00Z\n");
+    }
+
+    #[test]
+    fn test_json_keeps_types() {
+        let mut conn = setup_test_db();
+        setup(&mut conn);
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("results.json");
+
+        export_query_results(&conn, QUERY, ResultFormat::Json, &path).unwrap();
+
+        let json: serde_json::Value = serde_json::from_str(&std::fs::read_to_string(&path).unwrap()).unwrap();
+        assert_eq!(json, serde_json::json!([{
+            "label": "Laptop, \"work\"",
+            "memory": 16.5,
+            "memory_unit": "unit:GigaBYTE",
+            "date": "1970-01-01T00:00:00Z",
+        }]));
+    }
+}
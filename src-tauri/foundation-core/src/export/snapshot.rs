@@ -0,0 +1,195 @@
+// ============================================================================
+// Snapshot Export (the store as of a transaction)
+// ============================================================================
+// Nothing is ever deleted from the store: a retraction marks a triple with
+// the transaction that retracted it. So the store as it was after any
+// transaction is still in it, the triples asserted by then and not yet
+// retracted, and can be written out to share or debug a past state:
+// - SQLite: a copy of the database rolled back to the transaction, which
+//   opens like any FOUNDATION database (history up to the transaction
+//   included). Device state (API tokens, queued jobs, the write journal)
+//   is left out, and so is anything only later triples used (terms, quoted
+//   triples, spilled literals, attachment text, origins).
+// - Turtle: the triples current as of the transaction that normal queries
+//   see (not quarantined), as one file any RDF tool reads (without their
+//   history or origins).
+//
+// Triples retracted before schema version 8 have no retracting transaction;
+// they stay retracted in the SQLite copy and are left out of Turtle.
+// ============================================================================
+
+use rusqlite::Connection;
+use std::path::Path;
+use crate::eavto::{encryption, query};
+use crate::export::turtle::{write_turtle, OntologyMetadata};
+
+type Result<T> = std::result::Result<T, Box<dyn std::error::Error>>;
+
+/// Output format
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SnapshotFormat {
+    Sqlite,
+    Turtle,
+}
+
+impl SnapshotFormat {
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.to_ascii_lowercase().as_str() {
+            "sqlite" => Some(SnapshotFormat::Sqlite),
+            "turtle" | "ttl" => Some(SnapshotFormat::Turtle),
+            _ => None,
+        }
+    }
+}
+
+/// Summary of a written snapshot
+#[derive(Debug, Clone, PartialEq)]
+pub struct SnapshotExport {
+    pub tx: i64,
+    pub triples: usize, // Current as of the transaction
+}
+
+/// Write the store as of a transaction to a new file
+pub fn export_snapshot(conn: &Connection, tx: i64, path: &Path, format: SnapshotFormat) -> Result<SnapshotExport> {
+    let latest = query::get_latest_tx(conn)?;
+    if !(1..=latest).contains(&tx) {
+        return Err(format!("Transaction {} not found (latest is {})", tx, latest).into());
+    }
+    if path.exists() {
+        return Err(format!("{} already exists", path.display()).into());
+    }
+
+    let triples = query::get_as_of(conn, tx)?.triples;
+    match format {
+        SnapshotFormat::Sqlite => write_database(conn, tx, path)?,
+        SnapshotFormat::Turtle => {
+            let metadata = OntologyMetadata {
+                iri: Some(format!("http://foundation.local/snapshot/{}", tx)),
+                title: Some(format!("Snapshot as of transaction {}", tx)),
+                ..Default::default()
+            };
+            std::fs::write(path, write_turtle(&triples, &metadata))?;
+        }
+    }
+
+    Ok(SnapshotExport { tx, triples: triples.len() })
+}
+
+/// Copy the database to `path` and roll the copy back to the transaction
+fn write_database(conn: &Connection, tx: i64, path: &Path) -> Result<()> {
+    conn.execute("VACUUM INTO ?1", [path.to_string_lossy()])?;
+
+    let mut copy = encryption::open(path)?;
+    let rollback = copy.transaction()?;
+    rollback.execute("DELETE FROM quads WHERE tx > ?1", [tx])?;
+    rollback.execute("UPDATE quads SET retracted = 0, retracted_tx = NULL WHERE retracted_tx > ?1", [tx])?;
+    rollback.execute("DELETE FROM transactions WHERE tx > ?1", [tx])?;
+    rollback.execute("DELETE FROM sync_changesets WHERE tx IS NULL OR tx > ?1", [tx])?;
+    rollback.execute_batch(
+        "DELETE FROM api_tokens;
+         DELETE FROM outbox;
+         DELETE FROM write_journal;",
+    )?;
+
+    // What only later triples used: quoted triples (nested ones once their
+    // quoting statement is gone), terms, spilled literals, attachment text
+    // and origins
+    while rollback.execute(
+        "DELETE FROM statements
+         WHERE id NOT IN (SELECT subject_id FROM quads UNION SELECT object_id FROM quads WHERE object_id IS NOT NULL)
+           AND id NOT IN (SELECT subject_id FROM statements UNION SELECT object_id FROM statements WHERE object_id IS NOT NULL)",
+        [],
+    )? > 0 {}
+    rollback.execute_batch(
+        "DELETE FROM terms
+         WHERE id NOT IN (SELECT subject_id FROM quads UNION SELECT predicate_id FROM quads
+                          UNION SELECT object_id FROM quads WHERE object_id IS NOT NULL
+                          UNION SELECT id FROM statements UNION SELECT subject_id FROM statements
+                          UNION SELECT predicate_id FROM statements
+                          UNION SELECT object_id FROM statements WHERE object_id IS NOT NULL);
+         DELETE FROM blobs WHERE 'blob:' || hash NOT IN (SELECT value FROM terms);
+         DELETE FROM attachment_text
+         WHERE entity NOT IN (SELECT t.value FROM quads q JOIN terms t ON t.id = q.subject_id);
+         DELETE FROM origins
+         WHERE id NOT IN (SELECT origin_id FROM quads) AND name NOT IN (SELECT origin FROM transactions);",
+    )?;
+    rollback.commit()?;
+
+    // Reclaim the space of what was rolled back
+    copy.execute_batch("VACUUM")?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::eavto::{blob, quarantine, store, test_helpers::setup_test_db, Object, QuotedTriple, Triple};
+    use tempfile::TempDir;
+
+    fn name(value: &str) -> Object {
+        Object::Literal { value: value.to_string(), datatype: None, language: None }
+    }
+
+    #[test]
+    fn test_snapshot_as_of_a_transaction() {
+        let mut conn = setup_test_db();
+        let car = |value: &str| Triple::new("foundation:myCar", "foundation:name", name(value));
+        let first = store::assert_triples(&mut conn, &[car("Beetle")], "test").unwrap();
+        let renamed = store::apply_changes(&mut conn, &[car("Herbie")], &[car("Beetle")], "test").unwrap();
+
+        let as_of = |tx| query::get_as_of(&conn, tx).unwrap().triples;
+        assert_eq!(as_of(first)[0].object, name("Beetle"));
+        assert_eq!(as_of(renamed)[0].object, name("Herbie"));
+
+        let dir = TempDir::new().unwrap();
+        let ttl = dir.path().join("snapshot.ttl");
+        let export = export_snapshot(&conn, first, &ttl, SnapshotFormat::Turtle).unwrap();
+        assert_eq!(export.triples, 1);
+        let content = std::fs::read_to_string(&ttl).unwrap();
+        assert!(content.contains("\"Beetle\"") && !content.contains("Herbie"));
+
+        let db = dir.path().join("snapshot.db");
+        export_snapshot(&conn, first, &db, SnapshotFormat::Sqlite).unwrap();
+        let copy = Connection::open(&db).unwrap();
+        assert_eq!(query::get_latest_tx(&copy).unwrap(), first);
+        let current = query::get_by_entity(&copy, "foundation:myCar").unwrap().triples;
+        assert_eq!((current.len(), &current[0].object), (1, &name("Beetle")));
+
+        // Existing files are not overwritten; unknown transactions are errors
+        assert!(export_snapshot(&conn, first, &ttl, SnapshotFormat::Turtle).is_err());
+        assert!(export_snapshot(&conn, renamed + 1, &dir.path().join("later.ttl"), SnapshotFormat::Turtle).is_err());
+    }
+
+    #[test]
+    fn test_snapshot_leaves_out_what_came_later() {
+        let mut conn = setup_test_db();
+        let car = |predicate: &str, object: Object| Triple::new("foundation:myCar", predicate, object);
+        let first = store::assert_triples(&mut conn, &[car("foundation:name", name("Beetle"))], "test").unwrap();
+
+        // After the snapshot: a spilled literal, a quoted triple, attachment
+        // text and a new origin, all with text that must not be exported
+        blob::set_literal_limit(&conn, Some(16)).unwrap();
+        let secret = QuotedTriple::new("foundation:myCar", "foundation:owner", name("LaterSecretOwner"));
+        store::assert_triples(&mut conn, &[
+            car("foundation:notes", name("LaterSecretNotes that spill to a blob")),
+            Triple::new(secret.term(), "foundation:confidence", Object::Number(0.5)),
+        ], "later-origin").unwrap();
+        conn.execute("INSERT INTO attachment_text (entity, text) VALUES ('foundation:laterFile', 'LaterSecretText')", []).unwrap();
+
+        let dir = TempDir::new().unwrap();
+        let db = dir.path().join("snapshot.db");
+        export_snapshot(&conn, first, &db, SnapshotFormat::Sqlite).unwrap();
+        let bytes = std::fs::read(&db).unwrap();
+        for text in ["LaterSecret", "later-origin"] {
+            assert!(!bytes.windows(text.len()).any(|w| w == text.as_bytes()), "{} leaked", text);
+        }
+
+        // Quarantined triples are left out of Turtle
+        quarantine::quarantine(&conn, "peer").unwrap();
+        let latest = store::assert_triples(&mut conn, &[car("foundation:color", name("QuarantinedColor"))], "peer").unwrap();
+        let ttl = dir.path().join("snapshot.ttl");
+        export_snapshot(&conn, latest, &ttl, SnapshotFormat::Turtle).unwrap();
+        let content = std::fs::read_to_string(&ttl).unwrap();
+        assert!(content.contains("Beetle") && !content.contains("QuarantinedColor"));
+    }
+}
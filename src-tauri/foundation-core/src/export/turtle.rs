@@ -0,0 +1,397 @@
+// ============================================================================
+// Turtle Export (standalone ontology files)
+// ============================================================================
+// Writes the triples of one origin as a self-contained Turtle file that
+// other FOUNDATION users can import:
+// - A single owl:Ontology header built from the given metadata, falling back
+//   to the origin's own header (title, version, license, imports)
+// - @prefix declarations for every namespace used
+// - Terms the file uses but neither defines nor gets from the FOUNDATION
+//   core ontology are reported, so the model can be completed or given an
+//   owl:imports before it is shared
+//
+// Compressed IRIs (e.g., "foundation:Computer") are written as prefixed
+// names when valid in Turtle, as full IRIs otherwise.
+// ============================================================================
+
+use rusqlite::Connection;
+use std::collections::{BTreeMap, BTreeSet};
+use crate::eavto::{canonical::escape, query, statement, Object, QuotedTriple, Triple};
+use crate::namespaces::{expand_iri, prefixes};
+use crate::owl::Ontology;
+use crate::owl::vocabulary::{rdf, owl, dcterms};
+
+type Result<T> = std::result::Result<T, Box<dyn std::error::Error>>;
+
+/// Metadata for the owl:Ontology header of an exported file
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct OntologyMetadata {
+    pub iri: Option<String>, // Default: http://foundation.local/ontology/{origin}
+    pub version_iri: Option<String>,
+    pub version_info: Option<String>,
+    pub title: Option<String>,
+    pub description: Option<String>,
+    pub creators: Vec<String>,
+    pub license: Option<String>, // License IRI or name
+    pub imports: Vec<String>,
+}
+
+/// An origin exported as an ontology file
+#[derive(Debug, Clone)]
+pub struct OntologyFile {
+    pub iri: String,
+    pub content: String,
+    pub external_terms: Vec<String>, // Used but not defined in the file or the core ontology
+}
+
+/// Export the current triples of an origin as a standalone Turtle ontology
+pub fn export_origin_as_ontology(conn: &Connection, origin: &str, metadata: &OntologyMetadata) -> Result<OntologyFile> {
+    let origin_id = query::get_origin_id(conn, origin)?
+        .ok_or_else(|| format!("Origin {} not found", origin))?;
+    let triples = query::get_by_origin(conn, origin_id)?.triples;
+
+    // The origin's own header supplies anything the metadata leaves out
+    let own_header = Ontology::list(conn)?
+        .into_iter()
+        .find(|o| o.origin.as_deref() == Some(origin));
+    let metadata = with_defaults(metadata, own_header.as_ref(), origin);
+
+    // Ontology headers of the origin are replaced by the new one
+    let headers: BTreeSet<&str> = triples.iter()
+        .filter(|t| t.predicate == rdf::TYPE && t.object.as_iri() == Some(owl::ONTOLOGY))
+        .map(|t| t.subject.as_str())
+        .collect();
+    let triples: Vec<Triple> = triples.iter()
+        .filter(|t| !headers.contains(t.subject.as_str()))
+        .cloned()
+        .collect();
+
+    let external_terms = external_terms(conn, &triples)?;
+    let iri = metadata.iri.clone().unwrap_or_default();
+    Ok(OntologyFile {
+        content: write_turtle(&triples, &metadata),
+        iri,
+        external_terms,
+    })
+}
+
+fn with_defaults(metadata: &OntologyMetadata, header: Option<&Ontology>, origin: &str) -> OntologyMetadata {
+    let mut metadata = metadata.clone();
+    if let Some(header) = header {
+        metadata.iri = metadata.iri.or_else(|| Some(expand_iri(&header.iri)));
+        metadata.version_iri = metadata.version_iri.or_else(|| header.version_iri.clone());
+        metadata.version_info = metadata.version_info.or_else(|| header.version_info.clone());
+        metadata.title = metadata.title.or_else(|| header.title.clone());
+        metadata.license = metadata.license.or_else(|| header.license.clone());
+        if metadata.creators.is_empty() {
+            metadata.creators = header.creators.clone();
+        }
+        if metadata.imports.is_empty() {
+            metadata.imports = header.imports.clone();
+        }
+    }
+    if metadata.iri.is_none() {
+        metadata.iri = Some(format!("http://foundation.local/ontology/{}", local_name(origin)));
+    }
+    metadata
+}
+
+/// Origin name as an IRI path segment (e.g., "import:my model.ttl" -> "my_model")
+fn local_name(origin: &str) -> String {
+    let name = origin.rsplit(':').next().unwrap_or(origin);
+    let name = name.rsplit_once('.').map(|(stem, _)| stem).unwrap_or(name);
+    name.chars().map(|c| if c.is_alphanumeric() || c == '-' { c } else { '_' }).collect()
+}
+
+/// Terms used but not defined by the triples nor by the core ontology
+fn external_terms(conn: &Connection, triples: &[Triple]) -> Result<Vec<String>> {
+    let defined: BTreeSet<&str> = triples.iter().map(|t| t.subject.as_str()).collect();
+
+    let mut used: BTreeSet<&str> = BTreeSet::new();
+    for triple in triples {
+        used.insert(triple.predicate.as_str());
+        if let Object::Iri(iri) = &triple.object {
+            used.insert(iri.as_str());
+        }
+        if let Object::Literal { datatype: Some(datatype), .. } = &triple.object {
+            used.insert(datatype.as_str());
+        }
+    }
+
+    let mut external = Vec::new();
+    for term in used {
+        if defined.contains(term) || is_builtin(term) {
+            continue;
+        }
+        let in_core = query::get_by_entity(conn, term)?
+            .triples
+            .iter()
+            .any(|t| query::get_origin_name(conn, t.origin_id).ok().flatten().is_some_and(|o| query::is_core_origin(&o)));
+        if !in_core {
+            external.push(term.to_string());
+        }
+    }
+    Ok(external)
+}
+
+/// Vocabulary every RDF tool knows about
+fn is_builtin(iri: &str) -> bool {
+    ["rdf:", "rdfs:", "owl:", "xsd:"].iter().any(|prefix| iri.starts_with(prefix))
+}
+
+/// Serialize triples as Turtle, after an ontology header
+pub fn write_turtle(triples: &[Triple], metadata: &OntologyMetadata) -> String {
+    let iri = metadata.iri.clone().unwrap_or_default();
+    let header = header_triples(&iri, metadata);
+
+    // Prefixes: always the core vocabularies, plus every known one in use
+    let all_terms = triples.iter().chain(&header).flat_map(|t| {
+        let mut terms = vec![t.subject.as_str(), t.predicate.as_str()];
+        match &t.object {
+            Object::Iri(iri) => terms.push(iri.as_str()),
+            Object::Literal { datatype: Some(dt), .. } => terms.push(dt.as_str()),
+            _ => {}
+        }
+        terms
+    });
+    let mut used: BTreeSet<&str> = ["rdf", "rdfs", "owl", "xsd"].into_iter().collect();
+    for term in all_terms {
+        if let Some((prefix, _)) = term.split_once(':') {
+            used.insert(prefix);
+        }
+    }
+
+    let mut out = String::new();
+    for (prefix, namespace) in prefixes() {
+        if used.contains(prefix) {
+            out.push_str(&format!("@prefix {}: <{}> .\n", prefix, namespace));
+        }
+    }
+
+    // Banner, as in the core ontology files
+    let rule = "# =============================================================================\n";
+    out.push('\n');
+    out.push_str(rule);
+    out.push_str(&format!("# {}\n", metadata.title.as_deref().unwrap_or(&iri)));
+    out.push_str(rule);
+    if let Some(description) = &metadata.description {
+        for line in description.lines() {
+            out.push_str(format!("# {}", line).trim_end());
+            out.push('\n');
+        }
+        out.push_str("#\n");
+    }
+    if let Some(version) = &metadata.version_info {
+        out.push_str(&format!("# Version: {}\n", version));
+    }
+    if let Some(license) = &metadata.license {
+        out.push_str(&format!("# License: {}\n", license));
+    }
+    out.push_str(rule);
+
+    out.push('\n');
+    out.push_str(&write_subject(&iri, &header.iter().collect::<Vec<_>>()));
+
+    let mut by_subject: BTreeMap<&str, Vec<&Triple>> = BTreeMap::new();
+    for triple in triples {
+        by_subject.entry(triple.subject.as_str()).or_default().push(triple);
+    }
+    // Named entities first, then blank nodes
+    let (blank, named): (Vec<_>, Vec<_>) = by_subject.into_iter().partition(|(s, _)| s.starts_with("_:"));
+    for (subject, triples) in named.into_iter().chain(blank) {
+        out.push('\n');
+        out.push_str(&write_subject(subject, &triples));
+    }
+    out
+}
+
+fn header_triples(iri: &str, metadata: &OntologyMetadata) -> Vec<Triple> {
+    let text = |value: &str| Object::Literal { value: value.to_string(), datatype: None, language: None };
+
+    let mut header = vec![Triple::new(iri, rdf::TYPE, Object::Iri(owl::ONTOLOGY.to_string()))];
+    if let Some(version_iri) = &metadata.version_iri {
+        header.push(Triple::new(iri, owl::VERSION_IRI, Object::Iri(version_iri.clone())));
+    }
+    if let Some(version_info) = &metadata.version_info {
+        header.push(Triple::new(iri, owl::VERSION_INFO, text(version_info)));
+    }
+    if let Some(title) = &metadata.title {
+        header.push(Triple::new(iri, dcterms::TITLE, text(title)));
+    }
+    if let Some(description) = &metadata.description {
+        header.push(Triple::new(iri, dcterms::DESCRIPTION, text(description)));
+    }
+    for creator in &metadata.creators {
+        header.push(Triple::new(iri, dcterms::CREATOR, text(creator)));
+    }
+    if let Some(license) = &metadata.license {
+        // IRIs (full or prefixed) are linked, license names are stated as rights
+        let is_iri = license.contains("://") || (license.contains(':') && !license.contains(' '));
+        header.push(if is_iri {
+            Triple::new(iri, dcterms::LICENSE, Object::Iri(license.clone()))
+        } else {
+            Triple::new(iri, dcterms::RIGHTS, text(license))
+        });
+    }
+    for import in &metadata.imports {
+        header.push(Triple::new(iri, owl::IMPORTS, Object::Iri(import.clone())));
+    }
+    header
+}
+
+/// One subject with its predicate-object list
+fn write_subject(subject: &str, triples: &[&Triple]) -> String {
+    let mut by_predicate: Vec<(&str, Vec<String>)> = Vec::new();
+    for triple in triples {
+        let object = term(&triple.object);
+        match by_predicate.iter_mut().find(|(p, _)| *p == triple.predicate) {
+            Some((_, objects)) => objects.push(object),
+            None => by_predicate.push((triple.predicate.as_str(), vec![object])),
+        }
+    }
+    // rdf:type first, written as "a"
+    by_predicate.sort_by_key(|(p, _)| *p != rdf::TYPE);
+
+    let predicates: Vec<String> = by_predicate.iter()
+        .map(|(predicate, objects)| {
+            let predicate = if *predicate == rdf::TYPE { "a".to_string() } else { iri(predicate) };
+            format!("{} {}", predicate, objects.join(" , "))
+        })
+        .collect();
+
+    format!("{} {} .\n", iri(subject), predicates.join(" ;\n    "))
+}
+
+/// An IRI or blank node as a Turtle term
+fn iri(value: &str) -> String {
+    if let Some(quoted) = statement::parse(value) {
+        return quoted_triple(&quoted);
+    }
+    if let Some(label) = value.strip_prefix("_:") {
+        let label: String = label.chars().map(|c| if c.is_alphanumeric() || c == '_' { c } else { '_' }).collect();
+        return format!("_:{}", label);
+    }
+    match value.split_once(':') {
+        Some((prefix, local)) if is_prefixed_name(prefix, local) => value.to_string(),
+        _ => format!("<{}>", expand_iri(value).replace('>', "%3E")),
+    }
+}
+
+/// Whether prefix:local is a valid (and known) Turtle prefixed name
+fn is_prefixed_name(prefix: &str, local: &str) -> bool {
+    let known = prefixes().iter().any(|(p, _)| *p == prefix);
+    let mut chars = local.chars();
+    let valid_start = chars.next().is_some_and(|c| c.is_alphanumeric() || c == '_');
+    let valid_rest = chars.all(|c| c.is_alphanumeric() || matches!(c, '_' | '-'));
+    known && valid_start && valid_rest
+}
+
+/// An object as a Turtle term
+fn term(object: &Object) -> String {
+    let typed = |value: String, datatype: &str| format!("\"{}\"^^{}", escape(&value), iri(datatype));
+
+    match object {
+        Object::Iri(value) | Object::Blank(value) => iri(value),
+        Object::Literal { value, language: Some(lang), .. } => format!("\"{}\"@{}", escape(value), lang),
+        Object::Literal { value, datatype: Some(dt), .. } if dt != "xsd:string" => typed(value.clone(), dt),
+        Object::Literal { value, .. } => format!("\"{}\"", escape(value)),
+        Object::Integer(i) => typed(i.to_string(), "xsd:integer"),
+        Object::Number(n) => typed(n.to_string(), "xsd:decimal"),
+        Object::Boolean(b) => typed(b.to_string(), "xsd:boolean"),
+        Object::DateTime(ms) => {
+            let value = chrono::DateTime::from_timestamp_millis(*ms)
+                .map(|dt| dt.to_rfc3339())
+                .unwrap_or_else(|| ms.to_string());
+            typed(value, "xsd:dateTime")
+        }
+        Object::Triple(quoted) => quoted_triple(quoted),
+    }
+}
+
+/// A quoted triple as a Turtle-star term
+fn quoted_triple(quoted: &QuotedTriple) -> String {
+    format!("<< {} {} {} >>", iri(&quoted.subject), iri(&quoted.predicate), term(&quoted.object))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::eavto::{store, test_helpers::setup_test_db};
+    use crate::owl::vocabulary::rdfs;
+    use tempfile::TempDir;
+
+    fn literal(value: &str) -> Object {
+        Object::Literal { value: value.to_string(), datatype: Some("xsd:string".to_string()), language: None }
+    }
+
+    fn model() -> Vec<Triple> {
+        vec![
+            Triple::new("foundation:Recipe", rdf::TYPE, Object::Iri(owl::CLASS.to_string())),
+            Triple::new("foundation:Recipe", rdfs::LABEL, literal("Recipe \"classic\"\nstyle")),
+            Triple::new("foundation:Recipe", rdfs::SUB_CLASS_OF, Object::Blank("_:r1".to_string())),
+            Triple::new("_:r1", rdf::TYPE, Object::Iri(owl::RESTRICTION.to_string())),
+            Triple::new("_:r1", "owl:onProperty", Object::Iri("foundation:ingredient".to_string())),
+            Triple::new("foundation:Recipe", "foundation:servings", Object::Integer(4)),
+            Triple::new("http://example.org/Dish", rdfs::SUB_CLASS_OF, Object::Iri("foundation:Recipe".to_string())),
+        ]
+    }
+
+    #[test]
+    fn test_export_round_trips_through_import() {
+        let mut conn = setup_test_db();
+        store::assert_triples(&mut conn, &model(), "import:cooking.ttl").unwrap();
+
+        let metadata = OntologyMetadata {
+            title: Some("Cooking".to_string()),
+            version_info: Some("1.0.0".to_string()),
+            license: Some("http://creativecommons.org/licenses/by/4.0/".to_string()),
+            ..Default::default()
+        };
+        let file = export_origin_as_ontology(&conn, "import:cooking.ttl", &metadata).unwrap();
+        assert_eq!(file.iri, "http://foundation.local/ontology/cooking");
+        assert!(file.content.contains("@prefix foundation: <http://foundation.local/ontology/> ."));
+        assert!(file.content.contains("<http://foundation.local/ontology/cooking> a owl:Ontology ;"));
+        assert!(file.content.contains("# Version: 1.0.0"));
+        assert!(file.content.contains("foundation:Recipe a owl:Class ;"));
+        assert!(file.content.contains("\"Recipe \\\"classic\\\"\\nstyle\""));
+        assert_eq!(file.external_terms, vec!["foundation:ingredient", "foundation:servings"]);
+
+        // Another user imports the file
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("cooking.ttl");
+        std::fs::write(&path, &file.content).unwrap();
+        let mut other = setup_test_db();
+        crate::turtle::import_turtle_file(&mut other, &path, "import:cooking.ttl").unwrap();
+
+        let recipe = query::get_by_entity(&other, "foundation:Recipe").unwrap().triples;
+        assert_eq!(recipe.len(), 4);
+        let header = query::get_by_entity(&other, "foundation:cooking").unwrap().triples;
+        assert!(header.iter().any(|t| t.object.as_iri() == Some(owl::ONTOLOGY)));
+    }
+
+    #[test]
+    fn test_origin_header_supplies_defaults() {
+        let mut conn = setup_test_db();
+        let mut triples = model();
+        triples.push(Triple::new("http://example.org/cooking", rdf::TYPE, Object::Iri(owl::ONTOLOGY.to_string())));
+        triples.push(Triple::new("http://example.org/cooking", dcterms::TITLE, literal("Old title")));
+        store::assert_triples(&mut conn, &triples, "import:cooking.ttl").unwrap();
+
+        let file = export_origin_as_ontology(&conn, "import:cooking.ttl", &OntologyMetadata::default()).unwrap();
+        assert_eq!(file.iri, "http://example.org/cooking");
+        assert!(file.content.contains("# Old title"));
+        assert_eq!(file.content.matches("a owl:Ontology").count(), 1);
+
+        assert!(export_origin_as_ontology(&conn, "missing", &OntologyMetadata::default()).is_err());
+    }
+
+    #[test]
+    fn test_terms() {
+        assert_eq!(iri("foundation:Computer"), "foundation:Computer");
+        assert_eq!(iri("foundation:My.Thing"), "<http://foundation.local/ontology/My.Thing>");
+        assert_eq!(iri("http://example.org/x"), "<http://example.org/x>");
+        assert_eq!(iri("_:b-1"), "_:b_1");
+        assert_eq!(term(&Object::Literal { value: "olá".to_string(), datatype: None, language: Some("pt".to_string()) }), "\"olá\"@pt");
+        assert_eq!(term(&Object::Boolean(true)), "\"true\"^^xsd:boolean");
+    }
+}
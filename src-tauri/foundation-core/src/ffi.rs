@@ -0,0 +1,379 @@
+// ============================================================================
+// C ABI
+// ============================================================================
+// A minimal C interface to the store, so mobile shells and other languages
+// can embed the same database: open, assert, pattern queries and search.
+// The declarations are in include/foundation.h.
+//
+// A database is an opaque handle from foundation_open, released with
+// foundation_close; calls on one handle are serialized. Strings passed in
+// are NUL-terminated UTF-8, borrowed for the call. Strings handed out are
+// JSON, owned by the caller and released with foundation_string_free.
+//
+// Every function returns a FoundationStatus, one code per variant of
+// DbError and OwlError. On failure foundation_last_error describes it (per
+// thread, until the next call). Panics never cross the boundary.
+// ============================================================================
+
+use std::cell::RefCell;
+use std::ffi::{c_char, CStr, CString};
+use std::panic::{catch_unwind, AssertUnwindSafe};
+use std::path::Path;
+use std::ptr;
+use std::sync::Mutex;
+
+use rusqlite::Connection;
+use serde_json::json;
+
+use crate::eavto::{connection::{self, DbError}, pattern, store, Object, Triple};
+use crate::export::results::json;
+use crate::owl::{self, OwlError, SearchResult};
+
+/// Result of a call
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FoundationStatus {
+    Ok = 0,
+    InvalidArgument = 1,  // Null pointer or invalid UTF-8
+    Database = 2,         // DbError::ConnectionError, OwlError::DatabaseError
+    Schema = 3,           // DbError::SchemaError
+    Io = 4,               // DbError::IoError
+    Validation = 5,       // OwlError::ValidationError and RangeError, malformed queries
+    NotFound = 6,         // OwlError::NotFound
+    InvalidOperation = 7, // OwlError::InvalidOperation
+    Panic = 8,            // A bug: the handle should not be used again
+}
+
+/// An open database (opaque to C)
+pub struct FoundationDb {
+    conn: Mutex<Connection>,
+}
+
+/// A search function of the OWL layer
+type Search = fn(&Connection, &str, usize) -> Result<Vec<SearchResult>, OwlError>;
+
+/// A failed call: its status and the message for foundation_last_error
+struct Failure(FoundationStatus, String);
+
+impl Failure {
+    fn invalid(message: impl Into<String>) -> Self {
+        Failure(FoundationStatus::InvalidArgument, message.into())
+    }
+}
+
+impl From<DbError> for Failure {
+    fn from(err: DbError) -> Self {
+        match err {
+            DbError::ConnectionError(e) => Failure(FoundationStatus::Database, e.to_string()),
+            DbError::SchemaError(message) => Failure(FoundationStatus::Schema, message),
+            DbError::IoError(e) => Failure(FoundationStatus::Io, e.to_string()),
+        }
+    }
+}
+
+impl From<OwlError> for Failure {
+    fn from(err: OwlError) -> Self {
+        let status = match err {
+            OwlError::DatabaseError(_) => FoundationStatus::Database,
+            OwlError::ValidationError(_) | OwlError::RangeError { .. } => FoundationStatus::Validation,
+            OwlError::NotFound(_) => FoundationStatus::NotFound,
+            OwlError::InvalidOperation(_) => FoundationStatus::InvalidOperation,
+        };
+        Failure(status, err.to_string())
+    }
+}
+
+impl From<Box<dyn std::error::Error>> for Failure {
+    fn from(err: Box<dyn std::error::Error>) -> Self {
+        let err = match err.downcast::<OwlError>() {
+            Ok(err) => return (*err).into(),
+            Err(err) => err,
+        };
+        let status = if err.is::<rusqlite::Error>() {
+            FoundationStatus::Database
+        } else if err.is::<std::io::Error>() {
+            FoundationStatus::Io
+        } else {
+            FoundationStatus::Validation // Rejected input, e.g. a query that doesn't parse
+        };
+        Failure(status, err.to_string())
+    }
+}
+
+thread_local! {
+    static LAST_ERROR: RefCell<Option<CString>> = const { RefCell::new(None) };
+}
+
+/// Run a call: record its error, and turn panics into FoundationStatus::Panic
+fn call(f: impl FnOnce() -> Result<(), Failure>) -> FoundationStatus {
+    let failure = match catch_unwind(AssertUnwindSafe(f)) {
+        Ok(Ok(())) => None,
+        Ok(Err(failure)) => Some(failure),
+        Err(_) => Some(Failure(FoundationStatus::Panic, "Internal error (panic)".to_string())),
+    };
+    let status = failure.as_ref().map_or(FoundationStatus::Ok, |failure| failure.0);
+    LAST_ERROR.with(|last| {
+        *last.borrow_mut() = failure.map(|failure| {
+            CString::new(failure.1.replace('\0', " ")).unwrap_or_default()
+        });
+    });
+    status
+}
+
+/// A required string argument
+unsafe fn text<'a>(ptr: *const c_char, name: &str) -> Result<&'a str, Failure> {
+    optional_text(ptr, name)?.ok_or_else(|| Failure::invalid(format!("{} is null", name)))
+}
+
+/// An optional string argument (null: None)
+unsafe fn optional_text<'a>(ptr: *const c_char, name: &str) -> Result<Option<&'a str>, Failure> {
+    if ptr.is_null() {
+        return Ok(None);
+    }
+    CStr::from_ptr(ptr).to_str()
+        .map(Some)
+        .map_err(|_| Failure::invalid(format!("{} is not valid UTF-8", name)))
+}
+
+unsafe fn database<'a>(db: *const FoundationDb) -> Result<&'a FoundationDb, Failure> {
+    db.as_ref().ok_or_else(|| Failure::invalid("database handle is null"))
+}
+
+/// Hand a JSON value to the caller
+unsafe fn put_json(out: *mut *mut c_char, value: serde_json::Value) -> Result<(), Failure> {
+    // serde_json escapes control characters, so the text has no NUL
+    let text = CString::new(value.to_string()).map_err(|e| Failure::invalid(e.to_string()))?;
+    *out = text.into_raw();
+    Ok(())
+}
+
+impl FoundationDb {
+    fn conn(&self) -> std::sync::MutexGuard<'_, Connection> {
+        // A panic in an earlier call leaves the connection usable (SQLite rolls back)
+        self.conn.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+/// Open (creating and importing the ontologies if needed) the database at `path`
+///
+/// # Safety
+/// `path` must be a NUL-terminated string and `out` a valid pointer.
+#[no_mangle]
+pub unsafe extern "C" fn foundation_open(path: *const c_char, out: *mut *mut FoundationDb) -> FoundationStatus {
+    call(|| {
+        if out.is_null() {
+            return Err(Failure::invalid("out is null"));
+        }
+        *out = ptr::null_mut();
+        let path = text(path, "path")?;
+        let conn = connection::initialize_db(Path::new(path))?;
+        *out = Box::into_raw(Box::new(FoundationDb { conn: Mutex::new(conn) }));
+        Ok(())
+    })
+}
+
+/// Close a database (null is ignored)
+///
+/// # Safety
+/// `db` must come from foundation_open and not be used afterwards.
+#[no_mangle]
+pub unsafe extern "C" fn foundation_close(db: *mut FoundationDb) {
+    if !db.is_null() {
+        drop(Box::from_raw(db));
+    }
+}
+
+/// Assert one triple, in a transaction of its own
+///
+/// The object is an IRI (or "_:" blank node) unless a datatype or language
+/// is given. The transaction id goes to `out_tx` (may be null).
+///
+/// # Safety
+/// `db` must come from foundation_open; strings must be NUL-terminated
+/// (`datatype` and `language` may be null).
+#[no_mangle]
+pub unsafe extern "C" fn foundation_assert(
+    db: *const FoundationDb,
+    subject: *const c_char,
+    predicate: *const c_char,
+    object: *const c_char,
+    datatype: *const c_char,
+    language: *const c_char,
+    origin: *const c_char,
+    out_tx: *mut i64,
+) -> FoundationStatus {
+    call(|| {
+        let db = database(db)?;
+        let object = text(object, "object")?;
+        let object = match (optional_text(datatype, "datatype")?, optional_text(language, "language")?) {
+            (None, None) if object.starts_with("_:") => Object::Blank(object.to_string()),
+            (None, None) => Object::Iri(object.to_string()),
+            (datatype, language) => Object::Literal {
+                value: object.to_string(),
+                datatype: datatype.map(str::to_string),
+                language: language.map(str::to_string),
+            },
+        };
+        let triple = Triple::new(text(subject, "subject")?, text(predicate, "predicate")?, object);
+        let tx = store::assert_triples(&mut db.conn(), &[triple], text(origin, "origin")?)?;
+        if !out_tx.is_null() {
+            *out_tx = tx;
+        }
+        Ok(())
+    })
+}
+
+/// Run a pattern query (see eavto::pattern), up to `limit` rows (0: the
+/// query's own LIMIT)
+///
+/// `out_json` receives {"variables": [...], "rows": [[...]], "truncated": bool}.
+///
+/// # Safety
+/// `db` must come from foundation_open, `query` must be NUL-terminated and
+/// `out_json` a valid pointer.
+#[no_mangle]
+pub unsafe extern "C" fn foundation_query(
+    db: *const FoundationDb,
+    query: *const c_char,
+    limit: u32,
+    out_json: *mut *mut c_char,
+) -> FoundationStatus {
+    call(|| {
+        if out_json.is_null() {
+            return Err(Failure::invalid("out_json is null"));
+        }
+        let db = database(db)?;
+        let limit = (limit > 0).then_some(limit as usize);
+        let result = pattern::execute(&db.conn(), text(query, "query")?, limit)?;
+        let rows: Vec<Vec<serde_json::Value>> = result.rows.iter()
+            .map(|row| row.iter().map(json).collect())
+            .collect();
+        put_json(out_json, json!({
+            "variables": result.variables,
+            "rows": rows,
+            "truncated": result.truncated,
+        }))
+    })
+}
+
+/// Search classes, properties and individuals by label, up to `limit` results
+///
+/// `out_json` receives [{"id", "label", "kind", "type"}], kind being
+/// "class", "property" or "individual".
+///
+/// # Safety
+/// `db` must come from foundation_open, `query` must be NUL-terminated and
+/// `out_json` a valid pointer.
+#[no_mangle]
+pub unsafe extern "C" fn foundation_search(
+    db: *const FoundationDb,
+    query: *const c_char,
+    limit: u32,
+    out_json: *mut *mut c_char,
+) -> FoundationStatus {
+    call(|| {
+        if out_json.is_null() {
+            return Err(Failure::invalid("out_json is null"));
+        }
+        let db = database(db)?;
+        let query = text(query, "query")?;
+        let conn = db.conn();
+        let limit = limit as usize;
+
+        let searches: [(&str, Search); 3] = [
+            ("class", owl::search_classes),
+            ("property", owl::search_properties),
+            ("individual", owl::search_individuals),
+        ];
+        let mut results = Vec::new();
+        for (kind, search) in searches {
+            let remaining = limit.saturating_sub(results.len());
+            if remaining == 0 {
+                break;
+            }
+            results.extend(search(&conn, query, remaining)?.into_iter().map(|result| json!({
+                "id": result.id,
+                "label": result.label,
+                "kind": kind,
+                "type": result.type_iri,
+            })));
+        }
+        put_json(out_json, serde_json::Value::Array(results))
+    })
+}
+
+/// Release a string handed out by this library (null is ignored)
+///
+/// # Safety
+/// `s` must come from this library and not be used afterwards.
+#[no_mangle]
+pub unsafe extern "C" fn foundation_string_free(s: *mut c_char) {
+    if !s.is_null() {
+        drop(CString::from_raw(s));
+    }
+}
+
+/// Message of the last failed call on this thread (null after a success)
+///
+/// Valid until the next call on the thread; not to be freed.
+#[no_mangle]
+pub extern "C" fn foundation_last_error() -> *const c_char {
+    LAST_ERROR.with(|last| last.borrow().as_ref().map_or(ptr::null(), |message| message.as_ptr()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn c(s: &str) -> CString {
+        CString::new(s).unwrap()
+    }
+
+    unsafe fn take(s: *mut c_char) -> serde_json::Value {
+        let value = serde_json::from_str(CStr::from_ptr(s).to_str().unwrap()).unwrap();
+        foundation_string_free(s);
+        value
+    }
+
+    #[test]
+    fn test_open_assert_query_and_search() {
+        let dir = TempDir::new().unwrap();
+        let path = c(dir.path().join("test.db").to_str().unwrap());
+        unsafe {
+            let mut db = ptr::null_mut();
+            assert_eq!(foundation_open(path.as_ptr(), &mut db), FoundationStatus::Ok);
+            assert!(foundation_last_error().is_null());
+
+            let (subject, origin) = (c("foundation:ffiTestPerson"), c("test"));
+            let mut tx = 0;
+            assert_eq!(foundation_assert(db, subject.as_ptr(), c("rdf:type").as_ptr(), c("foundation:Person").as_ptr(),
+                ptr::null(), ptr::null(), origin.as_ptr(), &mut tx), FoundationStatus::Ok);
+            assert!(tx > 0);
+            assert_eq!(foundation_assert(db, subject.as_ptr(), c("rdfs:label").as_ptr(), c("Zephyrine Quill").as_ptr(),
+                c("xsd:string").as_ptr(), ptr::null(), origin.as_ptr(), ptr::null_mut()), FoundationStatus::Ok);
+
+            let mut out = ptr::null_mut();
+            let query = c("?p rdfs:label \"Zephyrine Quill\"");
+            assert_eq!(foundation_query(db, query.as_ptr(), 0, &mut out), FoundationStatus::Ok);
+            let result = take(out);
+            assert_eq!(result["variables"], json!(["p"]));
+            assert_eq!(result["rows"], json!([["foundation:ffiTestPerson"]]));
+
+            assert_eq!(foundation_search(db, c("Zephyrine").as_ptr(), 10, &mut out), FoundationStatus::Ok);
+            let results = take(out);
+            assert!(results.as_array().unwrap().iter()
+                .any(|r| r["id"] == "foundation:ffiTestPerson" && r["kind"] == "individual"));
+
+            // Errors come back as codes, with a message
+            assert_eq!(foundation_query(db, c("SELECT ?x WHERE {").as_ptr(), 0, &mut out), FoundationStatus::Validation);
+            assert!(!foundation_last_error().is_null());
+            assert_eq!(foundation_search(db, ptr::null(), 10, &mut out), FoundationStatus::InvalidArgument);
+            let message = CStr::from_ptr(foundation_last_error()).to_str().unwrap();
+            assert_eq!(message, "query is null");
+            assert_eq!(foundation_search(ptr::null(), query.as_ptr(), 10, &mut out), FoundationStatus::InvalidArgument);
+
+            foundation_close(db);
+        }
+    }
+}
@@ -0,0 +1,273 @@
+// ============================================================================
+// Fixtures - Synthetic Personal Graphs
+// ============================================================================
+// Generates realistic-looking personal data for tests, UI development and
+// demo mode: people, the places they visit, the files they keep and the
+// tasks they work on, typed with the core ontology.
+//
+// Distributions aim to look like a real graph rather than a uniform grid:
+// - A few people account for most activity (events, files and tasks pick
+//   their person with a power-law bias)
+// - Events cluster in the recent past (exponentially fewer further back)
+// - Most tasks are done; files are mostly photos and documents
+//
+// Generation is deterministic for a seed, so screenshots and tests are
+// reproducible. Fixtures are asserted under their own origin (demo:fixtures)
+// and loading them again replaces the previous set.
+// ============================================================================
+
+use rusqlite::Connection;
+use crate::eavto::{query, store, Object, Triple};
+use crate::owl::vocabulary::{rdf, rdfs};
+
+type Result<T> = std::result::Result<T, Box<dyn std::error::Error>>;
+
+/// Origin of generated fixtures
+pub const FIXTURES_ORIGIN: &str = "demo:fixtures";
+
+/// Dataset size when the caller gives none (people)
+pub const DEFAULT_SIZE: usize = 25;
+
+/// Largest dataset generated at once (people)
+pub const MAX_SIZE: usize = 10_000;
+
+const DAY_MS: i64 = 86_400_000;
+
+const FIRST_NAMES: &[&str] = &[
+    "Ana", "Bruno", "Carla", "Diego", "Elena", "Felipe", "Gabriela", "Hugo", "Isabel", "João",
+    "Karina", "Lucas", "Marta", "Nuno", "Olivia", "Pedro", "Queila", "Rafael", "Sofia", "Tiago",
+];
+const LAST_NAMES: &[&str] = &[
+    "Almeida", "Barros", "Costa", "Dias", "Esteves", "Ferreira", "Gomes", "Lima", "Martins", "Nunes",
+    "Oliveira", "Pereira", "Ribeiro", "Santos", "Teixeira", "Vieira",
+];
+const PLACES: &[(&str, f64, f64)] = &[
+    ("Home", -23.5614, -46.6559),
+    ("Office", -23.5869, -46.6817),
+    ("Gym", -23.5702, -46.6484),
+    ("Café Girondino", -23.5450, -46.6339),
+    ("Ibirapuera Park", -23.5874, -46.6576),
+    ("Airport", -23.4356, -46.4731),
+    ("Grandma's house", -22.9068, -43.1729),
+    ("Bookstore", -23.5614, -46.6702),
+];
+const TASKS: &[&str] = &[
+    "Renew passport", "Book dentist appointment", "Pay electricity bill", "Plan weekend trip",
+    "Back up photos", "Reply to landlord", "Update résumé", "Buy birthday present",
+    "File tax return", "Fix bike brakes", "Call insurance", "Organize garage",
+];
+/// (media type, extension, weight)
+const MEDIA_TYPES: &[(&str, &str, u32)] = &[
+    ("image/jpeg", "jpg", 50),
+    ("application/pdf", "pdf", 25),
+    ("text/plain", "txt", 10),
+    ("audio/mpeg", "mp3", 8),
+    ("video/mp4", "mp4", 7),
+];
+
+/// Size of a generated dataset
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FixtureConfig {
+    pub people: usize,
+    pub places: usize,
+    pub events: usize, // Visits
+    pub files: usize,  // Attachments
+    pub tasks: usize,
+    pub seed: u64,
+}
+
+impl FixtureConfig {
+    /// A dataset with proportions of a typical personal graph, scaled to a
+    /// number of people
+    pub fn sized(people: usize) -> Self {
+        let people = people.clamp(1, MAX_SIZE);
+        Self {
+            people,
+            places: PLACES.len(),
+            events: people * 8,
+            files: people * 4,
+            tasks: people * 3,
+            seed: 42,
+        }
+    }
+}
+
+/// What loading fixtures asserted
+#[derive(Debug, Clone, PartialEq)]
+pub struct FixtureReport {
+    pub tx: i64,
+    pub triples: usize,
+    pub replaced: usize, // Triples of earlier fixtures retracted
+}
+
+/// Generate the triples of a dataset, as of a time (Unix ms)
+pub fn generate(config: &FixtureConfig, now: i64) -> Vec<Triple> {
+    let mut rng = Rng(config.seed);
+    let mut triples = Vec::new();
+
+    let people: Vec<String> = (0..config.people.max(1)).map(|i| format!("foundation:Person_demo{:05}", i)).collect();
+    for person in &people {
+        let name = format!("{} {}", rng.pick(FIRST_NAMES), rng.pick(LAST_NAMES));
+        typed(&mut triples, person, "foundation:Person", &name);
+        triples.push(Triple::new(person, "foundation:name", text(&name)));
+    }
+
+    let places: Vec<String> = (0..config.places.max(1)).map(|i| format!("foundation:Place_demo{:05}", i)).collect();
+    for (i, place) in places.iter().enumerate() {
+        let (name, latitude, longitude) = PLACES[i % PLACES.len()];
+        let name = match i / PLACES.len() {
+            0 => name.to_string(),
+            n => format!("{} {}", name, n + 1),
+        };
+        typed(&mut triples, place, "foundation:Place", &name);
+        triples.push(Triple::new(place, "foundation:latitude", Object::Number(latitude + rng.jitter(0.01))));
+        triples.push(Triple::new(place, "foundation:longitude", Object::Number(longitude + rng.jitter(0.01))));
+    }
+
+    for i in 0..config.events {
+        let visit = format!("foundation:Visit_demo{:06}", i);
+        let place = &places[rng.skewed(places.len())];
+        let start = now - rng.recent(365) * DAY_MS + rng.below(14) as i64 * 3_600_000;
+        let duration = (rng.below(8) as i64 + 1) * 1_800_000;
+        typed(&mut triples, &visit, "foundation:Visit", &format!("Visit {}", i + 1));
+        triples.push(Triple::new(&visit, "foundation:visitedPlace", Object::Iri(place.clone())));
+        triples.push(Triple::new(&visit, "foundation:performedBy", Object::Iri(people[rng.skewed(people.len())].clone())));
+        triples.push(Triple::new(&visit, "foundation:validFrom", Object::DateTime(start)));
+        triples.push(Triple::new(&visit, "foundation:validUntil", Object::DateTime(start + duration)));
+    }
+
+    let total_weight: u32 = MEDIA_TYPES.iter().map(|(_, _, w)| w).sum();
+    for i in 0..config.files {
+        let file = format!("foundation:Attachment_demo{:06}", i);
+        let mut roll = rng.below(total_weight as usize) as u32;
+        let (media_type, extension, _) = MEDIA_TYPES.iter()
+            .find(|(_, _, weight)| match roll < *weight {
+                true => true,
+                false => { roll -= weight; false }
+            })
+            .unwrap_or(&MEDIA_TYPES[0]);
+        let name = format!("{}-{:04}.{}", extension.to_uppercase(), i + 1, extension);
+        typed(&mut triples, &file, "foundation:Attachment", &name);
+        triples.push(Triple::new(&file, "foundation:mediaType", text(media_type)));
+        triples.push(Triple::new(&file, "foundation:createdAt", Object::DateTime(now - rng.recent(730) * DAY_MS)));
+        triples.push(Triple::new(&file, "foundation:attachedTo", Object::Iri(people[rng.skewed(people.len())].clone())));
+    }
+
+    for i in 0..config.tasks {
+        let task = format!("foundation:Task_demo{:06}", i);
+        let started = now - rng.recent(120) * DAY_MS;
+        typed(&mut triples, &task, "foundation:Task", rng.pick(TASKS));
+        triples.push(Triple::new(&task, "foundation:performedBy", Object::Iri(people[rng.skewed(people.len())].clone())));
+        triples.push(Triple::new(&task, "foundation:startedAt", Object::DateTime(started)));
+        if rng.below(10) < 7 {
+            let completed = (started + (rng.recent(14) + 1) * DAY_MS).min(now);
+            triples.push(Triple::new(&task, "foundation:completedAt", Object::DateTime(completed)));
+        }
+    }
+
+    triples
+}
+
+/// Load a generated dataset, replacing fixtures loaded before, as one
+/// transaction
+pub fn load(conn: &mut Connection, config: &FixtureConfig) -> Result<FixtureReport> {
+    let previous = match query::get_origin_id(conn, FIXTURES_ORIGIN)? {
+        Some(origin_id) => query::get_by_origin(conn, origin_id)?.triples,
+        None => Vec::new(),
+    };
+    let triples = generate(config, store::now_millis());
+    let tx = store::apply_changes(conn, &triples, &previous, FIXTURES_ORIGIN)?;
+    Ok(FixtureReport { tx, triples: triples.len(), replaced: previous.len() })
+}
+
+fn typed(triples: &mut Vec<Triple>, iri: &str, class: &str, label: &str) {
+    triples.push(Triple::new(iri, rdf::TYPE, Object::Iri(class.to_string())));
+    triples.push(Triple::new(iri, rdfs::LABEL, text(label)));
+}
+
+fn text(value: &str) -> Object {
+    Object::Literal { value: value.to_string(), datatype: Some("xsd:string".to_string()), language: None }
+}
+
+/// Small deterministic generator (SplitMix64); fixtures don't need more
+struct Rng(u64);
+
+impl Rng {
+    fn next(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    /// Uniform in [0, 1)
+    fn unit(&mut self) -> f64 {
+        (self.next() >> 11) as f64 / (1u64 << 53) as f64
+    }
+
+    /// Uniform in [0, n)
+    fn below(&mut self, n: usize) -> usize {
+        (self.next() % n.max(1) as u64) as usize
+    }
+
+    fn pick<'a>(&mut self, items: &[&'a str]) -> &'a str {
+        items[self.below(items.len())]
+    }
+
+    /// Index in [0, n), low indexes much more likely (power law)
+    fn skewed(&mut self, n: usize) -> usize {
+        ((self.unit().powi(3) * n as f64) as usize).min(n.saturating_sub(1))
+    }
+
+    /// Days ago within a horizon, recent days more likely (exponential)
+    fn recent(&mut self, horizon_days: i64) -> i64 {
+        let days = -(1.0 - self.unit()).ln() * horizon_days as f64 / 4.0;
+        (days as i64).min(horizon_days)
+    }
+
+    /// Uniform in [-amount, amount)
+    fn jitter(&mut self, amount: f64) -> f64 {
+        (self.unit() * 2.0 - 1.0) * amount
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::eavto::{canonical, test_helpers::setup_test_db};
+
+    #[test]
+    fn test_generate_is_deterministic_and_skewed() {
+        let config = FixtureConfig::sized(20);
+        let triples = generate(&config, 1_700_000_000_000);
+        let lines = |triples: &[Triple]| triples.iter().map(canonical::line).collect::<Vec<_>>();
+        assert_eq!(lines(&triples), lines(&generate(&config, 1_700_000_000_000)));
+
+        let count = |class: &str| triples.iter()
+            .filter(|t| t.predicate == rdf::TYPE && t.object.as_iri() == Some(class))
+            .count();
+        assert_eq!(count("foundation:Person"), 20);
+        assert_eq!(count("foundation:Visit"), 160);
+        assert_eq!(count("foundation:Attachment"), 80);
+        assert_eq!(count("foundation:Task"), 60);
+
+        // The most active person does far more than an even share
+        let busiest = triples.iter()
+            .filter(|t| t.predicate == "foundation:performedBy" && t.object.as_iri() == Some("foundation:Person_demo00000"))
+            .count();
+        assert!(busiest > 220 / 20 * 3);
+    }
+
+    #[test]
+    fn test_load_replaces_previous_fixtures() {
+        let mut conn = setup_test_db();
+        let first = load(&mut conn, &FixtureConfig::sized(3)).unwrap();
+        assert_eq!(first.replaced, 0);
+
+        let second = load(&mut conn, &FixtureConfig { seed: 7, ..FixtureConfig::sized(2) }).unwrap();
+        assert_eq!(second.replaced, first.triples);
+        let origin = query::get_origin_id(&conn, FIXTURES_ORIGIN).unwrap().unwrap();
+        assert_eq!(query::get_by_origin(&conn, origin).unwrap().triples.len(), second.triples);
+    }
+}
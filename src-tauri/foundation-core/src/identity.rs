@@ -0,0 +1,291 @@
+// ============================================================================
+// Identity
+// ============================================================================
+// The local user's identity, for the decentralized plan: a secp256k1 key
+// pair generated on first run. The private key never enters the triple
+// store; it lives in the OS keychain (or another KeyStore). The public key
+// is published on foundation:ThisUser, so peers can verify what this
+// device signs.
+//
+// Keys and signatures are hex: the public key in compressed SEC1 form (33
+// bytes), signatures as ECDSA r || s (64 bytes, low S) over the SHA-256 of
+// the payload.
+//
+// Once the app shell sets the local identity (set_local), every assertion
+// transaction is signed: the signature covers the canonical serialization
+// of the transaction (origin, time and the triples it asserted), and is
+// stored with the signer's public key on the transaction. A transaction
+// that synced from another device can then be checked against the key of
+// whoever claims to have made it (verify_transaction).
+// ============================================================================
+
+use k256::ecdsa::{signature::{Signer, Verifier}, Signature, SigningKey, VerifyingKey};
+use rand_core::OsRng;
+use rusqlite::{Connection, OptionalExtension};
+use std::sync::OnceLock;
+
+use crate::eavto::{blob, canonical, query, store, Object, Transaction, Triple};
+
+type Result<T> = std::result::Result<T, Box<dyn std::error::Error>>;
+
+/// The local user
+pub const THIS_USER: &str = "foundation:ThisUser";
+
+/// Public key of a person's identity (hex, compressed SEC1)
+pub const PUBLIC_KEY: &str = "foundation:publicKey";
+
+/// Origin of the published public key
+const ORIGIN: &str = "identity";
+
+/// Keychain entry of the private key
+const KEYCHAIN_SERVICE: &str = "FOUNDATION";
+const KEYCHAIN_ACCOUNT: &str = "identity";
+
+/// Where the private key is kept
+pub trait KeyStore {
+    /// The stored private key (hex), if any
+    fn load(&self) -> Result<Option<String>>;
+
+    /// Store the private key (hex)
+    fn store(&self, secret: &str) -> Result<()>;
+}
+
+/// The OS keychain (macOS Keychain, Windows Credential Manager, Linux
+/// kernel keyring)
+pub struct Keychain;
+
+impl KeyStore for Keychain {
+    fn load(&self) -> Result<Option<String>> {
+        match keyring::Entry::new(KEYCHAIN_SERVICE, KEYCHAIN_ACCOUNT)?.get_password() {
+            Ok(secret) => Ok(Some(secret)),
+            Err(keyring::Error::NoEntry) => Ok(None),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    fn store(&self, secret: &str) -> Result<()> {
+        keyring::Entry::new(KEYCHAIN_SERVICE, KEYCHAIN_ACCOUNT)?.set_password(secret)?;
+        Ok(())
+    }
+}
+
+/// The identity transactions are signed with, when the app shell sets one
+static LOCAL: OnceLock<Identity> = OnceLock::new();
+
+/// A key pair
+#[derive(Clone)]
+pub struct Identity {
+    key: SigningKey,
+}
+
+impl Identity {
+    /// A new random key pair
+    pub fn generate() -> Self {
+        Self { key: SigningKey::random(&mut OsRng) }
+    }
+
+    /// The stored key pair, or a new one (stored before it is returned)
+    pub fn load_or_create(store: &dyn KeyStore) -> Result<Self> {
+        if let Some(secret) = store.load()? {
+            let key = SigningKey::from_slice(&hex::decode(secret.trim())?)
+                .map_err(|_| "The stored identity key is invalid")?;
+            return Ok(Self { key });
+        }
+        let identity = Self::generate();
+        store.store(&hex::encode(identity.key.to_bytes()))?;
+        Ok(identity)
+    }
+
+    /// Public key, hex
+    pub fn public_key(&self) -> String {
+        hex::encode(self.key.verifying_key().to_encoded_point(true).as_bytes())
+    }
+
+    /// Signature of a payload, hex
+    pub fn sign(&self, payload: &[u8]) -> String {
+        let signature: Signature = self.key.sign(payload);
+        hex::encode(signature.to_bytes())
+    }
+}
+
+/// Whether a signature (hex) of the payload was made with the key of
+/// `public_key` (hex)
+pub fn verify(public_key: &str, payload: &[u8], signature: &str) -> Result<bool> {
+    let key = VerifyingKey::from_sec1_bytes(&hex::decode(public_key)?)
+        .map_err(|_| format!("Invalid public key: {}", public_key))?;
+    let signature = Signature::from_slice(&hex::decode(signature)?)
+        .map_err(|_| format!("Invalid signature: {}", signature))?;
+    Ok(key.verify(payload, &signature).is_ok())
+}
+
+/// Sign assertion transactions with `identity` from now on
+///
+/// Only the first call has an effect.
+pub fn set_local(identity: Identity) {
+    let _ = LOCAL.set(identity);
+}
+
+/// The identity transactions are signed with, if set
+pub fn local() -> Option<&'static Identity> {
+    LOCAL.get()
+}
+
+/// Whether a transaction's signature holds
+#[derive(Debug, Clone, PartialEq)]
+pub enum Verification {
+    /// Signed by `signer`, and the content is what was signed
+    Valid { signer: String },
+    /// Signed by `signer`, but the content or the signature changed since
+    Invalid { signer: String },
+    /// Never signed (made before signing, or without an identity)
+    Unsigned,
+}
+
+/// Canonical bytes of a transaction: what its signature covers
+///
+/// Blob references are resolved, so the digest doesn't depend on the
+/// literal size limit of the store holding it. None if the transaction
+/// doesn't exist.
+pub(crate) fn transaction_payload(conn: &Connection, tx: i64) -> Result<Option<Vec<u8>>> {
+    let Some((origin, created_at)) = conn.query_row(
+        "SELECT origin, created_at FROM transactions WHERE tx = ?",
+        [tx],
+        |row| Ok((row.get::<_, String>(0)?, row.get::<_, i64>(1)?)),
+    ).optional()? else {
+        return Ok(None);
+    };
+
+    let triples = query::get_asserted_by_transaction(conn, tx)?
+        .triples
+        .into_iter()
+        .map(|t| Ok(Triple { object: blob::resolve(conn, &t.object)?, ..t }))
+        .collect::<Result<Vec<_>>>()?;
+    Ok(Some(canonical::serialize_transaction(&Transaction::new(tx, origin, created_at), &triples)))
+}
+
+/// Sign a transaction, storing the signature and the signer's public key on it
+pub(crate) fn sign_transaction(conn: &Connection, tx: i64, identity: &Identity) -> Result<()> {
+    let payload = transaction_payload(conn, tx)?
+        .ok_or_else(|| format!("Transaction {} not found", tx))?;
+    conn.execute(
+        "UPDATE transactions SET signature = ?, signer = ? WHERE tx = ?",
+        (identity.sign(&payload), identity.public_key(), tx),
+    )?;
+    Ok(())
+}
+
+/// Verify the signature of a transaction against its current content
+pub fn verify_transaction(conn: &Connection, tx: i64) -> Result<Verification> {
+    let (signature, signer) = conn.query_row(
+        "SELECT signature, signer FROM transactions WHERE tx = ?",
+        [tx],
+        |row| Ok((row.get::<_, Option<String>>(0)?, row.get::<_, Option<String>>(1)?)),
+    ).optional()?
+        .ok_or_else(|| format!("Transaction {} not found", tx))?;
+    let (Some(signature), Some(signer)) = (signature, signer) else {
+        return Ok(Verification::Unsigned);
+    };
+
+    let payload = transaction_payload(conn, tx)?.unwrap_or_default();
+    // A malformed signature or key is as untrustworthy as a wrong one
+    match verify(&signer, &payload, &signature).unwrap_or(false) {
+        true => Ok(Verification::Valid { signer }),
+        false => Ok(Verification::Invalid { signer }),
+    }
+}
+
+/// Publish the public key on foundation:ThisUser, replacing an older one
+///
+/// Returns the transaction, or None when the key was already published.
+pub fn publish(conn: &mut Connection, identity: &Identity) -> Result<Option<i64>> {
+    let key = identity.public_key();
+    let current: Vec<Triple> = query::get_by_entity_predicate(conn, THIS_USER, PUBLIC_KEY)?.triples;
+    if current.iter().any(|t| t.object.as_literal().as_deref() == Some(key.as_str())) {
+        return Ok(None);
+    }
+
+    let triple = Triple::new(THIS_USER, PUBLIC_KEY, Object::Literal {
+        value: key,
+        datatype: Some("xsd:string".to_string()),
+        language: None,
+    });
+    Ok(Some(store::apply_changes(conn, &[triple], &current, ORIGIN)?))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::eavto::test_helpers::setup_test_db;
+    use std::cell::RefCell;
+
+    /// A key store in memory
+    #[derive(Default)]
+    struct Memory(RefCell<Option<String>>);
+
+    impl KeyStore for Memory {
+        fn load(&self) -> Result<Option<String>> {
+            Ok(self.0.borrow().clone())
+        }
+
+        fn store(&self, secret: &str) -> Result<()> {
+            *self.0.borrow_mut() = Some(secret.to_string());
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_load_or_create_keeps_the_key() {
+        let store = Memory::default();
+        let first = Identity::load_or_create(&store).unwrap();
+        let second = Identity::load_or_create(&store).unwrap();
+        assert_eq!(first.public_key(), second.public_key());
+        assert_eq!(first.public_key().len(), 66);
+        assert_ne!(Identity::generate().public_key(), first.public_key());
+    }
+
+    #[test]
+    fn test_sign_and_verify() {
+        let identity = Identity::generate();
+        let signature = identity.sign(b"tx 42");
+        assert_eq!(signature.len(), 128);
+        assert!(verify(&identity.public_key(), b"tx 42", &signature).unwrap());
+        assert!(!verify(&identity.public_key(), b"tx 43", &signature).unwrap());
+        assert!(!verify(&Identity::generate().public_key(), b"tx 42", &signature).unwrap());
+        assert!(verify("not hex", b"tx 42", &signature).is_err());
+    }
+
+    #[test]
+    fn test_verify_transaction() {
+        let mut conn = setup_test_db();
+        let triples = crate::eavto::test_helpers::create_test_triples();
+        let tx = store::assert_triples(&mut conn, &triples, "test").unwrap();
+        assert_eq!(verify_transaction(&conn, tx).unwrap(), Verification::Unsigned);
+
+        let identity = Identity::generate();
+        sign_transaction(&conn, tx, &identity).unwrap();
+        let signer = identity.public_key();
+        assert_eq!(verify_transaction(&conn, tx).unwrap(), Verification::Valid { signer: signer.clone() });
+
+        // Retracting later doesn't change what the transaction said
+        store::retract_triples(&mut conn, &triples[..1], "test").unwrap();
+        assert_eq!(verify_transaction(&conn, tx).unwrap(), Verification::Valid { signer: signer.clone() });
+
+        conn.execute("UPDATE triples SET object_value = 'Forged' WHERE tx = ? AND predicate = 'rdfs:label'", [tx]).unwrap();
+        assert_eq!(verify_transaction(&conn, tx).unwrap(), Verification::Invalid { signer });
+        assert!(verify_transaction(&conn, tx + 100).is_err());
+    }
+
+    #[test]
+    fn test_publish_replaces_the_key() {
+        let mut conn = setup_test_db();
+        let identity = Identity::generate();
+        assert!(publish(&mut conn, &identity).unwrap().is_some());
+        assert!(publish(&mut conn, &identity).unwrap().is_none());
+
+        let other = Identity::generate();
+        publish(&mut conn, &other).unwrap();
+        let keys = query::get_by_entity_predicate(&conn, THIS_USER, PUBLIC_KEY).unwrap().triples;
+        assert_eq!(keys.len(), 1);
+        assert_eq!(keys[0].object.as_literal(), Some(other.public_key()));
+    }
+}
@@ -12,6 +12,11 @@ lazy_static::lazy_static! {
         m.insert("foundation:", "http://foundation.local/ontology/");
         m.insert("qudt:", "http://qudt.org/schema/qudt/");
         m.insert("unit:", "http://qudt.org/vocab/unit/");
+        m.insert("cur:", "http://qudt.org/vocab/currency/");
+        m.insert("dc:", "http://purl.org/dc/elements/1.1/");
+        m.insert("dcterms:", "http://purl.org/dc/terms/");
+        m.insert("obo:", "http://purl.obolibrary.org/obo/");
+        m.insert("prov:", "http://www.w3.org/ns/prov#");
         m
     };
 }
@@ -46,6 +51,17 @@ pub fn compress_iri(iri: &str) -> String {
     iri.to_string()
 }
 
+/// Lists all known prefixes with their namespaces (sorted by prefix)
+/// Examples:
+/// - ("owl", "http://www.w3.org/2002/07/owl#")
+pub fn prefixes() -> Vec<(&'static str, &'static str)> {
+    let mut prefixes: Vec<(&'static str, &'static str)> = NAMESPACES.iter()
+        .map(|(prefix, namespace)| ((*prefix).trim_end_matches(':'), *namespace))
+        .collect();
+    prefixes.sort();
+    prefixes
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -77,5 +93,14 @@ mod tests {
             "owl:Thing"
         );
         assert_eq!(compress_iri("custom:Thing"), "custom:Thing");
+        assert_eq!(compress_iri("http://purl.org/dc/terms/license"), "dcterms:license");
+        assert_eq!(compress_iri("http://purl.org/dc/elements/1.1/title"), "dc:title");
+    }
+
+    #[test]
+    fn test_prefixes() {
+        let prefixes = prefixes();
+        assert!(prefixes.contains(&("owl", "http://www.w3.org/2002/07/owl#")));
+        assert!(prefixes.windows(2).all(|w| w[0].0 < w[1].0));
     }
 }
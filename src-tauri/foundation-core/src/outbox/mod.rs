@@ -0,0 +1,264 @@
+// ============================================================================
+// Outbox Module
+// ============================================================================
+// Persistent queue of outbound work: queries to public SPARQL endpoints,
+// feed fetches, linked-data resolution, relay uploads. Anything that needs
+// the network is queued here rather than lost when the device is offline.
+//
+// - A job is a kind (which handler runs it) and a JSON payload
+// - The worker (worker.rs) runs due jobs in the background; a failed
+//   attempt is retried with exponential backoff, up to MAX_ATTEMPTS
+// - Jobs live in the outbox table, outside the triple store: they survive
+//   restarts, and belong to this device
+// ============================================================================
+
+pub mod worker;
+
+use rusqlite::{Connection, OptionalExtension, Row};
+use crate::eavto::store::now_millis;
+
+type Result<T> = std::result::Result<T, Box<dyn std::error::Error>>;
+
+/// Attempts before a job is given up on
+pub const MAX_ATTEMPTS: i64 = 12;
+
+/// Delay after the first failed attempt; doubled after each further one
+const BASE_BACKOFF: i64 = 30_000;
+
+/// Longest delay between attempts (6 hours)
+const MAX_BACKOFF: i64 = 6 * 3_600_000;
+
+/// Where a job is in its life
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum JobStatus {
+    Pending,   // Waiting for its next attempt
+    Done,
+    Failed,    // Out of attempts, or failed in a way retrying can't fix
+    Cancelled,
+}
+
+impl JobStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            JobStatus::Pending => "pending",
+            JobStatus::Done => "done",
+            JobStatus::Failed => "failed",
+            JobStatus::Cancelled => "cancelled",
+        }
+    }
+
+    pub fn parse(status: &str) -> Option<Self> {
+        match status {
+            "pending" => Some(JobStatus::Pending),
+            "done" => Some(JobStatus::Done),
+            "failed" => Some(JobStatus::Failed),
+            "cancelled" => Some(JobStatus::Cancelled),
+            _ => None,
+        }
+    }
+}
+
+/// A queued job
+#[derive(Debug, Clone, PartialEq)]
+pub struct Job {
+    pub id: i64,
+    pub kind: String,
+    pub payload: serde_json::Value,
+    pub status: JobStatus,
+    pub attempts: i64,
+    pub next_attempt_at: i64, // Unix ms
+    pub last_error: Option<String>,
+    pub created_at: i64,
+    pub completed_at: Option<i64>,
+}
+
+impl Job {
+    fn from_row(row: &Row) -> rusqlite::Result<Self> {
+        let payload: String = row.get("payload")?;
+        let status: String = row.get("status")?;
+        Ok(Self {
+            id: row.get("id")?,
+            kind: row.get("kind")?,
+            payload: serde_json::from_str(&payload).unwrap_or_default(),
+            status: JobStatus::parse(&status).unwrap_or(JobStatus::Failed),
+            attempts: row.get("attempts")?,
+            next_attempt_at: row.get("next_attempt_at")?,
+            last_error: row.get("last_error")?,
+            created_at: row.get("created_at")?,
+            completed_at: row.get("completed_at")?,
+        })
+    }
+}
+
+/// Queue a job to run as soon as possible
+///
+/// A pending job of the same kind and payload is not queued twice: its ID
+/// is returned instead.
+pub fn enqueue(conn: &Connection, kind: &str, payload: &serde_json::Value) -> Result<i64> {
+    let payload = serde_json::to_string(payload)?;
+    let pending: Option<i64> = conn.query_row(
+        "SELECT id FROM outbox WHERE kind = ? AND payload = ? AND status = 'pending'",
+        (kind, &payload),
+        |row| row.get(0),
+    ).optional()?;
+    if let Some(id) = pending {
+        return Ok(id);
+    }
+
+    let now = now_millis();
+    conn.execute(
+        "INSERT INTO outbox (kind, payload, next_attempt_at, created_at) VALUES (?, ?, ?, ?)",
+        (kind, &payload, now, now),
+    )?;
+    Ok(conn.last_insert_rowid())
+}
+
+/// Pending jobs due at a time (Unix ms), oldest first
+pub fn due(conn: &Connection, now: i64, limit: usize) -> Result<Vec<Job>> {
+    let mut stmt = conn.prepare(
+        "SELECT * FROM outbox WHERE status = 'pending' AND next_attempt_at <= ?
+         ORDER BY next_attempt_at, id LIMIT ?"
+    )?;
+    let jobs = stmt.query_map((now, limit as i64), Job::from_row)?
+        .collect::<rusqlite::Result<Vec<_>>>()?;
+    Ok(jobs)
+}
+
+/// Jobs, newest first, optionally only those with a status
+pub fn list(conn: &Connection, status: Option<JobStatus>, limit: usize) -> Result<Vec<Job>> {
+    let mut stmt = conn.prepare(
+        "SELECT * FROM outbox WHERE ?1 IS NULL OR status = ?1 ORDER BY created_at DESC, id DESC LIMIT ?2"
+    )?;
+    let jobs = stmt.query_map((status.map(|s| s.as_str()), limit as i64), Job::from_row)?
+        .collect::<rusqlite::Result<Vec<_>>>()?;
+    Ok(jobs)
+}
+
+/// Record a successful attempt
+pub fn complete(conn: &Connection, id: i64) -> Result<Job> {
+    conn.execute(
+        "UPDATE outbox SET status = 'done', attempts = attempts + 1, last_error = NULL, completed_at = ?
+         WHERE id = ?",
+        (now_millis(), id),
+    )?;
+    get(conn, id)
+}
+
+/// Record a failed attempt
+///
+/// The job is retried after a backoff delay, unless `retry` is false (the
+/// error is permanent) or it has run out of attempts.
+pub fn fail(conn: &Connection, id: i64, error: &str, retry: bool) -> Result<Job> {
+    let job = get(conn, id)?;
+    let attempts = job.attempts + 1;
+    let now = now_millis();
+    let status = if retry && attempts < MAX_ATTEMPTS { JobStatus::Pending } else { JobStatus::Failed };
+    conn.execute(
+        "UPDATE outbox SET status = ?, attempts = ?, next_attempt_at = ?, last_error = ?, completed_at = ?
+         WHERE id = ?",
+        (
+            status.as_str(),
+            attempts,
+            now + backoff(attempts),
+            error,
+            (status == JobStatus::Failed).then_some(now),
+            id,
+        ),
+    )?;
+    get(conn, id)
+}
+
+/// Run a failed or cancelled job again, with a fresh set of attempts
+pub fn retry(conn: &Connection, id: i64) -> Result<Job> {
+    let job = get(conn, id)?;
+    if !matches!(job.status, JobStatus::Failed | JobStatus::Cancelled) {
+        return Err(format!("Job {} is {}", id, job.status.as_str()).into());
+    }
+    conn.execute(
+        "UPDATE outbox SET status = 'pending', attempts = 0, next_attempt_at = ?, completed_at = NULL WHERE id = ?",
+        (now_millis(), id),
+    )?;
+    get(conn, id)
+}
+
+/// Cancel a pending job
+pub fn cancel(conn: &Connection, id: i64) -> Result<Job> {
+    let job = get(conn, id)?;
+    if job.status != JobStatus::Pending {
+        return Err(format!("Job {} is {}", id, job.status.as_str()).into());
+    }
+    conn.execute(
+        "UPDATE outbox SET status = 'cancelled', completed_at = ? WHERE id = ?",
+        (now_millis(), id),
+    )?;
+    get(conn, id)
+}
+
+/// Delay before the next attempt after a number of failed ones (ms)
+pub fn backoff(attempts: i64) -> i64 {
+    let doublings = attempts.clamp(1, 32) - 1;
+    BASE_BACKOFF.saturating_mul(1 << doublings).min(MAX_BACKOFF)
+}
+
+fn get(conn: &Connection, id: i64) -> Result<Job> {
+    conn.query_row("SELECT * FROM outbox WHERE id = ?", [id], Job::from_row)
+        .optional()?
+        .ok_or_else(|| format!("Job {} not found", id).into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::eavto::test_helpers::setup_test_db;
+
+    #[test]
+    fn test_enqueue_and_backoff() {
+        let conn = setup_test_db();
+        let payload = serde_json::json!({ "endpoint": "https://example.org/sparql" });
+        let id = enqueue(&conn, "federated_query", &payload).unwrap();
+        assert_eq!(enqueue(&conn, "federated_query", &payload).unwrap(), id);
+
+        let jobs = due(&conn, now_millis(), 10).unwrap();
+        assert_eq!(jobs.len(), 1);
+        assert_eq!(jobs[0].payload, payload);
+
+        // A failed attempt waits for its backoff
+        let job = fail(&conn, id, "offline", true).unwrap();
+        assert_eq!(job.status, JobStatus::Pending);
+        assert_eq!(job.attempts, 1);
+        assert_eq!(job.last_error.as_deref(), Some("offline"));
+        assert!(due(&conn, now_millis(), 10).unwrap().is_empty());
+        assert_eq!(due(&conn, job.next_attempt_at, 10).unwrap().len(), 1);
+
+        let job = complete(&conn, id).unwrap();
+        assert_eq!(job.status, JobStatus::Done);
+        assert!(job.completed_at.is_some());
+        assert!(due(&conn, i64::MAX, 10).unwrap().is_empty());
+
+        assert_eq!(backoff(1), 30_000);
+        assert_eq!(backoff(3), 120_000);
+        assert_eq!(backoff(40), MAX_BACKOFF);
+    }
+
+    #[test]
+    fn test_failed_jobs_retry_and_cancel() {
+        let conn = setup_test_db();
+        let id = enqueue(&conn, "relay_upload", &serde_json::json!({})).unwrap();
+
+        let job = fail(&conn, id, "bad request", false).unwrap();
+        assert_eq!(job.status, JobStatus::Failed);
+        assert!(cancel(&conn, id).is_err());
+        assert_eq!(list(&conn, Some(JobStatus::Failed), 10).unwrap().len(), 1);
+
+        let job = retry(&conn, id).unwrap();
+        assert_eq!((job.status, job.attempts), (JobStatus::Pending, 0));
+        for _ in 0..MAX_ATTEMPTS {
+            fail(&conn, id, "offline", true).unwrap();
+        }
+        assert_eq!(list(&conn, None, 10).unwrap()[0].status, JobStatus::Failed);
+
+        retry(&conn, id).unwrap();
+        assert_eq!(cancel(&conn, id).unwrap().status, JobStatus::Cancelled);
+        assert!(list(&conn, Some(JobStatus::Pending), 10).unwrap().is_empty());
+    }
+}
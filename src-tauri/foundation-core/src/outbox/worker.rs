@@ -0,0 +1,166 @@
+//! Outbox Worker
+//!
+//! Runs due outbox jobs in the background, one at a time, polling the queue
+//! every POLL_INTERVAL. Network errors are retried with backoff; errors the
+//! network can't explain (invalid payload, unknown job kind, a response the
+//! store rejects) fail the job at once.
+//!
+//! Handlers, by job kind:
+//! - federated_query: { endpoint, query } runs a query against a public
+//!   SPARQL endpoint and caches its results (see owl::federation)
+
+use std::sync::{Condvar, Mutex};
+use std::time::Duration;
+use tokio::runtime::Runtime;
+use super::Job;
+use crate::eavto::{store::now_millis, DbExecutor};
+use crate::owl::federation;
+
+type Result<T> = std::result::Result<T, Box<dyn std::error::Error>>;
+
+/// Time between checks of the queue
+const POLL_INTERVAL: Duration = Duration::from_secs(30);
+
+/// Jobs read from the queue per check
+const BATCH: usize = 20;
+
+/// Set by wake() to end the wait between checks early
+static WAKE: (Mutex<bool>, Condvar) = (Mutex::new(false), Condvar::new());
+
+/// Outcome of one attempt at a job
+#[derive(Debug, Clone, PartialEq)]
+pub enum Attempt {
+    Done,
+    Retry(String), // Transient failure (e.g. offline): try again later
+    Fail(String),  // Permanent failure
+}
+
+/// Start running outbox jobs in the background
+pub fn start(executor: DbExecutor) {
+    std::thread::spawn(move || {
+        let runtime = match tokio::runtime::Builder::new_current_thread().enable_time().build() {
+            Ok(runtime) => runtime,
+            Err(e) => return println!("⚠️  Outbox: {}", e),
+        };
+        loop {
+            if let Err(e) = run_due(&executor, &runtime, perform) {
+                println!("⚠️  Outbox: {}", e);
+            }
+            wait();
+        }
+    });
+}
+
+/// Check the queue now rather than at the end of the wait, e.g. when a
+/// mobile app returns to the foreground (the OS freezes background threads,
+/// and their timers with them)
+pub fn wake() {
+    let (woken, signal) = &WAKE;
+    *woken.lock().unwrap_or_else(|e| e.into_inner()) = true;
+    signal.notify_all();
+}
+
+/// Wait POLL_INTERVAL, or until woken
+fn wait() {
+    let (woken, signal) = &WAKE;
+    let guard = woken.lock().unwrap_or_else(|e| e.into_inner());
+    let (mut guard, _) = signal.wait_timeout_while(guard, POLL_INTERVAL, |woken| !*woken)
+        .unwrap_or_else(|e| e.into_inner());
+    *guard = false;
+}
+
+/// Run the jobs due now with a handler, recording each outcome
+/// Returns the number of jobs attempted
+pub fn run_due(
+    executor: &DbExecutor,
+    runtime: &Runtime,
+    handler: impl Fn(&DbExecutor, &Runtime, &Job) -> Attempt,
+) -> Result<usize> {
+    let jobs = runtime.block_on(executor.read(None, |conn| {
+        super::due(conn, now_millis(), BATCH).map_err(|e| e.to_string())
+    }))?;
+
+    for job in &jobs {
+        let attempt = handler(executor, runtime, job);
+        let id = job.id;
+        runtime.block_on(executor.write(move |conn| {
+            match &attempt {
+                Attempt::Done => super::complete(conn, id),
+                Attempt::Retry(error) => super::fail(conn, id, error, true),
+                Attempt::Fail(error) => super::fail(conn, id, error, false),
+            }.map_err(|e| e.to_string())
+        }))?;
+    }
+    Ok(jobs.len())
+}
+
+/// Run a job with the handler of its kind
+fn perform(executor: &DbExecutor, runtime: &Runtime, job: &Job) -> Attempt {
+    match job.kind.as_str() {
+        federation::OUTBOX_KIND => federated_query(executor, runtime, job),
+        kind => Attempt::Fail(format!("Unknown job kind {}", kind)),
+    }
+}
+
+fn federated_query(executor: &DbExecutor, runtime: &Runtime, job: &Job) -> Attempt {
+    let (Some(endpoint), Some(query)) = (job.payload["endpoint"].as_str(), job.payload["query"].as_str()) else {
+        return Attempt::Fail("Payload needs an endpoint and a query".to_string());
+    };
+    let body = match federation::fetch(endpoint, query) {
+        Ok(body) => body,
+        Err(e) => return Attempt::Retry(e.to_string()),
+    };
+
+    let (endpoint, query) = (endpoint.to_string(), query.to_string());
+    match runtime.block_on(executor.write(move |conn| {
+        federation::store_results(conn, &endpoint, &query, &body).map_err(|e| e.to_string())
+    })) {
+        Ok(_) => Attempt::Done,
+        Err(e) => Attempt::Fail(e),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::eavto::test_helpers::setup_test_db;
+    use crate::outbox::{self, JobStatus};
+
+    #[test]
+    fn test_wake_ends_the_wait() {
+        let waiter = std::thread::spawn(wait);
+        while !waiter.is_finished() {
+            wake();
+            std::thread::sleep(Duration::from_millis(10));
+        }
+        waiter.join().unwrap();
+    }
+
+    #[test]
+    fn test_run_due_records_outcomes() {
+        let conn = setup_test_db();
+        let done = outbox::enqueue(&conn, "feed_fetch", &serde_json::json!({ "feed": 1 })).unwrap();
+        let offline = outbox::enqueue(&conn, "feed_fetch", &serde_json::json!({ "feed": 2 })).unwrap();
+        let unknown = outbox::enqueue(&conn, "telepathy", &serde_json::json!({})).unwrap();
+        let runtime = tokio::runtime::Builder::new_current_thread().enable_all().build().unwrap();
+        let executor = runtime.block_on(async { DbExecutor::new(conn) });
+
+        let attempted = run_due(&executor, &runtime, |executor, runtime, job| match job.kind.as_str() {
+            "feed_fetch" if job.payload["feed"] == 1 => Attempt::Done,
+            "feed_fetch" => Attempt::Retry("offline".to_string()),
+            _ => perform(executor, runtime, job),
+        }).unwrap();
+        assert_eq!(attempted, 3);
+
+        let jobs = runtime.block_on(executor.read(None, |conn| {
+            outbox::list(conn, None, 10).map_err(|e| e.to_string())
+        })).unwrap();
+        let status = |id: i64| jobs.iter().find(|j| j.id == id).map(|j| j.status);
+        assert_eq!(status(done), Some(JobStatus::Done));
+        assert_eq!(status(offline), Some(JobStatus::Pending));
+        assert_eq!(status(unknown), Some(JobStatus::Failed));
+
+        // The retry waits for its backoff
+        assert_eq!(run_due(&executor, &runtime, |_, _, _| Attempt::Done).unwrap(), 0);
+    }
+}
@@ -0,0 +1,242 @@
+// ============================================================================
+// OWL Affiliation - Employment and Membership Periods
+// ============================================================================
+// A foundation:Affiliation ties a person to an organization with a role, for
+// the period given by its valid time (foundation:validFrom,
+// foundation:validUntil). Either end may be unknown: no start means "since
+// some time", no end means current.
+//
+// The queries are interval queries over that valid time: a person's
+// affiliations as a CV-style timeline, and an organization's members at a
+// date.
+// ============================================================================
+
+use rusqlite::Connection;
+use crate::eavto::{query, store, Object, Triple};
+use crate::owl::{Thing, Result, hash, string, OwlError, vocabulary::{rdf, rdfs}};
+
+pub const AFFILIATION: &str = "foundation:Affiliation";
+pub const AFFILIATED_PERSON: &str = "foundation:affiliatedPerson";
+pub const AFFILIATED_WITH: &str = "foundation:affiliatedWith";
+pub const ROLE: &str = "foundation:role";
+pub const VALID_FROM: &str = "foundation:validFrom";
+pub const VALID_UNTIL: &str = "foundation:validUntil";
+
+const PERSON: &str = "foundation:Person";
+const ORGANIZATION: &str = "foundation:Organization";
+
+/// Kinds of affiliation (subclasses of foundation:Affiliation)
+pub const KINDS: &[&str] = &["foundation:Employment", "foundation:Membership", "foundation:Education"];
+
+/// An affiliation and its period
+#[derive(Debug, Clone, PartialEq)]
+pub struct Affiliation {
+    pub iri: String,
+    pub kind: String, // Most specific class
+    pub person: String,
+    pub organization: String,
+    pub role: Option<String>,
+    pub from: Option<i64>, // Unix ms; None: unknown start
+    pub to: Option<i64>, // Unix ms; None: current
+}
+
+impl Affiliation {
+    /// Whether the period overlaps [from, to] (Unix ms, either end open)
+    pub fn overlaps(&self, from: Option<i64>, to: Option<i64>) -> bool {
+        let starts_before_end = match (self.from, to) {
+            (Some(start), Some(end)) => start <= end,
+            _ => true,
+        };
+        let ends_after_start = match (self.to, from) {
+            (Some(end), Some(start)) => end >= start,
+            _ => true,
+        };
+        starts_before_end && ends_after_start
+    }
+}
+
+/// Record a person's affiliation with an organization
+///
+/// kind: one of KINDS, or None for a plain foundation:Affiliation.
+/// period: (from, to) in Unix ms, either end unknown.
+pub fn add_affiliation(
+    conn: &mut Connection,
+    person: &str,
+    organization: &str,
+    role: Option<&str>,
+    kind: Option<&str>,
+    (from, to): (Option<i64>, Option<i64>),
+    origin: &str,
+) -> Result<String> {
+    if !has_type(conn, person, PERSON)? {
+        return Err(OwlError::ValidationError(format!("{} is not a Person", person)));
+    }
+    if !has_type(conn, organization, ORGANIZATION)? {
+        return Err(OwlError::ValidationError(format!("{} is not an Organization", organization)));
+    }
+    if let (Some(from), Some(to)) = (from, to) {
+        if from > to {
+            return Err(OwlError::ValidationError("The affiliation ends before it starts".to_string()));
+        }
+    }
+    let kind = kind.unwrap_or(AFFILIATION);
+    if kind != AFFILIATION && !KINDS.contains(&kind) {
+        return Err(OwlError::ValidationError(format!("Unknown affiliation kind: {}", kind)));
+    }
+    let role = role.map(str::trim).filter(|r| !r.is_empty());
+
+    let iri = format!(
+        "foundation:Affiliation_{}",
+        hash(&format!("{}|{}|{}|{}", person, organization, role.unwrap_or_default(), from.unwrap_or_default()))
+    );
+    if !query::get_by_entity_predicate(conn, &iri, rdf::TYPE)?.triples.is_empty() {
+        return Err(OwlError::InvalidOperation("This affiliation is already recorded".to_string()));
+    }
+
+    let organization_label = Thing::get(conn, organization).label;
+    let label = match role {
+        Some(role) => format!("{} at {}", role, organization_label),
+        None => organization_label,
+    };
+
+    let mut triples = vec![
+        Triple::new(&iri, rdf::TYPE, Object::Iri(kind.to_string())),
+        Triple::new(&iri, rdfs::LABEL, string(&label)),
+        Triple::new(&iri, AFFILIATED_PERSON, Object::Iri(person.to_string())),
+        Triple::new(&iri, AFFILIATED_WITH, Object::Iri(organization.to_string())),
+    ];
+    if let Some(role) = role {
+        triples.push(Triple::new(&iri, ROLE, string(role)));
+    }
+    if let Some(from) = from {
+        triples.push(Triple::new(&iri, VALID_FROM, Object::DateTime(from)));
+    }
+    if let Some(to) = to {
+        triples.push(Triple::new(&iri, VALID_UNTIL, Object::DateTime(to)));
+    }
+    store::assert_triples(conn, &triples, origin)?;
+    Ok(iri)
+}
+
+/// A person's affiliations, CV order: current first, then by end, latest
+/// first (ties by start, latest first)
+pub fn affiliations_of(conn: &Connection, person: &str) -> Result<Vec<Affiliation>> {
+    let mut affiliations = load(conn, AFFILIATED_PERSON, person)?;
+    affiliations.sort_by(|a, b| {
+        let end = |x: &Affiliation| x.to.unwrap_or(i64::MAX);
+        let start = |x: &Affiliation| x.from.unwrap_or(i64::MIN);
+        end(b).cmp(&end(a)).then(start(b).cmp(&start(a)))
+    });
+    Ok(affiliations)
+}
+
+/// An organization's affiliations overlapping a period (either end open),
+/// in order of start
+pub fn affiliations_with(conn: &Connection, organization: &str, from: Option<i64>, to: Option<i64>) -> Result<Vec<Affiliation>> {
+    let mut affiliations: Vec<Affiliation> = load(conn, AFFILIATED_WITH, organization)?
+        .into_iter()
+        .filter(|a| a.overlaps(from, to))
+        .collect();
+    affiliations.sort_by_key(|a| a.from.unwrap_or(i64::MIN));
+    Ok(affiliations)
+}
+
+/// Affiliations linked to an entity through a property
+fn load(conn: &Connection, property: &str, entity: &str) -> Result<Vec<Affiliation>> {
+    let mut affiliations = Vec::new();
+    for triple in query::get_by_predicate_object(conn, property, entity)?.triples {
+        let iri = triple.subject;
+        let value = |predicate: &str| -> Result<Option<Object>> {
+            Ok(query::get_by_entity_predicate(conn, &iri, predicate)?.triples.into_iter().next().map(|t| t.object))
+        };
+        let iri_value = |predicate: &str| -> Result<String> {
+            Ok(value(predicate)?.and_then(|o| o.as_iri().map(String::from)).unwrap_or_default())
+        };
+        let time = |predicate: &str| -> Result<Option<i64>> {
+            Ok(match value(predicate)? {
+                Some(Object::DateTime(ms)) => Some(ms),
+                _ => None,
+            })
+        };
+
+        let kind = query::get_by_entity_predicate(conn, &iri, rdf::TYPE)?.triples.iter()
+            .filter_map(|t| t.object.as_iri())
+            .find(|k| KINDS.contains(k))
+            .unwrap_or(AFFILIATION)
+            .to_string();
+
+        affiliations.push(Affiliation {
+            kind,
+            person: iri_value(AFFILIATED_PERSON)?,
+            organization: iri_value(AFFILIATED_WITH)?,
+            role: value(ROLE)?.and_then(|o| o.as_literal()),
+            from: time(VALID_FROM)?,
+            to: time(VALID_UNTIL)?,
+            iri,
+        });
+    }
+    Ok(affiliations)
+}
+
+fn has_type(conn: &Connection, entity: &str, class: &str) -> Result<bool> {
+    Ok(query::get_by_entity_predicate(conn, entity, rdf::TYPE)?.triples.iter()
+        .any(|t| t.object.as_iri() == Some(class)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::eavto::test_helpers::setup_test_db;
+
+    const YEAR: i64 = 365 * 86_400_000;
+
+    fn setup(conn: &mut Connection) {
+        store::assert_triples(conn, &[
+            Triple::new("foundation:Ana", rdf::TYPE, Object::Iri(PERSON.to_string())),
+            Triple::new("foundation:Bruno", rdf::TYPE, Object::Iri(PERSON.to_string())),
+            Triple::new("foundation:Acme", rdf::TYPE, Object::Iri(ORGANIZATION.to_string())),
+            Triple::new("foundation:Acme", rdfs::LABEL, string("Acme")),
+            Triple::new("foundation:Club", rdf::TYPE, Object::Iri(ORGANIZATION.to_string())),
+        ], "test").unwrap();
+    }
+
+    #[test]
+    fn test_add_affiliation_validates() {
+        let mut conn = setup_test_db();
+        setup(&mut conn);
+
+        let iri = add_affiliation(&mut conn, "foundation:Ana", "foundation:Acme", Some("Engineer"), Some("foundation:Employment"), (Some(0), None), "test").unwrap();
+        assert_eq!(Thing::get(&conn, &iri).label, "Engineer at Acme");
+
+        let again = add_affiliation(&mut conn, "foundation:Ana", "foundation:Acme", Some("Engineer"), None, (Some(0), None), "test");
+        assert!(matches!(again, Err(OwlError::InvalidOperation(_))));
+        assert!(add_affiliation(&mut conn, "foundation:Acme", "foundation:Acme", None, None, (None, None), "test").is_err());
+        assert!(add_affiliation(&mut conn, "foundation:Ana", "foundation:Club", None, None, (Some(YEAR), Some(0)), "test").is_err());
+        assert!(add_affiliation(&mut conn, "foundation:Ana", "foundation:Club", None, Some("foundation:Job"), (None, None), "test").is_err());
+    }
+
+    #[test]
+    fn test_timeline_and_members_at_a_date() {
+        let mut conn = setup_test_db();
+        setup(&mut conn);
+        add_affiliation(&mut conn, "foundation:Ana", "foundation:Acme", Some("Intern"), None, (Some(0), Some(YEAR)), "test").unwrap();
+        add_affiliation(&mut conn, "foundation:Ana", "foundation:Acme", Some("Engineer"), Some("foundation:Employment"), (Some(YEAR), None), "test").unwrap();
+        add_affiliation(&mut conn, "foundation:Ana", "foundation:Club", None, Some("foundation:Membership"), (None, Some(2 * YEAR)), "test").unwrap();
+        add_affiliation(&mut conn, "foundation:Bruno", "foundation:Acme", Some("CEO"), None, (Some(3 * YEAR), None), "test").unwrap();
+
+        let cv = affiliations_of(&conn, "foundation:Ana").unwrap();
+        let roles: Vec<Option<&str>> = cv.iter().map(|a| a.role.as_deref()).collect();
+        assert_eq!(roles, vec![Some("Engineer"), None, Some("Intern")]);
+        assert_eq!(cv[0].kind, "foundation:Employment");
+        assert_eq!((cv[2].from, cv[2].to), (Some(0), Some(YEAR)));
+
+        let members = |at: i64| -> Vec<String> {
+            affiliations_with(&conn, "foundation:Acme", Some(at), Some(at)).unwrap()
+                .into_iter().map(|a| a.role.unwrap_or_default()).collect()
+        };
+        assert_eq!(members(YEAR / 2), vec!["Intern"]);
+        assert_eq!(members(YEAR), vec!["Intern", "Engineer"]);
+        assert_eq!(members(4 * YEAR), vec!["Engineer", "CEO"]);
+        assert_eq!(affiliations_with(&conn, "foundation:Acme", None, None).unwrap().len(), 3);
+    }
+}
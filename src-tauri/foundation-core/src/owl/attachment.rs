@@ -0,0 +1,217 @@
+// ============================================================================
+// OWL Attachment - Files Attached to Entities, with Full-Text Search
+// ============================================================================
+// A foundation:Attachment is a file (foundation:filePath) attached to an
+// entity (foundation:attachedTo). The text of PDF and plain-text attachments
+// is extracted and kept in the attachment_text FTS5 index, keyed by the
+// attachment, so a search finds a contract by a clause it contains.
+//
+// The index holds derived data: attaching the same file again re-extracts
+// its text and replaces the indexed copy. Attachments whose triples were
+// retracted are left out of search results.
+// ============================================================================
+
+use rusqlite::{params, Connection};
+use std::path::Path;
+use crate::eavto::{query, store, Object, Triple};
+use crate::owl::{Thing, Result, hash, string, OwlError, vocabulary::{rdf, rdfs}};
+
+pub const ATTACHMENT: &str = "foundation:Attachment";
+pub const ATTACHED_TO: &str = "foundation:attachedTo";
+pub const FILE_PATH: &str = "foundation:filePath";
+pub const MEDIA_TYPE: &str = "foundation:mediaType";
+
+/// Media types by file extension; text/* and application/pdf are indexed
+const MEDIA_TYPES: &[(&str, &str)] = &[
+    ("pdf", "application/pdf"),
+    ("txt", "text/plain"),
+    ("text", "text/plain"),
+    ("log", "text/plain"),
+    ("md", "text/markdown"),
+    ("markdown", "text/markdown"),
+    ("csv", "text/csv"),
+    ("html", "text/html"),
+    ("htm", "text/html"),
+];
+
+/// Words of context on each side of a match in search snippets
+const SNIPPET_WORDS: i64 = 12;
+
+/// Outcome of attaching a file
+#[derive(Debug, Clone, PartialEq)]
+pub struct AttachmentImport {
+    pub iri: String,
+    pub media_type: String,
+    pub characters: usize, // Indexed text length (0: nothing to index)
+}
+
+/// An attachment whose text matches a search
+#[derive(Debug, Clone, PartialEq)]
+pub struct AttachmentHit {
+    pub iri: String,
+    pub label: String,
+    pub attached_to: Option<String>,
+    pub snippet: String, // Matched terms between [ and ]
+}
+
+/// Attach a file to an entity and index its text
+pub fn add_attachment(conn: &mut Connection, path: &Path, attached_to: &str, origin: &str) -> Result<AttachmentImport> {
+    if query::get_by_entity_predicate(conn, attached_to, rdf::TYPE)?.triples.is_empty() {
+        return Err(OwlError::NotFound(format!("Entity {}", attached_to)));
+    }
+    let path = path.canonicalize()
+        .map_err(|e| OwlError::ValidationError(format!("Cannot read {}: {}", path.display(), e)))?;
+    let file_name = path.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default();
+    let media_type = media_type(&path);
+    let text = extract_text(&path, media_type)?;
+
+    let path_text = path.to_string_lossy().to_string();
+    let iri = format!("foundation:Attachment_{}", hash(&format!("{}|{}", attached_to, path_text)));
+    let mut triples = vec![
+        Triple::new(&iri, rdf::TYPE, Object::Iri(ATTACHMENT.to_string())),
+        Triple::new(&iri, rdfs::LABEL, string(&file_name)),
+        Triple::new(&iri, FILE_PATH, string(&path_text)),
+        Triple::new(&iri, MEDIA_TYPE, string(media_type)),
+        Triple::new(&iri, ATTACHED_TO, Object::Iri(attached_to.to_string())),
+    ];
+    // Attaching the same file again only refreshes its text
+    if !query::get_by_entity_predicate(conn, &iri, rdf::TYPE)?.triples.is_empty() {
+        triples.clear();
+    }
+    if !triples.is_empty() {
+        store::assert_triples(conn, &triples, origin)?;
+    }
+
+    let characters = index_text(conn, &iri, text.as_deref().unwrap_or_default())?;
+    Ok(AttachmentImport { iri, media_type: media_type.to_string(), characters })
+}
+
+/// Replace the indexed text of an attachment, returning its length
+pub fn index_text(conn: &Connection, attachment: &str, text: &str) -> Result<usize> {
+    conn.execute("DELETE FROM attachment_text WHERE entity = ?1", params![attachment])?;
+    let text = text.split_whitespace().collect::<Vec<_>>().join(" ");
+    if !text.is_empty() {
+        conn.execute("INSERT INTO attachment_text (entity, text) VALUES (?1, ?2)", params![attachment, text])?;
+    }
+    Ok(text.chars().count())
+}
+
+/// Attachments whose text contains every word of the query (word prefixes
+/// match), best match first
+pub fn search_attachments(conn: &Connection, query: &str, limit: usize) -> Result<Vec<AttachmentHit>> {
+    let Some(expression) = match_expression(query) else { return Ok(Vec::new()) };
+
+    let mut stmt = conn.prepare(
+        "SELECT entity, snippet(attachment_text, 1, '[', ']', '…', ?2)
+         FROM attachment_text WHERE attachment_text MATCH ?1
+         ORDER BY rank"
+    )?;
+    let rows = stmt.query_map(params![expression, SNIPPET_WORDS], |row| Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?)))?
+        .collect::<std::result::Result<Vec<_>, _>>()?;
+
+    let mut hits = Vec::new();
+    for (iri, snippet) in rows {
+        if hits.len() >= limit {
+            break;
+        }
+        let is_attachment = query::get_by_entity_predicate(conn, &iri, rdf::TYPE)?.triples.iter()
+            .any(|t| t.object.as_iri() == Some(ATTACHMENT));
+        if !is_attachment {
+            continue; // Retracted
+        }
+        let attached_to = query::get_by_entity_predicate(conn, &iri, ATTACHED_TO)?.triples.first()
+            .and_then(|t| t.object.as_iri().map(String::from));
+        hits.push(AttachmentHit { label: Thing::get(conn, &iri).label, attached_to, snippet, iri });
+    }
+    Ok(hits)
+}
+
+/// FTS5 expression for a search: every word as a quoted prefix, so
+/// punctuation in the query is never read as query syntax
+fn match_expression(query: &str) -> Option<String> {
+    let terms: Vec<String> = query.split_whitespace()
+        .map(|word| format!("\"{}\"*", word.replace('"', "\"\"")))
+        .collect();
+    (!terms.is_empty()).then(|| terms.join(" "))
+}
+
+fn media_type(path: &Path) -> &'static str {
+    let extension = path.extension().map(|e| e.to_string_lossy().to_lowercase()).unwrap_or_default();
+    MEDIA_TYPES.iter()
+        .find(|(ext, _)| *ext == extension)
+        .map(|(_, media_type)| *media_type)
+        .unwrap_or("application/octet-stream")
+}
+
+/// Text of a file, None when the media type has no text to index
+fn extract_text(path: &Path, media_type: &str) -> Result<Option<String>> {
+    if media_type == "application/pdf" {
+        let path = path.to_path_buf();
+        // pdf-extract panics on some malformed PDFs
+        return match std::panic::catch_unwind(move || pdf_extract::extract_text(&path)) {
+            Ok(Ok(text)) => Ok(Some(text)),
+            Ok(Err(e)) => Err(OwlError::ValidationError(format!("Cannot extract PDF text: {}", e))),
+            Err(_) => Err(OwlError::ValidationError("Cannot extract PDF text: malformed PDF".to_string())),
+        };
+    }
+    if media_type.starts_with("text/") {
+        let bytes = std::fs::read(path)
+            .map_err(|e| OwlError::ValidationError(format!("Cannot read {}: {}", path.display(), e)))?;
+        return Ok(Some(String::from_utf8_lossy(&bytes).to_string()));
+    }
+    Ok(None)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::eavto::test_helpers::setup_test_db;
+
+    fn setup(conn: &mut Connection) -> tempfile::TempDir {
+        store::assert_triples(conn, &[
+            Triple::new("foundation:Apartment", rdf::TYPE, Object::Iri("foundation:Place".to_string())),
+        ], "test").unwrap();
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("lease.txt"), "The tenant pays the rent\non the fifth business day.\nPets are not allowed.").unwrap();
+        std::fs::write(dir.path().join("photo.jpg"), [0xff, 0xd8, 0xff]).unwrap();
+        dir
+    }
+
+    #[test]
+    fn test_attach_and_search_text() {
+        let mut conn = setup_test_db();
+        let dir = setup(&mut conn);
+
+        let lease = add_attachment(&mut conn, &dir.path().join("lease.txt"), "foundation:Apartment", "test").unwrap();
+        assert_eq!(lease.media_type, "text/plain");
+        assert!(lease.characters > 0);
+        let photo = add_attachment(&mut conn, &dir.path().join("photo.jpg"), "foundation:Apartment", "test").unwrap();
+        assert_eq!(photo.characters, 0);
+
+        let hits = search_attachments(&conn, "busi pets", 10).unwrap();
+        assert_eq!(hits.len(), 1);
+        assert_eq!(hits[0].iri, lease.iri);
+        assert_eq!(hits[0].label, "lease.txt");
+        assert_eq!(hits[0].attached_to.as_deref(), Some("foundation:Apartment"));
+        assert!(hits[0].snippet.contains("[Pets]"));
+
+        assert!(search_attachments(&conn, "garden", 10).unwrap().is_empty());
+        assert!(search_attachments(&conn, "\"rent) OR", 10).is_ok());
+    }
+
+    #[test]
+    fn test_reattach_replaces_text() {
+        let mut conn = setup_test_db();
+        let dir = setup(&mut conn);
+        let path = dir.path().join("lease.txt");
+
+        let first = add_attachment(&mut conn, &path, "foundation:Apartment", "test").unwrap();
+        std::fs::write(&path, "Rewritten: cats allowed").unwrap();
+        let second = add_attachment(&mut conn, &path, "foundation:Apartment", "test").unwrap();
+
+        assert_eq!(first.iri, second.iri);
+        assert!(search_attachments(&conn, "tenant", 10).unwrap().is_empty());
+        assert_eq!(search_attachments(&conn, "cats", 10).unwrap().len(), 1);
+        assert!(add_attachment(&mut conn, &path, "foundation:Nowhere", "test").is_err());
+    }
+}
@@ -0,0 +1,356 @@
+// ============================================================================
+// OWL Browsing - Web History Import
+// ============================================================================
+// Imports browser history databases (Chrome and Chromium-based browsers,
+// Firefox, Safari) as foundation:WebActivity facts, one per website and day
+// with the number of page visits. A year of browsing is hundreds of thousands
+// of page visits; per-day aggregates keep the graph usable. Individual page
+// visits (foundation:PageVisit) are only asserted when `raw` is set.
+//
+// Websites are identified by registered domain (mail.google.com is
+// google.com) and linked to the foundation:Organization operating them,
+// through a built-in lookup table extended by the caller.
+//
+// Re-importing a newer copy of the history only raises the counts of days
+// already imported: browsers expire old history, and a day that lost visits
+// to expiry keeps what was imported before.
+// ============================================================================
+
+use rusqlite::{Connection, OpenFlags};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::path::Path;
+use crate::eavto::{query, store, Object, Triple};
+use crate::owl::{finance, text, Class, Result, hash, string, OwlError, vocabulary::{rdf, rdfs}};
+
+pub const WEBSITE: &str = "foundation:Website";
+pub const WEB_ACTIVITY: &str = "foundation:WebActivity";
+pub const PAGE_VISIT: &str = "foundation:PageVisit";
+pub const DOMAIN: &str = "foundation:domain";
+pub const OPERATED_BY: &str = "foundation:operatedBy";
+pub const WEBSITE_PROPERTY: &str = "foundation:website";
+pub const ACTIVITY_DATE: &str = "foundation:activityDate";
+pub const VISIT_COUNT: &str = "foundation:visitCount";
+pub const PAGE_URL: &str = "foundation:pageUrl";
+pub const VISITED_AT: &str = "foundation:visitedAt";
+
+const DAY: i64 = 86_400_000;
+
+/// Microseconds between 1601-01-01 (Chrome/Windows epoch) and 1970-01-01
+const CHROME_EPOCH_OFFSET: i64 = 11_644_473_600_000_000;
+
+/// Seconds between 1970-01-01 and 2001-01-01 (Safari/Core Data epoch)
+const SAFARI_EPOCH_OFFSET: f64 = 978_307_200.0;
+
+/// Organizations operating common websites (registered domain, organization)
+const KNOWN_ORGANIZATIONS: &[(&str, &str)] = &[
+    ("google.com", "Google"),
+    ("youtube.com", "Google"),
+    ("gmail.com", "Google"),
+    ("github.com", "GitHub"),
+    ("microsoft.com", "Microsoft"),
+    ("live.com", "Microsoft"),
+    ("office.com", "Microsoft"),
+    ("linkedin.com", "LinkedIn"),
+    ("apple.com", "Apple"),
+    ("icloud.com", "Apple"),
+    ("amazon.com", "Amazon"),
+    ("facebook.com", "Meta"),
+    ("instagram.com", "Meta"),
+    ("whatsapp.com", "Meta"),
+    ("x.com", "X"),
+    ("twitter.com", "X"),
+    ("wikipedia.org", "Wikimedia Foundation"),
+    ("reddit.com", "Reddit"),
+    ("netflix.com", "Netflix"),
+    ("spotify.com", "Spotify"),
+    ("stackoverflow.com", "Stack Overflow"),
+];
+
+/// Public suffixes with two labels, for registered domains (bbc.co.uk)
+const TWO_LABEL_SUFFIXES: &[&str] = &[
+    "co.uk", "org.uk", "ac.uk", "gov.uk", "com.au", "net.au", "org.au", "co.jp", "ne.jp", "or.jp",
+    "com.br", "net.br", "org.br", "gov.br", "co.nz", "co.in", "com.mx", "com.ar", "co.za", "com.cn",
+];
+
+/// How to import a history
+#[derive(Debug, Clone, Default)]
+pub struct WebHistoryOptions {
+    pub raw: bool, // Also assert every page visit
+    pub organizations: HashMap<String, String>, // Registered domain -> organization name or IRI
+}
+
+/// A page opened in the browser
+#[derive(Debug, Clone, PartialEq)]
+pub struct PageView {
+    pub url: String,
+    pub visited_at: i64, // Unix ms
+}
+
+/// Outcome of an import
+#[derive(Debug, Clone, PartialEq)]
+pub struct WebHistoryImport {
+    pub visits: usize, // Page visits read (http and https)
+    pub activities: usize, // Website-days created
+    pub updated: usize, // Website-days with a higher count
+    pub websites: usize, // Websites created
+    pub organizations: usize, // Organizations created
+    pub page_visits: usize, // Page visits created (raw)
+    pub tx: Option<i64>,
+}
+
+/// Import a browser history database (Chrome "History", Firefox
+/// "places.sqlite" or Safari "History.db")
+pub fn import_web_history(conn: &mut Connection, path: &Path, options: &WebHistoryOptions) -> Result<WebHistoryImport> {
+    let views = read_history(path)?;
+    let file_name = path.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default();
+    import_page_views(conn, &views, options, &format!("import:{}", file_name))
+}
+
+/// Page views of a browser history database
+///
+/// The database is opened immutable, so a copy the browser still has open can
+/// be read without waiting for its lock.
+pub fn read_history(path: &Path) -> Result<Vec<PageView>> {
+    let uri = format!(
+        "file:{}?immutable=1",
+        path.to_string_lossy().replace('%', "%25").replace('?', "%3f").replace('#', "%23")
+    );
+    let history = Connection::open_with_flags(uri, OpenFlags::SQLITE_OPEN_READ_ONLY | OpenFlags::SQLITE_OPEN_URI)?;
+
+    let tables: HashSet<String> = history
+        .prepare("SELECT name FROM sqlite_master WHERE type = 'table'")?
+        .query_map([], |row| row.get(0))?
+        .collect::<std::result::Result<_, _>>()?;
+
+    // (query, visit time -> Unix ms)
+    let (sql, to_ms): (&str, fn(f64) -> i64) = if tables.contains("urls") && tables.contains("visits") {
+        ("SELECT u.url, v.visit_time FROM visits v JOIN urls u ON u.id = v.url",
+         |t| ((t as i64) - CHROME_EPOCH_OFFSET) / 1000)
+    } else if tables.contains("moz_places") && tables.contains("moz_historyvisits") {
+        ("SELECT p.url, v.visit_date FROM moz_historyvisits v JOIN moz_places p ON p.id = v.place_id",
+         |t| (t as i64) / 1000)
+    } else if tables.contains("history_items") && tables.contains("history_visits") {
+        ("SELECT i.url, v.visit_time FROM history_visits v JOIN history_items i ON i.id = v.history_item",
+         |t| ((t + SAFARI_EPOCH_OFFSET) * 1000.0) as i64)
+    } else {
+        return Err(OwlError::ValidationError(format!("Not a Chrome, Firefox or Safari history database: {}", path.display())));
+    };
+
+    let views = history
+        .prepare(sql)?
+        .query_map([], |row| Ok(PageView { url: row.get(0)?, visited_at: to_ms(row.get::<_, f64>(1)?) }))?
+        .collect::<std::result::Result<Vec<_>, _>>()?;
+    Ok(views)
+}
+
+/// Aggregate page views per website and day and assert them
+pub fn import_page_views(
+    conn: &mut Connection,
+    views: &[PageView],
+    options: &WebHistoryOptions,
+    origin: &str,
+) -> Result<WebHistoryImport> {
+    let mut report = WebHistoryImport {
+        visits: 0, activities: 0, updated: 0, websites: 0, organizations: 0, page_visits: 0, tx: None,
+    };
+    let mut days: BTreeMap<(String, i64), i64> = BTreeMap::new();
+    let mut websites: HashSet<String> = Class::get_instances(conn, WEBSITE)?.into_iter().collect();
+    let activities: HashSet<String> = Class::get_instances(conn, WEB_ACTIVITY)?.into_iter().collect();
+    let mut page_visits: HashSet<String> = if options.raw {
+        Class::get_instances(conn, PAGE_VISIT)?.into_iter().collect()
+    } else {
+        HashSet::new()
+    };
+    let mut organizations = finance::organizations_by_label(conn)?;
+    let mut additions = Vec::new();
+    let mut retractions = Vec::new();
+
+    for view in views {
+        let Some(domain) = registered_domain(&view.url) else { continue };
+        report.visits += 1;
+        *days.entry((domain.clone(), view.visited_at - view.visited_at.rem_euclid(DAY))).or_default() += 1;
+
+        let website = website_iri(&domain);
+        if websites.insert(website.clone()) {
+            additions.push(Triple::new(&website, rdf::TYPE, Object::Iri(WEBSITE.to_string())));
+            additions.push(Triple::new(&website, rdfs::LABEL, string(&domain)));
+            additions.push(Triple::new(&website, DOMAIN, string(&domain)));
+
+            let operator = options.organizations.get(&domain).map(String::as_str)
+                .or_else(|| KNOWN_ORGANIZATIONS.iter().find(|(d, _)| *d == domain).map(|(_, o)| *o));
+            if let Some(operator) = operator {
+                let organization = if operator.contains(':') {
+                    operator.to_string()
+                } else {
+                    let key = text::fold(operator);
+                    match organizations.get(&key) {
+                        Some(organization) => organization.clone(),
+                        None => {
+                            let organization = format!("foundation:Organization_{}", hash(&key));
+                            additions.push(Triple::new(&organization, rdf::TYPE, Object::Iri(finance::ORGANIZATION.to_string())));
+                            additions.push(Triple::new(&organization, rdfs::LABEL, string(operator)));
+                            organizations.insert(key, organization.clone());
+                            report.organizations += 1;
+                            organization
+                        }
+                    }
+                };
+                additions.push(Triple::new(&website, OPERATED_BY, Object::Iri(organization)));
+            }
+            report.websites += 1;
+        }
+
+        if options.raw {
+            let visit = format!("foundation:PageVisit_{}", hash(&format!("{}|{}", view.url, view.visited_at)));
+            if page_visits.insert(visit.clone()) {
+                additions.push(Triple::new(&visit, rdf::TYPE, Object::Iri(PAGE_VISIT.to_string())));
+                additions.push(Triple::new(&visit, rdfs::LABEL, string(&view.url)));
+                additions.push(Triple::new(&visit, PAGE_URL, Object::Literal {
+                    value: view.url.clone(), datatype: Some("xsd:anyURI".to_string()), language: None,
+                }));
+                additions.push(Triple::new(&visit, WEBSITE_PROPERTY, Object::Iri(website)));
+                additions.push(Triple::new(&visit, VISITED_AT, Object::DateTime(view.visited_at)));
+                report.page_visits += 1;
+            }
+        }
+    }
+
+    for ((domain, day), count) in days {
+        let activity = format!("foundation:WebActivity_{}", hash(&format!("{}|{}", domain, day)));
+        if activities.contains(&activity) {
+            let current = query::get_by_entity_predicate(conn, &activity, VISIT_COUNT)?.triples;
+            let previous = current.iter().filter_map(|t| t.object.as_literal()?.parse::<i64>().ok()).max().unwrap_or(0);
+            if count > previous {
+                additions.push(Triple::new(&activity, VISIT_COUNT, Object::Integer(count)));
+                retractions.extend(current);
+                report.updated += 1;
+            }
+            continue;
+        }
+
+        let date = chrono::DateTime::from_timestamp_millis(day).map(|d| d.format("%Y-%m-%d").to_string()).unwrap_or_default();
+        additions.push(Triple::new(&activity, rdf::TYPE, Object::Iri(WEB_ACTIVITY.to_string())));
+        additions.push(Triple::new(&activity, rdfs::LABEL, string(&format!("{}, {}", domain, date))));
+        additions.push(Triple::new(&activity, WEBSITE_PROPERTY, Object::Iri(website_iri(&domain))));
+        additions.push(Triple::new(&activity, ACTIVITY_DATE, Object::DateTime(day)));
+        additions.push(Triple::new(&activity, VISIT_COUNT, Object::Integer(count)));
+        report.activities += 1;
+    }
+
+    if !additions.is_empty() {
+        report.tx = Some(store::apply_changes(conn, &additions, &retractions, origin)?);
+    }
+    Ok(report)
+}
+
+/// Registered domain of an http(s) URL, lowercased and without subdomains
+/// (hosts without a dot, like localhost, and IP addresses are kept whole)
+pub fn registered_domain(url: &str) -> Option<String> {
+    let rest = url.strip_prefix("https://").or_else(|| url.strip_prefix("http://"))?;
+    let authority = rest.split(['/', '?', '#']).next()?;
+    let host = authority.rsplit('@').next()?;
+    if host.starts_with('[') {
+        return None; // IPv6
+    }
+    let host = host.split(':').next()?.trim_end_matches('.').to_lowercase();
+    if host.is_empty() {
+        return None;
+    }
+    if host.parse::<std::net::Ipv4Addr>().is_ok() {
+        return Some(host);
+    }
+
+    let labels: Vec<&str> = host.split('.').collect();
+    let suffix = labels[labels.len().saturating_sub(2)..].join(".");
+    let keep = if labels.len() >= 3 && TWO_LABEL_SUFFIXES.contains(&suffix.as_str()) { 3 } else { 2 };
+    Some(labels[labels.len().saturating_sub(keep)..].join("."))
+}
+
+fn website_iri(domain: &str) -> String {
+    format!("foundation:Website_{}", hash(domain))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::eavto::test_helpers::setup_test_db;
+    use crate::owl::Thing;
+
+    /// A Chrome history database with visits (url, Unix ms)
+    fn chrome_history(path: &Path, visits: &[(&str, i64)]) {
+        let history = Connection::open(path).unwrap();
+        history.execute_batch(
+            "CREATE TABLE urls (id INTEGER PRIMARY KEY, url TEXT);
+             CREATE TABLE visits (id INTEGER PRIMARY KEY, url INTEGER, visit_time INTEGER);"
+        ).unwrap();
+        for (url, ms) in visits {
+            history.execute("INSERT INTO urls (url) VALUES (?)", [url]).unwrap();
+            history.execute(
+                "INSERT INTO visits (url, visit_time) VALUES (last_insert_rowid(), ?)",
+                [ms * 1000 + CHROME_EPOCH_OFFSET],
+            ).unwrap();
+        }
+    }
+
+    #[test]
+    fn test_registered_domain() {
+        assert_eq!(registered_domain("https://mail.google.com/mail/u/0").as_deref(), Some("google.com"));
+        assert_eq!(registered_domain("http://www.bbc.co.uk/news?x=1").as_deref(), Some("bbc.co.uk"));
+        assert_eq!(registered_domain("http://user@localhost:3000/").as_deref(), Some("localhost"));
+        assert_eq!(registered_domain("https://192.168.0.1/admin").as_deref(), Some("192.168.0.1"));
+        assert_eq!(registered_domain("chrome://settings"), None);
+        assert_eq!(registered_domain("file:///home/user/page.html"), None);
+    }
+
+    #[test]
+    fn test_import_aggregates_per_domain_and_day() {
+        let mut conn = setup_test_db();
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("History");
+        chrome_history(&path, &[
+            ("https://github.com/a", 1_000),
+            ("https://gist.github.com/b", 2_000),
+            ("https://example.org/", 3_000),
+            ("https://github.com/c", DAY + 1_000),
+            ("chrome://newtab", 4_000),
+        ]);
+        let options = WebHistoryOptions {
+            organizations: HashMap::from([("example.org".to_string(), "Example Inc".to_string())]),
+            ..Default::default()
+        };
+
+        let report = import_web_history(&mut conn, &path, &options).unwrap();
+        assert_eq!((report.visits, report.activities, report.websites, report.organizations), (4, 3, 2, 2));
+        assert_eq!(report.page_visits, 0);
+
+        let first_day = format!("foundation:WebActivity_{}", hash("github.com|0"));
+        let count = query::get_by_entity_predicate(&conn, &first_day, VISIT_COUNT).unwrap();
+        assert_eq!(count.triples[0].object.as_literal().as_deref(), Some("2"));
+
+        let operator = query::get_by_entity_predicate(&conn, &website_iri("github.com"), OPERATED_BY).unwrap();
+        assert_eq!(Thing::get(&conn, operator.triples[0].object.as_iri().unwrap()).label, "GitHub");
+    }
+
+    #[test]
+    fn test_reimport_raises_counts_and_keeps_raw_visits() {
+        let mut conn = setup_test_db();
+        let view = |url: &str, ms: i64| PageView { url: url.to_string(), visited_at: ms };
+        let raw = WebHistoryOptions { raw: true, ..Default::default() };
+
+        let first = import_page_views(&mut conn, &[view("https://github.com/a", 1_000)], &raw, "test").unwrap();
+        assert_eq!((first.activities, first.page_visits), (1, 1));
+
+        let views = [view("https://github.com/a", 1_000), view("https://github.com/b", 2_000)];
+        let second = import_page_views(&mut conn, &views, &raw, "test").unwrap();
+        assert_eq!((second.activities, second.updated, second.page_visits, second.websites), (0, 1, 1, 0));
+
+        let activity = format!("foundation:WebActivity_{}", hash("github.com|0"));
+        let count = query::get_by_entity_predicate(&conn, &activity, VISIT_COUNT).unwrap();
+        assert_eq!(count.triples.len(), 1);
+        assert_eq!(count.triples[0].object.as_literal().as_deref(), Some("2"));
+
+        // An older copy with fewer visits changes nothing
+        let older = import_page_views(&mut conn, &[view("https://github.com/a", 1_000)], &raw, "test").unwrap();
+        assert_eq!((older.updated, older.tx), (0, None));
+    }
+}
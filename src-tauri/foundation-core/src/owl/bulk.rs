@@ -0,0 +1,392 @@
+// ============================================================================
+// OWL Bulk - Spreadsheet-Style Edits Across Instances
+// ============================================================================
+// Applies one value change to a property of every instance of a class:
+// - Set: replace the values with one value (added where missing)
+// - Replace: find-and-replace in string values
+// - ConvertUnit: convert numeric values between QUDT units
+//
+// A literal can also be renamed across the whole store (plan_literal_replace):
+// every fact whose value is exactly the old text, on any entity, gets the new
+// text, keeping its datatype and language. Facts whose datatype the new text
+// doesn't fit (e.g. "soon" for an xsd:date) are left alone and reported.
+//
+// Edits are planned first, so the affected entities can be previewed (dry
+// run), then applied as a single transaction. Planning checks every new
+// value: a typed literal must parse as its datatype and fall in the
+// property's range, so a bad value shows in the preview rather than failing
+// the write.
+// ============================================================================
+
+use rusqlite::Connection;
+use std::collections::hash_map::{Entry, HashMap};
+use crate::eavto::{query, store, Annotation, Object, Triple, TypedValue, ONTOLOGY_GRAPH};
+use crate::owl::{integrity, Class, Property, Result, OwlError};
+
+const CONVERSION_MULTIPLIER: &str = "qudt:conversionMultiplier";
+const CONVERSION_OFFSET: &str = "qudt:conversionOffset";
+const DIMENSION_VECTOR: &str = "qudt:hasDimensionVector";
+
+/// Value change applied to every instance
+#[derive(Debug, Clone, PartialEq)]
+pub enum ValueMapping {
+    Set(Object),
+    Replace { find: String, replace: String },
+    ConvertUnit { from: String, to: String },
+}
+
+/// Planned change to one entity
+#[derive(Debug, Clone)]
+pub struct BulkChange {
+    pub entity: String,
+    pub before: Vec<Triple>,
+    pub after: Vec<Triple>,
+}
+
+/// A fact a plan leaves alone, and why
+#[derive(Debug, Clone)]
+pub struct SkippedFact {
+    pub triple: Triple,
+    pub reason: String,
+}
+
+/// Plan a bulk edit of a property across the instances of a class
+///
+/// Entities the mapping leaves unchanged are not part of the plan. New values
+/// that don't fit the property fail the plan with a ValidationError listing
+/// them.
+pub fn plan(conn: &Connection, class: &str, property: &str, mapping: &ValueMapping) -> Result<Vec<BulkChange>> {
+    let convert = match mapping {
+        ValueMapping::ConvertUnit { from, to } => Some(conversion(conn, from, to)?),
+        _ => None,
+    };
+
+    let declared = Property::get(conn, property)?;
+    let mut misfits = Vec::new();
+    let mut changes = Vec::new();
+    for entity in Class::get_instances(conn, class)? {
+        let before = query::get_by_entity_predicate(conn, &entity, property)?.triples;
+
+        let after: Vec<Triple> = match mapping {
+            ValueMapping::Set(value) => vec![Triple::new(&entity, property, value.clone())],
+            ValueMapping::Replace { find, replace } => before.iter()
+                .map(|t| match &t.object {
+                    Object::Literal { value, datatype, language } if !find.is_empty() => Triple::new(&entity, property, Object::Literal {
+                        value: value.replace(find.as_str(), replace),
+                        datatype: datatype.clone(),
+                        language: language.clone(),
+                    }),
+                    _ => Triple::new(&entity, property, t.object.clone()),
+                })
+                .collect(),
+            ValueMapping::ConvertUnit { .. } => {
+                let (multiplier, offset) = convert.unwrap();
+                before.iter()
+                    .map(|t| match number(&t.object) {
+                        Some(n) => Triple::new(&entity, property, Object::Number(n * multiplier + offset)),
+                        None => Triple::new(&entity, property, t.object.clone()),
+                    })
+                    .collect()
+            }
+        };
+
+        let unchanged = before.len() == after.len()
+            && before.iter().zip(&after).all(|(b, a)| b.object == a.object);
+        if unchanged {
+            continue;
+        }
+        for triple in after.iter().filter(|a| !before.iter().any(|b| b.object == a.object)) {
+            if let Some(reason) = misfit(conn, &declared, &triple.object)? {
+                misfits.push(format!("{}: {}", entity, reason));
+            }
+        }
+        changes.push(BulkChange { entity, before, after });
+    }
+
+    if !misfits.is_empty() {
+        return Err(OwlError::ValidationError(format!(
+            "{} new value(s) don't fit {}: {}", misfits.len(), property, misfits.join("; ")
+        )));
+    }
+    Ok(changes)
+}
+
+/// Plan renaming a literal value across the store: every current fact whose
+/// value is exactly `old_value` (of `property`, or of any property) gets
+/// `new_value`
+///
+/// The ontology graph is left alone; entities are in IRI order. Facts the
+/// new value doesn't fit (its datatype, or the property's range) are skipped
+/// and returned next to the changes.
+pub fn plan_literal_replace(
+    conn: &Connection,
+    property: Option<&str>,
+    old_value: &str,
+    new_value: &str,
+) -> Result<(Vec<BulkChange>, Vec<SkippedFact>)> {
+    if old_value == new_value {
+        return Ok((vec![], vec![]));
+    }
+    let pattern = Object::Literal { value: old_value.to_string(), datatype: None, language: None };
+
+    let mut properties: HashMap<String, Property> = HashMap::new();
+    let mut changes: Vec<BulkChange> = Vec::new();
+    let mut skipped = Vec::new();
+    for triple in query::get_by_pattern(conn, None, property, Some(&pattern))?.triples {
+        let Object::Literal { datatype, language, .. } = &triple.object else { continue };
+        if triple.graph == ONTOLOGY_GRAPH {
+            continue;
+        }
+        let after = Triple::new(&triple.subject, &triple.predicate, Object::Literal {
+            value: new_value.to_string(),
+            datatype: datatype.clone(),
+            language: language.clone(),
+        });
+        let declared = match properties.entry(triple.predicate.clone()) {
+            Entry::Occupied(entry) => entry.into_mut(),
+            Entry::Vacant(entry) => entry.insert(Property::get(conn, &triple.predicate)?),
+        };
+        if let Some(reason) = misfit(conn, declared, &after.object)? {
+            skipped.push(SkippedFact { triple, reason });
+            continue;
+        }
+        match changes.last_mut() {
+            Some(change) if change.entity == triple.subject => {
+                change.before.push(triple);
+                change.after.push(after);
+            }
+            _ => changes.push(BulkChange { entity: triple.subject.clone(), before: vec![triple], after: vec![after] }),
+        }
+    }
+    Ok((changes, skipped))
+}
+
+/// Apply planned changes as one transaction; returns the transaction ID
+/// (None if there was nothing to change)
+pub fn apply(conn: &mut Connection, changes: &[BulkChange], origin: &str, annotation: &Annotation) -> Result<Option<i64>> {
+    if changes.is_empty() {
+        return Ok(None);
+    }
+
+    let retractions: Vec<Triple> = changes.iter().flat_map(|c| c.before.iter().cloned()).collect();
+    let mut additions: Vec<Triple> = changes.iter().flat_map(|c| c.after.iter().cloned()).collect();
+    additions.extend(integrity::check_predicates(conn, &additions)?);
+    Ok(Some(store::apply_annotated_changes(conn, &additions, &retractions, origin, annotation)?))
+}
+
+/// Why a value can't be written to a property, if it can't: a typed literal
+/// must parse as its datatype (the store refuses it otherwise) and the value
+/// must fall in the property's range
+fn misfit(conn: &Connection, property: &Property, object: &Object) -> Result<Option<String>> {
+    if let Object::Literal { value, datatype: Some(datatype), .. } = object {
+        if TypedValue::has_column(datatype) && TypedValue::parse(value, datatype).is_none() {
+            return Ok(Some(format!("'{}' is not a valid {}", value, datatype)));
+        }
+    }
+    match property.check_range(conn, object) {
+        Ok(()) => Ok(None),
+        Err(error @ OwlError::RangeError { .. }) => Ok(Some(error.to_string())),
+        Err(error) => Err(error),
+    }
+}
+
+/// Linear conversion between two QUDT units, as (multiplier, offset)
+///
+/// QUDT defines each unit by its conversion to the SI base unit:
+/// base = (value + offset) * multiplier
+fn conversion(conn: &Connection, from: &str, to: &str) -> Result<(f64, f64)> {
+    let dimension = |unit: &str| -> Result<Option<String>> {
+        Ok(query::get_by_entity_predicate(conn, unit, DIMENSION_VECTOR)?
+            .triples.first()
+            .and_then(|t| t.object.as_iri().map(String::from)))
+    };
+    if dimension(from)? != dimension(to)? {
+        return Err(OwlError::ValidationError(format!("Can't convert {} to {}: different dimensions", from, to)));
+    }
+
+    let (from_multiplier, from_offset) = unit_factors(conn, from)?;
+    let (to_multiplier, to_offset) = unit_factors(conn, to)?;
+
+    let multiplier = from_multiplier / to_multiplier;
+    let offset = from_offset * multiplier - to_offset;
+    Ok((multiplier, offset))
+}
+
+fn unit_factors(conn: &Connection, unit: &str) -> Result<(f64, f64)> {
+    let value = |predicate: &str| -> Result<Option<f64>> {
+        Ok(query::get_by_entity_predicate(conn, unit, predicate)?
+            .triples.first()
+            .and_then(|t| number(&t.object)))
+    };
+
+    let multiplier = value(CONVERSION_MULTIPLIER)?
+        .filter(|m| *m != 0.0)
+        .ok_or_else(|| OwlError::NotFound(format!("Conversion multiplier of {}", unit)))?;
+    Ok((multiplier, value(CONVERSION_OFFSET)?.unwrap_or(0.0)))
+}
+
+fn number(object: &Object) -> Option<f64> {
+    match object {
+        Object::Integer(i) => Some(*i as f64),
+        Object::Number(n) => Some(*n),
+        Object::Literal { value, .. } => value.parse().ok(),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::eavto::test_helpers::setup_test_db;
+    use crate::owl::vocabulary::rdf;
+
+    fn text(value: &str) -> Object {
+        Object::Literal { value: value.to_string(), datatype: Some("xsd:string".to_string()), language: None }
+    }
+
+    fn bookmark(id: usize, url: &str) -> Vec<Triple> {
+        let entity = format!("foundation:Bookmark{}", id);
+        vec![
+            Triple::new(&entity, rdf::TYPE, Object::Iri("foundation:Bookmark".to_string())),
+            Triple::new(&entity, "foundation:url", text(url)),
+        ]
+    }
+
+    fn values(conn: &Connection, entity: &str, property: &str) -> Vec<Object> {
+        query::get_by_entity_predicate(conn, entity, property).unwrap()
+            .triples.into_iter().map(|t| t.object).collect()
+    }
+
+    #[test]
+    fn test_replace_previews_then_applies_in_one_transaction() {
+        let mut conn = setup_test_db();
+        let mut triples = bookmark(1, "http://old.example/a");
+        triples.extend(bookmark(2, "http://old.example/b"));
+        triples.extend(bookmark(3, "https://other.example"));
+        store::assert_triples(&mut conn, &triples, "test").unwrap();
+
+        let mapping = ValueMapping::Replace { find: "http://old.".to_string(), replace: "https://new.".to_string() };
+        let changes = plan(&conn, "foundation:Bookmark", "foundation:url", &mapping).unwrap();
+        assert_eq!(changes.len(), 2);
+
+        // Planning alone changes nothing
+        assert_eq!(values(&conn, "foundation:Bookmark1", "foundation:url"), vec![text("http://old.example/a")]);
+
+        let tx = apply(&mut conn, &changes, "test", &Annotation::default()).unwrap().unwrap();
+        assert_eq!(values(&conn, "foundation:Bookmark1", "foundation:url"), vec![text("https://new.example/a")]);
+        assert_eq!(values(&conn, "foundation:Bookmark2", "foundation:url"), vec![text("https://new.example/b")]);
+        assert_eq!(query::get_by_entity_predicate(&conn, "foundation:Bookmark2", "foundation:url").unwrap().triples[0].tx, tx);
+    }
+
+    #[test]
+    fn test_set_adds_missing_values() {
+        let mut conn = setup_test_db();
+        store::assert_triples(&mut conn, &bookmark(1, "a"), "test").unwrap();
+
+        let mapping = ValueMapping::Set(Object::Boolean(true));
+        let changes = plan(&conn, "foundation:Bookmark", "foundation:archived", &mapping).unwrap();
+        apply(&mut conn, &changes, "test", &Annotation::default()).unwrap();
+        assert_eq!(values(&conn, "foundation:Bookmark1", "foundation:archived"), vec![Object::Boolean(true)]);
+
+        assert!(plan(&conn, "foundation:Bookmark", "foundation:archived", &mapping).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_set_refuses_values_that_dont_fit() {
+        let mut conn = setup_test_db();
+        let mut triples = bookmark(1, "a");
+        triples.push(Triple::new("foundation:visits", "rdfs:range", Object::Iri("xsd:integer".to_string())));
+        store::assert_triples(&mut conn, &triples, "test").unwrap();
+        let typed = |value: &str, datatype: &str| ValueMapping::Set(Object::Literal {
+            value: value.to_string(),
+            datatype: Some(datatype.to_string()),
+            language: None,
+        });
+
+        // Caught while planning (the dry run), before anything is written
+        for (property, mapping) in [
+            ("foundation:visits", typed("abc", "xsd:integer")),
+            ("foundation:visits", typed("12", "xsd:string")),
+            ("foundation:savedOn", typed("soon", "xsd:date")),
+        ] {
+            let error = plan(&conn, "foundation:Bookmark", property, &mapping).unwrap_err();
+            assert!(matches!(&error, OwlError::ValidationError(m) if m.contains("foundation:Bookmark1")), "{}", error);
+        }
+
+        let changes = plan(&conn, "foundation:Bookmark", "foundation:visits", &typed("12", "xsd:integer")).unwrap();
+        apply(&mut conn, &changes, "test", &Annotation::default()).unwrap();
+        assert_eq!(values(&conn, "foundation:Bookmark1", "foundation:visits"), vec![Object::Integer(12)]);
+    }
+
+    #[test]
+    fn test_replace_literal_across_entities() {
+        let mut conn = setup_test_db();
+        store::assert_triples(&mut conn, &[
+            Triple::new("foundation:Alice", "foundation:employer", text("Acme Corp")),
+            Triple::new("foundation:Bob", "foundation:employer", text("Acme Corp")),
+            Triple::new("foundation:Bob", "foundation:client", text("Acme Corp")),
+            Triple::new("foundation:Carol", "foundation:employer", text("Acme Corporation")),
+        ], "test").unwrap();
+
+        let (changes, _) = plan_literal_replace(&conn, Some("foundation:employer"), "Acme Corp", "ACME Corp").unwrap();
+        assert_eq!(changes.len(), 2);
+
+        let (changes, skipped) = plan_literal_replace(&conn, None, "Acme Corp", "ACME Corp").unwrap();
+        assert!(skipped.is_empty());
+        assert_eq!(changes.iter().map(|c| c.entity.as_str()).collect::<Vec<_>>(), vec!["foundation:Alice", "foundation:Bob"]);
+        assert_eq!(changes[1].after.len(), 2);
+
+        let tx = apply(&mut conn, &changes, "user-edit", &Annotation::default()).unwrap();
+        assert!(tx.is_some());
+        assert_eq!(values(&conn, "foundation:Bob", "foundation:client"), vec![text("ACME Corp")]);
+        assert_eq!(values(&conn, "foundation:Carol", "foundation:employer"), vec![text("Acme Corporation")]);
+        assert!(plan_literal_replace(&conn, None, "Acme Corp", "ACME Corp").unwrap().0.is_empty());
+    }
+
+    #[test]
+    fn test_replace_literal_skips_facts_it_doesnt_fit() {
+        let mut conn = setup_test_db();
+        let typed = |value: &str, datatype: &str| Object::Literal {
+            value: value.to_string(),
+            datatype: Some(datatype.to_string()),
+            language: None,
+        };
+        store::assert_triples(&mut conn, &[
+            Triple::new("foundation:Trip", "foundation:note", text("2024-05-01")),
+            Triple::new("foundation:Trip", "foundation:departsOn", typed("2024-05-01", "xsd:date")),
+        ], "test").unwrap();
+
+        let (changes, skipped) = plan_literal_replace(&conn, None, "2024-05-01", "soon").unwrap();
+        assert_eq!(changes.len(), 1);
+        assert_eq!((changes[0].after[0].predicate.as_str(), &changes[0].after[0].object), ("foundation:note", &text("soon")));
+        assert_eq!(skipped.len(), 1);
+        assert_eq!(skipped[0].triple.predicate, "foundation:departsOn");
+        assert!(skipped[0].reason.contains("xsd:date"));
+
+        apply(&mut conn, &changes, "user-edit", &Annotation::default()).unwrap();
+        assert_eq!(values(&conn, "foundation:Trip", "foundation:departsOn"), vec![typed("2024-05-01", "xsd:date")]);
+    }
+
+    #[test]
+    fn test_convert_unit() {
+        let mut conn = setup_test_db();
+        let unit = |iri: &str, multiplier: f64, offset: f64| vec![
+            Triple::new(iri, DIMENSION_VECTOR, Object::Iri("qkdv:A0E0L0I0M0H1T0D0".to_string())),
+            Triple::new(iri, CONVERSION_MULTIPLIER, Object::Number(multiplier)),
+            Triple::new(iri, CONVERSION_OFFSET, Object::Number(offset)),
+        ];
+        let mut triples = unit("unit:DEG_C", 1.0, 273.15);
+        triples.extend(unit("unit:K", 1.0, 0.0));
+        triples.push(Triple::new("unit:M", DIMENSION_VECTOR, Object::Iri("qkdv:A0E0L1I0M0H0T0D0".to_string())));
+        triples.push(Triple::new("foundation:Reading1", rdf::TYPE, Object::Iri("foundation:Reading".to_string())));
+        triples.push(Triple::new("foundation:Reading1", "foundation:temperature", Object::Number(20.0)));
+        store::assert_triples(&mut conn, &triples, "test").unwrap();
+
+        let mapping = ValueMapping::ConvertUnit { from: "unit:DEG_C".to_string(), to: "unit:K".to_string() };
+        let changes = plan(&conn, "foundation:Reading", "foundation:temperature", &mapping).unwrap();
+        assert!(number(&changes[0].after[0].object).is_some_and(|k| (k - 293.15).abs() < 1e-9));
+
+        let mapping = ValueMapping::ConvertUnit { from: "unit:DEG_C".to_string(), to: "unit:M".to_string() };
+        assert!(plan(&conn, "foundation:Reading", "foundation:temperature", &mapping).is_err());
+    }
+}
@@ -0,0 +1,200 @@
+// ============================================================================
+// OWL Changelog - What Happened to an Entity
+// ============================================================================
+// The history of an entity as a person reads it: "Label changed from Car to
+// Sports car", when and by which origin. Built from eavto::get_history (what
+// each transaction asserted) and the retractions, with the transaction of
+// each for its time, origin and message.
+//
+// Within a transaction, a retracted value and an asserted value of the same
+// property read as one change from the old value to the new; unpaired ones
+// are values added or removed.
+// ============================================================================
+
+use rusqlite::Connection;
+use std::collections::{BTreeMap, HashMap};
+use crate::eavto::{query, Object, Transaction, Triple};
+use crate::owl::{Result, Thing};
+
+/// What a change did to a property
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChangeKind {
+    Added,
+    Removed,
+    Changed,
+}
+
+impl ChangeKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ChangeKind::Added => "added",
+            ChangeKind::Removed => "removed",
+            ChangeKind::Changed => "changed",
+        }
+    }
+}
+
+/// A value as shown in the changelog
+#[derive(Debug, Clone, PartialEq)]
+pub struct ChangeValue {
+    pub value: String, // IRI or literal value
+    pub label: String, // Label of an IRI, readable form of a literal
+}
+
+/// One change to a property of the entity
+#[derive(Debug, Clone)]
+pub struct Change {
+    pub tx: i64,
+    pub created_at: i64, // Unix ms
+    pub origin: String,
+    pub message: Option<String>, // Of the transaction
+    pub property: Thing,
+    pub kind: ChangeKind,
+    pub from: Option<ChangeValue>, // Removed or changed values
+    pub to: Option<ChangeValue>,   // Added or changed values
+    pub description: String, // e.g. "Label changed from Car to Sports car"
+}
+
+/// Changes to an entity's properties, newest first
+pub fn entity_history(conn: &Connection, entity: &str) -> Result<Vec<Change>> {
+    // (asserted, retracted) per transaction
+    let mut by_tx: BTreeMap<i64, (Vec<Triple>, Vec<Triple>)> = BTreeMap::new();
+    for (tx, triples) in query::get_history(conn, entity)? {
+        by_tx.entry(tx).or_default().0.extend(triples);
+    }
+    for (tx, triple) in query::get_retractions(conn, entity)? {
+        by_tx.entry(tx).or_default().1.push(triple);
+    }
+
+    // Labels of the properties and IRI values
+    let mut iris: Vec<&str> = Vec::new();
+    for triple in by_tx.values().flat_map(|(asserted, retracted)| asserted.iter().chain(retracted)) {
+        iris.push(&triple.predicate);
+        iris.extend(triple.object.as_iri());
+    }
+    iris.sort();
+    iris.dedup();
+    let things: HashMap<String, Thing> = Thing::get_many(conn, &iris)
+        .into_iter()
+        .map(|thing| (thing.iri.clone(), thing))
+        .collect();
+    let value = |object: &Object| ChangeValue {
+        value: object.as_iri().map(String::from).or_else(|| object.as_literal()).unwrap_or_default(),
+        label: display(object, &things),
+    };
+
+    let mut changes = Vec::new();
+    for (tx, (asserted, retracted)) in by_tx.iter().rev() {
+        let transaction = query::get_transaction(conn, *tx)?;
+        let (created_at, origin, message) = match transaction {
+            Some(Transaction { created_at, origin, message, .. }) => (created_at, origin, message),
+            None => {
+                let Some(first) = asserted.first().or(retracted.first()) else { continue };
+                let origin = query::get_origin_name(conn, first.origin_id)?.unwrap_or_default();
+                (first.created_at, origin, None)
+            }
+        };
+
+        // Values per property, in the order the transaction wrote them
+        let mut properties: Vec<&str> = Vec::new();
+        for triple in asserted.iter().chain(retracted) {
+            if !properties.contains(&triple.predicate.as_str()) {
+                properties.push(&triple.predicate);
+            }
+        }
+        for property in properties {
+            let mut added = asserted.iter().filter(|t| t.predicate == property).map(|t| value(&t.object));
+            let mut removed = retracted.iter().filter(|t| t.predicate == property).map(|t| value(&t.object));
+            loop {
+                let (from, to) = (removed.next(), added.next());
+                let kind = match (&from, &to) {
+                    (Some(_), Some(_)) => ChangeKind::Changed,
+                    (Some(_), None) => ChangeKind::Removed,
+                    (None, Some(_)) => ChangeKind::Added,
+                    (None, None) => break,
+                };
+                let property = things[property].clone();
+                changes.push(Change {
+                    tx: *tx,
+                    created_at,
+                    origin: origin.clone(),
+                    message: message.clone(),
+                    description: describe(&property.label, kind, from.as_ref(), to.as_ref()),
+                    property,
+                    kind,
+                    from,
+                    to,
+                });
+            }
+        }
+    }
+
+    Ok(changes)
+}
+
+/// Readable form of a value: labels for IRIs, dates in RFC 3339
+fn display(object: &Object, things: &HashMap<String, Thing>) -> String {
+    match object {
+        Object::Iri(iri) | Object::Blank(iri) => things.get(iri).map_or_else(|| iri.clone(), |thing| thing.label.clone()),
+        Object::DateTime(millis) => chrono::DateTime::from_timestamp_millis(*millis)
+            .map_or_else(|| millis.to_string(), |dt| dt.to_rfc3339_opts(chrono::SecondsFormat::Secs, true)),
+        Object::Triple(quoted) => quoted.term(),
+        literal => literal.as_literal().unwrap_or_default(),
+    }
+}
+
+/// "Label changed from Car to Sports car", "Color set to Red", "Tag Old removed"
+fn describe(property: &str, kind: ChangeKind, from: Option<&ChangeValue>, to: Option<&ChangeValue>) -> String {
+    fn label(value: Option<&ChangeValue>) -> &str {
+        value.map(|v| v.label.as_str()).unwrap_or_default()
+    }
+    match kind {
+        ChangeKind::Changed => format!("{} changed from {} to {}", property, label(from), label(to)),
+        ChangeKind::Added => format!("{} set to {}", property, label(to)),
+        ChangeKind::Removed => format!("{} {} removed", property, label(from)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::eavto::{store, test_helpers::setup_test_db};
+    use crate::owl::vocabulary::rdfs;
+
+    fn label(value: &str) -> Object {
+        Object::Literal { value: value.to_string(), datatype: None, language: None }
+    }
+
+    #[test]
+    fn test_entity_history_pairs_retractions_with_assertions() {
+        let mut conn = setup_test_db();
+        let car = |predicate: &str, object: Object| Triple::new("foundation:myCar", predicate, object);
+        store::assert_triples(&mut conn, &[
+            Triple::new(rdfs::LABEL, rdfs::LABEL, label("Label")),
+            Triple::new("foundation:color", rdfs::LABEL, label("Color")),
+            Triple::new("foundation:Red", rdfs::LABEL, label("Red")),
+        ], "test").unwrap();
+        let created = store::assert_triples(&mut conn, &[
+            car(rdfs::LABEL, label("Car")),
+            car("foundation:color", Object::Iri("foundation:Red".into())),
+        ], "test").unwrap();
+        let renamed = store::apply_changes(&mut conn,
+            &[car(rdfs::LABEL, label("Sports car"))],
+            &[car(rdfs::LABEL, label("Car"))],
+            "user-edit",
+        ).unwrap();
+        store::retract_triples(&mut conn, &[car("foundation:color", Object::Iri("foundation:Red".into()))], "user-edit").unwrap();
+
+        let history = entity_history(&conn, "foundation:myCar").unwrap();
+        let descriptions: Vec<&str> = history.iter().map(|c| c.description.as_str()).collect();
+        assert_eq!(descriptions, [
+            "Color Red removed",
+            "Label changed from Car to Sports car",
+            "Label set to Car",
+            "Color set to Red",
+        ]);
+        assert_eq!((history[1].tx, history[1].kind, history[1].origin.as_str()), (renamed, ChangeKind::Changed, "user-edit"));
+        assert_eq!(history[3].to, Some(ChangeValue { value: "foundation:Red".into(), label: "Red".into() }));
+        assert_eq!((history[3].tx, history[3].origin.as_str()), (created, "test"));
+    }
+}
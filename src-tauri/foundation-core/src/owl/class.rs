@@ -6,7 +6,7 @@
 
 use rusqlite::Connection;
 use crate::eavto::{store, query, Triple, Object};
-use crate::owl::{Result, OwlError, Thing, vocabulary::{rdf, rdfs, owl}};
+use crate::owl::{Result, OwlError, Thing, ClassExpression, list, RestrictionConstraint, vocabulary::{rdf, rdfs, owl}};
 
 /// Represents an OWL/RDFS Class with all its data
 #[derive(Debug, Clone)]
@@ -19,6 +19,9 @@ pub struct Class {
     pub super_classes: Vec<Thing>,
     pub sub_classes: Vec<Thing>,
     pub properties: Vec<(String, String)>, // (property_iri, source_class_iri)
+    pub restrictions: Vec<Restriction>, // owl:Restriction superclasses, own and inherited
+    pub union_of: Vec<ClassExpression>, // owl:unionOf members, on the class or an equivalent class
+    pub intersection_of: Vec<ClassExpression>, // owl:intersectionOf members, likewise
     pub backlinks: Vec<(String, String, Object)>, // (source_entity, property_iri, value) - entities that reference this class
 }
 
@@ -34,6 +37,9 @@ impl Class {
             super_classes: Vec::new(),
             sub_classes: Vec::new(),
             properties: Vec::new(),
+            restrictions: Vec::new(),
+            union_of: Vec::new(),
+            intersection_of: Vec::new(),
             backlinks: Vec::new(),
         }
     }
@@ -59,26 +65,31 @@ impl Class {
 
         // Get types (rdf:type)
         let types_result = query::get_by_entity_predicate(conn, &iri, rdf::TYPE)?;
-        let types: Vec<Thing> = types_result.triples.iter()
+        let type_iris: Vec<&str> = types_result.triples.iter()
             .filter_map(|t| t.object.as_iri())
-            .map(|type_iri| Thing::get(conn, type_iri))
             .collect();
+        let types = Thing::get_many(conn, &type_iris);
 
         // Get super classes with their info (shallow - no recursion)
+        // Anonymous superclasses (restrictions) are read into `restrictions`
         let super_result = query::get_by_entity_predicate(conn, &iri, rdfs::SUB_CLASS_OF)?;
-        let super_classes: Vec<Thing> = super_result.triples.iter()
+        let super_iris: Vec<&str> = super_result.triples.iter()
+            .filter(|t| !matches!(t.object, Object::Blank(_)))
             .filter_map(|t| t.object.as_iri())
-            .map(|super_iri| Thing::get(conn, super_iri))
             .collect();
+        let super_classes = Thing::get_many(conn, &super_iris);
 
         // Get sub classes with their info (shallow - no recursion)
         let sub_result = query::get_by_predicate_object(conn, rdfs::SUB_CLASS_OF, &iri)?;
-        let sub_classes: Vec<Thing> = sub_result.triples.iter()
-            .map(|t| Thing::get(conn, &t.subject))
+        let sub_iris: Vec<&str> = sub_result.triples.iter()
+            .map(|t| t.subject.as_str())
             .collect();
+        let sub_classes = Thing::get_many(conn, &sub_iris);
 
         // Get properties with source
         let properties = Self::get_properties(conn, &iri)?;
+        let restrictions = Self::get_restrictions(conn, &iri, &mut std::collections::HashSet::new())?;
+        let (union_of, intersection_of) = Self::get_boolean_expressions(conn, &iri)?;
 
         // Get backlinks - instances of this class (rdf:type references)
         let backlinks_result = query::get_by_predicate_object(conn, rdf::TYPE, &iri)?;
@@ -95,10 +106,77 @@ impl Class {
             super_classes,
             sub_classes,
             properties,
+            restrictions,
+            union_of,
+            intersection_of,
             backlinks,
         })
     }
 
+    /// Members of the union and of the intersection the class is defined as:
+    /// `:Parent owl:unionOf (:Mother :Father)`, or the same through
+    /// `owl:equivalentClass [ owl:unionOf (...) ]`; imported ontologies use both
+    fn get_boolean_expressions(conn: &Connection, iri: &str) -> Result<(Vec<ClassExpression>, Vec<ClassExpression>)> {
+        let read_members = |predicate: &str| -> Result<Vec<ClassExpression>> {
+            let mut members = Vec::new();
+            for list in list::of(conn, iri, predicate)? {
+                for member in list {
+                    members.push(ClassExpression::read(conn, &member)?);
+                }
+            }
+            Ok(members)
+        };
+        let mut union_of = read_members(owl::UNION_OF)?;
+        let mut intersection_of = read_members(owl::INTERSECTION_OF)?;
+
+        for triple in query::get_by_entity_predicate(conn, iri, owl::EQUIVALENT_CLASS)?.triples {
+            if let Object::Blank(node) = &triple.object {
+                match ClassExpression::read(conn, node)? {
+                    ClassExpression::UnionOf(members) => union_of.extend(members),
+                    ClassExpression::IntersectionOf(members) => intersection_of.extend(members),
+                    _ => {}
+                }
+            }
+        }
+        Ok((union_of, intersection_of))
+    }
+
+    /// Get the restrictions on this class and its superclasses
+    /// (`rdfs:subClassOf [ a owl:Restriction ; ... ]`), nearest class first
+    fn get_restrictions(
+        conn: &Connection,
+        class_iri: &str,
+        visited: &mut std::collections::HashSet<String>,
+    ) -> Result<Vec<Restriction>> {
+        if !visited.insert(class_iri.to_string()) {
+            return Ok(Vec::new());
+        }
+
+        let mut restrictions = Vec::new();
+        let mut super_classes = Vec::new();
+        for triple in query::get_by_entity_predicate(conn, class_iri, rdfs::SUB_CLASS_OF)?.triples {
+            match &triple.object {
+                Object::Blank(node) => {
+                    if let ClassExpression::Restriction { property, constraint } = ClassExpression::read(conn, node)? {
+                        restrictions.push(Restriction { property, constraint, source_class: class_iri.to_string() });
+                    }
+                }
+                Object::Iri(super_iri) if super_iri != owl::THING => super_classes.push(super_iri.clone()),
+                _ => {}
+            }
+        }
+
+        for super_iri in super_classes {
+            restrictions.extend(Self::get_restrictions(conn, &super_iri, visited)?);
+        }
+        Ok(restrictions)
+    }
+
+    /// Restrictions on a property, own and inherited
+    pub fn restrictions_on(&self, property: &str) -> Vec<&Restriction> {
+        self.restrictions.iter().filter(|r| r.property == property).collect()
+    }
+
     /// Get all properties for this class (declared, used, and inherited)
     /// Returns Vec<(property_iri, source_class_iri)>
     fn get_properties(
@@ -119,6 +197,7 @@ impl Class {
         // Add inherited properties from superclasses recursively
         let super_result = query::get_by_entity_predicate(conn, class_iri, rdfs::SUB_CLASS_OF)?;
         let super_classes: Vec<String> = super_result.triples.iter()
+            .filter(|t| !matches!(t.object, Object::Blank(_)))
             .filter_map(|t| t.object.as_iri())
             .map(|s| s.to_string())
             .collect();
@@ -196,6 +275,34 @@ impl Class {
         }))
     }
 
+    /// Add an anonymous superclass (e.g., a restriction) to this class
+    /// Asserts `class rdfs:subClassOf _:node` together with the expression triples
+    /// Returns the blank node of the expression
+    pub fn add_restriction(
+        &self,
+        conn: &mut Connection,
+        expression: &ClassExpression,
+        origin: &str
+    ) -> Result<String> {
+        if expression.is_named() {
+            return Err(OwlError::InvalidOperation(
+                "Named classes are superclasses, not restrictions".to_string()
+            ));
+        }
+
+        if !self.exists(conn)? {
+            return Err(OwlError::NotFound(format!("Class {} not found", self.iri)));
+        }
+
+        // Seed with the current time so repeated restrictions get distinct blank nodes
+        let seed = format!("{}@{}", self.iri, chrono::Utc::now().timestamp_nanos_opt().unwrap_or_default());
+        let (node, mut triples) = expression.to_triples(&seed);
+        triples.push(Triple::new(&self.iri, rdfs::SUB_CLASS_OF, Object::Blank(node.clone())));
+        store::assert_triples(conn, &triples, origin)?;
+
+        Ok(node)
+    }
+
     /// Get all instances of this class (returned as IRIs only)
     /// Call separately when needed - can be thousands of instances
     pub fn get_instances(conn: &Connection, class_iri: &str) -> Result<Vec<String>> {
@@ -204,6 +311,31 @@ impl Class {
             .map(|t| t.subject.clone())
             .collect())
     }
+
+    /// Get all instances of this class, including instances of its subclasses
+    /// and entities typed by the domain or range of a property (RDFS
+    /// inference, see owl::inference)
+    pub fn get_inferred_instances(conn: &Connection, class_iri: &str) -> Result<Vec<String>> {
+        crate::owl::inference::instances(conn, class_iri)
+    }
+}
+
+/// A property restriction a class inherits from an anonymous superclass
+#[derive(Debug, Clone, PartialEq)]
+pub struct Restriction {
+    pub property: String,
+    pub constraint: RestrictionConstraint,
+    pub source_class: String, // Class whose rdfs:subClassOf holds the restriction
+}
+
+impl Restriction {
+    /// The restriction in Manchester syntax (e.g., "foundation:sourceFile exactly 1")
+    pub fn to_manchester(&self) -> String {
+        crate::owl::manchester::render(&ClassExpression::Restriction {
+            property: self.property.clone(),
+            constraint: self.constraint.clone(),
+        })
+    }
 }
 
 /// Type of class (RDFS or OWL)
@@ -297,4 +429,78 @@ mod tests {
         assert_eq!(class_data.super_classes.len(), 1, "Expected exactly 1 super class, found {}", class_data.super_classes.len());
         assert_eq!(class_data.super_classes[0].iri, "owl:Thing");
     }
+
+    #[test]
+    fn test_add_restriction() {
+        let mut conn = setup_test_db();
+        let class = Class::new("foundation:Computer");
+        class.assert(&mut conn, ClassType::OwlClass, "Computer", "computer-icon", None, "test").unwrap();
+
+        let expression = crate::owl::manchester::parse("foundation:hasProcessor some foundation:Processor").unwrap();
+        let node = class.add_restriction(&mut conn, &expression, "test").unwrap();
+
+        let axioms = crate::owl::ClassAxioms::get(&conn, "foundation:Computer").unwrap();
+        assert!(axioms.sub_class_of.contains(&expression));
+        assert_eq!(ClassExpression::read(&conn, &node).unwrap(), expression);
+
+        // Named classes are not restrictions
+        let named = ClassExpression::Named("foundation:Device".to_string());
+        assert!(class.add_restriction(&mut conn, &named, "test").is_err());
+
+        // Unknown classes are rejected
+        let missing = Class::new("foundation:Missing");
+        assert!(missing.add_restriction(&mut conn, &expression, "test").is_err());
+    }
+
+    #[test]
+    fn test_get_reads_inherited_restrictions() {
+        let mut conn = setup_test_db();
+        let device = Class::new("foundation:Device");
+        device.assert(&mut conn, ClassType::OwlClass, "Device", "devices", None, "test").unwrap();
+        let computer = Class::new("foundation:Computer");
+        computer.assert(&mut conn, ClassType::OwlClass, "Computer", "computer", Some("foundation:Device"), "test").unwrap();
+
+        let cardinality = crate::owl::manchester::parse("foundation:serialNumber exactly 1").unwrap();
+        device.add_restriction(&mut conn, &cardinality, "test").unwrap();
+        let some = crate::owl::manchester::parse("foundation:hasProcessor some foundation:Processor").unwrap();
+        computer.add_restriction(&mut conn, &some, "test").unwrap();
+
+        let class = Class::get(&conn, "foundation:Computer").unwrap();
+        assert_eq!(class.super_classes.len(), 1);
+        assert_eq!(class.restrictions.len(), 2);
+        assert_eq!(class.restrictions[0].source_class, "foundation:Computer");
+        assert_eq!(class.restrictions[0].to_manchester(), "foundation:hasProcessor some foundation:Processor");
+
+        let serial = class.restrictions_on("foundation:serialNumber");
+        assert_eq!(serial[0].constraint, RestrictionConstraint::ExactCardinality(1, None));
+        assert_eq!(serial[0].source_class, "foundation:Device");
+    }
+
+    #[test]
+    fn test_get_reads_union_and_intersection() {
+        let mut conn = setup_test_db();
+        let union = ClassExpression::UnionOf(vec![
+            ClassExpression::Named("foundation:Mother".to_string()),
+            ClassExpression::Named("foundation:Father".to_string()),
+        ]);
+        let (node, mut triples) = union.to_triples("foundation:Parent");
+        triples.push(Triple::new("foundation:Parent", owl::EQUIVALENT_CLASS, Object::Blank(node)));
+
+        // Declared on the class itself, as some RDF/XML ontologies do
+        let (node, intersection) = ClassExpression::IntersectionOf(vec![
+            ClassExpression::Named("foundation:Person".to_string()),
+            crate::owl::manchester::parse("foundation:hasChild some foundation:Person").unwrap(),
+        ]).to_triples("foundation:Parent intersection");
+        let head = intersection.iter().find(|t| t.subject == node && t.predicate == owl::INTERSECTION_OF).unwrap().object.clone();
+        triples.extend(intersection.into_iter().filter(|t| t.subject != node));
+        triples.push(Triple::new("foundation:Parent", owl::INTERSECTION_OF, head));
+        store::assert_triples(&mut conn, &triples, "test").unwrap();
+
+        let class = Class::get(&conn, "foundation:Parent").unwrap();
+        let ClassExpression::UnionOf(members) = union else { unreachable!() };
+        assert_eq!(class.union_of, members);
+        assert_eq!(class.intersection_of.len(), 2);
+        assert!(!class.intersection_of[1].is_named());
+        assert!(Class::get(&conn, "foundation:Mother").unwrap().union_of.is_empty());
+    }
 }
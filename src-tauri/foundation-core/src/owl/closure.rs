@@ -0,0 +1,82 @@
+// ============================================================================
+// OWL Closure - Transitive Property Reasoning
+// ============================================================================
+// Follows an owl:TransitiveProperty from an entity to everything it reaches:
+// if a part of b and b part of c, then a part of c. Links asserted with the
+// property's inverse (owl:inverseOf, declared on either property) count the
+// other way round, so "c has part b" also takes b to c.
+//
+// Traversal is breadth-first, so each entity is reported once, at its
+// shortest distance; cycles are harmless.
+// ============================================================================
+
+use rusqlite::Connection;
+use std::collections::{HashSet, VecDeque};
+use crate::eavto::query;
+use crate::owl::{Property, Result, OwlError, inference::inverses};
+
+/// An entity reached through a transitive property
+#[derive(Debug, Clone, PartialEq)]
+pub struct Reached {
+    pub iri: String,
+    pub depth: usize, // Links from the start (1: asserted directly)
+}
+
+/// Everything an entity reaches through a transitive property, nearest first
+pub fn transitive_closure(conn: &Connection, start: &str, property: &str) -> Result<Vec<Reached>> {
+    if !Property::get(conn, property).map(|p| p.is_transitive).unwrap_or(false) {
+        return Err(OwlError::ValidationError(format!("{} is not a transitive property", property)));
+    }
+    let inverses = inverses(conn, property)?;
+
+    let mut reached = Vec::new();
+    let mut seen = HashSet::from([start.to_string()]);
+    let mut queue = VecDeque::from([(start.to_string(), 0)]);
+
+    while let Some((iri, depth)) = queue.pop_front() {
+        let mut next: Vec<String> = query::get_by_entity_predicate(conn, &iri, property)?.triples.iter()
+            .filter_map(|t| t.object.as_iri().map(String::from))
+            .collect();
+        for inverse in &inverses {
+            next.extend(query::get_by_predicate_object(conn, inverse, &iri)?.triples.into_iter().map(|t| t.subject));
+        }
+
+        for target in next {
+            if seen.insert(target.clone()) {
+                reached.push(Reached { iri: target.clone(), depth: depth + 1 });
+                queue.push_back((target, depth + 1));
+            }
+        }
+    }
+    Ok(reached)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::eavto::{store, test_helpers::setup_test_db, Object, Triple};
+    use crate::owl::vocabulary::{owl, rdf};
+
+    fn link(subject: &str, predicate: &str, object: &str) -> Triple {
+        Triple::new(subject, predicate, Object::Iri(object.to_string()))
+    }
+
+    #[test]
+    fn test_closure_follows_inverse_links_and_cycles() {
+        let mut conn = setup_test_db();
+        store::assert_triples(&mut conn, &[
+            link("foundation:ancestorOf", rdf::TYPE, owl::TRANSITIVE_PROPERTY),
+            link("foundation:descendantOf", owl::INVERSE_OF, "foundation:ancestorOf"),
+            link("foundation:A", "foundation:ancestorOf", "foundation:B"),
+            link("foundation:C", "foundation:descendantOf", "foundation:B"), // B ancestorOf C
+            link("foundation:C", "foundation:ancestorOf", "foundation:D"),
+            link("foundation:D", "foundation:ancestorOf", "foundation:A"), // Cycle
+        ], "test").unwrap();
+
+        let reached = transitive_closure(&conn, "foundation:A", "foundation:ancestorOf").unwrap();
+        let found: Vec<(&str, usize)> = reached.iter().map(|r| (r.iri.as_str(), r.depth)).collect();
+        assert_eq!(found, vec![("foundation:B", 1), ("foundation:C", 2), ("foundation:D", 3)]);
+
+        assert!(transitive_closure(&conn, "foundation:A", "foundation:descendantOf").is_err());
+    }
+}
@@ -0,0 +1,206 @@
+// ============================================================================
+// OWL Consistency - Axiom Violations in the Data
+// ============================================================================
+// Reports individuals whose statements contradict the ontology:
+// - Typed with two classes declared owl:disjointWith each other (or with
+//   subclasses of them)
+// - More than one distinct value of an owl:FunctionalProperty
+// - Both a p b and b p a for an owl:AsymmetricProperty p (a p a included)
+//
+// The store accepts such statements (they often come from different
+// origins), so this is a report for the user to act on, not a write check.
+// Violations are sorted by kind, then entity, so pages of the report are
+// stable.
+// ============================================================================
+
+use rusqlite::Connection;
+use std::collections::{BTreeMap, BTreeSet};
+use crate::eavto::{canonical, query};
+use crate::owl::{Result, inference, vocabulary::{rdf, owl}};
+
+/// Kind of axiom a violation breaks
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum ViolationKind {
+    DisjointClasses,
+    FunctionalProperty,
+    AsymmetricProperty,
+}
+
+impl ViolationKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ViolationKind::DisjointClasses => "disjoint-classes",
+            ViolationKind::FunctionalProperty => "functional-property",
+            ViolationKind::AsymmetricProperty => "asymmetric-property",
+        }
+    }
+}
+
+/// An individual contradicting an axiom
+#[derive(Debug, Clone, PartialEq)]
+pub struct Violation {
+    pub kind: ViolationKind,
+    pub entity: String,
+    pub axiom: String,       // First class of the disjoint pair, or the property
+    pub values: Vec<String>, // Classes typed with, distinct values, or the entity linked back
+}
+
+impl Violation {
+    /// One-line description for the user
+    pub fn message(&self) -> String {
+        match self.kind {
+            ViolationKind::DisjointClasses => format!(
+                "{} is a {}, classes declared disjoint", self.entity, self.values.join(" and a ")
+            ),
+            ViolationKind::FunctionalProperty => format!(
+                "{} has {} values of functional property {}: {}", self.entity, self.values.len(), self.axiom, self.values.join(", ")
+            ),
+            ViolationKind::AsymmetricProperty => format!(
+                "{} and {} are linked both ways by asymmetric property {}", self.entity, self.values.join(", "), self.axiom
+            ),
+        }
+    }
+}
+
+/// Every violation in the store, sorted by kind and entity
+pub fn check(conn: &Connection) -> Result<Vec<Violation>> {
+    let mut violations = disjoint_classes(conn)?;
+    violations.extend(functional_properties(conn)?);
+    violations.extend(asymmetric_properties(conn)?);
+    violations.sort_by(|a, b| (a.kind, &a.entity, &a.axiom).cmp(&(b.kind, &b.entity, &b.axiom)));
+    Ok(violations)
+}
+
+/// Individuals typed with both classes of an owl:disjointWith pair
+fn disjoint_classes(conn: &Connection) -> Result<Vec<Violation>> {
+    let mut pairs = BTreeSet::new();
+    for triple in query::get_by_predicate(conn, owl::DISJOINT_WITH)?.triples {
+        let Some(other) = triple.object.as_iri().filter(|iri| !iri.starts_with("_:")) else { continue };
+        if triple.subject.starts_with("_:") {
+            continue;
+        }
+        let (a, b) = (triple.subject.clone(), other.to_string());
+        pairs.insert(if a <= b { (a, b) } else { (b, a) });
+    }
+
+    let mut violations = Vec::new();
+    for (a, b) in pairs {
+        let a_members = typed_with(conn, &a)?;
+        for (entity, b_class) in typed_with(conn, &b)? {
+            if let Some(a_class) = a_members.get(&entity) {
+                violations.push(Violation {
+                    kind: ViolationKind::DisjointClasses,
+                    entity,
+                    axiom: a.clone(),
+                    values: vec![a_class.clone(), b_class],
+                });
+            }
+        }
+    }
+    Ok(violations)
+}
+
+/// Individuals typed with a class or a subclass of it, and the class asserted
+fn typed_with(conn: &Connection, class: &str) -> Result<BTreeMap<String, String>> {
+    let mut members = BTreeMap::new();
+    for class in inference::sub_classes(conn, class)? {
+        for triple in query::get_by_predicate_object(conn, rdf::TYPE, &class)?.triples {
+            members.entry(triple.subject).or_insert_with(|| class.clone());
+        }
+    }
+    Ok(members)
+}
+
+/// Subjects with more than one distinct value of a functional property
+fn functional_properties(conn: &Connection) -> Result<Vec<Violation>> {
+    let mut violations = Vec::new();
+    for property in declared(conn, owl::FUNCTIONAL_PROPERTY)? {
+        let mut values: BTreeMap<String, BTreeSet<String>> = BTreeMap::new();
+        for triple in query::get_by_predicate(conn, &property)?.triples {
+            values.entry(triple.subject).or_default().insert(canonical::object(&triple.object));
+        }
+        for (entity, values) in values.into_iter().filter(|(_, values)| values.len() > 1) {
+            violations.push(Violation {
+                kind: ViolationKind::FunctionalProperty,
+                entity,
+                axiom: property.clone(),
+                values: values.into_iter().collect(),
+            });
+        }
+    }
+    Ok(violations)
+}
+
+/// Pairs linked both ways by an asymmetric property, reported once
+fn asymmetric_properties(conn: &Connection) -> Result<Vec<Violation>> {
+    let mut violations = Vec::new();
+    for property in declared(conn, owl::ASYMMETRIC_PROPERTY)? {
+        let links: BTreeSet<(String, String)> = query::get_by_predicate(conn, &property)?.triples.into_iter()
+            .filter_map(|t| t.object.as_iri().map(|object| (t.subject.clone(), object.to_string())))
+            .collect();
+        for (subject, object) in &links {
+            if subject <= object && links.contains(&(object.clone(), subject.clone())) {
+                violations.push(Violation {
+                    kind: ViolationKind::AsymmetricProperty,
+                    entity: subject.clone(),
+                    axiom: property.clone(),
+                    values: vec![object.clone()],
+                });
+            }
+        }
+    }
+    Ok(violations)
+}
+
+/// Properties declared with a characteristic (rdf:type owl:FunctionalProperty, ...)
+fn declared(conn: &Connection, characteristic: &str) -> Result<Vec<String>> {
+    let mut properties: Vec<String> = query::get_by_predicate_object(conn, rdf::TYPE, characteristic)?.triples.into_iter()
+        .map(|t| t.subject)
+        .collect();
+    properties.sort();
+    properties.dedup();
+    Ok(properties)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::eavto::{store, test_helpers::setup_test_db, Object, Triple};
+    use crate::owl::vocabulary::rdfs;
+
+    fn link(subject: &str, predicate: &str, object: &str) -> Triple {
+        Triple::new(subject, predicate, Object::Iri(object.to_string()))
+    }
+
+    #[test]
+    fn test_check_reports_each_kind_of_violation() {
+        let mut conn = setup_test_db();
+        store::assert_triples(&mut conn, &[
+            link("foundation:Person", owl::DISJOINT_WITH, "foundation:Organization"),
+            link("foundation:Employee", rdfs::SUB_CLASS_OF, "foundation:Person"),
+            link("foundation:birthDate", rdf::TYPE, owl::FUNCTIONAL_PROPERTY),
+            link("foundation:parentOf", rdf::TYPE, owl::ASYMMETRIC_PROPERTY),
+            link("foundation:Ana", rdf::TYPE, "foundation:Employee"),
+            link("foundation:Ana", rdf::TYPE, "foundation:Organization"),
+            link("foundation:Bruno", rdf::TYPE, "foundation:Person"),
+            Triple::new("foundation:Bruno", "foundation:birthDate", Object::DateTime(0)),
+            Triple::new("foundation:Bruno", "foundation:birthDate", Object::DateTime(86_400_000)),
+            Triple::new("foundation:Carla", "foundation:birthDate", Object::DateTime(0)),
+            link("foundation:Bruno", "foundation:parentOf", "foundation:Carla"),
+            link("foundation:Carla", "foundation:parentOf", "foundation:Bruno"),
+            link("foundation:Carla", "foundation:parentOf", "foundation:Davi"),
+        ], "test").unwrap();
+
+        let violations = check(&conn).unwrap();
+        let found: Vec<(ViolationKind, &str)> = violations.iter().map(|v| (v.kind, v.entity.as_str())).collect();
+        assert_eq!(found, vec![
+            (ViolationKind::DisjointClasses, "foundation:Ana"),
+            (ViolationKind::FunctionalProperty, "foundation:Bruno"),
+            (ViolationKind::AsymmetricProperty, "foundation:Bruno"),
+        ]);
+        assert_eq!(violations[0].values, vec!["foundation:Organization", "foundation:Employee"]);
+        assert_eq!(violations[1].values.len(), 2);
+        assert_eq!(violations[2].values, vec!["foundation:Carla"]);
+        assert!(violations[0].message().contains("disjoint"));
+    }
+}
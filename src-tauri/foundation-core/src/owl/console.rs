@@ -0,0 +1,165 @@
+// ============================================================================
+// OWL Console - Query History and Saved Queries
+// ============================================================================
+// State of the in-app query console (queries themselves run through
+// eavto::pattern):
+// - History: the most recent queries, newest first, kept in the metadata
+//   table since it's app state rather than knowledge
+// - Favorites: foundation:SavedQuery entities, so saved queries are data
+//   that can be labeled, linked and synced like anything else
+// ============================================================================
+
+use rusqlite::Connection;
+use serde::{Deserialize, Serialize};
+use crate::eavto::{query, store, Object, Triple};
+use crate::owl::{Result, hash, string, OwlError, vocabulary::{rdf, rdfs}};
+
+/// Saved query class
+pub const SAVED_QUERY: &str = "foundation:SavedQuery";
+
+/// Text of a saved query
+pub const QUERY_TEXT: &str = "foundation:queryText";
+
+/// Metadata key holding the query history (JSON)
+const HISTORY_KEY: &str = "console_history";
+
+/// Queries kept in the history
+const HISTORY_SIZE: usize = 50;
+
+/// A query run in the console
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HistoryEntry {
+    pub query: String,
+    pub executed_at: i64, // Unix ms
+    pub rows: usize,
+    pub error: Option<String>,
+}
+
+/// A favorite query
+#[derive(Debug, Clone, PartialEq)]
+pub struct SavedQuery {
+    pub iri: String,
+    pub label: String,
+    pub query: String,
+}
+
+/// Query history, newest first
+pub fn history(conn: &Connection) -> Result<Vec<HistoryEntry>> {
+    match query::get_metadata(conn, HISTORY_KEY)? {
+        Some(json) => serde_json::from_str(&json)
+            .map_err(|e| OwlError::DatabaseError(format!("Invalid query history: {}", e))),
+        None => Ok(Vec::new()),
+    }
+}
+
+/// Add a query to the history
+///
+/// Running a query again moves it to the top instead of repeating it.
+pub fn record(conn: &Connection, entry: HistoryEntry) -> Result<()> {
+    let mut entries = history(conn)?;
+    entries.retain(|e| e.query != entry.query);
+    entries.insert(0, entry);
+    entries.truncate(HISTORY_SIZE);
+
+    let json = serde_json::to_string(&entries)
+        .map_err(|e| OwlError::DatabaseError(e.to_string()))?;
+    store::set_metadata(conn, HISTORY_KEY, Some(&json))?;
+    Ok(())
+}
+
+/// Saved queries, by label
+pub fn favorites(conn: &Connection) -> Result<Vec<SavedQuery>> {
+    let mut saved = Vec::new();
+    for triple in query::get_by_predicate_object(conn, rdf::TYPE, SAVED_QUERY)?.triples {
+        let literal = |predicate: &str| -> Result<Option<String>> {
+            Ok(query::get_by_entity_predicate(conn, &triple.subject, predicate)?
+                .triples.first()
+                .and_then(|t| t.object.as_literal()))
+        };
+        if let Some(text) = literal(QUERY_TEXT)? {
+            saved.push(SavedQuery {
+                label: literal(rdfs::LABEL)?.unwrap_or_else(|| text.clone()),
+                iri: triple.subject,
+                query: text,
+            });
+        }
+    }
+    saved.sort_by(|a, b| a.label.cmp(&b.label).then_with(|| a.iri.cmp(&b.iri)));
+    saved.dedup_by(|a, b| a.iri == b.iri);
+    Ok(saved)
+}
+
+/// Save a query as a favorite; returns its IRI
+///
+/// The IRI is derived from the query text, so saving a query again renames
+/// the existing favorite.
+pub fn save_favorite(conn: &mut Connection, label: &str, text: &str, origin: &str) -> Result<String> {
+    let text = text.trim();
+    if text.is_empty() {
+        return Err(OwlError::ValidationError("Query text is empty".to_string()));
+    }
+
+    let iri = format!("foundation:SavedQuery_{}", hash(text));
+
+    let additions = vec![
+        Triple::new(&iri, rdf::TYPE, Object::Iri(SAVED_QUERY.to_string())),
+        Triple::new(&iri, rdfs::LABEL, string(label.trim())),
+        Triple::new(&iri, QUERY_TEXT, string(text)),
+    ];
+    let retractions: Vec<Triple> = query::get_by_entity(conn, &iri)?.triples;
+
+    store::apply_changes(conn, &additions, &retractions, origin)?;
+    Ok(iri)
+}
+
+/// Remove a favorite; returns the transaction ID
+pub fn remove_favorite(conn: &mut Connection, iri: &str, origin: &str) -> Result<i64> {
+    let triples = query::get_by_entity(conn, iri)?.triples;
+    if !triples.iter().any(|t| t.predicate == rdf::TYPE && t.object.as_iri() == Some(SAVED_QUERY)) {
+        return Err(OwlError::NotFound(format!("Saved query {}", iri)));
+    }
+    Ok(store::apply_changes(conn, &[], &triples, origin)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::eavto::test_helpers::setup_test_db;
+
+    fn entry(query: &str) -> HistoryEntry {
+        HistoryEntry { query: query.to_string(), executed_at: 0, rows: 1, error: None }
+    }
+
+    #[test]
+    fn test_history_is_newest_first_without_repeats() {
+        let conn = setup_test_db();
+        assert!(history(&conn).unwrap().is_empty());
+
+        record(&conn, entry("?s a ?c")).unwrap();
+        record(&conn, entry("?s rdfs:label ?l")).unwrap();
+        record(&conn, entry("?s a ?c")).unwrap();
+
+        let queries: Vec<String> = history(&conn).unwrap().into_iter().map(|e| e.query).collect();
+        assert_eq!(queries, vec!["?s a ?c", "?s rdfs:label ?l"]);
+
+        for i in 0..HISTORY_SIZE + 5 {
+            record(&conn, entry(&format!("?s ?p {}", i))).unwrap();
+        }
+        assert_eq!(history(&conn).unwrap().len(), HISTORY_SIZE);
+    }
+
+    #[test]
+    fn test_favorites() {
+        let mut conn = setup_test_db();
+        let iri = save_favorite(&mut conn, "Classes", "?s a ?c", "user-edit").unwrap();
+        assert_eq!(save_favorite(&mut conn, "All types", "?s a ?c", "user-edit").unwrap(), iri);
+
+        let saved = favorites(&conn).unwrap();
+        assert_eq!(saved, vec![SavedQuery { iri: iri.clone(), label: "All types".to_string(), query: "?s a ?c".to_string() }]);
+
+        remove_favorite(&mut conn, &iri, "user-edit").unwrap();
+        assert!(favorites(&conn).unwrap().is_empty());
+        assert!(remove_favorite(&mut conn, &iri, "user-edit").is_err());
+    }
+}
@@ -0,0 +1,113 @@
+// ============================================================================
+// OWL Contacts - Person Matching
+// ============================================================================
+// Resolves contact names and phone numbers found in imported data to
+// foundation:Person entities, so the same person imported from several
+// sources is one entity:
+// - Names match the label or foundation:name of a person, ignoring case,
+//   spacing, diacritics and the "~" prefix chat apps put before unsaved
+//   contacts
+// - Phone numbers match foundation:phoneNumber on their digits
+//
+// Unknown contacts become new persons, with IRIs derived from the match key.
+// ============================================================================
+
+use rusqlite::Connection;
+use std::collections::HashMap;
+use crate::eavto::{query, Object, Triple};
+use crate::owl::{Class, Thing, Result, hash, string, text, vocabulary::{rdf, rdfs}};
+
+pub const PERSON: &str = "foundation:Person";
+pub const NAME: &str = "foundation:name";
+pub const PHONE_NUMBER: &str = "foundation:phoneNumber";
+
+/// Fewer digits aren't a phone number (short codes, years)
+const MIN_PHONE_DIGITS: usize = 7;
+
+/// Index of the persons in the store, by match key
+pub struct Contacts {
+    persons: HashMap<String, String>, // Match key -> person IRI
+    pub created: usize, // Persons created by `resolve`
+}
+
+impl Contacts {
+    /// Index the existing persons
+    pub fn load(conn: &Connection) -> Result<Self> {
+        let mut persons = HashMap::new();
+        for iri in Class::get_instances(conn, PERSON)? {
+            let mut keys = vec![name_key(&Thing::get(conn, &iri).label)];
+            for triple in query::get_by_entity_predicate(conn, &iri, NAME)?.triples {
+                keys.extend(triple.object.as_literal().map(|name| name_key(&name)));
+            }
+            for triple in query::get_by_entity_predicate(conn, &iri, PHONE_NUMBER)?.triples {
+                keys.extend(triple.object.as_literal().and_then(|phone| phone_key(&phone)));
+            }
+            for key in keys {
+                persons.entry(key).or_insert_with(|| iri.clone());
+            }
+        }
+        Ok(Self { persons, created: 0 })
+    }
+
+    /// Person for a contact name or phone number, adding the triples of a new
+    /// person when there's no match
+    pub fn resolve(&mut self, contact: &str, triples: &mut Vec<Triple>) -> String {
+        let contact = contact.trim().trim_start_matches('~').trim();
+        let phone = phone_key(contact);
+        let key = phone.clone().unwrap_or_else(|| name_key(contact));
+        if let Some(person) = self.persons.get(&key) {
+            return person.clone();
+        }
+
+        let person = format!("foundation:Person_{}", hash(&key));
+        triples.push(Triple::new(&person, rdf::TYPE, Object::Iri(PERSON.to_string())));
+        triples.push(Triple::new(&person, rdfs::LABEL, string(contact)));
+        if phone.is_some() {
+            triples.push(Triple::new(&person, PHONE_NUMBER, string(contact)));
+        } else {
+            triples.push(Triple::new(&person, NAME, string(contact)));
+        }
+        self.persons.insert(key, person.clone());
+        self.created += 1;
+        person
+    }
+}
+
+/// Match key of a name: folded (see owl::text)
+fn name_key(name: &str) -> String {
+    format!("name:{}", text::fold(name.trim().trim_start_matches('~')))
+}
+
+/// Match key of a phone number (its digits), if the text is one
+fn phone_key(text: &str) -> Option<String> {
+    let is_phone = text.chars().all(|c| c.is_ascii_digit() || " +-().\u{a0}\u{202a}\u{202c}".contains(c));
+    let digits: String = text.chars().filter(char::is_ascii_digit).collect();
+    (is_phone && digits.len() >= MIN_PHONE_DIGITS).then(|| format!("tel:{}", digits))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::eavto::{store, test_helpers::setup_test_db};
+
+    #[test]
+    fn test_resolve_matches_names_and_phones() {
+        let mut conn = setup_test_db();
+        store::assert_triples(&mut conn, &[
+            Triple::new("foundation:Maria", rdf::TYPE, Object::Iri(PERSON.to_string())),
+            Triple::new("foundation:Maria", rdfs::LABEL, string("Maria Silva")),
+            Triple::new("foundation:Maria", PHONE_NUMBER, string("+55 11 98765-4321")),
+        ], "test").unwrap();
+
+        let mut contacts = Contacts::load(&conn).unwrap();
+        let mut triples = Vec::new();
+        assert_eq!(contacts.resolve("maria  SILVA", &mut triples), "foundation:Maria");
+        assert_eq!(contacts.resolve("+55 (11) 98765 4321", &mut triples), "foundation:Maria");
+        assert!(triples.is_empty());
+
+        let john = contacts.resolve("~ John", &mut triples);
+        assert_eq!(contacts.resolve("john", &mut triples), john);
+        assert_eq!(contacts.created, 1);
+        assert_eq!(triples.len(), 3);
+    }
+}
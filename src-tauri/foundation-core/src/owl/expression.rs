@@ -0,0 +1,379 @@
+// ============================================================================
+// OWL Class Expressions
+// ============================================================================
+// Anonymous class expressions (restrictions, boolean constructors, enumerations)
+// as stored in the triple store through blank nodes.
+//
+// Reading walks the blank-node structure starting from the object of an axiom
+// (e.g. `rdfs:subClassOf _:b1`). Writing produces the blank-node triples for an
+// expression so they can be asserted in the same transaction as the axiom.
+// ============================================================================
+
+use rusqlite::Connection;
+use sha2::{Sha256, Digest};
+use crate::eavto::{query, Triple, Object};
+use crate::owl::{Result, OwlError, list, vocabulary::{rdf, rdfs, owl}};
+
+/// Guard against cyclic or pathological blank-node structures
+const MAX_DEPTH: usize = 32;
+
+/// An OWL class expression
+#[derive(Debug, Clone, PartialEq)]
+pub enum ClassExpression {
+    /// Named class (e.g., "foundation:Computer")
+    Named(String),
+
+    /// Property restriction (owl:Restriction)
+    Restriction {
+        property: String,
+        constraint: RestrictionConstraint,
+    },
+
+    /// owl:intersectionOf
+    IntersectionOf(Vec<ClassExpression>),
+
+    /// owl:unionOf
+    UnionOf(Vec<ClassExpression>),
+
+    /// owl:complementOf
+    ComplementOf(Box<ClassExpression>),
+
+    /// owl:oneOf (enumeration of individuals)
+    OneOf(Vec<String>),
+}
+
+/// Constraint part of an owl:Restriction
+#[derive(Debug, Clone, PartialEq)]
+pub enum RestrictionConstraint {
+    SomeValuesFrom(Box<ClassExpression>),
+    AllValuesFrom(Box<ClassExpression>),
+    HasValue(Object),
+    MinCardinality(u32, Option<Box<ClassExpression>>),
+    MaxCardinality(u32, Option<Box<ClassExpression>>),
+    ExactCardinality(u32, Option<Box<ClassExpression>>),
+}
+
+/// Logical axioms and annotations of a named class
+#[derive(Debug, Clone)]
+pub struct ClassAxioms {
+    pub iri: String,
+    pub label: Option<String>,
+    pub comment: Option<String>,
+    pub sub_class_of: Vec<ClassExpression>,
+    pub equivalent_to: Vec<ClassExpression>,
+    pub disjoint_with: Vec<ClassExpression>,
+}
+
+impl ClassAxioms {
+    /// Collect the axioms asserted about a class
+    pub fn get(conn: &Connection, class_iri: &str) -> Result<Self> {
+        let literal = |predicate: &str| -> Result<Option<String>> {
+            let result = query::get_by_entity_predicate(conn, class_iri, predicate)?;
+            Ok(result.triples.first().and_then(|t| t.object.as_literal()))
+        };
+
+        let expressions = |predicate: &str| -> Result<Vec<ClassExpression>> {
+            let result = query::get_by_entity_predicate(conn, class_iri, predicate)?;
+            let mut expressions = Vec::new();
+            for triple in &result.triples {
+                if let Some(node) = triple.object.as_iri() {
+                    expressions.push(ClassExpression::read(conn, node)?);
+                }
+            }
+            Ok(expressions)
+        };
+
+        Ok(Self {
+            iri: class_iri.to_string(),
+            label: literal(rdfs::LABEL)?,
+            comment: literal(rdfs::COMMENT)?,
+            sub_class_of: expressions(rdfs::SUB_CLASS_OF)?,
+            equivalent_to: expressions(owl::EQUIVALENT_CLASS)?,
+            disjoint_with: expressions(owl::DISJOINT_WITH)?,
+        })
+    }
+}
+
+impl ClassExpression {
+    /// Read the class expression rooted at `node`
+    /// Named IRIs are returned as-is; blank nodes are decoded recursively
+    pub fn read(conn: &Connection, node: &str) -> Result<Self> {
+        Self::read_at_depth(conn, node, 0)
+    }
+
+    fn read_at_depth(conn: &Connection, node: &str, depth: usize) -> Result<Self> {
+        if !node.starts_with("_:") {
+            return Ok(ClassExpression::Named(node.to_string()));
+        }
+
+        if depth > MAX_DEPTH {
+            return Err(OwlError::ValidationError(
+                format!("Class expression at {} is nested too deeply", node)
+            ));
+        }
+
+        let triples = query::get_by_entity(conn, node)?.triples;
+        let object_of = |predicate: &str| -> Option<&Object> {
+            triples.iter().find(|t| t.predicate == predicate).map(|t| &t.object)
+        };
+        let iri_of = |predicate: &str| -> Option<String> {
+            object_of(predicate).and_then(|o| o.as_iri()).map(|s| s.to_string())
+        };
+        let cardinality_of = |predicate: &str| -> Result<Option<u32>> {
+            match object_of(predicate) {
+                Some(obj) => {
+                    let value = obj.as_literal().unwrap_or_default();
+                    value.parse::<u32>().map(Some).map_err(|_| OwlError::ValidationError(
+                        format!("Invalid cardinality '{}' on {}", value, node)
+                    ))
+                }
+                None => Ok(None),
+            }
+        };
+        let read_child = |child: &str| Self::read_at_depth(conn, child, depth + 1);
+
+        if let Some(property) = iri_of(owl::ON_PROPERTY) {
+            let qualifier = match iri_of(owl::ON_CLASS) {
+                Some(class) => Some(Box::new(read_child(&class)?)),
+                None => None,
+            };
+
+            let constraint = if let Some(filler) = iri_of(owl::SOME_VALUES_FROM) {
+                RestrictionConstraint::SomeValuesFrom(Box::new(read_child(&filler)?))
+            } else if let Some(filler) = iri_of(owl::ALL_VALUES_FROM) {
+                RestrictionConstraint::AllValuesFrom(Box::new(read_child(&filler)?))
+            } else if let Some(value) = object_of(owl::HAS_VALUE) {
+                RestrictionConstraint::HasValue(value.clone())
+            } else if let Some(n) = cardinality_of(owl::MIN_CARDINALITY)?.or(cardinality_of(owl::MIN_QUALIFIED_CARDINALITY)?) {
+                RestrictionConstraint::MinCardinality(n, qualifier)
+            } else if let Some(n) = cardinality_of(owl::MAX_CARDINALITY)?.or(cardinality_of(owl::MAX_QUALIFIED_CARDINALITY)?) {
+                RestrictionConstraint::MaxCardinality(n, qualifier)
+            } else if let Some(n) = cardinality_of(owl::CARDINALITY)?.or(cardinality_of(owl::QUALIFIED_CARDINALITY)?) {
+                RestrictionConstraint::ExactCardinality(n, qualifier)
+            } else {
+                return Err(OwlError::ValidationError(
+                    format!("Restriction {} on {} has no supported constraint", node, property)
+                ));
+            };
+
+            return Ok(ClassExpression::Restriction { property, constraint });
+        }
+
+        if let Some(head) = iri_of(owl::INTERSECTION_OF) {
+            let members = list::read(conn, &head)?
+                .iter()
+                .map(|m| read_child(m))
+                .collect::<Result<Vec<_>>>()?;
+            return Ok(ClassExpression::IntersectionOf(members));
+        }
+
+        if let Some(head) = iri_of(owl::UNION_OF) {
+            let members = list::read(conn, &head)?
+                .iter()
+                .map(|m| read_child(m))
+                .collect::<Result<Vec<_>>>()?;
+            return Ok(ClassExpression::UnionOf(members));
+        }
+
+        if let Some(complement) = iri_of(owl::COMPLEMENT_OF) {
+            return Ok(ClassExpression::ComplementOf(Box::new(read_child(&complement)?)));
+        }
+
+        if let Some(head) = iri_of(owl::ONE_OF) {
+            return Ok(ClassExpression::OneOf(list::read(conn, &head)?));
+        }
+
+        Err(OwlError::ValidationError(format!("Blank node {} is not a class expression", node)))
+    }
+
+    /// Check if this is a named class
+    pub fn is_named(&self) -> bool {
+        matches!(self, ClassExpression::Named(_))
+    }
+
+    /// Build the triples representing this expression
+    ///
+    /// Returns the node to reference the expression by (the IRI itself for named
+    /// classes, a fresh blank node otherwise) and the triples describing it.
+    /// `seed` makes generated blank node ids unique per call site.
+    pub fn to_triples(&self, seed: &str) -> (String, Vec<Triple>) {
+        let mut triples = Vec::new();
+        let mut counter = 0;
+        let node = self.write(seed, &mut counter, &mut triples);
+        (node, triples)
+    }
+
+    fn write(&self, seed: &str, counter: &mut usize, triples: &mut Vec<Triple>) -> String {
+        let node = match self {
+            ClassExpression::Named(iri) => return iri.clone(),
+            _ => blank_node_id(seed, counter),
+        };
+
+        match self {
+            ClassExpression::Named(_) => unreachable!(),
+            ClassExpression::Restriction { property, constraint } => {
+                triples.push(Triple::new(&node, rdf::TYPE, Object::Iri(owl::RESTRICTION.to_string())));
+                triples.push(Triple::new(&node, owl::ON_PROPERTY, Object::Iri(property.clone())));
+
+                let (predicate, qualified_predicate, value, qualifier) = match constraint {
+                    RestrictionConstraint::SomeValuesFrom(filler) => {
+                        let filler_node = filler.write(seed, counter, triples);
+                        triples.push(Triple::new(&node, owl::SOME_VALUES_FROM, node_object(filler_node)));
+                        return node;
+                    }
+                    RestrictionConstraint::AllValuesFrom(filler) => {
+                        let filler_node = filler.write(seed, counter, triples);
+                        triples.push(Triple::new(&node, owl::ALL_VALUES_FROM, node_object(filler_node)));
+                        return node;
+                    }
+                    RestrictionConstraint::HasValue(value) => {
+                        triples.push(Triple::new(&node, owl::HAS_VALUE, value.clone()));
+                        return node;
+                    }
+                    RestrictionConstraint::MinCardinality(n, q) => (owl::MIN_CARDINALITY, owl::MIN_QUALIFIED_CARDINALITY, *n, q),
+                    RestrictionConstraint::MaxCardinality(n, q) => (owl::MAX_CARDINALITY, owl::MAX_QUALIFIED_CARDINALITY, *n, q),
+                    RestrictionConstraint::ExactCardinality(n, q) => (owl::CARDINALITY, owl::QUALIFIED_CARDINALITY, *n, q),
+                };
+
+                let cardinality = Object::Literal {
+                    value: value.to_string(),
+                    datatype: Some("xsd:nonNegativeInteger".to_string()),
+                    language: None,
+                };
+
+                match qualifier {
+                    Some(class) => {
+                        let class_node = class.write(seed, counter, triples);
+                        triples.push(Triple::new(&node, qualified_predicate, cardinality));
+                        triples.push(Triple::new(&node, owl::ON_CLASS, node_object(class_node)));
+                    }
+                    None => triples.push(Triple::new(&node, predicate, cardinality)),
+                }
+            }
+            ClassExpression::IntersectionOf(members) | ClassExpression::UnionOf(members) => {
+                let predicate = if matches!(self, ClassExpression::IntersectionOf(_)) {
+                    owl::INTERSECTION_OF
+                } else {
+                    owl::UNION_OF
+                };
+                let items: Vec<String> = members.iter()
+                    .map(|m| m.write(seed, counter, triples))
+                    .collect();
+                let head = write_list(&items, seed, counter, triples);
+                triples.push(Triple::new(&node, rdf::TYPE, Object::Iri(owl::CLASS.to_string())));
+                triples.push(Triple::new(&node, predicate, node_object(head)));
+            }
+            ClassExpression::ComplementOf(inner) => {
+                let inner_node = inner.write(seed, counter, triples);
+                triples.push(Triple::new(&node, rdf::TYPE, Object::Iri(owl::CLASS.to_string())));
+                triples.push(Triple::new(&node, owl::COMPLEMENT_OF, node_object(inner_node)));
+            }
+            ClassExpression::OneOf(individuals) => {
+                let head = write_list(individuals, seed, counter, triples);
+                triples.push(Triple::new(&node, rdf::TYPE, Object::Iri(owl::CLASS.to_string())));
+                triples.push(Triple::new(&node, owl::ONE_OF, node_object(head)));
+            }
+        }
+
+        node
+    }
+}
+
+/// Write an rdf:List of IRIs and return its head node
+fn write_list(items: &[String], seed: &str, counter: &mut usize, triples: &mut Vec<Triple>) -> String {
+    let nodes: Vec<String> = items.iter().map(|_| blank_node_id(seed, counter)).collect();
+
+    for (i, item) in items.iter().enumerate() {
+        let rest = nodes.get(i + 1).cloned().unwrap_or_else(|| rdf::NIL.to_string());
+        triples.push(Triple::new(&nodes[i], rdf::FIRST, node_object(item.clone())));
+        triples.push(Triple::new(&nodes[i], rdf::REST, node_object(rest)));
+    }
+
+    nodes.first().cloned().unwrap_or_else(|| rdf::NIL.to_string())
+}
+
+/// Wrap a node reference as an object (blank node or IRI)
+fn node_object(node: String) -> Object {
+    if node.starts_with("_:") {
+        Object::Blank(node)
+    } else {
+        Object::Iri(node)
+    }
+}
+
+/// Generate a blank node id that is stable for a given seed and position
+fn blank_node_id(seed: &str, counter: &mut usize) -> String {
+    *counter += 1;
+    let mut hasher = Sha256::new();
+    hasher.update(seed.as_bytes());
+    hasher.update(counter.to_le_bytes());
+    let digest = format!("{:x}", hasher.finalize());
+    format!("_:expr{}", &digest[..16])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::eavto::{store, test_helpers::setup_test_db};
+
+    fn some(property: &str, class: &str) -> ClassExpression {
+        ClassExpression::Restriction {
+            property: property.to_string(),
+            constraint: RestrictionConstraint::SomeValuesFrom(Box::new(ClassExpression::Named(class.to_string()))),
+        }
+    }
+
+    #[test]
+    fn test_named_expression_has_no_triples() {
+        let (node, triples) = ClassExpression::Named("foundation:Person".to_string()).to_triples("seed");
+        assert_eq!(node, "foundation:Person");
+        assert!(triples.is_empty());
+    }
+
+    #[test]
+    fn test_restriction_round_trip() {
+        let mut conn = setup_test_db();
+        let expression = some("foundation:hasProcessor", "foundation:Processor");
+
+        let (node, triples) = expression.to_triples("foundation:Computer");
+        assert!(node.starts_with("_:"));
+        store::assert_triples(&mut conn, &triples, "test").unwrap();
+
+        let read = ClassExpression::read(&conn, &node).unwrap();
+        assert_eq!(read, expression);
+    }
+
+    #[test]
+    fn test_qualified_cardinality_and_union_round_trip() {
+        let mut conn = setup_test_db();
+        let expression = ClassExpression::Restriction {
+            property: "foundation:hasMemory".to_string(),
+            constraint: RestrictionConstraint::MinCardinality(
+                2,
+                Some(Box::new(ClassExpression::UnionOf(vec![
+                    ClassExpression::Named("foundation:Memory".to_string()),
+                    some("foundation:partOf", "foundation:Computer"),
+                ]))),
+            ),
+        };
+
+        let (node, triples) = expression.to_triples("seed");
+        store::assert_triples(&mut conn, &triples, "test").unwrap();
+
+        assert_eq!(ClassExpression::read(&conn, &node).unwrap(), expression);
+    }
+
+    #[test]
+    fn test_class_axioms_collects_sub_class_expressions() {
+        let mut conn = setup_test_db();
+        let (node, mut triples) = some("foundation:hasUser", "foundation:Person").to_triples("seed");
+        triples.push(Triple::new("foundation:Computer", rdfs::SUB_CLASS_OF, Object::Iri("foundation:PhysicalThing".to_string())));
+        triples.push(Triple::new("foundation:Computer", rdfs::SUB_CLASS_OF, Object::Blank(node)));
+        store::assert_triples(&mut conn, &triples, "test").unwrap();
+
+        let axioms = ClassAxioms::get(&conn, "foundation:Computer").unwrap();
+        assert_eq!(axioms.sub_class_of.len(), 2);
+        assert!(axioms.sub_class_of.iter().any(|e| e.is_named()));
+        assert!(axioms.sub_class_of.contains(&some("foundation:hasUser", "foundation:Person")));
+    }
+}
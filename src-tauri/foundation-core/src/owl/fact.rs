@@ -0,0 +1,140 @@
+// ============================================================================
+// OWL Fact - Annotations of Individual Facts
+// ============================================================================
+// Notes about one triple rather than about an entity: how sure we are of it
+// (foundation:confidence, 0 to 1), a note, and where it came from
+// (prov:wasDerivedFrom). They're triples about the fact's quoted triple
+// (RDF-star, see eavto::statement), so they have a history, an origin and
+// sync like anything else, and annotating a fact doesn't assert it.
+// ============================================================================
+
+use rusqlite::Connection;
+use crate::eavto::{query, statement, store, Object, QuotedTriple, Triple};
+use crate::owl::{OwlError, Result};
+
+pub const CONFIDENCE: &str = "foundation:confidence";
+pub const NOTE: &str = "foundation:note";
+pub const WAS_DERIVED_FROM: &str = "prov:wasDerivedFrom";
+
+/// What is said about a fact
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct FactAnnotations {
+    pub confidence: Option<f64>, // From 0 (doubtful) to 1 (certain)
+    pub note: Option<String>,
+    pub derived_from: Vec<String>, // IRIs of sources
+}
+
+impl FactAnnotations {
+    pub fn is_empty(&self) -> bool {
+        *self == FactAnnotations::default()
+    }
+}
+
+/// Annotations of a fact (empty if it has none)
+pub fn annotations(conn: &Connection, fact: &QuotedTriple) -> Result<FactAnnotations> {
+    let mut annotations = FactAnnotations::default();
+    for triple in query::get_by_entity(conn, &fact.term())?.triples {
+        match (triple.predicate.as_str(), triple.object) {
+            (CONFIDENCE, Object::Number(n)) => annotations.confidence = Some(n),
+            (CONFIDENCE, Object::Integer(n)) => annotations.confidence = Some(n as f64),
+            (NOTE, note) => annotations.note = note.as_literal(),
+            (WAS_DERIVED_FROM, Object::Iri(source)) => annotations.derived_from.push(source),
+            _ => {}
+        }
+    }
+    annotations.derived_from.sort();
+    Ok(annotations)
+}
+
+/// Facts about an entity (as their subject) that have annotations, with them
+pub fn annotated_facts(conn: &Connection, entity: &str) -> Result<Vec<(QuotedTriple, FactAnnotations)>> {
+    let mut facts = Vec::new();
+    for fact in statement::about(conn, entity)? {
+        let annotations = annotations(conn, &fact)?;
+        if !annotations.is_empty() {
+            facts.push((fact, annotations));
+        }
+    }
+    Ok(facts)
+}
+
+/// Replace the annotations of a fact (empty ones remove them)
+/// Returns the transaction, or None if nothing changed
+pub fn annotate(
+    conn: &mut Connection,
+    fact: &QuotedTriple,
+    annotations: &FactAnnotations,
+    origin: &str,
+) -> Result<Option<i64>> {
+    if let Some(confidence) = annotations.confidence.filter(|c| !(0.0..=1.0).contains(c)) {
+        return Err(OwlError::ValidationError(format!("Confidence must be between 0 and 1, not {}", confidence)));
+    }
+
+    let subject = fact.term();
+    let mut wanted: Vec<Triple> = annotations.derived_from.iter()
+        .map(|source| Triple::new(&subject, WAS_DERIVED_FROM, Object::Iri(source.clone())))
+        .collect();
+    if let Some(confidence) = annotations.confidence {
+        wanted.push(Triple::new(&subject, CONFIDENCE, Object::Number(confidence)));
+    }
+    if let Some(note) = annotations.note.as_deref().map(str::trim).filter(|note| !note.is_empty()) {
+        wanted.push(Triple::new(&subject, NOTE, Object::Literal {
+            value: note.to_string(),
+            datatype: Some("xsd:string".to_string()),
+            language: None,
+        }));
+    }
+
+    let existing: Vec<Triple> = query::get_by_entity(conn, &subject)?.triples.into_iter()
+        .filter(|t| [CONFIDENCE, NOTE, WAS_DERIVED_FROM].contains(&t.predicate.as_str()))
+        .collect();
+    let same = |a: &Triple, b: &Triple| a.predicate == b.predicate && a.object == b.object;
+    let retractions: Vec<Triple> = existing.iter()
+        .filter(|t| !wanted.iter().any(|w| same(w, t)))
+        .cloned()
+        .collect();
+    let additions: Vec<Triple> = wanted.into_iter()
+        .filter(|w| !existing.iter().any(|t| same(w, t)))
+        .collect();
+
+    if additions.is_empty() && retractions.is_empty() {
+        return Ok(None);
+    }
+    Ok(Some(store::apply_changes(conn, &additions, &retractions, origin)?))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::eavto::test_helpers::setup_test_db;
+
+    #[test]
+    fn test_annotate_a_fact_without_asserting_it() {
+        let mut conn = setup_test_db();
+        let fact = QuotedTriple::new("foundation:alice", "foundation:birthYear", Object::Integer(1990));
+        let sourced = FactAnnotations {
+            confidence: Some(0.8),
+            note: Some("  From her passport ".to_string()),
+            derived_from: vec!["foundation:passport".to_string()],
+        };
+
+        assert!(annotate(&mut conn, &fact, &sourced, "test").unwrap().is_some());
+        let read = annotations(&conn, &fact).unwrap();
+        assert_eq!((read.confidence, read.note.as_deref()), (Some(0.8), Some("From her passport")));
+        assert_eq!(read.derived_from, ["foundation:passport"]);
+        assert!(query::get_by_entity(&conn, "foundation:alice").unwrap().triples.is_empty());
+        assert_eq!(annotated_facts(&conn, "foundation:alice").unwrap(), vec![(fact.clone(), read)]);
+
+        // Unchanged annotations write nothing; changed ones replace the old
+        let trimmed = FactAnnotations { note: Some("From her passport".to_string()), ..sourced.clone() };
+        assert!(annotate(&mut conn, &fact, &trimmed, "test").unwrap().is_none());
+        let doubtful = FactAnnotations { confidence: Some(0.3), ..Default::default() };
+        annotate(&mut conn, &fact, &doubtful, "test").unwrap();
+        assert_eq!(annotations(&conn, &fact).unwrap(), doubtful);
+
+        let invalid = FactAnnotations { confidence: Some(1.5), ..Default::default() };
+        assert!(annotate(&mut conn, &fact, &invalid, "test").is_err());
+        annotate(&mut conn, &fact, &FactAnnotations::default(), "test").unwrap();
+        assert!(annotated_facts(&conn, "foundation:alice").unwrap().is_empty());
+    }
+}
@@ -0,0 +1,257 @@
+// ============================================================================
+// OWL Federation - Public SPARQL Endpoints
+// ============================================================================
+// Enriches the local graph with public knowledge (Wikidata, DBpedia) on
+// demand: a CONSTRUCT or DESCRIBE query is sent to a SPARQL endpoint and the
+// triples it returns are cached in the store.
+//
+// Each (endpoint, query) pair caches into its own origin,
+// federated:{endpoint}#{hash}, put in quarantine the first time it is
+// fetched: results stay out of normal queries until the user approves them
+// (see eavto::quarantine). Refreshing a query replaces its cached triples
+// with the new results, keeping the review status; a rejected query is not
+// fetched again.
+//
+// Fetch times are kept in metadata (federation:{hash}), so a query asked
+// again within its cache TTL is answered from the store. A query the
+// endpoint can't be asked now (offline) is queued in the outbox and fetched
+// in the background.
+// ============================================================================
+
+use rusqlite::Connection;
+use serde::{Deserialize, Serialize};
+use sha2::{Sha256, Digest};
+use std::collections::HashSet;
+use std::time::Duration;
+use crate::eavto::quarantine::{self, OriginStatus};
+use crate::eavto::{canonical, query, store, Triple};
+use crate::owl::{Result, OwlError};
+
+/// Outbox job kind of queries queued while offline (see outbox::worker)
+pub const OUTBOX_KIND: &str = "federated_query";
+
+/// Cache TTL when the caller gives none: one day, in seconds
+pub const DEFAULT_CACHE_TTL: i64 = 86_400;
+
+/// Media types requested from endpoints (N-Triples is parsed as Turtle)
+const ACCEPT: &str = "text/turtle, application/n-triples;q=0.9";
+
+/// Wikidata refuses requests without an identifying User-Agent
+const USER_AGENT: &str = concat!("FOUNDATION/", env!("CARGO_PKG_VERSION"), " (federated query)");
+
+const TIMEOUT: Duration = Duration::from_secs(60);
+
+/// Results of a federated query
+#[derive(Debug, Clone)]
+pub struct FederatedResult {
+    pub origin: String,
+    pub triples: Vec<Triple>,
+    pub cached: bool,    // Answered from the store, without asking the endpoint
+    pub fetched_at: i64, // When the endpoint was last asked (Unix ms)
+    pub status: OriginStatus,
+}
+
+/// Fetch record kept in metadata
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct FetchRecord {
+    endpoint: String,
+    query: String,
+    fetched_at: i64,
+}
+
+/// Origin caching the results of a query
+pub fn origin_name(endpoint: &str, sparql: &str) -> String {
+    format!("federated:{}#{}", endpoint, &query_hash(endpoint, sparql)[..12])
+}
+
+/// Cached results of a query, if fetched within `cache_ttl` seconds
+///
+/// Fails for a query whose results were rejected, so it isn't fetched again.
+pub fn cached(conn: &Connection, endpoint: &str, sparql: &str, cache_ttl: i64) -> Result<Option<FederatedResult>> {
+    let origin = origin_name(endpoint, sparql);
+    let status = match quarantine::get_status(conn, &origin)? {
+        Some(OriginStatus::Rejected) => return Err(rejected(&origin)),
+        status => status.unwrap_or(OriginStatus::Quarantined),
+    };
+    let Some(fetched_at) = fetched_at(conn, endpoint, sparql)? else { return Ok(None) };
+    if store::now_millis() - fetched_at >= cache_ttl.saturating_mul(1000) {
+        return Ok(None);
+    }
+
+    Ok(Some(FederatedResult {
+        triples: origin_triples(conn, &origin)?,
+        origin,
+        cached: true,
+        fetched_at,
+        status,
+    }))
+}
+
+/// Ask an endpoint for the triples of a CONSTRUCT or DESCRIBE query,
+/// returning the response body
+pub fn fetch(endpoint: &str, sparql: &str) -> Result<String> {
+    check_query_form(sparql)?;
+
+    let response = ureq::get(endpoint)
+        .query("query", sparql)
+        .set("Accept", ACCEPT)
+        .set("User-Agent", USER_AGENT)
+        .timeout(TIMEOUT)
+        .call()
+        .map_err(|e| OwlError::InvalidOperation(format!("{} did not answer: {}", endpoint, e)))?;
+    response.into_string()
+        .map_err(|e| OwlError::InvalidOperation(format!("Unreadable response from {}: {}", endpoint, e)))
+}
+
+/// Queue a query to be fetched in the background, for when the endpoint
+/// can't be reached now; returns the outbox job
+pub fn queue(conn: &Connection, endpoint: &str, sparql: &str) -> Result<i64> {
+    check_query_form(sparql)?;
+    Ok(crate::outbox::enqueue(conn, OUTBOX_KIND, &serde_json::json!({ "endpoint": endpoint, "query": sparql }))?)
+}
+
+/// Cache the response of an endpoint to a query, replacing earlier results
+pub fn store_results(conn: &mut Connection, endpoint: &str, sparql: &str, body: &str) -> Result<FederatedResult> {
+    let origin = origin_name(endpoint, sparql);
+    let status = match quarantine::get_status(conn, &origin)? {
+        Some(OriginStatus::Rejected) => return Err(rejected(&origin)),
+        Some(status) => status,
+        None => {
+            quarantine::quarantine(conn, &origin)?;
+            OriginStatus::Quarantined
+        }
+    };
+
+    let now = store::now_millis();
+    let parsed = crate::turtle::pipeline::parse(body.as_bytes(), crate::turtle::RdfFormat::Turtle, 0, now)
+        .map_err(|e| OwlError::ValidationError(format!("Invalid response from {}: {:?}", endpoint, e)))?;
+    let mut seen = HashSet::new();
+    let results: Vec<Triple> = parsed.into_iter().filter(|t| seen.insert(key(t))).collect();
+
+    let current = origin_triples(conn, &origin)?;
+    let current_keys: HashSet<String> = current.iter().map(key).collect();
+    let additions: Vec<Triple> = results.iter().filter(|t| !current_keys.contains(&key(t))).cloned().collect();
+    let retractions: Vec<Triple> = current.into_iter().filter(|t| !seen.contains(&key(t))).collect();
+    if !additions.is_empty() || !retractions.is_empty() {
+        store::apply_changes(conn, &additions, &retractions, &origin)?;
+    }
+
+    let record = FetchRecord { endpoint: endpoint.to_string(), query: sparql.to_string(), fetched_at: now };
+    let record = serde_json::to_string(&record).map_err(|e| OwlError::DatabaseError(e.to_string()))?;
+    store::set_metadata(conn, &metadata_key(endpoint, sparql), Some(&record))?;
+
+    Ok(FederatedResult {
+        triples: origin_triples(conn, &origin)?,
+        origin,
+        cached: false,
+        fetched_at: now,
+        status,
+    })
+}
+
+/// Only queries returning triples can be cached as triples
+fn check_query_form(sparql: &str) -> Result<()> {
+    let form = sparql.lines()
+        .map(|line| line.trim())
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .flat_map(|line| line.split_whitespace())
+        .map(|word| word.to_ascii_uppercase())
+        .find(|word| matches!(word.as_str(), "SELECT" | "ASK" | "CONSTRUCT" | "DESCRIBE"));
+
+    match form.as_deref() {
+        Some("CONSTRUCT") | Some("DESCRIBE") => Ok(()),
+        Some(form) => Err(OwlError::ValidationError(format!(
+            "{} queries return no triples; use CONSTRUCT or DESCRIBE", form
+        ))),
+        None => Err(OwlError::ValidationError("Not a SPARQL query".to_string())),
+    }
+}
+
+fn rejected(origin: &str) -> OwlError {
+    OwlError::InvalidOperation(format!("Results of this query were rejected ({})", origin))
+}
+
+fn fetched_at(conn: &Connection, endpoint: &str, sparql: &str) -> Result<Option<i64>> {
+    Ok(query::get_metadata(conn, &metadata_key(endpoint, sparql))?
+        .and_then(|value| serde_json::from_str::<FetchRecord>(&value).ok())
+        .map(|record| record.fetched_at))
+}
+
+fn origin_triples(conn: &Connection, origin: &str) -> Result<Vec<Triple>> {
+    match query::get_origin_id(conn, origin)? {
+        Some(origin_id) => Ok(query::get_by_origin(conn, origin_id)?.triples),
+        None => Ok(Vec::new()),
+    }
+}
+
+fn key(triple: &Triple) -> String {
+    format!("{} {} {}", triple.subject, triple.predicate, canonical::object(&triple.object))
+}
+
+fn metadata_key(endpoint: &str, sparql: &str) -> String {
+    format!("federation:{}", query_hash(endpoint, sparql))
+}
+
+fn query_hash(endpoint: &str, sparql: &str) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(endpoint.as_bytes());
+    hasher.update([0]);
+    hasher.update(sparql.trim().as_bytes());
+    format!("{:x}", hasher.finalize())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::eavto::test_helpers::setup_test_db;
+
+    const ENDPOINT: &str = "https://query.wikidata.org/sparql";
+    const QUERY: &str = "PREFIX wd: <http://www.wikidata.org/entity/>\nDESCRIBE wd:Q90";
+
+    #[test]
+    fn test_results_cached_in_quarantine() {
+        let mut conn = setup_test_db();
+        assert!(cached(&conn, ENDPOINT, QUERY, DEFAULT_CACHE_TTL).unwrap().is_none());
+
+        let body = "<http://www.wikidata.org/entity/Q90> <http://www.w3.org/2000/01/rdf-schema#label> \"Paris\"@en .\n\
+                    <http://www.wikidata.org/entity/Q90> <http://www.w3.org/2000/01/rdf-schema#label> \"Paris\"@en .\n\
+                    <http://www.wikidata.org/entity/Q90> <http://www.wikidata.org/prop/direct/P1082> \"2145906\"^^<http://www.w3.org/2001/XMLSchema#integer> .\n";
+        let result = store_results(&mut conn, ENDPOINT, QUERY, body).unwrap();
+        assert!(!result.cached);
+        assert_eq!(result.status, OriginStatus::Quarantined);
+        assert_eq!(result.triples.len(), 2);
+        assert!(result.origin.starts_with("federated:https://query.wikidata.org/sparql#"));
+
+        // Hidden from normal queries until approved
+        let subject = &result.triples[0].subject;
+        assert!(query::get_by_entity(&conn, subject).unwrap().triples.is_empty());
+
+        let hit = cached(&conn, ENDPOINT, QUERY, DEFAULT_CACHE_TTL).unwrap().unwrap();
+        assert!(hit.cached);
+        assert_eq!(hit.triples.len(), 2);
+        assert!(cached(&conn, ENDPOINT, QUERY, 0).unwrap().is_none());
+
+        // A refresh replaces the cached results
+        quarantine::approve(&conn, &result.origin).unwrap();
+        let body = "<http://www.wikidata.org/entity/Q90> <http://www.w3.org/2000/01/rdf-schema#label> \"Paris\"@en .\n";
+        let refreshed = store_results(&mut conn, ENDPOINT, QUERY, body).unwrap();
+        assert_eq!(refreshed.status, OriginStatus::Active);
+        assert_eq!(refreshed.triples.len(), 1);
+        assert_eq!(query::get_by_entity(&conn, subject).unwrap().triples.len(), 1);
+    }
+
+    #[test]
+    fn test_rejected_and_select_queries_refused() {
+        let mut conn = setup_test_db();
+        assert!(check_query_form("# Cities\nCONSTRUCT { ?s ?p ?o } WHERE { ?s ?p ?o }").is_ok());
+        assert!(check_query_form("SELECT ?s WHERE { ?s ?p ?o }").is_err());
+        assert!(fetch(ENDPOINT, "ASK { ?s ?p ?o }").is_err());
+
+        let origin = origin_name(ENDPOINT, QUERY);
+        quarantine::quarantine(&conn, &origin).unwrap();
+        quarantine::reject(&mut conn, &origin).unwrap();
+        assert!(cached(&conn, ENDPOINT, QUERY, DEFAULT_CACHE_TTL).is_err());
+        assert!(store_results(&mut conn, ENDPOINT, QUERY, "").is_err());
+    }
+}
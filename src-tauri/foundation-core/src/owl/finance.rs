@@ -0,0 +1,482 @@
+// ============================================================================
+// OWL Finance - Bank Statement Import
+// ============================================================================
+// Imports bank statements (OFX, or CSV exports described by a profile) as
+// foundation:FinancialTransaction entities:
+// - Signed amounts with their currency (QUDT currency units, cur:USD ...)
+// - Posting dates as xsd:dateTime
+// - CSV numbers and dates read as the profile's locale writes them (see
+//   owl::locale)
+// - Counterparties linked to foundation:Organization entities, reusing an
+//   existing organization with the same label
+//
+// Transaction IRIs are derived from (date, amount, reference), so importing
+// overlapping statements doesn't duplicate transactions.
+// ============================================================================
+
+use rusqlite::Connection;
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
+use chrono::{NaiveDate, NaiveDateTime};
+use crate::eavto::{query, store, Object, Triple};
+use crate::owl::{Class, Thing, Result, hash, string, OwlError, text, locale::Locale, vocabulary::{rdf, rdfs}};
+
+pub const FINANCIAL_TRANSACTION: &str = "foundation:FinancialTransaction";
+pub const AMOUNT: &str = "foundation:amount";
+pub const CURRENCY: &str = "foundation:currency";
+pub const TRANSACTION_DATE: &str = "foundation:transactionDate";
+pub const REFERENCE: &str = "foundation:reference";
+pub const COUNTERPARTY: &str = "foundation:counterparty";
+pub const ACCOUNT: &str = "foundation:account";
+
+pub(crate) const ORGANIZATION: &str = "foundation:Organization";
+
+/// Currency when neither the statement nor the profile names one
+const DEFAULT_CURRENCY: &str = "USD";
+
+/// Statement file format
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StatementFormat {
+    Ofx,
+    Csv,
+}
+
+/// How to read a statement
+///
+/// The column names apply to CSV files (matched case-insensitively against
+/// the header row); OFX files are self-describing.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ImportProfile {
+    pub format: Option<StatementFormat>, // None: from the file extension
+    pub currency: Option<String>, // ISO 4217 code, when the file has none
+    pub account: Option<String>, // FinancialAccount the transactions belong to
+    pub delimiter: char,
+    pub date_column: String,
+    pub date_format: String, // chrono format, e.g. "%d/%m/%Y"
+    pub amount_column: String,
+    pub decimal_comma: bool, // "1.234,56"
+    pub locale: Option<String>, // BCP 47 tag ("pt-BR"): its number and date conventions apply too
+    pub description_column: String,
+    pub reference_column: String,
+    pub counterparty_column: String,
+}
+
+impl Default for ImportProfile {
+    fn default() -> Self {
+        Self {
+            format: None,
+            currency: None,
+            account: None,
+            delimiter: ',',
+            date_column: "date".to_string(),
+            date_format: "%Y-%m-%d".to_string(),
+            amount_column: "amount".to_string(),
+            decimal_comma: false,
+            locale: None,
+            description_column: "description".to_string(),
+            reference_column: "reference".to_string(),
+            counterparty_column: "payee".to_string(),
+        }
+    }
+}
+
+impl ImportProfile {
+    /// How numbers and dates are written: the profile's date format first,
+    /// then the locale's conventions
+    pub fn locale(&self) -> Result<Locale> {
+        let mut locale = match &self.locale {
+            Some(tag) => Locale::from_tag(tag)
+                .ok_or_else(|| OwlError::ValidationError(format!("Unknown locale: {}", tag)))?,
+            None => Locale::default(),
+        };
+        locale.decimal_comma |= self.decimal_comma;
+        locale.date_formats.insert(0, self.date_format.clone());
+        Ok(locale)
+    }
+}
+
+/// A transaction read from a statement
+#[derive(Debug, Clone, PartialEq)]
+pub struct StatementEntry {
+    pub date: i64, // Unix ms
+    pub amount: f64,
+    pub currency: String, // ISO 4217 code
+    pub reference: Option<String>,
+    pub counterparty: Option<String>,
+    pub description: Option<String>,
+}
+
+/// Outcome of an import
+#[derive(Debug, Clone, PartialEq)]
+pub struct FinanceImport {
+    pub imported: usize,
+    pub duplicates: usize, // Already imported (or repeated in the file)
+    pub organizations: usize, // Counterparties created
+    pub tx: Option<i64>,
+}
+
+/// Import a statement file
+pub fn import_statement(conn: &mut Connection, path: &Path, profile: &ImportProfile) -> Result<FinanceImport> {
+    let text = std::fs::read_to_string(path)
+        .map_err(|e| OwlError::InvalidOperation(format!("Can't read {}: {}", path.display(), e)))?;
+
+    let format = profile.format
+        .or_else(|| match path.extension()?.to_str()?.to_ascii_lowercase().as_str() {
+            "ofx" | "qfx" => Some(StatementFormat::Ofx),
+            "csv" => Some(StatementFormat::Csv),
+            _ => None,
+        })
+        .ok_or_else(|| OwlError::ValidationError(format!("Unknown statement format: {}", path.display())))?;
+
+    let entries = match format {
+        StatementFormat::Ofx => parse_ofx(&text, profile)?,
+        StatementFormat::Csv => parse_csv(&text, profile)?,
+    };
+
+    let file_name = path.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default();
+    import_entries(conn, &entries, profile.account.as_deref(), &format!("import:{}", file_name))
+}
+
+/// Assert statement entries as transactions, skipping duplicates
+pub fn import_entries(
+    conn: &mut Connection,
+    entries: &[StatementEntry],
+    account: Option<&str>,
+    origin: &str,
+) -> Result<FinanceImport> {
+    let mut organizations = organizations_by_label(conn)?;
+    let mut created = 0;
+    let mut duplicates = 0;
+    let mut seen = HashSet::new();
+    let mut triples = Vec::new();
+
+    for entry in entries {
+        let iri = transaction_iri(entry);
+        let exists = !query::get_by_entity_predicate(conn, &iri, rdf::TYPE)?.triples.is_empty();
+        if exists || !seen.insert(iri.clone()) {
+            duplicates += 1;
+            continue;
+        }
+
+        let label = entry.description.clone()
+            .or_else(|| entry.counterparty.clone())
+            .unwrap_or_else(|| format!("Transaction {}", entry.reference.as_deref().unwrap_or_default()));
+        triples.push(Triple::new(&iri, rdf::TYPE, Object::Iri(FINANCIAL_TRANSACTION.to_string())));
+        triples.push(Triple::new(&iri, rdfs::LABEL, string(&label)));
+        triples.push(Triple::new(&iri, AMOUNT, Object::Number(entry.amount)));
+        triples.push(Triple::new(&iri, CURRENCY, Object::Iri(format!("cur:{}", entry.currency))));
+        triples.push(Triple::new(&iri, TRANSACTION_DATE, Object::DateTime(entry.date)));
+        if let Some(reference) = &entry.reference {
+            triples.push(Triple::new(&iri, REFERENCE, string(reference)));
+        }
+        if let Some(account) = account {
+            triples.push(Triple::new(&iri, ACCOUNT, Object::Iri(account.to_string())));
+        }
+
+        if let Some(name) = entry.counterparty.as_deref().map(str::trim).filter(|n| !n.is_empty()) {
+            let key = text::fold(name);
+            let organization = match organizations.get(&key) {
+                Some(organization) => organization.clone(),
+                None => {
+                    let organization = format!("foundation:Organization_{}", hash(&key));
+                    triples.push(Triple::new(&organization, rdf::TYPE, Object::Iri(ORGANIZATION.to_string())));
+                    triples.push(Triple::new(&organization, rdfs::LABEL, string(name)));
+                    organizations.insert(key, organization.clone());
+                    created += 1;
+                    organization
+                }
+            };
+            triples.push(Triple::new(&iri, COUNTERPARTY, Object::Iri(organization)));
+        }
+    }
+
+    let tx = if triples.is_empty() {
+        None
+    } else {
+        Some(store::assert_triples(conn, &triples, origin)?)
+    };
+
+    Ok(FinanceImport {
+        imported: seen.len(),
+        duplicates,
+        organizations: created,
+        tx,
+    })
+}
+
+/// Transaction IRI, identical for the same (date, amount, reference)
+///
+/// The description stands in for a missing reference.
+fn transaction_iri(entry: &StatementEntry) -> String {
+    let reference = entry.reference.as_deref().or(entry.description.as_deref()).unwrap_or_default();
+    format!("foundation:FinancialTransaction_{}", hash(&format!("{}|{}|{}", entry.date, entry.amount, reference)))
+}
+
+/// Existing organizations, by folded label
+pub(crate) fn organizations_by_label(conn: &Connection) -> Result<HashMap<String, String>> {
+    let mut organizations = HashMap::new();
+    for iri in Class::get_instances(conn, ORGANIZATION)? {
+        let label = text::fold(&Thing::get(conn, &iri).label);
+        organizations.entry(label).or_insert(iri);
+    }
+    Ok(organizations)
+}
+
+// ============================================================================
+// OFX
+// ============================================================================
+
+/// Parse an OFX statement (SGML 1.x or XML 2.x)
+pub fn parse_ofx(text: &str, profile: &ImportProfile) -> Result<Vec<StatementEntry>> {
+    let currency = ofx_field(text, "CURDEF")
+        .or_else(|| profile.currency.clone())
+        .unwrap_or_else(|| DEFAULT_CURRENCY.to_string())
+        .to_uppercase();
+
+    let mut entries = Vec::new();
+    for block in text.split("<STMTTRN>").skip(1) {
+        let block = block.split("</STMTTRN>").next().unwrap_or(block);
+
+        let posted = ofx_field(block, "DTPOSTED")
+            .ok_or_else(|| OwlError::ValidationError("OFX transaction without DTPOSTED".to_string()))?;
+        let amount = ofx_field(block, "TRNAMT")
+            .ok_or_else(|| OwlError::ValidationError("OFX transaction without TRNAMT".to_string()))?;
+
+        entries.push(StatementEntry {
+            date: ofx_date(&posted)?,
+            amount: Locale::default().parse_number(&amount)
+                .ok_or_else(|| OwlError::ValidationError(format!("Invalid amount: {}", amount)))?,
+            currency: currency.clone(),
+            reference: ofx_field(block, "FITID"),
+            counterparty: ofx_field(block, "NAME"),
+            description: ofx_field(block, "MEMO").or_else(|| ofx_field(block, "NAME")),
+        });
+    }
+    Ok(entries)
+}
+
+/// Value of the first <TAG>value element (closing tags are optional in OFX 1.x)
+fn ofx_field(text: &str, tag: &str) -> Option<String> {
+    let start = text.find(&format!("<{}>", tag))? + tag.len() + 2;
+    let value = text[start..].split('<').next()?.trim();
+    (!value.is_empty()).then(|| value.to_string())
+}
+
+/// OFX date: YYYYMMDD[HHMMSS[.XXX]][[offset:TZ]], UTC when no offset is given
+fn ofx_date(value: &str) -> Result<i64> {
+    let invalid = || OwlError::ValidationError(format!("Invalid OFX date: {}", value));
+
+    let (digits, zone) = match value.split_once('[') {
+        Some((digits, zone)) => (digits, Some(zone.trim_end_matches(']'))),
+        None => (value, None),
+    };
+    let digits: String = digits.chars().take_while(|c| c.is_ascii_digit()).collect();
+    let date = NaiveDate::parse_from_str(digits.get(..8).ok_or_else(invalid)?, "%Y%m%d").map_err(|_| invalid())?;
+    let time = match digits.get(8..14) {
+        Some(time) => NaiveDateTime::parse_from_str(&format!("{}{}", &digits[..8], time), "%Y%m%d%H%M%S").map_err(|_| invalid())?,
+        None => date.and_hms_opt(0, 0, 0).ok_or_else(invalid)?,
+    };
+
+    let offset_hours: f64 = zone
+        .and_then(|zone| zone.split(':').next())
+        .map(|offset| offset.parse().map_err(|_| invalid()))
+        .transpose()?
+        .unwrap_or(0.0);
+    Ok(time.and_utc().timestamp_millis() - (offset_hours * 3_600_000.0) as i64)
+}
+
+// ============================================================================
+// CSV
+// ============================================================================
+
+/// Parse a CSV statement using the profile's columns
+pub fn parse_csv(text: &str, profile: &ImportProfile) -> Result<Vec<StatementEntry>> {
+    let mut records = csv_records(text, profile.delimiter).into_iter();
+    let header: Vec<String> = records.next()
+        .ok_or_else(|| OwlError::ValidationError("The CSV file is empty".to_string()))?
+        .iter()
+        .map(|h| h.trim().to_lowercase())
+        .collect();
+    let column = |name: &str| header.iter().position(|h| *h == name.to_lowercase());
+
+    let date_column = column(&profile.date_column)
+        .ok_or_else(|| OwlError::ValidationError(format!("No \"{}\" column", profile.date_column)))?;
+    let amount_column = column(&profile.amount_column)
+        .ok_or_else(|| OwlError::ValidationError(format!("No \"{}\" column", profile.amount_column)))?;
+    let description_column = column(&profile.description_column);
+    let reference_column = column(&profile.reference_column);
+    let counterparty_column = column(&profile.counterparty_column);
+
+    let currency = profile.currency.clone().unwrap_or_else(|| DEFAULT_CURRENCY.to_string()).to_uppercase();
+    let locale = profile.locale()?;
+
+    let mut entries = Vec::new();
+    for (i, record) in records.enumerate() {
+        if record.iter().all(|field| field.trim().is_empty()) {
+            continue;
+        }
+        let line = i + 2;
+        let field = |column: Option<usize>| column
+            .and_then(|c| record.get(c))
+            .map(|value| value.trim().to_string())
+            .filter(|value| !value.is_empty());
+
+        let date = field(Some(date_column)).unwrap_or_default();
+        let date = locale.parse_datetime(&date)
+            .ok_or_else(|| OwlError::ValidationError(format!("Line {}: date \"{}\" doesn't match {}", line, date, profile.date_format)))?;
+        let amount = field(Some(amount_column)).unwrap_or_default();
+
+        entries.push(StatementEntry {
+            date,
+            amount: locale.parse_number(&amount)
+                .ok_or_else(|| OwlError::ValidationError(format!("Line {}: Invalid amount: {}", line, amount)))?,
+            currency: currency.clone(),
+            reference: field(reference_column),
+            counterparty: field(counterparty_column),
+            description: field(description_column),
+        });
+    }
+    Ok(entries)
+}
+
+/// Records of a CSV text (RFC 4180 quoting; quoted fields may span lines)
+pub(crate) fn csv_records(text: &str, delimiter: char) -> Vec<Vec<String>> {
+    let mut records = Vec::new();
+    let mut record = Vec::new();
+    let mut field = String::new();
+    let mut quoted = false;
+    let mut chars = text.trim_start_matches('\u{feff}').chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if quoted && chars.peek() == Some(&'"') => {
+                chars.next();
+                field.push('"');
+            }
+            '"' => quoted = !quoted,
+            c if c == delimiter && !quoted => record.push(std::mem::take(&mut field)),
+            '\r' if !quoted => {}
+            '\n' if !quoted => {
+                record.push(std::mem::take(&mut field));
+                records.push(std::mem::take(&mut record));
+            }
+            c => field.push(c),
+        }
+    }
+    if !field.is_empty() || !record.is_empty() {
+        record.push(field);
+        records.push(record);
+    }
+    records
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::eavto::test_helpers::setup_test_db;
+
+    const OFX: &str = "OFXHEADER:100
+DATA:OFXSGML
+<OFX><BANKMSGSRSV1><STMTTRNRS><STMTRS>
+<CURDEF>BRL
+<BANKTRANLIST>
+<STMTTRN>
+<TRNTYPE>DEBIT
+<DTPOSTED>20240314120000[-3:BRT]
+<TRNAMT>-42.90
+<FITID>2024031401
+<NAME>Padaria Central
+<MEMO>Card purchase
+</STMTTRN>
+<STMTTRN>
+<TRNTYPE>CREDIT
+<DTPOSTED>20240315
+<TRNAMT>5000.00
+<FITID>2024031501
+<NAME>ACME CORP
+</STMTTRN>
+</BANKTRANLIST>
+</STMTRS></STMTTRNRS></BANKMSGSRSV1></OFX>";
+
+    #[test]
+    fn test_parse_ofx() {
+        let entries = parse_ofx(OFX, &ImportProfile::default()).unwrap();
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].amount, -42.90);
+        assert_eq!(entries[0].currency, "BRL");
+        assert_eq!(entries[0].reference.as_deref(), Some("2024031401"));
+        assert_eq!(entries[0].counterparty.as_deref(), Some("Padaria Central"));
+        assert_eq!(entries[0].description.as_deref(), Some("Card purchase"));
+        // 12:00 at UTC-3 is 15:00 UTC
+        assert_eq!(entries[0].date, chrono::NaiveDate::from_ymd_opt(2024, 3, 14).unwrap()
+            .and_hms_opt(15, 0, 0).unwrap().and_utc().timestamp_millis());
+        assert_eq!(entries[1].description.as_deref(), Some("ACME CORP"));
+    }
+
+    #[test]
+    fn test_parse_csv_with_profile() {
+        let csv = "Data;Valor;Histórico;Documento\n\
+                   14/03/2024;\"-1.234,56\";\"Aluguel; março\";991\n\
+                   15/03/2024;R$ 10,00;Pix recebido;\n";
+        let profile = ImportProfile {
+            delimiter: ';',
+            date_column: "Data".to_string(),
+            date_format: "%d/%m/%Y".to_string(),
+            amount_column: "Valor".to_string(),
+            decimal_comma: true,
+            description_column: "Histórico".to_string(),
+            reference_column: "Documento".to_string(),
+            currency: Some("brl".to_string()),
+            ..Default::default()
+        };
+
+        let entries = parse_csv(csv, &profile).unwrap();
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].amount, -1234.56);
+        assert_eq!(entries[0].description.as_deref(), Some("Aluguel; março"));
+        assert_eq!(entries[0].reference.as_deref(), Some("991"));
+        assert_eq!(entries[1].amount, 10.0);
+        assert_eq!(entries[1].reference, None);
+        assert_eq!(entries[1].currency, "BRL");
+
+        assert!(parse_csv("Data;Valor\nyesterday;1\n", &profile).is_err());
+
+        // A locale instead of explicit formats
+        let profile = ImportProfile { locale: Some("pt-BR".to_string()), ..profile };
+        let entries = parse_csv("Data;Valor\n28.01.2025;1.234,50\n", &ImportProfile {
+            date_format: "%Y".to_string(),
+            decimal_comma: false,
+            ..profile
+        }).unwrap();
+        assert_eq!(entries[0].amount, 1234.5);
+        assert_eq!(entries[0].date, NaiveDate::from_ymd_opt(2025, 1, 28).unwrap()
+            .and_hms_opt(0, 0, 0).unwrap().and_utc().timestamp_millis());
+    }
+
+    #[test]
+    fn test_import_links_counterparties_and_skips_duplicates() {
+        let mut conn = setup_test_db();
+        store::assert_triples(&mut conn, &[
+            Triple::new("foundation:Acme", rdf::TYPE, Object::Iri(ORGANIZATION.to_string())),
+            Triple::new("foundation:Acme", rdfs::LABEL, string("Acme Corp")),
+        ], "test").unwrap();
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("march.ofx");
+        std::fs::write(&path, OFX.replace("ACME CORP", "Acme Corp")).unwrap();
+
+        let first = import_statement(&mut conn, &path, &ImportProfile::default()).unwrap();
+        assert_eq!((first.imported, first.duplicates, first.organizations), (2, 0, 1));
+
+        let acme: Vec<Triple> = query::get_by_predicate_object(&conn, COUNTERPARTY, "foundation:Acme").unwrap().triples;
+        assert_eq!(acme.len(), 1);
+        let amount = query::get_by_entity_predicate(&conn, &acme[0].subject, AMOUNT).unwrap().triples;
+        assert_eq!(amount[0].object, Object::Number(5000.0));
+        let currency = query::get_by_entity_predicate(&conn, &acme[0].subject, CURRENCY).unwrap().triples;
+        assert_eq!(currency[0].object, Object::Iri("cur:BRL".to_string()));
+
+        // Importing an overlapping statement adds nothing
+        let again = import_statement(&mut conn, &path, &ImportProfile::default()).unwrap();
+        assert_eq!((again.imported, again.duplicates, again.tx), (0, 2, None));
+    }
+}
@@ -0,0 +1,168 @@
+// ============================================================================
+// OWL Functional Syntax
+// ============================================================================
+// Renders class axioms in OWL 2 Functional-Style Syntax.
+//
+// Restrictions use Object* or Data* constructors depending on whether the
+// restricted property is declared as an owl:DatatypeProperty.
+// ============================================================================
+
+use rusqlite::Connection;
+use crate::eavto::{query, Object};
+use crate::owl::{Result, vocabulary::{rdf, owl}};
+use crate::owl::expression::{ClassAxioms, ClassExpression, RestrictionConstraint};
+
+/// Export the axioms of a class in functional syntax
+pub fn export_class(conn: &Connection, class_iri: &str) -> Result<String> {
+    let axioms = ClassAxioms::get(conn, class_iri)?;
+    let class = render_iri(&axioms.iri);
+
+    let mut lines = vec![format!("Declaration(Class({}))", class)];
+
+    if let Some(label) = &axioms.label {
+        lines.push(format!("AnnotationAssertion(rdfs:label {} {})", class, quote(label)));
+    }
+    if let Some(comment) = &axioms.comment {
+        lines.push(format!("AnnotationAssertion(rdfs:comment {} {})", class, quote(comment)));
+    }
+
+    for expression in &axioms.sub_class_of {
+        lines.push(format!("SubClassOf({} {})", class, render(conn, expression)?));
+    }
+    for expression in &axioms.equivalent_to {
+        lines.push(format!("EquivalentClasses({} {})", class, render(conn, expression)?));
+    }
+    for expression in &axioms.disjoint_with {
+        lines.push(format!("DisjointClasses({} {})", class, render(conn, expression)?));
+    }
+
+    Ok(lines.join("\n") + "\n")
+}
+
+/// Render a class expression in functional syntax
+pub fn render(conn: &Connection, expression: &ClassExpression) -> Result<String> {
+    let render_all = |members: &[ClassExpression]| -> Result<String> {
+        Ok(members.iter()
+            .map(|m| render(conn, m))
+            .collect::<Result<Vec<_>>>()?
+            .join(" "))
+    };
+
+    Ok(match expression {
+        ClassExpression::Named(iri) => render_iri(iri),
+        ClassExpression::IntersectionOf(members) => format!("ObjectIntersectionOf({})", render_all(members)?),
+        ClassExpression::UnionOf(members) => format!("ObjectUnionOf({})", render_all(members)?),
+        ClassExpression::ComplementOf(inner) => format!("ObjectComplementOf({})", render(conn, inner)?),
+        ClassExpression::OneOf(individuals) => format!(
+            "ObjectOneOf({})",
+            individuals.iter().map(|i| render_iri(i)).collect::<Vec<_>>().join(" ")
+        ),
+        ClassExpression::Restriction { property, constraint } => {
+            let kind = if is_data_property(conn, property)? { "Data" } else { "Object" };
+            let p = render_iri(property);
+
+            match constraint {
+                RestrictionConstraint::SomeValuesFrom(filler) => {
+                    format!("{}SomeValuesFrom({} {})", kind, p, render(conn, filler)?)
+                }
+                RestrictionConstraint::AllValuesFrom(filler) => {
+                    format!("{}AllValuesFrom({} {})", kind, p, render(conn, filler)?)
+                }
+                RestrictionConstraint::HasValue(value) => {
+                    let kind = if value.is_literal() { "Data" } else { "Object" };
+                    format!("{}HasValue({} {})", kind, p, render_value(value))
+                }
+                RestrictionConstraint::MinCardinality(n, q) => render_cardinality(conn, kind, "Min", *n, &p, q)?,
+                RestrictionConstraint::MaxCardinality(n, q) => render_cardinality(conn, kind, "Max", *n, &p, q)?,
+                RestrictionConstraint::ExactCardinality(n, q) => render_cardinality(conn, kind, "Exact", *n, &p, q)?,
+            }
+        }
+    })
+}
+
+fn render_cardinality(
+    conn: &Connection,
+    kind: &str,
+    bound: &str,
+    n: u32,
+    property: &str,
+    qualifier: &Option<Box<ClassExpression>>,
+) -> Result<String> {
+    Ok(match qualifier {
+        Some(class) => format!("{}{}Cardinality({} {} {})", kind, bound, n, property, render(conn, class)?),
+        None => format!("{}{}Cardinality({} {})", kind, bound, n, property),
+    })
+}
+
+fn is_data_property(conn: &Connection, property: &str) -> Result<bool> {
+    let types = query::get_by_entity_predicate(conn, property, rdf::TYPE)?;
+    Ok(types.triples.iter().any(|t| t.object.as_iri() == Some(owl::DATATYPE_PROPERTY)))
+}
+
+fn render_iri(iri: &str) -> String {
+    if iri.contains("://") {
+        format!("<{}>", iri)
+    } else {
+        iri.to_string()
+    }
+}
+
+fn render_value(value: &Object) -> String {
+    match value {
+        Object::Iri(iri) | Object::Blank(iri) => render_iri(iri),
+        Object::Literal { value, datatype: Some(dt), .. } => format!("{}^^{}", quote(value), render_iri(dt)),
+        Object::Literal { value, language: Some(lang), .. } => format!("{}@{}", quote(value), lang),
+        other => match other.datatype() {
+            Some(dt) => format!("{}^^{}", quote(&other.as_literal().unwrap_or_default()), dt),
+            None => quote(&other.as_literal().unwrap_or_default()),
+        },
+    }
+}
+
+fn quote(value: &str) -> String {
+    format!("\"{}\"", value.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::eavto::{store, test_helpers::setup_test_db, Triple};
+    use crate::owl::manchester;
+    use crate::owl::vocabulary::rdfs;
+
+    #[test]
+    fn test_render_object_and_data_restrictions() {
+        let mut conn = setup_test_db();
+        store::assert_triples(&mut conn, &[
+            Triple::new("foundation:serialNumber", rdf::TYPE, Object::Iri(owl::DATATYPE_PROPERTY.to_string())),
+        ], "test").unwrap();
+
+        let object = manchester::parse("foundation:hasPart min 1 foundation:Wheel").unwrap();
+        assert_eq!(
+            render(&conn, &object).unwrap(),
+            "ObjectMinCardinality(1 foundation:hasPart foundation:Wheel)"
+        );
+
+        let data = manchester::parse("foundation:serialNumber some xsd:string").unwrap();
+        assert_eq!(
+            render(&conn, &data).unwrap(),
+            "DataSomeValuesFrom(foundation:serialNumber xsd:string)"
+        );
+    }
+
+    #[test]
+    fn test_export_class_axioms() {
+        let mut conn = setup_test_db();
+        let (node, mut triples) = manchester::parse("not foundation:Software")
+            .unwrap()
+            .to_triples("foundation:Hardware");
+        triples.push(Triple::new("foundation:Hardware", rdfs::SUB_CLASS_OF, Object::Iri("foundation:Thing".to_string())));
+        triples.push(Triple::new("foundation:Hardware", owl::EQUIVALENT_CLASS, Object::Blank(node)));
+        store::assert_triples(&mut conn, &triples, "test").unwrap();
+
+        let output = export_class(&conn, "foundation:Hardware").unwrap();
+        assert!(output.contains("Declaration(Class(foundation:Hardware))"));
+        assert!(output.contains("SubClassOf(foundation:Hardware foundation:Thing)"));
+        assert!(output.contains("EquivalentClasses(foundation:Hardware ObjectComplementOf(foundation:Software))"));
+    }
+}
@@ -0,0 +1,109 @@
+// ============================================================================
+// OWL Geo - Places and Distance Queries
+// ============================================================================
+// foundation:Place entities carry WGS 84 coordinates (foundation:latitude,
+// foundation:longitude, decimal degrees). Nearby places are found with a
+// bounding box over the typed number columns, then filtered by great-circle
+// distance.
+// ============================================================================
+
+use rusqlite::Connection;
+use crate::eavto::query;
+use crate::owl::{Thing, Result};
+
+pub const PLACE: &str = "foundation:Place";
+pub const LATITUDE: &str = "foundation:latitude";
+pub const LONGITUDE: &str = "foundation:longitude";
+
+/// Mean Earth radius (meters)
+const EARTH_RADIUS: f64 = 6_371_000.0;
+
+/// A place and its coordinates
+#[derive(Debug, Clone, PartialEq)]
+pub struct Place {
+    pub iri: String,
+    pub label: String,
+    pub latitude: f64,
+    pub longitude: f64,
+}
+
+/// Great-circle distance in meters (haversine)
+pub fn distance(lat1: f64, lon1: f64, lat2: f64, lon2: f64) -> f64 {
+    let (phi1, phi2) = (lat1.to_radians(), lat2.to_radians());
+    let d_phi = (lat2 - lat1).to_radians();
+    let d_lambda = (lon2 - lon1).to_radians();
+
+    let a = (d_phi / 2.0).sin().powi(2) + phi1.cos() * phi2.cos() * (d_lambda / 2.0).sin().powi(2);
+    2.0 * EARTH_RADIUS * a.sqrt().asin()
+}
+
+/// Places within a radius (meters) of a point, nearest first, with their distance
+pub fn near(conn: &Connection, latitude: f64, longitude: f64, radius: f64) -> Result<Vec<(Place, f64)>> {
+    // Bounding box: degrees of latitude are ~constant, degrees of longitude
+    // shrink towards the poles
+    let d_lat = (radius / EARTH_RADIUS).to_degrees();
+    let d_lon = d_lat / latitude.to_radians().cos().abs().max(1e-6);
+
+    let mut stmt = conn.prepare(&format!(
+        "SELECT lat.subject, lat.object_number, lon.object_number
+         FROM triples lat
+         JOIN triples lon ON lon.subject = lat.subject AND lon.predicate = ?2
+         WHERE lat.predicate = ?1
+           AND lat.object_number BETWEEN ?3 AND ?4
+           AND lon.object_number BETWEEN ?5 AND ?6
+           AND {} AND {}",
+        query::visible("lat"),
+        query::visible("lon")
+    ))?;
+    let candidates = stmt
+        .query_map(
+            rusqlite::params![LATITUDE, LONGITUDE, latitude - d_lat, latitude + d_lat, longitude - d_lon, longitude + d_lon],
+            |row| Ok((row.get::<_, String>(0)?, row.get::<_, f64>(1)?, row.get::<_, f64>(2)?)),
+        )?
+        .collect::<std::result::Result<Vec<_>, _>>()?;
+
+    let mut places: Vec<(Place, f64)> = candidates.into_iter()
+        .map(|(iri, lat, lon)| (iri, lat, lon, distance(latitude, longitude, lat, lon)))
+        .filter(|(_, _, _, d)| *d <= radius)
+        .map(|(iri, lat, lon, d)| (Place { label: Thing::get(conn, &iri).label, iri, latitude: lat, longitude: lon }, d))
+        .collect();
+    places.sort_by(|a, b| a.1.total_cmp(&b.1));
+    places.dedup_by(|a, b| a.0.iri == b.0.iri);
+    Ok(places)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::eavto::{store, test_helpers::setup_test_db, Object, Triple};
+
+    fn place(iri: &str, latitude: f64, longitude: f64) -> Vec<Triple> {
+        vec![
+            Triple::new(iri, "rdf:type", Object::Iri(PLACE.to_string())),
+            Triple::new(iri, LATITUDE, Object::Number(latitude)),
+            Triple::new(iri, LONGITUDE, Object::Number(longitude)),
+        ]
+    }
+
+    #[test]
+    fn test_distance() {
+        // Paris to London is ~344 km
+        let d = distance(48.8566, 2.3522, 51.5074, -0.1278);
+        assert!((d - 343_500.0).abs() < 1_000.0);
+        assert_eq!(distance(10.0, 10.0, 10.0, 10.0), 0.0);
+    }
+
+    #[test]
+    fn test_near() {
+        let mut conn = setup_test_db();
+        let mut triples = place("foundation:Office", -23.5613, -46.6565);
+        triples.extend(place("foundation:Cafe", -23.5620, -46.6570)); // ~90 m away
+        triples.extend(place("foundation:Beach", -23.9608, -46.3336));
+        store::assert_triples(&mut conn, &triples, "test").unwrap();
+
+        let found = near(&conn, -23.5613, -46.6565, 200.0).unwrap();
+        let iris: Vec<&str> = found.iter().map(|(p, _)| p.iri.as_str()).collect();
+        assert_eq!(iris, vec!["foundation:Office", "foundation:Cafe"]);
+        assert!(found[1].1 > 50.0 && found[1].1 < 150.0);
+    }
+}
@@ -0,0 +1,580 @@
+// ============================================================================
+// OWL Individual - Individual/Instance Operations
+// ============================================================================
+// High-level operations for managing individuals (instances of classes)
+//
+// IMPORTANT: Individuals are instances, NOT classes
+// - Individuals use rdf:type to declare their class
+// - Individuals NEVER use rdfs:subClassOf (that's for classes)
+// - Example: foundation:John rdf:type foundation:Person (NOT subClassOf)
+// ============================================================================
+
+use rusqlite::Connection;
+use std::collections::{BTreeSet, HashMap, HashSet};
+use crate::eavto::{canonical, store, query, Triple, Object};
+use crate::owl::{Result, OwlError, Thing, hash, Class, Property, PropertyType, inference, text, vocabulary::{owl, rdf, rdfs}};
+
+/// Represents an OWL Individual (instance of a class)
+///
+/// An Individual is an instance of a Class, not a Class itself.
+/// It uses rdf:type to declare its class membership.
+///
+/// Example:
+/// ```text
+/// foundation:John rdf:type foundation:Person .  // John is an instance
+/// foundation:Person rdf:type owl:Class .         // Person is a class
+/// ```
+#[derive(Debug, Clone)]
+pub struct Individual {
+    pub iri: String,
+    pub label: Option<String>,
+    pub icon: Option<String>,
+    pub comment: Option<String>,
+    pub types: Vec<Thing>,
+    pub properties: Vec<(String, Object)>, // (property_iri, value)
+    pub backlinks: Vec<(String, String, Object)>, // (source_entity, property_iri, value) - entities that reference this individual
+    pub same_as: Vec<String>, // Other IRIs of the same individual (owl:sameAs), merged into this one
+}
+
+/// A property value for a new individual, as the frontend sends it
+#[derive(Debug, Clone)]
+pub struct PropertyInput {
+    pub property: String,
+    pub value: serde_json::Value, // String, number or boolean; entity IRIs as strings
+    pub unit: Option<String>, // QUDT unit of a number, checked against the property's
+    pub language: Option<String>, // Language tag of a string
+}
+
+/// What retracting an individual removed
+#[derive(Debug, Clone, PartialEq)]
+pub struct Retraction {
+    pub tx: i64,
+    pub triples: usize,   // Triples about the individual
+    pub backlinks: usize, // Triples of other entities pointing to it
+}
+
+impl Individual {
+    /// Create a new empty Individual reference (only IRI)
+    pub fn new(iri: impl Into<String>) -> Self {
+        Self {
+            iri: iri.into(),
+            label: None,
+            icon: None,
+            comment: None,
+            types: Vec::new(),
+            properties: Vec::new(),
+            backlinks: Vec::new(),
+            same_as: Vec::new(),
+        }
+    }
+
+    /// Get complete individual data from database
+    ///
+    /// Individuals linked by owl:sameAs are one entity: the data of every
+    /// IRI in the set is merged under the canonical one (see `same_as`).
+    pub fn get(conn: &Connection, iri: impl Into<String>) -> Result<Self> {
+        let mut members = same_as(conn, &iri.into())?;
+        let iri = members.remove(0);
+        let all: Vec<&str> = std::iter::once(iri.as_str()).chain(members.iter().map(String::as_str)).collect();
+
+        // Get label, icon and comment: the canonical IRI's, else the first alias with one
+        let first_literal = |predicate: &str| -> Result<Option<String>> {
+            for member in &all {
+                let result = query::get_by_entity_predicate(conn, member, predicate)?;
+                if let Some(value) = result.triples.first().and_then(|t| t.object.as_literal()) {
+                    return Ok(Some(value));
+                }
+            }
+            Ok(None)
+        };
+        let label = first_literal(rdfs::LABEL)?;
+        let icon = first_literal("foundation:icon")?;
+        let comment = first_literal(rdfs::COMMENT)?;
+
+        let mut types: Vec<Thing> = Vec::new();
+        let mut properties: Vec<(String, Object)> = Vec::new();
+        let mut backlinks: Vec<(String, String, Object)> = Vec::new();
+        let mut seen_properties = HashSet::new();
+        let mut seen_backlinks = HashSet::new();
+        let mut inverses = HashMap::new(); // Predicate: whether it has an inverse
+        for member in &all {
+            // Get types (classes)
+            let types_result = query::get_by_entity_predicate(conn, member, rdf::TYPE)?;
+            let mut new_types: Vec<&str> = Vec::new();
+            for type_iri in types_result.triples.iter().filter_map(|t| t.object.as_iri()) {
+                if !types.iter().any(|t| t.iri == type_iri) && !new_types.contains(&type_iri) {
+                    new_types.push(type_iri);
+                }
+            }
+            types.extend(Thing::get_many(conn, &new_types));
+
+            // Get all properties (excluding metadata like label, icon, comment, and the sameAs links)
+            let all_triples = query::get_by_entity(conn, member)?;
+            for t in all_triples.triples {
+                if t.predicate == rdfs::LABEL
+                    || t.predicate == rdfs::COMMENT
+                    || t.predicate == "foundation:icon"
+                    || t.predicate == owl::SAME_AS
+                {
+                    continue;
+                }
+                if seen_properties.insert((t.predicate.clone(), canonical::object(&t.object))) {
+                    properties.push((t.predicate, t.object));
+                }
+            }
+            // And those implied by inverse properties and property chains
+            for t in inference::implied(conn, member)? {
+                if seen_properties.insert((t.predicate.clone(), canonical::object(&t.object))) {
+                    properties.push((t.predicate, t.object));
+                }
+            }
+
+            // Get backlinks - entities that reference this individual
+            let backlinks_result = query::get_by_object(conn, member)?;
+            for t in backlinks_result.triples {
+                // Exclude self-references and rdf:type
+                if all.contains(&t.subject.as_str()) || t.predicate == rdf::TYPE {
+                    continue;
+                }
+                // A link through a property with an inverse is one of the properties
+                if !inverses.contains_key(&t.predicate) {
+                    inverses.insert(t.predicate.clone(), !inference::inverses(conn, &t.predicate)?.is_empty());
+                }
+                if inverses[&t.predicate] {
+                    continue;
+                }
+                if seen_backlinks.insert((t.subject.clone(), t.predicate.clone(), canonical::object(&t.object))) {
+                    backlinks.push((t.subject, t.predicate, t.object));
+                }
+            }
+        }
+
+        Ok(Self {
+            iri,
+            label,
+            icon,
+            comment,
+            types,
+            properties,
+            backlinks,
+            same_as: members,
+        })
+    }
+
+    /// Assert individual with required metadata (label and icon)
+    /// This is the recommended way to create individuals
+    pub fn assert(
+        &self,
+        conn: &mut Connection,
+        class_iri: &str,
+        label: &str,
+        icon: &str,
+        origin: &str
+    ) -> Result<()> {
+        // Create individual type
+        let triple = Triple::new(&self.iri, rdf::TYPE, Object::Iri(class_iri.to_string()));
+        store::assert_triples(conn, &[triple], origin)?;
+
+        // Add required label
+        let label_obj = Object::Literal {
+            value: label.to_string(),
+            datatype: Some("xsd:string".to_string()),
+            language: None,
+        };
+        let label_triple = Triple::new(&self.iri, rdfs::LABEL, label_obj);
+        store::assert_triples(conn, &[label_triple], origin)?;
+
+        // Add required icon
+        let icon_obj = Object::Literal {
+            value: icon.to_string(),
+            datatype: Some("xsd:string".to_string()),
+            language: None,
+        };
+        let icon_triple = Triple::new(&self.iri, "foundation:icon", icon_obj);
+        store::assert_triples(conn, &[icon_triple], origin)?;
+
+        Ok(())
+    }
+
+    /// Create an individual of a class, with property values validated
+    /// against the properties the class declares (its own and inherited),
+    /// their ranges and units. Nothing is written when a value is invalid.
+    ///
+    /// The IRI is minted from the class name: foundation:{Class}_{hash}
+    pub fn create(
+        conn: &mut Connection,
+        class_iri: &str,
+        label: &str,
+        icon: &str,
+        values: &[PropertyInput],
+        origin: &str
+    ) -> Result<Self> {
+        if label.trim().is_empty() {
+            return Err(OwlError::ValidationError("Label is required".to_string()));
+        }
+        if !Class::new(class_iri).exists(conn)? {
+            return Err(OwlError::NotFound(format!("Class {} not found", class_iri)));
+        }
+
+        let class = Class::get(conn, class_iri)?;
+        let mut triples = Vec::new();
+        for input in values {
+            if !class.properties.iter().any(|(property, _)| *property == input.property) {
+                return Err(OwlError::ValidationError(
+                    format!("Property {} is not defined for class {}", input.property, class_iri)
+                ));
+            }
+            let object = Property::get(conn, &input.property)?
+                .value(conn, &input.value, input.unit.as_deref(), input.language.as_deref())?;
+            triples.push((input.property.clone(), object));
+        }
+
+        let label = text::normalize(label);
+        let individual = Individual::new(Self::mint_iri(conn, class_iri, &label)?);
+        individual.assert(conn, class_iri, &label, icon, origin)?;
+        if !triples.is_empty() {
+            let triples: Vec<Triple> = triples.into_iter()
+                .map(|(property, object)| Triple::new(&individual.iri, &property, object))
+                .collect();
+            store::assert_triples(conn, &triples, origin)?;
+        }
+
+        Individual::get(conn, individual.iri)
+    }
+
+    /// A new IRI for an individual of a class, unused in the store
+    fn mint_iri(conn: &Connection, class_iri: &str, label: &str) -> Result<String> {
+        let name = class_iri.rsplit([':', '/', '#']).next().unwrap_or("Individual");
+        let mut seed = format!("{}@{}", label, chrono::Utc::now().timestamp_nanos_opt().unwrap_or_default());
+        loop {
+            let iri = format!("foundation:{}_{}", name, hash(&seed));
+            if !Individual::new(iri.as_str()).exists(conn)? {
+                return Ok(iri);
+            }
+            seed.push('+');
+        }
+    }
+
+    /// Set a property of this individual, as one transaction
+    ///
+    /// The value is typed by the property's range, or by `datatype` when the
+    /// property declares none. A functional property's current values are
+    /// retracted; other properties gain the value next to theirs.
+    /// Returns the stored value.
+    pub fn set_property(
+        &self,
+        conn: &mut Connection,
+        property: &str,
+        value: &str,
+        datatype: Option<&str>,
+        origin: &str
+    ) -> Result<Object> {
+        self.check_property(conn, property)?;
+        let mut declared = Property::get(conn, property)?;
+        if let Some(datatype) = datatype {
+            match declared.ranges.first() {
+                _ if declared.property_type == PropertyType::ObjectProperty => {
+                    return Err(OwlError::ValidationError(format!("{} takes an entity, not a {}", property, datatype)));
+                }
+                Some(range) if range != datatype && range != rdfs::LITERAL => {
+                    return Err(OwlError::ValidationError(format!("{} takes {}, not {}", property, range, datatype)));
+                }
+                _ => declared.ranges = vec![datatype.to_string()],
+            }
+        }
+        let object = declared.value(conn, &serde_json::Value::String(value.to_string()), None, None)?;
+
+        let current = query::get_by_entity_predicate(conn, &self.iri, property)?.triples;
+        let retractions: Vec<Triple> = match declared.is_functional {
+            true => current.iter().filter(|t| t.object != object).cloned().collect(),
+            false => Vec::new(),
+        };
+        let additions = match current.iter().any(|t| t.object == object) {
+            true => Vec::new(),
+            false => vec![Triple::new(&self.iri, property, object.clone())],
+        };
+        if !additions.is_empty() || !retractions.is_empty() {
+            store::apply_changes(conn, &additions, &retractions, origin)?;
+        }
+        Ok(object)
+    }
+
+    /// Add a property to this individual
+    /// Validates that the property is defined in the individual's class or inherited from parent classes
+    pub fn add_property(&self, conn: &mut Connection, property: &str, value: Object, origin: &str) -> Result<()> {
+        self.check_property(conn, property)?;
+        Property::get(conn, property)?.check_range(conn, &value)?;
+
+        // Property is valid, assert the triple
+        let triple = Triple::new(&self.iri, property, value);
+        store::assert_triples(conn, &[triple], origin)?;
+        Ok(())
+    }
+
+    /// Check a property is defined in one of this individual's classes or their parents
+    fn check_property(&self, conn: &Connection, property: &str) -> Result<()> {
+        // Get individual's types (classes)
+        let types_result = query::get_by_entity_predicate(conn, &self.iri, rdf::TYPE)?;
+
+        if types_result.triples.is_empty() {
+            return Err(OwlError::NotFound(format!("Individual {} has no rdf:type", self.iri)));
+        }
+
+        // Check if property is valid for any of the individual's classes
+        let mut property_is_valid = false;
+
+        for triple in &types_result.triples {
+            if let Some(class_iri) = triple.object.as_iri() {
+                // Get class with all its properties (including inherited)
+                if let Ok(class) = Class::get(conn, class_iri) {
+                    // Check if property exists in this class or its parents
+                    if class.properties.iter().any(|(prop_iri, _)| prop_iri == property) {
+                        property_is_valid = true;
+                        break;
+                    }
+                }
+            }
+        }
+
+        if !property_is_valid {
+            return Err(OwlError::InvalidOperation(
+                format!("Property {} is not defined in any class of individual {}", property, self.iri)
+            ));
+        }
+        Ok(())
+    }
+
+    /// Retract this individual: every current triple about it and, with
+    /// `include_backlinks`, the triples of other entities pointing to it, as
+    /// one transaction
+    ///
+    /// Entities of the bundled core ontology can't be retracted.
+    pub fn retract(&self, conn: &mut Connection, include_backlinks: bool, origin: &str) -> Result<Retraction> {
+        let triples = query::get_by_entity(conn, &self.iri)?.triples;
+        if triples.is_empty() {
+            return Err(OwlError::NotFound(format!("Entity {} not found", self.iri)));
+        }
+        for triple in &triples {
+            let origin_name = query::get_origin_name(conn, triple.origin_id)?.unwrap_or_default();
+            if query::is_core_origin(&origin_name) {
+                return Err(OwlError::InvalidOperation(
+                    format!("{} is part of the core ontology ({})", self.iri, origin_name)
+                ));
+            }
+        }
+
+        let backlinks: Vec<Triple> = match include_backlinks {
+            true => query::get_by_object(conn, &self.iri)?.triples.into_iter()
+                .filter(|t| t.subject != self.iri)
+                .collect(),
+            false => Vec::new(),
+        };
+
+        let retractions: Vec<Triple> = triples.iter().chain(&backlinks).cloned().collect();
+        let tx = store::apply_changes(conn, &[], &retractions, origin)?;
+        Ok(Retraction { tx, triples: triples.len(), backlinks: backlinks.len() })
+    }
+
+    /// Check if this individual exists (has at least one triple)
+    pub fn exists(&self, conn: &Connection) -> Result<bool> {
+        let result = query::get_by_entity(conn, &self.iri)?;
+        Ok(!result.triples.is_empty())
+    }
+
+    /// Find individuals of a specific class that match property constraints
+    ///
+    /// This uses an efficient SQL JOIN query to find all individuals matching all criteria.
+    /// Can be used with one or multiple properties.
+    ///
+    /// Example:
+    /// ```ignore
+    /// // Single property
+    /// let releases = Individual::find_by_class_and_properties(
+    ///     conn,
+    ///     "foundation:SoftwareRelease",
+    ///     &[("foundation:versionNumber", "0.1.0")]
+    /// )?;
+    ///
+    /// // Multiple properties
+    /// let releases = Individual::find_by_class_and_properties(
+    ///     conn,
+    ///     "foundation:SoftwareRelease",
+    ///     &[
+    ///         ("foundation:versionNumber", "0.1.0"),
+    ///         ("foundation:releaseOf", "foundation:FoundationProduct"),
+    ///     ]
+    /// )?;
+    /// ```
+    pub fn find_by_class_and_properties(
+        conn: &Connection,
+        class_iri: &str,
+        properties: &[(&str, &str)],
+    ) -> Result<Vec<String>> {
+        query::find_by_class_and_properties(conn, class_iri, properties)
+            .map_err(|e| OwlError::DatabaseError(e.to_string()))
+    }
+}
+
+/// The IRIs an individual is the same as: those linked to it by owl:sameAs,
+/// in either direction and through each other, itself included
+///
+/// Sorted, so every member of the set names the same canonical IRI: the
+/// first one.
+pub fn same_as(conn: &Connection, iri: &str) -> Result<Vec<String>> {
+    let mut members = BTreeSet::from([iri.to_string()]);
+    let mut pending = vec![iri.to_string()];
+    while let Some(member) = pending.pop() {
+        let forward = query::get_by_entity_predicate(conn, &member, owl::SAME_AS)?.triples.into_iter()
+            .filter_map(|t| t.object.as_iri().map(str::to_string));
+        let backward = query::get_by_predicate_object(conn, owl::SAME_AS, &member)?.triples.into_iter()
+            .map(|t| t.subject);
+        for other in forward.chain(backward).collect::<Vec<_>>() {
+            if members.insert(other.clone()) {
+                pending.push(other);
+            }
+        }
+    }
+    Ok(members.into_iter().collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::owl::ClassType;
+    use crate::eavto::test_helpers::setup_test_db;
+
+    fn input(property: &str, value: serde_json::Value) -> PropertyInput {
+        PropertyInput { property: property.to_string(), value, unit: None, language: None }
+    }
+
+    #[test]
+    fn test_create_validates_values() {
+        let mut conn = setup_test_db();
+        Class::new("foundation:Computer").assert(&mut conn, ClassType::OwlClass, "Computer", "computer", None, "test").unwrap();
+        Property::new("foundation:memorySize").assert(
+            &mut conn, PropertyType::DatatypeProperty, "memory size", None,
+            Some("foundation:Computer"), Some("xsd:integer"), Some("unit:GigaBYTE"), "test"
+        ).unwrap();
+
+        let laptop = Individual::create(&mut conn, "foundation:Computer", "Laptop", "computer", &[
+            input("foundation:memorySize", serde_json::json!("16")),
+        ], "user-edit").unwrap();
+        assert!(laptop.iri.starts_with("foundation:Computer_"));
+        assert_eq!(laptop.label.as_deref(), Some("Laptop"));
+        assert!(laptop.properties.contains(&("foundation:memorySize".to_string(), Object::Integer(16))));
+
+        // Undeclared properties, invalid values and unknown classes write nothing
+        let before = query::get_all(&conn).unwrap().triples.len();
+        assert!(Individual::create(&mut conn, "foundation:Computer", "Desktop", "computer", &[
+            input("foundation:emial", serde_json::json!("a@b.c")),
+        ], "user-edit").is_err());
+        assert!(Individual::create(&mut conn, "foundation:Computer", "Desktop", "computer", &[
+            input("foundation:memorySize", serde_json::json!("lots")),
+        ], "user-edit").is_err());
+        assert!(Individual::create(&mut conn, "foundation:Spaceship", "Rocket", "rocket", &[], "user-edit").is_err());
+        assert_eq!(query::get_all(&conn).unwrap().triples.len(), before);
+    }
+
+    #[test]
+    fn test_set_property_replaces_functional_values() {
+        let mut conn = setup_test_db();
+        Class::new("foundation:Computer").assert(&mut conn, ClassType::OwlClass, "Computer", "computer", None, "test").unwrap();
+        Property::new("foundation:memorySize").assert(
+            &mut conn, PropertyType::DatatypeProperty, "memory size", None,
+            Some("foundation:Computer"), Some("xsd:integer"), Some("unit:GigaBYTE"), "test"
+        ).unwrap();
+        Property::new("foundation:nickname").assert(
+            &mut conn, PropertyType::DatatypeProperty, "nickname", None,
+            Some("foundation:Computer"), None, None, "test"
+        ).unwrap();
+        store::assert_triples(&mut conn, &[
+            Triple::new("foundation:memorySize", rdf::TYPE, Object::Iri("owl:FunctionalProperty".to_string())),
+        ], "test").unwrap();
+        let laptop = Individual::create(&mut conn, "foundation:Computer", "Laptop", "computer", &[
+            input("foundation:memorySize", serde_json::json!(16)),
+            input("foundation:nickname", serde_json::json!("Old")),
+        ], "test").unwrap();
+
+        assert_eq!(laptop.set_property(&mut conn, "foundation:memorySize", "32", None, "user-edit").unwrap(), Object::Integer(32));
+        let sizes = query::get_by_entity_predicate(&conn, &laptop.iri, "foundation:memorySize").unwrap().triples;
+        assert_eq!(sizes.iter().map(|t| t.object.clone()).collect::<Vec<_>>(), vec![Object::Integer(32)]);
+
+        // Other properties gain the value; the datatype types it when no range is declared
+        let date = laptop.set_property(&mut conn, "foundation:nickname", "2024-05-01", Some("xsd:date"), "user-edit").unwrap();
+        assert_eq!(date.datatype(), Some("xsd:date"));
+        assert_eq!(query::get_by_entity_predicate(&conn, &laptop.iri, "foundation:nickname").unwrap().triples.len(), 2);
+
+        // Values that don't parse as their datatype are refused before the write
+        for (value, datatype) in [("noon", "xsd:time"), ("soon", "xsd:date"), ("yesterday", "xsd:dateTime")] {
+            let error = laptop.set_property(&mut conn, "foundation:nickname", value, Some(datatype), "user-edit").unwrap_err();
+            assert!(matches!(error, OwlError::ValidationError(_)), "{}: {}", value, error);
+        }
+        assert!(laptop.set_property(&mut conn, "foundation:nickname", "09:30:00", Some("xsd:time"), "user-edit").is_ok());
+
+        assert!(laptop.set_property(&mut conn, "foundation:memorySize", "32", Some("xsd:string"), "user-edit").is_err());
+        assert!(laptop.set_property(&mut conn, "foundation:emial", "a@b.c", None, "user-edit").is_err());
+    }
+
+    #[test]
+    fn test_retract_with_backlinks() {
+        let mut conn = setup_test_db();
+        store::assert_triples(&mut conn, &[
+            Triple::new("foundation:Laptop", rdf::TYPE, Object::Iri("foundation:Computer".to_string())),
+            Triple::new("foundation:Laptop", rdfs::LABEL, Object::Literal {
+                value: "Laptop".to_string(),
+                datatype: Some("xsd:string".to_string()),
+                language: None,
+            }),
+            Triple::new("foundation:Ana", "foundation:owns", Object::Iri("foundation:Laptop".to_string())),
+        ], "test").unwrap();
+
+        let laptop = Individual::new("foundation:Laptop");
+        let retraction = laptop.retract(&mut conn, true, "user-edit").unwrap();
+        assert_eq!((retraction.triples, retraction.backlinks), (2, 1));
+        assert!(!laptop.exists(&conn).unwrap());
+        assert!(query::get_by_entity(&conn, "foundation:Ana").unwrap().triples.is_empty());
+        assert!(laptop.retract(&mut conn, true, "user-edit").is_err());
+
+        store::assert_triples(&mut conn, &[
+            Triple::new("rdfs:Resource", rdfs::LABEL, Object::Iri("Resource".to_string())),
+        ], "rdf:core").unwrap();
+        assert!(Individual::new("rdfs:Resource").retract(&mut conn, false, "user-edit").is_err());
+    }
+
+    #[test]
+    fn test_get_merges_same_as_individuals() {
+        let mut conn = setup_test_db();
+        let text = |value: &str| Object::Literal { value: value.to_string(), datatype: Some("xsd:string".to_string()), language: None };
+        store::assert_triples(&mut conn, &[
+            Triple::new("foundation:Ana", rdf::TYPE, Object::Iri("foundation:Person".to_string())),
+            Triple::new("foundation:Ana", rdfs::LABEL, text("Ana")),
+            Triple::new("foundation:Ana", "foundation:email", text("ana@example.org")),
+            Triple::new("foundation:Task_1", "foundation:performedBy", Object::Iri("foundation:Ana".to_string())),
+        ], "user-edit").unwrap();
+        store::assert_triples(&mut conn, &[
+            Triple::new("contacts:ana", rdf::TYPE, Object::Iri("foundation:Person".to_string())),
+            Triple::new("contacts:ana", "foundation:email", text("ana@example.org")),
+            Triple::new("contacts:ana", "foundation:phone", text("+55 11 5555-0100")),
+            Triple::new("contacts:ana", owl::SAME_AS, Object::Iri("foundation:Ana".to_string())),
+            Triple::new("mail:ana", owl::SAME_AS, Object::Iri("contacts:ana".to_string())),
+            Triple::new("mail:thread", "foundation:from", Object::Iri("mail:ana".to_string())),
+        ], "sync:contacts").unwrap();
+
+        assert_eq!(same_as(&conn, "foundation:Ana").unwrap(), vec!["contacts:ana", "foundation:Ana", "mail:ana"]);
+
+        // Any alias gives the merged individual, under the canonical IRI
+        let ana = Individual::get(&conn, "mail:ana").unwrap();
+        assert_eq!(ana.iri, "contacts:ana");
+        assert_eq!(ana.same_as, vec!["foundation:Ana", "mail:ana"]);
+        assert_eq!(ana.label.as_deref(), Some("Ana"));
+        assert_eq!(ana.types.len(), 1);
+        let mut properties: Vec<&str> = ana.properties.iter().map(|(p, _)| p.as_str()).collect();
+        properties.sort();
+        assert_eq!(properties, vec!["foundation:email", "foundation:phone", rdf::TYPE]);
+        let mut backlinks: Vec<&str> = ana.backlinks.iter().map(|(s, _, _)| s.as_str()).collect();
+        backlinks.sort();
+        assert_eq!(backlinks, vec!["foundation:Task_1", "mail:thread"]);
+
+        assert_eq!(same_as(&conn, "foundation:Bruno").unwrap(), vec!["foundation:Bruno"]);
+    }
+}
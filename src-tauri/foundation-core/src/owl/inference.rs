@@ -0,0 +1,347 @@
+// ============================================================================
+// OWL Inference - RDFS Entailment
+// ============================================================================
+// Answers queries with the RDFS entailments of the store, computed at query
+// time rather than stored (the store is append-only, so materialized
+// entailments would outlive the triples they came from):
+// - rdfs:subClassOf and rdfs:subPropertyOf are transitive
+// - An instance of a class is an instance of its superclasses (rdfs9)
+// - A statement with a property is a statement with its superproperties (rdfs7)
+// - The subject of a property is an instance of its rdfs:domain (rdfs2), and
+//   an IRI object an instance of its rdfs:range (rdfs3)
+//
+// And two OWL property axioms, so a relation asserted once reads from both
+// ends:
+// - a p b gives b q a when p and q are owl:inverseOf each other (declared on
+//   either property)
+// - a p1 b and b p2 c give a p c when p has owl:propertyChainAxiom (p1 p2);
+//   longer chains are skipped
+//
+// Class expressions (blank nodes) in the hierarchy are skipped.
+// ============================================================================
+
+use rusqlite::Connection;
+use std::collections::{HashMap, HashSet, VecDeque};
+use crate::eavto::{query, Object, Triple};
+use crate::owl::{Result, list, vocabulary::{owl, rdf, rdfs}};
+
+/// A class and all its subclasses, the class first
+pub fn sub_classes(conn: &Connection, class: &str) -> Result<Vec<String>> {
+    descendants(conn, class, rdfs::SUB_CLASS_OF)
+}
+
+/// A class and all its superclasses, nearest first
+pub fn super_classes(conn: &Connection, class: &str) -> Result<Vec<String>> {
+    ancestors(conn, class, rdfs::SUB_CLASS_OF)
+}
+
+/// A property and all its subproperties, the property first
+pub fn sub_properties(conn: &Connection, property: &str) -> Result<Vec<String>> {
+    descendants(conn, property, rdfs::SUB_PROPERTY_OF)
+}
+
+/// A property and all its superproperties, nearest first
+pub fn super_properties(conn: &Connection, property: &str) -> Result<Vec<String>> {
+    ancestors(conn, property, rdfs::SUB_PROPERTY_OF)
+}
+
+/// Asserted and inferred members of a class
+///
+/// Asserted instances of the class and its subclasses come first, then the
+/// entities typed by the domain or range of a property.
+pub fn instances(conn: &Connection, class: &str) -> Result<Vec<String>> {
+    let classes = sub_classes(conn, class)?;
+    let mut seen = HashSet::new();
+    let mut members = Vec::new();
+    let mut add = |iri: String| {
+        if !iri.starts_with("_:") && seen.insert(iri.clone()) {
+            members.push(iri);
+        }
+    };
+
+    for class in &classes {
+        for triple in query::get_by_predicate_object(conn, rdf::TYPE, class)?.triples {
+            add(triple.subject);
+        }
+    }
+    for class in &classes {
+        for property in properties_with(conn, rdfs::DOMAIN, class)? {
+            for triple in query::get_by_predicate(conn, &property)?.triples {
+                add(triple.subject);
+            }
+        }
+        for property in properties_with(conn, rdfs::RANGE, class)? {
+            for triple in query::get_by_predicate(conn, &property)?.triples {
+                if let Some(object) = triple.object.as_iri() {
+                    add(object.to_string());
+                }
+            }
+        }
+    }
+    Ok(members)
+}
+
+/// Asserted and inferred classes of an entity, asserted first
+pub fn types(conn: &Connection, entity: &str) -> Result<Vec<String>> {
+    let mut direct: Vec<String> = query::get_by_entity_predicate(conn, entity, rdf::TYPE)?.triples.iter()
+        .filter_map(|t| t.object.as_iri().map(String::from))
+        .collect();
+
+    let mut used: Vec<String> = query::get_by_entity(conn, entity)?.triples.into_iter().map(|t| t.predicate).collect();
+    used.sort();
+    used.dedup();
+    for property in used {
+        for property in super_properties(conn, &property)? {
+            direct.extend(iri_values(conn, &property, rdfs::DOMAIN)?);
+        }
+    }
+    let mut referencing: Vec<String> = query::get_by_object(conn, entity)?.triples.into_iter().map(|t| t.predicate).collect();
+    referencing.sort();
+    referencing.dedup();
+    for property in referencing {
+        for property in super_properties(conn, &property)? {
+            direct.extend(iri_values(conn, &property, rdfs::RANGE)?);
+        }
+    }
+
+    let mut seen = HashSet::new();
+    let mut types = Vec::new();
+    for class in direct {
+        for class in super_classes(conn, &class)? {
+            if seen.insert(class.clone()) {
+                types.push(class);
+            }
+        }
+    }
+    Ok(types)
+}
+
+/// Whether an entity is (asserted or inferred) an instance of a class
+pub fn is_instance_of(conn: &Connection, entity: &str, class: &str) -> Result<bool> {
+    Ok(types(conn, entity)?.iter().any(|t| t == class))
+}
+
+/// Statements of an entity with a property or any of its subproperties,
+/// asserted first, then those implied by inverses and property chains
+pub fn values(conn: &Connection, entity: &str, property: &str) -> Result<Vec<Triple>> {
+    let properties = sub_properties(conn, property)?;
+    let mut triples = Vec::new();
+    for property in &properties {
+        triples.extend(query::get_by_entity_predicate(conn, entity, property)?.triples);
+    }
+    let mut implied = Vec::new();
+    for property in &properties {
+        for inverse in inverses(conn, property)? {
+            for triple in query::get_by_predicate_object(conn, &inverse, entity)?.triples {
+                implied.push(Triple::new(entity, property, Object::Iri(triple.subject)));
+            }
+        }
+        for (first, second) in chains(conn, property)? {
+            implied.extend(chained(conn, entity, property, &first, &second)?);
+        }
+    }
+    Ok(merge_implied(triples, implied))
+}
+
+/// Statements about an entity implied by inverses and property chains, not
+/// asserted themselves
+pub fn implied(conn: &Connection, entity: &str) -> Result<Vec<Triple>> {
+    let mut implied = Vec::new();
+    let mut inverses_of: HashMap<String, Vec<String>> = HashMap::new();
+    for triple in query::get_by_object(conn, entity)?.triples {
+        if !inverses_of.contains_key(&triple.predicate) {
+            inverses_of.insert(triple.predicate.clone(), inverses(conn, &triple.predicate)?);
+        }
+        for inverse in &inverses_of[&triple.predicate] {
+            implied.push(Triple::new(entity, inverse, Object::Iri(triple.subject.clone())));
+        }
+    }
+
+    for axiom in query::get_by_predicate(conn, owl::PROPERTY_CHAIN_AXIOM)?.triples {
+        if let Some([first, second]) = axiom.object.as_iri().and_then(|head| two_step(conn, head)) {
+            implied.extend(chained(conn, entity, &axiom.subject, &first, &second)?);
+        }
+    }
+
+    let asserted = query::get_by_entity(conn, entity)?.triples;
+    let count = asserted.len();
+    Ok(merge_implied(asserted, implied).split_off(count))
+}
+
+/// Properties declared inverse of a property, on either side
+pub fn inverses(conn: &Connection, property: &str) -> Result<Vec<String>> {
+    let mut inverses = iri_values(conn, property, owl::INVERSE_OF)?;
+    inverses.extend(query::get_by_predicate_object(conn, owl::INVERSE_OF, property)?.triples.into_iter().map(|t| t.subject));
+    inverses.sort();
+    inverses.dedup();
+    Ok(inverses)
+}
+
+/// Two-step property chains of a property: (p1, p2) for each
+/// owl:propertyChainAxiom (p1 p2) it declares
+pub fn chains(conn: &Connection, property: &str) -> Result<Vec<(String, String)>> {
+    Ok(iri_values(conn, property, owl::PROPERTY_CHAIN_AXIOM)?.iter()
+        .filter_map(|head| two_step(conn, head))
+        .map(|[first, second]| (first, second))
+        .collect())
+}
+
+/// The members of a chain list, if it has exactly two
+fn two_step(conn: &Connection, head: &str) -> Option<[String; 2]> {
+    list::read(conn, head).ok()?.try_into().ok()
+}
+
+/// entity `property` c for each entity `first` b, b `second` c
+fn chained(conn: &Connection, entity: &str, property: &str, first: &str, second: &str) -> Result<Vec<Triple>> {
+    let mut triples = Vec::new();
+    for middle in iri_values(conn, entity, first)? {
+        for end in iri_values(conn, &middle, second)? {
+            triples.push(Triple::new(entity, property, Object::Iri(end)));
+        }
+    }
+    Ok(triples)
+}
+
+/// Statements followed by the implied ones they don't already state
+fn merge_implied(mut statements: Vec<Triple>, implied: Vec<Triple>) -> Vec<Triple> {
+    let key = |t: &Triple| (t.subject.clone(), t.predicate.clone(), t.object.as_iri().map(String::from));
+    let mut seen: HashSet<_> = statements.iter().map(key).collect();
+    statements.extend(implied.into_iter().filter(|t| seen.insert(key(t))));
+    statements
+}
+
+/// Properties whose domain or range is a class, with their subproperties
+fn properties_with(conn: &Connection, axis: &str, class: &str) -> Result<Vec<String>> {
+    let mut properties = Vec::new();
+    for triple in query::get_by_predicate_object(conn, axis, class)?.triples {
+        properties.extend(sub_properties(conn, &triple.subject)?);
+    }
+    properties.sort();
+    properties.dedup();
+    Ok(properties)
+}
+
+/// Breadth-first walk down a hierarchy relation (child relation parent)
+fn descendants(conn: &Connection, start: &str, relation: &str) -> Result<Vec<String>> {
+    walk(start, |iri| Ok(query::get_by_predicate_object(conn, relation, iri)?.triples.into_iter()
+        .map(|t| t.subject)
+        .collect()))
+}
+
+/// Breadth-first walk up a hierarchy relation
+fn ancestors(conn: &Connection, start: &str, relation: &str) -> Result<Vec<String>> {
+    walk(start, |iri| iri_values(conn, iri, relation))
+}
+
+fn walk(start: &str, next: impl Fn(&str) -> Result<Vec<String>>) -> Result<Vec<String>> {
+    let mut seen = HashSet::from([start.to_string()]);
+    let mut found = vec![start.to_string()];
+    let mut queue = VecDeque::from([start.to_string()]);
+    while let Some(iri) = queue.pop_front() {
+        for related in next(&iri)? {
+            if !related.starts_with("_:") && seen.insert(related.clone()) {
+                found.push(related.clone());
+                queue.push_back(related);
+            }
+        }
+    }
+    Ok(found)
+}
+
+fn iri_values(conn: &Connection, entity: &str, property: &str) -> Result<Vec<String>> {
+    Ok(query::get_by_entity_predicate(conn, entity, property)?.triples.iter()
+        .filter_map(|t| t.object.as_iri().map(String::from))
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::eavto::{store, test_helpers::setup_test_db};
+
+    fn link(subject: &str, predicate: &str, object: &str) -> Triple {
+        Triple::new(subject, predicate, Object::Iri(object.to_string()))
+    }
+
+    fn setup(conn: &mut Connection) {
+        store::assert_triples(conn, &[
+            link("foundation:Person", rdfs::SUB_CLASS_OF, "foundation:Agent"),
+            link("foundation:Organization", rdfs::SUB_CLASS_OF, "foundation:Agent"),
+            link("foundation:Employee", rdfs::SUB_CLASS_OF, "foundation:Person"),
+            link("foundation:Employee", rdfs::SUB_CLASS_OF, "_:restriction"),
+            link("foundation:worksFor", rdfs::DOMAIN, "foundation:Employee"),
+            link("foundation:worksFor", rdfs::RANGE, "foundation:Organization"),
+            link("foundation:ceoOf", rdfs::SUB_PROPERTY_OF, "foundation:worksFor"),
+            link("foundation:Ana", rdf::TYPE, "foundation:Person"),
+            link("foundation:Bruno", rdf::TYPE, "foundation:Employee"),
+            link("foundation:Carla", "foundation:ceoOf", "foundation:Acme"),
+        ], "test").unwrap();
+    }
+
+    #[test]
+    fn test_hierarchies_are_transitive() {
+        let mut conn = setup_test_db();
+        setup(&mut conn);
+
+        assert_eq!(sub_classes(&conn, "foundation:Agent").unwrap(), vec![
+            "foundation:Agent", "foundation:Person", "foundation:Organization", "foundation:Employee",
+        ]);
+        assert_eq!(super_classes(&conn, "foundation:Employee").unwrap(), vec![
+            "foundation:Employee", "foundation:Person", "foundation:Agent",
+        ]);
+        assert_eq!(super_properties(&conn, "foundation:ceoOf").unwrap(), vec!["foundation:ceoOf", "foundation:worksFor"]);
+        assert_eq!(values(&conn, "foundation:Carla", "foundation:worksFor").unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_instances_include_subclasses_domain_and_range() {
+        let mut conn = setup_test_db();
+        setup(&mut conn);
+
+        let agents = instances(&conn, "foundation:Agent").unwrap();
+        assert_eq!(agents, vec!["foundation:Ana", "foundation:Bruno", "foundation:Acme", "foundation:Carla"]);
+        assert_eq!(instances(&conn, "foundation:Organization").unwrap(), vec!["foundation:Acme"]);
+
+        let carla = types(&conn, "foundation:Carla").unwrap();
+        assert_eq!(carla, vec!["foundation:Employee", "foundation:Person", "foundation:Agent"]);
+        assert!(is_instance_of(&conn, "foundation:Acme", "foundation:Agent").unwrap());
+        assert!(!is_instance_of(&conn, "foundation:Ana", "foundation:Employee").unwrap());
+    }
+
+    #[test]
+    fn test_inverses_and_chains_are_implied() {
+        let mut conn = setup_test_db();
+        store::assert_triples(&mut conn, &[
+            link("foundation:ownedBy", owl::INVERSE_OF, "foundation:owns"),
+            link("foundation:uncleOf", owl::PROPERTY_CHAIN_AXIOM, "_:chain1"),
+            link("_:chain1", rdf::FIRST, "foundation:brotherOf"),
+            link("_:chain1", rdf::REST, "_:chain2"),
+            link("_:chain2", rdf::FIRST, "foundation:parentOf"),
+            link("_:chain2", rdf::REST, rdf::NIL),
+            link("foundation:Ana", "foundation:owns", "foundation:Laptop"),
+            link("foundation:Bruno", "foundation:brotherOf", "foundation:Carla"),
+            link("foundation:Carla", "foundation:parentOf", "foundation:Davi"),
+        ], "test").unwrap();
+
+        assert_eq!(inverses(&conn, "foundation:owns").unwrap(), vec!["foundation:ownedBy"]);
+        let owner = values(&conn, "foundation:Laptop", "foundation:ownedBy").unwrap();
+        assert_eq!(owner.len(), 1);
+        assert_eq!(owner[0].object.as_iri(), Some("foundation:Ana"));
+
+        assert_eq!(chains(&conn, "foundation:uncleOf").unwrap(), vec![
+            ("foundation:brotherOf".to_string(), "foundation:parentOf".to_string()),
+        ]);
+        let nephews = values(&conn, "foundation:Bruno", "foundation:uncleOf").unwrap();
+        assert_eq!(nephews[0].object.as_iri(), Some("foundation:Davi"));
+
+        let laptop: Vec<(String, String)> = implied(&conn, "foundation:Laptop").unwrap().into_iter()
+            .map(|t| (t.predicate, t.object.as_iri().unwrap().to_string()))
+            .collect();
+        assert_eq!(laptop, vec![("foundation:ownedBy".to_string(), "foundation:Ana".to_string())]);
+        assert_eq!(implied(&conn, "foundation:Bruno").unwrap().len(), 1);
+
+        // Stated once, not implied again
+        store::assert_triples(&mut conn, &[link("foundation:Laptop", "foundation:ownedBy", "foundation:Ana")], "test").unwrap();
+        assert!(implied(&conn, "foundation:Laptop").unwrap().is_empty());
+    }
+}
@@ -0,0 +1,179 @@
+// ============================================================================
+// OWL Integrity - Write-Time Checks on User Assertions
+// ============================================================================
+// Catches mistakes where they are made instead of in a report afterwards.
+//
+// Predicate check: the predicate of a triple the user asserts must be a
+// declared property (rdf:Property or owl:*Property), so a typo such as
+// foundation:emial is refused instead of silently starting a new property.
+// How strictly it applies is the predicate policy (metadata value
+// predicate_policy):
+// - lenient: no check (the default)
+// - strict: triples with an undeclared predicate are refused
+// - auto-declare: an undeclared predicate in the user namespace is declared
+//   as a minimal rdf:Property along with the write; others are refused
+//
+// Imports and sync are not checked: they bring their own vocabularies.
+// ============================================================================
+
+use rusqlite::Connection;
+use std::collections::BTreeSet;
+use crate::eavto::{query, store, Object, Triple};
+use crate::owl::{Result, OwlError, PROPERTY_TYPES};
+use crate::owl::vocabulary::{rdf, rdfs};
+
+/// Metadata key of the predicate policy
+pub const PREDICATE_POLICY_KEY: &str = "predicate_policy";
+
+/// Namespace of the user's own vocabulary: the one properties are
+/// auto-declared in
+pub const USER_NAMESPACE: &str = "foundation:";
+
+/// How undeclared predicates are handled
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum PredicatePolicy {
+    #[default]
+    Lenient,
+    Strict,
+    AutoDeclare,
+}
+
+impl PredicatePolicy {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            PredicatePolicy::Lenient => "lenient",
+            PredicatePolicy::Strict => "strict",
+            PredicatePolicy::AutoDeclare => "auto-declare",
+        }
+    }
+
+    pub fn parse(policy: &str) -> Option<Self> {
+        match policy {
+            "lenient" => Some(PredicatePolicy::Lenient),
+            "strict" => Some(PredicatePolicy::Strict),
+            "auto-declare" => Some(PredicatePolicy::AutoDeclare),
+            _ => None,
+        }
+    }
+}
+
+/// The configured predicate policy
+pub fn predicate_policy(conn: &Connection) -> Result<PredicatePolicy> {
+    match query::get_metadata(conn, PREDICATE_POLICY_KEY)? {
+        Some(value) => PredicatePolicy::parse(&value)
+            .ok_or_else(|| OwlError::ValidationError(format!("Invalid {}: {}", PREDICATE_POLICY_KEY, value))),
+        None => Ok(PredicatePolicy::default()),
+    }
+}
+
+/// Set the predicate policy
+pub fn set_predicate_policy(conn: &Connection, policy: PredicatePolicy) -> Result<()> {
+    Ok(store::set_metadata(conn, PREDICATE_POLICY_KEY, Some(policy.as_str()))?)
+}
+
+/// Check the predicates of triples about to be asserted, per the policy
+///
+/// Returns the declarations to assert along with them (auto-declare), or
+/// fails listing the undeclared predicates. A predicate the triples
+/// themselves declare counts as declared.
+pub fn check_predicates(conn: &Connection, triples: &[Triple]) -> Result<Vec<Triple>> {
+    let policy = predicate_policy(conn)?;
+    if policy == PredicatePolicy::Lenient {
+        return Ok(Vec::new());
+    }
+
+    let declared_here: BTreeSet<&str> = triples.iter()
+        .filter(|t| t.predicate == rdf::TYPE && t.object.as_iri().is_some_and(|c| PROPERTY_TYPES.contains(&c)))
+        .map(|t| t.subject.as_str())
+        .collect();
+
+    let mut undeclared = BTreeSet::new();
+    for predicate in triples.iter().map(|t| t.predicate.as_str()).collect::<BTreeSet<_>>() {
+        if !declared_here.contains(predicate) && !is_declared(conn, predicate)? {
+            undeclared.insert(predicate);
+        }
+    }
+    if undeclared.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let refused: Vec<&str> = match policy {
+        PredicatePolicy::AutoDeclare => undeclared.iter().copied().filter(|p| !p.starts_with(USER_NAMESPACE)).collect(),
+        _ => undeclared.iter().copied().collect(),
+    };
+    if !refused.is_empty() {
+        return Err(OwlError::ValidationError(format!(
+            "Undeclared properties: {} (declare them first)", refused.join(", ")
+        )));
+    }
+
+    Ok(undeclared.into_iter().flat_map(declaration).collect())
+}
+
+/// Whether a predicate is declared as a property
+pub fn is_declared(conn: &Connection, predicate: &str) -> Result<bool> {
+    Ok(query::get_by_entity_predicate(conn, predicate, rdf::TYPE)?
+        .triples
+        .iter()
+        .any(|t| t.object.as_iri().is_some_and(|c| PROPERTY_TYPES.contains(&c))))
+}
+
+/// Minimal declaration of a property: its type and a label from its name
+fn declaration(predicate: &str) -> Vec<Triple> {
+    let name = &predicate[USER_NAMESPACE.len()..];
+    vec![
+        Triple::new(predicate, rdf::TYPE, Object::Iri(rdf::PROPERTY.to_string())),
+        Triple::new(predicate, rdfs::LABEL, Object::Literal {
+            value: name.to_string(),
+            datatype: Some("xsd:string".to_string()),
+            language: None,
+        }),
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::eavto::test_helpers::setup_test_db;
+    use crate::owl::vocabulary::owl;
+
+    fn email(subject: &str, predicate: &str) -> Triple {
+        Triple::new(subject, predicate, Object::Literal {
+            value: "ana@example.org".to_string(),
+            datatype: Some("xsd:string".to_string()),
+            language: None,
+        })
+    }
+
+    #[test]
+    fn test_predicate_policies() {
+        let mut conn = setup_test_db();
+        store::assert_triples(&mut conn, &[
+            Triple::new(rdf::TYPE, rdf::TYPE, Object::Iri(rdf::PROPERTY.to_string())),
+            Triple::new("foundation:email", rdf::TYPE, Object::Iri(owl::DATATYPE_PROPERTY.to_string())),
+        ], "test").unwrap();
+        let typo = [email("foundation:Ana", "foundation:emial")];
+
+        assert_eq!(predicate_policy(&conn).unwrap(), PredicatePolicy::Lenient);
+        assert!(check_predicates(&conn, &typo).unwrap().is_empty());
+
+        set_predicate_policy(&conn, PredicatePolicy::Strict).unwrap();
+        assert!(check_predicates(&conn, &[email("foundation:Ana", "foundation:email")]).unwrap().is_empty());
+        let error = check_predicates(&conn, &typo).unwrap_err().to_string();
+        assert!(error.contains("foundation:emial"));
+
+        // Declared in the same batch
+        let mut declared = typo.to_vec();
+        declared.push(Triple::new("foundation:emial", rdf::TYPE, Object::Iri(rdf::PROPERTY.to_string())));
+        assert!(check_predicates(&conn, &declared).unwrap().is_empty());
+
+        set_predicate_policy(&conn, PredicatePolicy::AutoDeclare).unwrap();
+        let declarations = check_predicates(&conn, &typo).unwrap();
+        assert_eq!(declarations.len(), 2);
+        assert_eq!(declarations[0].subject, "foundation:emial");
+        assert!(check_predicates(&conn, &[email("foundation:Ana", "schema:emial")]).is_err());
+
+        store::assert_triples(&mut conn, &declarations, "test").unwrap();
+        assert!(is_declared(&conn, "foundation:emial").unwrap());
+    }
+}
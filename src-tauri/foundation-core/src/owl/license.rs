@@ -0,0 +1,330 @@
+// ============================================================================
+// OWL License - License Tracking for Imported Sources
+// ============================================================================
+// Each imported ontology or dataset (an origin) declares its license as
+// dcterms:license on its owl:Ontology header. Sources without a header get
+// one when a license is declared for them, so every source is described
+// the same way.
+//
+// Exports combine sources, so licenses are classified (CC, GPL, ODbL, ...)
+// and checked for combinations that can't be redistributed together:
+// - No-derivatives sources can't be combined with anything else
+// - Different share-alike licenses each require derivatives to use theirs
+// - Non-commercial terms can't be added to share-alike sources
+// ============================================================================
+
+use rusqlite::Connection;
+use crate::eavto::{query, store, Object, Triple};
+use crate::owl::{Ontology, Result, OwlError, vocabulary::{rdf, rdfs, owl, dcterms}};
+
+/// License of one source in the store
+#[derive(Debug, Clone, PartialEq)]
+pub struct SourceLicense {
+    pub origin: String,
+    pub source: String,
+    pub license: Option<String>,
+}
+
+/// Terms of a recognized license
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LicenseTerms {
+    pub id: &'static str,
+    /// Derivatives must use the same license (family name, e.g. "CC-BY-SA")
+    pub share_alike: Option<&'static str>,
+    pub non_commercial: bool,
+    pub no_derivatives: bool,
+}
+
+impl LicenseTerms {
+    const fn new(id: &'static str) -> Self {
+        Self { id, share_alike: None, non_commercial: false, no_derivatives: false }
+    }
+
+    const fn share_alike(mut self, family: &'static str) -> Self {
+        self.share_alike = Some(family);
+        self
+    }
+
+    const fn non_commercial(mut self) -> Self {
+        self.non_commercial = true;
+        self
+    }
+
+    const fn no_derivatives(mut self) -> Self {
+        self.no_derivatives = true;
+        self
+    }
+}
+
+/// Known licenses, most specific patterns first
+const KNOWN_LICENSES: &[(&[&str], LicenseTerms)] = &[
+    (&["publicdomain/zero", "cc0"], LicenseTerms::new("CC0-1.0")),
+    (&["publicdomain/mark", "pddl"], LicenseTerms::new("PDDL-1.0")),
+    (&["licenses/by-nc-sa", "cc-by-nc-sa"], LicenseTerms::new("CC-BY-NC-SA").share_alike("CC-BY-NC-SA").non_commercial()),
+    (&["licenses/by-nc-nd", "cc-by-nc-nd"], LicenseTerms::new("CC-BY-NC-ND").non_commercial().no_derivatives()),
+    (&["licenses/by-nc", "cc-by-nc"], LicenseTerms::new("CC-BY-NC").non_commercial()),
+    (&["licenses/by-nd", "cc-by-nd"], LicenseTerms::new("CC-BY-ND").no_derivatives()),
+    (&["licenses/by-sa", "cc-by-sa"], LicenseTerms::new("CC-BY-SA").share_alike("CC-BY-SA")),
+    (&["licenses/by/", "licenses/by-", "cc-by", "cc by"], LicenseTerms::new("CC-BY")),
+    (&["odbl", "opendatacommons.org/licenses/odbl"], LicenseTerms::new("ODbL-1.0").share_alike("ODbL")),
+    (&["agpl"], LicenseTerms::new("AGPL-3.0").share_alike("AGPL")),
+    (&["lgpl"], LicenseTerms::new("LGPL").share_alike("LGPL")),
+    (&["gpl", "gnu general public"], LicenseTerms::new("GPL").share_alike("GPL")),
+    (&["apache"], LicenseTerms::new("Apache-2.0")),
+    (&["bsd"], LicenseTerms::new("BSD")),
+    (&["mit"], LicenseTerms::new("MIT")),
+];
+
+/// Recognize a license from its IRI, SPDX identifier or name
+pub fn classify(license: &str) -> Option<LicenseTerms> {
+    let normalized = license.to_lowercase().replace('_', "-");
+    KNOWN_LICENSES.iter()
+        .find(|(patterns, _)| patterns.iter().any(|p| normalized.contains(p)))
+        .map(|(_, terms)| *terms)
+}
+
+/// Short name of a license for display (SPDX-style id when recognized)
+pub fn short_name(license: &str) -> String {
+    classify(license)
+        .map(|terms| terms.id.to_string())
+        .unwrap_or_else(|| license.to_string())
+}
+
+/// Licenses of the sources in the store, optionally limited to one origin
+///
+/// Sources without a declared license are listed with `license: None`.
+pub fn source_licenses(conn: &Connection, origin: Option<&str>) -> Result<Vec<SourceLicense>> {
+    let ontologies = Ontology::list(conn)?;
+    let origins = match origin {
+        Some(name) => vec![name.to_string()],
+        None => query::get_active_origins(conn)?,
+    };
+
+    let mut sources = Vec::new();
+    for origin in origins {
+        let headers: Vec<&Ontology> = ontologies.iter()
+            .filter(|o| o.origin.as_deref() == Some(origin.as_str()))
+            .collect();
+
+        if headers.is_empty() {
+            sources.push(SourceLicense { source: origin.clone(), origin, license: None });
+            continue;
+        }
+        for ontology in headers {
+            sources.push(SourceLicense {
+                origin: origin.clone(),
+                source: ontology.display_title().to_string(),
+                license: ontology.license.clone(),
+            });
+        }
+    }
+
+    Ok(sources)
+}
+
+/// Declare the license of a source, replacing any previous declaration
+///
+/// The license is asserted on the source's owl:Ontology headers; a header
+/// is created for sources that don't have one. Returns the header IRIs.
+pub fn declare(conn: &mut Connection, origin: &str, license: &str) -> Result<Vec<String>> {
+    let origin_id = query::get_origin_id(conn, origin)?
+        .ok_or_else(|| OwlError::NotFound(format!("Origin {} not found", origin)))?;
+
+    let mut headers: Vec<String> = query::get_by_predicate_object(conn, rdf::TYPE, owl::ONTOLOGY)?
+        .triples
+        .into_iter()
+        .filter(|t| t.origin_id == origin_id)
+        .map(|t| t.subject)
+        .collect();
+    headers.sort();
+    headers.dedup();
+
+    let mut additions = Vec::new();
+    let mut retractions = Vec::new();
+
+    if headers.is_empty() {
+        let header = source_iri(origin);
+        additions.push(Triple::new(&header, rdf::TYPE, Object::Iri(owl::ONTOLOGY.to_string())));
+        additions.push(Triple::new(&header, rdfs::LABEL, Object::Literal {
+            value: origin.to_string(),
+            datatype: Some("xsd:string".to_string()),
+            language: None,
+        }));
+        headers.push(header);
+    }
+
+    let license_object = if license.contains("://") {
+        Object::Iri(license.to_string())
+    } else {
+        Object::Literal {
+            value: license.to_string(),
+            datatype: Some("xsd:string".to_string()),
+            language: None,
+        }
+    };
+
+    for header in &headers {
+        retractions.extend(
+            query::get_by_entity(conn, header)?
+                .triples
+                .into_iter()
+                .filter(|t| t.predicate == dcterms::LICENSE || t.predicate == "http://purl.org/dc/terms/license")
+        );
+        additions.push(Triple::new(header, dcterms::LICENSE, license_object.clone()));
+    }
+
+    store::apply_changes(conn, &additions, &retractions, origin)?;
+    Ok(headers)
+}
+
+/// Warnings for sources whose licenses can't be combined in one export
+pub fn compatibility_warnings(sources: &[SourceLicense]) -> Vec<String> {
+    let classified: Vec<(&SourceLicense, Option<LicenseTerms>)> = sources.iter()
+        .filter(|s| s.license.is_some())
+        .map(|s| (s, s.license.as_deref().and_then(classify)))
+        .collect();
+
+    let mut warnings = Vec::new();
+
+    for (source, terms) in &classified {
+        if terms.is_none() {
+            warnings.push(format!(
+                "{}: license \"{}\" is not recognized, check its terms before redistributing",
+                source.source,
+                source.license.as_deref().unwrap_or_default()
+            ));
+        }
+    }
+
+    let describe = |source: &SourceLicense, terms: &LicenseTerms| format!("{} ({})", source.source, terms.id);
+
+    for (i, (a, a_terms)) in classified.iter().enumerate() {
+        for (b, b_terms) in &classified[i + 1..] {
+            let (Some(a_terms), Some(b_terms)) = (a_terms, b_terms) else {
+                continue;
+            };
+            if a.origin == b.origin {
+                continue;
+            }
+
+            let (a_name, b_name) = (describe(a, a_terms), describe(b, b_terms));
+
+            if a_terms.no_derivatives || b_terms.no_derivatives {
+                let (nd, other) = if a_terms.no_derivatives { (&a_name, &b_name) } else { (&b_name, &a_name) };
+                warnings.push(format!("{} does not allow derivatives and can't be combined with {}", nd, other));
+                continue;
+            }
+
+            match (a_terms.share_alike, b_terms.share_alike) {
+                (Some(a_family), Some(b_family)) if a_family != b_family => {
+                    warnings.push(format!(
+                        "{} and {} both require derivatives to use their own license",
+                        a_name, b_name
+                    ));
+                    continue;
+                }
+                (Some(_), None) if b_terms.non_commercial => {
+                    warnings.push(format!("{} adds non-commercial terms that share-alike {} does not allow", b_name, a_name));
+                }
+                (None, Some(_)) if a_terms.non_commercial => {
+                    warnings.push(format!("{} adds non-commercial terms that share-alike {} does not allow", a_name, b_name));
+                }
+                _ => {}
+            }
+        }
+    }
+
+    warnings
+}
+
+/// Header IRI for a source without an owl:Ontology header
+fn source_iri(origin: &str) -> String {
+    let name: String = origin.chars()
+        .map(|c| if c.is_alphanumeric() { c } else { '_' })
+        .collect();
+    format!("foundation:Source_{}", name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::eavto::test_helpers::setup_test_db;
+
+    fn source(origin: &str, license: Option<&str>) -> SourceLicense {
+        SourceLicense {
+            origin: origin.to_string(),
+            source: origin.to_string(),
+            license: license.map(|l| l.to_string()),
+        }
+    }
+
+    #[test]
+    fn test_classify() {
+        assert_eq!(classify("http://creativecommons.org/licenses/by/4.0/").unwrap().id, "CC-BY");
+        assert_eq!(classify("https://creativecommons.org/licenses/by-sa/4.0/").unwrap().id, "CC-BY-SA");
+        assert_eq!(classify("CC-BY-NC-ND-4.0").unwrap().id, "CC-BY-NC-ND");
+        assert_eq!(classify("http://creativecommons.org/publicdomain/zero/1.0/").unwrap().id, "CC0-1.0");
+        assert_eq!(classify("GNU GPL").unwrap().id, "GPL");
+        assert_eq!(classify("LGPL-2.1").unwrap().id, "LGPL");
+        assert!(classify("Proprietary").is_none());
+        assert_eq!(short_name("http://creativecommons.org/licenses/by/4.0/"), "CC-BY");
+    }
+
+    #[test]
+    fn test_permissive_sources_are_compatible() {
+        let sources = [
+            source("import:bfo.owl", Some("http://creativecommons.org/licenses/by/4.0/")),
+            source("import:units.ttl", Some("CC0-1.0")),
+            source("user-edit", None),
+        ];
+        assert!(compatibility_warnings(&sources).is_empty());
+    }
+
+    #[test]
+    fn test_incompatible_combinations() {
+        let nd = [source("a", Some("CC-BY-ND-4.0")), source("b", Some("CC-BY-4.0"))];
+        assert!(compatibility_warnings(&nd)[0].contains("does not allow derivatives"));
+
+        let share_alike = [source("a", Some("CC-BY-SA-4.0")), source("b", Some("GPL-3.0"))];
+        assert!(compatibility_warnings(&share_alike)[0].contains("their own license"));
+
+        let non_commercial = [source("a", Some("CC-BY-SA-4.0")), source("b", Some("CC-BY-NC-4.0"))];
+        assert!(compatibility_warnings(&non_commercial)[0].contains("non-commercial"));
+
+        let unknown = [source("a", Some("Proprietary"))];
+        assert!(compatibility_warnings(&unknown)[0].contains("not recognized"));
+    }
+
+    #[test]
+    fn test_declare_license_creates_header() {
+        let mut conn = setup_test_db();
+        store::assert_triples(&mut conn, &[
+            Triple::new("foundation:Thing1", rdf::TYPE, Object::Iri(owl::CLASS.to_string())),
+        ], "test").unwrap();
+
+        let headers = declare(&mut conn, "test", "http://creativecommons.org/licenses/by/4.0/").unwrap();
+        assert_eq!(headers, vec!["foundation:Source_test"]);
+
+        let sources = source_licenses(&conn, Some("test")).unwrap();
+        assert_eq!(sources.len(), 1);
+        assert_eq!(sources[0].license.as_deref(), Some("http://creativecommons.org/licenses/by/4.0/"));
+
+        // Declaring again replaces the license
+        declare(&mut conn, "test", "CC0-1.0").unwrap();
+        let sources = source_licenses(&conn, None).unwrap();
+        assert_eq!(sources.len(), 1);
+        assert_eq!(sources[0].license.as_deref(), Some("CC0-1.0"));
+    }
+
+    #[test]
+    fn test_declare_license_on_existing_header() {
+        let mut conn = setup_test_db();
+        store::assert_triples(&mut conn, &[
+            Triple::new("http://example.org/onto", rdf::TYPE, Object::Iri(owl::ONTOLOGY.to_string())),
+        ], "test").unwrap();
+
+        let headers = declare(&mut conn, "test", "MIT").unwrap();
+        assert_eq!(headers, vec!["http://example.org/onto"]);
+        assert!(matches!(declare(&mut conn, "missing", "MIT"), Err(OwlError::NotFound(_))));
+    }
+}
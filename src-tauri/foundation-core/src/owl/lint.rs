@@ -0,0 +1,420 @@
+// ============================================================================
+// OWL Lint - Common Problems in User Data
+// ============================================================================
+// Checks the triples users wrote (any origin but the bundled ontologies)
+// for mistakes the store accepts but the ontology says are wrong:
+// - A literal where the property expects an IRI (an object property, or a
+//   class range)
+// - A number stored as a string where the range is numeric
+// - A user class without a label or an icon
+// - A property used on an entity whose asserted types are outside its
+//   domain
+//
+// Each issue comes with a suggestion; when the fix is unambiguous it is also
+// a BulkChange, so it can be previewed and applied through owl::bulk:
+// - A literal that is the exact label of one entity (of the range, if any)
+//   becomes a link to it
+// - A numeric string becomes an integer or a number
+// - A missing label is derived from the class IRI ("foundation:HomeOffice"
+//   gets "Home Office")
+// Icons and domains need the user's judgement, so they have no fix.
+// ============================================================================
+
+use rusqlite::Connection;
+use std::collections::{BTreeMap, BTreeSet};
+use crate::eavto::{query, Object, Triple, XsdType, ONTOLOGY_GRAPH};
+use crate::owl::{bulk::BulkChange, inference, Property, PropertyType, Result, vocabulary::{rdf, rdfs, owl}};
+
+const ICON: &str = "foundation:icon";
+
+/// Kind of problem found
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum LintKind {
+    LiteralForIri,
+    NumberAsString,
+    MissingLabel,
+    MissingIcon,
+    OutsideDomain,
+}
+
+impl LintKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            LintKind::LiteralForIri => "literal-for-iri",
+            LintKind::NumberAsString => "number-as-string",
+            LintKind::MissingLabel => "missing-label",
+            LintKind::MissingIcon => "missing-icon",
+            LintKind::OutsideDomain => "outside-domain",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "literal-for-iri" => Some(LintKind::LiteralForIri),
+            "number-as-string" => Some(LintKind::NumberAsString),
+            "missing-label" => Some(LintKind::MissingLabel),
+            "missing-icon" => Some(LintKind::MissingIcon),
+            "outside-domain" => Some(LintKind::OutsideDomain),
+            _ => None,
+        }
+    }
+}
+
+/// Part of the user data to check
+#[derive(Debug, Clone, PartialEq)]
+pub enum Scope {
+    All,
+    Class(String), // Instances of the class (and its subclasses)
+    Entity(String),
+}
+
+/// A problem in user data, and how to fix it
+#[derive(Debug, Clone)]
+pub struct Issue {
+    pub kind: LintKind,
+    pub entity: String,
+    pub property: Option<String>,
+    pub message: String,
+    pub suggestion: String,
+    pub fix: Option<BulkChange>, // Applicable through owl::bulk::apply
+}
+
+/// Every issue in the scope, sorted by kind and entity
+pub fn run(conn: &Connection, scope: &Scope) -> Result<Vec<Issue>> {
+    let triples = user_triples(conn, scope)?;
+    let mut properties: BTreeMap<String, Property> = BTreeMap::new();
+    let mut issues = Vec::new();
+
+    for triple in &triples {
+        if triple.subject.starts_with("_:") || triple.predicate == rdf::TYPE {
+            continue;
+        }
+        if !properties.contains_key(&triple.predicate) {
+            properties.insert(triple.predicate.clone(), Property::get(conn, &triple.predicate)?);
+        }
+        let property = &properties[&triple.predicate];
+
+        if let Some(issue) = literal_for_iri(conn, property, triple)? {
+            issues.push(issue);
+        }
+        if let Some(issue) = number_as_string(property, triple) {
+            issues.push(issue);
+        }
+    }
+
+    let mut subjects: BTreeMap<&str, BTreeSet<&str>> = BTreeMap::new();
+    for triple in &triples {
+        subjects.entry(triple.subject.as_str()).or_default().insert(triple.predicate.as_str());
+    }
+    for (subject, predicates) in &subjects {
+        if subject.starts_with("_:") {
+            continue;
+        }
+        issues.extend(class_annotations(conn, subject)?);
+        issues.extend(outside_domain(conn, subject, predicates, &properties)?);
+    }
+
+    issues.sort_by(|a, b| (a.kind, &a.entity, &a.property).cmp(&(b.kind, &b.entity, &b.property)));
+    Ok(issues)
+}
+
+/// Current triples written by users, restricted to the scope
+fn user_triples(conn: &Connection, scope: &Scope) -> Result<Vec<Triple>> {
+    let subjects: Option<BTreeSet<String>> = match scope {
+        Scope::All => None,
+        Scope::Class(class) => Some(inference::instances(conn, class)?.into_iter().collect()),
+        Scope::Entity(entity) => Some(BTreeSet::from([entity.clone()])),
+    };
+
+    let mut triples = Vec::new();
+    for name in query::get_active_origins(conn)? {
+        if query::is_core_origin(&name) {
+            continue;
+        }
+        let Some(origin_id) = query::get_origin_id(conn, &name)? else { continue };
+        triples.extend(query::get_by_origin(conn, origin_id)?.triples.into_iter()
+            .filter(|t| t.graph != ONTOLOGY_GRAPH)
+            .filter(|t| subjects.as_ref().is_none_or(|s| s.contains(&t.subject))));
+    }
+    Ok(triples)
+}
+
+/// A literal value of a property whose values are entities
+fn literal_for_iri(conn: &Connection, property: &Property, triple: &Triple) -> Result<Option<Issue>> {
+    let Object::Literal { value, .. } = &triple.object else { return Ok(None) };
+    let class_ranges: Vec<&str> = property.ranges.iter()
+        .map(String::as_str)
+        .filter(|r| XsdType::from_iri(r).is_none() && !matches!(*r, rdfs::LITERAL | rdfs::RESOURCE | owl::THING | rdf::LANG_STRING))
+        .collect();
+    if property.property_type != PropertyType::ObjectProperty && class_ranges.is_empty() {
+        return Ok(None);
+    }
+
+    let mut candidates = Vec::new();
+    for entity in query::find_entities_by_attribute_value(conn, rdfs::LABEL, value)? {
+        let mut in_range = true;
+        for range in &class_ranges {
+            in_range &= inference::is_instance_of(conn, &entity, range)?;
+        }
+        if in_range && entity != triple.subject {
+            candidates.push(entity);
+        }
+    }
+
+    let expected = match class_ranges.is_empty() {
+        true => "an entity".to_string(),
+        false => format!("a {}", class_ranges.join(" and ")),
+    };
+    let (suggestion, fix) = match candidates.as_slice() {
+        [entity] => (
+            format!("Link to {}, labelled \"{}\"", entity, value),
+            Some(replace(triple, Object::Iri(entity.clone()))),
+        ),
+        [] => (format!("Create {} labelled \"{}\" and link to it", expected, value), None),
+        _ => (format!("Link to one of {}, all labelled \"{}\"", candidates.join(", "), value), None),
+    };
+
+    Ok(Some(Issue {
+        kind: LintKind::LiteralForIri,
+        entity: triple.subject.clone(),
+        property: Some(triple.predicate.clone()),
+        message: format!("{} of {} is the text \"{}\", but {} expects {}", triple.predicate, triple.subject, value, triple.predicate, expected),
+        suggestion,
+        fix,
+    }))
+}
+
+/// A string value that is a number, of a property with a numeric range
+fn number_as_string(property: &Property, triple: &Triple) -> Option<Issue> {
+    let Object::Literal { value, datatype, language: None } = &triple.object else { return None };
+    if datatype.as_deref().is_some_and(|d| d != "xsd:string") {
+        return None;
+    }
+    let range = property.ranges.iter()
+        .filter_map(|r| XsdType::from_iri(r))
+        .find(XsdType::is_numeric)?;
+
+    let trimmed = value.trim();
+    let number = match (range.is_integer(), trimmed.parse::<i64>(), trimmed.parse::<f64>()) {
+        (true, Ok(i), _) => Object::Integer(i),
+        (false, _, Ok(n)) if n.is_finite() => Object::Number(n),
+        _ => return None,
+    };
+
+    Some(Issue {
+        kind: LintKind::NumberAsString,
+        entity: triple.subject.clone(),
+        property: Some(triple.predicate.clone()),
+        message: format!("{} of {} is the text \"{}\", but its range is {}", triple.predicate, triple.subject, value, range.as_iri()),
+        suggestion: format!("Store {} as a number", trimmed),
+        fix: Some(replace(triple, number)),
+    })
+}
+
+/// Missing label or icon of a user class
+fn class_annotations(conn: &Connection, subject: &str) -> Result<Vec<Issue>> {
+    let is_class = query::get_by_entity_predicate(conn, subject, rdf::TYPE)?.triples.iter()
+        .any(|t| matches!(t.object.as_iri(), Some(owl::CLASS) | Some(rdfs::CLASS)));
+    if !is_class {
+        return Ok(vec![]);
+    }
+
+    let mut issues = Vec::new();
+    if query::get_by_entity_predicate(conn, subject, rdfs::LABEL)?.triples.is_empty() {
+        let label = label_of(subject);
+        issues.push(Issue {
+            kind: LintKind::MissingLabel,
+            entity: subject.to_string(),
+            property: Some(rdfs::LABEL.to_string()),
+            message: format!("Class {} has no label", subject),
+            suggestion: format!("Label it \"{}\"", label),
+            fix: Some(BulkChange {
+                entity: subject.to_string(),
+                before: vec![],
+                after: vec![Triple::new(subject, rdfs::LABEL, Object::Literal { value: label, datatype: None, language: None })],
+            }),
+        });
+    }
+    if query::get_by_entity_predicate(conn, subject, ICON)?.triples.is_empty() {
+        issues.push(Issue {
+            kind: LintKind::MissingIcon,
+            entity: subject.to_string(),
+            property: Some(ICON.to_string()),
+            message: format!("Class {} has no icon", subject),
+            suggestion: "Pick an icon so its instances are easy to tell apart".to_string(),
+            fix: None,
+        });
+    }
+    Ok(issues)
+}
+
+/// Properties of an entity whose domain none of its asserted types falls in
+///
+/// Untyped entities are left alone: RDFS inference types them by the domain.
+fn outside_domain(
+    conn: &Connection,
+    subject: &str,
+    predicates: &BTreeSet<&str>,
+    properties: &BTreeMap<String, Property>,
+) -> Result<Vec<Issue>> {
+    let asserted: Vec<String> = query::get_by_entity_predicate(conn, subject, rdf::TYPE)?.triples.iter()
+        .filter_map(|t| t.object.as_iri().map(String::from))
+        .collect();
+    if asserted.is_empty() {
+        return Ok(vec![]);
+    }
+    let mut types = BTreeSet::new();
+    for class in &asserted {
+        types.extend(inference::super_classes(conn, class)?);
+    }
+
+    let mut issues = Vec::new();
+    for predicate in predicates {
+        let Some(property) = properties.get(*predicate) else { continue };
+        let domains: Vec<&str> = property.domains.iter()
+            .map(String::as_str)
+            .filter(|d| !d.starts_with("_:") && !matches!(*d, rdfs::RESOURCE | owl::THING))
+            .collect();
+        if domains.is_empty() || domains.iter().all(|d| types.contains(*d)) {
+            continue;
+        }
+        issues.push(Issue {
+            kind: LintKind::OutsideDomain,
+            entity: subject.to_string(),
+            property: Some(predicate.to_string()),
+            message: format!("{} is a {}, but {} applies to a {}", subject, asserted.join(" and a "), predicate, domains.join(" and ")),
+            suggestion: format!("Type {} as a {}, or use another property", subject, domains.join(" and ")),
+            fix: None,
+        });
+    }
+    Ok(issues)
+}
+
+/// Change replacing the object of one triple
+fn replace(triple: &Triple, object: Object) -> BulkChange {
+    BulkChange {
+        entity: triple.subject.clone(),
+        before: vec![triple.clone()],
+        after: vec![Triple::new(&triple.subject, &triple.predicate, object)],
+    }
+}
+
+/// Label derived from an IRI's local name ("foundation:HomeOffice" gives
+/// "Home Office", "ex:home_office" gives "home office")
+fn label_of(iri: &str) -> String {
+    let local = iri.rsplit([':', '/', '#']).next().unwrap_or(iri);
+    let mut label = String::with_capacity(local.len());
+    let mut previous: Option<char> = None;
+    for c in local.chars() {
+        match c {
+            '_' | '-' => label.push(' '),
+            c if c.is_uppercase() && previous.is_some_and(|p| p.is_lowercase() || p.is_ascii_digit()) => {
+                label.push(' ');
+                label.push(c);
+            }
+            c => label.push(c),
+        }
+        previous = Some(c);
+    }
+    label
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::eavto::{store, Annotation};
+    use crate::eavto::test_helpers::setup_test_db;
+    use crate::owl::bulk;
+
+    fn literal(value: &str) -> Object {
+        Object::Literal { value: value.to_string(), datatype: None, language: None }
+    }
+
+    fn ontology(conn: &mut Connection) {
+        store::assert_triples(conn, &[
+            Triple::new("ex:Person", rdf::TYPE, Object::Iri(owl::CLASS.to_string())),
+            Triple::new("ex:Person", rdfs::LABEL, literal("Person")),
+            Triple::new("ex:Person", ICON, literal("person")),
+            Triple::new("ex:Car", rdf::TYPE, Object::Iri(owl::CLASS.to_string())),
+            Triple::new("ex:Car", rdfs::LABEL, literal("Car")),
+            Triple::new("ex:Car", ICON, literal("car")),
+            Triple::new("ex:owner", rdf::TYPE, Object::Iri(owl::OBJECT_PROPERTY.to_string())),
+            Triple::new("ex:owner", rdfs::DOMAIN, Object::Iri("ex:Car".to_string())),
+            Triple::new("ex:owner", rdfs::RANGE, Object::Iri("ex:Person".to_string())),
+            Triple::new("ex:seats", rdf::TYPE, Object::Iri(owl::DATATYPE_PROPERTY.to_string())),
+            Triple::new("ex:seats", rdfs::RANGE, Object::Iri("xsd:integer".to_string())),
+        ], "core").unwrap();
+    }
+
+    #[test]
+    fn test_literal_and_number_fixes() {
+        let mut conn = setup_test_db();
+        ontology(&mut conn);
+        store::assert_triples(&mut conn, &[
+            Triple::new("ex:ada", rdf::TYPE, Object::Iri("ex:Person".to_string())),
+            Triple::new("ex:ada", rdfs::LABEL, literal("Ada")),
+            Triple::new("ex:beetle", rdf::TYPE, Object::Iri("ex:Car".to_string())),
+            Triple::new("ex:beetle", "ex:owner", literal("Ada")),
+            Triple::new("ex:beetle", "ex:seats", literal("4")),
+        ], "user-edit").unwrap();
+
+        let issues = run(&conn, &Scope::All).unwrap();
+        let kinds: Vec<LintKind> = issues.iter().map(|i| i.kind).collect();
+        assert_eq!(kinds, vec![LintKind::LiteralForIri, LintKind::NumberAsString]);
+
+        let fixes: Vec<BulkChange> = issues.into_iter().filter_map(|i| i.fix).collect();
+        bulk::apply(&mut conn, &fixes, "user-edit", &Annotation::default()).unwrap();
+
+        let owner = query::get_by_entity_predicate(&conn, "ex:beetle", "ex:owner").unwrap().triples;
+        assert_eq!(owner[0].object, Object::Iri("ex:ada".to_string()));
+        let seats = query::get_by_entity_predicate(&conn, "ex:beetle", "ex:seats").unwrap().triples;
+        assert_eq!(seats[0].object, Object::Integer(4));
+        assert!(run(&conn, &Scope::All).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_user_class_annotations_and_domain() {
+        let mut conn = setup_test_db();
+        ontology(&mut conn);
+        store::assert_triples(&mut conn, &[
+            Triple::new("ex:HomeOffice", rdf::TYPE, Object::Iri(owl::CLASS.to_string())),
+            Triple::new("ex:bob", rdf::TYPE, Object::Iri("ex:Person".to_string())),
+            Triple::new("ex:bob", "ex:seats", Object::Integer(1)),
+            Triple::new("ex:bob", "ex:owner", Object::Iri("ex:bob".to_string())),
+        ], "user-edit").unwrap();
+
+        let issues = run(&conn, &Scope::All).unwrap();
+        let kinds: Vec<(LintKind, &str)> = issues.iter().map(|i| (i.kind, i.entity.as_str())).collect();
+        assert_eq!(kinds, vec![
+            (LintKind::MissingLabel, "ex:HomeOffice"),
+            (LintKind::MissingIcon, "ex:HomeOffice"),
+            (LintKind::OutsideDomain, "ex:bob"),
+        ]);
+        let label = &issues[0].fix.as_ref().unwrap().after[0];
+        assert_eq!(label.object, literal("Home Office"));
+        assert!(issues[1].fix.is_none());
+
+        // Core classes and entities outside the scope aren't checked
+        assert!(run(&conn, &Scope::Entity("ex:Person".to_string())).unwrap().is_empty());
+        assert_eq!(run(&conn, &Scope::Class("ex:Person".to_string())).unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_ambiguous_literal_has_no_fix() {
+        let mut conn = setup_test_db();
+        ontology(&mut conn);
+        store::assert_triples(&mut conn, &[
+            Triple::new("ex:ada", rdf::TYPE, Object::Iri("ex:Person".to_string())),
+            Triple::new("ex:ada", rdfs::LABEL, literal("Ada")),
+            Triple::new("ex:ada2", rdf::TYPE, Object::Iri("ex:Person".to_string())),
+            Triple::new("ex:ada2", rdfs::LABEL, literal("Ada")),
+            Triple::new("ex:beetle", rdf::TYPE, Object::Iri("ex:Car".to_string())),
+            Triple::new("ex:beetle", "ex:owner", literal("Ada")),
+        ], "user-edit").unwrap();
+
+        let issues = run(&conn, &Scope::Entity("ex:beetle".to_string())).unwrap();
+        assert_eq!(issues.len(), 1);
+        assert!(issues[0].fix.is_none());
+        assert!(issues[0].suggestion.contains("ex:ada2"));
+    }
+}
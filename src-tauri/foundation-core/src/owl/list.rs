@@ -0,0 +1,86 @@
+// ============================================================================
+// OWL List - rdf:List Collections
+// ============================================================================
+// OWL spells ordered arguments as rdf:Lists: the classes of owl:unionOf and
+// owl:intersectionOf, the individuals of owl:oneOf, the properties of an
+// owl:propertyChainAxiom. A list is a chain of blank nodes, each with its
+// item (rdf:first) and the rest of the list (rdf:rest), ending in rdf:nil.
+//
+// Imported ontologies can hold broken lists: a node without rdf:first or
+// rdf:rest, or a rest that loops back. They're errors rather than partial
+// lists, as a union missing members would say something else.
+// ============================================================================
+
+use rusqlite::Connection;
+use std::collections::HashSet;
+use crate::eavto::query;
+use crate::owl::{OwlError, Result, vocabulary::rdf};
+
+/// The items of the rdf:List starting at `head`, in order
+/// IRIs (and blank nodes) as they are, literals by their value
+pub fn read(conn: &Connection, head: &str) -> Result<Vec<String>> {
+    let mut items = Vec::new();
+    let mut visited = HashSet::new();
+    let mut current = head.to_string();
+
+    while current != rdf::NIL {
+        if !visited.insert(current.clone()) {
+            return Err(OwlError::ValidationError(format!("rdf:List at {} loops back to {}", head, current)));
+        }
+
+        let first = query::get_by_entity_predicate(conn, &current, rdf::FIRST)?;
+        let item = first.triples.first()
+            .and_then(|t| t.object.as_iri().map(|s| s.to_string()).or_else(|| t.object.as_literal()))
+            .ok_or_else(|| OwlError::ValidationError(format!("rdf:List node {} has no rdf:first", current)))?;
+        items.push(item);
+
+        let rest = query::get_by_entity_predicate(conn, &current, rdf::REST)?;
+        current = rest.triples.first()
+            .and_then(|t| t.object.as_iri())
+            .map(|s| s.to_string())
+            .ok_or_else(|| OwlError::ValidationError(format!("rdf:List node {} has no rdf:rest", current)))?;
+    }
+
+    Ok(items)
+}
+
+/// The lists an entity has for a predicate (e.g. every
+/// owl:propertyChainAxiom of a property), in no particular order
+pub fn of(conn: &Connection, entity: &str, predicate: &str) -> Result<Vec<Vec<String>>> {
+    query::get_by_entity_predicate(conn, entity, predicate)?.triples.iter()
+        .filter_map(|t| t.object.as_iri())
+        .map(|head| read(conn, head))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::eavto::{store, test_helpers::setup_test_db, Object, Triple};
+    use crate::owl::vocabulary::owl;
+
+    fn node(value: &str) -> Object {
+        if value.starts_with("_:") { Object::Blank(value.to_string()) } else { Object::Iri(value.to_string()) }
+    }
+
+    #[test]
+    fn test_read_lists_and_reject_broken_ones() {
+        let mut conn = setup_test_db();
+        store::assert_triples(&mut conn, &[
+            Triple::new("foundation:uncleOf", owl::PROPERTY_CHAIN_AXIOM, node("_:l1")),
+            Triple::new("_:l1", rdf::FIRST, node("foundation:parentOf")),
+            Triple::new("_:l1", rdf::REST, node("_:l2")),
+            Triple::new("_:l2", rdf::FIRST, node("foundation:brotherOf")),
+            Triple::new("_:l2", rdf::REST, node(rdf::NIL)),
+            Triple::new("_:loop", rdf::FIRST, node("foundation:A")),
+            Triple::new("_:loop", rdf::REST, node("_:loop")),
+            Triple::new("_:open", rdf::FIRST, node("foundation:A")),
+        ], "test").unwrap();
+
+        assert_eq!(read(&conn, "_:l1").unwrap(), ["foundation:parentOf", "foundation:brotherOf"]);
+        assert!(read(&conn, rdf::NIL).unwrap().is_empty());
+        assert_eq!(of(&conn, "foundation:uncleOf", owl::PROPERTY_CHAIN_AXIOM).unwrap().len(), 1);
+        assert!(read(&conn, "_:loop").is_err());
+        assert!(read(&conn, "_:open").is_err());
+    }
+}
@@ -0,0 +1,311 @@
+// ============================================================================
+// OWL Listening - Music and Podcast Listening History
+// ============================================================================
+// Imports streaming history as foundation:Listen events:
+// - Spotify account data (StreamingHistory*.json, music and podcasts) and
+//   extended streaming history (Streaming_History_Audio_*.json)
+// - Last.fm scrobbles, as API pages (recenttracks JSON) or CSV exports
+//   (artist, album, track, date)
+//
+// Each listen links to a foundation:Track, which links to a foundation:Artist.
+// Artists are reused by name, tracks by (artist, title). A listen is
+// identified by when it started, so overlapping exports don't duplicate
+// listens. Histories run to tens of thousands of listens: existing entities
+// are loaded once per import, and all triples go in one transaction.
+// ============================================================================
+
+use rusqlite::Connection;
+use serde_json::Value;
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
+use chrono::NaiveDateTime;
+use crate::eavto::{store, Object, Triple};
+use crate::owl::{finance, Class, Thing, Result, hash, string, OwlError, vocabulary::{rdf, rdfs}};
+
+pub const LISTEN: &str = "foundation:Listen";
+pub const TRACK: &str = "foundation:Track";
+pub const ARTIST: &str = "foundation:Artist";
+pub const LISTENED_TRACK: &str = "foundation:listenedTrack";
+pub const LISTENED_AT: &str = "foundation:listenedAt";
+pub const PLAYED_DURATION: &str = "foundation:playedDuration";
+pub const TRACK_ARTIST: &str = "foundation:artist";
+pub const ALBUM: &str = "foundation:album";
+
+/// Spotify logs skips too; shorter plays aren't listens (Spotify's own
+/// threshold for counting a stream)
+const MIN_PLAYED_MS: i64 = 30_000;
+
+/// One play of a track
+#[derive(Debug, Clone, PartialEq)]
+pub struct ListenEntry {
+    pub started_at: i64, // Unix ms
+    pub artist: String, // Show, for podcast episodes
+    pub track: String, // Episode, for podcast episodes
+    pub album: Option<String>,
+    pub played_ms: Option<i64>,
+}
+
+/// Outcome of an import
+#[derive(Debug, Clone, PartialEq)]
+pub struct ListeningImport {
+    pub imported: usize,
+    pub duplicates: usize, // Already imported (or repeated in the file)
+    pub skipped: usize, // Plays too short to count
+    pub tracks: usize, // Tracks created
+    pub artists: usize, // Artists created
+    pub tx: Option<i64>,
+}
+
+/// Import a Spotify or Last.fm export (JSON, or Last.fm CSV)
+pub fn import_listening_history(conn: &mut Connection, path: &Path) -> Result<ListeningImport> {
+    let text = std::fs::read_to_string(path)
+        .map_err(|e| OwlError::InvalidOperation(format!("Can't read {}: {}", path.display(), e)))?;
+
+    let is_csv = path.extension().and_then(|e| e.to_str()).is_some_and(|e| e.eq_ignore_ascii_case("csv"));
+    let entries = if is_csv { parse_lastfm_csv(&text)? } else { parse_json(&text)? };
+
+    let file_name = path.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default();
+    import_entries(conn, &entries, &format!("import:{}", file_name))
+}
+
+/// Assert entries as listens of tracks by artists, skipping duplicates
+pub fn import_entries(conn: &mut Connection, entries: &[ListenEntry], origin: &str) -> Result<ListeningImport> {
+    let mut report = ListeningImport { imported: 0, duplicates: 0, skipped: 0, tracks: 0, artists: 0, tx: None };
+    let mut listens: HashSet<String> = Class::get_instances(conn, LISTEN)?.into_iter().collect();
+    let mut tracks: HashSet<String> = Class::get_instances(conn, TRACK)?.into_iter().collect();
+    let mut artists = artists_by_label(conn)?;
+    let mut triples = Vec::new();
+
+    for entry in entries {
+        if entry.played_ms.is_some_and(|ms| ms < MIN_PLAYED_MS) {
+            report.skipped += 1;
+            continue;
+        }
+
+        let listen = format!("foundation:Listen_{}", hash(&(entry.started_at / 1000).to_string()));
+        if !listens.insert(listen.clone()) {
+            report.duplicates += 1;
+            continue;
+        }
+
+        let key = entry.artist.trim().to_lowercase();
+        let artist = match artists.get(&key) {
+            Some(artist) => artist.clone(),
+            None => {
+                let artist = format!("foundation:Artist_{}", hash(&key));
+                triples.push(Triple::new(&artist, rdf::TYPE, Object::Iri(ARTIST.to_string())));
+                triples.push(Triple::new(&artist, rdfs::LABEL, string(entry.artist.trim())));
+                artists.insert(key, artist.clone());
+                report.artists += 1;
+                artist
+            }
+        };
+
+        let track = format!("foundation:Track_{}", hash(&format!("{}|{}", artist, entry.track.trim().to_lowercase())));
+        if tracks.insert(track.clone()) {
+            triples.push(Triple::new(&track, rdf::TYPE, Object::Iri(TRACK.to_string())));
+            triples.push(Triple::new(&track, rdfs::LABEL, string(entry.track.trim())));
+            triples.push(Triple::new(&track, TRACK_ARTIST, Object::Iri(artist)));
+            if let Some(album) = entry.album.as_deref().filter(|a| !a.is_empty()) {
+                triples.push(Triple::new(&track, ALBUM, string(album)));
+            }
+            report.tracks += 1;
+        }
+
+        triples.push(Triple::new(&listen, rdf::TYPE, Object::Iri(LISTEN.to_string())));
+        triples.push(Triple::new(&listen, rdfs::LABEL, string(&format!("{} - {}", entry.artist.trim(), entry.track.trim()))));
+        triples.push(Triple::new(&listen, LISTENED_TRACK, Object::Iri(track)));
+        triples.push(Triple::new(&listen, LISTENED_AT, Object::DateTime(entry.started_at)));
+        if let Some(ms) = entry.played_ms {
+            triples.push(Triple::new(&listen, PLAYED_DURATION, Object::Number(ms as f64 / 1000.0)));
+        }
+        report.imported += 1;
+    }
+
+    if !triples.is_empty() {
+        report.tx = Some(store::assert_triples(conn, &triples, origin)?);
+    }
+    Ok(report)
+}
+
+/// Existing artists, by lowercased label
+fn artists_by_label(conn: &Connection) -> Result<HashMap<String, String>> {
+    let mut artists = HashMap::new();
+    for iri in Class::get_instances(conn, ARTIST)? {
+        let label = Thing::get(conn, &iri).label.to_lowercase();
+        artists.entry(label).or_insert(iri);
+    }
+    Ok(artists)
+}
+
+// ============================================================================
+// Export formats
+// ============================================================================
+
+/// Entries of a Spotify or Last.fm JSON export
+pub fn parse_json(text: &str) -> Result<Vec<ListenEntry>> {
+    let json: Value = serde_json::from_str(text)
+        .map_err(|e| OwlError::ValidationError(format!("Not a listening history JSON file: {}", e)))?;
+
+    // Last.fm API pages: one page, or an array of them
+    let pages: Vec<&Value> = match &json {
+        Value::Array(items) => items.iter().filter(|i| i.get("recenttracks").is_some()).collect(),
+        page => page.get("recenttracks").into_iter().map(|_| page).collect(),
+    };
+    if !pages.is_empty() {
+        return Ok(pages.into_iter()
+            .filter_map(|page| page.pointer("/recenttracks/track").and_then(Value::as_array))
+            .flatten()
+            .filter_map(lastfm_scrobble)
+            .collect());
+    }
+
+    let items = json.as_array()
+        .ok_or_else(|| OwlError::ValidationError("Expected a Spotify streaming history array or Last.fm recenttracks".to_string()))?;
+    Ok(items.iter().filter_map(spotify_stream).collect())
+}
+
+/// A Spotify stream; both formats log when it ended
+fn spotify_stream(item: &Value) -> Option<ListenEntry> {
+    let text = |key: &str| item.get(key).and_then(Value::as_str).map(String::from);
+
+    if let Some(ts) = text("ts") {
+        // Extended streaming history
+        let ended = chrono::DateTime::parse_from_rfc3339(&ts).ok()?.timestamp_millis();
+        let played_ms = item.get("ms_played").and_then(Value::as_i64)?;
+        let (artist, track) = match (text("master_metadata_album_artist_name"), text("master_metadata_track_name")) {
+            (Some(artist), Some(track)) => (artist, track),
+            _ => (text("episode_show_name")?, text("episode_name")?),
+        };
+        return Some(ListenEntry {
+            started_at: ended - played_ms,
+            artist,
+            track,
+            album: text("master_metadata_album_album_name"),
+            played_ms: Some(played_ms),
+        });
+    }
+
+    // Account data: "endTime" in UTC, to the minute
+    let ended = NaiveDateTime::parse_from_str(&text("endTime")?, "%Y-%m-%d %H:%M").ok()?.and_utc().timestamp_millis();
+    let played_ms = item.get("msPlayed").and_then(Value::as_i64)?;
+    let artist = text("artistName").or_else(|| text("podcastName"))?;
+    let track = text("trackName").or_else(|| text("episodeName"))?;
+    Some(ListenEntry { started_at: ended - played_ms, artist, track, album: None, played_ms: Some(played_ms) })
+}
+
+/// A Last.fm scrobble; the track playing now has no date yet
+fn lastfm_scrobble(track: &Value) -> Option<ListenEntry> {
+    let uts: i64 = track.pointer("/date/uts").and_then(Value::as_str)?.parse().ok()?;
+    let text = |value: Option<&Value>| value.and_then(Value::as_str).map(String::from);
+    let artist = text(track.pointer("/artist/#text")).or_else(|| text(track.pointer("/artist/name")))?;
+    Some(ListenEntry {
+        started_at: uts * 1000,
+        artist,
+        track: text(track.get("name"))?,
+        album: text(track.pointer("/album/#text")).filter(|a| !a.is_empty()),
+        played_ms: None,
+    })
+}
+
+/// Entries of a Last.fm CSV export: artist, album, track, date
+/// ("14 Mar 2024 08:12", UTC), with or without a header row
+pub fn parse_lastfm_csv(text: &str) -> Result<Vec<ListenEntry>> {
+    let mut entries = Vec::new();
+    for (i, record) in finance::csv_records(text, ',').into_iter().enumerate() {
+        if record.iter().all(|f| f.trim().is_empty()) {
+            continue;
+        }
+        let [artist, album, track, date] = record.as_slice() else {
+            return Err(OwlError::ValidationError(format!("Line {}: expected artist, album, track, date", i + 1)));
+        };
+        let Ok(date) = NaiveDateTime::parse_from_str(date.trim(), "%d %b %Y %H:%M") else {
+            if i == 0 {
+                continue; // Header
+            }
+            return Err(OwlError::ValidationError(format!("Line {}: invalid date: {}", i + 1, date)));
+        };
+        entries.push(ListenEntry {
+            started_at: date.and_utc().timestamp_millis(),
+            artist: artist.clone(),
+            track: track.clone(),
+            album: Some(album.clone()).filter(|a| !a.is_empty()),
+            played_ms: None,
+        });
+    }
+    Ok(entries)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::eavto::{query, test_helpers::setup_test_db};
+
+    #[test]
+    fn test_parse_spotify_formats() {
+        let account = r#"[
+            {"endTime": "2024-03-14 08:15", "artistName": "Miles Davis", "trackName": "So What", "msPlayed": 180000},
+            {"endTime": "2024-03-14 09:00", "podcastName": "A Show", "episodeName": "Episode 1", "msPlayed": 1200000}
+        ]"#;
+        let entries = parse_json(account).unwrap();
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].started_at, 1710404100000 - 180000);
+        assert_eq!((entries[1].artist.as_str(), entries[1].track.as_str()), ("A Show", "Episode 1"));
+
+        let extended = r#"[{"ts": "2024-03-14T08:15:00Z", "ms_played": 60000,
+            "master_metadata_track_name": "So What", "master_metadata_album_artist_name": "Miles Davis",
+            "master_metadata_album_album_name": "Kind of Blue"}]"#;
+        let entries = parse_json(extended).unwrap();
+        assert_eq!(entries[0].album.as_deref(), Some("Kind of Blue"));
+        assert_eq!(entries[0].started_at, 1710404100000 - 60000);
+    }
+
+    #[test]
+    fn test_parse_lastfm_formats() {
+        let page = r##"{"recenttracks": {"track": [
+            {"name": "Now Playing", "artist": {"#text": "Radiohead"}, "@attr": {"nowplaying": "true"}},
+            {"name": "Airbag", "artist": {"#text": "Radiohead"}, "album": {"#text": "OK Computer"}, "date": {"uts": "1710404100"}}
+        ]}}"##;
+        let entries = parse_json(page).unwrap();
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].started_at, 1710404100000);
+
+        let csv = "artist,album,track,date\nRadiohead,OK Computer,Airbag,14 Mar 2024 08:15\n";
+        let entries = parse_lastfm_csv(csv).unwrap();
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].started_at, 1710404100000);
+        assert!(parse_lastfm_csv("Radiohead,Airbag\n").is_err());
+    }
+
+    #[test]
+    fn test_import_links_tracks_and_skips_duplicates() {
+        let mut conn = setup_test_db();
+        let listen = |minute: i64, artist: &str, track: &str, played_ms: Option<i64>| ListenEntry {
+            started_at: minute * 60_000,
+            artist: artist.to_string(),
+            track: track.to_string(),
+            album: None,
+            played_ms,
+        };
+        let entries = vec![
+            listen(0, "Miles Davis", "So What", Some(540_000)),
+            listen(10, "miles davis", "So what", Some(540_000)), // Same track
+            listen(20, "Miles Davis", "Freddie Freeloader", Some(5_000)), // Skipped
+            listen(30, "Radiohead", "Airbag", None),
+        ];
+
+        let report = import_entries(&mut conn, &entries, "test").unwrap();
+        assert_eq!((report.imported, report.skipped, report.tracks, report.artists), (3, 1, 2, 2));
+
+        let mut played: Vec<String> = Class::get_instances(&conn, LISTEN).unwrap().iter()
+            .map(|listen| query::get_by_entity_predicate(&conn, listen, LISTENED_TRACK).unwrap().triples[0].object.as_iri().unwrap().to_string())
+            .collect();
+        played.sort();
+        played.dedup();
+        assert_eq!(played.len(), 2);
+
+        // Overlapping export: only the new listen is added
+        let again = import_entries(&mut conn, &[listen(30, "Radiohead", "Airbag", None), listen(40, "Radiohead", "Airbag", None)], "test").unwrap();
+        assert_eq!((again.imported, again.duplicates, again.tracks, again.artists), (1, 1, 0, 0));
+    }
+}
@@ -0,0 +1,148 @@
+// ============================================================================
+// OWL Locale - Locale-Formatted Literals in Imported Data
+// ============================================================================
+// Spreadsheets and bank exports write numbers and dates the way their
+// country does: "1.234,56" in Brazil or Germany, "28/01/2025" almost
+// everywhere but the US. An import profile names the locale of a source
+// (a BCP 47 tag such as "pt-BR"), and its values are parsed into canonical
+// typed literals instead of failing or being stored as opaque strings.
+//
+// - Numbers: decimal comma or point, grouping separators (".", ",", spaces,
+//   apostrophes) and currency symbols ignored, "(12.50)" is negative
+// - Dates: ISO 8601 is always understood; then the locale's day/month order,
+//   with "/", "." or "-" separators and an optional time
+// ============================================================================
+
+use chrono::{DateTime, NaiveDate, NaiveDateTime};
+use crate::eavto::Object;
+
+/// Languages writing numbers with a decimal comma
+const DECIMAL_COMMA_LANGUAGES: &[&str] = &[
+    "pt", "de", "fr", "es", "it", "nl", "ru", "pl", "tr", "sv", "da", "nb", "no", "fi", "cs", "id", "uk", "ro",
+];
+
+/// Languages writing dates year first (2025/01/28)
+const YEAR_FIRST_LANGUAGES: &[&str] = &["ja", "zh", "ko", "hu"];
+
+/// Times that may follow a date
+const TIME_FORMATS: &[&str] = &["", " %H:%M", " %H:%M:%S"];
+
+/// How numbers and dates are written in a source
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Locale {
+    pub decimal_comma: bool, // "1.234,56"
+    pub date_formats: Vec<String>, // chrono formats, tried in order after ISO 8601
+}
+
+impl Default for Locale {
+    /// ISO dates and decimal points only
+    fn default() -> Self {
+        Self { decimal_comma: false, date_formats: Vec::new() }
+    }
+}
+
+impl Locale {
+    /// The conventions of a BCP 47 tag ("pt-BR", "en_US", "de")
+    pub fn from_tag(tag: &str) -> Option<Self> {
+        let tag = tag.trim().replace('_', "-").to_ascii_lowercase();
+        let mut parts = tag.split('-');
+        let language = parts.next().filter(|l| (2..=3).contains(&l.len()) && l.chars().all(|c| c.is_ascii_alphabetic()))?;
+        let region = parts.next().unwrap_or_default();
+
+        let order = if YEAR_FIRST_LANGUAGES.contains(&language) {
+            ["%Y", "%m", "%d"]
+        } else if language == "en" && matches!(region, "" | "us" | "ph") {
+            ["%m", "%d", "%Y"]
+        } else {
+            ["%d", "%m", "%Y"]
+        };
+        let mut date_formats = Vec::new();
+        for separator in ["/", ".", "-"] {
+            for time in TIME_FORMATS {
+                date_formats.push(format!("{}{}", order.join(separator), time));
+            }
+        }
+
+        Some(Self {
+            decimal_comma: DECIMAL_COMMA_LANGUAGES.contains(&language) && !(language == "de" && region == "ch"),
+            date_formats,
+        })
+    }
+
+    /// A number as written in this locale
+    pub fn parse_number(&self, text: &str) -> Option<f64> {
+        let text = text.trim();
+        let negative = text.starts_with('(') && text.ends_with(')');
+        let number: String = text.chars()
+            .filter(|c| c.is_ascii_digit() || matches!(c, '-' | '+' | '.' | ','))
+            .collect();
+        let number = match self.decimal_comma {
+            true => number.replace('.', "").replace(',', "."),
+            false => number.replace(',', ""),
+        };
+
+        let value: f64 = number.parse().ok()?;
+        Some(if negative { -value.abs() } else { value })
+    }
+
+    /// A date or date-time as written in this locale, as Unix ms (UTC)
+    pub fn parse_datetime(&self, text: &str) -> Option<i64> {
+        let text = text.trim();
+        if let Ok(datetime) = DateTime::parse_from_rfc3339(text) {
+            return Some(datetime.timestamp_millis());
+        }
+
+        let iso = ["%Y-%m-%dT%H:%M:%S", "%Y-%m-%d %H:%M:%S", "%Y-%m-%d"];
+        iso.iter().copied()
+            .chain(self.date_formats.iter().map(String::as_str))
+            .find_map(|format| NaiveDateTime::parse_from_str(text, format).ok()
+                .or_else(|| NaiveDate::parse_from_str(text, format).ok().and_then(|d| d.and_hms_opt(0, 0, 0))))
+            .map(|datetime| datetime.and_utc().timestamp_millis())
+    }
+
+    /// The typed literal for a value of an XSD datatype, or None if the text
+    /// isn't one (other datatypes are kept as they are)
+    pub fn literal(&self, text: &str, datatype: &str) -> Option<Object> {
+        match datatype {
+            "xsd:integer" | "xsd:int" | "xsd:long" | "xsd:nonNegativeInteger" => {
+                let number = self.parse_number(text)?;
+                (number.fract() == 0.0).then_some(Object::Integer(number as i64))
+            }
+            "xsd:decimal" | "xsd:double" | "xsd:float" => self.parse_number(text).map(Object::Number),
+            "xsd:dateTime" | "xsd:date" => self.parse_datetime(text).map(Object::DateTime),
+            _ => Some(Object::Literal {
+                value: text.to_string(),
+                datatype: Some(datatype.to_string()),
+                language: None,
+            }),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn millis(y: i32, m: u32, d: u32) -> i64 {
+        NaiveDate::from_ymd_opt(y, m, d).unwrap().and_hms_opt(0, 0, 0).unwrap().and_utc().timestamp_millis()
+    }
+
+    #[test]
+    fn test_locale_literals() {
+        let brazil = Locale::from_tag("pt-BR").unwrap();
+        assert_eq!(brazil.parse_number("R$ 1.234,56"), Some(1234.56));
+        assert_eq!(brazil.parse_number("(12,50)"), Some(-12.5));
+        assert_eq!(brazil.parse_datetime("28/01/2025"), Some(millis(2025, 1, 28)));
+        assert_eq!(brazil.literal("1.000", "xsd:integer"), Some(Object::Integer(1000)));
+
+        let us = Locale::from_tag("en_US").unwrap();
+        assert_eq!(us.parse_number("1,234.56"), Some(1234.56));
+        assert_eq!(us.parse_datetime("01/28/2025 14:30"), Some(millis(2025, 1, 28) + 52_200_000));
+        assert_eq!(us.parse_datetime("28/01/2025"), None);
+
+        // ISO 8601 whatever the locale
+        assert_eq!(Locale::from_tag("de").unwrap().parse_datetime("2025-01-28"), Some(millis(2025, 1, 28)));
+        assert_eq!(Locale::default().literal("soon", "xsd:date"), None);
+        assert!(Locale::from_tag("not a tag").is_none());
+    }
+}
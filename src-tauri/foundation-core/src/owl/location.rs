@@ -0,0 +1,364 @@
+// ============================================================================
+// OWL Location - Location History and Visits
+// ============================================================================
+// Imports Google location history (Takeout Records.json, Semantic Location
+// History, or the on-device Timeline export) as foundation:Visit entities:
+// - Raw GPS points are clustered into stays: consecutive points within
+//   STAY_RADIUS of each other for at least MIN_STAY
+// - Visits the export already recognized are taken as they are
+// - Each stay is attached to the nearest foundation:Place within
+//   PLACE_RADIUS (see owl::geo), or to a new place
+//
+// The stay interval is the visit's valid time (foundation:validFrom,
+// foundation:validUntil), so `visits` can answer "where was I in March".
+// Visit IRIs are derived from (place, arrival), so re-importing the same
+// history adds nothing.
+// ============================================================================
+
+use rusqlite::Connection;
+use serde_json::Value;
+use std::path::Path;
+use crate::eavto::{query, store, Object, Triple};
+use crate::owl::{geo, Thing, Result, hash, string, OwlError, vocabulary::{rdf, rdfs}};
+
+pub const VISIT: &str = "foundation:Visit";
+pub const VISITED_PLACE: &str = "foundation:visitedPlace";
+pub const VALID_FROM: &str = "foundation:validFrom";
+pub const VALID_UNTIL: &str = "foundation:validUntil";
+
+/// Points farther than this from a stay's center start a new stay (meters)
+const STAY_RADIUS: f64 = 100.0;
+
+/// Shorter stays are passing through, not visits (ms)
+const MIN_STAY: i64 = 10 * 60 * 1000;
+
+/// A stay is at an existing place this close (meters)
+const PLACE_RADIUS: f64 = 100.0;
+
+/// A GPS fix
+#[derive(Debug, Clone, PartialEq)]
+pub struct LocationPoint {
+    pub timestamp: i64, // Unix ms
+    pub latitude: f64,
+    pub longitude: f64,
+}
+
+/// Time spent at one location
+#[derive(Debug, Clone, PartialEq)]
+pub struct Stay {
+    pub start: i64, // Unix ms
+    pub end: i64,
+    pub latitude: f64,
+    pub longitude: f64,
+    pub name: Option<String>, // Place name, when the export has one
+}
+
+/// A visit, as found by `visits`
+#[derive(Debug, Clone, PartialEq)]
+pub struct Visit {
+    pub iri: String,
+    pub place: String,
+    pub place_label: String,
+    pub start: i64,
+    pub end: i64,
+}
+
+/// Outcome of an import
+#[derive(Debug, Clone, PartialEq)]
+pub struct LocationImport {
+    pub points: usize, // Raw GPS points read
+    pub visits: usize, // Visits created
+    pub duplicates: usize, // Visits already imported
+    pub places: usize, // Places created
+    pub tx: Option<i64>,
+}
+
+/// Import a Google location history export
+pub fn import_location_history(conn: &mut Connection, path: &Path) -> Result<LocationImport> {
+    let text = std::fs::read_to_string(path)
+        .map_err(|e| OwlError::InvalidOperation(format!("Can't read {}: {}", path.display(), e)))?;
+    let (points, mut stays) = parse_google(&text)?;
+    stays.extend(cluster(&points));
+
+    let file_name = path.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default();
+    let mut report = import_stays(conn, &stays, &format!("import:{}", file_name))?;
+    report.points = points.len();
+    Ok(report)
+}
+
+/// Visits overlapping a period (Unix ms), in order of arrival
+pub fn visits(conn: &Connection, from: i64, to: i64) -> Result<Vec<Visit>> {
+    let mut stmt = conn.prepare(&format!(
+        "SELECT v.subject, p.object, f.object_datetime, u.object_datetime
+         FROM triples v
+         JOIN triples p ON p.subject = v.subject AND p.predicate = ?2
+         JOIN triples f ON f.subject = v.subject AND f.predicate = ?3
+         JOIN triples u ON u.subject = v.subject AND u.predicate = ?4
+         WHERE v.predicate = ?5 AND v.object = ?1
+           AND f.object_datetime < ?7 AND u.object_datetime > ?6
+           AND {} AND {} AND {} AND {}
+         ORDER BY f.object_datetime",
+        query::visible("v"), query::visible("p"), query::visible("f"), query::visible("u")
+    ))?;
+    let rows = stmt
+        .query_map(
+            rusqlite::params![VISIT, VISITED_PLACE, VALID_FROM, VALID_UNTIL, rdf::TYPE, from, to],
+            |row| Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?, row.get::<_, i64>(2)?, row.get::<_, i64>(3)?)),
+        )?
+        .collect::<std::result::Result<Vec<_>, _>>()?;
+
+    Ok(rows.into_iter()
+        .map(|(iri, place, start, end)| Visit { place_label: Thing::get(conn, &place).label, iri, place, start, end })
+        .collect())
+}
+
+/// Assert stays as visits, attached to nearby or new places
+pub fn import_stays(conn: &mut Connection, stays: &[Stay], origin: &str) -> Result<LocationImport> {
+    let mut report = LocationImport { points: 0, visits: 0, duplicates: 0, places: 0, tx: None };
+    let mut new_places: Vec<geo::Place> = Vec::new();
+    let mut triples = Vec::new();
+
+    for stay in stays {
+        let nearby = geo::near(conn, stay.latitude, stay.longitude, PLACE_RADIUS)?
+            .into_iter()
+            .map(|(place, _)| place)
+            .next()
+            .or_else(|| new_places.iter()
+                .find(|p| geo::distance(p.latitude, p.longitude, stay.latitude, stay.longitude) <= PLACE_RADIUS)
+                .cloned());
+
+        let place = match nearby {
+            Some(place) => place,
+            None => {
+                let place = geo::Place {
+                    iri: format!("foundation:Place_{}", hash(&format!("{:.4},{:.4}", stay.latitude, stay.longitude))),
+                    label: stay.name.clone()
+                        .unwrap_or_else(|| format!("{:.4}, {:.4}", stay.latitude, stay.longitude)),
+                    latitude: stay.latitude,
+                    longitude: stay.longitude,
+                };
+                triples.push(Triple::new(&place.iri, rdf::TYPE, Object::Iri(geo::PLACE.to_string())));
+                triples.push(Triple::new(&place.iri, rdfs::LABEL, string(&place.label)));
+                triples.push(Triple::new(&place.iri, geo::LATITUDE, Object::Number(place.latitude)));
+                triples.push(Triple::new(&place.iri, geo::LONGITUDE, Object::Number(place.longitude)));
+                new_places.push(place.clone());
+                report.places += 1;
+                place
+            }
+        };
+
+        let visit = format!("foundation:Visit_{}", hash(&format!("{}|{}", place.iri, stay.start)));
+        let exists = !query::get_by_entity_predicate(conn, &visit, rdf::TYPE)?.triples.is_empty()
+            || triples.iter().any(|t| t.subject == visit);
+        if exists {
+            report.duplicates += 1;
+            continue;
+        }
+
+        triples.push(Triple::new(&visit, rdf::TYPE, Object::Iri(VISIT.to_string())));
+        triples.push(Triple::new(&visit, rdfs::LABEL, string(&format!("Visit to {}", place.label))));
+        triples.push(Triple::new(&visit, VISITED_PLACE, Object::Iri(place.iri.clone())));
+        triples.push(Triple::new(&visit, VALID_FROM, Object::DateTime(stay.start)));
+        triples.push(Triple::new(&visit, VALID_UNTIL, Object::DateTime(stay.end)));
+        report.visits += 1;
+    }
+
+    if !triples.is_empty() {
+        report.tx = Some(store::assert_triples(conn, &triples, origin)?);
+    }
+    Ok(report)
+}
+
+/// Cluster GPS points into stays (see module docs)
+pub fn cluster(points: &[LocationPoint]) -> Vec<Stay> {
+    let mut points = points.to_vec();
+    points.sort_by_key(|p| p.timestamp);
+
+    let mut stays = Vec::new();
+    let mut current: Vec<LocationPoint> = Vec::new();
+    for point in points {
+        if let Some((latitude, longitude)) = centroid(&current) {
+            if geo::distance(latitude, longitude, point.latitude, point.longitude) > STAY_RADIUS {
+                stays.extend(to_stay(&current));
+                current.clear();
+            }
+        }
+        current.push(point);
+    }
+    stays.extend(to_stay(&current));
+    stays
+}
+
+fn centroid(points: &[LocationPoint]) -> Option<(f64, f64)> {
+    if points.is_empty() {
+        return None;
+    }
+    let n = points.len() as f64;
+    Some((
+        points.iter().map(|p| p.latitude).sum::<f64>() / n,
+        points.iter().map(|p| p.longitude).sum::<f64>() / n,
+    ))
+}
+
+fn to_stay(points: &[LocationPoint]) -> Option<Stay> {
+    let (first, last) = (points.first()?, points.last()?);
+    if last.timestamp - first.timestamp < MIN_STAY {
+        return None;
+    }
+    let (latitude, longitude) = centroid(points)?;
+    Some(Stay { start: first.timestamp, end: last.timestamp, latitude, longitude, name: None })
+}
+
+// ============================================================================
+// Google export formats
+// ============================================================================
+
+/// Raw points and recognized visits of a Google location history export
+pub fn parse_google(text: &str) -> Result<(Vec<LocationPoint>, Vec<Stay>)> {
+    let json: Value = serde_json::from_str(text)
+        .map_err(|e| OwlError::ValidationError(format!("Not a location history JSON file: {}", e)))?;
+    let array = |value: &Value, key: &str| value.get(key).and_then(Value::as_array).cloned().unwrap_or_default();
+
+    let mut points = Vec::new();
+    let mut stays = Vec::new();
+
+    // Records.json
+    for location in array(&json, "locations") {
+        let timestamp = location.get("timestamp").and_then(Value::as_str).and_then(parse_time)
+            .or_else(|| location.get("timestampMs").and_then(Value::as_str).and_then(|ms| ms.parse().ok()));
+        if let (Some(timestamp), Some((latitude, longitude))) = (timestamp, e7(&location)) {
+            points.push(LocationPoint { timestamp, latitude, longitude });
+        }
+    }
+
+    // Semantic Location History
+    for object in array(&json, "timelineObjects") {
+        let Some(visit) = object.get("placeVisit") else { continue };
+        let location = visit.get("location").cloned().unwrap_or_default();
+        let duration = visit.get("duration").cloned().unwrap_or_default();
+        let time = |key: &str| duration.get(key).and_then(Value::as_str).and_then(parse_time)
+            .or_else(|| duration.get(format!("{}Ms", key)).and_then(Value::as_str).and_then(|ms| ms.parse().ok()));
+
+        if let (Some(start), Some(end), Some((latitude, longitude))) = (time("startTimestamp"), time("endTimestamp"), e7(&location)) {
+            let name = location.get("name").and_then(Value::as_str).map(String::from);
+            stays.push(Stay { start, end, latitude, longitude, name });
+        }
+    }
+
+    // On-device Timeline export
+    for segment in array(&json, "semanticSegments") {
+        let time = |key: &str| segment.get(key).and_then(Value::as_str).and_then(parse_time);
+        let visit_location = segment.pointer("/visit/topCandidate/placeLocation/latLng").and_then(Value::as_str).and_then(lat_lng);
+        if let (Some(start), Some(end), Some((latitude, longitude))) = (time("startTime"), time("endTime"), visit_location) {
+            stays.push(Stay { start, end, latitude, longitude, name: None });
+        }
+        for point in array(&segment, "timelinePath") {
+            let timestamp = point.get("time").and_then(Value::as_str).and_then(parse_time);
+            let location = point.get("point").and_then(Value::as_str).and_then(lat_lng);
+            if let (Some(timestamp), Some((latitude, longitude))) = (timestamp, location) {
+                points.push(LocationPoint { timestamp, latitude, longitude });
+            }
+        }
+    }
+
+    Ok((points, stays))
+}
+
+/// Coordinates in E7 integers (degrees * 10^7)
+fn e7(value: &Value) -> Option<(f64, f64)> {
+    let latitude = value.get("latitudeE7")?.as_f64()? / 1e7;
+    let longitude = value.get("longitudeE7")?.as_f64()? / 1e7;
+    Some((latitude, longitude))
+}
+
+/// Coordinates as "-23.5613°, -46.6565°"
+fn lat_lng(value: &str) -> Option<(f64, f64)> {
+    let (latitude, longitude) = value.split_once(',')?;
+    let degrees = |s: &str| s.trim().trim_end_matches('°').parse::<f64>().ok();
+    Some((degrees(latitude)?, degrees(longitude)?))
+}
+
+fn parse_time(value: &str) -> Option<i64> {
+    chrono::DateTime::parse_from_rfc3339(value).ok().map(|dt| dt.timestamp_millis())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::eavto::test_helpers::setup_test_db;
+
+    const MINUTE: i64 = 60_000;
+
+    fn point(minute: i64, latitude: f64, longitude: f64) -> LocationPoint {
+        LocationPoint { timestamp: minute * MINUTE, latitude, longitude }
+    }
+
+    #[test]
+    fn test_cluster_points_into_stays() {
+        let points = vec![
+            // Home for 30 minutes (jittering a few meters)
+            point(0, -23.56130, -46.65650),
+            point(15, -23.56135, -46.65655),
+            point(30, -23.56128, -46.65648),
+            // Driving
+            point(35, -23.5800, -46.6700),
+            point(40, -23.6000, -46.6900),
+            // Office for an hour
+            point(45, -23.62000, -46.70000),
+            point(105, -23.62005, -46.70002),
+        ];
+
+        let stays = cluster(&points);
+        assert_eq!(stays.len(), 2);
+        assert_eq!((stays[0].start, stays[0].end), (0, 30 * MINUTE));
+        assert_eq!((stays[1].start, stays[1].end), (45 * MINUTE, 105 * MINUTE));
+        assert!((stays[1].latitude + 23.62).abs() < 0.001);
+    }
+
+    #[test]
+    fn test_parse_google_formats() {
+        let records = r#"{"locations": [
+            {"latitudeE7": -235613000, "longitudeE7": -466565000, "timestamp": "2024-03-14T12:00:00.000Z"},
+            {"latitudeE7": -235613000, "longitudeE7": -466565000, "timestampMs": "1710417600000"}
+        ]}"#;
+        let (points, stays) = parse_google(records).unwrap();
+        assert_eq!(points.len(), 2);
+        assert_eq!(points[0].timestamp, 1710417600000);
+        assert_eq!(points[0].latitude, -23.5613);
+        assert!(stays.is_empty());
+
+        let timeline = r#"{"semanticSegments": [{
+            "startTime": "2024-03-14T09:00:00.000-03:00",
+            "endTime": "2024-03-14T18:00:00.000-03:00",
+            "visit": {"topCandidate": {"placeLocation": {"latLng": "-23.6200000°, -46.7000000°"}}}
+        }]}"#;
+        let (_, stays) = parse_google(timeline).unwrap();
+        assert_eq!(stays.len(), 1);
+        assert_eq!(stays[0].end - stays[0].start, 9 * 60 * MINUTE);
+        assert_eq!(stays[0].longitude, -46.7);
+
+        assert!(parse_google("not json").is_err());
+    }
+
+    #[test]
+    fn test_import_reuses_places_and_answers_timeline_queries() {
+        let mut conn = setup_test_db();
+        let office = |start: i64, end: i64| Stay {
+            start: start * MINUTE, end: end * MINUTE,
+            latitude: -23.62, longitude: -46.70, name: Some("Office".to_string()),
+        };
+
+        let report = import_stays(&mut conn, &[office(0, 60), office(1440, 1500)], "test").unwrap();
+        assert_eq!((report.visits, report.places), (2, 1));
+
+        // Same history again: nothing new
+        let again = import_stays(&mut conn, &[office(0, 60)], "test").unwrap();
+        assert_eq!((again.visits, again.duplicates, again.tx), (0, 1, None));
+
+        let day_two = visits(&conn, 1000 * MINUTE, 2000 * MINUTE).unwrap();
+        assert_eq!(day_two.len(), 1);
+        assert_eq!(day_two[0].place_label, "Office");
+        assert_eq!(day_two[0].start, 1440 * MINUTE);
+        assert_eq!(visits(&conn, 0, 3000 * MINUTE).unwrap().len(), 2);
+    }
+}
@@ -0,0 +1,503 @@
+// ============================================================================
+// Manchester Syntax
+// ============================================================================
+// Renders class frames in OWL Manchester syntax (as shown by Protégé) and
+// parses simple Manchester class expressions back into ClassExpression values.
+//
+// Supported expressions:
+// - Named classes (prefixed names or <full-iris>)
+// - `and`, `or`, `not`, parentheses, `{a, b}` enumerations
+// - `p some C`, `p only C`, `p value v`
+// - `p min n [C]`, `p max n [C]`, `p exactly n [C]`
+// ============================================================================
+
+use rusqlite::Connection;
+use crate::eavto::Object;
+use crate::owl::{Result, OwlError};
+use crate::owl::expression::{ClassAxioms, ClassExpression, RestrictionConstraint};
+
+/// Export a class frame in Manchester syntax
+pub fn export_class(conn: &Connection, class_iri: &str) -> Result<String> {
+    let axioms = ClassAxioms::get(conn, class_iri)?;
+
+    let mut out = format!("Class: {}\n", render_iri(&axioms.iri));
+
+    let mut annotations = Vec::new();
+    if let Some(label) = &axioms.label {
+        annotations.push(format!("rdfs:label {}", quote(label)));
+    }
+    if let Some(comment) = &axioms.comment {
+        annotations.push(format!("rdfs:comment {}", quote(comment)));
+    }
+    write_section(&mut out, "Annotations", &annotations);
+
+    let render_all = |expressions: &[ClassExpression]| -> Vec<String> {
+        expressions.iter().map(render).collect()
+    };
+    write_section(&mut out, "SubClassOf", &render_all(&axioms.sub_class_of));
+    write_section(&mut out, "EquivalentTo", &render_all(&axioms.equivalent_to));
+    write_section(&mut out, "DisjointWith", &render_all(&axioms.disjoint_with));
+
+    Ok(out)
+}
+
+fn write_section(out: &mut String, name: &str, items: &[String]) {
+    if items.is_empty() {
+        return;
+    }
+    out.push_str(&format!("\n    {}:\n", name));
+    out.push_str(&items.iter()
+        .map(|item| format!("        {}", item))
+        .collect::<Vec<_>>()
+        .join(",\n"));
+    out.push('\n');
+}
+
+/// Render a class expression in Manchester syntax
+pub fn render(expression: &ClassExpression) -> String {
+    match expression {
+        ClassExpression::Named(iri) => render_iri(iri),
+        ClassExpression::Restriction { property, constraint } => {
+            let property = render_iri(property);
+            match constraint {
+                RestrictionConstraint::SomeValuesFrom(filler) => format!("{} some {}", property, render_operand(filler)),
+                RestrictionConstraint::AllValuesFrom(filler) => format!("{} only {}", property, render_operand(filler)),
+                RestrictionConstraint::HasValue(value) => format!("{} value {}", property, render_value(value)),
+                RestrictionConstraint::MinCardinality(n, q) => render_cardinality(&property, "min", *n, q),
+                RestrictionConstraint::MaxCardinality(n, q) => render_cardinality(&property, "max", *n, q),
+                RestrictionConstraint::ExactCardinality(n, q) => render_cardinality(&property, "exactly", *n, q),
+            }
+        }
+        ClassExpression::IntersectionOf(members) => {
+            members.iter().map(render_operand).collect::<Vec<_>>().join(" and ")
+        }
+        ClassExpression::UnionOf(members) => {
+            members.iter().map(render_operand).collect::<Vec<_>>().join(" or ")
+        }
+        ClassExpression::ComplementOf(inner) => format!("not {}", render_operand(inner)),
+        ClassExpression::OneOf(individuals) => {
+            format!("{{{}}}", individuals.iter().map(|i| render_iri(i)).collect::<Vec<_>>().join(", "))
+        }
+    }
+}
+
+/// Render a nested expression, parenthesized unless it is atomic
+fn render_operand(expression: &ClassExpression) -> String {
+    match expression {
+        ClassExpression::Named(_) | ClassExpression::OneOf(_) => render(expression),
+        _ => format!("({})", render(expression)),
+    }
+}
+
+fn render_cardinality(property: &str, keyword: &str, n: u32, qualifier: &Option<Box<ClassExpression>>) -> String {
+    match qualifier {
+        Some(class) => format!("{} {} {} {}", property, keyword, n, render_operand(class)),
+        None => format!("{} {} {}", property, keyword, n),
+    }
+}
+
+fn render_iri(iri: &str) -> String {
+    if iri.contains("://") {
+        format!("<{}>", iri)
+    } else {
+        iri.to_string()
+    }
+}
+
+fn render_value(value: &Object) -> String {
+    match value {
+        Object::Iri(iri) | Object::Blank(iri) => render_iri(iri),
+        Object::Literal { value, datatype: Some(dt), .. } => format!("{}^^{}", quote(value), render_iri(dt)),
+        Object::Literal { value, language: Some(lang), .. } => format!("{}@{}", quote(value), lang),
+        Object::Literal { value, .. } => quote(value),
+        Object::Integer(i) => i.to_string(),
+        Object::Number(n) => format!("{:?}", n),
+        Object::Boolean(b) => b.to_string(),
+        Object::DateTime(ms) => {
+            let formatted = chrono::DateTime::from_timestamp_millis(*ms)
+                .map(|dt| dt.to_rfc3339())
+                .unwrap_or_else(|| ms.to_string());
+            format!("{}^^xsd:dateTime", quote(&formatted))
+        }
+        Object::Triple(quoted) => quoted.term(),
+    }
+}
+
+fn quote(value: &str) -> String {
+    format!("\"{}\"", value.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+// ============================================================================
+// Parser
+// ============================================================================
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Name(String),
+    Literal(Object),
+    LParen,
+    RParen,
+    LBrace,
+    RBrace,
+    Comma,
+}
+
+/// Parse a Manchester class expression
+pub fn parse(input: &str) -> Result<ClassExpression> {
+    let tokens = tokenize(input)?;
+    let mut parser = Parser { tokens, pos: 0 };
+    let expression = parser.union()?;
+
+    if let Some(token) = parser.peek() {
+        return Err(syntax_error(format!("Unexpected {:?} after expression", token)));
+    }
+
+    Ok(expression)
+}
+
+fn syntax_error(msg: String) -> OwlError {
+    OwlError::ValidationError(format!("Manchester syntax: {}", msg))
+}
+
+fn tokenize(input: &str) -> Result<Vec<Token>> {
+    let chars: Vec<char> = input.chars().collect();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+
+    while i < chars.len() {
+        let c = chars[i];
+        match c {
+            c if c.is_whitespace() => i += 1,
+            '(' => { tokens.push(Token::LParen); i += 1; }
+            ')' => { tokens.push(Token::RParen); i += 1; }
+            '{' => { tokens.push(Token::LBrace); i += 1; }
+            '}' => { tokens.push(Token::RBrace); i += 1; }
+            ',' => { tokens.push(Token::Comma); i += 1; }
+            '<' => {
+                let end = chars[i..].iter().position(|&c| c == '>')
+                    .ok_or_else(|| syntax_error("Unterminated <IRI>".to_string()))?;
+                tokens.push(Token::Name(chars[i + 1..i + end].iter().collect()));
+                i += end + 1;
+            }
+            '"' => {
+                let mut value = String::new();
+                i += 1;
+                loop {
+                    match chars.get(i) {
+                        None => return Err(syntax_error("Unterminated string literal".to_string())),
+                        Some('"') => { i += 1; break; }
+                        Some('\\') => {
+                            if let Some(&escaped) = chars.get(i + 1) {
+                                value.push(escaped);
+                            }
+                            i += 2;
+                        }
+                        Some(&c) => { value.push(c); i += 1; }
+                    }
+                }
+
+                let mut datatype = None;
+                let mut language = None;
+                if chars.get(i) == Some(&'^') && chars.get(i + 1) == Some(&'^') {
+                    i += 2;
+                    let start = i;
+                    while i < chars.len() && is_name_char(chars[i]) {
+                        i += 1;
+                    }
+                    datatype = Some(chars[start..i].iter().collect());
+                } else if chars.get(i) == Some(&'@') {
+                    i += 1;
+                    let start = i;
+                    while i < chars.len() && (chars[i].is_alphanumeric() || chars[i] == '-') {
+                        i += 1;
+                    }
+                    language = Some(chars[start..i].iter().collect());
+                }
+
+                tokens.push(Token::Literal(Object::Literal { value, datatype, language }));
+            }
+            c if is_name_char(c) => {
+                let start = i;
+                while i < chars.len() && is_name_char(chars[i]) {
+                    i += 1;
+                }
+                tokens.push(Token::Name(chars[start..i].iter().collect()));
+            }
+            other => return Err(syntax_error(format!("Unexpected character '{}'", other))),
+        }
+    }
+
+    Ok(tokens)
+}
+
+fn is_name_char(c: char) -> bool {
+    !c.is_whitespace() && !matches!(c, '(' | ')' | '{' | '}' | ',' | '"' | '<' | '>')
+}
+
+fn is_keyword(name: &str) -> bool {
+    matches!(name, "and" | "or" | "not" | "some" | "only" | "value" | "min" | "max" | "exactly")
+}
+
+struct Parser {
+    tokens: Vec<Token>,
+    pos: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos)
+    }
+
+    fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.pos).cloned();
+        self.pos += 1;
+        token
+    }
+
+    fn peek_keyword(&self, keyword: &str) -> bool {
+        matches!(self.peek(), Some(Token::Name(name)) if name == keyword)
+    }
+
+    fn expect(&mut self, expected: Token) -> Result<()> {
+        match self.next() {
+            Some(token) if token == expected => Ok(()),
+            other => Err(syntax_error(format!("Expected {:?}, found {:?}", expected, other))),
+        }
+    }
+
+    fn union(&mut self) -> Result<ClassExpression> {
+        let mut members = vec![self.intersection()?];
+        while self.peek_keyword("or") {
+            self.pos += 1;
+            members.push(self.intersection()?);
+        }
+        Ok(if members.len() == 1 { members.remove(0) } else { ClassExpression::UnionOf(members) })
+    }
+
+    fn intersection(&mut self) -> Result<ClassExpression> {
+        let mut members = vec![self.primary()?];
+        while self.peek_keyword("and") {
+            self.pos += 1;
+            members.push(self.primary()?);
+        }
+        Ok(if members.len() == 1 { members.remove(0) } else { ClassExpression::IntersectionOf(members) })
+    }
+
+    fn primary(&mut self) -> Result<ClassExpression> {
+        match self.next() {
+            Some(Token::LParen) => {
+                let inner = self.union()?;
+                self.expect(Token::RParen)?;
+                Ok(inner)
+            }
+            Some(Token::LBrace) => {
+                let mut individuals = Vec::new();
+                loop {
+                    match self.next() {
+                        Some(Token::Name(name)) if !is_keyword(&name) => individuals.push(name),
+                        other => return Err(syntax_error(format!("Expected individual, found {:?}", other))),
+                    }
+                    match self.next() {
+                        Some(Token::Comma) => continue,
+                        Some(Token::RBrace) => break,
+                        other => return Err(syntax_error(format!("Expected ',' or '}}', found {:?}", other))),
+                    }
+                }
+                Ok(ClassExpression::OneOf(individuals))
+            }
+            Some(Token::Name(name)) if name == "not" => {
+                Ok(ClassExpression::ComplementOf(Box::new(self.primary()?)))
+            }
+            Some(Token::Name(name)) if !is_keyword(&name) => self.restriction_tail(name),
+            other => Err(syntax_error(format!("Expected class expression, found {:?}", other))),
+        }
+    }
+
+    /// After a name: either a restriction on that property or a named class
+    fn restriction_tail(&mut self, name: String) -> Result<ClassExpression> {
+        let keyword = match self.peek() {
+            Some(Token::Name(k)) if matches!(k.as_str(), "some" | "only" | "value" | "min" | "max" | "exactly") => k.clone(),
+            _ => return Ok(ClassExpression::Named(name)),
+        };
+        self.pos += 1;
+
+        let constraint = match keyword.as_str() {
+            "some" => RestrictionConstraint::SomeValuesFrom(Box::new(self.primary()?)),
+            "only" => RestrictionConstraint::AllValuesFrom(Box::new(self.primary()?)),
+            "value" => RestrictionConstraint::HasValue(self.value()?),
+            _ => {
+                let n = match self.next() {
+                    Some(Token::Name(n)) => n.parse::<u32>()
+                        .map_err(|_| syntax_error(format!("Invalid cardinality '{}'", n)))?,
+                    other => return Err(syntax_error(format!("Expected cardinality, found {:?}", other))),
+                };
+                let qualifier = if self.starts_operand() {
+                    Some(Box::new(self.primary()?))
+                } else {
+                    None
+                };
+                match keyword.as_str() {
+                    "min" => RestrictionConstraint::MinCardinality(n, qualifier),
+                    "max" => RestrictionConstraint::MaxCardinality(n, qualifier),
+                    _ => RestrictionConstraint::ExactCardinality(n, qualifier),
+                }
+            }
+        };
+
+        Ok(ClassExpression::Restriction { property: name, constraint })
+    }
+
+    fn starts_operand(&self) -> bool {
+        match self.peek() {
+            Some(Token::LParen) | Some(Token::LBrace) => true,
+            Some(Token::Name(name)) => name == "not" || !is_keyword(name),
+            _ => false,
+        }
+    }
+
+    fn value(&mut self) -> Result<Object> {
+        match self.next() {
+            Some(Token::Literal(literal)) => Ok(literal),
+            Some(Token::Name(name)) if name == "true" || name == "false" => Ok(Object::Boolean(name == "true")),
+            Some(Token::Name(name)) => {
+                if let Ok(i) = name.parse::<i64>() {
+                    Ok(Object::Integer(i))
+                } else if let Ok(n) = name.parse::<f64>() {
+                    Ok(Object::Number(n))
+                } else {
+                    Ok(Object::Iri(name))
+                }
+            }
+            other => Err(syntax_error(format!("Expected value, found {:?}", other))),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::eavto::{store, test_helpers::setup_test_db, Triple};
+    use crate::owl::vocabulary::{rdf, rdfs, owl};
+
+    fn named(iri: &str) -> ClassExpression {
+        ClassExpression::Named(iri.to_string())
+    }
+
+    #[test]
+    fn test_parse_named_class() {
+        assert_eq!(parse("foundation:Person").unwrap(), named("foundation:Person"));
+        assert_eq!(
+            parse("<http://example.org/Thing>").unwrap(),
+            named("http://example.org/Thing")
+        );
+    }
+
+    #[test]
+    fn test_parse_some_restriction() {
+        let expression = parse("foundation:hasProcessor some foundation:Processor").unwrap();
+        assert_eq!(expression, ClassExpression::Restriction {
+            property: "foundation:hasProcessor".to_string(),
+            constraint: RestrictionConstraint::SomeValuesFrom(Box::new(named("foundation:Processor"))),
+        });
+    }
+
+    #[test]
+    fn test_parse_precedence() {
+        // `and` binds tighter than `or`
+        let expression = parse("foundation:A or foundation:B and not foundation:C").unwrap();
+        assert_eq!(expression, ClassExpression::UnionOf(vec![
+            named("foundation:A"),
+            ClassExpression::IntersectionOf(vec![
+                named("foundation:B"),
+                ClassExpression::ComplementOf(Box::new(named("foundation:C"))),
+            ]),
+        ]));
+    }
+
+    #[test]
+    fn test_parse_qualified_cardinality() {
+        let expression = parse("foundation:hasPart min 2 (foundation:Wheel or foundation:Track)").unwrap();
+        match expression {
+            ClassExpression::Restriction { constraint: RestrictionConstraint::MinCardinality(2, Some(q)), .. } => {
+                assert!(matches!(*q, ClassExpression::UnionOf(_)));
+            }
+            other => panic!("Unexpected expression: {:?}", other),
+        }
+
+        let unqualified = parse("foundation:hasOwner max 1").unwrap();
+        assert!(matches!(
+            unqualified,
+            ClassExpression::Restriction { constraint: RestrictionConstraint::MaxCardinality(1, None), .. }
+        ));
+    }
+
+    #[test]
+    fn test_parse_value_and_one_of() {
+        let expression = parse("foundation:status value \"active\"@en").unwrap();
+        assert_eq!(expression, ClassExpression::Restriction {
+            property: "foundation:status".to_string(),
+            constraint: RestrictionConstraint::HasValue(Object::Literal {
+                value: "active".to_string(),
+                datatype: None,
+                language: Some("en".to_string()),
+            }),
+        });
+
+        let one_of = parse("{foundation:Red, foundation:Green}").unwrap();
+        assert_eq!(one_of, ClassExpression::OneOf(vec![
+            "foundation:Red".to_string(),
+            "foundation:Green".to_string(),
+        ]));
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert!(parse("").is_err());
+        assert!(parse("foundation:p some").is_err());
+        assert!(parse("(foundation:A").is_err());
+        assert!(parse("foundation:p min many").is_err());
+        assert!(parse("foundation:A foundation:B").is_err());
+    }
+
+    #[test]
+    fn test_render_parse_round_trip() {
+        let inputs = [
+            "foundation:hasProcessor some foundation:Processor",
+            "foundation:A and (foundation:p only (foundation:B or foundation:C))",
+            "not (foundation:p exactly 1 foundation:B)",
+            "foundation:p value 42",
+            "{foundation:Red, foundation:Green}",
+        ];
+
+        for input in inputs {
+            let expression = parse(input).unwrap();
+            assert_eq!(render(&expression), input);
+            assert_eq!(parse(&render(&expression)).unwrap(), expression);
+        }
+    }
+
+    #[test]
+    fn test_export_class_frame() {
+        let mut conn = setup_test_db();
+        let restriction = parse("foundation:hasProcessor some foundation:Processor").unwrap();
+        let (node, mut triples) = restriction.to_triples("foundation:Computer");
+
+        triples.push(Triple::new("foundation:Computer", rdf::TYPE, Object::Iri(owl::CLASS.to_string())));
+        triples.push(Triple::new("foundation:Computer", rdfs::LABEL, Object::Literal {
+            value: "Computer".to_string(),
+            datatype: None,
+            language: None,
+        }));
+        triples.push(Triple::new("foundation:Computer", rdfs::SUB_CLASS_OF, Object::Iri("foundation:Device".to_string())));
+        triples.push(Triple::new("foundation:Computer", rdfs::SUB_CLASS_OF, Object::Blank(node)));
+        store::assert_triples(&mut conn, &triples, "test").unwrap();
+
+        let frame = export_class(&conn, "foundation:Computer").unwrap();
+        assert!(frame.starts_with("Class: foundation:Computer\n"));
+        assert!(frame.contains("rdfs:label \"Computer\""));
+        assert!(frame.contains("SubClassOf:"));
+        assert!(frame.contains("foundation:Device"));
+        assert!(frame.contains("foundation:hasProcessor some foundation:Processor"));
+        assert!(!frame.contains("EquivalentTo:"));
+    }
+}
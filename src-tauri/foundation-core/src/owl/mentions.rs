@@ -0,0 +1,229 @@
+// ============================================================================
+// OWL Mentions - Entity Linking in Literal Text
+// ============================================================================
+// Scans the text of user entities (notes, descriptions, comments) for the
+// labels of other entities and links them with foundation:mentions, so free
+// text and the graph stay connected.
+//
+// Links are either proposed for review or created under their own origin,
+// which records that they were inferred rather than entered. A link the user
+// retracted is never proposed again.
+//
+// Linking is incremental: only entities written since the last run are
+// scanned, both as text (new notes) and as targets (new entities that old
+// notes already mention).
+// ============================================================================
+
+use rusqlite::Connection;
+use std::collections::{BTreeMap, HashSet};
+use crate::eavto::{query, store, Object, Triple};
+use crate::owl::{Result, suggestions, text, vocabulary::{rdf, rdfs}};
+
+/// Link property
+pub const MENTIONS: &str = "foundation:mentions";
+
+/// Origin of links created by entity linking
+pub const MENTIONS_ORIGIN: &str = "auto:mentions";
+
+/// Metadata key holding the last transaction scanned for mentions
+const LINKED_TX_KEY: &str = "mentions_linked_tx";
+
+/// Shorter labels match too many words by accident
+const MIN_LABEL_LENGTH: usize = 3;
+
+/// Properties naming an entity, matched in text
+const NAME_PROPERTIES: &[&str] = &[rdfs::LABEL, "foundation:name"];
+
+/// String properties that aren't text
+const NON_TEXT_PROPERTIES: &[&str] = &["foundation:identifier", "foundation:icon"];
+
+/// An entity named in another entity's text
+#[derive(Debug, Clone, PartialEq)]
+pub struct Mention {
+    pub source: String,
+    pub target: String,
+    pub property: String, // Property holding the text
+    pub label: String, // Label of the target found in the text
+}
+
+impl Mention {
+    pub fn to_triple(&self) -> Triple {
+        Triple::new(&self.source, MENTIONS, Object::Iri(self.target.clone()))
+    }
+}
+
+/// All unlinked mentions in user data
+pub fn propose(conn: &Connection) -> Result<Vec<Mention>> {
+    find(conn, None)
+}
+
+/// Link mentions under the mentions origin; returns the transaction ID
+pub fn link(conn: &mut Connection, mentions: &[Mention]) -> Result<Option<i64>> {
+    if mentions.is_empty() {
+        return Ok(None);
+    }
+
+    let triples: Vec<Triple> = mentions.iter().map(Mention::to_triple).collect();
+    Ok(Some(store::assert_triples(conn, &triples, MENTIONS_ORIGIN)?))
+}
+
+/// Link the mentions involving entities written since the last run
+pub fn link_new(conn: &mut Connection) -> Result<Vec<Mention>> {
+    let since: i64 = query::get_metadata(conn, LINKED_TX_KEY)?
+        .and_then(|tx| tx.parse().ok())
+        .unwrap_or(0);
+    let changed: HashSet<String> = query::get_changed_subjects(conn, since)?.into_iter().collect();
+
+    let mentions = find(conn, Some(&changed))?;
+    link(conn, &mentions)?;
+
+    let latest = query::get_latest_tx(conn)?;
+    store::set_metadata(conn, LINKED_TX_KEY, Some(&latest.to_string()))?;
+    Ok(mentions)
+}
+
+/// Unlinked mentions whose source or target is in scope (all if None)
+fn find(conn: &Connection, scope: Option<&HashSet<String>>) -> Result<Vec<Mention>> {
+    let triples = suggestions::user_data(conn)?;
+    let in_scope = |entity: &str| scope.is_none_or(|s| s.contains(entity));
+
+    // Folded label -> entities, longest labels first so they win overlaps
+    let mut labels: BTreeMap<String, Vec<&str>> = BTreeMap::new();
+    for triple in triples.iter().filter(|t| NAME_PROPERTIES.contains(&t.predicate.as_str())) {
+        if let Some(label) = triple.object.as_literal() {
+            let label = text::fold(&label);
+            if label.chars().count() >= MIN_LABEL_LENGTH {
+                labels.entry(label).or_default().push(triple.subject.as_str());
+            }
+        }
+    }
+    let mut labels: Vec<(String, Vec<&str>)> = labels.into_iter().collect();
+    labels.sort_by(|a, b| b.0.len().cmp(&a.0.len()).then_with(|| a.0.cmp(&b.0)));
+
+    let skip = existing_links(conn)?;
+    let mut seen = HashSet::new();
+    let mut mentions = Vec::new();
+
+    for triple in &triples {
+        if NAME_PROPERTIES.contains(&triple.predicate.as_str())
+            || NON_TEXT_PROPERTIES.contains(&triple.predicate.as_str())
+            || triple.predicate == rdf::TYPE
+        {
+            continue;
+        }
+        let text = match &triple.object {
+            Object::Literal { value, datatype, .. }
+                if datatype.as_deref().is_none_or(|d| d == "xsd:string" || d == rdf::LANG_STRING) => text::fold(value),
+            _ => continue,
+        };
+
+        for (label, targets) in &labels {
+            if !contains_word(&text, label) {
+                continue;
+            }
+            for target in targets {
+                let pair = (triple.subject.clone(), target.to_string());
+                if *target == triple.subject
+                    || !(in_scope(&triple.subject) || in_scope(target))
+                    || skip.contains(&pair)
+                    || !seen.insert(pair)
+                {
+                    continue;
+                }
+                mentions.push(Mention {
+                    source: triple.subject.clone(),
+                    target: target.to_string(),
+                    property: triple.predicate.clone(),
+                    label: label.clone(),
+                });
+            }
+        }
+    }
+    Ok(mentions)
+}
+
+/// (source, target) pairs linked now or in the past (retracted links were rejected)
+fn existing_links(conn: &Connection) -> Result<HashSet<(String, String)>> {
+    let mut stmt = conn.prepare("SELECT subject, object FROM triples WHERE predicate = ?")?;
+    let links = stmt
+        .query_map([MENTIONS], |row| Ok((row.get(0)?, row.get(1)?)))?
+        .collect::<std::result::Result<HashSet<_>, _>>()?;
+    Ok(links)
+}
+
+/// Whether text contains the word (or phrase) at word boundaries
+fn contains_word(text: &str, word: &str) -> bool {
+    text.match_indices(word).any(|(start, _)| {
+        let before = text[..start].chars().next_back();
+        let after = text[start + word.len()..].chars().next();
+        !before.is_some_and(char::is_alphanumeric) && !after.is_some_and(char::is_alphanumeric)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::eavto::test_helpers::setup_test_db;
+
+    fn literal(subject: &str, predicate: &str, value: &str) -> Triple {
+        Triple::new(subject, predicate, Object::Literal {
+            value: value.to_string(),
+            datatype: Some("xsd:string".to_string()),
+            language: None,
+        })
+    }
+
+    #[test]
+    fn test_contains_word() {
+        assert!(contains_word("met with acme corp today", "acme corp"));
+        assert!(contains_word("acme.", "acme"));
+        assert!(!contains_word("acmes are great", "acme"));
+        assert!(!contains_word("joanna", "ann"));
+    }
+
+    #[test]
+    fn test_propose_and_link() {
+        let mut conn = setup_test_db();
+        store::assert_triples(&mut conn, &[
+            literal("foundation:Acme", rdfs::LABEL, "Acme Corp"),
+            literal("foundation:Note1", rdfs::COMMENT, "Call with ACME Corp about the renewal"),
+            literal("foundation:SaoPaulo", rdfs::LABEL, "São Paulo"),
+            literal("foundation:Note3", rdfs::COMMENT, "Flight to Sao Paulo"),
+            literal("foundation:Note2", rdfs::COMMENT, "Nothing relevant"),
+        ], "test").unwrap();
+
+        let mentions = propose(&conn).unwrap();
+        assert_eq!(mentions.len(), 2);
+        assert_eq!((mentions[0].source.as_str(), mentions[0].target.as_str()), ("foundation:Note1", "foundation:Acme"));
+        // Diacritics don't matter
+        assert_eq!((mentions[1].source.as_str(), mentions[1].target.as_str()), ("foundation:Note3", "foundation:SaoPaulo"));
+
+        assert!(link(&mut conn, &mentions).unwrap().is_some());
+        assert!(propose(&conn).unwrap().is_empty());
+
+        // A rejected link isn't proposed again
+        store::retract_triples(&mut conn, &[mentions[0].to_triple(), mentions[1].to_triple()], MENTIONS_ORIGIN).unwrap();
+        assert!(propose(&conn).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_link_new_is_incremental() {
+        let mut conn = setup_test_db();
+        store::assert_triples(&mut conn, &[
+            literal("foundation:Note1", rdfs::COMMENT, "Lunch with Maria"),
+        ], "test").unwrap();
+        assert!(link_new(&mut conn).unwrap().is_empty());
+
+        // A new entity is found in old text
+        store::assert_triples(&mut conn, &[literal("foundation:Maria", rdfs::LABEL, "Maria")], "test").unwrap();
+        let linked = link_new(&mut conn).unwrap();
+        assert_eq!(linked.len(), 1);
+        assert_eq!(linked[0].target, "foundation:Maria");
+
+        let links = query::get_by_entity_predicate(&conn, "foundation:Note1", MENTIONS).unwrap().triples;
+        assert_eq!(links.len(), 1);
+        assert_eq!(query::get_origin_name(&conn, links[0].origin_id).unwrap().as_deref(), Some(MENTIONS_ORIGIN));
+
+        assert!(link_new(&mut conn).unwrap().is_empty());
+    }
+}
@@ -0,0 +1,364 @@
+// ============================================================================
+// OWL Messaging - Chat Export Import
+// ============================================================================
+// Imports chat exports as foundation:Conversation entities, with the number
+// of messages each participant sent per day (foundation:MessageActivity):
+// - WhatsApp "Export chat" text files (Android and iOS line formats)
+// - Telegram Desktop JSON exports (one chat, or all chats)
+//
+// Senders are resolved to foundation:Person entities through owl::contacts,
+// so daily message counts weigh the links of the person network. Message
+// texts (foundation:Message) are only asserted when `bodies` is set.
+//
+// Like web history, re-importing a longer export only raises counts.
+// ============================================================================
+
+use rusqlite::Connection;
+use serde_json::Value;
+use std::collections::{BTreeMap, HashSet};
+use std::path::Path;
+use chrono::NaiveDate;
+use crate::eavto::{query, store, Object, Triple};
+use crate::owl::{contacts::Contacts, Class, Result, hash, string, OwlError, vocabulary::{rdf, rdfs}};
+
+pub const CONVERSATION: &str = "foundation:Conversation";
+pub const MESSAGE_ACTIVITY: &str = "foundation:MessageActivity";
+pub const MESSAGE: &str = "foundation:Message";
+pub const PARTICIPANT: &str = "foundation:participant";
+pub const MESSAGING_SERVICE: &str = "foundation:messagingService";
+pub const CONVERSATION_PROPERTY: &str = "foundation:conversation";
+pub const SENDER: &str = "foundation:sender";
+pub const ACTIVITY_DATE: &str = "foundation:activityDate";
+pub const MESSAGE_COUNT: &str = "foundation:messageCount";
+pub const SENT_AT: &str = "foundation:sentAt";
+pub const TEXT: &str = "foundation:text";
+
+const DAY: i64 = 86_400_000;
+
+/// A message of an export
+#[derive(Debug, Clone, PartialEq)]
+pub struct ChatMessage {
+    pub sender: String, // Name or phone number, as the export shows it
+    pub sent_at: i64, // Unix ms
+    pub text: String,
+}
+
+/// A conversation of an export, with its messages
+#[derive(Debug, Clone, PartialEq)]
+pub struct Chat {
+    pub service: String, // "WhatsApp" | "Telegram"
+    pub id: String, // Identifies the chat within the service
+    pub name: String,
+    pub messages: Vec<ChatMessage>,
+}
+
+/// Outcome of an import
+#[derive(Debug, Clone, PartialEq)]
+pub struct MessagingImport {
+    pub messages: usize, // Messages read
+    pub conversations: usize, // Conversations created
+    pub activities: usize, // Person-days created
+    pub updated: usize, // Person-days with a higher count
+    pub persons: usize, // Persons created
+    pub bodies: usize, // Messages created (with `bodies`)
+    pub tx: Option<i64>,
+}
+
+/// Import a chat export: WhatsApp (.txt) or Telegram (.json)
+pub fn import_chat_export(conn: &mut Connection, path: &Path, bodies: bool) -> Result<MessagingImport> {
+    let text = std::fs::read_to_string(path)
+        .map_err(|e| OwlError::InvalidOperation(format!("Can't read {}: {}", path.display(), e)))?;
+
+    let is_json = path.extension().and_then(|e| e.to_str()).is_some_and(|e| e.eq_ignore_ascii_case("json"));
+    let chats = if is_json {
+        parse_telegram(&text)?
+    } else {
+        // "WhatsApp Chat with Maria.txt" (the name is the other participant or the group)
+        let stem = path.file_stem().map(|s| s.to_string_lossy().to_string()).unwrap_or_default();
+        let name = stem.strip_prefix("WhatsApp Chat with ").or_else(|| stem.strip_prefix("WhatsApp Chat - ")).unwrap_or(&stem);
+        vec![parse_whatsapp(&text, name)?]
+    };
+
+    let file_name = path.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default();
+    import_chats(conn, &chats, bodies, &format!("import:{}", file_name))
+}
+
+/// Assert conversations and their daily message counts per sender
+pub fn import_chats(conn: &mut Connection, chats: &[Chat], bodies: bool, origin: &str) -> Result<MessagingImport> {
+    let mut report = MessagingImport {
+        messages: 0, conversations: 0, activities: 0, updated: 0, persons: 0, bodies: 0, tx: None,
+    };
+    let mut contacts = Contacts::load(conn)?;
+    let conversations: HashSet<String> = Class::get_instances(conn, CONVERSATION)?.into_iter().collect();
+    let activities: HashSet<String> = Class::get_instances(conn, MESSAGE_ACTIVITY)?.into_iter().collect();
+    let mut messages: HashSet<String> = if bodies {
+        Class::get_instances(conn, MESSAGE)?.into_iter().collect()
+    } else {
+        HashSet::new()
+    };
+    let mut additions = Vec::new();
+    let mut retractions = Vec::new();
+
+    for chat in chats {
+        let conversation = format!("foundation:Conversation_{}", hash(&format!("{}|{}", chat.service, chat.id)));
+        let is_new = !conversations.contains(&conversation);
+        if is_new {
+            additions.push(Triple::new(&conversation, rdf::TYPE, Object::Iri(CONVERSATION.to_string())));
+            additions.push(Triple::new(&conversation, rdfs::LABEL, string(&chat.name)));
+            additions.push(Triple::new(&conversation, MESSAGING_SERVICE, string(&chat.service)));
+            report.conversations += 1;
+        }
+        let participants: HashSet<String> = query::get_by_entity_predicate(conn, &conversation, PARTICIPANT)?
+            .triples.iter()
+            .filter_map(|t| t.object.as_iri().map(String::from))
+            .collect();
+
+        let mut days: BTreeMap<(String, i64), i64> = BTreeMap::new();
+        for message in &chat.messages {
+            let person = contacts.resolve(&message.sender, &mut additions);
+            *days.entry((person.clone(), message.sent_at - message.sent_at.rem_euclid(DAY))).or_default() += 1;
+            report.messages += 1;
+
+            if bodies {
+                let iri = format!("foundation:Message_{}", hash(&format!("{}|{}|{}|{}", conversation, person, message.sent_at, message.text)));
+                if messages.insert(iri.clone()) {
+                    let preview: String = message.text.chars().take(80).collect();
+                    additions.push(Triple::new(&iri, rdf::TYPE, Object::Iri(MESSAGE.to_string())));
+                    additions.push(Triple::new(&iri, rdfs::LABEL, string(&preview)));
+                    additions.push(Triple::new(&iri, CONVERSATION_PROPERTY, Object::Iri(conversation.clone())));
+                    additions.push(Triple::new(&iri, SENDER, Object::Iri(person)));
+                    additions.push(Triple::new(&iri, SENT_AT, Object::DateTime(message.sent_at)));
+                    additions.push(Triple::new(&iri, TEXT, string(&message.text)));
+                    report.bodies += 1;
+                }
+            }
+        }
+
+        let senders: HashSet<&String> = days.keys().map(|(person, _)| person).collect();
+        for person in senders.into_iter().filter(|p| !participants.contains(*p)) {
+            additions.push(Triple::new(&conversation, PARTICIPANT, Object::Iri(person.clone())));
+        }
+
+        for ((person, day), count) in days {
+            let activity = format!("foundation:MessageActivity_{}", hash(&format!("{}|{}|{}", conversation, person, day)));
+            if activities.contains(&activity) {
+                let current = query::get_by_entity_predicate(conn, &activity, MESSAGE_COUNT)?.triples;
+                let previous = current.iter().filter_map(|t| t.object.as_literal()?.parse::<i64>().ok()).max().unwrap_or(0);
+                if count > previous {
+                    additions.push(Triple::new(&activity, MESSAGE_COUNT, Object::Integer(count)));
+                    retractions.extend(current);
+                    report.updated += 1;
+                }
+                continue;
+            }
+
+            let date = chrono::DateTime::from_timestamp_millis(day).map(|d| d.format("%Y-%m-%d").to_string()).unwrap_or_default();
+            additions.push(Triple::new(&activity, rdf::TYPE, Object::Iri(MESSAGE_ACTIVITY.to_string())));
+            additions.push(Triple::new(&activity, rdfs::LABEL, string(&format!("{}, {}", chat.name, date))));
+            additions.push(Triple::new(&activity, CONVERSATION_PROPERTY, Object::Iri(conversation.clone())));
+            additions.push(Triple::new(&activity, SENDER, Object::Iri(person)));
+            additions.push(Triple::new(&activity, ACTIVITY_DATE, Object::DateTime(day)));
+            additions.push(Triple::new(&activity, MESSAGE_COUNT, Object::Integer(count)));
+            report.activities += 1;
+        }
+    }
+
+    report.persons = contacts.created;
+    if !additions.is_empty() {
+        report.tx = Some(store::apply_changes(conn, &additions, &retractions, origin)?);
+    }
+    Ok(report)
+}
+
+// ============================================================================
+// WhatsApp
+// ============================================================================
+
+/// A line starting a WhatsApp message (or a system notice, without sender)
+struct WhatsAppLine<'a> {
+    date: [u32; 3], // As written; day and month order depends on the phone's locale
+    minutes: u32, // Since midnight
+    seconds: u32,
+    sender: Option<&'a str>,
+    text: &'a str,
+}
+
+/// Messages of a WhatsApp chat export; times are taken as UTC
+///
+/// Lines look like "14/03/2024, 08:15 - Maria: Hi" (Android) or
+/// "[14/03/2024, 08:15:32] Maria: Hi" (iOS), with the date in the phone's
+/// locale order: the export is day-first unless a date shows otherwise.
+pub fn parse_whatsapp(text: &str, name: &str) -> Result<Chat> {
+    let lines: Vec<WhatsAppLine> = text.lines().filter_map(whatsapp_line).collect();
+    if lines.is_empty() && !text.trim().is_empty() {
+        return Err(OwlError::ValidationError("Not a WhatsApp chat export".to_string()));
+    }
+    let month_first = lines.iter().any(|l| l.date[1] > 12) && !lines.iter().any(|l| l.date[0] > 12);
+
+    let mut messages = Vec::new();
+    for line in lines {
+        let Some(sender) = line.sender else { continue };
+        let [a, b, year] = line.date;
+        let (day, month) = if month_first { (b, a) } else { (a, b) };
+        let year = if year < 100 { 2000 + year } else { year };
+        let Some(date) = NaiveDate::from_ymd_opt(year as i32, month, day) else { continue };
+        let midnight = date.and_hms_opt(0, 0, 0).unwrap_or_default().and_utc().timestamp_millis();
+        messages.push(ChatMessage {
+            sender: sender.to_string(),
+            sent_at: midnight + (line.minutes as i64 * 60 + line.seconds as i64) * 1000,
+            text: line.text.to_string(),
+        });
+    }
+
+    Ok(Chat { service: "WhatsApp".to_string(), id: name.to_string(), name: name.to_string(), messages })
+}
+
+fn whatsapp_line(line: &str) -> Option<WhatsAppLine<'_>> {
+    let line = line.trim_start_matches(['\u{feff}', '\u{200e}']);
+    let (bracketed, line) = match line.strip_prefix('[') {
+        Some(rest) => (true, rest),
+        None => (false, line),
+    };
+
+    let (date, rest) = line.split_once(", ")?;
+    let (time, rest) = if bracketed { rest.split_once("] ")? } else { rest.split_once(" - ")? };
+
+    let parts: Vec<u32> = date.split(['/', '.', '-']).map(|p| p.trim().parse().ok()).collect::<Option<_>>()?;
+    let date: [u32; 3] = parts.try_into().ok()?;
+    let (minutes, seconds) = clock(time)?;
+
+    // "Maria: Hi"; notices ("Messages are end-to-end encrypted") have no sender
+    let (sender, text) = match rest.split_once(": ") {
+        Some((sender, text)) => (Some(sender.trim_start_matches('\u{200e}')), text),
+        None => (None, rest),
+    };
+    Some(WhatsAppLine { date, minutes, seconds, sender, text })
+}
+
+/// "08:15", "08:15:32", "8:15 PM" -> (minutes since midnight, seconds)
+fn clock(time: &str) -> Option<(u32, u32)> {
+    let time = time.replace(['\u{202f}', '\u{a0}'], " ").to_ascii_lowercase();
+    let (time, pm) = match time.trim().split_once(' ') {
+        Some((time, suffix)) => (time.to_string(), Some(suffix.replace('.', "") == "pm")),
+        None => (time.trim().to_string(), None),
+    };
+
+    let mut fields = time.split(':').map(|f| f.parse::<u32>().ok());
+    let mut hour = fields.next()??;
+    let minute = fields.next()??;
+    let second = fields.next().flatten().unwrap_or(0);
+    match pm {
+        Some(true) if hour < 12 => hour += 12,
+        Some(false) if hour == 12 => hour = 0,
+        _ => {}
+    }
+    (hour < 24 && minute < 60).then_some((hour * 60 + minute, second))
+}
+
+// ============================================================================
+// Telegram
+// ============================================================================
+
+/// Chats of a Telegram Desktop export (result.json of one chat, or of all)
+pub fn parse_telegram(text: &str) -> Result<Vec<Chat>> {
+    let json: Value = serde_json::from_str(text)
+        .map_err(|e| OwlError::ValidationError(format!("Not a Telegram export: {}", e)))?;
+
+    let chats: Vec<&Value> = match json.pointer("/chats/list").and_then(Value::as_array) {
+        Some(list) => list.iter().collect(),
+        None if json.get("messages").is_some() => vec![&json],
+        None => return Err(OwlError::ValidationError("Not a Telegram export: no chats or messages".to_string())),
+    };
+
+    Ok(chats.into_iter().map(|chat| {
+        let id = chat.get("id").map(|id| id.to_string()).unwrap_or_default();
+        let name = chat.get("name").and_then(Value::as_str).unwrap_or("Saved Messages").to_string();
+        let messages = chat.get("messages").and_then(Value::as_array).into_iter().flatten()
+            .filter(|m| m.get("type").and_then(Value::as_str) == Some("message"))
+            .filter_map(telegram_message)
+            .collect();
+        Chat { service: "Telegram".to_string(), id, name, messages }
+    }).collect())
+}
+
+fn telegram_message(message: &Value) -> Option<ChatMessage> {
+    let sender = message.get("from").and_then(Value::as_str)?.to_string();
+
+    // "date_unixtime" since 2022; older exports only have a local "date"
+    let sent_at = match message.get("date_unixtime").and_then(Value::as_str) {
+        Some(seconds) => seconds.parse::<i64>().ok()? * 1000,
+        None => chrono::NaiveDateTime::parse_from_str(message.get("date")?.as_str()?, "%Y-%m-%dT%H:%M:%S")
+            .ok()?.and_utc().timestamp_millis(),
+    };
+
+    // Plain text, or an array of strings and formatted entities
+    let text = match message.get("text") {
+        Some(Value::Array(parts)) => parts.iter()
+            .map(|p| p.as_str().or_else(|| p.get("text").and_then(Value::as_str)).unwrap_or_default())
+            .collect(),
+        Some(Value::String(text)) => text.clone(),
+        _ => String::new(),
+    };
+    Some(ChatMessage { sender, sent_at, text })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::eavto::test_helpers::setup_test_db;
+
+    #[test]
+    fn test_parse_whatsapp_formats() {
+        let android = "14/03/2024, 08:15 - Messages and calls are end-to-end encrypted.\n\
+                       14/03/2024, 08:15 - Maria: Good morning\n\
+                       second line of the same message\n\
+                       14/03/2024, 20:01 - +55 11 98765-4321: Hi";
+        let chat = parse_whatsapp(android, "Family").unwrap();
+        assert_eq!(chat.messages.len(), 2);
+        assert_eq!(chat.messages[0].sent_at, 1710404100000);
+        assert_eq!(chat.messages[1].sender, "+55 11 98765-4321");
+
+        let ios = "[3/14/24, 8:15:32\u{202f}PM] Maria: Hi\n[3/15/24, 12:01:00\u{202f}AM] Maria: Late";
+        let chat = parse_whatsapp(ios, "Maria").unwrap();
+        assert_eq!(chat.messages[0].sent_at, 1710404100000 + 12 * 3_600_000 + 32_000);
+        assert_eq!(chat.messages[1].sent_at, 1710460800000 + 60_000);
+
+        assert!(parse_whatsapp("just some text", "x").is_err());
+    }
+
+    #[test]
+    fn test_parse_telegram() {
+        let export = r#"{"chats": {"list": [{"id": 42, "name": "Maria", "messages": [
+            {"type": "service", "date_unixtime": "1710404100", "actor": "Maria"},
+            {"type": "message", "date_unixtime": "1710404100", "from": "Maria", "text": ["See ", {"type": "bold", "text": "you"}]},
+            {"type": "message", "date": "2024-03-14T08:16:00", "from": "Me", "text": "Sure"}
+        ]}]}}"#;
+        let chats = parse_telegram(export).unwrap();
+        assert_eq!((chats[0].id.as_str(), chats[0].messages.len()), ("42", 2));
+        assert_eq!(chats[0].messages[0].text, "See you");
+        assert_eq!(chats[0].messages[1].sent_at, 1710404160000);
+    }
+
+    #[test]
+    fn test_import_counts_per_person_and_day() {
+        let mut conn = setup_test_db();
+        let message = |sender: &str, sent_at: i64| ChatMessage { sender: sender.to_string(), sent_at, text: "hi".to_string() };
+        let chat = |messages| Chat { service: "WhatsApp".to_string(), id: "Family".to_string(), name: "Family".to_string(), messages };
+
+        let first = import_chats(&mut conn, &[chat(vec![
+            message("Maria", 1_000), message("maria", 2_000), message("John", 3_000), message("Maria", DAY + 1),
+        ])], false, "test").unwrap();
+        assert_eq!((first.messages, first.conversations, first.activities, first.persons), (4, 1, 3, 2));
+        assert_eq!(first.bodies, 0);
+
+        let conversation = format!("foundation:Conversation_{}", hash("WhatsApp|Family"));
+        assert_eq!(query::get_by_entity_predicate(&conn, &conversation, PARTICIPANT).unwrap().triples.len(), 2);
+
+        // A longer export of the same chat
+        let second = import_chats(&mut conn, &[chat(vec![
+            message("Maria", 1_000), message("Maria", 2_000), message("Maria", 2_500), message("John", 3_000),
+        ])], true, "test").unwrap();
+        assert_eq!((second.conversations, second.activities, second.updated, second.persons), (0, 0, 1, 0));
+        assert_eq!(second.bodies, 4);
+    }
+}
@@ -0,0 +1,393 @@
+// ============================================================================
+// OWL Module - RDF/RDFS/OWL Abstraction Layer
+// ============================================================================
+// Provides high-level interfaces for managing RDF, RDFS, and OWL concepts
+// over the EAVTO triple store.
+//
+// Architecture:
+// - Abstracts EAVTO triple operations into semantic operations
+// - Provides type-safe interfaces for Classes, Properties, and Individuals
+// - Implements RDF/RDFS/OWL vocabulary operations
+// ============================================================================
+
+mod class;
+mod property;
+mod individual;
+mod thing;
+mod expression;
+mod ontology;
+mod ontology_release;
+pub mod affiliation;
+pub mod attachment;
+pub mod browsing;
+pub mod bulk;
+pub mod changelog;
+pub mod closure;
+pub mod consistency;
+pub mod console;
+pub mod contacts;
+pub mod fact;
+pub mod federation;
+pub mod finance;
+pub mod geo;
+pub mod inference;
+pub mod integrity;
+pub mod license;
+pub mod lint;
+pub mod list;
+pub mod listening;
+pub mod locale;
+pub mod location;
+pub mod mentions;
+pub mod neighborhood;
+pub mod path;
+pub mod messaging;
+pub mod palette;
+pub mod parthood;
+pub mod phash;
+pub mod photos;
+pub mod suggestions;
+pub mod table;
+pub mod text;
+pub mod tree;
+pub mod startup;
+pub mod workspace;
+pub mod manchester;
+pub mod functional;
+pub mod vocabulary;
+
+pub use class::{Class, ClassType, Restriction};
+pub use property::{Property, ObjectProperty, DatatypeProperty, PropertyType, PROPERTY_KINDS, PROPERTY_TYPES};
+pub use individual::{Individual, PropertyInput, Retraction, same_as};
+pub use thing::Thing;
+pub use expression::{ClassExpression, RestrictionConstraint, ClassAxioms};
+pub use ontology::Ontology;
+pub use ontology_release::{ImportRun, OntologyRelease, OntologyUpgrade};
+pub use crate::eavto::Object;
+
+use rusqlite::Connection;
+use sha2::{Sha256, Digest};
+
+/// OWL operation errors
+#[derive(Debug)]
+pub enum OwlError {
+    DatabaseError(String),
+    ValidationError(String),
+    NotFound(String),
+    InvalidOperation(String),
+    /// A value outside a property's rdfs:range
+    RangeError { property: String, expected: Vec<String>, found: String },
+}
+
+impl std::fmt::Display for OwlError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            OwlError::DatabaseError(msg) => write!(f, "Database error: {}", msg),
+            OwlError::ValidationError(msg) => write!(f, "Validation error: {}", msg),
+            OwlError::NotFound(msg) => write!(f, "Not found: {}", msg),
+            OwlError::InvalidOperation(msg) => write!(f, "Invalid operation: {}", msg),
+            OwlError::RangeError { property, expected, found } => write!(
+                f, "Validation error: {} expects {}, got {}", property, expected.join(" or "), found
+            ),
+        }
+    }
+}
+
+impl std::error::Error for OwlError {}
+
+impl From<rusqlite::Error> for OwlError {
+    fn from(err: rusqlite::Error) -> Self {
+        OwlError::DatabaseError(err.to_string())
+    }
+}
+
+impl From<Box<dyn std::error::Error>> for OwlError {
+    fn from(err: Box<dyn std::error::Error>) -> Self {
+        // A value the store refused is the caller's to fix
+        match err.downcast_ref::<crate::eavto::InvalidLiteral>() {
+            Some(invalid) => OwlError::ValidationError(invalid.to_string()),
+            None => OwlError::DatabaseError(err.to_string()),
+        }
+    }
+}
+
+type Result<T> = std::result::Result<T, OwlError>;
+
+/// Short, stable hash for IRIs of imported entities (the first 16 hex
+/// digits of the SHA-256): the same value always names the same entity
+pub(crate) fn hash(value: &str) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(value.as_bytes());
+    format!("{:x}", hasher.finalize())[..16].to_string()
+}
+
+/// An xsd:string literal
+pub(crate) fn string(value: &str) -> Object {
+    Object::Literal { value: value.to_string(), datatype: Some("xsd:string".to_string()), language: None }
+}
+
+/// Search result for classes, properties and individuals
+#[derive(Debug, Clone)]
+pub struct SearchResult {
+    pub id: String,
+    pub label: String,
+    pub icon: Option<String>,
+    pub is_class: bool,
+    pub type_iri: Option<String>, // Direct (asserted) type
+}
+
+/// Kinds of entity a search can be restricted to
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EntityKind {
+    Class,
+    Property,
+    Individual,
+}
+
+impl EntityKind {
+    pub fn parse(kind: &str) -> Option<Self> {
+        match kind {
+            "class" => Some(EntityKind::Class),
+            "property" => Some(EntityKind::Property),
+            "individual" => Some(EntityKind::Individual),
+            _ => None,
+        }
+    }
+}
+
+/// Search for classes by label (case-insensitive, ranked by relevance)
+pub fn search_classes(conn: &Connection, query: &str, limit: usize) -> Result<Vec<SearchResult>> {
+    use vocabulary::{rdf, owl};
+    use crate::eavto::query;
+
+    // Get all classes
+    let all_classes_result = query::get_by_predicate_object(conn, rdf::TYPE, owl::CLASS)?;
+
+    let mut results = Vec::new();
+    let query_lower = text::fold(query);
+
+    for triple in all_classes_result.triples {
+        let class_iri = &triple.subject;
+
+        // Get thing (basic entity info)
+        let thing = Thing::get(conn, class_iri);
+        let label_lower = text::fold(&thing.label);
+
+        // Check if matches query (case-insensitive)
+        if label_lower.contains(&query_lower) {
+            // Calculate relevance score (lower is better)
+            let score = if label_lower == query_lower {
+                0 // Exact match
+            } else if label_lower.starts_with(&query_lower) {
+                1 // Starts with query
+            } else {
+                2 // Contains query
+            };
+
+            results.push((score, SearchResult {
+                id: class_iri.clone(),
+                label: thing.label,
+                icon: thing.icon,
+                is_class: true,
+                type_iri: Some(owl::CLASS.to_string()),
+            }));
+        }
+    }
+
+    // Sort by relevance score, then label length, then alphabetically
+    results.sort_by(|a, b| {
+        a.0.cmp(&b.0)
+            .then_with(|| a.1.label.len().cmp(&b.1.label.len()))
+            .then_with(|| a.1.label.cmp(&b.1.label))
+    });
+
+    // Take top results and remove scores
+    Ok(results.into_iter().take(limit).map(|(_, r)| r).collect())
+}
+
+/// Search for individuals by label (case-insensitive, ranked by relevance)
+pub fn search_individuals(conn: &Connection, query: &str, limit: usize) -> Result<Vec<SearchResult>> {
+    search_individuals_of(conn, query, limit, None)
+}
+
+/// Search for individuals by label, optionally only members of a class
+///
+/// Members of a class include inferred ones (instances of subclasses, and
+/// entities typed by property domains and ranges): searching
+/// foundation:Agent also finds persons and organizations.
+pub fn search_individuals_of(conn: &Connection, query: &str, limit: usize, class: Option<&str>) -> Result<Vec<SearchResult>> {
+    use vocabulary::{rdf, rdfs, owl};
+    use crate::eavto::query;
+
+    // Candidates: members of the class, or all entities with an rdf:type
+    // that are neither classes nor properties
+    let candidates: Vec<String> = match class {
+        Some(class) => inference::instances(conn, class)?,
+        None => {
+            let types = query::get_by_predicate(conn, rdf::TYPE)?.triples;
+            let schema: std::collections::HashSet<&str> = types.iter()
+                .filter(|t| t.object.as_iri().is_some_and(|c| c == owl::CLASS || PROPERTY_TYPES.contains(&c)))
+                .map(|t| t.subject.as_str())
+                .collect();
+            types.iter()
+                .filter(|t| !schema.contains(t.subject.as_str()))
+                .map(|t| t.subject.clone())
+                .collect()
+        }
+    };
+
+    let mut seen = std::collections::HashSet::new();
+    let mut results = Vec::new();
+    let query_lower = text::fold(query);
+
+    for individual_iri in &candidates {
+        // Skip if already processed
+        if !seen.insert(individual_iri.clone()) {
+            continue;
+        }
+
+        // Get label
+        let label_result = query::get_by_entity_predicate(conn, individual_iri, rdfs::LABEL)?;
+        if let Some(label_triple) = label_result.triples.first() {
+            if let Object::Literal { value: label, .. } = &label_triple.object {
+                let label_lower = text::fold(label);
+
+                // Check if matches query (case-insensitive)
+                if label_lower.contains(&query_lower) {
+                    // Get icon
+                    let icon_result = query::get_by_entity_predicate(conn, individual_iri, "foundation:icon")?;
+                    let icon = icon_result.triples.first().and_then(|t| {
+                        if let Object::Literal { value, .. } = &t.object {
+                            Some(value.clone())
+                        } else {
+                            None
+                        }
+                    });
+
+                    // Calculate relevance score (lower is better)
+                    let score = if label_lower == query_lower {
+                        0 // Exact match
+                    } else if label_lower.starts_with(&query_lower) {
+                        1 // Starts with query
+                    } else {
+                        2 // Contains query
+                    };
+
+                    results.push((score, SearchResult {
+                        id: individual_iri.clone(),
+                        label: label.clone(),
+                        icon,
+                        is_class: false,
+                        type_iri: direct_type(conn, individual_iri)?,
+                    }));
+                }
+            }
+        }
+    }
+
+    // Sort by relevance score, then label length, then alphabetically
+    results.sort_by(|a, b| {
+        a.0.cmp(&b.0)
+            .then_with(|| a.1.label.len().cmp(&b.1.label.len()))
+            .then_with(|| a.1.label.cmp(&b.1.label))
+    });
+
+    // Take top results and remove scores
+    Ok(results.into_iter().take(limit).map(|(_, r)| r).collect())
+}
+
+/// Search for properties by label (case-insensitive, ranked by relevance)
+pub fn search_properties(conn: &Connection, query: &str, limit: usize) -> Result<Vec<SearchResult>> {
+    use crate::eavto::query;
+
+    let mut seen = std::collections::HashSet::new();
+    let mut results = Vec::new();
+    let query_lower = text::fold(query);
+
+    for property_type in PROPERTY_TYPES {
+        for triple in query::get_by_predicate_object(conn, vocabulary::rdf::TYPE, property_type)?.triples {
+            if !seen.insert(triple.subject.clone()) {
+                continue;
+            }
+            let thing = Thing::get(conn, &triple.subject);
+            let label_lower = text::fold(&thing.label);
+            if !label_lower.contains(&query_lower) {
+                continue;
+            }
+
+            let score = if label_lower == query_lower {
+                0
+            } else if label_lower.starts_with(&query_lower) {
+                1
+            } else {
+                2
+            };
+            results.push((score, SearchResult {
+                type_iri: direct_type(conn, &triple.subject)?,
+                id: triple.subject,
+                label: thing.label,
+                icon: thing.icon,
+                is_class: false,
+            }));
+        }
+    }
+
+    results.sort_by(|a, b| {
+        a.0.cmp(&b.0)
+            .then_with(|| a.1.label.len().cmp(&b.1.label.len()))
+            .then_with(|| a.1.label.cmp(&b.1.label))
+    });
+    Ok(results.into_iter().take(limit).map(|(_, r)| r).collect())
+}
+
+/// First asserted type of an entity, skipping owl:NamedIndividual and the
+/// characteristics of properties (functional, transitive, ...) when a more
+/// telling type is asserted
+fn direct_type(conn: &Connection, iri: &str) -> Result<Option<String>> {
+    use vocabulary::rdf;
+
+    let types: Vec<String> = crate::eavto::query::get_by_entity_predicate(conn, iri, rdf::TYPE)?.triples.iter()
+        .filter_map(|t| t.object.as_iri().map(String::from))
+        .collect();
+    let generic = |t: &str| t == "owl:NamedIndividual" || (PROPERTY_TYPES.contains(&t) && !PROPERTY_KINDS.contains(&t));
+    Ok(types.iter().find(|t| !generic(t)).or(types.first()).cloned())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::eavto::{store, test_helpers::setup_test_db, Triple};
+    use vocabulary::{rdf, rdfs, owl};
+
+    #[test]
+    fn test_search_by_kind_reports_direct_type() {
+        let mut conn = setup_test_db();
+        let label = |iri: &str, label: &str| Triple::new(iri, rdfs::LABEL, Object::Literal {
+            value: label.to_string(),
+            datatype: Some("xsd:string".to_string()),
+            language: None,
+        });
+        store::assert_triples(&mut conn, &[
+            Triple::new("foundation:Boat", rdf::TYPE, Object::Iri(owl::CLASS.to_string())),
+            label("foundation:Boat", "Boat"),
+            Triple::new("foundation:boatName", rdf::TYPE, Object::Iri(owl::DATATYPE_PROPERTY.to_string())),
+            Triple::new("foundation:boatName", rdf::TYPE, Object::Iri(owl::FUNCTIONAL_PROPERTY.to_string())),
+            label("foundation:boatName", "boat name"),
+            Triple::new("foundation:Aurora", rdf::TYPE, Object::Iri("foundation:Boat".to_string())),
+            label("foundation:Aurora", "Boat Aurora"),
+        ], "test").unwrap();
+
+        let individuals = search_individuals(&conn, "boat", 10).unwrap();
+        assert_eq!(individuals.len(), 1);
+        assert_eq!(search_individuals(&conn, "aurorá", 10).unwrap().len(), 1);
+        assert_eq!(individuals[0].type_iri.as_deref(), Some("foundation:Boat"));
+
+        let properties = search_properties(&conn, "boat", 10).unwrap();
+        assert_eq!(properties.len(), 1);
+        assert_eq!(properties[0].type_iri.as_deref(), Some(owl::DATATYPE_PROPERTY));
+
+        let classes = search_classes(&conn, "boat", 10).unwrap();
+        assert_eq!(classes[0].type_iri.as_deref(), Some(owl::CLASS));
+        assert_eq!(EntityKind::parse("property"), Some(EntityKind::Property));
+    }
+}
@@ -0,0 +1,165 @@
+// ============================================================================
+// OWL Neighborhood - Graph Traversal
+// ============================================================================
+// The entities within some links of an entity: a breadth-first traversal
+// over the IRI links of the store, followed in both directions (a class
+// reaches its superclasses and its subclasses), optionally through some
+// predicates only.
+//
+// The traversal stops growing at a node budget; links between the nodes
+// reached are all kept. Expanding a node of the result is another traversal
+// from it, so the graph can grow incrementally.
+// ============================================================================
+
+use rusqlite::Connection;
+use std::collections::{HashMap, HashSet};
+use crate::eavto::{query, Object};
+use crate::owl::{Class, Result, Thing};
+
+/// An entity reached
+#[derive(Debug, Clone)]
+pub struct Neighbor {
+    pub thing: Thing,
+    pub depth: usize, // Links from the start entity
+    pub is_class: bool,
+}
+
+/// A link between two entities reached
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct NeighborLink {
+    pub source: String,
+    pub predicate: String,
+    pub target: String,
+}
+
+/// The entities within some links of an entity, nearest first
+#[derive(Debug, Clone)]
+pub struct Neighborhood {
+    pub nodes: Vec<Neighbor>,
+    pub links: Vec<NeighborLink>,
+    pub truncated: bool, // The node budget stopped the traversal
+}
+
+/// Traverse the links around an entity
+/// depth: links followed from it (0: the entity alone)
+/// predicates: the predicates followed (None: all)
+/// max_nodes: entities reached at most, the start entity included
+pub fn neighborhood(
+    conn: &Connection,
+    entity: &str,
+    depth: usize,
+    predicates: Option<&[String]>,
+    max_nodes: usize,
+) -> Result<Neighborhood> {
+    let follows = |predicate: &str| predicates.is_none_or(|predicates| predicates.iter().any(|p| p == predicate));
+
+    let mut depths: HashMap<String, usize> = HashMap::from([(entity.to_string(), 0)]);
+    let mut order = vec![entity.to_string()];
+    let mut links = Vec::new();
+    let mut seen_links = HashSet::new();
+    let mut truncated = false;
+
+    let mut frontier = vec![entity.to_string()];
+    for level in 1..=depth {
+        let mut next = Vec::new();
+        for node in &frontier {
+            for link in links_of(conn, node)?.into_iter().filter(|link| follows(&link.predicate)) {
+                let other = if link.source == *node { &link.target } else { &link.source };
+                if !depths.contains_key(other) {
+                    if depths.len() >= max_nodes {
+                        truncated = true;
+                        continue;
+                    }
+                    depths.insert(other.clone(), level);
+                    order.push(other.clone());
+                    next.push(other.clone());
+                }
+                if seen_links.insert(link.clone()) {
+                    links.push(link);
+                }
+            }
+        }
+        if next.is_empty() {
+            break;
+        }
+        frontier = next;
+    }
+
+    let iris: Vec<&str> = order.iter().map(String::as_str).collect();
+    let nodes = Thing::get_many(conn, &iris)
+        .into_iter()
+        .map(|thing| {
+            Ok(Neighbor {
+                depth: depths[&thing.iri],
+                is_class: Class::new(&thing.iri).exists(conn)?,
+                thing,
+            })
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    Ok(Neighborhood { nodes, links, truncated })
+}
+
+/// The IRI links of an entity, outgoing then incoming
+pub(crate) fn links_of(conn: &Connection, entity: &str) -> Result<Vec<NeighborLink>> {
+    let outgoing = query::get_by_entity(conn, entity)?.triples.into_iter()
+        .filter_map(|t| match t.object {
+            Object::Iri(target) => Some(NeighborLink { source: t.subject, predicate: t.predicate, target }),
+            _ => None,
+        });
+    let incoming = query::get_by_object(conn, entity)?.triples.into_iter()
+        .filter_map(|t| t.object.as_iri().map(|target| NeighborLink {
+            source: t.subject.clone(),
+            predicate: t.predicate.clone(),
+            target: target.to_string(),
+        }));
+    Ok(outgoing.chain(incoming).collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::eavto::{store, test_helpers::setup_test_db, Triple};
+    use crate::owl::vocabulary::{owl, rdf, rdfs};
+
+    fn iri(value: &str) -> Object {
+        Object::Iri(value.to_string())
+    }
+
+    #[test]
+    fn test_neighborhood_by_depth_and_predicate() {
+        let mut conn = setup_test_db();
+        store::assert_triples(&mut conn, &[
+            Triple::new("foundation:Vehicle", rdf::TYPE, iri(owl::CLASS)),
+            Triple::new("foundation:Car", rdf::TYPE, iri(owl::CLASS)),
+            Triple::new("foundation:Car", rdfs::SUB_CLASS_OF, iri("foundation:Vehicle")),
+            Triple::new("foundation:SportsCar", rdfs::SUB_CLASS_OF, iri("foundation:Car")),
+            Triple::new("foundation:myCar", rdf::TYPE, iri("foundation:Car")),
+            Triple::new("foundation:myCar", "foundation:ownedBy", iri("foundation:alice")),
+        ], "test").unwrap();
+
+        let alone = neighborhood(&conn, "foundation:Car", 0, None, 10).unwrap();
+        assert_eq!((alone.nodes.len(), alone.links.len()), (1, 0));
+
+        let near = neighborhood(&conn, "foundation:Car", 1, None, 10).unwrap();
+        let iris: Vec<&str> = near.nodes.iter().map(|n| n.thing.iri.as_str()).collect();
+        assert_eq!(iris[0], "foundation:Car");
+        assert_eq!(iris.len(), 5); // owl:Class, Vehicle, SportsCar, myCar
+        assert!(near.nodes[0].is_class && !near.truncated);
+
+        let two = neighborhood(&conn, "foundation:Car", 2, None, 10).unwrap();
+        let alice = two.nodes.iter().find(|n| n.thing.iri == "foundation:alice").unwrap();
+        assert_eq!(alice.depth, 2);
+
+        // Subclass links only: up to Vehicle, down to SportsCar
+        let taxonomy = [rdfs::SUB_CLASS_OF.to_string()];
+        let classes = neighborhood(&conn, "foundation:Vehicle", 3, Some(&taxonomy), 10).unwrap();
+        let iris: Vec<&str> = classes.nodes.iter().map(|n| n.thing.iri.as_str()).collect();
+        assert_eq!(iris, ["foundation:Vehicle", "foundation:Car", "foundation:SportsCar"]);
+        assert_eq!(classes.links.len(), 2);
+
+        let budget = neighborhood(&conn, "foundation:Car", 2, None, 3).unwrap();
+        assert_eq!(budget.nodes.len(), 3);
+        assert!(budget.truncated);
+    }
+}
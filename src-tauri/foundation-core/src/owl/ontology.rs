@@ -0,0 +1,171 @@
+// ============================================================================
+// OWL Ontology - Ontology Headers as Entities
+// ============================================================================
+// Reads owl:Ontology headers (title, version, license, creators) of imported
+// files, together with where and when they were imported, so ontologies can
+// be shown as "BFO 2.0 (CC-BY) imported 2025-01-12, 952 axioms" rather than
+// by their origin string.
+//
+// Metadata is read from the common annotation vocabularies: Dublin Core
+// elements and terms, with rdfs:label as the fallback title.
+// ============================================================================
+
+use rusqlite::Connection;
+use crate::eavto::{query, Object};
+use crate::namespaces::compress_iri;
+use crate::owl::{Result, OwlError, vocabulary::{rdf, rdfs, owl, dc, dcterms}};
+
+/// Predicates holding an ontology's title, in order of preference
+const TITLE_PREDICATES: &[&str] = &[dcterms::TITLE, dc::TITLE, rdfs::LABEL];
+
+/// Predicates holding an ontology's license, in order of preference
+const LICENSE_PREDICATES: &[&str] = &[dcterms::LICENSE, dcterms::RIGHTS, dc::RIGHTS];
+
+/// Predicates holding an ontology's creators
+const CREATOR_PREDICATES: &[&str] = &[dcterms::CREATOR, dc::CREATOR];
+
+/// An owl:Ontology header with import metadata
+#[derive(Debug, Clone, PartialEq)]
+pub struct Ontology {
+    pub iri: String,
+    pub title: Option<String>,
+    pub version_iri: Option<String>,
+    pub version_info: Option<String>,
+    pub license: Option<String>,
+    pub creators: Vec<String>,
+    pub imports: Vec<String>,
+    pub source_file: Option<String>,
+    pub imported_at: Option<i64>,
+    pub origin: Option<String>,
+    pub axiom_count: i64,
+}
+
+impl Ontology {
+    /// Get an ontology by IRI
+    pub fn get(conn: &Connection, iri: &str) -> Result<Self> {
+        let triples = query::get_by_entity(conn, iri)?.triples;
+        let header = triples.iter()
+            .find(|t| t.predicate == rdf::TYPE && t.object.as_iri() == Some(owl::ONTOLOGY))
+            .ok_or_else(|| OwlError::NotFound(format!("Ontology {} not found", iri)))?;
+
+        let values = |predicates: &[&str]| -> Vec<String> {
+            predicates.iter()
+                .flat_map(|predicate| triples.iter().filter(move |t| compress_iri(&t.predicate) == *predicate))
+                .filter_map(|t| value_of(&t.object))
+                .collect()
+        };
+        let first = |predicates: &[&str]| values(predicates).into_iter().next();
+
+        let mut creators: Vec<String> = Vec::new();
+        for creator in values(CREATOR_PREDICATES) {
+            if !creators.contains(&creator) {
+                creators.push(creator);
+            }
+        }
+
+        let imported_at = triples.iter()
+            .find(|t| t.predicate == "foundation:importedAt")
+            .and_then(|t| match t.object {
+                Object::DateTime(time) | Object::Integer(time) => Some(time),
+                _ => None,
+            });
+
+        Ok(Self {
+            iri: iri.to_string(),
+            title: first(TITLE_PREDICATES),
+            version_iri: first(&[owl::VERSION_IRI]),
+            version_info: first(&[owl::VERSION_INFO]),
+            license: first(LICENSE_PREDICATES),
+            creators,
+            imports: values(&[owl::IMPORTS]),
+            source_file: first(&["foundation:sourceFile"]),
+            imported_at,
+            origin: query::get_origin_name(conn, header.origin_id)?,
+            axiom_count: query::count_by_origin(conn, header.origin_id)?,
+        })
+    }
+
+    /// All ontologies in the store, ordered by title
+    pub fn list(conn: &Connection) -> Result<Vec<Self>> {
+        let mut iris: Vec<String> = query::get_by_predicate_object(conn, rdf::TYPE, owl::ONTOLOGY)?
+            .triples
+            .into_iter()
+            .map(|t| t.subject)
+            .collect();
+        iris.sort();
+        iris.dedup();
+
+        let mut ontologies = iris.iter()
+            .map(|iri| Self::get(conn, iri))
+            .collect::<Result<Vec<_>>>()?;
+        ontologies.sort_by_key(|ontology| ontology.display_title().to_lowercase());
+        Ok(ontologies)
+    }
+
+    /// Title, falling back to the IRI
+    pub fn display_title(&self) -> &str {
+        self.title.as_deref().unwrap_or(&self.iri)
+    }
+}
+
+/// IRI or lexical value of an annotation
+fn value_of(object: &Object) -> Option<String> {
+    object.as_iri().map(|s| s.to_string()).or_else(|| object.as_literal())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::eavto::{store, test_helpers::setup_test_db, Triple};
+    use crate::owl::string;
+
+    fn import_bfo(conn: &mut Connection) {
+        store::assert_triples(conn, &[
+            Triple::new("http://purl.obolibrary.org/obo/bfo.owl", rdf::TYPE, Object::Iri(owl::ONTOLOGY.to_string())),
+            Triple::new("http://purl.obolibrary.org/obo/bfo.owl", dc::TITLE, string("BFO")),
+            Triple::new("http://purl.obolibrary.org/obo/bfo.owl", owl::VERSION_INFO, string("2.0")),
+            Triple::new("http://purl.obolibrary.org/obo/bfo.owl", dcterms::LICENSE, Object::Iri("http://creativecommons.org/licenses/by/4.0/".to_string())),
+            Triple::new("http://purl.obolibrary.org/obo/bfo.owl", dc::CREATOR, string("Barry Smith")),
+            Triple::new("http://purl.obolibrary.org/obo/bfo.owl", "http://purl.org/dc/terms/creator", string("Alan Ruttenberg")),
+            Triple::new("http://purl.obolibrary.org/obo/bfo.owl", "foundation:importedAt", Object::DateTime(1736640000000)),
+            Triple::new("obo:BFO_0000001", rdf::TYPE, Object::Iri(owl::CLASS.to_string())),
+        ], "test").unwrap();
+    }
+
+    #[test]
+    fn test_get_ontology_metadata() {
+        let mut conn = setup_test_db();
+        import_bfo(&mut conn);
+
+        let ontology = Ontology::get(&conn, "http://purl.obolibrary.org/obo/bfo.owl").unwrap();
+        assert_eq!(ontology.title.as_deref(), Some("BFO"));
+        assert_eq!(ontology.version_info.as_deref(), Some("2.0"));
+        assert_eq!(ontology.license.as_deref(), Some("http://creativecommons.org/licenses/by/4.0/"));
+        // Full and prefixed Dublin Core IRIs are both recognized
+        assert_eq!(ontology.creators, vec!["Alan Ruttenberg", "Barry Smith"]);
+        assert_eq!(ontology.imported_at, Some(1736640000000));
+        assert_eq!(ontology.origin.as_deref(), Some("test"));
+        assert_eq!(ontology.axiom_count, 8);
+    }
+
+    #[test]
+    fn test_title_falls_back_to_label_and_iri() {
+        let mut conn = setup_test_db();
+        store::assert_triples(&mut conn, &[
+            Triple::new("http://example.org/a", rdf::TYPE, Object::Iri(owl::ONTOLOGY.to_string())),
+            Triple::new("http://example.org/a", rdfs::LABEL, string("Zeta")),
+            Triple::new("http://example.org/b", rdf::TYPE, Object::Iri(owl::ONTOLOGY.to_string())),
+        ], "test").unwrap();
+
+        let ontologies = Ontology::list(&conn).unwrap();
+        assert_eq!(ontologies.len(), 2);
+        assert_eq!(ontologies[0].display_title(), "http://example.org/b");
+        assert_eq!(ontologies[1].title.as_deref(), Some("Zeta"));
+    }
+
+    #[test]
+    fn test_get_missing_ontology() {
+        let conn = setup_test_db();
+        assert!(matches!(Ontology::get(&conn, "http://example.org/none"), Err(OwlError::NotFound(_))));
+    }
+}
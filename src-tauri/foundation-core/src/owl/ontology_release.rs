@@ -0,0 +1,538 @@
+// ============================================================================
+// OWL Ontology Release - Versioned Ontology Imports
+// ============================================================================
+// Each import of an ontology file is recorded as a foundation:OntologyRelease
+// individual (source file, checksum, version IRI, import transaction).
+//
+// Releases are append-only: re-importing a changed file records a new release
+// linked to the previous one via foundation:previousRelease. The import
+// transaction ties every asserted fact back to the release that asserted it.
+//
+// When a file is upgraded in place (axiom diff instead of a full re-import),
+// a foundation:OntologyUpgrade report records what the migration changed.
+//
+// Each pass over the ontology files that imported something (initialization,
+// or a startup after an update) is summarized as a foundation:ImportRun.
+// ============================================================================
+
+use rusqlite::Connection;
+use crate::eavto::{store, query, Triple, Object};
+use crate::owl::{Result, OwlError, string, vocabulary::{rdf, rdfs, owl}};
+
+/// Class IRI for ontology releases
+pub const ONTOLOGY_RELEASE: &str = "foundation:OntologyRelease";
+
+/// Class IRI for upgrade reports
+pub const ONTOLOGY_UPGRADE: &str = "foundation:OntologyUpgrade";
+
+/// Class IRI for import run summaries
+pub const IMPORT_RUN: &str = "foundation:ImportRun";
+
+/// A recorded import of an ontology file
+#[derive(Debug, Clone, PartialEq)]
+pub struct OntologyRelease {
+    pub iri: String,
+    pub source_file: String,
+    pub checksum: String,
+    pub ontology: Option<String>,
+    pub version_iri: Option<String>,
+    pub imported_at: i64,
+    pub tx: i64,
+    pub triple_count: i64,
+    pub previous_release: Option<String>,
+}
+
+impl OntologyRelease {
+    /// Record a release for the triples asserted in `tx`
+    ///
+    /// The ontology IRI and version IRI are read from the owl:Ontology header
+    /// asserted in that transaction, if the file declares one.
+    pub fn record(
+        conn: &mut Connection,
+        source_file: &str,
+        checksum: &str,
+        tx: i64,
+        triple_count: i64,
+        origin: &str,
+    ) -> Result<Self> {
+        let previous = Self::latest_for_file(conn, source_file)?;
+
+        // Upgrade transactions only carry changed triples, so fall back to
+        // the previous release for a header the upgrade left untouched
+        let asserted = query::get_by_transaction(conn, tx)?;
+        let ontology = asserted.triples.iter()
+            .find(|t| t.predicate == rdf::TYPE && t.object.as_iri() == Some(owl::ONTOLOGY))
+            .map(|t| t.subject.clone())
+            .or_else(|| previous.as_ref().and_then(|p| p.ontology.clone()));
+        let version_iri = ontology.as_ref().and_then(|ontology| {
+            asserted.triples.iter()
+                .find(|t| &t.subject == ontology && t.predicate == owl::VERSION_IRI)
+                .and_then(|t| t.object.as_iri())
+                .map(|s| s.to_string())
+        }).or_else(|| previous.as_ref().and_then(|p| p.version_iri.clone()));
+
+        let previous_release = previous.map(|r| r.iri);
+
+        let release = Self {
+            iri: release_iri(source_file, tx),
+            source_file: source_file.to_string(),
+            checksum: checksum.to_string(),
+            ontology,
+            version_iri,
+            imported_at: chrono::Utc::now().timestamp_millis(),
+            tx,
+            triple_count,
+            previous_release,
+        };
+
+        store::assert_triples(conn, &release.to_triples(), origin)?;
+        Ok(release)
+    }
+
+    /// Get a release by IRI
+    pub fn get(conn: &Connection, iri: &str) -> Result<Self> {
+        let triples = query::get_by_entity(conn, iri)?.triples;
+        let is_release = triples.iter()
+            .any(|t| t.predicate == rdf::TYPE && t.object.as_iri() == Some(ONTOLOGY_RELEASE));
+        if !is_release {
+            return Err(OwlError::NotFound(format!("Ontology release {} not found", iri)));
+        }
+
+        let object_of = |predicate: &str| triples.iter().find(|t| t.predicate == predicate).map(|t| &t.object);
+        let literal_of = |predicate: &str| object_of(predicate).and_then(|o| o.as_literal());
+        let iri_of = |predicate: &str| object_of(predicate).and_then(|o| o.as_iri()).map(|s| s.to_string());
+        let integer_of = |predicate: &str| match object_of(predicate) {
+            Some(Object::Integer(i)) | Some(Object::DateTime(i)) => *i,
+            _ => 0,
+        };
+
+        Ok(Self {
+            iri: iri.to_string(),
+            source_file: literal_of("foundation:sourceFile").unwrap_or_default(),
+            checksum: literal_of("foundation:checksum").unwrap_or_default(),
+            ontology: iri_of("foundation:releaseOfOntology"),
+            version_iri: iri_of(owl::VERSION_IRI),
+            imported_at: integer_of("foundation:importedAt"),
+            tx: integer_of("foundation:importTransaction"),
+            triple_count: integer_of("foundation:tripleCount"),
+            previous_release: iri_of("foundation:previousRelease"),
+        })
+    }
+
+    /// All releases, oldest first
+    pub fn list(conn: &Connection) -> Result<Vec<Self>> {
+        let result = query::get_by_predicate_object(conn, rdf::TYPE, ONTOLOGY_RELEASE)?;
+        let mut releases = result.triples.iter()
+            .map(|t| Self::get(conn, &t.subject))
+            .collect::<Result<Vec<_>>>()?;
+        releases.sort_by_key(|r| r.tx);
+        Ok(releases)
+    }
+
+    /// All releases of a file, oldest first
+    pub fn for_file(conn: &Connection, source_file: &str) -> Result<Vec<Self>> {
+        Ok(Self::list(conn)?
+            .into_iter()
+            .filter(|r| r.source_file == source_file)
+            .collect())
+    }
+
+    /// Most recent release of a file
+    pub fn latest_for_file(conn: &Connection, source_file: &str) -> Result<Option<Self>> {
+        Ok(Self::for_file(conn, source_file)?.pop())
+    }
+
+    /// The release whose import asserted transaction `tx`
+    pub fn for_transaction(conn: &Connection, tx: i64) -> Result<Option<Self>> {
+        let entities = query::find_entities_by_attribute_value(
+            conn,
+            "foundation:importTransaction",
+            &tx.to_string(),
+        )?;
+
+        match entities.first() {
+            Some(iri) => Ok(Some(Self::get(conn, iri)?)),
+            None => Ok(None),
+        }
+    }
+
+    fn to_triples(&self) -> Vec<Triple> {
+        let short_checksum: String = self.checksum.chars().take(8).collect();
+        let mut triples = vec![
+            Triple::new(&self.iri, rdf::TYPE, Object::Iri(ONTOLOGY_RELEASE.to_string())),
+            Triple::new(&self.iri, rdf::TYPE, Object::Iri("owl:NamedIndividual".to_string())),
+            Triple::new(&self.iri, rdfs::LABEL, string(&format!("{} ({})", self.source_file, short_checksum))),
+            Triple::new(&self.iri, "foundation:sourceFile", string(&self.source_file)),
+            Triple::new(&self.iri, "foundation:checksum", string(&self.checksum)),
+            Triple::new(&self.iri, "foundation:importedAt", Object::DateTime(self.imported_at)),
+            Triple::new(&self.iri, "foundation:importTransaction", Object::Integer(self.tx)),
+            Triple::new(&self.iri, "foundation:tripleCount", Object::Integer(self.triple_count)),
+        ];
+
+        if let Some(ontology) = &self.ontology {
+            triples.push(Triple::new(&self.iri, "foundation:releaseOfOntology", Object::Iri(ontology.clone())));
+        }
+        if let Some(version_iri) = &self.version_iri {
+            triples.push(Triple::new(&self.iri, owl::VERSION_IRI, Object::Iri(version_iri.clone())));
+        }
+        if let Some(previous) = &self.previous_release {
+            triples.push(Triple::new(&self.iri, "foundation:previousRelease", Object::Iri(previous.clone())));
+        }
+
+        triples
+    }
+}
+
+/// Report of a migration from one release of a file to the next
+#[derive(Debug, Clone, PartialEq)]
+pub struct OntologyUpgrade {
+    pub iri: String,
+    pub source_file: String,
+    pub from_release: Option<String>,
+    pub to_release: String,
+    pub added: i64,
+    pub retracted: i64,
+    pub tx: i64,
+    pub imported_at: i64,
+}
+
+impl OntologyUpgrade {
+    /// Record the upgrade that produced `release`
+    pub fn record(
+        conn: &mut Connection,
+        release: &OntologyRelease,
+        added: i64,
+        retracted: i64,
+        origin: &str,
+    ) -> Result<Self> {
+        let upgrade = Self {
+            iri: upgrade_iri(&release.source_file, release.tx),
+            source_file: release.source_file.clone(),
+            from_release: release.previous_release.clone(),
+            to_release: release.iri.clone(),
+            added,
+            retracted,
+            tx: release.tx,
+            imported_at: release.imported_at,
+        };
+
+        store::assert_triples(conn, &upgrade.to_triples(), origin)?;
+        Ok(upgrade)
+    }
+
+    /// Get an upgrade report by IRI
+    pub fn get(conn: &Connection, iri: &str) -> Result<Self> {
+        let triples = query::get_by_entity(conn, iri)?.triples;
+        let is_upgrade = triples.iter()
+            .any(|t| t.predicate == rdf::TYPE && t.object.as_iri() == Some(ONTOLOGY_UPGRADE));
+        if !is_upgrade {
+            return Err(OwlError::NotFound(format!("Ontology upgrade {} not found", iri)));
+        }
+
+        let object_of = |predicate: &str| triples.iter().find(|t| t.predicate == predicate).map(|t| &t.object);
+        let iri_of = |predicate: &str| object_of(predicate).and_then(|o| o.as_iri()).map(|s| s.to_string());
+        let integer_of = |predicate: &str| match object_of(predicate) {
+            Some(Object::Integer(i)) | Some(Object::DateTime(i)) => *i,
+            _ => 0,
+        };
+
+        Ok(Self {
+            iri: iri.to_string(),
+            source_file: object_of("foundation:sourceFile").and_then(|o| o.as_literal()).unwrap_or_default(),
+            from_release: iri_of("foundation:upgradedFrom"),
+            to_release: iri_of("foundation:upgradedTo").unwrap_or_default(),
+            added: integer_of("foundation:addedTripleCount"),
+            retracted: integer_of("foundation:retractedTripleCount"),
+            tx: integer_of("foundation:migrationTransaction"),
+            imported_at: integer_of("foundation:importedAt"),
+        })
+    }
+
+    /// All upgrade reports of a file, oldest first
+    pub fn for_file(conn: &Connection, source_file: &str) -> Result<Vec<Self>> {
+        let result = query::get_by_predicate_object(conn, rdf::TYPE, ONTOLOGY_UPGRADE)?;
+        let mut upgrades = result.triples.iter()
+            .map(|t| Self::get(conn, &t.subject))
+            .filter(|u| u.as_ref().map_or(true, |u| u.source_file == source_file))
+            .collect::<Result<Vec<_>>>()?;
+        upgrades.sort_by_key(|u| u.tx);
+        Ok(upgrades)
+    }
+
+    fn to_triples(&self) -> Vec<Triple> {
+        let mut triples = vec![
+            Triple::new(&self.iri, rdf::TYPE, Object::Iri(ONTOLOGY_UPGRADE.to_string())),
+            Triple::new(&self.iri, rdf::TYPE, Object::Iri("owl:NamedIndividual".to_string())),
+            Triple::new(&self.iri, rdfs::LABEL, string(&format!("{} upgrade (+{} -{})", self.source_file, self.added, self.retracted))),
+            Triple::new(&self.iri, "foundation:sourceFile", string(&self.source_file)),
+            Triple::new(&self.iri, "foundation:upgradedTo", Object::Iri(self.to_release.clone())),
+            Triple::new(&self.iri, "foundation:addedTripleCount", Object::Integer(self.added)),
+            Triple::new(&self.iri, "foundation:retractedTripleCount", Object::Integer(self.retracted)),
+            Triple::new(&self.iri, "foundation:migrationTransaction", Object::Integer(self.tx)),
+            Triple::new(&self.iri, "foundation:importedAt", Object::DateTime(self.imported_at)),
+        ];
+
+        if let Some(from) = &self.from_release {
+            triples.push(Triple::new(&self.iri, "foundation:upgradedFrom", Object::Iri(from.clone())));
+        }
+
+        triples
+    }
+}
+
+/// Summary of one pass of importing ontology files
+#[derive(Debug, Clone, PartialEq)]
+pub struct ImportRun {
+    pub iri: String,
+    pub files: Vec<String>,
+    pub facts: i64,
+    pub duration_ms: i64,
+    pub warnings: Vec<String>,
+    pub imported_at: i64,
+}
+
+impl ImportRun {
+    /// Record a run that finished now
+    pub fn record(
+        conn: &mut Connection,
+        files: Vec<String>,
+        facts: i64,
+        duration_ms: i64,
+        warnings: Vec<String>,
+        origin: &str,
+    ) -> Result<Self> {
+        let imported_at = chrono::Utc::now().timestamp_millis();
+        let run = Self {
+            iri: format!("foundation:ImportRun_{}", imported_at),
+            files,
+            facts,
+            duration_ms,
+            warnings,
+            imported_at,
+        };
+
+        store::assert_triples(conn, &run.to_triples(), origin)?;
+        Ok(run)
+    }
+
+    /// Get a run by IRI
+    pub fn get(conn: &Connection, iri: &str) -> Result<Self> {
+        let triples = query::get_by_entity(conn, iri)?.triples;
+        let is_run = triples.iter()
+            .any(|t| t.predicate == rdf::TYPE && t.object.as_iri() == Some(IMPORT_RUN));
+        if !is_run {
+            return Err(OwlError::NotFound(format!("Import run {} not found", iri)));
+        }
+
+        let object_of = |predicate: &str| triples.iter().find(|t| t.predicate == predicate).map(|t| &t.object);
+        let literals_of = |predicate: &str| {
+            let mut values: Vec<String> = triples.iter()
+                .filter(|t| t.predicate == predicate)
+                .filter_map(|t| t.object.as_literal())
+                .collect();
+            values.sort();
+            values
+        };
+        let integer_of = |predicate: &str| match object_of(predicate) {
+            Some(Object::Integer(i)) | Some(Object::DateTime(i)) => *i,
+            _ => 0,
+        };
+
+        Ok(Self {
+            iri: iri.to_string(),
+            files: literals_of("foundation:importedFile"),
+            facts: integer_of("foundation:factCount"),
+            duration_ms: integer_of("foundation:durationMillis"),
+            warnings: literals_of("foundation:importWarning"),
+            imported_at: integer_of("foundation:importedAt"),
+        })
+    }
+
+    /// Most recent run
+    pub fn latest(conn: &Connection) -> Result<Option<Self>> {
+        let result = query::get_by_predicate_object(conn, rdf::TYPE, IMPORT_RUN)?;
+        let mut runs = result.triples.iter()
+            .map(|t| Self::get(conn, &t.subject))
+            .collect::<Result<Vec<_>>>()?;
+        runs.sort_by_key(|r| r.imported_at);
+        Ok(runs.pop())
+    }
+
+    /// One-line summary, e.g. "12,431 facts from 9 ontologies loaded in 41s, 3 warnings"
+    pub fn summary(&self) -> String {
+        Self::summarize(self.facts, self.files.len(), self.duration_ms, self.warnings.len())
+    }
+
+    /// One-line summary of a run with these counts
+    pub fn summarize(facts: i64, ontologies: usize, duration_ms: i64, warnings: usize) -> String {
+        let mut summary = format!(
+            "{} facts from {} {} loaded in {}s",
+            thousands(facts),
+            ontologies,
+            if ontologies == 1 { "ontology" } else { "ontologies" },
+            (duration_ms + 500) / 1000,
+        );
+        if warnings > 0 {
+            summary.push_str(&format!(", {} warning{}", warnings, if warnings == 1 { "" } else { "s" }));
+        }
+        summary
+    }
+
+    fn to_triples(&self) -> Vec<Triple> {
+        let mut triples = vec![
+            Triple::new(&self.iri, rdf::TYPE, Object::Iri(IMPORT_RUN.to_string())),
+            Triple::new(&self.iri, rdf::TYPE, Object::Iri("owl:NamedIndividual".to_string())),
+            Triple::new(&self.iri, rdfs::LABEL, string(&self.summary())),
+            Triple::new(&self.iri, "foundation:ontologyCount", Object::Integer(self.files.len() as i64)),
+            Triple::new(&self.iri, "foundation:factCount", Object::Integer(self.facts)),
+            Triple::new(&self.iri, "foundation:durationMillis", Object::Integer(self.duration_ms)),
+            Triple::new(&self.iri, "foundation:importedAt", Object::DateTime(self.imported_at)),
+        ];
+        triples.extend(self.files.iter().map(|f| Triple::new(&self.iri, "foundation:importedFile", string(f))));
+        triples.extend(self.warnings.iter().map(|w| Triple::new(&self.iri, "foundation:importWarning", string(w))));
+
+        triples
+    }
+}
+
+/// An integer with thousands separators (12431 -> "12,431")
+fn thousands(n: i64) -> String {
+    let digits = n.unsigned_abs().to_string();
+    let mut grouped = String::new();
+    for (i, digit) in digits.chars().enumerate() {
+        if i > 0 && (digits.len() - i).is_multiple_of(3) {
+            grouped.push(',');
+        }
+        grouped.push(digit);
+    }
+    if n < 0 { format!("-{}", grouped) } else { grouped }
+}
+
+/// Build a release IRI from the file name and import transaction
+fn release_iri(source_file: &str, tx: i64) -> String {
+    format!("foundation:OntologyRelease_{}_{}", file_stem(source_file), tx)
+}
+
+/// Build an upgrade report IRI from the file name and migration transaction
+fn upgrade_iri(source_file: &str, tx: i64) -> String {
+    format!("foundation:OntologyUpgrade_{}_{}", file_stem(source_file), tx)
+}
+
+/// File name without extension, reduced to IRI-safe characters
+fn file_stem(source_file: &str) -> String {
+    source_file
+        .rsplit_once('.')
+        .map(|(stem, _)| stem)
+        .unwrap_or(source_file)
+        .chars()
+        .map(|c| if c.is_alphanumeric() { c } else { '_' })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::eavto::test_helpers::setup_test_db;
+
+    fn import(conn: &mut Connection, triples: &[Triple]) -> i64 {
+        store::assert_triples(conn, triples, "test").unwrap()
+    }
+
+    #[test]
+    fn test_release_iri() {
+        assert_eq!(release_iri("Computer.ttl", 7), "foundation:OntologyRelease_Computer_7");
+        assert_eq!(release_iri("qudt-units.ttl", 3), "foundation:OntologyRelease_qudt_units_3");
+    }
+
+    #[test]
+    fn test_record_and_get_release() {
+        let mut conn = setup_test_db();
+        let tx = import(&mut conn, &[
+            Triple::new("http://example.org/onto", rdf::TYPE, Object::Iri(owl::ONTOLOGY.to_string())),
+            Triple::new("http://example.org/onto", owl::VERSION_IRI, Object::Iri("http://example.org/onto/2.0".to_string())),
+            Triple::new("foundation:Thing", rdf::TYPE, Object::Iri(owl::CLASS.to_string())),
+        ]);
+
+        let release = OntologyRelease::record(&mut conn, "onto.owl", "abc123", tx, 3, "test").unwrap();
+        assert_eq!(release.ontology.as_deref(), Some("http://example.org/onto"));
+        assert_eq!(release.version_iri.as_deref(), Some("http://example.org/onto/2.0"));
+        assert_eq!(release.previous_release, None);
+
+        let loaded = OntologyRelease::get(&conn, &release.iri).unwrap();
+        assert_eq!(loaded, release);
+    }
+
+    #[test]
+    fn test_reimport_links_previous_release() {
+        let mut conn = setup_test_db();
+        let tx1 = import(&mut conn, &[Triple::new("foundation:A", rdf::TYPE, Object::Iri(owl::CLASS.to_string()))]);
+        let first = OntologyRelease::record(&mut conn, "A.ttl", "v1", tx1, 1, "test").unwrap();
+
+        let tx2 = import(&mut conn, &[Triple::new("foundation:B", rdf::TYPE, Object::Iri(owl::CLASS.to_string()))]);
+        let second = OntologyRelease::record(&mut conn, "A.ttl", "v2", tx2, 1, "test").unwrap();
+
+        assert_eq!(second.previous_release, Some(first.iri.clone()));
+        assert_eq!(OntologyRelease::for_file(&conn, "A.ttl").unwrap().len(), 2);
+        assert_eq!(OntologyRelease::latest_for_file(&conn, "A.ttl").unwrap().unwrap().iri, second.iri);
+    }
+
+    #[test]
+    fn test_release_keeps_header_from_previous_release() {
+        let mut conn = setup_test_db();
+        let tx1 = import(&mut conn, &[
+            Triple::new("http://example.org/onto", rdf::TYPE, Object::Iri(owl::ONTOLOGY.to_string())),
+            Triple::new("foundation:A", rdf::TYPE, Object::Iri(owl::CLASS.to_string())),
+        ]);
+        OntologyRelease::record(&mut conn, "A.ttl", "v1", tx1, 2, "test").unwrap();
+
+        // An upgrade transaction that doesn't touch the header
+        let tx2 = import(&mut conn, &[Triple::new("foundation:B", rdf::TYPE, Object::Iri(owl::CLASS.to_string()))]);
+        let second = OntologyRelease::record(&mut conn, "A.ttl", "v2", tx2, 3, "test").unwrap();
+        assert_eq!(second.ontology.as_deref(), Some("http://example.org/onto"));
+    }
+
+    #[test]
+    fn test_record_upgrade() {
+        let mut conn = setup_test_db();
+        let tx1 = import(&mut conn, &[Triple::new("foundation:A", rdf::TYPE, Object::Iri(owl::CLASS.to_string()))]);
+        let first = OntologyRelease::record(&mut conn, "A.ttl", "v1", tx1, 1, "test").unwrap();
+
+        let tx2 = import(&mut conn, &[Triple::new("foundation:B", rdf::TYPE, Object::Iri(owl::CLASS.to_string()))]);
+        let second = OntologyRelease::record(&mut conn, "A.ttl", "v2", tx2, 2, "test").unwrap();
+
+        let upgrade = OntologyUpgrade::record(&mut conn, &second, 1, 0, "test").unwrap();
+        assert_eq!(upgrade.iri, format!("foundation:OntologyUpgrade_A_{}", tx2));
+        assert_eq!(upgrade.from_release, Some(first.iri));
+        assert_eq!(upgrade.to_release, second.iri);
+
+        assert_eq!(OntologyUpgrade::get(&conn, &upgrade.iri).unwrap(), upgrade);
+        assert_eq!(OntologyUpgrade::for_file(&conn, "A.ttl").unwrap(), vec![upgrade]);
+        assert!(OntologyUpgrade::for_file(&conn, "B.ttl").unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_release_for_transaction() {
+        let mut conn = setup_test_db();
+        let tx = import(&mut conn, &[Triple::new("foundation:A", rdf::TYPE, Object::Iri(owl::CLASS.to_string()))]);
+        let release = OntologyRelease::record(&mut conn, "A.ttl", "v1", tx, 1, "test").unwrap();
+
+        let found = OntologyRelease::for_transaction(&conn, tx).unwrap().unwrap();
+        assert_eq!(found.iri, release.iri);
+        assert!(OntologyRelease::for_transaction(&conn, tx + 100).unwrap().is_none());
+    }
+
+    #[test]
+    fn test_record_import_run() {
+        let mut conn = setup_test_db();
+        assert!(ImportRun::latest(&conn).unwrap().is_none());
+
+        let files = vec!["Computer.ttl".to_string(), "Person.ttl".to_string()];
+        let warnings = vec!["Failed to import Broken.ttl: ParseError".to_string()];
+        let run = ImportRun::record(&mut conn, files, 12_431, 41_200, warnings, "test").unwrap();
+        assert_eq!(run.summary(), "12,431 facts from 2 ontologies loaded in 41s, 1 warning");
+
+        assert_eq!(ImportRun::get(&conn, &run.iri).unwrap(), run);
+        assert_eq!(ImportRun::latest(&conn).unwrap(), Some(run));
+        assert_eq!(ImportRun::summarize(57, 1, 300, 0), "57 facts from 1 ontology loaded in 0s");
+    }
+}
@@ -0,0 +1,251 @@
+// ============================================================================
+// OWL Palette - Command Palette Actions
+// ============================================================================
+// Actions offered by the frontend command palette:
+// - Commands: app features from a catalog; some apply only to the entity in
+//   context (any entity, or instances of a class, by RDFS inference)
+// - Queries: saved queries (foundation:SavedQuery), run in the console
+// - Navigation: entities opened from the palette before, and the entities
+//   the context links to
+//
+// Each use of an action is counted on a foundation:PaletteUsage entity, so
+// the ranking (most used first, then most recently used) is data that syncs
+// like anything else.
+// ============================================================================
+
+use rusqlite::Connection;
+use std::collections::{HashMap, HashSet};
+use crate::eavto::{query, store, Object, Triple};
+use crate::owl::{console, inference, Thing, Result, hash, OwlError, vocabulary::rdf};
+
+pub const PALETTE_USAGE: &str = "foundation:PaletteUsage";
+pub const PALETTE_ACTION: &str = "foundation:paletteAction";
+pub const USE_COUNT: &str = "foundation:useCount";
+pub const LAST_USED: &str = "foundation:lastUsed";
+
+/// Navigation targets remembered from earlier uses
+const NAVIGATION_HISTORY: usize = 50;
+
+/// Entity a command applies to
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Scope {
+    Global,
+    Entity, // Any entity in context
+    Instance(&'static str), // Instances of a class
+}
+
+/// Palette commands: (label, Tauri command, scope)
+const COMMANDS: &[(&str, &str, Scope)] = &[
+    ("Search", "entity__search", Scope::Global),
+    ("Open query console", "console__execute", Scope::Global),
+    ("Show history", "history__feed", Scope::Global),
+    ("Undo a change", "transaction__undo", Scope::Global),
+    ("Import ontology file", "ontology__import_file", Scope::Global),
+    ("Import from another FOUNDATION database", "import__foundation_db", Scope::Global),
+    ("Import bank statement", "finance__import", Scope::Global),
+    ("Import photo folder", "photos__import", Scope::Global),
+    ("Review duplicate photos", "photos__duplicates", Scope::Global),
+    ("Attach file", "attachment__add", Scope::Entity),
+    ("Show parts", "parts__of", Scope::Entity),
+    ("Add affiliation", "person__add_affiliation", Scope::Instance("foundation:Person")),
+    ("Create instance", "entity__create", Scope::Instance("owl:Class")),
+    ("Edit all instances", "bulk__update", Scope::Instance("owl:Class")),
+    ("Export class", "class__export", Scope::Instance("owl:Class")),
+];
+
+/// Kind of palette action
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ActionKind {
+    Command,
+    Query,
+    Navigate,
+}
+
+impl ActionKind {
+    fn prefix(self) -> &'static str {
+        match self {
+            ActionKind::Command => "command",
+            ActionKind::Query => "query",
+            ActionKind::Navigate => "navigate",
+        }
+    }
+}
+
+/// An action offered by the palette
+#[derive(Debug, Clone, PartialEq)]
+pub struct PaletteAction {
+    pub id: String, // kind:target, e.g. "command:entity__search"
+    pub kind: ActionKind,
+    pub label: String,
+    pub target: String, // Tauri command, saved query IRI or entity IRI
+    pub uses: i64,
+    pub last_used: Option<i64>, // Unix ms
+}
+
+/// Actions available in a context (an entity IRI), most used first
+///
+/// Context commands come before global ones among actions used equally often.
+pub fn actions(conn: &Connection, context: Option<&str>) -> Result<Vec<PaletteAction>> {
+    let usage = usage(conn)?;
+    let mut actions = Vec::new();
+    let mut seen = HashSet::new();
+    let mut add = |kind: ActionKind, label: String, target: String| {
+        let id = format!("{}:{}", kind.prefix(), target);
+        if seen.insert(id.clone()) {
+            let (uses, last_used) = usage.get(&id).copied().map_or((0, None), |(uses, last)| (uses, Some(last)));
+            actions.push(PaletteAction { id, kind, label, target, uses, last_used });
+        }
+    };
+
+    if let Some(context) = context {
+        for (label, command, scope) in COMMANDS {
+            let applies = match scope {
+                Scope::Global => false,
+                Scope::Entity => true,
+                Scope::Instance(class) => inference::is_instance_of(conn, context, class)?,
+            };
+            if applies {
+                add(ActionKind::Command, label.to_string(), command.to_string());
+            }
+        }
+    }
+    for (label, command, scope) in COMMANDS {
+        if *scope == Scope::Global {
+            add(ActionKind::Command, label.to_string(), command.to_string());
+        }
+    }
+
+    for saved in console::favorites(conn)? {
+        add(ActionKind::Query, saved.label, saved.iri);
+    }
+
+    if let Some(context) = context {
+        for triple in query::get_by_entity(conn, context)?.triples {
+            if let Some(target) = triple.object.as_iri().filter(|iri| triple.predicate != rdf::TYPE && !iri.starts_with("_:")) {
+                add(ActionKind::Navigate, Thing::get(conn, target).label, target.to_string());
+            }
+        }
+    }
+    let mut visited: Vec<(&String, &(i64, i64))> = usage.iter()
+        .filter(|(id, _)| id.starts_with("navigate:"))
+        .collect();
+    visited.sort_by(|a, b| b.1.cmp(a.1).then_with(|| a.0.cmp(b.0)));
+    for (id, _) in visited.into_iter().take(NAVIGATION_HISTORY) {
+        let target = &id["navigate:".len()..];
+        add(ActionKind::Navigate, Thing::get(conn, target).label, target.to_string());
+    }
+
+    // Stable, so equally used actions keep their order
+    actions.sort_by(|a, b| b.uses.cmp(&a.uses).then_with(|| b.last_used.cmp(&a.last_used)));
+    Ok(actions)
+}
+
+/// Count a use of an action; returns the transaction ID
+pub fn record_use(conn: &mut Connection, action: &str, origin: &str) -> Result<i64> {
+    let valid = [ActionKind::Command, ActionKind::Query, ActionKind::Navigate].iter()
+        .any(|kind| action.strip_prefix(kind.prefix()).and_then(|rest| rest.strip_prefix(':')).is_some_and(|t| !t.is_empty()));
+    if !valid {
+        return Err(OwlError::ValidationError(format!("Invalid palette action: {}", action)));
+    }
+
+    let iri = format!("foundation:PaletteUsage_{}", hash(action));
+    let existing = query::get_by_entity(conn, &iri)?.triples;
+    let uses = existing.iter()
+        .find(|t| t.predicate == USE_COUNT)
+        .and_then(|t| match t.object { Object::Integer(n) => Some(n), _ => None })
+        .unwrap_or(0);
+
+    let mut additions = vec![
+        Triple::new(&iri, USE_COUNT, Object::Integer(uses + 1)),
+        Triple::new(&iri, LAST_USED, Object::DateTime(store::now_millis())),
+    ];
+    if existing.is_empty() {
+        additions.push(Triple::new(&iri, rdf::TYPE, Object::Iri(PALETTE_USAGE.to_string())));
+        additions.push(Triple::new(&iri, PALETTE_ACTION, Object::Literal {
+            value: action.to_string(),
+            datatype: Some("xsd:string".to_string()),
+            language: None,
+        }));
+    }
+    let retractions: Vec<Triple> = existing.into_iter()
+        .filter(|t| t.predicate == USE_COUNT || t.predicate == LAST_USED)
+        .collect();
+
+    Ok(store::apply_changes(conn, &additions, &retractions, origin)?)
+}
+
+/// Use count and last use time of every action used before, by action ID
+fn usage(conn: &Connection) -> Result<HashMap<String, (i64, i64)>> {
+    let mut usage = HashMap::new();
+    for triple in query::get_by_predicate(conn, PALETTE_ACTION)?.triples {
+        let Some(action) = triple.object.as_literal() else { continue };
+        let mut uses = 0;
+        let mut last_used = 0;
+        for t in query::get_by_entity(conn, &triple.subject)?.triples {
+            match (t.predicate.as_str(), &t.object) {
+                (USE_COUNT, Object::Integer(n)) => uses = *n,
+                (LAST_USED, Object::DateTime(ms)) => last_used = *ms,
+                _ => {}
+            }
+        }
+        usage.insert(action, (uses, last_used));
+    }
+    Ok(usage)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::eavto::test_helpers::setup_test_db;
+    use crate::owl::vocabulary::rdfs;
+
+    fn setup(conn: &mut Connection) {
+        store::assert_triples(conn, &[
+            Triple::new("foundation:Employee", rdfs::SUB_CLASS_OF, Object::Iri("foundation:Person".to_string())),
+            Triple::new("foundation:Ana", rdf::TYPE, Object::Iri("foundation:Employee".to_string())),
+            Triple::new("foundation:Ana", "foundation:worksFor", Object::Iri("foundation:Acme".to_string())),
+        ], "test").unwrap();
+        console::save_favorite(conn, "People", "SELECT ?p WHERE { ?p a foundation:Person . }", "test").unwrap();
+    }
+
+    fn ids(actions: &[PaletteAction]) -> Vec<&str> {
+        actions.iter().map(|a| a.id.as_str()).collect()
+    }
+
+    #[test]
+    fn test_actions_in_context() {
+        let mut conn = setup_test_db();
+        setup(&mut conn);
+
+        let global = actions(&conn, None).unwrap();
+        assert_eq!(global[0].id, "command:entity__search");
+        assert!(!ids(&global).contains(&"command:person__add_affiliation"));
+        assert!(global.iter().any(|a| a.kind == ActionKind::Query && a.label == "People"));
+
+        // Ana is a Person by inference, and links to Acme
+        let ana = actions(&conn, Some("foundation:Ana")).unwrap();
+        let ana = ids(&ana);
+        assert_eq!(&ana[..3], &["command:attachment__add", "command:parts__of", "command:person__add_affiliation"]);
+        assert!(!ana.contains(&"command:bulk__update"));
+        assert!(ana.contains(&"navigate:foundation:Acme"));
+    }
+
+    #[test]
+    fn test_ranked_by_usage() {
+        let mut conn = setup_test_db();
+        setup(&mut conn);
+
+        record_use(&mut conn, "command:photos__import", "test").unwrap();
+        record_use(&mut conn, "navigate:foundation:Ana", "test").unwrap();
+        record_use(&mut conn, "navigate:foundation:Ana", "test").unwrap();
+
+        let ranked = actions(&conn, None).unwrap();
+        assert_eq!(&ids(&ranked)[..2], &["navigate:foundation:Ana", "command:photos__import"]);
+        assert_eq!(ranked[0].uses, 2);
+        assert!(ranked[0].last_used.is_some());
+        assert_eq!(query::get_by_predicate_object(&conn, rdf::TYPE, PALETTE_USAGE).unwrap().triples.len(), 2);
+
+        assert!(record_use(&mut conn, "launch:rockets", "test").is_err());
+        assert!(record_use(&mut conn, "command:", "test").is_err());
+    }
+}
@@ -0,0 +1,166 @@
+// ============================================================================
+// OWL Parthood - Parts, Wholes and Shopping Lists
+// ============================================================================
+// Part-whole queries over the BFO relations (obo:BFO_0000050 part of,
+// obo:BFO_0000051 has part), answered with the transitive closure
+// (owl::closure): the parts of a meal plan include the ingredients of the
+// recipes of its recipes.
+//
+// A shopping list adds up the foundation:Ingredient parts of some wholes per
+// product and unit, and takes off the product's quantity on hand when it's
+// in the same unit. Each part counts once per whole, however many paths lead
+// to it.
+// ============================================================================
+
+use rusqlite::Connection;
+use std::collections::BTreeMap;
+use crate::eavto::{query, Object};
+use crate::owl::{closure::{self, Reached}, Result, vocabulary::rdf};
+
+pub const PART_OF: &str = "obo:BFO_0000050";
+pub const HAS_PART: &str = "obo:BFO_0000051";
+pub const INGREDIENT: &str = "foundation:Ingredient";
+pub const INGREDIENT_PRODUCT: &str = "foundation:ingredientProduct";
+pub const QUANTITY: &str = "foundation:quantity";
+pub const QUANTITY_UNIT: &str = "foundation:quantityUnit";
+pub const QUANTITY_ON_HAND: &str = "foundation:quantityOnHand";
+
+/// A product to have for some wholes
+#[derive(Debug, Clone, PartialEq)]
+pub struct ShoppingItem {
+    pub product: String,
+    pub unit: Option<String>, // None: a count
+    pub needed: f64,
+    pub on_hand: f64, // In the same unit (0 when stocked in another unit)
+    pub to_buy: f64,
+}
+
+/// All parts of a whole, nearest first
+pub fn parts(conn: &Connection, whole: &str) -> Result<Vec<Reached>> {
+    closure::transitive_closure(conn, whole, HAS_PART)
+}
+
+/// All wholes a part belongs to, nearest first
+pub fn wholes(conn: &Connection, part: &str) -> Result<Vec<Reached>> {
+    closure::transitive_closure(conn, part, PART_OF)
+}
+
+/// Products needed by the ingredients of some wholes, less what's on hand
+pub fn shopping_list(conn: &Connection, wholes: &[String]) -> Result<Vec<ShoppingItem>> {
+    let mut needed: BTreeMap<(String, Option<String>), f64> = BTreeMap::new();
+    for whole in wholes {
+        for part in parts(conn, whole)? {
+            let is_ingredient = query::get_by_entity_predicate(conn, &part.iri, rdf::TYPE)?.triples.iter()
+                .any(|t| t.object.as_iri() == Some(INGREDIENT));
+            if !is_ingredient {
+                continue;
+            }
+            let Some(product) = iri_value(conn, &part.iri, INGREDIENT_PRODUCT)? else { continue };
+            let quantity = number_value(conn, &part.iri, QUANTITY)?.unwrap_or(1.0);
+            let unit = iri_value(conn, &part.iri, QUANTITY_UNIT)?;
+            *needed.entry((product, unit)).or_default() += quantity;
+        }
+    }
+
+    let mut items = Vec::new();
+    for ((product, unit), needed) in needed {
+        let on_hand = if iri_value(conn, &product, QUANTITY_UNIT)? == unit {
+            number_value(conn, &product, QUANTITY_ON_HAND)?.unwrap_or(0.0)
+        } else {
+            0.0
+        };
+        items.push(ShoppingItem { to_buy: (needed - on_hand).max(0.0), product, unit, needed, on_hand });
+    }
+    Ok(items)
+}
+
+fn iri_value(conn: &Connection, entity: &str, property: &str) -> Result<Option<String>> {
+    Ok(query::get_by_entity_predicate(conn, entity, property)?.triples.first()
+        .and_then(|t| t.object.as_iri().map(String::from)))
+}
+
+fn number_value(conn: &Connection, entity: &str, property: &str) -> Result<Option<f64>> {
+    Ok(query::get_by_entity_predicate(conn, entity, property)?.triples.first()
+        .and_then(|t| match &t.object {
+            Object::Number(n) => Some(*n),
+            Object::Integer(i) => Some(*i as f64),
+            other => other.as_literal()?.parse().ok(),
+        }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::eavto::{store, test_helpers::setup_test_db, Triple};
+    use crate::owl::vocabulary::owl;
+
+    fn link(subject: &str, predicate: &str, object: &str) -> Triple {
+        Triple::new(subject, predicate, Object::Iri(object.to_string()))
+    }
+
+    fn ingredient(iri: &str, product: &str, quantity: f64, unit: Option<&str>, whole: &str) -> Vec<Triple> {
+        let mut triples = vec![
+            link(iri, rdf::TYPE, INGREDIENT),
+            link(iri, INGREDIENT_PRODUCT, product),
+            Triple::new(iri, QUANTITY, Object::Number(quantity)),
+            link(iri, PART_OF, whole),
+        ];
+        triples.extend(unit.map(|u| link(iri, QUANTITY_UNIT, u)));
+        triples
+    }
+
+    fn setup(conn: &mut Connection) {
+        let mut triples = vec![
+            link(PART_OF, rdf::TYPE, owl::TRANSITIVE_PROPERTY),
+            link(HAS_PART, rdf::TYPE, owl::TRANSITIVE_PROPERTY),
+            link(PART_OF, owl::INVERSE_OF, HAS_PART),
+            // Week plan has pizza and bread; pizza has dough
+            link("foundation:Week", HAS_PART, "foundation:Pizza"),
+            link("foundation:Week", HAS_PART, "foundation:Bread"),
+            link("foundation:Dough", PART_OF, "foundation:Pizza"),
+            // 2 kg of flour on hand
+            link("foundation:Flour", QUANTITY_UNIT, "unit:KiloGM"),
+            Triple::new("foundation:Flour", QUANTITY_ON_HAND, Object::Number(2.0)),
+        ];
+        triples.extend(ingredient("foundation:DoughFlour", "foundation:Flour", 0.5, Some("unit:KiloGM"), "foundation:Dough"));
+        triples.extend(ingredient("foundation:BreadFlour", "foundation:Flour", 2.0, Some("unit:KiloGM"), "foundation:Bread"));
+        triples.extend(ingredient("foundation:Cheese", "foundation:Mozzarella", 200.0, Some("unit:GM"), "foundation:Pizza"));
+        triples.extend(ingredient("foundation:Eggs", "foundation:Egg", 2.0, None, "foundation:Bread"));
+        store::assert_triples(conn, &triples, "test").unwrap();
+    }
+
+    #[test]
+    fn test_parts_and_wholes() {
+        let mut conn = setup_test_db();
+        setup(&mut conn);
+
+        let pizza: Vec<String> = parts(&conn, "foundation:Pizza").unwrap().into_iter().map(|r| r.iri).collect();
+        assert_eq!(pizza.len(), 3);
+        assert!(pizza.contains(&"foundation:DoughFlour".to_string()));
+
+        let flour_in: Vec<(String, usize)> = wholes(&conn, "foundation:DoughFlour").unwrap().into_iter().map(|r| (r.iri, r.depth)).collect();
+        assert_eq!(flour_in, vec![
+            ("foundation:Dough".to_string(), 1),
+            ("foundation:Pizza".to_string(), 2),
+            ("foundation:Week".to_string(), 3),
+        ]);
+    }
+
+    #[test]
+    fn test_shopping_list_sums_and_subtracts_stock() {
+        let mut conn = setup_test_db();
+        setup(&mut conn);
+
+        let list = shopping_list(&conn, &["foundation:Week".to_string()]).unwrap();
+        let item = |product: &str| list.iter().find(|i| i.product == product).unwrap().clone();
+
+        let flour = item("foundation:Flour");
+        assert_eq!((flour.needed, flour.on_hand, flour.to_buy), (2.5, 2.0, 0.5));
+        assert_eq!(item("foundation:Mozzarella").to_buy, 200.0);
+        assert_eq!((item("foundation:Egg").unit, item("foundation:Egg").to_buy), (None, 2.0));
+
+        // Two pizzas need twice the cheese
+        let two = shopping_list(&conn, &["foundation:Pizza".to_string(), "foundation:Pizza".to_string()]).unwrap();
+        assert_eq!(two.iter().find(|i| i.product == "foundation:Mozzarella").unwrap().needed, 400.0);
+    }
+}
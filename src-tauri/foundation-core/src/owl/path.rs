@@ -0,0 +1,140 @@
+// ============================================================================
+// OWL Path - Shortest Path Between Two Entities
+// ============================================================================
+// How two entities are related: the fewest IRI links between them, followed
+// in either direction. The search grows from both ends at once, a level at
+// a time from the smaller side, so it reads far fewer entities than a
+// search from one end when the graph branches a lot.
+//
+// RDF, RDFS, OWL and XSD terms are not passed through: every class is an
+// owl:Class, and a path through it says nothing about how two things relate.
+// ============================================================================
+
+use rusqlite::Connection;
+use std::collections::HashMap;
+use crate::owl::neighborhood::{links_of, NeighborLink};
+use crate::owl::Result;
+
+/// Namespaces of the vocabulary terms paths don't pass through
+const VOCABULARY: &[&str] = &["rdf:", "rdfs:", "owl:", "xsd:"];
+
+/// A path between two entities
+#[derive(Debug, Clone, PartialEq)]
+pub struct Path {
+    pub nodes: Vec<String>,       // From the first entity to the second
+    pub links: Vec<NeighborLink>, // links[i] joins nodes[i] and nodes[i + 1], in its stored direction
+}
+
+/// Where a search from one end reached an entity: (previous entity, link to it)
+type Parents = HashMap<String, Option<(String, NeighborLink)>>;
+
+/// The shortest path between two entities, if they're within `max_depth`
+/// links of each other
+pub fn find_path(conn: &Connection, from: &str, to: &str, max_depth: usize) -> Result<Option<Path>> {
+    if from == to {
+        return Ok(Some(Path { nodes: vec![from.to_string()], links: Vec::new() }));
+    }
+
+    let mut forward: Parents = HashMap::from([(from.to_string(), None)]);
+    let mut backward: Parents = HashMap::from([(to.to_string(), None)]);
+    let mut forward_frontier = vec![from.to_string()];
+    let mut backward_frontier = vec![to.to_string()];
+
+    for _ in 0..max_depth {
+        if forward_frontier.is_empty() || backward_frontier.is_empty() {
+            break;
+        }
+
+        // Grow the smaller side by one level
+        let from_start = forward_frontier.len() <= backward_frontier.len();
+        let (parents, frontier, other) = if from_start {
+            (&mut forward, &mut forward_frontier, &backward)
+        } else {
+            (&mut backward, &mut backward_frontier, &forward)
+        };
+
+        let mut next = Vec::new();
+        let mut meeting = None;
+        for node in frontier.iter() {
+            for link in links_of(conn, node)? {
+                let neighbor = if link.source == *node { &link.target } else { &link.source };
+                if parents.contains_key(neighbor) || (is_vocabulary(neighbor) && !other.contains_key(neighbor)) {
+                    continue;
+                }
+                parents.insert(neighbor.clone(), Some((node.clone(), link.clone())));
+                if other.contains_key(neighbor) {
+                    meeting.get_or_insert_with(|| neighbor.clone());
+                }
+                next.push(neighbor.clone());
+            }
+        }
+
+        if let Some(meeting) = meeting {
+            return Ok(Some(join(&forward, &backward, &meeting)));
+        }
+        *frontier = next;
+    }
+
+    Ok(None)
+}
+
+/// The path through the entity where both searches met
+fn join(forward: &Parents, backward: &Parents, meeting: &str) -> Path {
+    let mut nodes = vec![meeting.to_string()];
+    let mut links = Vec::new();
+
+    let mut node = meeting;
+    while let Some(Some((previous, link))) = forward.get(node) {
+        nodes.insert(0, previous.clone());
+        links.insert(0, link.clone());
+        node = previous;
+    }
+    let mut node = meeting;
+    while let Some(Some((next, link))) = backward.get(node) {
+        nodes.push(next.clone());
+        links.push(link.clone());
+        node = next;
+    }
+
+    Path { nodes, links }
+}
+
+fn is_vocabulary(iri: &str) -> bool {
+    VOCABULARY.iter().any(|namespace| iri.starts_with(namespace))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::eavto::{store, test_helpers::setup_test_db, Object, Triple};
+    use crate::owl::vocabulary::{owl, rdf};
+
+    fn iri(value: &str) -> Object {
+        Object::Iri(value.to_string())
+    }
+
+    #[test]
+    fn test_find_path_in_either_direction() {
+        let mut conn = setup_test_db();
+        store::assert_triples(&mut conn, &[
+            Triple::new("foundation:alice", rdf::TYPE, iri(owl::THING)),
+            Triple::new("foundation:carol", rdf::TYPE, iri(owl::THING)),
+            Triple::new("foundation:alice", "foundation:worksFor", iri("foundation:acme")),
+            Triple::new("foundation:bob", "foundation:worksFor", iri("foundation:acme")),
+            Triple::new("foundation:bob", "foundation:knows", iri("foundation:carol")),
+        ], "test").unwrap();
+
+        let path = find_path(&conn, "foundation:alice", "foundation:carol", 4).unwrap().unwrap();
+        assert_eq!(path.nodes, ["foundation:alice", "foundation:acme", "foundation:bob", "foundation:carol"]);
+        let predicates: Vec<&str> = path.links.iter().map(|link| link.predicate.as_str()).collect();
+        assert_eq!(predicates, ["foundation:worksFor", "foundation:worksFor", "foundation:knows"]);
+        assert_eq!(path.links[1].source, "foundation:bob"); // Followed backwards
+
+        // Not through owl:Thing, nor further than max_depth
+        assert!(find_path(&conn, "foundation:alice", "foundation:carol", 2).unwrap().is_none());
+        assert!(find_path(&conn, "foundation:alice", "foundation:dave", 4).unwrap().is_none());
+
+        let same = find_path(&conn, "foundation:bob", "foundation:bob", 0).unwrap().unwrap();
+        assert!(same.links.is_empty());
+    }
+}
@@ -0,0 +1,451 @@
+// ============================================================================
+// OWL Perceptual Hash - Near-Duplicate Image Detection
+// ============================================================================
+// A 64-bit difference hash (dHash) of an image: the image is shrunk to 9x8
+// gray levels and each bit tells whether a pixel is brighter than its right
+// neighbour. Resized, recompressed or slightly edited copies of a photo have
+// hashes a few bits apart; different photos differ in about half the bits.
+//
+// The hash only needs a tiny gray image, so images are decoded cheaply:
+// - JPEG: only the DC coefficient of each 8x8 luma block is decoded (the
+//   block's average brightness), an image 1/8 the size without any IDCT.
+//   Baseline and extended sequential JPEGs are read; progressive ones are not
+// - PNG: decoded with the png crate and converted to luma
+// ============================================================================
+
+use std::io::Cursor;
+
+/// Hamming distance at or under which two hashes are the same picture
+pub const NEAR_DUPLICATE_DISTANCE: u32 = 6;
+
+/// A gray image, row by row
+struct Gray {
+    width: usize,
+    height: usize,
+    pixels: Vec<f64>,
+}
+
+/// Perceptual hash of an encoded JPEG or PNG image, None when the format
+/// isn't supported or the data is corrupt
+pub fn perceptual_hash(bytes: &[u8]) -> Option<u64> {
+    let gray = if bytes.starts_with(&[0xff, 0xd8]) {
+        jpeg_dc_image(bytes)?
+    } else if bytes.starts_with(b"\x89PNG") {
+        png_image(bytes)?
+    } else {
+        return None;
+    };
+    Some(dhash(&gray))
+}
+
+/// Number of differing bits between two hashes
+pub fn distance(a: u64, b: u64) -> u32 {
+    (a ^ b).count_ones()
+}
+
+/// Difference hash: 9x8 box-filtered thumbnail, one bit per horizontal
+/// gradient
+fn dhash(image: &Gray) -> u64 {
+    let thumbnail = resize(image, 9, 8);
+    let mut hash = 0u64;
+    for y in 0..8 {
+        for x in 0..8 {
+            hash <<= 1;
+            if thumbnail[y * 9 + x] > thumbnail[y * 9 + x + 1] {
+                hash |= 1;
+            }
+        }
+    }
+    hash
+}
+
+/// Area-average resize (nearest pixel when upscaling)
+fn resize(image: &Gray, width: usize, height: usize) -> Vec<f64> {
+    let mut out = vec![0.0; width * height];
+    for (ty, row) in out.chunks_mut(width).enumerate() {
+        let y0 = ty * image.height / height;
+        let y1 = ((ty + 1) * image.height / height).max(y0 + 1);
+        for (tx, value) in row.iter_mut().enumerate() {
+            let x0 = tx * image.width / width;
+            let x1 = ((tx + 1) * image.width / width).max(x0 + 1);
+            let mut sum = 0.0;
+            for y in y0..y1 {
+                sum += image.pixels[y * image.width + x0..y * image.width + x1].iter().sum::<f64>();
+            }
+            *value = sum / ((y1 - y0) * (x1 - x0)) as f64;
+        }
+    }
+    out
+}
+
+fn png_image(bytes: &[u8]) -> Option<Gray> {
+    let mut decoder = png::Decoder::new(Cursor::new(bytes));
+    decoder.set_transformations(png::Transformations::normalize_to_color8());
+    let mut reader = decoder.read_info().ok()?;
+    let mut buffer = vec![0; reader.output_buffer_size()];
+    let frame = reader.next_frame(&mut buffer).ok()?;
+    let (width, height) = (frame.width as usize, frame.height as usize);
+    let channels = frame.color_type.samples();
+    if width == 0 || height == 0 {
+        return None;
+    }
+
+    let mut pixels = Vec::with_capacity(width * height);
+    for row in buffer[..frame.line_size * height].chunks(frame.line_size) {
+        for pixel in row[..width * channels].chunks(channels) {
+            pixels.push(match channels {
+                1 | 2 => pixel[0] as f64,
+                _ => 0.299 * pixel[0] as f64 + 0.587 * pixel[1] as f64 + 0.114 * pixel[2] as f64,
+            });
+        }
+    }
+    Some(Gray { width, height, pixels })
+}
+
+// ----------------------------------------------------------------------------
+// JPEG (DC coefficients only)
+// ----------------------------------------------------------------------------
+
+/// Canonical Huffman table (JPEG Annex C/F)
+#[derive(Clone, Default)]
+struct Huffman {
+    min_code: [i32; 17],
+    max_code: [i32; 17], // -1: no codes of that length
+    offset: [usize; 17], // Index of the first symbol of each length
+    symbols: Vec<u8>,
+}
+
+impl Huffman {
+    fn new(counts: &[u8], symbols: &[u8]) -> Self {
+        let mut table = Huffman { max_code: [-1; 17], symbols: symbols.to_vec(), ..Default::default() };
+        let (mut code, mut index) = (0i32, 0usize);
+        for length in 1..=16 {
+            let count = counts[length - 1] as usize;
+            table.offset[length] = index;
+            table.min_code[length] = code;
+            if count > 0 {
+                table.max_code[length] = code + count as i32 - 1;
+            }
+            code = (code + count as i32) << 1;
+            index += count;
+        }
+        table
+    }
+}
+
+struct Component {
+    id: u8,
+    h: usize,
+    v: usize,
+}
+
+/// Bits of the entropy-coded segment, without stuffed zero bytes
+struct BitReader<'a> {
+    data: &'a [u8],
+    position: usize,
+    bits: u32,
+    count: u32,
+}
+
+impl<'a> BitReader<'a> {
+    fn bit(&mut self) -> Option<u32> {
+        if self.count == 0 {
+            let byte = *self.data.get(self.position)?;
+            if byte == 0xff {
+                // 0xFF00 is a literal 0xFF; anything else is a marker
+                if self.data.get(self.position + 1) != Some(&0) {
+                    return None;
+                }
+                self.position += 1;
+            }
+            self.position += 1;
+            self.bits = byte as u32;
+            self.count = 8;
+        }
+        self.count -= 1;
+        Some((self.bits >> self.count) & 1)
+    }
+
+    fn receive(&mut self, length: u8) -> Option<i32> {
+        let mut value = 0i32;
+        for _ in 0..length {
+            value = (value << 1) | self.bit()? as i32;
+        }
+        Some(value)
+    }
+
+    /// Receive a signed value of `length` bits (JPEG EXTEND)
+    fn receive_extend(&mut self, length: u8) -> Option<i32> {
+        if length == 0 {
+            return Some(0);
+        }
+        let value = self.receive(length)?;
+        Some(if value < 1 << (length - 1) { value - (1 << length) + 1 } else { value })
+    }
+
+    fn decode(&mut self, table: &Huffman) -> Option<u8> {
+        let mut code = 0i32;
+        for length in 1..=16 {
+            code = (code << 1) | self.bit()? as i32;
+            if code <= table.max_code[length] {
+                return table.symbols.get(table.offset[length] + (code - table.min_code[length]) as usize).copied();
+            }
+        }
+        None
+    }
+
+    /// Skip to the byte after a restart marker
+    fn restart(&mut self) -> Option<()> {
+        self.count = 0;
+        while self.data.get(self.position) != Some(&0xff) {
+            self.position += 1;
+            if self.position >= self.data.len() {
+                return None;
+            }
+        }
+        while self.data.get(self.position) == Some(&0xff) {
+            self.position += 1;
+        }
+        matches!(self.data.get(self.position)?, 0xd0..=0xd7).then(|| self.position += 1)
+    }
+}
+
+/// Image of the DC coefficients of the luma blocks of a sequential JPEG
+fn jpeg_dc_image(bytes: &[u8]) -> Option<Gray> {
+    let mut dc_tables: [Huffman; 4] = Default::default();
+    let mut ac_tables: [Huffman; 4] = Default::default();
+    let mut components: Vec<Component> = Vec::new();
+    let (mut width, mut height, mut restart_interval) = (0usize, 0usize, 0usize);
+    let mut position = 2;
+
+    loop {
+        while bytes.get(position) == Some(&0xff) && bytes.get(position + 1) == Some(&0xff) {
+            position += 1;
+        }
+        if *bytes.get(position)? != 0xff {
+            return None;
+        }
+        let marker = *bytes.get(position + 1)?;
+        let length = u16::from_be_bytes([*bytes.get(position + 2)?, *bytes.get(position + 3)?]) as usize;
+        let segment = bytes.get(position + 4..position + 2 + length)?;
+
+        match marker {
+            // Baseline and extended sequential, Huffman coded
+            0xc0 | 0xc1 => {
+                height = u16::from_be_bytes([segment[1], segment[2]]) as usize;
+                width = u16::from_be_bytes([segment[3], segment[4]]) as usize;
+                components = segment.get(6..6 + 3 * segment[5] as usize)?.chunks(3)
+                    .map(|c| Component { id: c[0], h: (c[1] >> 4).max(1) as usize, v: (c[1] & 15).max(1) as usize })
+                    .collect();
+            }
+            // Progressive, lossless, arithmetic coded: not supported
+            0xc2 | 0xc3 | 0xc5..=0xc7 | 0xc9..=0xcb | 0xcd..=0xcf => return None,
+            0xc4 => {
+                let mut rest = segment;
+                while rest.len() > 17 {
+                    let (class, id) = (rest[0] >> 4, (rest[0] & 3) as usize);
+                    let counts = &rest[1..17];
+                    let total: usize = counts.iter().map(|&c| c as usize).sum();
+                    let table = Huffman::new(counts, rest.get(17..17 + total)?);
+                    if class == 0 { dc_tables[id] = table } else { ac_tables[id] = table }
+                    rest = &rest[17 + total..];
+                }
+            }
+            0xdd => restart_interval = u16::from_be_bytes([*segment.first()?, *segment.get(1)?]) as usize,
+            0xda => {
+                let scan: Vec<(usize, u8, u8)> = segment.get(1..1 + 2 * segment[0] as usize)?.chunks(2)
+                    .filter_map(|c| components.iter().position(|k| k.id == c[0]).map(|i| (i, c[1] >> 4, c[1] & 3)))
+                    .collect();
+                // Luma is the first component; chroma-only scans are skipped
+                if scan.iter().any(|&(i, _, _)| i == 0) {
+                    return decode_scan(&bytes[position + 2 + length..], &components, &scan, (width, height), restart_interval, &dc_tables, &ac_tables);
+                }
+                position += 2 + length;
+                // Skip the entropy-coded data to the next marker
+                while position + 1 < bytes.len() && !(bytes[position] == 0xff && bytes[position + 1] != 0 && !(0xd0..=0xd7).contains(&bytes[position + 1])) {
+                    position += 1;
+                }
+                continue;
+            }
+            0xd9 => return None,
+            _ => {}
+        }
+        position += 2 + length;
+    }
+}
+
+/// Decode a scan, keeping the DC value of each luma block
+fn decode_scan(
+    data: &[u8],
+    components: &[Component],
+    scan: &[(usize, u8, u8)], // Component index, DC table, AC table
+    (width, height): (usize, usize),
+    restart_interval: usize,
+    dc_tables: &[Huffman; 4],
+    ac_tables: &[Huffman; 4],
+) -> Option<Gray> {
+    if width == 0 || height == 0 {
+        return None;
+    }
+    let h_max = components.iter().map(|c| c.h).max()?;
+    let v_max = components.iter().map(|c| c.v).max()?;
+    let luma = &components[0];
+    // Luma blocks covering the image
+    let blocks_wide = (width * luma.h).div_ceil(h_max).div_ceil(8);
+    let blocks_high = (height * luma.v).div_ceil(v_max).div_ceil(8);
+
+    // Interleaved scans code MCUs of h x v blocks per component; a scan of one
+    // component codes its blocks one by one
+    let (mcus_wide, mcus_high, layout): (usize, usize, Vec<(usize, usize, usize)>) = if scan.len() == 1 {
+        (blocks_wide, blocks_high, vec![(0, 1, 1)])
+    } else {
+        let layout = scan.iter().map(|&(i, _, _)| (i, components[i].h, components[i].v)).collect();
+        (width.div_ceil(8 * h_max), height.div_ceil(8 * v_max), layout)
+    };
+
+    let mut pixels = vec![0.0; blocks_wide * blocks_high];
+    let mut predictions = vec![0i32; components.len()];
+    let mut reader = BitReader { data, position: 0, bits: 0, count: 0 };
+
+    for mcu in 0..mcus_wide * mcus_high {
+        if restart_interval > 0 && mcu > 0 && mcu % restart_interval == 0 {
+            reader.restart()?;
+            predictions.iter_mut().for_each(|p| *p = 0);
+        }
+        let (mcu_x, mcu_y) = (mcu % mcus_wide, mcu / mcus_wide);
+        for (&(component, dc, ac), &(_, h, v)) in scan.iter().zip(&layout) {
+            for block in 0..h * v {
+                let size = reader.decode(&dc_tables[dc as usize])?;
+                predictions[component] += reader.receive_extend(size)?;
+                skip_ac(&mut reader, &ac_tables[ac as usize])?;
+
+                if component == 0 {
+                    let x = mcu_x * h + block % h;
+                    let y = mcu_y * v + block / h;
+                    if x < blocks_wide && y < blocks_high {
+                        pixels[y * blocks_wide + x] = predictions[0] as f64;
+                    }
+                }
+            }
+        }
+    }
+    Some(Gray { width: blocks_wide, height: blocks_high, pixels })
+}
+
+/// Read past the 63 AC coefficients of a block
+fn skip_ac(reader: &mut BitReader, table: &Huffman) -> Option<()> {
+    let mut k = 1;
+    while k < 64 {
+        let symbol = reader.decode(table)?;
+        let (run, size) = (symbol >> 4, symbol & 15);
+        if size == 0 {
+            if run != 15 {
+                break; // End of block
+            }
+            k += 16;
+            continue;
+        }
+        reader.receive(size)?;
+        k += run as usize + 1;
+    }
+    Some(())
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+
+    /// A grayscale baseline JPEG with flat 8x8 blocks (DC only), shaded per
+    /// block by `shade(block_x, block_y)`
+    pub(crate) fn jpeg(blocks_wide: usize, blocks_high: usize, shade: impl Fn(usize, usize) -> u8) -> Vec<u8> {
+        let mut out = vec![0xff, 0xd8];
+        let segment = |out: &mut Vec<u8>, marker: u8, body: &[u8]| {
+            out.extend([0xff, marker]);
+            out.extend(((body.len() + 2) as u16).to_be_bytes());
+            out.extend(body);
+        };
+        let mut quantization = vec![0u8];
+        quantization.extend([1u8; 64]);
+        segment(&mut out, 0xdb, &quantization);
+        let (w, h) = (((blocks_wide * 8) as u16).to_be_bytes(), ((blocks_high * 8) as u16).to_be_bytes());
+        segment(&mut out, 0xc0, &[8, h[0], h[1], w[0], w[1], 1, 1, 0x11, 0]);
+        // Standard luma DC table; an AC table with only end-of-block ("0")
+        let mut dc = vec![0x00, 0, 1, 5, 1, 1, 1, 1, 1, 1, 0, 0, 0, 0, 0, 0, 0];
+        dc.extend(0..12u8);
+        segment(&mut out, 0xc4, &dc);
+        let mut ac = vec![0x10, 1];
+        ac.extend([0u8; 15]);
+        ac.push(0);
+        segment(&mut out, 0xc4, &ac);
+        segment(&mut out, 0xda, &[1, 1, 0x00, 0, 63, 0]);
+
+        // DC codes by size: (code, length)
+        const DC_CODES: [(u32, u32); 12] = [(0, 2), (2, 3), (3, 3), (4, 3), (5, 3), (6, 3), (14, 4), (30, 5), (62, 6), (126, 7), (254, 8), (510, 9)];
+        let mut bits: Vec<bool> = Vec::new();
+        let mut push = |value: u32, length: u32| (0..length).rev().for_each(|i| bits.push((value >> i) & 1 == 1));
+        let mut previous = 0i32;
+        for y in 0..blocks_high {
+            for x in 0..blocks_wide {
+                let coefficient = (shade(x, y) as i32 - 128) * 8;
+                let diff = coefficient - previous;
+                previous = coefficient;
+                let size = 32 - diff.unsigned_abs().leading_zeros();
+                let (code, length) = DC_CODES[size as usize];
+                push(code, length);
+                push(if diff >= 0 { diff as u32 } else { (diff + (1 << size) - 1) as u32 }, size);
+                push(0, 1); // End of block
+            }
+        }
+        while !bits.len().is_multiple_of(8) {
+            bits.push(true);
+        }
+        for byte in bits.chunks(8) {
+            let byte = byte.iter().fold(0u8, |b, &bit| (b << 1) | bit as u8);
+            out.push(byte);
+            if byte == 0xff {
+                out.push(0);
+            }
+        }
+        out.extend([0xff, 0xd9]);
+        out
+    }
+
+    pub(crate) fn png(width: u32, height: u32, shade: impl Fn(u32, u32) -> u8) -> Vec<u8> {
+        let mut out = Vec::new();
+        let mut encoder = png::Encoder::new(&mut out, width, height);
+        encoder.set_color(png::ColorType::Rgb);
+        let mut writer = encoder.write_header().unwrap();
+        let mut data = Vec::new();
+        for y in 0..height {
+            for x in 0..width {
+                data.extend([shade(x, y); 3]);
+            }
+        }
+        writer.write_image_data(&data).unwrap();
+        writer.finish().unwrap();
+        out
+    }
+
+    #[test]
+    fn test_jpeg_dc_decoding() {
+        let bytes = jpeg(4, 3, |x, y| (x * 60 + y * 10) as u8);
+        let image = jpeg_dc_image(&bytes).unwrap();
+        assert_eq!((image.width, image.height), (4, 3));
+        // DC value: (shade - 128) * 8
+        assert_eq!(image.pixels[0], -1024.0);
+        assert_eq!(image.pixels[2 * 4 + 3], ((180 + 20) - 128) as f64 * 8.0);
+    }
+
+    #[test]
+    fn test_near_duplicates_across_formats_and_sizes() {
+        let gradient = |x: f64, y: f64| (128.0 + 100.0 * (x * 6.0).sin() * (y * 3.0).cos()) as u8;
+        let large = perceptual_hash(&jpeg(32, 24, |x, y| gradient(x as f64 / 32.0, y as f64 / 24.0))).unwrap();
+        let small = perceptual_hash(&png(90, 80, |x, y| gradient(x as f64 / 90.0, y as f64 / 80.0))).unwrap();
+        let other = perceptual_hash(&png(90, 80, |x, y| gradient(1.0 - y as f64 / 80.0, x as f64 / 90.0))).unwrap();
+
+        assert!(distance(large, small) <= NEAR_DUPLICATE_DISTANCE);
+        assert!(distance(large, other) > NEAR_DUPLICATE_DISTANCE);
+        assert_eq!(perceptual_hash(b"GIF89a"), None);
+        assert_eq!(perceptual_hash(&[0xff, 0xd8, 0xff]), None);
+    }
+}
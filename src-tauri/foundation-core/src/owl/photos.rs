@@ -0,0 +1,302 @@
+// ============================================================================
+// OWL Photos - Photo Folder Import with Duplicate Detection
+// ============================================================================
+// Imports the photos of a folder (and its subfolders) as foundation:Photo
+// entities, without making one entity per copy:
+// - Identical files (same bytes) are one photo, whatever folder they're in;
+//   each copy adds a foundation:filePath
+// - Near-duplicates (resized, recompressed, lightly edited: perceptual
+//   hashes at most NEAR_DUPLICATE_DISTANCE bits apart) are separate photos
+//   linked to the original with foundation:duplicateOf
+//
+// Duplicate links are suggestions to review: confirming one merges the copy
+// into the original, rejecting one records the photos as owl:differentFrom
+// so later imports don't link them again.
+// ============================================================================
+
+use rusqlite::Connection;
+use sha2::{Sha256, Digest};
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use crate::eavto::{query, store, Object, Triple};
+use crate::owl::{Class, Result, string, OwlError, vocabulary::{rdf, rdfs, owl}};
+use crate::owl::phash::{self, NEAR_DUPLICATE_DISTANCE};
+
+pub const PHOTO: &str = "foundation:Photo";
+pub const PERCEPTUAL_HASH: &str = "foundation:perceptualHash";
+pub const DUPLICATE_OF: &str = "foundation:duplicateOf";
+pub const FILE_PATH: &str = "foundation:filePath";
+pub const MEDIA_TYPE: &str = "foundation:mediaType";
+
+/// Photo file extensions and their media types
+const MEDIA_TYPES: &[(&str, &str)] = &[
+    ("jpg", "image/jpeg"),
+    ("jpeg", "image/jpeg"),
+    ("png", "image/png"),
+    ("heic", "image/heic"),
+    ("webp", "image/webp"),
+];
+
+/// Outcome of a photo folder import
+#[derive(Debug, Clone, PartialEq)]
+pub struct PhotoImport {
+    pub files: usize,
+    pub photos: usize, // Photos created
+    pub copies: usize, // Identical to a known photo, in another place
+    pub unchanged: usize, // Already imported from the same path
+    pub duplicates: usize, // Near-duplicates linked to an original
+    pub unhashed: usize, // Formats or files the perceptual hash can't read
+    pub tx: Option<i64>,
+}
+
+/// A near-duplicate photo and its original
+#[derive(Debug, Clone, PartialEq)]
+pub struct Duplicate {
+    pub photo: String,
+    pub original: String,
+    pub distance: Option<u32>, // Bits between the perceptual hashes
+}
+
+/// A photo with a perceptual hash, to match new photos against
+struct Known {
+    iri: String,
+    hash: u64,
+    original: Option<String>,
+}
+
+/// Import the photos of a folder and its subfolders
+pub fn import_photos(conn: &mut Connection, folder: &Path) -> Result<PhotoImport> {
+    let mut files = Vec::new();
+    collect_files(folder, &mut files)
+        .map_err(|e| OwlError::InvalidOperation(format!("Can't read {}: {}", folder.display(), e)))?;
+    files.sort();
+
+    let mut report = PhotoImport { files: files.len(), photos: 0, copies: 0, unchanged: 0, duplicates: 0, unhashed: 0, tx: None };
+    let mut paths: HashMap<String, HashSet<String>> = HashMap::new(); // Photo -> file paths
+    for photo in Class::get_instances(conn, PHOTO)? {
+        let photo_paths = query::get_by_entity_predicate(conn, &photo, FILE_PATH)?.triples.into_iter()
+            .filter_map(|t| t.object.as_literal())
+            .collect();
+        paths.insert(photo, photo_paths);
+    }
+    let recorded: HashSet<String> = paths.values().flatten().cloned().collect();
+    let mut known = known_hashes(conn, paths.keys())?;
+    let distinct = distinct_pairs(conn)?;
+    let mut triples = Vec::new();
+
+    for file in files {
+        let bytes = std::fs::read(&file)
+            .map_err(|e| OwlError::InvalidOperation(format!("Can't read {}: {}", file.display(), e)))?;
+        let path = file.to_string_lossy().to_string();
+        let photo = format!("foundation:Photo_{}", content_hash(&bytes));
+
+        if !paths.contains_key(&photo) && recorded.contains(&path) {
+            report.unchanged += 1; // Merged into its original on review
+            continue;
+        }
+        if let Some(photo_paths) = paths.get_mut(&photo) {
+            if photo_paths.insert(path.clone()) {
+                triples.push(Triple::new(&photo, FILE_PATH, string(&path)));
+                report.copies += 1;
+            } else {
+                report.unchanged += 1;
+            }
+            continue;
+        }
+        paths.insert(photo.clone(), HashSet::from([path.clone()]));
+
+        let file_name = file.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default();
+        triples.push(Triple::new(&photo, rdf::TYPE, Object::Iri(PHOTO.to_string())));
+        triples.push(Triple::new(&photo, rdfs::LABEL, string(&file_name)));
+        triples.push(Triple::new(&photo, FILE_PATH, string(&path)));
+        triples.push(Triple::new(&photo, MEDIA_TYPE, string(media_type(&file).unwrap_or_default())));
+        report.photos += 1;
+
+        let Some(hash) = phash::perceptual_hash(&bytes) else {
+            report.unhashed += 1;
+            continue;
+        };
+        triples.push(Triple::new(&photo, PERCEPTUAL_HASH, string(&format!("{:016x}", hash))));
+
+        // Link to the closest photo, or to its original when it's a copy itself
+        let closest = known.iter()
+            .map(|k| (phash::distance(k.hash, hash), k))
+            .filter(|(d, _)| *d <= NEAR_DUPLICATE_DISTANCE)
+            .min_by_key(|(d, _)| *d)
+            .map(|(_, k)| k.original.clone().unwrap_or_else(|| k.iri.clone()))
+            .filter(|original| !distinct.contains(&pair(original, &photo)));
+        if let Some(original) = &closest {
+            triples.push(Triple::new(&photo, DUPLICATE_OF, Object::Iri(original.clone())));
+            report.duplicates += 1;
+        }
+        known.push(Known { iri: photo, hash, original: closest });
+    }
+
+    if !triples.is_empty() {
+        let folder_name = folder.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default();
+        report.tx = Some(store::assert_triples(conn, &triples, &format!("import:{}", folder_name))?);
+    }
+    Ok(report)
+}
+
+/// Near-duplicate links waiting for review
+pub fn duplicates(conn: &Connection) -> Result<Vec<Duplicate>> {
+    let mut duplicates = Vec::new();
+    for triple in query::get_by_predicate(conn, DUPLICATE_OF)?.triples {
+        let Some(original) = triple.object.as_iri() else { continue };
+        let photo_hash = perceptual_hash_of(conn, &triple.subject)?;
+        let original_hash = perceptual_hash_of(conn, original)?;
+        duplicates.push(Duplicate {
+            distance: photo_hash.zip(original_hash).map(|(a, b)| phash::distance(a, b)),
+            original: original.to_string(),
+            photo: triple.subject,
+        });
+    }
+    duplicates.sort_by(|a, b| a.original.cmp(&b.original).then(a.photo.cmp(&b.photo)));
+    Ok(duplicates)
+}
+
+/// Settle a duplicate link: the same picture merges the photo into its
+/// original (its file paths move over, the photo is retracted); a different
+/// one drops the link and records the photos as owl:differentFrom
+pub fn review_duplicate(conn: &mut Connection, photo: &str, same: bool, origin: &str) -> Result<i64> {
+    let link = query::get_by_entity_predicate(conn, photo, DUPLICATE_OF)?.triples.into_iter().next()
+        .ok_or_else(|| OwlError::NotFound(format!("Duplicate link of {}", photo)))?;
+    let original = link.object.as_iri().unwrap_or_default().to_string();
+
+    let (additions, retractions) = if same {
+        let photo_triples = query::get_by_entity(conn, photo)?.triples;
+        let additions: Vec<Triple> = photo_triples.iter()
+            .filter(|t| t.predicate == FILE_PATH)
+            .map(|t| Triple::new(&original, FILE_PATH, t.object.clone()))
+            .collect();
+        (additions, photo_triples)
+    } else {
+        (vec![Triple::new(photo, owl::DIFFERENT_FROM, Object::Iri(original))], vec![link])
+    };
+    Ok(store::apply_changes(conn, &additions, &retractions, origin)?)
+}
+
+fn known_hashes<'a>(conn: &Connection, photos: impl Iterator<Item = &'a String>) -> Result<Vec<Known>> {
+    let mut known = Vec::new();
+    for photo in photos {
+        let Some(hash) = perceptual_hash_of(conn, photo)? else { continue };
+        let original = query::get_by_entity_predicate(conn, photo, DUPLICATE_OF)?.triples.first()
+            .and_then(|t| t.object.as_iri().map(String::from));
+        known.push(Known { iri: photo.clone(), hash, original });
+    }
+    Ok(known)
+}
+
+/// Photo pairs reviewed as different pictures
+fn distinct_pairs(conn: &Connection) -> Result<HashSet<(String, String)>> {
+    Ok(query::get_by_predicate(conn, owl::DIFFERENT_FROM)?.triples.iter()
+        .filter_map(|t| t.object.as_iri().map(|o| pair(&t.subject, o)))
+        .collect())
+}
+
+fn pair(a: &str, b: &str) -> (String, String) {
+    if a <= b { (a.to_string(), b.to_string()) } else { (b.to_string(), a.to_string()) }
+}
+
+fn perceptual_hash_of(conn: &Connection, photo: &str) -> Result<Option<u64>> {
+    Ok(query::get_by_entity_predicate(conn, photo, PERCEPTUAL_HASH)?.triples.first()
+        .and_then(|t| t.object.as_literal())
+        .and_then(|hex| u64::from_str_radix(&hex, 16).ok()))
+}
+
+fn collect_files(folder: &Path, files: &mut Vec<PathBuf>) -> std::io::Result<()> {
+    for entry in std::fs::read_dir(folder)? {
+        let path = entry?.path();
+        let hidden = path.file_name().is_some_and(|n| n.to_string_lossy().starts_with('.'));
+        if hidden {
+            continue;
+        }
+        if path.is_dir() {
+            collect_files(&path, files)?;
+        } else if media_type(&path).is_some() {
+            files.push(path);
+        }
+    }
+    Ok(())
+}
+
+fn media_type(path: &Path) -> Option<&'static str> {
+    let extension = path.extension()?.to_string_lossy().to_lowercase();
+    MEDIA_TYPES.iter().find(|(ext, _)| *ext == extension).map(|(_, media_type)| *media_type)
+}
+
+fn content_hash(bytes: &[u8]) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(bytes);
+    format!("{:x}", hasher.finalize())[..16].to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::eavto::test_helpers::setup_test_db;
+    use crate::owl::phash::tests::{jpeg, png};
+
+    fn waves(x: f64, y: f64) -> u8 {
+        (128.0 + 100.0 * (x * 6.0).sin() * (y * 3.0).cos()) as u8
+    }
+
+    /// Two folders: a photo, its exact copy, a smaller PNG of it, and an
+    /// unrelated picture
+    fn folders() -> tempfile::TempDir {
+        let dir = tempfile::tempdir().unwrap();
+        let (camera, export) = (dir.path().join("camera"), dir.path().join("export"));
+        std::fs::create_dir_all(&camera).unwrap();
+        std::fs::create_dir_all(&export).unwrap();
+
+        let photo = jpeg(32, 24, |x, y| waves(x as f64 / 32.0, y as f64 / 24.0));
+        std::fs::write(camera.join("IMG_0001.jpg"), &photo).unwrap();
+        std::fs::write(export.join("IMG_0001.jpg"), &photo).unwrap();
+        std::fs::write(export.join("IMG_0001-small.png"), png(90, 80, |x, y| waves(x as f64 / 90.0, y as f64 / 80.0))).unwrap();
+        std::fs::write(camera.join("IMG_0002.jpg"), jpeg(32, 24, |x, y| waves(1.0 - y as f64 / 24.0, x as f64 / 32.0))).unwrap();
+        std::fs::write(camera.join("notes.txt"), "not a photo").unwrap();
+        dir
+    }
+
+    #[test]
+    fn test_import_merges_copies_and_links_near_duplicates() {
+        let mut conn = setup_test_db();
+        let dir = folders();
+
+        let report = import_photos(&mut conn, dir.path()).unwrap();
+        assert_eq!((report.files, report.photos, report.copies, report.duplicates), (4, 3, 1, 1));
+
+        let links = duplicates(&conn).unwrap();
+        assert_eq!(links.len(), 1);
+        assert!(links[0].distance.unwrap() <= NEAR_DUPLICATE_DISTANCE);
+        let original_paths = query::get_by_entity_predicate(&conn, &links[0].original, FILE_PATH).unwrap().triples;
+        assert_eq!(original_paths.len(), 2);
+
+        let again = import_photos(&mut conn, dir.path()).unwrap();
+        assert_eq!((again.photos, again.unchanged, again.tx), (0, 4, None));
+    }
+
+    #[test]
+    fn test_review_duplicates() {
+        let mut conn = setup_test_db();
+        let dir = folders();
+        import_photos(&mut conn, dir.path()).unwrap();
+        let link = duplicates(&conn).unwrap().remove(0);
+
+        // Different pictures: unlinked and remembered
+        let mut other = setup_test_db();
+        import_photos(&mut other, dir.path()).unwrap();
+        review_duplicate(&mut other, &link.photo, false, "test").unwrap();
+        assert!(duplicates(&other).unwrap().is_empty());
+        assert!(distinct_pairs(&other).unwrap().contains(&pair(&link.photo, &link.original)));
+
+        // Same picture: merged into the original
+        review_duplicate(&mut conn, &link.photo, true, "test").unwrap();
+        assert!(duplicates(&conn).unwrap().is_empty());
+        assert!(query::get_by_entity(&conn, &link.photo).unwrap().triples.is_empty());
+        assert_eq!(query::get_by_entity_predicate(&conn, &link.original, FILE_PATH).unwrap().triples.len(), 3);
+        assert!(review_duplicate(&mut conn, &link.photo, true, "test").is_err());
+        assert_eq!(import_photos(&mut conn, dir.path()).unwrap().photos, 0);
+    }
+}
@@ -5,8 +5,29 @@
 // ============================================================================
 
 use rusqlite::Connection;
-use crate::eavto::{store, query, Triple, Object};
-use crate::owl::{Result, OwlError, vocabulary::{rdf, rdfs, owl}};
+use crate::eavto::{store, query, Triple, Object, TypedValue, XsdType};
+use crate::owl::{inference, Result, OwlError, vocabulary::{rdf, rdfs, owl}};
+
+/// Types that make an entity a property: its kind
+pub const PROPERTY_KINDS: &[&str] = &[
+    rdf::PROPERTY,
+    owl::OBJECT_PROPERTY,
+    owl::DATATYPE_PROPERTY,
+    owl::ANNOTATION_PROPERTY,
+];
+
+/// Types that declare a property: its kind, or a characteristic that
+/// implies it is one
+pub const PROPERTY_TYPES: &[&str] = &[
+    rdf::PROPERTY,
+    owl::OBJECT_PROPERTY,
+    owl::DATATYPE_PROPERTY,
+    owl::ANNOTATION_PROPERTY,
+    owl::FUNCTIONAL_PROPERTY,
+    owl::INVERSE_FUNCTIONAL_PROPERTY,
+    owl::TRANSITIVE_PROPERTY,
+    owl::SYMMETRIC_PROPERTY,
+];
 
 /// Base Property type with complete data
 #[derive(Debug, Clone)]
@@ -219,6 +240,150 @@ impl Property {
         let result = query::get_by_entity_predicate(conn, &self.iri, rdf::TYPE)?;
         Ok(!result.triples.is_empty())
     }
+
+    /// Object for a value given as JSON (string, number or boolean), typed
+    /// by the property's range
+    ///
+    /// Object properties (and ranges that are classes) take the IRI of an
+    /// existing entity. A unit, when given, must be the property's QUDT unit:
+    /// values are stored in that unit.
+    pub fn value(&self, conn: &Connection, value: &serde_json::Value, unit: Option<&str>, language: Option<&str>) -> Result<Object> {
+        if let Some(unit) = unit {
+            if self.unit.as_deref() != Some(unit) {
+                return Err(OwlError::ValidationError(match &self.unit {
+                    Some(expected) => format!("{} is measured in {}, not {}", self.iri, expected, unit),
+                    None => format!("{} has no unit, but {} was given", self.iri, unit),
+                }));
+            }
+        }
+
+        let invalid = |expected: &str| OwlError::ValidationError(
+            format!("Invalid value for {}: expected {}, got {}", self.iri, expected, value)
+        );
+        let range = self.ranges.first().map(String::as_str);
+        let is_datatype = range.is_some_and(|r| XsdType::from_iri(r).is_some() || r == rdfs::LITERAL);
+
+        if self.property_type == PropertyType::ObjectProperty || (range.is_some() && !is_datatype) {
+            let iri = value.as_str().ok_or_else(|| invalid("an entity IRI"))?;
+            if query::get_by_entity(conn, iri)?.triples.is_empty() {
+                return Err(OwlError::NotFound(format!("Entity {} not found", iri)));
+            }
+            let object = Object::Iri(iri.to_string());
+            self.check_range(conn, &object)?;
+            return Ok(object);
+        }
+
+        let text = |value: &str, datatype: &str| Object::Literal {
+            value: value.to_string(),
+            datatype: Some(datatype.to_string()),
+            language: language.map(|l| l.to_string()),
+        };
+
+        match range.and_then(XsdType::from_iri) {
+            Some(xsd) if xsd.is_integer() => value.as_i64()
+                .or_else(|| value.as_str().and_then(|s| s.trim().parse().ok()))
+                .map(Object::Integer)
+                .ok_or_else(|| invalid("an integer")),
+            Some(xsd) if xsd.is_numeric() => value.as_f64()
+                .or_else(|| value.as_str().and_then(|s| s.trim().parse().ok()))
+                .map(Object::Number)
+                .ok_or_else(|| invalid("a number")),
+            Some(XsdType::Boolean) => value.as_bool()
+                .or_else(|| value.as_str().and_then(|s| s.trim().parse().ok()))
+                .map(Object::Boolean)
+                .ok_or_else(|| invalid("a boolean")),
+            Some(XsdType::DateTime) => value.as_i64()
+                .or_else(|| value.as_str().and_then(|s| XsdType::DateTime.epoch_millis(s).or_else(|| XsdType::Date.epoch_millis(s))))
+                .map(Object::DateTime)
+                .ok_or_else(|| invalid("an ISO 8601 date-time")),
+            // Dates and times keep their lexical form, checked as the store will
+            Some(xsd @ (XsdType::Date | XsdType::Time)) => value.as_str()
+                .filter(|s| TypedValue::parse(s, xsd.as_iri()).is_some())
+                .map(|s| text(s.trim(), xsd.as_iri()))
+                .ok_or_else(|| invalid(if xsd == XsdType::Date { "an ISO 8601 date" } else { "an ISO 8601 time" })),
+            Some(xsd) => value.as_str()
+                .map(|s| text(s, xsd.as_iri()))
+                .ok_or_else(|| invalid(xsd.as_iri())),
+            // No declared datatype: typed by the JSON value
+            None => match value {
+                serde_json::Value::String(s) => Ok(text(s, "xsd:string")),
+                serde_json::Value::Bool(b) => Ok(Object::Boolean(*b)),
+                serde_json::Value::Number(n) => Ok(n.as_i64().map(Object::Integer)
+                    .unwrap_or_else(|| Object::Number(n.as_f64().unwrap_or_default()))),
+                _ => Err(invalid("a string, number or boolean")),
+            },
+        }
+    }
+
+    /// Check a value falls in the property's range: entities must be
+    /// (asserted) instances of every range class or its subclasses, and
+    /// literals must have a datatype the range accepts
+    ///
+    /// Fails with a RangeError listing the expected types.
+    pub fn check_range(&self, conn: &Connection, object: &Object) -> Result<()> {
+        let ranges: Vec<&str> = self.ranges.iter()
+            .map(String::as_str)
+            .filter(|r| !matches!(*r, rdfs::RESOURCE | owl::THING))
+            .collect();
+        if ranges.is_empty() {
+            return Ok(());
+        }
+        let error = |found: String| OwlError::RangeError {
+            property: self.iri.clone(),
+            expected: ranges.iter().map(|r| r.to_string()).collect(),
+            found,
+        };
+
+        match object {
+            Object::Iri(iri) | Object::Blank(iri) => {
+                let mut types = Vec::new();
+                for triple in query::get_by_entity_predicate(conn, iri, rdf::TYPE)?.triples {
+                    if let Some(class) = triple.object.as_iri() {
+                        types.extend(inference::super_classes(conn, class)?);
+                    }
+                }
+                if ranges.iter().all(|r| types.iter().any(|t| t == r)) {
+                    return Ok(());
+                }
+                let asserted: Vec<String> = query::get_by_entity_predicate(conn, iri, rdf::TYPE)?.triples.iter()
+                    .filter_map(|t| t.object.as_iri().map(String::from))
+                    .collect();
+                Err(error(match asserted.is_empty() {
+                    true => format!("{} (untyped)", iri),
+                    false => format!("{} ({})", iri, asserted.join(", ")),
+                }))
+            }
+            literal => {
+                let datatype = literal.datatype().unwrap_or(match literal {
+                    Object::Literal { language: Some(_), .. } => rdf::LANG_STRING,
+                    _ => "xsd:string",
+                });
+                if ranges.iter().all(|r| accepts_datatype(r, literal, datatype)) {
+                    return Ok(());
+                }
+                Err(error(format!("a {} literal", datatype)))
+            }
+        }
+    }
+}
+
+/// Whether a datatype range accepts a literal of a datatype
+///
+/// Numbers are compared by kind, since values are stored as xsd:integer or
+/// xsd:decimal whatever their declared datatype; date-times (xsd:dateTime)
+/// are accepted by an xsd:date range too.
+fn accepts_datatype(range: &str, literal: &Object, datatype: &str) -> bool {
+    if range == rdfs::LITERAL || range == datatype {
+        return true;
+    }
+    match (XsdType::from_iri(range), literal) {
+        (Some(xsd), Object::Integer(_)) => xsd.is_numeric(),
+        (Some(xsd), Object::Number(_)) => xsd.is_float(),
+        (Some(xsd), Object::DateTime(_)) => matches!(xsd, XsdType::DateTime | XsdType::Date),
+        (Some(XsdType::String), Object::Literal { language: Some(_), .. }) => true,
+        (Some(XsdType::String), Object::Literal { datatype: None, .. }) => true,
+        _ => false,
+    }
 }
 
 /// ObjectProperty is just an alias - use Property with PropertyType::ObjectProperty
@@ -406,4 +571,70 @@ mod tests {
         let property = Property::get(&conn, "foundation:hasParent").unwrap();
         assert!(property.is_functional);
     }
+
+    #[test]
+    fn test_value_typed_by_range() {
+        let mut conn = setup_test_db();
+        Property::new("foundation:memorySize").assert(
+            &mut conn, PropertyType::DatatypeProperty, "memory size", None,
+            Some("foundation:Computer"), Some("xsd:integer"), Some("unit:GigaBYTE"), "test"
+        ).unwrap();
+        Property::new("foundation:owner").assert(
+            &mut conn, PropertyType::ObjectProperty, "owner", None,
+            Some("foundation:Computer"), Some("foundation:Person"), None, "test"
+        ).unwrap();
+        store::assert_triples(&mut conn, &[
+            Triple::new("foundation:Ana", rdf::TYPE, Object::Iri("foundation:Person".to_string())),
+        ], "test").unwrap();
+
+        let size = Property::get(&conn, "foundation:memorySize").unwrap();
+        assert_eq!(size.value(&conn, &serde_json::json!(16), None, None).unwrap(), Object::Integer(16));
+        assert_eq!(size.value(&conn, &serde_json::json!("32"), Some("unit:GigaBYTE"), None).unwrap(), Object::Integer(32));
+        assert!(size.value(&conn, &serde_json::json!("lots"), None, None).is_err());
+        assert!(size.value(&conn, &serde_json::json!(16), Some("unit:MegaBYTE"), None).is_err());
+
+        let owner = Property::get(&conn, "foundation:owner").unwrap();
+        assert_eq!(owner.value(&conn, &serde_json::json!("foundation:Ana"), None, None).unwrap(), Object::Iri("foundation:Ana".to_string()));
+        assert!(owner.value(&conn, &serde_json::json!("foundation:Nobody"), None, None).is_err());
+
+        let untyped = Property::new("foundation:nickname");
+        assert_eq!(untyped.value(&conn, &serde_json::json!(true), None, None).unwrap(), Object::Boolean(true));
+    }
+
+    #[test]
+    fn test_check_range() {
+        let mut conn = setup_test_db();
+        Property::new("foundation:owner").assert(
+            &mut conn, PropertyType::ObjectProperty, "owner", None,
+            Some("foundation:Computer"), Some("foundation:Agent"), None, "test"
+        ).unwrap();
+        Property::new("foundation:memorySize").assert(
+            &mut conn, PropertyType::DatatypeProperty, "memory size", None,
+            Some("foundation:Computer"), Some("xsd:decimal"), Some("unit:GigaBYTE"), "test"
+        ).unwrap();
+        store::assert_triples(&mut conn, &[
+            Triple::new("foundation:Person", rdfs::SUB_CLASS_OF, Object::Iri("foundation:Agent".to_string())),
+            Triple::new("foundation:Ana", rdf::TYPE, Object::Iri("foundation:Person".to_string())),
+            Triple::new("foundation:Lisbon", rdf::TYPE, Object::Iri("foundation:Place".to_string())),
+        ], "test").unwrap();
+
+        // Instances of subclasses are in the range
+        let owner = Property::get(&conn, "foundation:owner").unwrap();
+        assert!(owner.check_range(&conn, &Object::Iri("foundation:Ana".to_string())).is_ok());
+        match owner.check_range(&conn, &Object::Iri("foundation:Lisbon".to_string())) {
+            Err(OwlError::RangeError { expected, found, .. }) => {
+                assert_eq!(expected, vec!["foundation:Agent"]);
+                assert_eq!(found, "foundation:Lisbon (foundation:Place)");
+            }
+            other => panic!("Expected a range error, got {:?}", other),
+        }
+        assert!(owner.value(&conn, &serde_json::json!("foundation:Lisbon"), None, None).is_err());
+
+        let size = Property::get(&conn, "foundation:memorySize").unwrap();
+        assert!(size.check_range(&conn, &Object::Integer(16)).is_ok());
+        assert!(size.check_range(&conn, &Object::Number(15.5)).is_ok());
+        let error = size.check_range(&conn, &Object::Boolean(true)).unwrap_err().to_string();
+        assert!(error.contains("expects xsd:decimal, got a xsd:boolean literal"));
+        assert!(size.check_range(&conn, &Object::Iri("foundation:Ana".to_string())).is_err());
+    }
 }
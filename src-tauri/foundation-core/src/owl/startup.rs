@@ -0,0 +1,240 @@
+// ============================================================================
+// OWL Startup - How the App Launches
+// ============================================================================
+// The startup settings are facts on foundation:Startup (a
+// foundation:StartupSettings), so they sync and export like any other data:
+// - foundation:skipStartupStats: leave database statistics out of the launch
+// - foundation:deferCollectors: start the background collectors (outbox
+//   worker, ontology watcher) a while after launch instead of right away
+// - foundation:integrityCheck: check the database integrity on every launch
+//   ("every-launch") or at most once a week ("weekly", the default)
+// - foundation:watchOntology: re-import core-ontology files as they are
+//   saved (on by default in dev builds only)
+//
+// The app shell reads them before its init thread spawns, from the database
+// file as it is (read), so they take effect from the next launch. A
+// database that doesn't exist yet, or predates the settings, starts with
+// the defaults.
+// ============================================================================
+
+use rusqlite::{Connection, OpenFlags};
+use std::path::Path;
+use crate::eavto::{query, store, Object, Triple};
+use crate::owl::{Result, OwlError};
+
+/// The startup settings entity
+pub const STARTUP: &str = "foundation:Startup";
+
+pub const SKIP_STARTUP_STATS: &str = "foundation:skipStartupStats";
+pub const DEFER_COLLECTORS: &str = "foundation:deferCollectors";
+pub const INTEGRITY_CHECK: &str = "foundation:integrityCheck";
+pub const WATCH_ONTOLOGY: &str = "foundation:watchOntology";
+
+/// Metadata key of the last integrity check (Unix ms)
+pub const INTEGRITY_CHECKED_KEY: &str = "integrity_checked_at";
+
+/// Time between weekly integrity checks
+const WEEK_MS: i64 = 7 * 24 * 60 * 60 * 1000;
+
+/// How often the database integrity is checked at launch
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum IntegrityCheck {
+    EveryLaunch,
+    #[default]
+    Weekly,
+}
+
+impl IntegrityCheck {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            IntegrityCheck::EveryLaunch => "every-launch",
+            IntegrityCheck::Weekly => "weekly",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "every-launch" => Some(IntegrityCheck::EveryLaunch),
+            "weekly" => Some(IntegrityCheck::Weekly),
+            _ => None,
+        }
+    }
+}
+
+/// Startup settings
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct StartupSettings {
+    pub skip_stats: bool,
+    pub defer_collectors: bool,
+    pub integrity_check: IntegrityCheck,
+    pub watch_ontology: bool,
+}
+
+impl Default for StartupSettings {
+    fn default() -> Self {
+        Self {
+            skip_stats: false,
+            defer_collectors: false,
+            integrity_check: IntegrityCheck::default(),
+            watch_ontology: cfg!(debug_assertions),
+        }
+    }
+}
+
+impl StartupSettings {
+    /// The settings stored in a database (defaults for those never set)
+    pub fn load(conn: &Connection) -> Result<Self> {
+        let mut settings = Self::default();
+        for triple in query::get_by_entity(conn, STARTUP)?.triples {
+            match triple.predicate.as_str() {
+                SKIP_STARTUP_STATS => settings.skip_stats = as_bool(&triple.object)?,
+                DEFER_COLLECTORS => settings.defer_collectors = as_bool(&triple.object)?,
+                WATCH_ONTOLOGY => settings.watch_ontology = as_bool(&triple.object)?,
+                INTEGRITY_CHECK => {
+                    let value = triple.object.as_literal().unwrap_or_default();
+                    settings.integrity_check = IntegrityCheck::parse(&value)
+                        .ok_or_else(|| OwlError::ValidationError(format!("Invalid {}: {}", INTEGRITY_CHECK, value)))?;
+                }
+                _ => {}
+            }
+        }
+        Ok(settings)
+    }
+
+    /// The settings of the database at `db_path`, read before it is opened
+    /// for good
+    ///
+    /// Never fails: a missing or unreadable database, or invalid settings,
+    /// give the defaults.
+    pub fn read(db_path: &Path) -> Self {
+        if !db_path.exists() {
+            return Self::default();
+        }
+        Connection::open_with_flags(db_path, OpenFlags::SQLITE_OPEN_READ_ONLY)
+            .ok()
+            .filter(|conn| crate::eavto::encryption::apply(conn).is_ok())
+            .and_then(|conn| Self::load(&conn).ok())
+            .unwrap_or_default()
+    }
+
+    /// Store the settings, replacing the current ones
+    ///
+    /// Returns the transaction ID (None if nothing changed).
+    pub fn save(&self, conn: &mut Connection, origin: &str) -> Result<Option<i64>> {
+        let wanted = [
+            Triple::new(STARTUP, SKIP_STARTUP_STATS, Object::Boolean(self.skip_stats)),
+            Triple::new(STARTUP, DEFER_COLLECTORS, Object::Boolean(self.defer_collectors)),
+            Triple::new(STARTUP, INTEGRITY_CHECK, Object::Literal {
+                value: self.integrity_check.as_str().to_string(),
+                datatype: Some("xsd:string".to_string()),
+                language: None,
+            }),
+            Triple::new(STARTUP, WATCH_ONTOLOGY, Object::Boolean(self.watch_ontology)),
+        ];
+
+        let current: Vec<Triple> = query::get_by_entity(conn, STARTUP)?
+            .triples
+            .into_iter()
+            .filter(|t| [SKIP_STARTUP_STATS, DEFER_COLLECTORS, INTEGRITY_CHECK, WATCH_ONTOLOGY].contains(&t.predicate.as_str()))
+            .collect();
+        let assertions: Vec<Triple> = wanted.iter()
+            .filter(|w| !current.iter().any(|c| c.predicate == w.predicate && c.object == w.object))
+            .cloned()
+            .collect();
+        let retractions: Vec<Triple> = current.into_iter()
+            .filter(|c| assertions.iter().any(|a| a.predicate == c.predicate))
+            .collect();
+        if assertions.is_empty() {
+            return Ok(None);
+        }
+
+        Ok(Some(store::apply_changes(conn, &assertions, &retractions, origin)?))
+    }
+
+    /// Whether the integrity check is due at this launch
+    pub fn integrity_check_due(&self, conn: &Connection, now: i64) -> Result<bool> {
+        if self.integrity_check == IntegrityCheck::EveryLaunch {
+            return Ok(true);
+        }
+        let last = query::get_metadata(conn, INTEGRITY_CHECKED_KEY)?
+            .and_then(|value| value.parse::<i64>().ok());
+        Ok(last.is_none_or(|last| now - last >= WEEK_MS))
+    }
+}
+
+/// Check the database integrity (SQLite's integrity_check), recording when
+///
+/// Returns the problems found (empty: the database is sound).
+pub fn check_integrity(conn: &Connection, now: i64) -> Result<Vec<String>> {
+    let mut stmt = conn.prepare("PRAGMA integrity_check")?;
+    let problems: Vec<String> = stmt.query_map([], |row| row.get::<_, String>(0))?
+        .collect::<std::result::Result<Vec<_>, _>>()?
+        .into_iter()
+        .filter(|line| line != "ok")
+        .collect();
+    store::set_metadata(conn, INTEGRITY_CHECKED_KEY, Some(&now.to_string()))?;
+    Ok(problems)
+}
+
+fn as_bool(object: &Object) -> Result<bool> {
+    match object {
+        Object::Boolean(b) => Ok(*b),
+        other => match other.as_literal().as_deref() {
+            Some("true") | Some("1") => Ok(true),
+            Some("false") | Some("0") => Ok(false),
+            _ => Err(OwlError::ValidationError(format!("Not a boolean: {:?}", other))),
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::eavto::test_helpers::setup_test_db;
+
+    #[test]
+    fn test_save_and_load() {
+        let mut conn = setup_test_db();
+        assert_eq!(StartupSettings::load(&conn).unwrap(), StartupSettings::default());
+
+        let fast = StartupSettings {
+            skip_stats: true,
+            defer_collectors: true,
+            integrity_check: IntegrityCheck::Weekly,
+            watch_ontology: false,
+        };
+        assert!(fast.save(&mut conn, "test").unwrap().is_some());
+        assert!(fast.save(&mut conn, "test").unwrap().is_none());
+        assert_eq!(StartupSettings::load(&conn).unwrap(), fast);
+
+        let careful = StartupSettings {
+            defer_collectors: false,
+            integrity_check: IntegrityCheck::EveryLaunch,
+            watch_ontology: true,
+            ..fast
+        };
+        careful.save(&mut conn, "test").unwrap();
+        assert_eq!(StartupSettings::load(&conn).unwrap(), careful);
+        assert_eq!(query::get_by_entity(&conn, STARTUP).unwrap().triples.len(), 4);
+    }
+
+    #[test]
+    fn test_integrity_check_weekly() {
+        let conn = setup_test_db();
+        let weekly = StartupSettings::default();
+        assert!(weekly.integrity_check_due(&conn, 1_000).unwrap());
+
+        assert!(check_integrity(&conn, 1_000).unwrap().is_empty());
+        assert!(!weekly.integrity_check_due(&conn, 1_000 + WEEK_MS - 1).unwrap());
+        assert!(weekly.integrity_check_due(&conn, 1_000 + WEEK_MS).unwrap());
+
+        let every = StartupSettings { integrity_check: IntegrityCheck::EveryLaunch, ..weekly };
+        assert!(every.integrity_check_due(&conn, 1_000).unwrap());
+    }
+
+    #[test]
+    fn test_read_missing_database_gives_defaults() {
+        let dir = tempfile::TempDir::new().unwrap();
+        assert_eq!(StartupSettings::read(&dir.path().join("none.db")), StartupSettings::default());
+    }
+}
@@ -0,0 +1,311 @@
+// ============================================================================
+// OWL Suggestions - Statistics-Driven Modeling Suggestions
+// ============================================================================
+// Looks for patterns in user data that the ontology could model better:
+// - The same literal on many entities ("40 notes have company 'Acme Corp'"):
+//   the value deserves an entity of its own, linked instead of repeated
+// - Entities without rdf:type: they should be classified
+// - Properties that keep appearing together on untyped entities: they
+//   describe a class that doesn't exist yet
+//
+// User data excludes schema terms (classes, properties, ontology headers,
+// restrictions), blank nodes, and the bundled core ontology.
+// ============================================================================
+
+use rusqlite::Connection;
+use std::collections::{BTreeMap, BTreeSet, HashSet};
+use crate::eavto::{query, Object, Triple};
+use crate::owl::{Thing, Result, vocabulary::{rdf, rdfs, owl}};
+
+/// A literal must repeat on this many entities to be suggested as an entity
+const MIN_REPEATED_LITERAL: usize = 3;
+
+/// Two properties must appear together on this many untyped entities
+const MIN_CO_OCCURRENCE: usize = 5;
+
+/// Longer literals are text, not names of things
+const MAX_LITERAL_LENGTH: usize = 80;
+
+/// Affected entities listed per suggestion
+const SAMPLE_SIZE: usize = 10;
+
+/// Types that make an entity part of the schema rather than user data
+const SCHEMA_TYPES: &[&str] = &[
+    owl::CLASS, rdfs::CLASS, rdfs::DATATYPE, rdf::PROPERTY,
+    owl::OBJECT_PROPERTY, owl::DATATYPE_PROPERTY, owl::ANNOTATION_PROPERTY,
+    owl::RESTRICTION, "owl:Ontology",
+];
+
+/// Properties that describe any entity, so they don't suggest a class
+const DESCRIPTIVE_PROPERTIES: &[&str] = &[
+    rdf::TYPE, rdfs::LABEL, rdfs::COMMENT, "foundation:icon", query::IN_WORKSPACE,
+];
+
+/// What a suggestion proposes
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SuggestionKind {
+    CreateEntity,
+    ClassifyEntities,
+    DefineClass,
+}
+
+impl SuggestionKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SuggestionKind::CreateEntity => "create_entity",
+            SuggestionKind::ClassifyEntities => "classify_entities",
+            SuggestionKind::DefineClass => "define_class",
+        }
+    }
+}
+
+/// A modeling suggestion
+#[derive(Debug, Clone, PartialEq)]
+pub struct Suggestion {
+    pub kind: SuggestionKind,
+    pub message: String,
+    pub count: usize, // Entities affected
+    pub entities: Vec<String>, // Sample of the affected entities
+    pub predicates: Vec<String>,
+    pub value: Option<String>, // Repeated literal (CreateEntity)
+    pub existing: Option<String>, // Entity already labeled with the value (CreateEntity)
+}
+
+/// Modeling suggestions for the user data, most entities affected first
+pub fn suggest(conn: &Connection) -> Result<Vec<Suggestion>> {
+    let triples = user_data(conn)?;
+
+    let mut suggestions = repeated_literals(conn, &triples)?;
+    suggestions.extend(untyped_entities(&triples));
+    suggestions.extend(co_occurring_properties(conn, &triples));
+
+    suggestions.sort_by(|a, b| b.count.cmp(&a.count).then_with(|| a.message.cmp(&b.message)));
+    Ok(suggestions)
+}
+
+/// Current triples about user entities (see module docs)
+pub(crate) fn user_data(conn: &Connection) -> Result<Vec<Triple>> {
+    let mut stmt = conn.prepare("SELECT id, name FROM origins")?;
+    let origins = stmt
+        .query_map([], |row| Ok((row.get::<_, i64>(0)?, row.get::<_, String>(1)?)))?
+        .collect::<std::result::Result<Vec<_>, _>>()?;
+    let core_origins: HashSet<i64> = origins.into_iter()
+        .filter(|(_, name)| query::is_core_origin(name))
+        .map(|(id, _)| id)
+        .collect();
+
+    let triples = query::get_all(conn)?.triples;
+    let schema: HashSet<&str> = triples.iter()
+        .filter(|t| t.predicate == rdf::TYPE)
+        .filter(|t| t.object.as_iri().is_some_and(|class| SCHEMA_TYPES.contains(&class)))
+        .map(|t| t.subject.as_str())
+        .collect();
+
+    let user: Vec<Triple> = triples.iter()
+        .filter(|t| !core_origins.contains(&t.origin_id))
+        .filter(|t| !t.subject.starts_with("_:") && !schema.contains(t.subject.as_str()))
+        .cloned()
+        .collect();
+    Ok(user)
+}
+
+fn repeated_literals(conn: &Connection, triples: &[Triple]) -> Result<Vec<Suggestion>> {
+    // (predicate, value) -> entities
+    let mut occurrences: BTreeMap<(&str, String), BTreeSet<&str>> = BTreeMap::new();
+    for triple in triples {
+        if matches!(triple.predicate.as_str(), rdfs::LABEL | rdfs::COMMENT) {
+            continue;
+        }
+        if let Object::Literal { value, datatype, .. } = &triple.object {
+            let is_string = datatype.as_deref().is_none_or(|d| d == "xsd:string" || d == rdf::LANG_STRING);
+            let value = value.trim();
+            if is_string && !value.is_empty() && value.chars().count() <= MAX_LITERAL_LENGTH {
+                occurrences.entry((triple.predicate.as_str(), value.to_string()))
+                    .or_default()
+                    .insert(triple.subject.as_str());
+            }
+        }
+    }
+
+    let mut suggestions = Vec::new();
+    for ((predicate, value), entities) in occurrences {
+        if entities.len() < MIN_REPEATED_LITERAL {
+            continue;
+        }
+
+        let existing = find_by_label(triples, &value);
+        let property = Thing::get(conn, predicate).label;
+        let message = match &existing {
+            Some(entity) => format!(
+                "{} entities have {} \"{}\" — link them to {}?",
+                entities.len(), property, value, Thing::get(conn, entity).label
+            ),
+            None => format!(
+                "{} entities have {} \"{}\" — create an entity for it?",
+                entities.len(), property, value
+            ),
+        };
+
+        suggestions.push(Suggestion {
+            kind: SuggestionKind::CreateEntity,
+            message,
+            count: entities.len(),
+            entities: sample(entities),
+            predicates: vec![predicate.to_string()],
+            value: Some(value),
+            existing,
+        });
+    }
+    Ok(suggestions)
+}
+
+fn find_by_label(triples: &[Triple], value: &str) -> Option<String> {
+    triples.iter()
+        .find(|t| t.predicate == rdfs::LABEL && t.object.as_literal().is_some_and(|l| l.trim().eq_ignore_ascii_case(value)))
+        .map(|t| t.subject.clone())
+}
+
+fn untyped_entities(triples: &[Triple]) -> Option<Suggestion> {
+    let untyped = untyped(triples);
+    if untyped.is_empty() {
+        return None;
+    }
+
+    Some(Suggestion {
+        kind: SuggestionKind::ClassifyEntities,
+        message: format!("{} entities have no type — classify them?", untyped.len()),
+        count: untyped.len(),
+        entities: sample(untyped),
+        predicates: Vec::new(),
+        value: None,
+        existing: None,
+    })
+}
+
+fn co_occurring_properties(conn: &Connection, triples: &[Triple]) -> Vec<Suggestion> {
+    let untyped = untyped(triples);
+
+    // Entity -> its non-descriptive properties
+    let mut properties: BTreeMap<&str, BTreeSet<&str>> = BTreeMap::new();
+    for triple in triples.iter().filter(|t| untyped.contains(t.subject.as_str())) {
+        if !DESCRIPTIVE_PROPERTIES.contains(&triple.predicate.as_str()) {
+            properties.entry(triple.subject.as_str()).or_default().insert(triple.predicate.as_str());
+        }
+    }
+
+    // (property, property) -> entities having both
+    let mut pairs: BTreeMap<(&str, &str), BTreeSet<&str>> = BTreeMap::new();
+    for (entity, predicates) in &properties {
+        let predicates: Vec<&str> = predicates.iter().copied().collect();
+        for (i, a) in predicates.iter().enumerate() {
+            for b in &predicates[i + 1..] {
+                pairs.entry((a, b)).or_default().insert(entity);
+            }
+        }
+    }
+
+    pairs.into_iter()
+        .filter(|(_, entities)| entities.len() >= MIN_CO_OCCURRENCE)
+        .map(|((a, b), entities)| Suggestion {
+            kind: SuggestionKind::DefineClass,
+            message: format!(
+                "{} untyped entities have both {} and {} — define a class for them?",
+                entities.len(), Thing::get(conn, a).label, Thing::get(conn, b).label
+            ),
+            count: entities.len(),
+            entities: sample(entities),
+            predicates: vec![a.to_string(), b.to_string()],
+            value: None,
+            existing: None,
+        })
+        .collect()
+}
+
+fn untyped(triples: &[Triple]) -> BTreeSet<&str> {
+    let typed: HashSet<&str> = triples.iter()
+        .filter(|t| t.predicate == rdf::TYPE)
+        .map(|t| t.subject.as_str())
+        .collect();
+
+    triples.iter()
+        .map(|t| t.subject.as_str())
+        .filter(|s| !typed.contains(s))
+        .collect()
+}
+
+fn sample(entities: BTreeSet<&str>) -> Vec<String> {
+    entities.into_iter().take(SAMPLE_SIZE).map(String::from).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::eavto::{store, test_helpers::setup_test_db};
+
+    fn literal(subject: &str, predicate: &str, value: &str) -> Triple {
+        Triple::new(subject, predicate, Object::Literal {
+            value: value.to_string(),
+            datatype: Some("xsd:string".to_string()),
+            language: None,
+        })
+    }
+
+    fn of_kind(suggestions: &[Suggestion], kind: SuggestionKind) -> Vec<&Suggestion> {
+        suggestions.iter().filter(|s| s.kind == kind).collect()
+    }
+
+    #[test]
+    fn test_repeated_literal_suggests_entity() {
+        let mut conn = setup_test_db();
+        let mut triples = vec![
+            Triple::new("foundation:company", rdf::TYPE, Object::Iri(owl::DATATYPE_PROPERTY.to_string())),
+            literal("foundation:company", rdfs::LABEL, "company"),
+        ];
+        for i in 0..4 {
+            let note = format!("foundation:Note{}", i);
+            triples.push(Triple::new(&note, rdf::TYPE, Object::Iri("foundation:Note".to_string())));
+            triples.push(literal(&note, "foundation:company", "Acme Corp"));
+        }
+        triples.push(literal("foundation:Note9", "foundation:company", "Other Inc"));
+        store::assert_triples(&mut conn, &triples, "test").unwrap();
+
+        let suggestions = suggest(&conn).unwrap();
+        let create = of_kind(&suggestions, SuggestionKind::CreateEntity);
+        assert_eq!(create.len(), 1);
+        assert_eq!(create[0].count, 4);
+        assert_eq!(create[0].value.as_deref(), Some("Acme Corp"));
+        assert_eq!(create[0].existing, None);
+        assert!(create[0].message.contains("company \"Acme Corp\""));
+
+        // Once an entity is labeled with the value, suggest linking to it
+        store::assert_triples(&mut conn, &[literal("foundation:Acme", rdfs::LABEL, "Acme Corp")], "test").unwrap();
+        let suggestions = suggest(&conn).unwrap();
+        let create = of_kind(&suggestions, SuggestionKind::CreateEntity);
+        assert_eq!(create[0].existing.as_deref(), Some("foundation:Acme"));
+    }
+
+    #[test]
+    fn test_untyped_entities_and_co_occurring_properties() {
+        let mut conn = setup_test_db();
+        let mut triples = vec![
+            Triple::new("foundation:Book", rdf::TYPE, Object::Iri(owl::CLASS.to_string())),
+        ];
+        for i in 0..5 {
+            let entity = format!("foundation:Item{}", i);
+            triples.push(literal(&entity, "foundation:author", &format!("Author {}", i)));
+            triples.push(literal(&entity, "foundation:isbn", &format!("ISBN {}", i)));
+            triples.push(literal(&entity, rdfs::LABEL, &format!("Item {}", i)));
+        }
+        store::assert_triples(&mut conn, &triples, "test").unwrap();
+
+        let suggestions = suggest(&conn).unwrap();
+
+        let classify = of_kind(&suggestions, SuggestionKind::ClassifyEntities);
+        assert_eq!(classify.len(), 1);
+        assert_eq!(classify[0].count, 5); // The class is schema, not untyped data
+
+        let define = of_kind(&suggestions, SuggestionKind::DefineClass);
+        assert_eq!(define.len(), 1);
+        assert_eq!(define[0].predicates, vec!["foundation:author", "foundation:isbn"]);
+    }
+}
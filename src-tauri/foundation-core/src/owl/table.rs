@@ -0,0 +1,268 @@
+// ============================================================================
+// OWL Table - Instances of a Class as Rows
+// ============================================================================
+// A spreadsheet view of a class: its members (asserted and inferred, see
+// inference::instances) as rows, some properties as columns, filtered by
+// facets, sorted by a column and read a page at a time.
+//
+// Facets count the values of each column over the filtered members, so a
+// view can offer "Color: red (12), blue (3)" next to the rows it lists.
+// ============================================================================
+
+use rusqlite::Connection;
+use std::cmp::Ordering;
+use std::collections::{HashMap, HashSet};
+use crate::eavto::{query, Object};
+use crate::owl::{inference, Class, Result, Thing};
+
+/// Values listed per facet at most, most common first
+const FACET_VALUES: usize = 20;
+
+/// Members kept: those with one of the values of the property
+#[derive(Debug, Clone)]
+pub struct FacetFilter {
+    pub property: String,
+    pub values: Vec<String>, // IRIs or literal values
+}
+
+/// A value of a cell
+#[derive(Debug, Clone, PartialEq)]
+pub struct Cell {
+    pub value: String, // IRI or literal value
+    pub label: String, // Label of an IRI, the value of a literal
+    pub is_iri: bool,
+}
+
+/// An instance and its values, one list per column
+#[derive(Debug, Clone)]
+pub struct Row {
+    pub thing: Thing,
+    pub cells: Vec<Vec<Cell>>,
+}
+
+/// A value of a column and the filtered members that have it
+#[derive(Debug, Clone)]
+pub struct FacetValue {
+    pub cell: Cell,
+    pub count: usize,
+}
+
+/// Values of a column over the filtered members
+#[derive(Debug, Clone)]
+pub struct Facet {
+    pub property: String,
+    pub values: Vec<FacetValue>, // Most common first
+}
+
+/// A page of the instances of a class
+#[derive(Debug, Clone)]
+pub struct InstanceTable {
+    pub columns: Vec<Thing>,
+    pub rows: Vec<Row>,
+    pub total: usize, // Members after filtering, over all pages
+    pub facets: Vec<Facet>,
+}
+
+/// What to list of a class
+#[derive(Debug, Clone, Default)]
+pub struct TableQuery {
+    pub columns: Option<Vec<String>>, // None: the properties of the class
+    pub filters: Vec<FacetFilter>,
+    pub sort_by: Option<String>, // A property; None: by label
+    pub descending: bool,
+    pub page: usize, // From 0
+    pub page_size: usize,
+}
+
+/// A page of the instances of a class
+pub fn instances(conn: &Connection, class: &str, table: &TableQuery) -> Result<InstanceTable> {
+    let columns = match &table.columns {
+        Some(columns) => columns.clone(),
+        None => Class::get(conn, class)?.properties.into_iter().map(|(property, _)| property).collect(),
+    };
+
+    // Values of the members for every property the table reads
+    let mut read: Vec<&str> = columns.iter().map(String::as_str)
+        .chain(table.filters.iter().map(|f| f.property.as_str()))
+        .chain(table.sort_by.as_deref())
+        .collect();
+    read.sort();
+    read.dedup();
+    let mut members = Vec::new();
+    for member in inference::instances(conn, class)? {
+        let mut values: HashMap<String, Vec<Object>> = HashMap::new();
+        for triple in query::get_by_entity(conn, &member)?.triples {
+            if read.contains(&triple.predicate.as_str()) {
+                values.entry(triple.predicate).or_default().push(triple.object);
+            }
+        }
+        members.push((member, values));
+    }
+
+    members.retain(|(_, values)| {
+        table.filters.iter().all(|filter| {
+            values.get(&filter.property).is_some_and(|objects| {
+                objects.iter().any(|object| filter.values.iter().any(|value| *value == text(object)))
+            })
+        })
+    });
+
+    // Labels of the members and of the IRI values they have
+    let mut iris: HashSet<&str> = members.iter().map(|(member, _)| member.as_str()).collect();
+    iris.extend(columns.iter().map(String::as_str));
+    for (_, values) in &members {
+        iris.extend(values.values().flatten().filter_map(Object::as_iri));
+    }
+    let iris: Vec<&str> = iris.into_iter().collect();
+    let things: HashMap<String, Thing> = Thing::get_many(conn, &iris)
+        .into_iter()
+        .map(|thing| (thing.iri.clone(), thing))
+        .collect();
+    let cell = |object: &Object| Cell {
+        value: text(object),
+        label: object.as_iri().map_or_else(|| text(object), |iri| things[iri].label.clone()),
+        is_iri: object.as_iri().is_some(),
+    };
+
+    members.sort_by(|(a, a_values), (b, b_values)| {
+        let by_label = || things[a].label.to_lowercase().cmp(&things[b].label.to_lowercase());
+        match &table.sort_by {
+            Some(property) => compare(
+                a_values.get(property).and_then(|v| v.first()),
+                b_values.get(property).and_then(|v| v.first()),
+                table.descending,
+            ).then_with(by_label),
+            None if table.descending => by_label().reverse(),
+            None => by_label(),
+        }
+    });
+
+    let facets = columns.iter()
+        .map(|property| {
+            let mut counts: HashMap<String, (Cell, usize)> = HashMap::new();
+            for (_, values) in &members {
+                let mut seen = HashSet::new();
+                for object in values.get(property).into_iter().flatten() {
+                    let value = cell(object);
+                    if seen.insert(value.value.clone()) {
+                        counts.entry(value.value.clone()).or_insert((value, 0)).1 += 1;
+                    }
+                }
+            }
+            let mut values: Vec<FacetValue> = counts.into_values()
+                .map(|(cell, count)| FacetValue { cell, count })
+                .collect();
+            values.sort_by(|a, b| b.count.cmp(&a.count).then_with(|| a.cell.label.cmp(&b.cell.label)));
+            values.truncate(FACET_VALUES);
+            Facet { property: property.clone(), values }
+        })
+        .collect();
+
+    let total = members.len();
+    let rows = members.iter()
+        .skip(table.page * table.page_size)
+        .take(table.page_size)
+        .map(|(member, values)| Row {
+            thing: things[member].clone(),
+            cells: columns.iter()
+                .map(|property| values.get(property).into_iter().flatten().map(&cell).collect())
+                .collect(),
+        })
+        .collect();
+
+    Ok(InstanceTable {
+        columns: columns.iter().map(|property| things[property].clone()).collect(),
+        rows,
+        total,
+        facets,
+    })
+}
+
+/// A value as filters name it: the IRI, or the literal value
+fn text(object: &Object) -> String {
+    object.as_iri().map(String::from).or_else(|| object.as_literal()).unwrap_or_default()
+}
+
+/// Order of two sort values: numbers and dates by value, text ignoring
+/// case, missing values last either way
+fn compare(a: Option<&Object>, b: Option<&Object>, descending: bool) -> Ordering {
+    let number = |object: &Object| match object {
+        Object::Integer(i) => Some(*i as f64),
+        Object::Number(n) => Some(*n),
+        Object::DateTime(dt) => Some(*dt as f64),
+        _ => None,
+    };
+    match (a, b) {
+        (Some(a), Some(b)) => {
+            let order = match (number(a), number(b)) {
+                (Some(x), Some(y)) => x.partial_cmp(&y).unwrap_or(Ordering::Equal),
+                _ => text(a).to_lowercase().cmp(&text(b).to_lowercase()),
+            };
+            if descending { order.reverse() } else { order }
+        }
+        (Some(_), None) => Ordering::Less,
+        (None, Some(_)) => Ordering::Greater,
+        (None, None) => Ordering::Equal,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::eavto::{store, test_helpers::setup_test_db, Triple};
+    use crate::owl::vocabulary::{rdf, rdfs};
+
+    fn iri(value: &str) -> Object {
+        Object::Iri(value.to_string())
+    }
+
+    fn label(value: &str) -> Object {
+        Object::Literal { value: value.to_string(), datatype: None, language: None }
+    }
+
+    #[test]
+    fn test_instances_filtered_sorted_and_paged() {
+        let mut conn = setup_test_db();
+        store::assert_triples(&mut conn, &[
+            Triple::new("foundation:SportsCar", rdfs::SUB_CLASS_OF, iri("foundation:Car")),
+            Triple::new("foundation:Red", rdfs::LABEL, label("Red")),
+            Triple::new("foundation:a", rdf::TYPE, iri("foundation:Car")),
+            Triple::new("foundation:a", rdfs::LABEL, label("Alpha")),
+            Triple::new("foundation:a", "foundation:color", iri("foundation:Red")),
+            Triple::new("foundation:a", "foundation:seats", Object::Integer(12)),
+            Triple::new("foundation:b", rdf::TYPE, iri("foundation:SportsCar")),
+            Triple::new("foundation:b", rdfs::LABEL, label("Beta")),
+            Triple::new("foundation:b", "foundation:color", iri("foundation:Red")),
+            Triple::new("foundation:b", "foundation:seats", Object::Integer(2)),
+            Triple::new("foundation:c", rdf::TYPE, iri("foundation:Car")),
+            Triple::new("foundation:c", rdfs::LABEL, label("Gamma")),
+            Triple::new("foundation:c", "foundation:color", iri("foundation:Blue")),
+        ], "test").unwrap();
+
+        let columns = vec!["foundation:color".to_string(), "foundation:seats".to_string()];
+        let query = TableQuery { columns: Some(columns), page_size: 2, ..Default::default() };
+
+        // Subclass members are rows too; by label by default
+        let table = instances(&conn, "foundation:Car", &query).unwrap();
+        let labels: Vec<&str> = table.rows.iter().map(|r| r.thing.label.as_str()).collect();
+        assert_eq!((labels, table.total), (vec!["Alpha", "Beta"], 3));
+        assert_eq!(table.rows[0].cells[0], vec![Cell { value: "foundation:Red".into(), label: "Red".into(), is_iri: true }]);
+        let colors = &table.facets[0].values;
+        assert_eq!((colors[0].cell.label.as_str(), colors[0].count), ("Red", 2));
+
+        // Numbers sort by value, missing values last
+        let sorted = TableQuery { sort_by: Some("foundation:seats".into()), page_size: 3, ..query.clone() };
+        let table = instances(&conn, "foundation:Car", &sorted).unwrap();
+        let labels: Vec<&str> = table.rows.iter().map(|r| r.thing.label.as_str()).collect();
+        assert_eq!(labels, ["Beta", "Alpha", "Gamma"]);
+        let descending = TableQuery { descending: true, ..sorted };
+        let table = instances(&conn, "foundation:Car", &descending).unwrap();
+        assert_eq!(table.rows[0].thing.label, "Alpha");
+        assert_eq!(table.rows[2].thing.label, "Gamma");
+
+        let filters = vec![FacetFilter { property: "foundation:color".into(), values: vec!["foundation:Red".into()] }];
+        let red = TableQuery { filters, page: 1, page_size: 1, ..query };
+        let table = instances(&conn, "foundation:Car", &red).unwrap();
+        assert_eq!((table.total, table.rows[0].thing.label.as_str()), (2, "Beta"));
+    }
+}
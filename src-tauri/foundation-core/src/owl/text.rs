@@ -0,0 +1,57 @@
+// ============================================================================
+// OWL Text - Label Normalization
+// ============================================================================
+// One routine decides when two labels are "the same text", shared by search,
+// deduplication (contacts, counterparties) and entity linking (mentions), so
+// an entity found by one is found by all.
+//
+// Folding makes matching forgiving of how a name was typed:
+// - Unicode NFC first, so composed and decomposed forms are equal
+// - Diacritics removed ("São Paulo" matches "Sao Paulo", "Müller" "Muller")
+// - Letters that don't decompose mapped to their ASCII look-alikes (ø, ł, ß)
+// - Case ignored, whitespace collapsed
+//
+// Folding is for comparing only: stored labels keep their exact spelling.
+// ============================================================================
+
+use unicode_normalization::{char::is_combining_mark, UnicodeNormalization};
+
+/// Canonical (NFC) form of a text
+pub fn normalize(text: &str) -> String {
+    text.nfc().collect()
+}
+
+/// Match form of a text: no diacritics, lowercase, single-spaced
+pub fn fold(text: &str) -> String {
+    let mut folded = String::with_capacity(text.len());
+    for c in text.nfd().filter(|c| !is_combining_mark(*c)) {
+        match c {
+            'ø' | 'Ø' => folded.push('o'),
+            'ł' | 'Ł' => folded.push('l'),
+            'đ' | 'Đ' | 'ð' | 'Ð' => folded.push('d'),
+            'ħ' | 'Ħ' => folded.push('h'),
+            'ı' => folded.push('i'),
+            'ß' => folded.push_str("ss"),
+            'æ' | 'Æ' => folded.push_str("ae"),
+            'œ' | 'Œ' => folded.push_str("oe"),
+            'þ' | 'Þ' => folded.push_str("th"),
+            c => folded.extend(c.to_lowercase()),
+        }
+    }
+    folded.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fold() {
+        assert_eq!(fold("São  Paulo"), "sao paulo");
+        assert_eq!(fold("Sa\u{0303}o Paulo"), fold("São Paulo"));
+        assert_eq!(fold("Łódź"), "lodz");
+        assert_eq!(fold("Straße"), "strasse");
+        assert_eq!(fold("Ørsted"), "orsted");
+        assert_eq!(normalize("Sa\u{0303}o"), "São");
+    }
+}
@@ -0,0 +1,118 @@
+// ============================================================================
+// OWL Thing - Basic Entity Operations
+// ============================================================================
+// Represents owl:Thing - the most basic entity with just metadata
+// All entities (classes, individuals) are ultimately Things
+// ============================================================================
+
+use rusqlite::Connection;
+use std::collections::HashMap;
+use crate::eavto::query;
+use crate::owl::vocabulary::rdfs;
+use serde::Serialize;
+use ts_rs::TS;
+
+const ICON: &str = "foundation:icon";
+
+/// Represents owl:Thing - basic entity with metadata only
+#[derive(Debug, Clone, Serialize, PartialEq, TS)]
+#[ts(export)]
+pub struct Thing {
+    pub iri: String,
+    pub label: String,
+    pub icon: Option<String>,
+}
+
+impl Thing {
+    /// Get basic entity info (id, label, icon only - no relationships)
+    /// If no rdfs:label exists, returns the IRI as label
+    pub fn get(conn: &Connection, iri: impl Into<String>) -> Thing {
+        let iri = iri.into();
+
+        let label = query::get_by_entity_predicate(conn, &iri, rdfs::LABEL)
+            .ok()
+            .and_then(|r| r.triples.first().and_then(|t| t.object.as_literal()))
+            .unwrap_or_else(|| iri.clone());
+
+        let icon = query::get_by_entity_predicate(conn, &iri, ICON)
+            .ok()
+            .and_then(|r| r.triples.first().and_then(|t| t.object.as_literal()));
+
+        Thing {
+            iri,
+            label,
+            icon,
+        }
+    }
+
+    /// Get the basic info of several entities in one query, in the order
+    /// given (as `get` would for each: a list of classes or backlinks
+    /// otherwise costs two queries per entity)
+    pub fn get_many(conn: &Connection, iris: &[&str]) -> Vec<Thing> {
+        let values = Self::read_many(conn, iris).unwrap_or_default();
+        iris.iter()
+            .map(|iri| {
+                let label = values.get(&(iri.to_string(), rdfs::LABEL)).cloned().flatten();
+                let icon = values.get(&(iri.to_string(), ICON)).cloned().flatten();
+                Thing {
+                    iri: iri.to_string(),
+                    label: label.unwrap_or_else(|| iri.to_string()),
+                    icon,
+                }
+            })
+            .collect()
+    }
+
+    /// Latest label and icon of entities, by (IRI, predicate); None for an
+    /// IRI value, which isn't a label
+    fn read_many(conn: &Connection, iris: &[&str]) -> rusqlite::Result<HashMap<(String, &'static str), Option<String>>> {
+        let mut values = HashMap::new();
+        if iris.is_empty() {
+            return Ok(values);
+        }
+
+        let mut stmt = conn.prepare(&format!(
+            "SELECT subject, predicate, object_value FROM triples t
+             WHERE subject IN ({}) AND predicate IN (?, ?) AND {}
+             ORDER BY tx DESC",
+            vec!["?"; iris.len()].join(", "),
+            query::visible("t")
+        ))?;
+        let params = iris.iter().copied().chain([rdfs::LABEL, ICON]);
+        let rows = stmt.query_map(rusqlite::params_from_iter(params), |row| {
+            Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?, row.get::<_, Option<String>>(2)?))
+        })?;
+        for row in rows {
+            let (subject, predicate, value) = row?;
+            let predicate = if predicate == rdfs::LABEL { rdfs::LABEL } else { ICON };
+            values.entry((subject, predicate)).or_insert(value);
+        }
+        Ok(values)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::eavto::{store, test_helpers::setup_test_db, Object, Triple};
+
+    #[test]
+    fn test_get_many_matches_get() {
+        let mut conn = setup_test_db();
+        let label = |value: &str| Object::Literal { value: value.to_string(), datatype: None, language: None };
+        store::assert_triples(&mut conn, &[
+            Triple::new("foundation:Car", rdfs::LABEL, label("Car")),
+            Triple::new("foundation:Car", ICON, label("directions_car")),
+            Triple::new("foundation:Bike", rdfs::LABEL, label("Bike")),
+        ], "test").unwrap();
+        store::assert_triples(&mut conn, &[Triple::new("foundation:Car", rdfs::LABEL, label("Automobile"))], "test").unwrap();
+
+        let iris = ["foundation:Bike", "foundation:Car", "foundation:Unknown", "foundation:Car"];
+        let things = Thing::get_many(&conn, &iris);
+        let each: Vec<Thing> = iris.iter().map(|iri| Thing::get(&conn, *iri)).collect();
+        assert_eq!(things, each);
+        assert_eq!(things[1].label, "Automobile");
+        assert_eq!(things[2].label, "foundation:Unknown");
+        assert!(Thing::get_many(&conn, &[]).is_empty());
+    }
+}
@@ -0,0 +1,159 @@
+// ============================================================================
+// OWL Tree - Class Hierarchy
+// ============================================================================
+// The subclass tree under a class, with how many subclasses and direct
+// instances each class has, for a sidebar tree view. One recursive query
+// walks the hierarchy and counts, however many classes it reaches.
+//
+// Named classes without a named superclass are under owl:Thing, as OWL has
+// them. A class with several superclasses appears under each of them.
+// Nodes below the depth come without their children, but with their child
+// count, so the view knows which ones can expand (another call from them).
+// ============================================================================
+
+use rusqlite::Connection;
+use std::collections::{HashMap, HashSet};
+use crate::eavto::query;
+use crate::owl::vocabulary::{owl, rdf, rdfs};
+use crate::owl::{Result, Thing};
+
+/// A class of the tree
+#[derive(Debug, Clone)]
+pub struct ClassTree {
+    pub thing: Thing,
+    pub child_count: u64,    // Direct subclasses, listed or not
+    pub instance_count: u64, // Direct instances (rdf:type)
+    pub children: Vec<ClassTree>, // By label, empty below the depth
+}
+
+/// The subclass tree under a class, `depth` levels deep (0: the root alone)
+pub fn class_tree(conn: &Connection, root: &str, depth: usize) -> Result<ClassTree> {
+    let sql = format!(
+        "WITH RECURSIVE
+           sub(child, parent) AS (
+             SELECT t.subject, t.object FROM triples t
+             WHERE t.predicate = ?3 AND t.object_type = 'iri' AND t.subject != t.object AND {0}
+             UNION
+             SELECT t.subject, ?4 FROM triples t
+             WHERE t.predicate = ?5 AND t.object = ?6 AND t.subject != ?4 AND t.subject NOT LIKE '\\_:%' ESCAPE '\\'
+               AND {0}
+               AND NOT EXISTS (SELECT 1 FROM triples s
+                               WHERE s.subject = t.subject AND s.predicate = ?3
+                                 AND s.object_type = 'iri' AND s.object != s.subject AND s.retracted = 0)
+           ),
+           tree(iri, parent, depth) AS (
+             SELECT ?1, NULL, 0
+             UNION
+             SELECT sub.child, sub.parent, tree.depth + 1 FROM sub JOIN tree ON sub.parent = tree.iri
+             WHERE tree.depth < ?2
+           )
+         SELECT DISTINCT tree.iri, tree.parent,
+                (SELECT COUNT(*) FROM sub WHERE sub.parent = tree.iri),
+                (SELECT COUNT(DISTINCT i.subject) FROM triples i WHERE i.predicate = ?5 AND i.object = tree.iri AND {1})
+         FROM tree",
+        query::visible("t"),
+        query::visible("i")
+    );
+    let mut stmt = conn.prepare(&sql)?;
+    let rows = stmt.query_map(
+        rusqlite::params![root, depth as i64, rdfs::SUB_CLASS_OF, owl::THING, rdf::TYPE, owl::CLASS],
+        |row| Ok((row.get::<_, String>(0)?, row.get::<_, Option<String>>(1)?, row.get::<_, u64>(2)?, row.get::<_, u64>(3)?)),
+    )?;
+
+    let mut children: HashMap<String, Vec<String>> = HashMap::new();
+    let mut counts: HashMap<String, (u64, u64)> = HashMap::new();
+    for row in rows {
+        let (iri, parent, child_count, instance_count) = row?;
+        if let Some(parent) = parent {
+            let siblings = children.entry(parent).or_default();
+            if !siblings.contains(&iri) {
+                siblings.push(iri.clone());
+            }
+        }
+        counts.insert(iri, (child_count, instance_count));
+    }
+
+    let iris: Vec<&str> = counts.keys().map(String::as_str).collect();
+    let things: HashMap<String, Thing> = Thing::get_many(conn, &iris)
+        .into_iter()
+        .map(|thing| (thing.iri.clone(), thing))
+        .collect();
+
+    Ok(build(root, depth, &things, &children, &counts, &mut HashSet::new()))
+}
+
+/// The tree under a class, from the rows of the query
+/// path: the classes above, so a subclass cycle ends where it loops
+fn build<'a>(
+    iri: &'a str,
+    depth: usize,
+    things: &HashMap<String, Thing>,
+    children: &'a HashMap<String, Vec<String>>,
+    counts: &HashMap<String, (u64, u64)>,
+    path: &mut HashSet<&'a str>,
+) -> ClassTree {
+    path.insert(iri);
+    let mut subtrees = Vec::new();
+    if let Some(subclasses) = children.get(iri).filter(|_| depth > 0) {
+        for subclass in subclasses {
+            if !path.contains(subclass.as_str()) {
+                subtrees.push(build(subclass, depth - 1, things, children, counts, path));
+            }
+        }
+    }
+    subtrees.sort_by_key(|subtree| subtree.thing.label.to_lowercase());
+    path.remove(iri);
+
+    let (child_count, instance_count) = counts[iri];
+    ClassTree {
+        thing: things[iri].clone(),
+        child_count,
+        instance_count,
+        children: subtrees,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::eavto::{store, test_helpers::setup_test_db, Object, Triple};
+
+    fn iri(value: &str) -> Object {
+        Object::Iri(value.to_string())
+    }
+
+    #[test]
+    fn test_class_tree_with_counts() {
+        let mut conn = setup_test_db();
+        store::assert_triples(&mut conn, &[
+            Triple::new("foundation:Vehicle", rdf::TYPE, iri(owl::CLASS)),
+            Triple::new("foundation:Car", rdfs::SUB_CLASS_OF, iri("foundation:Vehicle")),
+            Triple::new("foundation:Bike", rdfs::SUB_CLASS_OF, iri("foundation:Vehicle")),
+            Triple::new("foundation:SportsCar", rdfs::SUB_CLASS_OF, iri("foundation:Car")),
+            Triple::new("foundation:Place", rdfs::SUB_CLASS_OF, iri(owl::THING)),
+            Triple::new("_:union", rdf::TYPE, iri(owl::CLASS)),
+            Triple::new("foundation:myCar", rdf::TYPE, iri("foundation:Car")),
+            Triple::new("foundation:yourCar", rdf::TYPE, iri("foundation:Car")),
+        ], "test").unwrap();
+
+        // Vehicle has no superclass: it's under owl:Thing, as Place is
+        let tree = class_tree(&conn, owl::THING, 1).unwrap();
+        let roots: Vec<&str> = tree.children.iter().map(|c| c.thing.iri.as_str()).collect();
+        assert_eq!(roots, ["foundation:Place", "foundation:Vehicle"]);
+        assert_eq!(tree.child_count, 2);
+        let vehicle = &tree.children[1];
+        assert_eq!((vehicle.child_count, vehicle.children.len()), (2, 0));
+
+        let vehicle = class_tree(&conn, "foundation:Vehicle", 3).unwrap();
+        let car = vehicle.children.iter().find(|c| c.thing.iri == "foundation:Car").unwrap();
+        assert_eq!((car.child_count, car.instance_count), (1, 2));
+        assert_eq!(car.children[0].thing.iri, "foundation:SportsCar");
+
+        // A subclass cycle ends where it loops
+        store::assert_triples(&mut conn, &[
+            Triple::new("foundation:Vehicle", rdfs::SUB_CLASS_OF, iri("foundation:SportsCar")),
+        ], "test").unwrap();
+        let cycle = class_tree(&conn, "foundation:Vehicle", 10).unwrap();
+        assert!(cycle.children.iter().find(|c| c.thing.iri == "foundation:Car").unwrap().children[0].children.is_empty());
+    }
+}
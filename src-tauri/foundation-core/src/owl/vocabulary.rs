@@ -13,6 +13,9 @@ pub mod rdf {
     pub const PREDICATE: &str = "rdf:predicate";
     pub const OBJECT: &str = "rdf:object";
     pub const LANG_STRING: &str = "rdf:langString";
+    pub const FIRST: &str = "rdf:first";
+    pub const REST: &str = "rdf:rest";
+    pub const NIL: &str = "rdf:nil";
 }
 
 /// RDFS vocabulary
@@ -49,6 +52,7 @@ pub mod owl {
     pub const DISJOINT_WITH: &str = "owl:disjointWith";
     pub const EQUIVALENT_PROPERTY: &str = "owl:equivalentProperty";
     pub const INVERSE_OF: &str = "owl:inverseOf";
+    pub const PROPERTY_CHAIN_AXIOM: &str = "owl:propertyChainAxiom";
     pub const SAME_AS: &str = "owl:sameAs";
     pub const DIFFERENT_FROM: &str = "owl:differentFrom";
 
@@ -60,6 +64,39 @@ pub mod owl {
     pub const MIN_CARDINALITY: &str = "owl:minCardinality";
     pub const MAX_CARDINALITY: &str = "owl:maxCardinality";
     pub const CARDINALITY: &str = "owl:cardinality";
+    pub const ON_CLASS: &str = "owl:onClass";
+    pub const MIN_QUALIFIED_CARDINALITY: &str = "owl:minQualifiedCardinality";
+    pub const MAX_QUALIFIED_CARDINALITY: &str = "owl:maxQualifiedCardinality";
+    pub const QUALIFIED_CARDINALITY: &str = "owl:qualifiedCardinality";
+
+    pub const UNION_OF: &str = "owl:unionOf";
+    pub const INTERSECTION_OF: &str = "owl:intersectionOf";
+    pub const COMPLEMENT_OF: &str = "owl:complementOf";
+    pub const ONE_OF: &str = "owl:oneOf";
+
+    pub const ONTOLOGY: &str = "owl:Ontology";
+    pub const IMPORTS: &str = "owl:imports";
+    pub const VERSION_IRI: &str = "owl:versionIRI";
+    pub const VERSION_INFO: &str = "owl:versionInfo";
+    pub const PRIOR_VERSION: &str = "owl:priorVersion";
+}
+
+/// Dublin Core Elements vocabulary (ontology metadata)
+pub mod dc {
+    pub const TITLE: &str = "dc:title";
+    pub const CREATOR: &str = "dc:creator";
+    pub const RIGHTS: &str = "dc:rights";
+}
+
+/// Dublin Core Terms vocabulary (ontology metadata)
+pub mod dcterms {
+    pub const TITLE: &str = "dcterms:title";
+    pub const DESCRIPTION: &str = "dcterms:description";
+    pub const CREATOR: &str = "dcterms:creator";
+    pub const LICENSE: &str = "dcterms:license";
+    pub const RIGHTS: &str = "dcterms:rights";
+    pub const SOURCE: &str = "dcterms:source";
+    pub const CREATED: &str = "dcterms:created";
 }
 
 #[cfg(test)]
@@ -256,6 +293,11 @@ mod tests {
         assert_eq!(owl::INVERSE_OF, "owl:inverseOf");
     }
 
+    #[test]
+    fn test_owl_property_chain_axiom() {
+        assert_eq!(owl::PROPERTY_CHAIN_AXIOM, "owl:propertyChainAxiom");
+    }
+
     #[test]
     fn test_owl_same_as() {
         assert_eq!(owl::SAME_AS, "owl:sameAs");
@@ -310,6 +352,53 @@ mod tests {
         assert_eq!(owl::CARDINALITY, "owl:cardinality");
     }
 
+    #[test]
+    fn test_owl_qualified_cardinality() {
+        assert_eq!(owl::ON_CLASS, "owl:onClass");
+        assert_eq!(owl::MIN_QUALIFIED_CARDINALITY, "owl:minQualifiedCardinality");
+        assert_eq!(owl::MAX_QUALIFIED_CARDINALITY, "owl:maxQualifiedCardinality");
+        assert_eq!(owl::QUALIFIED_CARDINALITY, "owl:qualifiedCardinality");
+    }
+
+    // ========================================================================
+    // OWL Vocabulary Tests - Boolean Class Constructors
+    // ========================================================================
+
+    #[test]
+    fn test_owl_boolean_constructors() {
+        assert_eq!(owl::UNION_OF, "owl:unionOf");
+        assert_eq!(owl::INTERSECTION_OF, "owl:intersectionOf");
+        assert_eq!(owl::COMPLEMENT_OF, "owl:complementOf");
+        assert_eq!(owl::ONE_OF, "owl:oneOf");
+    }
+
+    // ========================================================================
+    // OWL Vocabulary Tests - Ontology Header
+    // ========================================================================
+
+    #[test]
+    fn test_owl_ontology_header() {
+        assert_eq!(owl::ONTOLOGY, "owl:Ontology");
+        assert_eq!(owl::IMPORTS, "owl:imports");
+        assert_eq!(owl::VERSION_IRI, "owl:versionIRI");
+        assert_eq!(owl::VERSION_INFO, "owl:versionInfo");
+        assert_eq!(owl::PRIOR_VERSION, "owl:priorVersion");
+    }
+
+    #[test]
+    fn test_dublin_core_metadata() {
+        assert_eq!(dc::TITLE, "dc:title");
+        assert_eq!(dcterms::LICENSE, "dcterms:license");
+        assert_eq!(dcterms::CREATOR, "dcterms:creator");
+    }
+
+    #[test]
+    fn test_rdf_list_vocabulary() {
+        assert_eq!(rdf::FIRST, "rdf:first");
+        assert_eq!(rdf::REST, "rdf:rest");
+        assert_eq!(rdf::NIL, "rdf:nil");
+    }
+
     // ========================================================================
     // Integration Tests
     // ========================================================================
@@ -0,0 +1,185 @@
+// ============================================================================
+// OWL Workspace - Partitioning Personal Data into Contexts
+// ============================================================================
+// Workspaces (foundation:Workspace individuals: Personal, Work, Research)
+// partition entities into contexts. An entity joins a workspace through a
+// foundation:inWorkspace annotation, usually added when its facts are
+// asserted.
+//
+// One workspace can be active at a time. While one is, the query layer only
+// shows entities in that workspace or in none at all, so shared data (the
+// ontology, the workspaces themselves) is always visible.
+// ============================================================================
+
+use rusqlite::Connection;
+use crate::eavto::{query, store, Object, Triple};
+use crate::owl::{Thing, Result, OwlError, vocabulary::rdf};
+
+/// Workspace class
+pub const WORKSPACE: &str = "foundation:Workspace";
+
+/// Workspace membership of an entity
+pub const IN_WORKSPACE: &str = query::IN_WORKSPACE;
+
+/// A workspace, as shown in the workspace switcher
+#[derive(Debug, Clone, PartialEq)]
+pub struct Workspace {
+    pub iri: String,
+    pub label: String,
+}
+
+/// All workspaces, by label
+pub fn list(conn: &Connection) -> Result<Vec<Workspace>> {
+    let mut workspaces: Vec<Workspace> = query::get_by_predicate_object(conn, rdf::TYPE, WORKSPACE)?
+        .triples
+        .into_iter()
+        .map(|t| Workspace { label: Thing::get(conn, &t.subject).label, iri: t.subject })
+        .collect();
+    workspaces.sort_by(|a, b| a.label.cmp(&b.label));
+    workspaces.dedup_by(|a, b| a.iri == b.iri);
+    Ok(workspaces)
+}
+
+/// The active workspace (None: all workspaces are shown)
+pub fn active(conn: &Connection) -> Result<Option<String>> {
+    Ok(query::get_metadata(conn, query::ACTIVE_WORKSPACE_KEY)?)
+}
+
+/// Switch the active workspace (None shows all workspaces)
+pub fn switch(conn: &Connection, workspace: Option<&str>) -> Result<()> {
+    if let Some(workspace) = workspace {
+        ensure_exists(conn, workspace)?;
+    }
+    store::set_metadata(conn, query::ACTIVE_WORKSPACE_KEY, workspace)?;
+    Ok(())
+}
+
+/// Add workspace membership for every entity the triples describe
+///
+/// Used at assert time, so new facts land in a workspace along with their
+/// entity. Blank nodes belong to the entity that refers to them and aren't
+/// annotated.
+pub fn annotate(triples: &[Triple], workspace: &str) -> Vec<Triple> {
+    let mut subjects: Vec<&str> = triples.iter()
+        .map(|t| t.subject.as_str())
+        .filter(|s| !s.starts_with("_:"))
+        .collect();
+    subjects.sort();
+    subjects.dedup();
+
+    triples.iter()
+        .cloned()
+        .chain(subjects.into_iter().map(|s| membership(s, workspace)))
+        .collect()
+}
+
+/// Move an entity to a workspace (None: no workspace, visible everywhere)
+///
+/// Returns the transaction ID (None if the membership was unchanged).
+pub fn assign(conn: &mut Connection, entity: &str, workspace: Option<&str>, origin: &str) -> Result<Option<i64>> {
+    if let Some(workspace) = workspace {
+        ensure_exists(conn, workspace)?;
+    }
+
+    // Membership is read unfiltered: the entity may be hidden by the active workspace
+    let mut stmt = conn.prepare(
+        "SELECT object FROM triples WHERE subject = ? AND predicate = ? AND retracted = 0"
+    )?;
+    let current = stmt
+        .query_map([entity, IN_WORKSPACE], |row| row.get::<_, String>(0))?
+        .collect::<std::result::Result<Vec<_>, _>>()?;
+    drop(stmt);
+
+    if current.len() <= 1 && current.first().map(String::as_str) == workspace {
+        return Ok(None);
+    }
+
+    let retractions: Vec<Triple> = current.iter().map(|w| membership(entity, w)).collect();
+    let additions: Vec<Triple> = workspace.map(|w| membership(entity, w)).into_iter().collect();
+    Ok(Some(store::apply_changes(conn, &additions, &retractions, origin)?))
+}
+
+fn membership(entity: &str, workspace: &str) -> Triple {
+    Triple::new(entity, IN_WORKSPACE, Object::Iri(workspace.to_string()))
+}
+
+fn ensure_exists(conn: &Connection, workspace: &str) -> Result<()> {
+    let is_workspace = query::get_by_entity_predicate(conn, workspace, rdf::TYPE)?
+        .triples
+        .iter()
+        .any(|t| t.object.as_iri() == Some(WORKSPACE));
+
+    if is_workspace {
+        Ok(())
+    } else {
+        Err(OwlError::NotFound(format!("Workspace {}", workspace)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::eavto::test_helpers::setup_test_db;
+
+    fn label(subject: &str, value: &str) -> Triple {
+        Triple::new(subject, "rdfs:label", Object::Literal {
+            value: value.to_string(),
+            datatype: Some("xsd:string".to_string()),
+            language: None,
+        })
+    }
+
+    fn setup() -> Connection {
+        let mut conn = setup_test_db();
+        store::assert_triples(&mut conn, &[
+            Triple::new("foundation:Workspace_Personal", rdf::TYPE, Object::Iri(WORKSPACE.to_string())),
+            label("foundation:Workspace_Personal", "Personal"),
+            Triple::new("foundation:Workspace_Work", rdf::TYPE, Object::Iri(WORKSPACE.to_string())),
+            label("foundation:Workspace_Work", "Work"),
+        ], "test").unwrap();
+        conn
+    }
+
+    fn is_visible(conn: &Connection, entity: &str) -> bool {
+        !query::get_by_entity(conn, entity).unwrap().triples.is_empty()
+    }
+
+    #[test]
+    fn test_active_workspace_filters_entities() {
+        let mut conn = setup();
+        store::assert_triples(&mut conn, &annotate(&[label("foundation:Dentist", "Dentist")], "foundation:Workspace_Personal"), "test").unwrap();
+        store::assert_triples(&mut conn, &annotate(&[label("foundation:Client", "Client")], "foundation:Workspace_Work"), "test").unwrap();
+        store::assert_triples(&mut conn, &[label("foundation:Shared", "Shared")], "test").unwrap();
+
+        // No active workspace: everything is visible
+        assert_eq!(active(&conn).unwrap(), None);
+        assert!(is_visible(&conn, "foundation:Dentist") && is_visible(&conn, "foundation:Client"));
+
+        switch(&conn, Some("foundation:Workspace_Work")).unwrap();
+        assert_eq!(active(&conn).unwrap().as_deref(), Some("foundation:Workspace_Work"));
+        assert!(is_visible(&conn, "foundation:Client"));
+        assert!(!is_visible(&conn, "foundation:Dentist"));
+        assert!(is_visible(&conn, "foundation:Shared"));
+        assert_eq!(list(&conn).unwrap().len(), 2);
+
+        switch(&conn, None).unwrap();
+        assert!(is_visible(&conn, "foundation:Dentist"));
+
+        assert!(switch(&conn, Some("foundation:Dentist")).is_err());
+    }
+
+    #[test]
+    fn test_assign_moves_hidden_entities() {
+        let mut conn = setup();
+        store::assert_triples(&mut conn, &annotate(&[label("foundation:Paper", "Paper")], "foundation:Workspace_Personal"), "test").unwrap();
+        switch(&conn, Some("foundation:Workspace_Work")).unwrap();
+        assert!(!is_visible(&conn, "foundation:Paper"));
+
+        assert!(assign(&mut conn, "foundation:Paper", Some("foundation:Workspace_Work"), "test").unwrap().is_some());
+        assert!(is_visible(&conn, "foundation:Paper"));
+        assert!(assign(&mut conn, "foundation:Paper", Some("foundation:Workspace_Work"), "test").unwrap().is_none());
+
+        let memberships = query::get_by_entity_predicate(&conn, "foundation:Paper", IN_WORKSPACE).unwrap();
+        assert_eq!(memberships.triples.len(), 1);
+    }
+}
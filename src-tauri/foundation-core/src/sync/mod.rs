@@ -0,0 +1,425 @@
+// ============================================================================
+// Sync - Changesets Between FOUNDATION Instances
+// ============================================================================
+// A changeset is one transaction as it travels between devices: its origin,
+// time, message and tags, its signature, the triple rows it asserted and
+// retracted, and the blobs its large literals live in. Rows travel column
+// by column, like database merges copy them (eavto::merge), so nothing is
+// re-parsed on the way.
+//
+// A changeset's id is the hash of its content, so the same transaction is
+// recognized whichever device sends it: ids of changesets served and
+// applied are kept (sync_changesets), and a known changeset is never
+// applied twice, including one that comes back from the device it was
+// sent to.
+//
+// Applying a changeset writes a transaction with the same origin and time,
+// recording when it was received (see eavto::skew). Rows already current
+// are skipped; when nothing is left to write, only the id is recorded.
+// The signature is kept when every row was written, so the transaction
+// verifies here as it did on its device (identity::verify_transaction).
+//
+// The transport is p2p: instances on the local network find each other
+// and pull the changesets they miss.
+// ============================================================================
+
+pub mod p2p;
+
+use rusqlite::{Connection, OptionalExtension};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use crate::eavto::{blob, query, quarantine::{self, OriginStatus}, store};
+
+type Result<T> = std::result::Result<T, Box<dyn std::error::Error>>;
+
+/// Metadata key of this instance's sync id
+const PEER_ID_KEY: &str = "sync_peer_id";
+
+/// Metadata key prefix of the cursor of each peer: the last of its
+/// transactions pulled
+const CURSOR_KEY: &str = "sync_cursor:";
+
+/// One transaction, as it travels between instances
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Changeset {
+    pub id: String,
+    pub tx: i64, // Transaction ID on the instance that sent it
+    pub origin: String,
+    pub created_at: i64,
+    pub message: Option<String>,
+    pub tags: Vec<String>,
+    pub signature: Option<String>,
+    pub signer: Option<String>,
+    pub asserted: Vec<Row>,
+    pub retracted: Vec<Row>,
+    pub blobs: Vec<Blob>,
+}
+
+/// A triple row, column by column
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Row {
+    pub subject: String,
+    pub predicate: String,
+    pub object: Option<String>,
+    pub object_value: Option<String>,
+    pub object_datatype: Option<String>,
+    pub object_language: Option<String>,
+    pub object_type: String,
+    pub object_number: Option<f64>,
+    pub object_integer: Option<i64>,
+    pub object_datetime: Option<i64>,
+    pub object_boolean: Option<i64>,
+    pub graph: String,
+}
+
+/// A large literal a row refers to (eavto::blob)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Blob {
+    pub hash: String,
+    pub content: String,
+    pub datatype: Option<String>,
+    pub language: Option<String>,
+}
+
+/// This instance's sync id, created on first use
+pub fn peer_id(conn: &Connection) -> Result<String> {
+    if let Some(id) = query::get_metadata(conn, PEER_ID_KEY)? {
+        return Ok(id);
+    }
+    let mut bytes = [0u8; 16];
+    rand_core::RngCore::fill_bytes(&mut rand_core::OsRng, &mut bytes);
+    let id = hex::encode(bytes);
+    store::set_metadata(conn, PEER_ID_KEY, Some(&id))?;
+    Ok(id)
+}
+
+/// The last transaction pulled from a peer (0: none yet)
+pub fn cursor(conn: &Connection, peer: &str) -> Result<i64> {
+    Ok(query::get_metadata(conn, &format!("{}{}", CURSOR_KEY, peer))?
+        .and_then(|value| value.parse().ok())
+        .unwrap_or(0))
+}
+
+/// Record the last transaction pulled from a peer
+pub fn set_cursor(conn: &Connection, peer: &str, tx: i64) -> Result<()> {
+    store::set_metadata(conn, &format!("{}{}", CURSOR_KEY, peer), Some(&tx.to_string()))
+}
+
+/// Changesets of the transactions after one, oldest first, with the last
+/// transaction examined (pass it as `after_tx` next time)
+///
+/// Left out: the core ontology (every instance imports its own),
+/// quarantined origins, and transactions with nothing in them. The ids of
+/// the changesets are recorded, so they aren't applied back here.
+pub fn changesets_after(conn: &Connection, after_tx: i64, limit: usize) -> Result<(Vec<Changeset>, i64)> {
+    let transactions = query::get_transactions_after(conn, after_tx, limit)?;
+    let last_tx = transactions.last().map_or(after_tx, |t| t.tx);
+
+    let mut changesets = Vec::new();
+    for transaction in transactions {
+        if query::is_core_origin(&transaction.origin)
+            || quarantine::get_status(conn, &transaction.origin)? == Some(OriginStatus::Quarantined)
+        {
+            continue;
+        }
+        let asserted = rows(conn, "tx = ?", transaction.tx)?;
+        let retracted = rows(conn, "retracted_tx = ?", transaction.tx)?;
+        if asserted.is_empty() && retracted.is_empty() {
+            continue;
+        }
+
+        let (signature, signer) = conn.query_row(
+            "SELECT signature, signer FROM transactions WHERE tx = ?",
+            [transaction.tx],
+            |row| Ok((row.get(0)?, row.get(1)?)),
+        )?;
+        let mut blobs = Vec::new();
+        for row in &asserted {
+            if let Some(reference) = row.object.as_deref().filter(|o| blob::is_reference(o)) {
+                blobs.extend(read_blob(conn, reference)?);
+            }
+        }
+
+        let id = id(&transaction.origin, transaction.created_at, &asserted, &retracted);
+        record(conn, &id, Some(transaction.tx), None)?;
+        changesets.push(Changeset {
+            id,
+            tx: transaction.tx,
+            origin: transaction.origin,
+            created_at: transaction.created_at,
+            message: transaction.message,
+            tags: transaction.tags,
+            signature,
+            signer,
+            asserted,
+            retracted,
+            blobs,
+        });
+    }
+    Ok((changesets, last_tx))
+}
+
+/// Apply a changeset received from a peer
+///
+/// Returns the transaction written, or None when the changeset was already
+/// known or had nothing new.
+pub fn apply(conn: &mut Connection, changeset: &Changeset, peer: &str) -> Result<Option<i64>> {
+    if id(&changeset.origin, changeset.created_at, &changeset.asserted, &changeset.retracted) != changeset.id {
+        return Err(format!("Changeset {} doesn't match its content", changeset.id).into());
+    }
+    if is_known(conn, &changeset.id)? {
+        return Ok(None);
+    }
+
+    let tx = conn.savepoint()?;
+    let asserted: Vec<&Row> = changeset.asserted.iter()
+        .map(|row| Ok((row, is_current(&tx, row)?)))
+        .collect::<Result<Vec<_>>>()?
+        .into_iter()
+        .filter_map(|(row, current)| (!current).then_some(row))
+        .collect();
+    let retracted: Vec<&Row> = changeset.retracted.iter()
+        .map(|row| Ok((row, is_current(&tx, row)?)))
+        .collect::<Result<Vec<_>>>()?
+        .into_iter()
+        .filter_map(|(row, current)| current.then_some(row))
+        .collect();
+
+    let received_at = store::now_millis();
+    if asserted.is_empty() && retracted.is_empty() {
+        record(&tx, &changeset.id, None, Some(peer))?;
+        tx.commit()?;
+        return Ok(None);
+    }
+
+    // The signature only holds for the transaction as it was signed
+    let complete = asserted.len() == changeset.asserted.len();
+    let tags = match changeset.tags.is_empty() {
+        true => None,
+        false => Some(serde_json::to_string(&changeset.tags)?),
+    };
+    tx.execute(
+        "INSERT INTO transactions (origin, created_at, received_at, message, tags, signature, signer)
+         VALUES (?, ?, ?, ?, ?, ?, ?)",
+        rusqlite::params![
+            changeset.origin, changeset.created_at, received_at, changeset.message, tags,
+            changeset.signature.as_ref().filter(|_| complete), changeset.signer.as_ref().filter(|_| complete),
+        ],
+    )?;
+    let tx_id = tx.last_insert_rowid();
+    let origin_id = store::get_or_create_origin(&tx, &changeset.origin)?;
+
+    for row in asserted {
+        tx.execute(
+            "INSERT INTO triples
+                (subject, predicate, object, object_value, object_datatype, object_language, object_type,
+                 object_number, object_integer, object_datetime, object_boolean,
+                 graph, tx, origin_id, retracted, created_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, 0, ?15)",
+            rusqlite::params![
+                row.subject, row.predicate, row.object, row.object_value, row.object_datatype,
+                row.object_language, row.object_type, row.object_number, row.object_integer,
+                row.object_datetime, row.object_boolean, row.graph, tx_id, origin_id, changeset.created_at,
+            ],
+        )?;
+    }
+    for row in retracted {
+        tx.execute(
+            "UPDATE triples SET retracted = 1, retracted_tx = ?9
+             WHERE subject = ?1 AND predicate = ?2 AND object_type = ?3
+               AND object IS ?4 AND object_value IS ?5
+               AND object_datatype IS ?6 AND object_language IS ?7
+               AND graph = ?8
+               AND retracted = 0",
+            rusqlite::params![
+                row.subject, row.predicate, row.object_type, row.object, row.object_value,
+                row.object_datatype, row.object_language, row.graph, tx_id,
+            ],
+        )?;
+    }
+    for blob in &changeset.blobs {
+        tx.execute(
+            "INSERT OR IGNORE INTO blobs (hash, content, datatype, language, size, created_at) VALUES (?, ?, ?, ?, ?, ?)",
+            rusqlite::params![blob.hash, blob.content, blob.datatype, blob.language, blob.content.len() as i64, received_at],
+        )?;
+    }
+
+    record(&tx, &changeset.id, Some(tx_id), Some(peer))?;
+    tx.commit()?;
+    Ok(Some(tx_id))
+}
+
+/// Content id of a changeset: what it says, whatever the order of its
+/// rows and wherever it is stored
+fn id(origin: &str, created_at: i64, asserted: &[Row], retracted: &[Row]) -> String {
+    let lines = |rows: &[Row]| {
+        let mut lines: Vec<String> = rows.iter()
+            .map(|row| serde_json::to_string(row).unwrap_or_default())
+            .collect();
+        lines.sort();
+        lines.join("\n")
+    };
+
+    let mut hasher = Sha256::new();
+    hasher.update(format!("{}\n{}\n", origin, created_at));
+    hasher.update(lines(asserted));
+    hasher.update("\n--\n");
+    hasher.update(lines(retracted));
+    hex::encode(hasher.finalize())
+}
+
+/// Rows of the triples table matching a condition on one transaction
+fn rows(conn: &Connection, condition: &str, tx: i64) -> Result<Vec<Row>> {
+    let mut stmt = conn.prepare(&format!(
+        "SELECT subject, predicate, object, object_value, object_datatype, object_language, object_type,
+                object_number, object_integer, object_datetime, object_boolean, graph
+         FROM triples
+         WHERE {}
+         ORDER BY rowid",
+        condition
+    ))?;
+    let rows = stmt.query_map([tx], |row| {
+        Ok(Row {
+            subject: row.get(0)?,
+            predicate: row.get(1)?,
+            object: row.get(2)?,
+            object_value: row.get(3)?,
+            object_datatype: row.get(4)?,
+            object_language: row.get(5)?,
+            object_type: row.get(6)?,
+            object_number: row.get(7)?,
+            object_integer: row.get(8)?,
+            object_datetime: row.get(9)?,
+            object_boolean: row.get(10)?,
+            graph: row.get(11)?,
+        })
+    })?.collect::<std::result::Result<Vec<_>, _>>()?;
+    Ok(rows)
+}
+
+/// The blob a reference stands for
+fn read_blob(conn: &Connection, reference: &str) -> Result<Option<Blob>> {
+    let hash = &reference[blob::BLOB_PREFIX.len()..];
+    Ok(conn.query_row(
+        "SELECT content, datatype, language FROM blobs WHERE hash = ?",
+        [hash],
+        |row| Ok(Blob { hash: hash.to_string(), content: row.get(0)?, datatype: row.get(1)?, language: row.get(2)? }),
+    ).optional()?)
+}
+
+/// Whether a row is current here (same value in the same graph)
+fn is_current(conn: &Connection, row: &Row) -> Result<bool> {
+    Ok(conn.query_row(
+        "SELECT EXISTS(
+            SELECT 1 FROM triples
+            WHERE subject = ?1 AND predicate = ?2 AND object_type = ?3
+              AND object IS ?4 AND object_value IS ?5
+              AND object_datatype IS ?6 AND object_language IS ?7
+              AND graph = ?8
+              AND retracted = 0)",
+        rusqlite::params![
+            row.subject, row.predicate, row.object_type, row.object, row.object_value,
+            row.object_datatype, row.object_language, row.graph,
+        ],
+        |r| r.get(0),
+    )?)
+}
+
+fn is_known(conn: &Connection, id: &str) -> Result<bool> {
+    Ok(conn.query_row("SELECT EXISTS(SELECT 1 FROM sync_changesets WHERE id = ?)", [id], |row| row.get(0))?)
+}
+
+/// Record a changeset id, with its transaction here (None: nothing was written)
+fn record(conn: &Connection, id: &str, tx: Option<i64>, peer: Option<&str>) -> Result<()> {
+    conn.execute(
+        "INSERT OR IGNORE INTO sync_changesets (id, tx, peer, recorded_at) VALUES (?, ?, ?, ?)",
+        rusqlite::params![id, tx, peer, store::now_millis()],
+    )?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::eavto::{test_helpers::setup_test_db, Object, Triple};
+
+    fn label(subject: &str, value: &str) -> Triple {
+        Triple::new(subject, "rdfs:label", Object::Literal {
+            value: value.to_string(),
+            datatype: Some("xsd:string".to_string()),
+            language: None,
+        })
+    }
+
+    #[test]
+    fn test_changesets_travel_and_dont_come_back() {
+        let mut a = setup_test_db();
+        let mut b = setup_test_db();
+        store::assert_triples(&mut a, &[
+            label("foundation:Ana", "Ana"),
+            Triple::new("foundation:Ana", "foundation:age", Object::Integer(30)),
+        ], "user-edit").unwrap();
+        store::apply_changes(&mut a, &[label("foundation:Ana", "Ana Lima")], &[label("foundation:Ana", "Ana")], "user-edit").unwrap();
+
+        let (changesets, last_tx) = changesets_after(&a, 0, 10).unwrap();
+        assert_eq!(changesets.len(), 2);
+        assert_eq!(last_tx, changesets[1].tx);
+        assert_eq!(changesets[1].retracted.len(), 1);
+        for changeset in &changesets {
+            assert!(apply(&mut b, changeset, "a").unwrap().is_some());
+            assert!(apply(&mut b, changeset, "a").unwrap().is_none());
+        }
+
+        let labels = query::get_by_entity_predicate(&b, "foundation:Ana", "rdfs:label").unwrap().triples;
+        assert_eq!(labels.len(), 1);
+        assert_eq!(labels[0].object.as_literal().as_deref(), Some("Ana Lima"));
+        let age = query::get_by_entity_predicate(&b, "foundation:Ana", "foundation:age").unwrap().triples;
+        assert_eq!(age[0].object, Object::Integer(30));
+
+        // B serves them back with the same ids, and A already knows them
+        let (echoed, _) = changesets_after(&b, 0, 10).unwrap();
+        assert_eq!(echoed.iter().map(|c| &c.id).collect::<Vec<_>>(), changesets.iter().map(|c| &c.id).collect::<Vec<_>>());
+        for changeset in &echoed {
+            assert!(apply(&mut a, changeset, "b").unwrap().is_none());
+        }
+    }
+
+    #[test]
+    fn test_apply_keeps_the_signature() {
+        let mut a = setup_test_db();
+        let mut b = setup_test_db();
+        let tx = store::assert_triples(&mut a, &[label("foundation:Note", "Note")], "user-edit").unwrap();
+        let identity = crate::identity::Identity::generate();
+        crate::identity::sign_transaction(&a, tx, &identity).unwrap();
+
+        let (changesets, _) = changesets_after(&a, 0, 10).unwrap();
+        let applied = apply(&mut b, &changesets[0], "a").unwrap().unwrap();
+        assert_eq!(
+            crate::identity::verify_transaction(&b, applied).unwrap(),
+            crate::identity::Verification::Valid { signer: identity.public_key() },
+        );
+    }
+
+    #[test]
+    fn test_apply_rejects_tampered_changesets() {
+        let mut a = setup_test_db();
+        store::assert_triples(&mut a, &[label("foundation:Note", "Note")], "user-edit").unwrap();
+        let (mut changesets, _) = changesets_after(&a, 0, 10).unwrap();
+        changesets[0].asserted[0].object_value = Some("Forged".to_string());
+        assert!(apply(&mut setup_test_db(), &changesets[0], "a").is_err());
+    }
+
+    #[test]
+    fn test_peer_id_and_cursor() {
+        let conn = setup_test_db();
+        let id = peer_id(&conn).unwrap();
+        assert_eq!(id.len(), 32);
+        assert_eq!(peer_id(&conn).unwrap(), id);
+
+        assert_eq!(cursor(&conn, "other").unwrap(), 0);
+        set_cursor(&conn, "other", 42).unwrap();
+        assert_eq!(cursor(&conn, "other").unwrap(), 42);
+    }
+}
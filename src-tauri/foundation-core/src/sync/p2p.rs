@@ -0,0 +1,404 @@
+// ============================================================================
+// Sync P2P - Instances on the Local Network
+// ============================================================================
+// Each running instance listens on a TCP port and announces itself over
+// mDNS (_foundation._tcp), named by its sync id. Instances that find each
+// other exchange their transaction heads and pull the changesets they
+// miss, every SYNC_INTERVAL and whenever a peer appears.
+//
+// Protocol: newline-delimited JSON messages. The puller connects and says
+// hello (its sync id, name and head), the other side answers the same; if
+// the other side's head is past the puller's cursor for it, the puller
+// asks for the transactions after the cursor, and receives them as
+// changesets followed by the end marker. Each side pulls from the other,
+// so both end up with everything.
+//
+// There is no pairing yet: sync is for trusted networks. Whoever sent a
+// transaction, its signature tells who made it (identity).
+// ============================================================================
+
+use mdns_sd::{ServiceDaemon, ServiceEvent, ServiceInfo};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::io::{BufRead, BufReader, Write};
+use std::net::{SocketAddr, TcpListener, TcpStream};
+use std::sync::{mpsc, Arc, Mutex};
+use std::time::Duration;
+use tokio::runtime::Runtime;
+use ts_rs::TS;
+use super::Changeset;
+use crate::eavto::{store::now_millis, DbExecutor};
+
+type Result<T> = std::result::Result<T, Box<dyn std::error::Error>>;
+
+/// mDNS service type instances announce themselves with
+pub const SERVICE_TYPE: &str = "_foundation._tcp.local.";
+
+/// Time between pulls from known peers
+const SYNC_INTERVAL: Duration = Duration::from_secs(30);
+
+/// How long connecting to a peer, or waiting for its next message, may take
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(5);
+const READ_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Transactions read from the store per batch of changesets
+const BATCH: usize = 100;
+
+/// State of the sync service
+#[derive(Debug, Clone, Default, Serialize, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export)]
+pub struct SyncStatus {
+    pub running: bool,
+    pub peer_id: Option<String>, // This instance's sync id
+    pub port: Option<u16>,       // TCP port peers connect to
+    #[ts(type = "number")]
+    pub peers: usize,
+    #[ts(type = "number")]
+    pub received: u64,           // Changesets applied since the service started
+    #[ts(type = "number | null")]
+    pub last_sync_at: Option<i64>,
+    pub last_error: Option<String>,
+}
+
+/// Another instance found on the network
+#[derive(Debug, Clone, Serialize, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export)]
+pub struct Peer {
+    pub id: String,
+    pub name: String,
+    pub addresses: Vec<String>, // ip:port
+    #[ts(type = "number | null")]
+    pub head: Option<i64>,      // Its latest transaction, when last heard from
+    #[ts(type = "number")]
+    pub cursor: i64,            // Its last transaction pulled
+    #[ts(type = "number")]
+    pub last_seen: i64,
+    #[ts(type = "number | null")]
+    pub last_synced: Option<i64>,
+}
+
+/// A protocol message
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum Message {
+    Hello { peer: String, name: String, head: i64 },
+    Pull { after: i64 },
+    Changeset { changeset: Box<Changeset> },
+    End { last: i64 },
+}
+
+/// What a pull brought
+#[derive(Debug, Clone, PartialEq)]
+pub struct Pulled {
+    pub peer: String,
+    pub name: String,
+    pub head: i64,
+    pub cursor: i64,
+    pub applied: u64,
+}
+
+/// The sync service; clones share it
+#[derive(Clone, Default)]
+pub struct Service {
+    state: Arc<Mutex<State>>,
+}
+
+#[derive(Default)]
+struct State {
+    status: SyncStatus,
+    peers: BTreeMap<String, Peer>,
+    daemon: Option<ServiceDaemon>,
+}
+
+impl Service {
+    /// Start listening, announcing and pulling (does nothing if running)
+    ///
+    /// name: how this instance is shown to others (e.g. the host name)
+    pub fn start(&self, executor: DbExecutor, name: &str) -> Result<SyncStatus> {
+        if self.status().running {
+            return Ok(self.status());
+        }
+
+        let runtime = runtime()?;
+        let peer_id = runtime.block_on(executor.write(|conn| super::peer_id(conn).map_err(|e| e.to_string())))?.value;
+        let listener = TcpListener::bind("0.0.0.0:0")?;
+        let port = listener.local_addr()?.port();
+
+        let daemon = ServiceDaemon::new()?;
+        let info = ServiceInfo::new(
+            SERVICE_TYPE, &peer_id, &format!("{}.local.", peer_id), "", port, &[("name", name)][..],
+        )?.enable_addr_auto();
+        daemon.register(info)?;
+        let events = daemon.browse(SERVICE_TYPE)?;
+
+        {
+            let mut state = self.lock();
+            state.status = SyncStatus {
+                running: true,
+                peer_id: Some(peer_id.clone()),
+                port: Some(port),
+                ..SyncStatus::default()
+            };
+            state.daemon = Some(daemon);
+        }
+
+        // Serve pulls from peers
+        let serving = executor.clone();
+        let (me, my_name) = (peer_id.clone(), name.to_string());
+        std::thread::spawn(move || {
+            for stream in listener.incoming() {
+                let Ok(stream) = stream else { continue };
+                let (executor, me, my_name) = (serving.clone(), me.clone(), my_name.clone());
+                std::thread::spawn(move || {
+                    if let Err(e) = serve(stream, &executor, &me, &my_name) {
+                        println!("⚠️  Sync: {}", e);
+                    }
+                });
+            }
+        });
+
+        // Track peers as they are announced; a new one is pulled from at once
+        let (trigger, triggered) = mpsc::channel::<()>();
+        let discovery = self.clone();
+        let me = peer_id.clone();
+        std::thread::spawn(move || {
+            while let Ok(event) = events.recv() {
+                if let ServiceEvent::ServiceResolved(info) = event {
+                    let id = info.get_fullname().trim_end_matches(SERVICE_TYPE).trim_end_matches('.').to_string();
+                    if id == me {
+                        continue;
+                    }
+                    let addresses = info.get_addresses().iter()
+                        .map(|ip| SocketAddr::new(*ip, info.get_port()).to_string())
+                        .collect();
+                    let name = info.get_property_val_str("name").unwrap_or(&id).to_string();
+                    discovery.seen(&id, &name, addresses);
+                    let _ = trigger.send(());
+                }
+            }
+        });
+
+        // Pull from every known peer
+        let puller = self.clone();
+        let my_name = name.to_string();
+        std::thread::spawn(move || loop {
+            puller.pull_all(&executor, &runtime, &peer_id, &my_name);
+            match triggered.recv_timeout(SYNC_INTERVAL) {
+                Ok(()) | Err(mpsc::RecvTimeoutError::Timeout) => {}
+                Err(mpsc::RecvTimeoutError::Disconnected) => break,
+            }
+        });
+
+        Ok(self.status())
+    }
+
+    /// State of the service
+    pub fn status(&self) -> SyncStatus {
+        let state = self.lock();
+        SyncStatus { peers: state.peers.len(), ..state.status.clone() }
+    }
+
+    /// Peers found, by id
+    pub fn peers(&self) -> Vec<Peer> {
+        self.lock().peers.values().cloned().collect()
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, State> {
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Record a peer announced on the network
+    fn seen(&self, id: &str, name: &str, addresses: Vec<String>) {
+        let mut state = self.lock();
+        let peer = state.peers.entry(id.to_string()).or_insert_with(|| Peer {
+            id: id.to_string(),
+            name: name.to_string(),
+            addresses: Vec::new(),
+            head: None,
+            cursor: 0,
+            last_seen: 0,
+            last_synced: None,
+        });
+        peer.name = name.to_string();
+        peer.addresses = addresses;
+        peer.last_seen = now_millis();
+    }
+
+    /// Pull from each known peer, from the first of its addresses that answers
+    fn pull_all(&self, executor: &DbExecutor, runtime: &Runtime, me: &str, name: &str) {
+        for peer in self.peers() {
+            let mut error = None;
+            for address in peer.addresses.iter().filter_map(|a| a.parse::<SocketAddr>().ok()) {
+                match pull(address, executor, runtime, me, name) {
+                    Ok(pulled) => {
+                        error = None;
+                        let now = now_millis();
+                        let mut state = self.lock();
+                        state.status.received += pulled.applied;
+                        state.status.last_sync_at = Some(now);
+                        if let Some(peer) = state.peers.get_mut(&pulled.peer) {
+                            peer.head = Some(pulled.head);
+                            peer.cursor = pulled.cursor;
+                            peer.last_seen = now;
+                            peer.last_synced = Some(now);
+                        }
+                        break;
+                    }
+                    Err(e) => error = Some(format!("{} ({}): {}", peer.name, address, e)),
+                }
+            }
+            if let Some(error) = error {
+                self.lock().status.last_error = Some(error);
+            }
+        }
+    }
+}
+
+/// Pull the changesets a peer has and this instance misses
+pub fn pull(address: SocketAddr, executor: &DbExecutor, runtime: &Runtime, me: &str, name: &str) -> Result<Pulled> {
+    let stream = TcpStream::connect_timeout(&address, CONNECT_TIMEOUT)?;
+    stream.set_read_timeout(Some(READ_TIMEOUT))?;
+    let mut reader = BufReader::new(stream.try_clone()?);
+    let mut writer = stream;
+
+    send(&mut writer, &Message::Hello { peer: me.to_string(), name: name.to_string(), head: executor.committed_tx() })?;
+    let Message::Hello { peer, name, head } = receive(&mut reader)? else {
+        return Err("Expected hello".into());
+    };
+    let peer_key = peer.clone();
+    let mut cursor = runtime.block_on(executor.read(None, move |conn| {
+        super::cursor(conn, &peer_key).map_err(|e| e.to_string())
+    }))?;
+    let mut pulled = Pulled { peer, name, head, cursor, applied: 0 };
+    if head <= cursor {
+        return Ok(pulled);
+    }
+
+    send(&mut writer, &Message::Pull { after: cursor })?;
+    loop {
+        match receive(&mut reader)? {
+            Message::Changeset { changeset } => {
+                let peer = pulled.peer.clone();
+                let applied = runtime.block_on(executor.write(move |conn| {
+                    let applied = super::apply(conn, &changeset, &peer).map_err(|e| e.to_string())?;
+                    super::set_cursor(conn, &peer, changeset.tx).map_err(|e| e.to_string())?;
+                    Ok(applied)
+                }))?.value;
+                if applied.is_some() {
+                    pulled.applied += 1;
+                }
+            }
+            Message::End { last } => {
+                cursor = last;
+                break;
+            }
+            _ => return Err("Expected a changeset".into()),
+        }
+    }
+
+    let peer = pulled.peer.clone();
+    runtime.block_on(executor.write(move |conn| super::set_cursor(conn, &peer, cursor).map_err(|e| e.to_string())))?;
+    pulled.cursor = cursor;
+    Ok(pulled)
+}
+
+/// Answer a peer's pull
+pub fn serve(stream: TcpStream, executor: &DbExecutor, me: &str, name: &str) -> Result<()> {
+    stream.set_read_timeout(Some(READ_TIMEOUT))?;
+    let runtime = runtime()?;
+    let mut reader = BufReader::new(stream.try_clone()?);
+    let mut writer = stream;
+
+    let Message::Hello { .. } = receive(&mut reader)? else {
+        return Err("Expected hello".into());
+    };
+    let head = executor.committed_tx();
+    send(&mut writer, &Message::Hello { peer: me.to_string(), name: name.to_string(), head })?;
+
+    let mut cursor = match receive(&mut reader) {
+        Ok(Message::Pull { after }) => after,
+        Ok(_) => return Err("Expected pull".into()),
+        Err(_) => return Ok(()), // Up to date: the peer hung up
+    };
+    while cursor < head {
+        // Serving records the changesets' ids, so they aren't applied back here
+        let (changesets, last) = runtime.block_on(executor.write(move |conn| {
+            super::changesets_after(conn, cursor, BATCH).map_err(|e| e.to_string())
+        }))?.value;
+        for changeset in changesets {
+            send(&mut writer, &Message::Changeset { changeset: Box::new(changeset) })?;
+        }
+        if last == cursor {
+            break;
+        }
+        cursor = last;
+    }
+    send(&mut writer, &Message::End { last: cursor })
+}
+
+fn send(writer: &mut TcpStream, message: &Message) -> Result<()> {
+    let mut line = serde_json::to_vec(message)?;
+    line.push(b'\n');
+    writer.write_all(&line)?;
+    Ok(())
+}
+
+fn receive(reader: &mut BufReader<TcpStream>) -> Result<Message> {
+    let mut line = String::new();
+    if reader.read_line(&mut line)? == 0 {
+        return Err("Connection closed".into());
+    }
+    Ok(serde_json::from_str(&line)?)
+}
+
+fn runtime() -> Result<Runtime> {
+    Ok(tokio::runtime::Builder::new_current_thread().enable_time().build()?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::eavto::{query, store, test_helpers::setup_test_db, Object, Triple};
+
+    /// Serve pulls from an executor on a loopback port
+    fn listen(executor: DbExecutor, me: &'static str) -> SocketAddr {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let address = listener.local_addr().unwrap();
+        std::thread::spawn(move || {
+            for stream in listener.incoming() {
+                serve(stream.unwrap(), &executor, me, me).unwrap();
+            }
+        });
+        address
+    }
+
+    #[test]
+    fn test_pull_between_instances() {
+        let mut conn = setup_test_db();
+        store::assert_triples(&mut conn, &[
+            Triple::new("foundation:Ana", "foundation:age", Object::Integer(30)),
+        ], "user-edit").unwrap();
+        let a = DbExecutor::new(conn);
+        let b = DbExecutor::new(setup_test_db());
+        let runtime = runtime().unwrap();
+
+        let address = listen(a.clone(), "a");
+        let pulled = pull(address, &b, &runtime, "b", "b").unwrap();
+        assert_eq!((pulled.peer.as_str(), pulled.applied, pulled.cursor), ("a", 1, a.committed_tx()));
+
+        // Up to date: nothing more to pull
+        assert_eq!(pull(address, &b, &runtime, "b", "b").unwrap().applied, 0);
+
+        let ages = runtime.block_on(b.read(None, |conn| {
+            query::get_by_entity_predicate(conn, "foundation:Ana", "foundation:age").map_err(|e| e.to_string())
+        })).unwrap().triples;
+        assert_eq!(ages.len(), 1);
+        assert_eq!(ages[0].object, Object::Integer(30));
+
+        // Pulling back brings nothing new to A
+        let address = listen(b.clone(), "b");
+        assert_eq!(pull(address, &a, &runtime, "a", "a").unwrap().applied, 0);
+    }
+}
@@ -0,0 +1,51 @@
+// ============================================================================
+// Bundled Core Ontology
+// ============================================================================
+// The core-ontology files, compiled into the crate by build.rs. Dev builds
+// read the source tree; app shells that can't (Android and iOS sandboxes,
+// installed desktop builds) unpack these copies into their data directory
+// and import from there (see set_ontology_dir).
+//
+// Unpacking only rewrites files whose content changed, so the import's
+// change detection skips the others.
+// ============================================================================
+
+use std::fs;
+use std::io;
+use std::path::Path;
+
+include!(concat!(env!("OUT_DIR"), "/bundled_ontologies.rs"));
+
+/// Write the bundled files into `dir`; returns the number written
+pub fn unpack(dir: &Path) -> io::Result<usize> {
+    fs::create_dir_all(dir)?;
+    let mut written = 0;
+    for (name, content) in FILES {
+        let path = dir.join(name);
+        if fs::read_to_string(&path).ok().as_deref() != Some(*content) {
+            fs::write(&path, content)?;
+            written += 1;
+        }
+    }
+    Ok(written)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_unpack_writes_changed_files_only() {
+        let dir = TempDir::new().unwrap();
+        assert!(FILES.iter().any(|(name, _)| *name == "Person.ttl"));
+
+        assert_eq!(unpack(dir.path()).unwrap(), FILES.len());
+        assert_eq!(unpack(dir.path()).unwrap(), 0);
+
+        fs::write(dir.path().join("Person.ttl"), "# edited").unwrap();
+        assert_eq!(unpack(dir.path()).unwrap(), 1);
+        assert_eq!(fs::read_to_string(dir.path().join("Person.ttl")).unwrap(), FILES.iter()
+            .find(|(name, _)| *name == "Person.ttl").unwrap().1);
+    }
+}
@@ -0,0 +1,174 @@
+// ============================================================================
+// Import Jobs
+// ============================================================================
+// An import the user started (a file, a URL) runs as a job: it gets an ID,
+// reports its progress every REPORT_INTERVAL (triples parsed, triples per
+// second, and the time left, estimated from the bytes read), and can be
+// cancelled from another thread.
+//
+// The pipeline checks for cancellation as it parses and once more before
+// committing: a cancelled import returns ImportError::Cancelled and writes
+// nothing, its transaction being rolled back.
+//
+// Jobs are registered while their handle lives; dropping it (the import
+// returned) unregisters the job.
+// ============================================================================
+
+use std::collections::BTreeMap;
+use std::io::Read;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, Weak};
+use std::time::{Duration, Instant};
+use crate::ImportProgress;
+
+/// Time between two progress reports of a job
+pub const REPORT_INTERVAL: Duration = Duration::from_millis(500);
+
+/// Stage of the progress reports of jobs (startup imports use theirs)
+pub const STAGE: &str = "job";
+
+/// Jobs running, by ID
+static JOBS: Mutex<BTreeMap<u64, Weak<ImportJob>>> = Mutex::new(BTreeMap::new());
+static NEXT_ID: AtomicU64 = AtomicU64::new(1);
+
+type Reporter = Box<dyn Fn(ImportProgress) + Send + Sync>;
+
+/// A running import
+pub struct ImportJob {
+    pub id: u64,
+    pub name: String,
+    total_bytes: Option<u64>,
+    bytes_read: AtomicU64,
+    triples: AtomicU64,
+    cancelled: AtomicBool,
+    started: Instant,
+    last_report: Mutex<Instant>,
+    report: Reporter,
+}
+
+impl std::fmt::Debug for ImportJob {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ImportJob").field("id", &self.id).field("name", &self.name).finish()
+    }
+}
+
+/// Start a job, reporting its progress to `report` (a first report is sent
+/// now, so the caller learns the job ID)
+/// total_bytes: size of the source, for the time estimate (None: unknown)
+pub fn start(name: &str, total_bytes: Option<u64>, report: impl Fn(ImportProgress) + Send + Sync + 'static) -> Arc<ImportJob> {
+    let now = Instant::now();
+    let job = Arc::new(ImportJob {
+        id: NEXT_ID.fetch_add(1, Ordering::Relaxed),
+        name: name.to_string(),
+        total_bytes,
+        bytes_read: AtomicU64::new(0),
+        triples: AtomicU64::new(0),
+        cancelled: AtomicBool::new(false),
+        started: now,
+        last_report: Mutex::new(now),
+        report: Box::new(report),
+    });
+
+    let mut jobs = JOBS.lock().unwrap();
+    jobs.retain(|_, job| job.strong_count() > 0);
+    jobs.insert(job.id, Arc::downgrade(&job));
+    drop(jobs);
+
+    (job.report)(job.progress());
+    job
+}
+
+/// Cancel a running job; returns false if no job has this ID (it finished)
+pub fn cancel(id: u64) -> bool {
+    match JOBS.lock().unwrap().get(&id).and_then(Weak::upgrade) {
+        Some(job) => {
+            job.cancelled.store(true, Ordering::Relaxed);
+            true
+        }
+        None => false,
+    }
+}
+
+impl ImportJob {
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::Relaxed)
+    }
+
+    /// Progress of the job now
+    pub fn progress(&self) -> ImportProgress {
+        let triples = self.triples.load(Ordering::Relaxed);
+        let elapsed = self.started.elapsed();
+        let seconds = elapsed.as_secs_f64();
+        let bytes_read = self.bytes_read.load(Ordering::Relaxed);
+        let eta_ms = self.total_bytes
+            .filter(|_| bytes_read > 0)
+            .map(|total| (elapsed.as_millis() as f64 * total.saturating_sub(bytes_read) as f64 / bytes_read as f64) as u64);
+
+        ImportProgress {
+            stage: STAGE.to_string(),
+            current_file: self.name.clone(),
+            current: 1,
+            total: 1,
+            triples,
+            job_id: Some(self.id),
+            triples_per_sec: (seconds > 0.0).then(|| triples as f64 / seconds),
+            eta_ms,
+        }
+    }
+
+    /// Note triples parsed, reporting progress when it's due
+    pub(super) fn parsed(&self, triples: u64) {
+        self.triples.store(triples, Ordering::Relaxed);
+        let mut last_report = self.last_report.lock().unwrap();
+        if last_report.elapsed() >= REPORT_INTERVAL {
+            *last_report = Instant::now();
+            drop(last_report);
+            (self.report)(self.progress());
+        }
+    }
+
+    /// Report the final progress
+    pub(super) fn finished(&self, triples: u64) {
+        self.triples.store(triples, Ordering::Relaxed);
+        (self.report)(ImportProgress { eta_ms: Some(0), ..self.progress() });
+    }
+}
+
+/// A reader counting the bytes a job read from its source
+pub(super) struct Counted<R> {
+    pub inner: R,
+    pub job: Arc<ImportJob>,
+}
+
+impl<R: Read> Read for Counted<R> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        let read = self.inner.read(buf)?;
+        self.job.bytes_read.fetch_add(read as u64, Ordering::Relaxed);
+        Ok(read)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cancel_and_unregister() {
+        let reports = Arc::new(Mutex::new(Vec::new()));
+        let sink = reports.clone();
+        let job = start("Vehicle.ttl", Some(100), move |progress| sink.lock().unwrap().push(progress));
+        assert_eq!(reports.lock().unwrap()[0].job_id, Some(job.id));
+
+        let mut reader = Counted { inner: &[0u8; 25][..], job: job.clone() };
+        std::io::copy(&mut reader, &mut std::io::sink()).unwrap();
+        assert!(job.progress().eta_ms.is_some());
+
+        assert!(cancel(job.id));
+        assert!(job.is_cancelled());
+
+        let id = job.id;
+        drop(reader);
+        drop(job);
+        assert!(!cancel(id));
+    }
+}
@@ -0,0 +1,575 @@
+// ============================================================================
+// JSON-LD Parser
+// ============================================================================
+// Turns a JSON-LD document into RDF statements for the import path
+//
+// Supported:
+// - Inline @context (objects, arrays of them, null to reset): terms, prefixes
+//   for compact IRIs, @vocab, @base, @language, and term definitions with
+//   @id, @type ("@id", "@vocab" or a datatype), @container and @language
+// - Node objects with @id, @type, nested nodes and @graph (named graphs are
+//   merged into the default graph)
+// - Value objects (@value with @type or @language), @list, @set, and native
+//   numbers and booleans
+//
+// Remote contexts are not fetched: a context given as a URL is an error.
+// Keys that don't expand to an absolute IRI are dropped, as in JSON-LD
+// expansion; a node whose @id doesn't resolve becomes a blank node.
+//
+// IRIs come out expanded; the import compresses them with the known
+// namespaces like it does for Turtle and RDF/XML.
+// ============================================================================
+
+use serde_json::{Map, Value};
+use std::collections::HashMap;
+use rio_api::model::{BlankNode, Literal, NamedNode, Subject, Term, Triple as RioTriple};
+
+const RDF: &str = "http://www.w3.org/1999/02/22-rdf-syntax-ns#";
+const XSD: &str = "http://www.w3.org/2001/XMLSchema#";
+
+/// Subject or object node of a statement
+#[derive(Debug, Clone, PartialEq)]
+pub enum Node {
+    Iri(String),
+    Blank(String), // Without the "_:" prefix
+}
+
+/// Object of a statement
+#[derive(Debug, Clone, PartialEq)]
+pub enum Object {
+    Node(Node),
+    Literal {
+        value: String,
+        datatype: Option<String>,
+        language: Option<String>,
+    },
+}
+
+/// An RDF statement read from a JSON-LD document
+#[derive(Debug, Clone, PartialEq)]
+pub struct Statement {
+    pub subject: Node,
+    pub predicate: String,
+    pub object: Object,
+}
+
+impl Statement {
+    /// Borrow the statement as a rio triple, to convert it like parsed Turtle
+    pub fn with_rio<T>(&self, f: impl FnOnce(&RioTriple) -> T) -> T {
+        let subject = match &self.subject {
+            Node::Iri(iri) => Subject::NamedNode(NamedNode { iri }),
+            Node::Blank(id) => Subject::BlankNode(BlankNode { id }),
+        };
+        let object = match &self.object {
+            Object::Node(Node::Iri(iri)) => Term::NamedNode(NamedNode { iri }),
+            Object::Node(Node::Blank(id)) => Term::BlankNode(BlankNode { id }),
+            Object::Literal { value, language: Some(language), .. } => {
+                Term::Literal(Literal::LanguageTaggedString { value, language })
+            }
+            Object::Literal { value, datatype: Some(datatype), .. } => {
+                Term::Literal(Literal::Typed { value, datatype: NamedNode { iri: datatype } })
+            }
+            Object::Literal { value, .. } => Term::Literal(Literal::Simple { value }),
+        };
+        f(&RioTriple {
+            subject,
+            predicate: NamedNode { iri: &self.predicate },
+            object,
+        })
+    }
+}
+
+/// How a term's values are read
+#[derive(Debug, Clone, Default)]
+struct TermDefinition {
+    iri: String, // Expanded IRI, or a keyword for aliases such as "id": "@id"
+    coerce: Option<String>, // "@id", "@vocab" or a datatype IRI
+    container: Option<String>, // "@list", "@set", "@language" or "@index"
+    language: Option<Option<String>>, // Set when the term overrides the default language
+}
+
+/// Active context
+#[derive(Debug, Clone, Default)]
+struct Context {
+    terms: HashMap<String, TermDefinition>,
+    vocab: Option<String>,
+    base: Option<String>,
+    language: Option<String>,
+}
+
+impl Context {
+    /// Apply a local context (the value of @context)
+    fn update(&self, local: &Value) -> Result<Context, String> {
+        match local {
+            Value::Null => Ok(Context::default()),
+            Value::String(url) => Err(format!("Remote context is not supported: {}", url)),
+            Value::Array(contexts) => contexts.iter().try_fold(self.clone(), |ctx, local| ctx.update(local)),
+            Value::Object(map) => {
+                let mut ctx = self.clone();
+                if let Some(vocab) = map.get("@vocab") {
+                    ctx.vocab = vocab.as_str().and_then(|v| ctx.expand_iri(v, true));
+                }
+                if let Some(base) = map.get("@base") {
+                    ctx.base = base.as_str().map(|b| ctx.resolve(b).unwrap_or_else(|| b.to_string()));
+                }
+                if let Some(language) = map.get("@language") {
+                    ctx.language = language.as_str().map(str::to_lowercase);
+                }
+
+                let mut defining = HashMap::new();
+                for term in map.keys().filter(|k| !k.starts_with('@')) {
+                    ctx.define(map, term, &mut defining)?;
+                }
+                Ok(ctx)
+            }
+            other => Err(format!("Invalid @context: {}", other)),
+        }
+    }
+
+    /// Define a term from a local context, defining the terms it refers to first
+    /// defining: false while a term is being defined, true once it is
+    fn define(&mut self, local: &Map<String, Value>, term: &str, defining: &mut HashMap<String, bool>) -> Result<(), String> {
+        match defining.get(term) {
+            Some(true) => return Ok(()),
+            Some(false) => return Err(format!("Cyclic term definition in @context: {}", term)),
+            None => {}
+        }
+        defining.insert(term.to_string(), false);
+
+        let value = &local[term];
+        let (id, coerce) = match value {
+            Value::Null => {
+                self.terms.remove(term);
+                defining.insert(term.to_string(), true);
+                return Ok(());
+            }
+            Value::String(id) => (Some(id.as_str()), None),
+            Value::Object(def) => {
+                if def.contains_key("@reverse") {
+                    return Err(format!("Reverse properties are not supported: {}", term));
+                }
+                (def.get("@id").and_then(Value::as_str), def.get("@type").and_then(Value::as_str))
+            }
+            other => return Err(format!("Invalid definition for term {}: {}", term, other)),
+        };
+
+        // Terms this definition refers to, as an alias or a prefix
+        for reference in [Some(term), id, coerce].into_iter().flatten() {
+            let prefix = reference.split_once(':').map_or(reference, |(prefix, _)| prefix);
+            if prefix != term && local.contains_key(prefix) && !prefix.starts_with('@') {
+                self.define(local, prefix, defining)?;
+            }
+        }
+
+        let iri = match id {
+            Some(id) => self.expand_iri(id, true),
+            None if term.contains(':') => self.expand_iri(term, true),
+            None => self.vocab.as_ref().map(|vocab| format!("{}{}", vocab, term)),
+        };
+        let Some(iri) = iri else {
+            return Err(format!("Term {} doesn't expand to an IRI", term));
+        };
+
+        let mut definition = TermDefinition {
+            iri,
+            coerce: coerce.map(|c| match c {
+                "@id" | "@vocab" => Some(c.to_string()),
+                datatype => self.expand_iri(datatype, true),
+            }).unwrap_or_default(),
+            ..Default::default()
+        };
+        if let Value::Object(def) = value {
+            definition.container = def.get("@container").and_then(Value::as_str).map(str::to_string);
+            if let Some(language) = def.get("@language") {
+                definition.language = Some(language.as_str().map(str::to_lowercase));
+            }
+        }
+
+        self.terms.insert(term.to_string(), definition);
+        defining.insert(term.to_string(), true);
+        Ok(())
+    }
+
+    /// Expand a term, compact IRI or relative IRI
+    /// vocab: terms and @vocab apply (keys, types); otherwise relative IRIs
+    /// resolve against @base (node IDs)
+    fn expand_iri(&self, value: &str, vocab: bool) -> Option<String> {
+        if value.starts_with('@') {
+            return Some(value.to_string());
+        }
+        if vocab {
+            if let Some(definition) = self.terms.get(value) {
+                return Some(definition.iri.clone());
+            }
+        }
+        if let Some((prefix, suffix)) = value.split_once(':') {
+            if prefix == "_" || suffix.starts_with("//") {
+                return Some(value.to_string());
+            }
+            return match self.terms.get(prefix) {
+                Some(definition) => Some(format!("{}{}", definition.iri, suffix)),
+                None => Some(value.to_string()), // Absolute IRI
+            };
+        }
+        match &self.vocab {
+            Some(vocab_iri) if vocab => Some(format!("{}{}", vocab_iri, value)),
+            _ => self.resolve(value),
+        }
+    }
+
+    /// Resolve a relative IRI against @base
+    fn resolve(&self, relative: &str) -> Option<String> {
+        let base = self.base.as_deref()?;
+        if relative.is_empty() {
+            return Some(base.to_string());
+        }
+        if relative.starts_with('#') {
+            return Some(format!("{}{}", base.split('#').next().unwrap_or(base), relative));
+        }
+        let directory = base.rfind('/').map_or(base, |slash| &base[..=slash]);
+        Some(format!("{}{}", directory, relative.trim_start_matches("./")))
+    }
+
+    /// Keyword a key stands for: itself, or the keyword it is an alias of
+    fn keyword<'a>(&'a self, key: &'a str) -> Option<&'a str> {
+        if key.starts_with('@') {
+            return Some(key);
+        }
+        self.terms.get(key)
+            .map(|definition| definition.iri.as_str())
+            .filter(|iri| iri.starts_with('@'))
+    }
+}
+
+/// Parse a JSON-LD document into statements
+pub fn parse(json: &str) -> Result<Vec<Statement>, String> {
+    let document: Value = serde_json::from_str(json).map_err(|e| format!("Invalid JSON: {}", e))?;
+    let mut parser = Parser::default();
+    parser.top_level(&document, &Context::default())?;
+    Ok(parser.statements)
+}
+
+#[derive(Default)]
+struct Parser {
+    statements: Vec<Statement>,
+    blank_nodes: usize,
+}
+
+impl Parser {
+    fn top_level(&mut self, value: &Value, ctx: &Context) -> Result<(), String> {
+        match value {
+            Value::Array(items) => {
+                for item in items {
+                    self.top_level(item, ctx)?;
+                }
+                Ok(())
+            }
+            Value::Object(map) => self.node(map, ctx).map(|_| ()),
+            _ => Ok(()), // Bare values at the top level state nothing
+        }
+    }
+
+    fn blank_node(&mut self) -> Node {
+        self.blank_nodes += 1;
+        Node::Blank(format!("jsonld{}", self.blank_nodes))
+    }
+
+    fn node_from_id(&mut self, id: &str, ctx: &Context, vocab: bool) -> Node {
+        match ctx.expand_iri(id, vocab) {
+            Some(iri) if iri.starts_with("_:") => Node::Blank(iri[2..].to_string()),
+            Some(iri) if iri.contains(':') && !iri.starts_with('@') => Node::Iri(iri),
+            _ => self.blank_node(),
+        }
+    }
+
+    /// Read a node object, returning its node
+    /// Nodes holding only @graph (and @context) state nothing themselves
+    fn node(&mut self, map: &Map<String, Value>, ctx: &Context) -> Result<Option<Node>, String> {
+        let local;
+        let ctx = match map.get("@context") {
+            Some(context) => {
+                local = ctx.update(context)?;
+                &local
+            }
+            None => ctx,
+        };
+
+        let mut id = None;
+        let mut types = Vec::new();
+        let mut properties = Vec::new();
+        for (key, value) in map {
+            match ctx.keyword(key) {
+                Some("@id") => id = value.as_str(),
+                Some("@type") => types.extend(as_array(value).iter().filter_map(Value::as_str)),
+                Some("@graph") => {
+                    for item in as_array(value) {
+                        self.top_level(item, ctx)?;
+                    }
+                }
+                Some(_) => {} // @context, and keywords that state nothing here
+                None => properties.push((key, value)),
+            }
+        }
+
+        if id.is_none() && types.is_empty() && properties.is_empty() && map.keys().any(|k| ctx.keyword(k) == Some("@graph")) {
+            return Ok(None);
+        }
+
+        let subject = match id {
+            Some(id) => self.node_from_id(id, ctx, false),
+            None => self.blank_node(),
+        };
+        for type_iri in types {
+            let object = self.node_from_id(type_iri, ctx, true);
+            self.push(&subject, &format!("{}type", RDF), Object::Node(object));
+        }
+        for (key, value) in properties {
+            let Some(predicate) = ctx.expand_iri(key, true).filter(|iri| iri.contains(':') && !iri.starts_with("_:")) else {
+                continue;
+            };
+            let definition = ctx.terms.get(key.as_str()).cloned().unwrap_or_default();
+            for object in self.property_values(value, ctx, &definition)? {
+                self.push(&subject, &predicate, object);
+            }
+        }
+        Ok(Some(subject))
+    }
+
+    /// Objects of a property, following its term definition
+    fn property_values(&mut self, value: &Value, ctx: &Context, definition: &TermDefinition) -> Result<Vec<Object>, String> {
+        match (definition.container.as_deref(), value) {
+            (Some("@list"), Value::Array(items)) => Ok(vec![self.list(items, ctx, definition)?]),
+            (Some("@language"), Value::Object(languages)) => {
+                let mut objects = Vec::new();
+                for (language, values) in languages {
+                    for value in as_array(values).iter().filter_map(Value::as_str) {
+                        objects.push(Object::Literal {
+                            value: value.to_string(),
+                            datatype: None,
+                            language: Some(language.to_lowercase()),
+                        });
+                    }
+                }
+                Ok(objects)
+            }
+            (Some("@index"), Value::Object(index)) => {
+                let mut objects = Vec::new();
+                for value in index.values() {
+                    objects.extend(self.values(value, ctx, definition)?);
+                }
+                Ok(objects)
+            }
+            _ => self.values(value, ctx, definition),
+        }
+    }
+
+    /// Objects of a value, or of each value in an array or @set
+    fn values(&mut self, value: &Value, ctx: &Context, definition: &TermDefinition) -> Result<Vec<Object>, String> {
+        match value {
+            Value::Null => Ok(Vec::new()),
+            Value::Array(items) => {
+                let mut objects = Vec::new();
+                for item in items {
+                    objects.extend(self.values(item, ctx, definition)?);
+                }
+                Ok(objects)
+            }
+            Value::Object(map) => {
+                if let Some(set) = map.get("@set") {
+                    return self.values(set, ctx, definition);
+                }
+                if let Some(list) = map.get("@list") {
+                    return Ok(vec![self.list(as_array(list), ctx, definition)?]);
+                }
+                if map.contains_key("@value") {
+                    return Ok(value_object(map, ctx).into_iter().collect());
+                }
+                Ok(self.node(map, ctx)?.map(Object::Node).into_iter().collect())
+            }
+            Value::String(s) => Ok(vec![match definition.coerce.as_deref() {
+                Some("@id") => Object::Node(self.node_from_id(s, ctx, false)),
+                Some("@vocab") => Object::Node(self.node_from_id(s, ctx, true)),
+                Some(datatype) => typed(s.clone(), datatype),
+                None => Object::Literal {
+                    value: s.clone(),
+                    datatype: None,
+                    language: definition.language.clone().unwrap_or_else(|| ctx.language.clone()),
+                },
+            }]),
+            Value::Number(_) | Value::Bool(_) => Ok(native(value, definition.coerce.as_deref()).into_iter().collect()),
+        }
+    }
+
+    /// An rdf:List of the items, returning its head (rdf:nil when empty)
+    fn list(&mut self, items: &[Value], ctx: &Context, definition: &TermDefinition) -> Result<Object, String> {
+        let mut objects = Vec::new();
+        for item in items {
+            match item {
+                Value::Array(nested) => objects.push(self.list(nested, ctx, definition)?),
+                item => objects.extend(self.values(item, ctx, definition)?),
+            }
+        }
+
+        let nodes: Vec<Node> = objects.iter().map(|_| self.blank_node()).collect();
+        for (i, (node, object)) in nodes.iter().zip(objects).enumerate() {
+            self.push(node, &format!("{}first", RDF), object);
+            let rest = nodes.get(i + 1).cloned().unwrap_or_else(|| Node::Iri(format!("{}nil", RDF)));
+            self.push(node, &format!("{}rest", RDF), Object::Node(rest));
+        }
+        Ok(Object::Node(nodes.into_iter().next().unwrap_or_else(|| Node::Iri(format!("{}nil", RDF)))))
+    }
+
+    fn push(&mut self, subject: &Node, predicate: &str, object: Object) {
+        self.statements.push(Statement {
+            subject: subject.clone(),
+            predicate: predicate.to_string(),
+            object,
+        });
+    }
+}
+
+/// Literal of a value object ({"@value": ..., "@type" or "@language": ...})
+fn value_object(map: &Map<String, Value>, ctx: &Context) -> Option<Object> {
+    let value = &map["@value"];
+    let datatype = map.get("@type").and_then(Value::as_str).and_then(|t| ctx.expand_iri(t, true));
+    match value {
+        Value::String(s) => Some(match datatype {
+            Some(datatype) => typed(s.clone(), &datatype),
+            None => Object::Literal {
+                value: s.clone(),
+                datatype: None,
+                language: map.get("@language").and_then(Value::as_str).map(str::to_lowercase),
+            },
+        }),
+        Value::Number(_) | Value::Bool(_) => native(value, datatype.as_deref()),
+        _ => None,
+    }
+}
+
+/// Literal of a native number or boolean, typed by its JSON type unless a
+/// datatype is given
+fn native(value: &Value, datatype: Option<&str>) -> Option<Object> {
+    let (lexical, native_type) = match value {
+        Value::Bool(b) => (b.to_string(), "boolean"),
+        Value::Number(n) if n.is_i64() || n.is_u64() => (n.to_string(), "integer"),
+        Value::Number(n) => (n.as_f64()?.to_string(), "double"),
+        _ => return None,
+    };
+    let datatype = match datatype {
+        Some("@id" | "@vocab") | None => format!("{}{}", XSD, native_type),
+        Some(datatype) => datatype.to_string(),
+    };
+    Some(typed(lexical, &datatype))
+}
+
+fn typed(value: String, datatype: &str) -> Object {
+    Object::Literal { value, datatype: Some(datatype.to_string()), language: None }
+}
+
+fn as_array(value: &Value) -> &[Value] {
+    match value {
+        Value::Array(items) => items,
+        other => std::slice::from_ref(other),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn iri(value: &str) -> Node {
+        Node::Iri(value.to_string())
+    }
+
+    fn find<'a>(statements: &'a [Statement], subject: &Node, predicate: &str) -> Vec<&'a Object> {
+        statements.iter()
+            .filter(|s| &s.subject == subject && s.predicate == predicate)
+            .map(|s| &s.object)
+            .collect()
+    }
+
+    #[test]
+    fn test_context_expansion() {
+        let statements = parse(r#"{
+            "@context": {
+                "@vocab": "http://schema.org/",
+                "foaf": "http://xmlns.com/foaf/0.1/",
+                "nick": "foaf:nick",
+                "knows": {"@id": "foaf:knows", "@type": "@id"},
+                "born": {"@id": "http://schema.org/birthDate", "@type": "xsd:date"},
+                "xsd": "http://www.w3.org/2001/XMLSchema#",
+                "id": "@id"
+            },
+            "id": "http://example.org/ana",
+            "@type": ["Person", "foaf:Agent"],
+            "name": "Ana",
+            "nick": "ana",
+            "knows": "http://example.org/bia",
+            "born": "1990-05-01"
+        }"#).unwrap();
+
+        let ana = iri("http://example.org/ana");
+        assert_eq!(find(&statements, &ana, &format!("{}type", RDF)), vec![
+            &Object::Node(iri("http://schema.org/Person")),
+            &Object::Node(iri("http://xmlns.com/foaf/0.1/Agent")),
+        ]);
+        assert_eq!(find(&statements, &ana, "http://schema.org/name").len(), 1);
+        assert_eq!(find(&statements, &ana, "http://xmlns.com/foaf/0.1/nick").len(), 1);
+        assert_eq!(find(&statements, &ana, "http://xmlns.com/foaf/0.1/knows"), vec![&Object::Node(iri("http://example.org/bia"))]);
+        assert_eq!(find(&statements, &ana, "http://schema.org/birthDate"), vec![
+            &typed("1990-05-01".to_string(), "http://www.w3.org/2001/XMLSchema#date"),
+        ]);
+    }
+
+    #[test]
+    fn test_literals_nodes_and_lists() {
+        let statements = parse(r#"{
+            "@context": {"@vocab": "http://example.org/", "@language": "en",
+                         "steps": {"@container": "@list"}, "unmapped": null},
+            "@graph": [{
+                "@id": "http://example.org/recipe",
+                "title": "Bread",
+                "note": {"@value": "Pão", "@language": "pt"},
+                "servings": 4,
+                "rating": 4.5,
+                "vegan": true,
+                "author": {"name": "Bia"},
+                "steps": ["mix", "bake"],
+                "tags": {"@set": ["easy", "cheap"]}
+            }]
+        }"#).unwrap();
+
+        let recipe = iri("http://example.org/recipe");
+        let literal = |value: &str, datatype: Option<&str>, language: Option<&str>| Object::Literal {
+            value: value.to_string(),
+            datatype: datatype.map(str::to_string),
+            language: language.map(str::to_string),
+        };
+        assert_eq!(find(&statements, &recipe, "http://example.org/title"), vec![&literal("Bread", None, Some("en"))]);
+        assert_eq!(find(&statements, &recipe, "http://example.org/note"), vec![&literal("Pão", None, Some("pt"))]);
+        assert_eq!(find(&statements, &recipe, "http://example.org/servings"), vec![&literal("4", Some(&format!("{}integer", XSD)), None)]);
+        assert_eq!(find(&statements, &recipe, "http://example.org/rating"), vec![&literal("4.5", Some(&format!("{}double", XSD)), None)]);
+        assert_eq!(find(&statements, &recipe, "http://example.org/vegan"), vec![&literal("true", Some(&format!("{}boolean", XSD)), None)]);
+        assert_eq!(find(&statements, &recipe, "http://example.org/tags").len(), 2);
+
+        // Nested node objects become blank nodes
+        let Object::Node(author) = find(&statements, &recipe, "http://example.org/author")[0] else { panic!("author is a node") };
+        assert_eq!(find(&statements, author, "http://example.org/name"), vec![&literal("Bia", None, Some("en"))]);
+
+        // Lists become rdf:first/rdf:rest chains
+        let Object::Node(head) = find(&statements, &recipe, "http://example.org/steps")[0].clone() else { panic!("list head is a node") };
+        assert_eq!(find(&statements, &head, &format!("{}first", RDF)), vec![&literal("mix", None, Some("en"))]);
+        let Object::Node(second) = find(&statements, &head, &format!("{}rest", RDF))[0].clone() else { panic!("rest is a node") };
+        assert_eq!(find(&statements, &second, &format!("{}rest", RDF)), vec![&Object::Node(iri(&format!("{}nil", RDF)))]);
+    }
+
+    #[test]
+    fn test_unsupported_documents() {
+        assert!(parse(r#"{"@context": "https://schema.org/", "name": "Ana"}"#).is_err());
+        assert!(parse(r#"{"@context": {"a": "b:x", "b": "a:y"}}"#).is_err());
+        assert!(parse("not json").is_err());
+
+        // Without @vocab, keys that aren't IRIs state nothing
+        assert!(parse(r#"{"@id": "http://example.org/a", "name": "A"}"#).unwrap().is_empty());
+    }
+}
@@ -0,0 +1,589 @@
+// ============================================================================
+// Turtle Import Module
+// ============================================================================
+// Imports RDF/Turtle ontologies into the EAVTO fact store
+//
+// This module converts parsed RDF to EAVTO triples; pipeline parses Turtle,
+// N-Triples, RDF/XML and JSON-LD and imports them, for every caller
+// ============================================================================
+
+use rusqlite::Connection;
+use rio_turtle::TurtleError;
+use rio_xml::RdfXmlError;
+use rio_api::model::{Term, Triple as RioTriple};
+use std::path::Path;
+use crate::eavto::{Triple, QuotedTriple, Object, XsdType};
+use sha2::{Sha256, Digest};
+use ts_rs::TS;
+use crate::ImportObserver;
+
+mod jsonld;
+mod upgrade;
+pub mod pipeline;
+pub mod job;
+pub mod remote;
+pub mod watch;
+pub mod bundled;
+
+pub use upgrade::{upgrade_turtle_file, has_existing_triples, UpgradeStats};
+pub use pipeline::RdfFormat;
+
+/// Import error types
+#[derive(Debug)]
+pub enum ImportError {
+    IoError(std::io::Error),
+    TurtleError(TurtleError),
+    XmlError(RdfXmlError),
+    JsonLdError(String),
+    DatabaseError(String),
+    DownloadError(String),
+    InvalidLiteral(String), // A typed literal whose value doesn't fit its datatype
+    Cancelled, // The import job was cancelled (nothing was written)
+}
+
+impl From<std::io::Error> for ImportError {
+    fn from(err: std::io::Error) -> Self {
+        ImportError::IoError(err)
+    }
+}
+
+impl From<TurtleError> for ImportError {
+    fn from(err: TurtleError) -> Self {
+        ImportError::TurtleError(err)
+    }
+}
+
+impl From<RdfXmlError> for ImportError {
+    fn from(err: RdfXmlError) -> Self {
+        ImportError::XmlError(err)
+    }
+}
+
+impl From<Box<dyn std::error::Error>> for ImportError {
+    fn from(err: Box<dyn std::error::Error>) -> Self {
+        ImportError::DatabaseError(err.to_string())
+    }
+}
+
+impl From<rusqlite::Error> for ImportError {
+    fn from(err: rusqlite::Error) -> Self {
+        ImportError::DatabaseError(err.to_string())
+    }
+}
+
+/// Import statistics
+#[derive(Debug, serde::Serialize, TS)]
+#[ts(export)]
+pub struct ImportStats {
+    pub file: String,
+    pub format: String,
+    #[ts(type = "number")]
+    pub triples_processed: u64,
+    #[ts(type = "number")]
+    pub facts_inserted: u64,
+    #[ts(type = "number")]
+    pub tx_start: i64,
+    #[ts(type = "number")]
+    pub tx_end: i64,
+}
+
+/// Converts RIO Subject to string representation
+fn subject_to_string(subject: &rio_api::model::Subject) -> Result<String, ImportError> {
+    Ok(match subject {
+        rio_api::model::Subject::NamedNode(node) => node.iri.to_string(),
+        rio_api::model::Subject::BlankNode(bn) => format!("_:{}", bn.id),
+        rio_api::model::Subject::Triple(triple) => quoted_triple(triple)?.term(),
+    })
+}
+
+/// Converts a RIO quoted triple (RDF-star `<< s p o >>`) to a QuotedTriple
+fn quoted_triple(rio_triple: &RioTriple) -> Result<QuotedTriple, ImportError> {
+    Ok(QuotedTriple::of(&rio_to_eavto_triple(rio_triple, 0, 0, 0)?))
+}
+
+/// Extracts literal value from a literal term
+fn get_literal_value(lit: &rio_api::model::Literal) -> String {
+    match lit {
+        rio_api::model::Literal::Simple { value } => value.to_string(),
+        rio_api::model::Literal::LanguageTaggedString { value, .. } => value.to_string(),
+        rio_api::model::Literal::Typed { value, .. } => value.to_string(),
+    }
+}
+
+/// Extracts datatype IRI from a literal term
+fn get_literal_datatype(lit: &rio_api::model::Literal) -> String {
+    let full_iri = match lit {
+        rio_api::model::Literal::Simple { .. } => "http://www.w3.org/2001/XMLSchema#string",
+        rio_api::model::Literal::LanguageTaggedString { .. } => "http://www.w3.org/1999/02/22-rdf-syntax-ns#langString",
+        rio_api::model::Literal::Typed { datatype, .. } => datatype.iri,
+    };
+    crate::namespaces::compress_iri(full_iri)
+}
+
+/// Extracts language tag from a literal term
+fn get_literal_language(lit: &rio_api::model::Literal) -> Option<String> {
+    match lit {
+        rio_api::model::Literal::LanguageTaggedString { language, .. } => Some(language.to_string()),
+        _ => None,
+    }
+}
+
+/// Converts RIO triple to EAVTO Triple
+/// Typed literals that don't parse as their datatype are an error: the
+/// source is malformed RDF (and may be any file or URL)
+fn rio_to_eavto_triple(rio_triple: &RioTriple, tx: i64, origin_id: i64, created_at: i64) -> Result<Triple, ImportError> {
+    let subject_full = subject_to_string(&rio_triple.subject)?;
+    let subject = crate::namespaces::compress_iri(&subject_full);
+    let predicate = crate::namespaces::compress_iri(rio_triple.predicate.iri);
+
+    let object = match &rio_triple.object {
+        Term::NamedNode(node) => {
+            let compressed_iri = crate::namespaces::compress_iri(node.iri);
+            Object::Iri(compressed_iri)
+        }
+        Term::BlankNode(bn) => {
+            Object::Blank(format!("_:{}", bn.id))
+        }
+        Term::Literal(lit) => {
+            let value = get_literal_value(lit);
+            let datatype = get_literal_datatype(lit);
+            let language = get_literal_language(lit);
+            typed_literal(value, datatype, language)
+                .map_err(|e| ImportError::InvalidLiteral(format!("{} (on {} {})", e, subject, predicate)))?
+        }
+        Term::Triple(triple) => Object::Triple(Box::new(quoted_triple(triple)?)),
+    };
+
+    Ok(Triple {
+        subject,
+        predicate,
+        object,
+        graph: crate::eavto::DEFAULT_GRAPH.to_string(),
+        tx,
+        created_at,
+        origin_id,
+        retracted: false,
+    })
+}
+
+/// Parse typed literals into native types
+fn typed_literal(value: String, datatype: String, language: Option<String>) -> Result<Object, String> {
+    let invalid = |expected: &str| format!("Invalid {} literal '{}': expected {}", datatype, value, expected);
+    match datatype.as_str() {
+        "xsd:integer" | "xsd:int" | "xsd:long" => {
+            value.parse::<i64>().map(Object::Integer).map_err(|_| invalid("an integer"))
+        }
+        "xsd:decimal" | "xsd:double" | "xsd:float" => {
+            value.parse::<f64>().map(Object::Number).map_err(|_| invalid("a number"))
+        }
+        "xsd:boolean" => match value.as_str() {
+            "true" | "1" => Ok(Object::Boolean(true)),
+            "false" | "0" => Ok(Object::Boolean(false)),
+            _ => Err(invalid("'true', 'false', '1' or '0'")),
+        },
+        "xsd:dateTime" => {
+            // ISO 8601 (2025-01-28T18:38:46Z) to Unix epoch milliseconds;
+            // xsd:date and xsd:time stay literals, the store fills their column
+            XsdType::DateTime.epoch_millis(&value)
+                .map(Object::DateTime)
+                .ok_or_else(|| invalid("ISO 8601 (e.g., '2025-01-28T18:38:46Z')"))
+        }
+        _ => Ok(Object::Literal { value, datatype: Some(datatype), language }),
+    }
+}
+
+/// Import RDF triples from Turtle file using EAVTO
+pub fn import_turtle_file(
+    conn: &mut Connection,
+    file_path: &Path,
+    origin: &str,
+) -> Result<ImportStats, ImportError> {
+    pipeline::import_file(conn, file_path, Some(RdfFormat::Turtle), origin)
+}
+
+/// Import RDF triples from an RDF/XML file (e.g., saved by Protégé)
+pub fn import_rdfxml_file(
+    conn: &mut Connection,
+    file_path: &Path,
+    origin: &str,
+) -> Result<ImportStats, ImportError> {
+    pipeline::import_file(conn, file_path, Some(RdfFormat::RdfXml), origin)
+}
+
+/// Import RDF triples from a JSON-LD file
+/// Contexts must be inline; IRIs they expand to are compressed like Turtle's
+pub fn import_jsonld_file(
+    conn: &mut Connection,
+    file_path: &Path,
+    origin: &str,
+) -> Result<ImportStats, ImportError> {
+    pipeline::import_file(conn, file_path, Some(RdfFormat::JsonLd), origin)
+}
+
+/// Import an RDF file, choosing the parser from its extension
+/// (.ttl → Turtle, .nt → N-Triples, .owl/.rdf/.xml → RDF/XML, .jsonld/.json → JSON-LD),
+/// decoding gzip-compressed files (.ttl.gz, .nt.gz, ...) as they're read
+pub fn import_rdf_file(
+    conn: &mut Connection,
+    file_path: &Path,
+    origin: &str,
+) -> Result<ImportStats, ImportError> {
+    pipeline::import_file(conn, file_path, None, origin)
+}
+
+/// Directory of the ontology files, when the app shell chooses it
+static ONTOLOGY_DIR: std::sync::OnceLock<std::path::PathBuf> = std::sync::OnceLock::new();
+
+/// Import the FOUNDATION ontologies from `dir` (e.g. where bundled::unpack
+/// put them) rather than the source tree. Only the first call has an effect.
+pub fn set_ontology_dir(dir: std::path::PathBuf) {
+    let _ = ONTOLOGY_DIR.set(dir);
+}
+
+/// Directory of the FOUNDATION ontology files (core-ontology at the project root)
+///
+/// Found from the manifest directory of the crate being run, which is the
+/// app's or this one's depending on who runs it, unless set_ontology_dir
+/// chose another.
+pub fn core_ontology_dir() -> std::path::PathBuf {
+    if let Some(dir) = ONTOLOGY_DIR.get() {
+        return dir.clone();
+    }
+    let project_root = std::env::var("CARGO_MANIFEST_DIR")
+        .ok()
+        .and_then(|manifest_dir| {
+            Path::new(&manifest_dir).ancestors()
+                .skip(1)
+                .find(|dir| dir.join("core-ontology").is_dir())
+                .map(|dir| dir.to_path_buf())
+        })
+        .unwrap_or_else(|| std::env::current_dir().unwrap());
+
+    project_root.join("core-ontology")
+}
+
+/// What importing the FOUNDATION ontologies did
+#[derive(Debug, Clone, Default)]
+pub struct FoundationImport {
+    pub files: Vec<String>, // Files imported (unchanged ones are skipped)
+    pub triples: u64,
+    pub warnings: Vec<String>, // Problems that didn't stop the import
+}
+
+/// Import all FOUNDATION ontologies from filesystem, reporting progress
+pub fn import_all_foundation_ontologies(
+    conn: &mut Connection,
+    observer: Option<&dyn ImportObserver>,
+    base_triples: u64
+) -> Result<FoundationImport, ImportError> {
+    let mut total_triples = 0u64;
+    let mut files = Vec::new();
+    let mut warnings = Vec::new();
+    let mut warn = |warning: String| {
+        eprintln!("⚠️  {}", warning);
+        warnings.push(warning);
+    };
+
+    println!("\n🏛️  Importing FOUNDATION ontologies...\n");
+
+    let core_ontology_dir = core_ontology_dir();
+    println!("📂 Reading from: {}", core_ontology_dir.display());
+
+    // Read all .ttl files
+    let mut ttl_files: Vec<std::path::PathBuf> = Vec::new();
+
+    match std::fs::read_dir(&core_ontology_dir) {
+        Ok(entries) => {
+            for entry in entries {
+                if let Ok(entry) = entry {
+                    let path = entry.path();
+                    if path.is_file() && path.extension().and_then(|s| s.to_str()) == Some("ttl") {
+                        if let Some(filename) = path.file_name().and_then(|s| s.to_str()) {
+                            // Skip dtype.ttl (reserved for future use)
+                            if filename != "dtype.ttl" {
+                                ttl_files.push(path);
+                            }
+                        }
+                    }
+                }
+            }
+        }
+        Err(e) => {
+            eprintln!("⚠️  Error reading core-ontology directory: {}", e);
+            return Err(ImportError::IoError(e));
+        }
+    }
+
+    ttl_files.sort();
+
+    let total_files = ttl_files.len() as u32;
+    println!("📋 Found {} FOUNDATION ontology files\n", total_files);
+
+    // Import each file (with incremental import check)
+    for (index, file_path) in ttl_files.iter().enumerate() {
+        let filename = file_path.file_name().unwrap().to_str().unwrap();
+        let origin = format!("foundation:ontology:{}", filename);
+
+        // Check if file needs reimport
+        let should_import = match needs_reimport(conn, &file_path) {
+            Ok(needs) => needs,
+            Err(e) => {
+                warn(format!("Error checking {}: {:?}, importing anyway", filename, e));
+                true
+            }
+        };
+
+        if !should_import {
+            println!("⏭️  {} (unchanged, skipping)", filename);
+            continue;
+        }
+
+        // Emit progress event BEFORE importing (+3: core and dtype are 1 and 2)
+        pipeline::progress(observer, "foundation", filename, 3 + index as u32, 3 + total_files, base_triples + total_triples);
+
+        println!("📄 {}", filename);
+
+        // Files imported by an earlier app release are migrated by diff,
+        // so changed axioms replace the old ones instead of piling up
+        let is_upgrade = match has_existing_triples(conn, &origin) {
+            Ok(exists) => exists,
+            Err(e) => {
+                warn(format!("Error checking {}: {:?}, importing anyway", filename, e));
+                false
+            }
+        };
+
+        let result = if is_upgrade {
+            upgrade_turtle_file(conn, file_path, &origin)
+        } else {
+            import_turtle_file(conn, file_path, &origin)
+                .map(|stats| UpgradeStats { stats, added: 0, retracted: 0 })
+        };
+
+        match result {
+            Ok(upgraded) => {
+                let stats = &upgraded.stats;
+                total_triples += stats.triples_processed;
+                files.push(filename.to_string());
+                if is_upgrade {
+                    println!("   ✓ {} triples (+{} -{})", stats.triples_processed, upgraded.added, upgraded.retracted);
+                } else {
+                    println!("   ✓ {} triples", stats.triples_processed);
+                }
+
+                // Register imported file
+                match register_imported_file(conn, file_path, stats) {
+                    Ok(Some(release)) if is_upgrade => {
+                        if let Err(e) = crate::owl::OntologyUpgrade::record(
+                            conn,
+                            &release,
+                            upgraded.added as i64,
+                            upgraded.retracted as i64,
+                            "foundation:FOUNDATION",
+                        ) {
+                            warn(format!("Failed to record upgrade of {}: {}", filename, e));
+                        }
+                    }
+                    Ok(_) => {}
+                    Err(e) => warn(format!("Failed to register {}: {:?}", filename, e)),
+                }
+
+                // Emit progress event AFTER importing with updated triples (marked as completed)
+                pipeline::progress(observer, "foundation", filename, 3 + index as u32 + 1, 3 + total_files, base_triples + total_triples);
+            }
+            Err(e) => warn(format!("Failed to import {}: {:?}", filename, e)),
+        }
+    }
+
+    Ok(FoundationImport { files, triples: total_triples, warnings })
+}
+
+/// Check if a file needs to be reimported
+/// Returns true if:
+/// - File is not tracked yet
+/// - Checksum has changed
+/// - File modification time is newer than last import
+pub fn needs_reimport(
+    conn: &Connection,
+    file_path: &Path,
+) -> Result<bool, ImportError> {
+    let file_path_str = file_path.to_string_lossy().to_string();
+
+    // Query existing record
+    let result: Option<(i64, String)> = conn
+        .query_row(
+            "SELECT last_modified, checksum FROM ontology_files WHERE file_path = ?1",
+            [&file_path_str],
+            |row| Ok((row.get(0)?, row.get(1)?)),
+        )
+        .ok();
+
+    // If not tracked yet, needs import
+    let Some((stored_modified, stored_checksum)) = result else {
+        return Ok(true);
+    };
+
+    // Get current file metadata
+    let metadata = std::fs::metadata(file_path)?;
+    let current_modified = metadata.modified()?
+        .duration_since(std::time::SystemTime::UNIX_EPOCH)
+        .unwrap()
+        .as_secs() as i64;
+
+    // Calculate current checksum
+    let current_checksum = calculate_file_checksum(file_path)?;
+
+    // Check if checksums differ
+    if current_checksum != stored_checksum {
+        let file_name = file_path.file_name()
+            .and_then(|n| n.to_str())
+            .unwrap_or("unknown");
+        println!("🔄 File {} has changed (checksum mismatch)", file_name);
+        return Ok(true);
+    }
+
+    // Check if modification time is newer
+    if current_modified > stored_modified {
+        let file_name = file_path.file_name()
+            .and_then(|n| n.to_str())
+            .unwrap_or("unknown");
+        println!("🔄 File {} has been modified", file_name);
+        return Ok(true);
+    }
+
+    // File hasn't changed
+    Ok(false)
+}
+
+/// Calculate SHA-256 checksum of a file
+fn calculate_file_checksum(path: &Path) -> Result<String, ImportError> {
+    let file_content = std::fs::read(path)?;
+    let mut hasher = Sha256::new();
+    hasher.update(&file_content);
+    let result = hasher.finalize();
+    Ok(format!("{:x}", result))
+}
+
+/// Register an imported file in the tracking table
+///
+/// Also records a foundation:OntologyRelease for this import, so earlier
+/// versions of the file stay queryable after the tracking row is replaced.
+/// No release is recorded when the import wrote no transaction or the file
+/// matches the latest release (e.g., only its modification time changed).
+pub fn register_imported_file(
+    conn: &mut Connection,
+    file_path: &Path,
+    stats: &ImportStats,
+) -> Result<Option<crate::owl::OntologyRelease>, ImportError> {
+    let file_name = file_path.file_name()
+        .and_then(|n| n.to_str())
+        .ok_or_else(|| ImportError::DatabaseError("Invalid file name".to_string()))?;
+
+    let file_path_str = file_path.to_string_lossy().to_string();
+
+    // Get file metadata
+    let metadata = std::fs::metadata(file_path)?;
+    let last_modified = metadata.modified()?
+        .duration_since(std::time::SystemTime::UNIX_EPOCH)
+        .unwrap()
+        .as_secs() as i64;
+
+    // Calculate checksum
+    let checksum = calculate_file_checksum(file_path)?;
+
+    // Get current timestamp
+    let now = std::time::SystemTime::now()
+        .duration_since(std::time::SystemTime::UNIX_EPOCH)
+        .unwrap()
+        .as_secs() as i64;
+
+    // Insert or replace record
+    conn.execute(
+        "INSERT OR REPLACE INTO ontology_files (file_path, file_name, last_modified, last_imported, checksum, triple_count)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+        (
+            &file_path_str,
+            file_name,
+            last_modified,
+            now,
+            &checksum,
+            stats.triples_processed as i64,
+        ),
+    )?;
+
+    if stats.tx_start == 0 {
+        return Ok(None);
+    }
+
+    let latest = crate::owl::OntologyRelease::latest_for_file(conn, file_name)
+        .map_err(|e| ImportError::DatabaseError(format!("Failed to read releases: {}", e)))?;
+    if latest.is_some_and(|r| r.checksum == checksum) {
+        return Ok(None);
+    }
+
+    let release = crate::owl::OntologyRelease::record(
+        conn,
+        file_name,
+        &checksum,
+        stats.tx_start,
+        stats.triples_processed as i64,
+        "foundation:FOUNDATION",
+    ).map_err(|e| ImportError::DatabaseError(format!("Failed to record release: {}", e)))?;
+    println!("   🏷️  Recorded {}", release.iri);
+
+    Ok(Some(release))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::eavto::{query, test_helpers::setup_test_db};
+
+    #[test]
+    fn test_import_skips_repeated_statements() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("Vehicle.ttl");
+        std::fs::write(&path, r#"
+            @prefix owl: <http://www.w3.org/2002/07/owl#> .
+            @prefix rdfs: <http://www.w3.org/2000/01/rdf-schema#> .
+            @prefix foundation: <http://foundation.local/ontology/> .
+
+            foundation:Vehicle a owl:Class ; rdfs:label "Vehicle"@en .
+            foundation:Car a owl:Class ; rdfs:subClassOf foundation:Vehicle .
+            foundation:Vehicle a owl:Class ; rdfs:label "Vehicle"@en, "Veículo"@pt .
+        "#).unwrap();
+
+        let mut conn = setup_test_db();
+        let stats = import_turtle_file(&mut conn, &path, "import:Vehicle.ttl").unwrap();
+        assert_eq!((stats.triples_processed, stats.facts_inserted), (7, 5));
+        assert_eq!(query::get_by_transaction(&conn, stats.tx_start).unwrap().triples.len(), 5);
+    }
+
+    #[test]
+    fn test_import_jsonld_compresses_iris() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("Vehicle.jsonld");
+        std::fs::write(&path, r#"{
+            "@context": {
+                "owl": "http://www.w3.org/2002/07/owl#",
+                "rdfs": "http://www.w3.org/2000/01/rdf-schema#",
+                "foundation": "http://foundation.local/ontology/",
+                "label": {"@id": "rdfs:label", "@language": "en"},
+                "subClassOf": {"@id": "rdfs:subClassOf", "@type": "@id"}
+            },
+            "@graph": [
+                {"@id": "foundation:Vehicle", "@type": "owl:Class", "label": "Vehicle"},
+                {"@id": "foundation:Car", "@type": "owl:Class", "subClassOf": "foundation:Vehicle"}
+            ]
+        }"#).unwrap();
+
+        let mut conn = setup_test_db();
+        let stats = import_rdf_file(&mut conn, &path, "import:Vehicle.jsonld").unwrap();
+        assert_eq!((stats.format.as_str(), stats.facts_inserted), ("JSON-LD", 4));
+
+        let car = query::get_by_entity(&conn, "foundation:Car").unwrap().triples;
+        assert!(car.iter().any(|t| t.predicate == "rdfs:subClassOf" && t.object.as_iri() == Some("foundation:Vehicle")));
+        let label = query::get_by_entity_predicate(&conn, "foundation:Vehicle", "rdfs:label").unwrap().triples;
+        assert!(matches!(&label[0].object, Object::Literal { value, language: Some(l), .. } if value == "Vehicle" && l == "en"));
+    }
+}
@@ -0,0 +1,520 @@
+// ============================================================================
+// Import Pipeline
+// ============================================================================
+// The one path RDF takes into the store, whatever its syntax:
+//
+//   parse (Turtle, N-Triples, RDF/XML or JSON-LD, streamed from a reader,
+//          gzip-decoded on the fly for .gz files)
+//     → drop repeated statements
+//     → put triples in the graph of their origin
+//     → assert in one transaction, BATCH_SIZE triples at a time as they
+//       are parsed (the whole parse is never held in memory)
+//     → link owl:Ontology headers to their source
+//
+// File imports, the embedded core ontologies, ontology upgrades and
+// federated query results all parse here, and initialization reports its
+// progress through `progress`, so a fix to any step applies to all of them.
+// Imports the user starts run as jobs (see job), which report their own
+// progress and can be cancelled until their transaction commits.
+// ============================================================================
+
+use flate2::read::GzDecoder;
+use rusqlite::Connection;
+use rio_api::parser::TriplesParser;
+use rio_api::model::Triple as RioTriple;
+use rio_turtle::{NTriplesParser, TurtleError, TurtleParser};
+use rio_xml::{RdfXmlError, RdfXmlParser};
+use std::fs::File;
+use std::io::{BufRead, BufReader};
+use std::path::Path;
+use std::sync::Arc;
+use sha2::{Digest, Sha256};
+use std::collections::HashSet;
+use crate::eavto::{indexes, Assertion, Object, Triple};
+use crate::{ImportObserver, ImportProgress};
+use super::job::{Counted, ImportJob};
+use super::{jsonld, rio_to_eavto_triple, ImportError, ImportStats};
+
+/// Triples parsed before they are inserted
+const BATCH_SIZE: usize = 10_000;
+
+/// RDF syntaxes the pipeline parses
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RdfFormat {
+    Turtle,
+    NTriples,
+    RdfXml,
+    JsonLd,
+}
+
+impl RdfFormat {
+    /// Format of a file from its extension, looking past .gz
+    /// (.ttl → Turtle, .nt → N-Triples, .owl/.rdf/.xml → RDF/XML, .jsonld/.json → JSON-LD)
+    pub fn from_path(path: &Path) -> Option<Self> {
+        let path = if is_gzip(path) { Path::new(path.file_stem()?) } else { path };
+        let extension = path.extension()?.to_str()?.to_lowercase();
+        match extension.as_str() {
+            "ttl" => Some(RdfFormat::Turtle),
+            "nt" => Some(RdfFormat::NTriples),
+            "owl" | "rdf" | "xml" => Some(RdfFormat::RdfXml),
+            "jsonld" | "json" => Some(RdfFormat::JsonLd),
+            _ => None,
+        }
+    }
+
+    /// Format of a response body from its media type (Content-Type)
+    pub fn from_media_type(media_type: &str) -> Option<Self> {
+        let media_type = media_type.split(';').next()?.trim().to_lowercase();
+        match media_type.as_str() {
+            "text/turtle" | "application/x-turtle" => Some(RdfFormat::Turtle),
+            "application/n-triples" => Some(RdfFormat::NTriples),
+            "application/rdf+xml" | "application/owl+xml" => Some(RdfFormat::RdfXml),
+            "application/ld+json" => Some(RdfFormat::JsonLd),
+            _ => None,
+        }
+    }
+
+    /// Format from its name, as the frontend gives it
+    /// ("turtle", "ntriples", "rdfxml" or "jsonld")
+    pub fn parse(name: &str) -> Option<Self> {
+        match name.to_lowercase().as_str() {
+            "turtle" | "ttl" => Some(RdfFormat::Turtle),
+            "ntriples" | "n-triples" | "nt" => Some(RdfFormat::NTriples),
+            "rdfxml" | "rdf/xml" | "owl" => Some(RdfFormat::RdfXml),
+            "jsonld" | "json-ld" => Some(RdfFormat::JsonLd),
+            _ => None,
+        }
+    }
+
+    /// Name reported in ImportStats
+    pub fn name(&self) -> &'static str {
+        match self {
+            RdfFormat::Turtle => "Turtle",
+            RdfFormat::NTriples => "N-Triples",
+            RdfFormat::RdfXml => "RDF/XML",
+            RdfFormat::JsonLd => "JSON-LD",
+        }
+    }
+}
+
+/// Parse RDF from a reader into EAVTO triples (tx is set when asserting)
+pub fn parse(reader: impl BufRead, format: RdfFormat, origin_id: i64, created_at: i64) -> Result<Vec<Triple>, ImportError> {
+    let mut triples = Vec::new();
+    parse_job(reader, format, origin_id, created_at, None, |triple| {
+        triples.push(triple);
+        Ok(())
+    })?;
+    Ok(triples)
+}
+
+/// Parse RDF, handing each triple to `sink` as it's read, reporting to a
+/// job and stopping when it's cancelled; returns the number of triples
+fn parse_job(
+    reader: impl BufRead,
+    format: RdfFormat,
+    origin_id: i64,
+    created_at: i64,
+    job: Option<&ImportJob>,
+    mut sink: impl FnMut(Triple) -> Result<(), ImportError>,
+) -> Result<u64, ImportError> {
+    let mut count = 0u64;
+    let mut stopped = None; // The conversion or sink error that stopped parsing
+    let mut push = |rio_triple: &RioTriple| -> std::io::Result<()> {
+        let handled = rio_to_eavto_triple(rio_triple, 0, origin_id, created_at).and_then(&mut sink);
+        if let Err(e) = handled {
+            stopped = Some(e);
+            return Err(std::io::Error::new(std::io::ErrorKind::InvalidData, "Import stopped"));
+        }
+        count += 1;
+        if count.is_multiple_of(10000) {
+            println!("  Parsed {} triples...", count);
+        }
+        if let Some(job) = job.filter(|_| count.is_multiple_of(1000)) {
+            if job.is_cancelled() {
+                return Err(std::io::Error::new(std::io::ErrorKind::Interrupted, "Import cancelled"));
+            }
+            job.parsed(count);
+        }
+        Ok(())
+    };
+
+    let parsed: Result<(), ImportError> = match format {
+        RdfFormat::Turtle => TurtleParser::new(reader, None)
+            .parse_all(&mut |t: RioTriple| push(&t).map_err(TurtleError::from))
+            .map_err(Into::into),
+        RdfFormat::NTriples => NTriplesParser::new(reader)
+            .parse_all(&mut |t: RioTriple| push(&t).map_err(TurtleError::from))
+            .map_err(Into::into),
+        RdfFormat::RdfXml => RdfXmlParser::new(reader, None)
+            .parse_all(&mut |t: RioTriple| push(&t).map_err(RdfXmlError::from))
+            .map_err(Into::into),
+        RdfFormat::JsonLd => (|| {
+            // JSON-LD is framed by the whole document: read it, then convert
+            let mut content = String::new();
+            let mut reader = reader;
+            reader.read_to_string(&mut content)?;
+            for statement in jsonld::parse(&content).map_err(ImportError::JsonLdError)? {
+                statement.with_rio(&mut push)?;
+            }
+            Ok(())
+        })(),
+    };
+    if job.is_some_and(ImportJob::is_cancelled) {
+        return Err(ImportError::Cancelled);
+    }
+    if let Some(e) = stopped {
+        return Err(e);
+    }
+    parsed?;
+
+    Ok(count)
+}
+
+/// Whether a file is gzip-compressed (.gz)
+pub fn is_gzip(path: &Path) -> bool {
+    path.extension().and_then(|e| e.to_str()).is_some_and(|e| e.eq_ignore_ascii_case("gz"))
+}
+
+/// Open an RDF file for parsing, decoding it as it's read if compressed
+pub(super) fn open(path: &Path) -> Result<Box<dyn BufRead>, ImportError> {
+    let file = File::open(path)?;
+    if is_gzip(path) {
+        Ok(Box::new(BufReader::new(GzDecoder::new(file))))
+    } else {
+        Ok(Box::new(BufReader::new(file)))
+    }
+}
+
+/// Open an RDF file for a job, counting the bytes read from it
+pub(super) fn open_for_job(path: &Path, job: &Arc<ImportJob>) -> Result<Box<dyn BufRead>, ImportError> {
+    let file = Counted { inner: File::open(path)?, job: job.clone() };
+    if is_gzip(path) {
+        Ok(Box::new(BufReader::new(GzDecoder::new(file))))
+    } else {
+        Ok(Box::new(BufReader::new(file)))
+    }
+}
+
+/// Parse an RDF file (or its .gz) into EAVTO triples
+pub fn parse_file(path: &Path, format: RdfFormat, origin_id: i64, created_at: i64) -> Result<Vec<Triple>, ImportError> {
+    parse(open(path)?, format, origin_id, created_at)
+}
+
+/// Import RDF from a reader in one transaction
+/// name: the source, as reported in ImportStats and linked from ontology headers
+pub fn import(
+    conn: &mut Connection,
+    reader: impl BufRead,
+    name: &str,
+    format: RdfFormat,
+    origin: &str,
+) -> Result<ImportStats, ImportError> {
+    import_job(conn, reader, name, format, origin, None)
+}
+
+/// Import RDF as a job: progress is reported to it, and the import stops
+/// (writing nothing) if it's cancelled before its transaction commits
+pub fn import_job(
+    conn: &mut Connection,
+    reader: impl BufRead,
+    name: &str,
+    format: RdfFormat,
+    origin: &str,
+    job: Option<&ImportJob>,
+) -> Result<ImportStats, ImportError> {
+    println!("Importing {} file: {}", format.name(), name);
+
+    // The origin is created in the import's transaction: an import that
+    // fails or is cancelled leaves nothing behind
+    let mut assertion = Assertion::begin(conn, origin)?;
+    let (origin_id, created_at) = (assertion.origin_id(), assertion.created_at());
+
+    let mut batch = Vec::with_capacity(BATCH_SIZE);
+    let mut seen = HashSet::new();
+    let mut headers = Vec::new();
+    let mut facts_inserted = 0u64;
+    let mut insert = |assertion: &mut Assertion, batch: &mut Vec<Triple>| -> Result<(), ImportError> {
+        assign_graph(batch, origin);
+        for triple in batch.drain(..) {
+            assertion.insert(&triple)
+                .map_err(|e| ImportError::DatabaseError(format!("Store error: {:?}", e)))?;
+            facts_inserted += 1;
+        }
+        Ok(())
+    };
+
+    let triples_processed = parse_job(reader, format, origin_id, created_at, job, |triple| {
+        // Drop repeated statements, keeping the first
+        if !seen.insert(fingerprint(&triple)) {
+            return Ok(());
+        }
+        if is_header(&triple) {
+            headers.push(triple.clone());
+        }
+        batch.push(triple);
+        if batch.len() == BATCH_SIZE {
+            insert(&mut assertion, &mut batch)?;
+        }
+        Ok(())
+    })?;
+
+    // Keep ontology headers traceable to the file they came from
+    batch.extend(ontology_header_triples(&headers, name, created_at));
+    insert(&mut assertion, &mut batch)?;
+
+    if job.is_some_and(ImportJob::is_cancelled) {
+        return Err(ImportError::Cancelled);
+    }
+    let tx_id = assertion.commit()
+        .map_err(|e| ImportError::DatabaseError(format!("Store error: {:?}", e)))?;
+
+    println!("✅ Imported {} triples ({} facts) from {}", triples_processed, facts_inserted, name);
+    if let Some(job) = job {
+        // Startup imports are analyzed once, after the last file
+        indexes::analyze_after_import(conn, facts_inserted)?;
+        job.finished(triples_processed);
+    }
+
+    Ok(ImportStats {
+        file: name.to_string(),
+        format: format.name().to_string(),
+        triples_processed,
+        facts_inserted,
+        tx_start: tx_id,
+        tx_end: tx_id,
+    })
+}
+
+/// Import an RDF file (or its .gz) in one transaction (format None: from
+/// its extension)
+pub fn import_file(
+    conn: &mut Connection,
+    path: &Path,
+    format: Option<RdfFormat>,
+    origin: &str,
+) -> Result<ImportStats, ImportError> {
+    import_file_job(conn, path, format, origin, None)
+}
+
+/// Import an RDF file as a job (see import_job)
+pub fn import_file_job(
+    conn: &mut Connection,
+    path: &Path,
+    format: Option<RdfFormat>,
+    origin: &str,
+    job: Option<&Arc<ImportJob>>,
+) -> Result<ImportStats, ImportError> {
+    let format = match format.or_else(|| RdfFormat::from_path(path)) {
+        Some(format) => format,
+        None => return Err(ImportError::DatabaseError(format!(
+            "Unsupported RDF file extension: .{}",
+            path.extension().and_then(|e| e.to_str()).unwrap_or_default()
+        ))),
+    };
+    let name = path.file_name().unwrap_or_default().to_string_lossy().to_string();
+    match job {
+        Some(job) => import_job(conn, open_for_job(path, job)?, &name, format, origin, Some(job)),
+        None => import(conn, open(path)?, &name, format, origin),
+    }
+}
+
+/// Report import progress ("import-progress" event)
+/// stage: "core", "dtype" or "foundation"; current: 1-based file number
+pub fn progress(observer: Option<&dyn ImportObserver>, stage: &str, file: &str, current: u32, total: u32, triples: u64) {
+    if let Some(observer) = observer {
+        observer.progress(ImportProgress {
+            stage: stage.to_string(),
+            current_file: file.to_string(),
+            current,
+            total,
+            triples,
+            job_id: None,
+            triples_per_sec: None,
+            eta_ms: None,
+        });
+    }
+}
+
+/// Put imported triples in the graph of their origin (core ontologies in
+/// the ontology graph)
+pub(crate) fn assign_graph(triples: &mut [Triple], origin: &str) {
+    let graph = crate::eavto::query::graph_of_origin(origin);
+    for triple in triples {
+        triple.graph = graph.to_string();
+    }
+}
+
+/// Key of a statement, to drop repeated ones (files often state the same
+/// triple more than once; the store would otherwise hold each copy): a
+/// digest of its canonical line, so an import keeps 16 bytes per triple
+fn fingerprint(triple: &Triple) -> [u8; 16] {
+    let digest = Sha256::digest(crate::eavto::canonical::line(triple).as_bytes());
+    let mut key = [0; 16];
+    key.copy_from_slice(&digest[..16]);
+    key
+}
+
+/// Whether a triple is part of an owl:Ontology header that
+/// ontology_header_triples reads
+fn is_header(triple: &Triple) -> bool {
+    (triple.predicate == "rdf:type" && triple.object.as_iri() == Some("owl:Ontology"))
+        || triple.predicate == "owl:versionIRI"
+}
+
+/// Metadata triples for each owl:Ontology header found in a file
+///
+/// The header itself (ontology IRI, owl:versionIRI, owl:imports) is imported as-is;
+/// this links the ontology entity to its source file and import time.
+pub(super) fn ontology_header_triples(triples: &[Triple], file_name: &str, imported_at: i64) -> Vec<Triple> {
+    let mut ontologies: Vec<&str> = triples.iter()
+        .filter(|t| t.predicate == "rdf:type" && t.object.as_iri() == Some("owl:Ontology"))
+        .map(|t| t.subject.as_str())
+        .collect();
+    ontologies.sort();
+    ontologies.dedup();
+
+    ontologies.into_iter()
+        .flat_map(|ontology| {
+            if let Some(version_iri) = triples.iter()
+                .find(|t| t.subject == ontology && t.predicate == "owl:versionIRI")
+                .and_then(|t| t.object.as_iri())
+            {
+                println!("  📦 Ontology {} (version {})", ontology, version_iri);
+            } else {
+                println!("  📦 Ontology {}", ontology);
+            }
+
+            vec![
+                Triple::new(ontology, "foundation:sourceFile", Object::Literal {
+                    value: file_name.to_string(),
+                    datatype: Some("xsd:string".to_string()),
+                    language: None,
+                }),
+                Triple::new(ontology, "foundation:importedAt", Object::DateTime(imported_at)),
+            ]
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::eavto::{query, test_helpers::setup_test_db};
+
+    const VEHICLE_NT: &str = concat!(
+        "<http://foundation.local/ontology/Car> <http://www.w3.org/2000/01/rdf-schema#subClassOf> <http://foundation.local/ontology/Vehicle> .\n",
+        "<http://foundation.local/ontology/Car> <http://www.w3.org/2000/01/rdf-schema#label> \"Car\"@en .\n",
+        "<http://foundation.local/ontology/Car> <http://www.w3.org/2000/01/rdf-schema#label> \"Car\"@en .\n",
+    );
+
+    #[test]
+    fn test_formats_parse_to_the_same_triples() {
+        let turtle = r#"
+            @prefix rdfs: <http://www.w3.org/2000/01/rdf-schema#> .
+            @prefix foundation: <http://foundation.local/ontology/> .
+            foundation:Car rdfs:subClassOf foundation:Vehicle ; rdfs:label "Car"@en .
+        "#;
+        let canonical = |triples: Vec<Triple>| -> Vec<String> {
+            let mut lines: Vec<String> = triples.iter().map(crate::eavto::canonical::line).collect();
+            lines.sort();
+            lines.dedup();
+            lines
+        };
+
+        let from_turtle = canonical(parse(turtle.as_bytes(), RdfFormat::Turtle, 0, 0).unwrap());
+        let from_ntriples = canonical(parse(VEHICLE_NT.as_bytes(), RdfFormat::NTriples, 0, 0).unwrap());
+        assert_eq!(from_turtle.len(), 2);
+        assert_eq!(from_turtle, from_ntriples);
+    }
+
+    #[test]
+    fn test_malformed_typed_literals_are_errors() {
+        for (value, datatype) in [("12x", "integer"), ("1.2.3", "decimal"), ("maybe", "boolean"), ("yesterday", "dateTime")] {
+            let nt = format!(
+                "<http://foundation.local/ontology/a> <http://foundation.local/ontology/p> \"{}\"^^<http://www.w3.org/2001/XMLSchema#{}> .\n",
+                value, datatype
+            );
+            match parse(nt.as_bytes(), RdfFormat::NTriples, 0, 0) {
+                Err(ImportError::InvalidLiteral(message)) => assert!(message.contains(value), "{}", message),
+                other => panic!("Expected an invalid literal error for {}, got {:?}", value, other.map(|t| t.len())),
+            }
+        }
+    }
+
+    #[test]
+    fn test_import_ntriples_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("Vehicle.nt");
+        std::fs::write(&path, VEHICLE_NT).unwrap();
+
+        let mut conn = setup_test_db();
+        let stats = import_file(&mut conn, &path, None, "import:Vehicle.nt").unwrap();
+        assert_eq!((stats.format.as_str(), stats.triples_processed, stats.facts_inserted), ("N-Triples", 3, 2));
+        assert_eq!(query::get_by_entity(&conn, "foundation:Car").unwrap().triples.len(), 2);
+
+        assert!(import_file(&mut conn, &dir.path().join("Vehicle.csv"), None, "import:csv").is_err());
+    }
+
+    #[test]
+    fn test_import_streams_in_batches() {
+        let nt: String = (0..BATCH_SIZE + 10)
+            .map(|i| format!("<http://foundation.local/ontology/Note{}> <http://www.w3.org/2000/01/rdf-schema#label> \"Note\" .\n", i % (BATCH_SIZE + 5)))
+            .collect();
+
+        let mut conn = setup_test_db();
+        let stats = import(&mut conn, nt.as_bytes(), "notes.nt", RdfFormat::NTriples, "import:notes.nt").unwrap();
+        assert_eq!((stats.triples_processed, stats.facts_inserted), ((BATCH_SIZE + 10) as u64, (BATCH_SIZE + 5) as u64));
+        assert_eq!(query::get_by_entity(&conn, "foundation:Note0").unwrap().triples.len(), 1);
+    }
+
+    #[test]
+    fn test_failed_import_leaves_no_origin() {
+        let nt = format!(
+            "{}<http://foundation.local/ontology/Car> <http://foundation.local/ontology/wheels> \"four\"^^<http://www.w3.org/2001/XMLSchema#integer> .\n",
+            VEHICLE_NT
+        );
+
+        let mut conn = setup_test_db();
+        let result = import(&mut conn, nt.as_bytes(), "Vehicle.nt", RdfFormat::NTriples, "import:Vehicle.nt");
+        assert!(matches!(result, Err(ImportError::InvalidLiteral(_))));
+        assert!(query::get_by_entity(&conn, "foundation:Car").unwrap().triples.is_empty());
+        let origins: i64 = conn.query_row("SELECT COUNT(*) FROM origins WHERE name = 'import:Vehicle.nt'", [], |row| row.get(0)).unwrap();
+        assert_eq!(origins, 0);
+    }
+
+    #[test]
+    fn test_cancelled_job_writes_nothing() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("Vehicle.nt");
+        std::fs::write(&path, VEHICLE_NT).unwrap();
+
+        let mut conn = setup_test_db();
+        let job = super::super::job::start("Vehicle.nt", Some(VEHICLE_NT.len() as u64), |_| {});
+        assert!(super::super::job::cancel(job.id));
+        let result = import_file_job(&mut conn, &path, None, "import:Vehicle.nt", Some(&job));
+        assert!(matches!(result, Err(ImportError::Cancelled)));
+        assert!(query::get_by_entity(&conn, "foundation:Car").unwrap().triples.is_empty());
+
+        let job = super::super::job::start("Vehicle.nt", None, |_| {});
+        let stats = import_file_job(&mut conn, &path, None, "import:Vehicle.nt", Some(&job)).unwrap();
+        assert_eq!(stats.facts_inserted, 2);
+        assert_eq!(job.progress().triples, 3);
+    }
+
+    #[test]
+    fn test_import_gzip_compressed_file() {
+        use std::io::Write;
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("Vehicle.nt.gz");
+        let mut encoder = flate2::write::GzEncoder::new(File::create(&path).unwrap(), flate2::Compression::default());
+        encoder.write_all(VEHICLE_NT.as_bytes()).unwrap();
+        encoder.finish().unwrap();
+
+        assert_eq!(RdfFormat::from_path(&path), Some(RdfFormat::NTriples));
+        let mut conn = setup_test_db();
+        let stats = import_file(&mut conn, &path, None, "import:Vehicle.nt.gz").unwrap();
+        assert_eq!((stats.file.as_str(), stats.facts_inserted), ("Vehicle.nt.gz", 2));
+        assert_eq!(query::get_by_entity(&conn, "foundation:Car").unwrap().triples.len(), 2);
+    }
+}
@@ -0,0 +1,301 @@
+// ============================================================================
+// Import from URL
+// ============================================================================
+// Downloads an ontology over HTTPS and imports it through the pipeline.
+//
+// The request asks for any syntax the pipeline parses (content negotiation);
+// the response's Content-Type picks the parser, unless the caller names one.
+// The body is streamed to a temporary file outside the writer, then parsed
+// from it like a local file. The source URL and ETag are kept in
+// ontology_files, so downloading the same URL again sends If-None-Match and
+// skips an unchanged ontology; a changed one replaces what the previous
+// download wrote.
+//
+// Ontologies declare the ones they build on with owl:imports (BFO and CCO
+// use it heavily). `unresolved_imports` lists those the store doesn't hold
+// yet, as URLs to download in turn, each into its own origin.
+// ============================================================================
+
+use rusqlite::{Connection, OptionalExtension};
+use sha2::{Digest, Sha256};
+use std::io::{Read, Write};
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration;
+use crate::eavto::{query, store, Object};
+use crate::namespaces::expand_iri;
+use super::job::ImportJob;
+use super::pipeline::{self, RdfFormat};
+use super::{has_existing_triples, ImportError, ImportStats};
+
+/// Media types requested, in order of preference
+const ACCEPT: &str = "text/turtle, application/n-triples;q=0.9, application/rdf+xml;q=0.8, application/ld+json;q=0.7";
+
+const USER_AGENT: &str = concat!("FOUNDATION/", env!("CARGO_PKG_VERSION"), " (ontology import)");
+
+/// Ontology dumps can be large
+const TIMEOUT: Duration = Duration::from_secs(300);
+
+/// An ontology downloaded to a temporary file (removed when dropped)
+#[derive(Debug)]
+pub struct Download {
+    pub url: String,
+    pub name: String, // Last segment of the URL path, reported in ImportStats
+    pub path: PathBuf,
+    pub format: RdfFormat,
+    pub etag: Option<String>,
+    pub checksum: String, // SHA-256 of the body
+}
+
+impl Drop for Download {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_file(&self.path);
+    }
+}
+
+/// ETag of the last download of a URL, if it was imported before
+pub fn stored_etag(conn: &Connection, url: &str) -> Result<Option<String>, ImportError> {
+    let etag: Option<Option<String>> = conn.query_row(
+        "SELECT etag FROM ontology_files WHERE source_url = ?",
+        [url],
+        |row| row.get(0),
+    ).optional()?;
+    Ok(etag.flatten())
+}
+
+/// Download an ontology, unless it is unchanged since the download whose
+/// ETag is given (None is returned then)
+/// format: the syntax of the body (None: from the Content-Type, then the URL)
+pub fn download(url: &str, format: Option<RdfFormat>, etag: Option<&str>) -> Result<Option<Download>, ImportError> {
+    if !url.starts_with("https://") {
+        return Err(ImportError::DownloadError(format!("Only HTTPS URLs can be imported: {}", url)));
+    }
+
+    let mut request = ureq::get(url)
+        .set("Accept", ACCEPT)
+        .set("User-Agent", USER_AGENT)
+        .timeout(TIMEOUT);
+    if let Some(etag) = etag {
+        request = request.set("If-None-Match", etag);
+    }
+    let response = request.call()
+        .map_err(|e| ImportError::DownloadError(format!("{} could not be downloaded: {}", url, e)))?;
+    if response.status() == 304 {
+        println!("⏭️  {} (not modified, skipping)", url);
+        return Ok(None);
+    }
+
+    let name = url_name(url);
+    let format = format
+        .or_else(|| RdfFormat::from_media_type(response.content_type()))
+        .or_else(|| RdfFormat::from_path(std::path::Path::new(&name)))
+        .ok_or_else(|| ImportError::DownloadError(format!(
+            "Unknown RDF syntax of {} ({}); give its format", url, response.content_type()
+        )))?;
+    let etag = response.header("ETag").map(str::to_string);
+
+    // Keep .gz, so the pipeline decodes it as it parses
+    let path = std::env::temp_dir().join(format!(
+        "foundation-download-{}{}",
+        &format!("{:x}", Sha256::digest(url.as_bytes()))[..16],
+        if pipeline::is_gzip(std::path::Path::new(&name)) { ".gz" } else { "" },
+    ));
+
+    println!("⬇️  Downloading {}", url);
+    let mut reader = response.into_reader();
+    let mut file = std::fs::File::create(&path)?;
+    let mut hasher = Sha256::new();
+    let mut buffer = [0u8; 64 * 1024];
+    loop {
+        let read = reader.read(&mut buffer)?;
+        if read == 0 {
+            break;
+        }
+        hasher.update(&buffer[..read]);
+        file.write_all(&buffer[..read])?;
+    }
+
+    Ok(Some(Download {
+        url: url.to_string(),
+        name,
+        path,
+        format,
+        etag,
+        checksum: format!("{:x}", hasher.finalize()),
+    }))
+}
+
+/// Import a downloaded ontology into an origin, and record where it came from
+///
+/// What an earlier download of the origin wrote is retracted first, so the
+/// origin holds the current version only.
+/// job: report to this import job (see job), which can cancel it
+pub fn import(conn: &mut Connection, download: &Download, origin: &str, job: Option<&Arc<ImportJob>>) -> Result<ImportStats, ImportError> {
+    let reader = match job {
+        Some(job) => pipeline::open_for_job(&download.path, job)?,
+        None => pipeline::open(&download.path)?,
+    };
+
+    // In a savepoint, so a cancelled or failed import leaves the previous
+    // version in place
+    conn.execute_batch("SAVEPOINT url_import")?;
+    let imported = (|| {
+        if has_existing_triples(conn, origin)? {
+            if let Some(origin_id) = query::get_origin_id(conn, origin)? {
+                store::retract_origin(conn, origin_id)?;
+            }
+        }
+        pipeline::import_job(conn, reader, &download.name, download.format, origin, job.map(|job| job.as_ref()))
+    })();
+    let stats = match imported {
+        Ok(stats) => {
+            conn.execute_batch("RELEASE url_import")?;
+            stats
+        }
+        Err(e) => {
+            conn.execute_batch("ROLLBACK TO url_import; RELEASE url_import")?;
+            return Err(e);
+        }
+    };
+
+    let now = store::now_millis() / 1000;
+    conn.execute(
+        "INSERT OR REPLACE INTO ontology_files
+           (file_path, file_name, last_modified, last_imported, checksum, triple_count, source_url, etag)
+         VALUES (?1, ?2, ?3, ?3, ?4, ?5, ?1, ?6)",
+        rusqlite::params![download.url, download.name, now, download.checksum, stats.triples_processed as i64, download.etag],
+    )?;
+
+    Ok(stats)
+}
+
+/// Ontologies an origin imports (owl:imports) that the store doesn't hold
+/// yet, as the HTTPS URLs to download them from (http IRIs are upgraded)
+///
+/// An ontology is held when an owl:Ontology has its IRI, or its version
+/// IRI, or it was downloaded from that URL before.
+pub fn unresolved_imports(conn: &Connection, origin: &str) -> Result<Vec<String>, ImportError> {
+    let Some(origin_id) = query::get_origin_id(conn, origin)? else {
+        return Ok(Vec::new());
+    };
+
+    let mut imports: Vec<String> = query::get_by_origin(conn, origin_id)?
+        .triples
+        .into_iter()
+        .filter(|t| t.predicate == "owl:imports")
+        .filter_map(|t| t.object.as_iri().map(str::to_string))
+        .collect();
+    imports.sort();
+    imports.dedup();
+
+    let mut unresolved = Vec::new();
+    for iri in imports {
+        let ontology = Object::Iri("owl:Ontology".to_string());
+        let held = !query::get_by_pattern(conn, Some(&iri), Some("rdf:type"), Some(&ontology))?.triples.is_empty()
+            || !query::get_by_pattern(conn, None, Some("owl:versionIRI"), Some(&Object::Iri(iri.clone())))?.triples.is_empty();
+        let url = import_url(&iri);
+        if !held && stored_source(conn, &url)?.is_none() {
+            unresolved.push(url);
+        }
+    }
+    Ok(unresolved)
+}
+
+/// URL to download an imported ontology from
+fn import_url(iri: &str) -> String {
+    let iri = expand_iri(iri);
+    match iri.strip_prefix("http://") {
+        Some(rest) => format!("https://{}", rest),
+        None => iri,
+    }
+}
+
+/// The tracking row of a URL downloaded before
+fn stored_source(conn: &Connection, url: &str) -> Result<Option<String>, ImportError> {
+    Ok(conn.query_row(
+        "SELECT file_path FROM ontology_files WHERE source_url = ?",
+        [url],
+        |row| row.get(0),
+    ).optional()?)
+}
+
+/// Name of a downloaded file: the last segment of the URL path, or its host
+fn url_name(url: &str) -> String {
+    let without_query = url.split(['?', '#']).next().unwrap_or(url);
+    let path = without_query.split_once("://").map(|(_, rest)| rest).unwrap_or(without_query);
+    path.rsplit('/')
+        .find(|segment| !segment.is_empty())
+        .unwrap_or(path)
+        .to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::eavto::test_helpers::setup_test_db;
+
+    const VEHICLE_TTL: &str = r#"
+        @prefix rdfs: <http://www.w3.org/2000/01/rdf-schema#> .
+        @prefix foundation: <http://foundation.local/ontology/> .
+        foundation:Car rdfs:subClassOf foundation:Vehicle ; rdfs:label "Car"@en .
+    "#;
+
+    fn downloaded(url: &str, content: &str, etag: &str) -> Download {
+        let path = std::env::temp_dir().join(format!("foundation-download-test-{}", etag));
+        std::fs::write(&path, content).unwrap();
+        Download {
+            url: url.to_string(),
+            name: url_name(url),
+            path,
+            format: RdfFormat::Turtle,
+            etag: Some(etag.to_string()),
+            checksum: format!("{:x}", Sha256::digest(content.as_bytes())),
+        }
+    }
+
+    #[test]
+    fn test_import_records_source_and_replaces_previous_download() {
+        let url = "https://example.org/ontologies/vehicle.ttl?version=2";
+        let mut conn = setup_test_db();
+
+        let first = downloaded(url, VEHICLE_TTL, "\"v1\"");
+        let stats = import(&mut conn, &first, "import:vehicle", None).unwrap();
+        assert_eq!((stats.file.as_str(), stats.facts_inserted), ("vehicle.ttl", 2));
+        assert_eq!(stored_etag(&conn, url).unwrap().as_deref(), Some("\"v1\""));
+
+        let changed = VEHICLE_TTL.replace("\"Car\"@en", "\"Automobile\"@en");
+        import(&mut conn, &downloaded(url, &changed, "\"v2\""), "import:vehicle", None).unwrap();
+        let labels = query::get_by_entity_predicate(&conn, "foundation:Car", "rdfs:label").unwrap().triples;
+        assert_eq!(labels.len(), 1);
+        assert_eq!(stored_etag(&conn, url).unwrap().as_deref(), Some("\"v2\""));
+        assert!(stored_etag(&conn, "https://example.org/other.ttl").unwrap().is_none());
+    }
+
+    #[test]
+    fn test_unresolved_imports_skip_held_ontologies() {
+        let url = "https://example.org/ontologies/vehicle.ttl";
+        let header = r#"
+            @prefix owl: <http://www.w3.org/2002/07/owl#> .
+            <http://example.org/ontologies/vehicle> a owl:Ontology ;
+                owl:imports <http://purl.obolibrary.org/obo/bfo.owl>, <http://example.org/ontologies/vehicle>,
+                            <https://example.org/units.ttl> .
+        "#;
+        let mut conn = setup_test_db();
+        import(&mut conn, &downloaded(url, header, "\"h1\""), "import:vehicle", None).unwrap();
+        assert_eq!(
+            unresolved_imports(&conn, "import:vehicle").unwrap(),
+            vec!["https://example.org/units.ttl", "https://purl.obolibrary.org/obo/bfo.owl"],
+        );
+
+        // Downloaded before: resolved, whatever its ontology IRI
+        import(&mut conn, &downloaded("https://example.org/units.ttl", VEHICLE_TTL, "\"u1\""), "import:units", None).unwrap();
+        assert_eq!(unresolved_imports(&conn, "import:vehicle").unwrap(), vec!["https://purl.obolibrary.org/obo/bfo.owl"]);
+        assert!(unresolved_imports(&conn, "import:missing").unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_download_requires_https() {
+        assert!(matches!(download("http://example.org/vehicle.ttl", None, None), Err(ImportError::DownloadError(_))));
+        assert_eq!(url_name("https://example.org/"), "example.org");
+    }
+}
@@ -0,0 +1,231 @@
+// ============================================================================
+// Ontology Upgrade
+// ============================================================================
+// Re-imports a changed ontology file as a diff against what the store holds
+// for the file's origin, instead of asserting the whole file again.
+//
+// Blank nodes get fresh ids on every parse, so triples are compared in their
+// canonical form (see eavto::canonical), where a blank node is labeled by its
+// structure. When a blank node's structure changes, its label changes too, so
+// the triples hanging off it are replaced together with the triple that
+// references it.
+// ============================================================================
+
+use rusqlite::Connection;
+use std::collections::BTreeMap;
+use std::path::Path;
+use sha2::{Sha256, Digest};
+use crate::eavto::{canonical, query, store, Triple, Object};
+use super::{ImportError, ImportStats};
+use super::pipeline::{self, assign_graph, ontology_header_triples, RdfFormat};
+
+/// Predicate that changes on every import and is not part of the file's content
+const IMPORTED_AT: &str = "foundation:importedAt";
+
+/// Result of upgrading a file
+#[derive(Debug, serde::Serialize)]
+pub struct UpgradeStats {
+    pub stats: ImportStats,
+    pub added: u64,
+    pub retracted: u64,
+}
+
+/// Check whether the store already holds triples for an origin
+pub fn has_existing_triples(conn: &Connection, origin: &str) -> Result<bool, ImportError> {
+    let Some(origin_id) = query::get_origin_id(conn, origin)? else {
+        return Ok(false);
+    };
+
+    let exists: bool = conn.query_row(
+        "SELECT EXISTS(SELECT 1 FROM triples WHERE origin_id = ? AND retracted = 0)",
+        [origin_id],
+        |row| row.get(0),
+    )?;
+
+    Ok(exists)
+}
+
+/// Upgrade a previously imported Turtle file to its current contents
+///
+/// Retractions and additions are applied as one migration transaction.
+/// When nothing changed, no transaction is written (tx_start is 0).
+pub fn upgrade_turtle_file(
+    conn: &mut Connection,
+    file_path: &Path,
+    origin: &str,
+) -> Result<UpgradeStats, ImportError> {
+    let filename = file_path.file_name().unwrap().to_string_lossy().to_string();
+    println!("Upgrading Turtle file: {}", filename);
+
+    let origin_id = store::get_or_create_origin(conn, origin)?;
+    let created_at = store::now_millis();
+
+    let mut parsed = pipeline::parse_file(file_path, RdfFormat::Turtle, origin_id, created_at)?;
+    let triples_processed = parsed.len() as u64;
+    parsed.extend(ontology_header_triples(&parsed, &filename, created_at));
+    assign_graph(&mut parsed, origin);
+
+    let (import_times, parsed): (Vec<Triple>, Vec<Triple>) = parsed.into_iter()
+        .partition(|t| t.predicate == IMPORTED_AT);
+
+    let (previous_import_times, current): (Vec<Triple>, Vec<Triple>) = query::get_by_origin(conn, origin_id)?
+        .triples
+        .into_iter()
+        .partition(|t| t.predicate == IMPORTED_AT);
+
+    let (mut additions, mut retractions) = diff(&current, &parsed, &filename);
+
+    let stats = |tx: i64, inserted: u64| ImportStats {
+        file: filename.clone(),
+        format: "Turtle (upgrade)".to_string(),
+        triples_processed,
+        facts_inserted: inserted,
+        tx_start: tx,
+        tx_end: tx,
+    };
+
+    if additions.is_empty() && retractions.is_empty() {
+        println!("  No axiom changes in {}", filename);
+        return Ok(UpgradeStats { stats: stats(0, 0), added: 0, retracted: 0 });
+    }
+
+    let added = additions.len() as u64;
+    let retracted = retractions.len() as u64;
+
+    // Refresh the import time of ontology headers along with the content change
+    additions.extend(import_times);
+    retractions.extend(previous_import_times);
+
+    println!("  Applying migration: +{} -{} triples", added, retracted);
+    let tx_id = store::apply_changes(conn, &additions, &retractions, origin)
+        .map_err(|e| ImportError::DatabaseError(format!("Store error: {:?}", e)))?;
+
+    println!("✅ Upgraded {} (+{} -{})", filename, added, retracted);
+
+    Ok(UpgradeStats {
+        stats: stats(tx_id, additions.len() as u64),
+        added,
+        retracted,
+    })
+}
+
+/// Compute (additions, retractions) turning `current` into `target`
+///
+/// Triples are compared by their canonical form, so a blank node whose
+/// structure changed gets a new label and its whole structure is replaced.
+/// Additions get blank node ids from their canonical label and `scope`,
+/// so they stay stable across parses and don't collide with other files.
+fn diff(current: &[Triple], target: &[Triple], scope: &str) -> (Vec<Triple>, Vec<Triple>) {
+    let current_lines = canonical_lines(current);
+    let target_lines = canonical_lines(target);
+
+    let retractions = current_lines.iter()
+        .filter(|(line, _)| !target_lines.contains_key(*line))
+        .flat_map(|(_, entries)| entries.iter().map(|(original, _)| (*original).clone()))
+        .collect();
+
+    let additions = target_lines.iter()
+        .filter(|(line, _)| !current_lines.contains_key(*line))
+        .map(|(_, entries)| {
+            let mut triple = entries[0].1.clone();
+            triple.subject = scoped(&triple.subject, scope);
+            if let Object::Blank(id) = &triple.object {
+                triple.object = Object::Blank(scoped(id, scope));
+            }
+            triple
+        })
+        .collect();
+
+    (additions, retractions)
+}
+
+/// Triples grouped by canonical line: (original triple, canonically relabeled triple)
+///
+/// Structurally identical blank nodes share a line, so a line can have
+/// several originals in the store.
+fn canonical_lines(triples: &[Triple]) -> BTreeMap<String, Vec<(&Triple, Triple)>> {
+    let mut lines: BTreeMap<String, Vec<(&Triple, Triple)>> = BTreeMap::new();
+    for (relabeled, original) in canonical::relabel(triples).into_iter().zip(triples) {
+        lines.entry(canonical::line(&relabeled)).or_default().push((original, relabeled));
+    }
+    lines
+}
+
+/// Blank node id for a canonical label within one file
+fn scoped(node: &str, scope: &str) -> String {
+    if !node.starts_with("_:") {
+        return node.to_string();
+    }
+    let mut hasher = Sha256::new();
+    hasher.update(scope.as_bytes());
+    hasher.update(node.as_bytes());
+    let digest = format!("{:x}", hasher.finalize());
+    format!("_:{}", &digest[..16])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn iri(value: &str) -> Object {
+        Object::Iri(value.to_string())
+    }
+
+    fn blank(value: &str) -> Object {
+        Object::Blank(value.to_string())
+    }
+
+    fn restriction(node: &str, property: &str) -> Vec<Triple> {
+        vec![
+            Triple::new("foundation:Computer", "rdfs:subClassOf", blank(node)),
+            Triple::new(node, "rdf:type", iri("owl:Restriction")),
+            Triple::new(node, "owl:onProperty", iri(property)),
+        ]
+    }
+
+    #[test]
+    fn test_unchanged_blank_nodes_produce_no_diff() {
+        let mut current = restriction("_:riog1", "foundation:hasCPU");
+        current.push(Triple::new("foundation:Computer", "rdf:type", iri("owl:Class")));
+        let mut target = restriction("_:riog9", "foundation:hasCPU");
+        target.push(Triple::new("foundation:Computer", "rdf:type", iri("owl:Class")));
+
+        let (additions, retractions) = diff(&current, &target, "Computer.ttl");
+        assert!(additions.is_empty());
+        assert!(retractions.is_empty());
+    }
+
+    #[test]
+    fn test_changed_restriction_replaces_whole_structure() {
+        let current = restriction("_:riog1", "foundation:hasCPU");
+        let target = restriction("_:riog1", "foundation:hasProcessor");
+
+        let (additions, retractions) = diff(&current, &target, "Computer.ttl");
+        assert_eq!(retractions.len(), 3);
+        assert_eq!(additions.len(), 3);
+
+        // New blank node ids are derived from structure, not the parser's ids
+        let node = additions.iter()
+            .find(|t| t.predicate == "rdfs:subClassOf")
+            .and_then(|t| t.object.as_iri())
+            .unwrap()
+            .to_string();
+        assert!(node.starts_with("_:"));
+        assert_ne!(node, "_:riog1");
+        assert!(additions.iter().filter(|t| t.subject == node).count() == 2);
+    }
+
+    #[test]
+    fn test_literal_changes() {
+        let label = |value: &str| Triple::new("foundation:Computer", "rdfs:label", Object::Literal {
+            value: value.to_string(),
+            datatype: Some("xsd:string".to_string()),
+            language: None,
+        });
+
+        let (additions, retractions) = diff(&[label("Computer")], &[label("Computer System")], "Computer.ttl");
+        assert_eq!(additions.len(), 1);
+        assert_eq!(retractions.len(), 1);
+        assert_eq!(retractions[0].object.as_literal().as_deref(), Some("Computer"));
+    }
+}
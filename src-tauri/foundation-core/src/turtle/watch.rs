@@ -0,0 +1,155 @@
+// ============================================================================
+// Core Ontology Hot Reload
+// ============================================================================
+// Watches the core-ontology directory and re-imports a Turtle file as soon as
+// it is saved, so ontology changes show up without restarting the app. The
+// app starts it when the foundation:watchOntology startup setting is on (the
+// default in dev builds, see owl/startup.rs).
+//
+// A changed file is migrated by diff (see upgrade.rs): triples removed from
+// the file are retracted, new ones asserted. Each reload emits an
+// "ontology-reloaded" event (in the app) for the frontend to refresh its
+// views.
+//
+// Editors often write a file in several steps (truncate, write, rename), so
+// events are collected for DEBOUNCE before reloading.
+// ============================================================================
+
+use notify::{EventKind, RecursiveMode, Watcher};
+use rusqlite::Connection;
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc;
+use std::time::Duration;
+use ts_rs::TS;
+use crate::eavto::DbExecutor;
+use super::{
+    core_ontology_dir, has_existing_triples, import_turtle_file, needs_reimport, register_imported_file,
+    upgrade_turtle_file, ImportError, UpgradeStats,
+};
+
+/// Quiet time after a change before the file is reloaded
+const DEBOUNCE: Duration = Duration::from_millis(300);
+
+/// Payload of the "ontology-reloaded" event
+#[derive(Debug, Clone, Serialize, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export)]
+pub struct OntologyReload {
+    pub file: String,
+    #[ts(type = "number")]
+    pub added: u64,
+    #[ts(type = "number")]
+    pub retracted: u64,
+}
+
+/// Start watching the core-ontology directory, calling `on_reload` after
+/// each reload
+pub fn start(executor: DbExecutor, on_reload: impl Fn(OntologyReload) + Send + 'static) {
+    std::thread::spawn(move || {
+        let dir = core_ontology_dir();
+        let (sender, events) = mpsc::channel();
+        let mut watcher = match notify::recommended_watcher(sender) {
+            Ok(watcher) => watcher,
+            Err(e) => return eprintln!("⚠️  Ontology watcher: {}", e),
+        };
+        if let Err(e) = watcher.watch(&dir, RecursiveMode::NonRecursive) {
+            return eprintln!("⚠️  Ontology watcher on {}: {}", dir.display(), e);
+        }
+        let runtime = match tokio::runtime::Builder::new_current_thread().enable_time().build() {
+            Ok(runtime) => runtime,
+            Err(e) => return eprintln!("⚠️  Ontology watcher: {}", e),
+        };
+        println!("👀 Watching {} for ontology changes", dir.display());
+
+        while let Ok(event) = events.recv() {
+            let mut changed = turtle_files(event);
+            while let Ok(event) = events.recv_timeout(DEBOUNCE) {
+                changed.extend(turtle_files(event));
+            }
+            changed.sort();
+            changed.dedup();
+
+            for path in changed {
+                let file = path.clone();
+                let reloaded = runtime.block_on(executor.write(move |conn| {
+                    reload_file(conn, &file).map_err(|e| format!("{:?}", e))
+                }));
+                match reloaded {
+                    Ok(committed) => if let Some(upgrade) = committed.value {
+                        println!("🔄 Reloaded {} (+{} -{})", upgrade.stats.file, upgrade.added, upgrade.retracted);
+                        on_reload(OntologyReload {
+                            file: upgrade.stats.file,
+                            added: upgrade.added,
+                            retracted: upgrade.retracted,
+                        });
+                    },
+                    Err(e) => eprintln!("⚠️  Failed to reload {}: {}", path.display(), e),
+                }
+            }
+        }
+    });
+}
+
+/// Re-import an ontology file if it changed since it was last imported
+///
+/// Returns None for an unchanged (or deleted) file.
+pub fn reload_file(conn: &mut Connection, path: &Path) -> Result<Option<UpgradeStats>, ImportError> {
+    if !path.is_file() || !needs_reimport(conn, path)? {
+        return Ok(None);
+    }
+    let filename = path.file_name().and_then(|n| n.to_str()).unwrap_or_default();
+    let origin = format!("foundation:ontology:{}", filename);
+
+    let upgrade = match has_existing_triples(conn, &origin)? {
+        true => upgrade_turtle_file(conn, path, &origin)?,
+        false => {
+            let stats = import_turtle_file(conn, path, &origin)?;
+            UpgradeStats { added: stats.facts_inserted, retracted: 0, stats }
+        }
+    };
+    register_imported_file(conn, path, &upgrade.stats)?;
+    Ok(Some(upgrade))
+}
+
+/// Turtle files created or modified by a watcher event
+fn turtle_files(event: notify::Result<notify::Event>) -> Vec<PathBuf> {
+    match event {
+        Ok(event) if matches!(event.kind, EventKind::Create(_) | EventKind::Modify(_)) => event.paths.into_iter()
+            .filter(|p| p.extension().and_then(|e| e.to_str()) == Some("ttl"))
+            .filter(|p| p.file_name().and_then(|n| n.to_str()) != Some("dtype.ttl"))
+            .collect(),
+        _ => Vec::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::eavto::{query, test_helpers::setup_test_db};
+
+    const LABEL: &str = "<http://www.w3.org/2000/01/rdf-schema#label>";
+
+    #[test]
+    fn test_reload_retracts_stale_triples() {
+        let mut conn = setup_test_db();
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("Boat.ttl");
+
+        std::fs::write(&path, format!("<https://foundation.local/ontology/Boat> {} \"Boat\" .\n", LABEL)).unwrap();
+        let first = reload_file(&mut conn, &path).unwrap().unwrap();
+        assert_eq!(first.added, 1);
+        assert!(reload_file(&mut conn, &path).unwrap().is_none());
+
+        std::fs::write(&path, format!("<https://foundation.local/ontology/Boat> {} \"Ship\" .\n", LABEL)).unwrap();
+        let second = reload_file(&mut conn, &path).unwrap().unwrap();
+        assert_eq!((second.added, second.retracted), (1, 1));
+        let origin = query::get_origin_id(&conn, "foundation:ontology:Boat.ttl").unwrap().unwrap();
+        let triples = query::get_by_origin(&conn, origin).unwrap().triples;
+        assert_eq!(triples.len(), 1);
+        assert_eq!(triples[0].object.as_literal().as_deref(), Some("Ship"));
+
+        std::fs::remove_file(&path).unwrap();
+        assert!(reload_file(&mut conn, &path).unwrap().is_none());
+    }
+}
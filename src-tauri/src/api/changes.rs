@@ -0,0 +1,159 @@
+//! Change Feed
+//!
+//! Committed transactions as the /changes WebSocket streams them: each
+//! transaction with the triples it asserted and retracted, objects written as
+//! in JSON exports (numbers and booleans native, dates ISO 8601, IRIs in
+//! prefixed form).
+
+use rusqlite::Connection;
+use serde::Serialize;
+use std::collections::{HashMap, HashSet};
+use crate::eavto::quarantine::{self, OriginStatus};
+use crate::eavto::{query, Triple};
+use crate::export::results::json;
+
+type Result<T> = std::result::Result<T, Box<dyn std::error::Error>>;
+
+/// A committed transaction
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Change {
+    pub tx: i64,
+    pub origin: String,
+    pub created_at: i64, // Unix ms
+    pub message: Option<String>,
+    pub tags: Vec<String>,
+    pub asserted: Vec<ChangedTriple>,
+    pub retracted: Vec<ChangedTriple>,
+}
+
+/// A triple in a change
+#[derive(Debug, Serialize)]
+pub struct ChangedTriple {
+    pub subject: String,
+    pub predicate: String,
+    pub object: serde_json::Value,
+}
+
+impl From<&Triple> for ChangedTriple {
+    fn from(triple: &Triple) -> Self {
+        Self {
+            subject: triple.subject.clone(),
+            predicate: triple.predicate.clone(),
+            object: json(&triple.object),
+        }
+    }
+}
+
+/// Changes committed after a transaction, oldest first, with the last
+/// transaction examined (pass it as `after_tx` next time)
+///
+/// Transactions from quarantined origins are left out, and so are
+/// transactions with nothing left once filtered.
+/// workspace: only triples about entities in this workspace or in none
+pub fn changes_after(conn: &Connection, after_tx: i64, workspace: Option<&str>, limit: usize) -> Result<(Vec<Change>, i64)> {
+    let transactions = query::get_transactions_after(conn, after_tx, limit)?;
+    let last_tx = transactions.last().map_or(after_tx, |t| t.tx);
+    let memberships = match workspace {
+        Some(_) => workspace_memberships(conn)?,
+        None => HashMap::new(),
+    };
+    let in_scope = |triple: &Triple| match workspace {
+        Some(workspace) => memberships.get(&triple.subject).is_none_or(|w| w.contains(workspace)),
+        None => true,
+    };
+
+    let mut changes = Vec::new();
+    for transaction in transactions {
+        if quarantine::get_status(conn, &transaction.origin)? == Some(OriginStatus::Quarantined) {
+            continue;
+        }
+        let (asserted, retracted) = query::diff_transactions(conn, transaction.tx - 1, transaction.tx)?;
+        let asserted: Vec<ChangedTriple> = asserted.iter().filter(|t| in_scope(t)).map(Into::into).collect();
+        let retracted: Vec<ChangedTriple> = retracted.iter().filter(|t| in_scope(t)).map(Into::into).collect();
+        if asserted.is_empty() && retracted.is_empty() {
+            continue;
+        }
+
+        changes.push(Change {
+            tx: transaction.tx,
+            origin: transaction.origin,
+            created_at: transaction.created_at,
+            message: transaction.message,
+            tags: transaction.tags,
+            asserted,
+            retracted,
+        });
+    }
+    Ok((changes, last_tx))
+}
+
+/// Workspaces of each entity in one
+fn workspace_memberships(conn: &Connection) -> Result<HashMap<String, HashSet<String>>> {
+    let mut stmt = conn.prepare(
+        "SELECT subject, object FROM triples WHERE predicate = ? AND retracted = 0"
+    )?;
+    let mut memberships: HashMap<String, HashSet<String>> = HashMap::new();
+    for row in stmt.query_map([query::IN_WORKSPACE], |row| Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?)))? {
+        let (entity, workspace) = row?;
+        memberships.entry(entity).or_default().insert(workspace);
+    }
+    Ok(memberships)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::eavto::{store, test_helpers::setup_test_db, Object};
+
+    fn label(subject: &str, value: &str) -> Triple {
+        Triple::new(subject, "rdfs:label", Object::Literal {
+            value: value.to_string(),
+            datatype: Some("xsd:string".to_string()),
+            language: None,
+        })
+    }
+
+    #[test]
+    fn test_changes_after() {
+        let mut conn = setup_test_db();
+        let tx1 = store::assert_triples(&mut conn, &[
+            label("foundation:Ana", "Ana"),
+            Triple::new("foundation:Ana", "foundation:age", Object::Integer(30)),
+        ], "test").unwrap();
+        let tx2 = store::apply_changes(&mut conn, &[label("foundation:Ana", "Ana Lima")], &[label("foundation:Ana", "Ana")], "user-edit").unwrap();
+
+        let (changes, last_tx) = changes_after(&conn, 0, None, 10).unwrap();
+        assert_eq!(last_tx, tx2);
+        assert_eq!(changes.iter().map(|c| c.tx).collect::<Vec<_>>(), vec![tx1, tx2]);
+        assert_eq!(changes[0].asserted.len(), 2);
+        assert!(changes[0].asserted.iter().any(|t| t.object == serde_json::json!(30)));
+        assert_eq!(changes[1].origin, "user-edit");
+        assert_eq!(changes[1].asserted[0].object, serde_json::json!("Ana Lima"));
+        assert_eq!(changes[1].retracted[0].object, serde_json::json!("Ana"));
+
+        let (changes, last_tx) = changes_after(&conn, tx2, None, 10).unwrap();
+        assert!(changes.is_empty());
+        assert_eq!(last_tx, tx2);
+    }
+
+    #[test]
+    fn test_changes_filtered_by_workspace_and_quarantine() {
+        let mut conn = setup_test_db();
+        store::assert_triples(&mut conn, &[
+            label("foundation:Report", "Report"),
+            Triple::new("foundation:Report", query::IN_WORKSPACE, Object::Iri("foundation:Work".to_string())),
+            label("foundation:Diary", "Diary"),
+            Triple::new("foundation:Diary", query::IN_WORKSPACE, Object::Iri("foundation:Personal".to_string())),
+            label("foundation:Ontology", "Shared"),
+        ], "test").unwrap();
+        quarantine::quarantine(&conn, "web").unwrap();
+        let quarantined = store::assert_triples(&mut conn, &[label("foundation:Page", "Page")], "web").unwrap();
+
+        let (changes, last_tx) = changes_after(&conn, 0, Some("foundation:Work"), 10).unwrap();
+        assert_eq!(last_tx, quarantined);
+        assert_eq!(changes.len(), 1);
+        let subjects: HashSet<&str> = changes[0].asserted.iter().map(|t| t.subject.as_str()).collect();
+        assert_eq!(subjects, HashSet::from(["foundation:Report", "foundation:Ontology"]));
+    }
+}
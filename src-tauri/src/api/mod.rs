@@ -0,0 +1,182 @@
+// ============================================================================
+// HTTP API Module
+// ============================================================================
+// HTTP API for local scripts (see server.rs for the endpoints), and its
+// access control
+//
+// Capability model:
+// - Each command belongs to one scope (read, write, import, export, admin)
+// - A personal API token grants a set of scopes; a request for a command is
+//   allowed when one of them covers the command's scope
+// - Commands missing from the capability table need admin, so new commands
+//   stay closed to tokens until they are classified
+// ============================================================================
+
+pub mod changes;
+pub mod server;
+pub mod token;
+
+pub use token::ApiToken;
+
+/// What a token may do
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Scope {
+    Read,   // Queries and entity reads
+    Write,  // Edits to the graph
+    Import, // Imports from files and other databases
+    Export, // Serializing the graph to files
+    Admin,  // Everything, including token management
+}
+
+impl Scope {
+    pub const ALL: [Scope; 5] = [Scope::Read, Scope::Write, Scope::Import, Scope::Export, Scope::Admin];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Scope::Read => "read",
+            Scope::Write => "write",
+            Scope::Import => "import",
+            Scope::Export => "export",
+            Scope::Admin => "admin",
+        }
+    }
+
+    pub fn parse(scope: &str) -> Option<Self> {
+        Scope::ALL.into_iter().find(|s| s.as_str() == scope)
+    }
+
+    /// Whether this scope allows a command that requires `required`
+    pub fn covers(&self, required: Scope) -> bool {
+        *self == Scope::Admin || *self == required
+    }
+}
+
+/// Scope each command requires: (Tauri command, scope)
+const CAPABILITIES: &[(&str, Scope)] = &[
+    ("entity__get", Scope::Read),
+    ("entity__backlinks", Scope::Read),
+    ("entity__search", Scope::Read),
+    ("graph__neighborhood", Scope::Read),
+    ("graph__find_path", Scope::Read),
+    ("ontology__list_ontologies", Scope::Read),
+    ("ontology__tree", Scope::Read),
+    ("class__instances", Scope::Read),
+    ("ontology__list_releases", Scope::Read),
+    ("ontology__release_for_fact", Scope::Read),
+    ("ontology__list_upgrades", Scope::Read),
+    ("ontology__list_licenses", Scope::Read),
+    ("quarantine__list", Scope::Read),
+    ("origins__list", Scope::Read),
+    ("debug__recent_writes", Scope::Read),
+    ("debug__query_plans", Scope::Read),
+    ("workspace__list", Scope::Read),
+    ("suggestions__get", Scope::Read),
+    ("mentions__propose", Scope::Read),
+    ("import__clock_skew", Scope::Read),
+    ("console__execute", Scope::Read),
+    ("console__history", Scope::Read),
+    ("console__favorites", Scope::Read),
+    ("charts__series", Scope::Read),
+    ("location__visits", Scope::Read),
+    ("person__affiliations", Scope::Read),
+    ("organization__affiliations", Scope::Read),
+    ("parts__of", Scope::Read),
+    ("parts__wholes", Scope::Read),
+    ("parts__shopping_list", Scope::Read),
+    ("photos__duplicates", Scope::Read),
+    ("history__feed", Scope::Read),
+    ("history__diff", Scope::Read),
+    ("entity__history", Scope::Read),
+    ("transaction__verify", Scope::Read),
+    ("palette__actions", Scope::Read),
+    ("shortcuts__get_all", Scope::Read),
+    ("outbox__list", Scope::Read),
+    ("storage__literal_limit", Scope::Read),
+    ("identity__get_public_key", Scope::Read),
+    ("storage__blob", Scope::Read),
+    ("storage__usage", Scope::Read),
+    ("stats__ontology", Scope::Read),
+    ("query__range", Scope::Read),
+    ("fact__annotations", Scope::Read),
+    ("fact__annotated", Scope::Read),
+    ("db__encryption_status", Scope::Read),
+    ("integrity__predicate_policy", Scope::Read),
+    ("settings__startup", Scope::Read),
+    ("sync__status", Scope::Read),
+    ("sync__peers", Scope::Read),
+    ("validation__consistency", Scope::Read),
+    ("lint__run", Scope::Read),
+    ("entity__create", Scope::Write),
+    ("entity__set_property", Scope::Write),
+    ("entity__retract", Scope::Write),
+    ("class__add_restriction", Scope::Write),
+    ("ontology__declare_license", Scope::Write),
+    ("sandbox__begin", Scope::Write),
+    ("sandbox__assert", Scope::Write),
+    ("sandbox__retract", Scope::Write),
+    ("sandbox__entity_get", Scope::Write),
+    ("sandbox__commit", Scope::Write),
+    ("sandbox__discard", Scope::Write),
+    ("workspace__assign", Scope::Write),
+    ("mentions__link", Scope::Write),
+    ("mentions__link_new", Scope::Write),
+    ("fact__annotate", Scope::Write),
+    ("bulk__update", Scope::Write),
+    ("bulk__replace_literal", Scope::Write),
+    ("lint__fix", Scope::Write),
+    ("console__save_favorite", Scope::Write),
+    ("console__remove_favorite", Scope::Write),
+    ("person__add_affiliation", Scope::Write),
+    ("attachment__add", Scope::Write),
+    ("photos__review_duplicate", Scope::Write),
+    ("transaction__undo", Scope::Write),
+    ("palette__record", Scope::Write),
+    ("outbox__retry", Scope::Write),
+    ("outbox__cancel", Scope::Write),
+    ("ontology__import_file", Scope::Import),
+    ("import__foundation_db", Scope::Import),
+    ("import__from_url", Scope::Import),
+    ("import__resolve_imports", Scope::Import),
+    ("import__cancel", Scope::Import),
+    ("finance__import", Scope::Import),
+    ("location__import", Scope::Import),
+    ("listening__import", Scope::Import),
+    ("browsing__import", Scope::Import),
+    ("messaging__import", Scope::Import),
+    ("photos__import", Scope::Import),
+    ("federated__query", Scope::Import),
+    ("class__export", Scope::Export),
+    ("ontology__export_rdfxml", Scope::Export),
+    ("export__origin_as_ontology", Scope::Export),
+    ("export__query_results", Scope::Export),
+    ("export__nquads", Scope::Export),
+    ("export__snapshot", Scope::Export),
+    // HTTP endpoints
+    ("changes", Scope::Read),
+];
+
+/// Scope a command requires; admin for commands not in the capability table
+pub fn required_scope(command: &str) -> Scope {
+    CAPABILITIES.iter()
+        .find(|(name, _)| *name == command)
+        .map_or(Scope::Admin, |(_, scope)| *scope)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_required_scope() {
+        assert_eq!(required_scope("entity__search"), Scope::Read);
+        assert_eq!(required_scope("transaction__undo"), Scope::Write);
+        assert_eq!(required_scope("photos__import"), Scope::Import);
+        assert_eq!(required_scope("api__create_token"), Scope::Admin);
+        assert_eq!(required_scope("setup__init"), Scope::Admin);
+
+        assert!(Scope::Admin.covers(Scope::Import));
+        assert!(!Scope::Write.covers(Scope::Read));
+        assert_eq!(Scope::parse("export"), Some(Scope::Export));
+        assert_eq!(Scope::parse("root"), None);
+    }
+}
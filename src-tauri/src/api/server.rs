@@ -0,0 +1,235 @@
+//! HTTP API Server
+//!
+//! Listens on the loopback interface for local scripts. Requests carry a
+//! personal API token (see token.rs), as `Authorization: Bearer <token>` or,
+//! for browser WebSocket clients that can't set headers, a `token` query
+//! parameter.
+//!
+//! Endpoints:
+//! - GET /changes (WebSocket, read scope): streams committed transactions,
+//!   one JSON text message each (see changes.rs). Query parameters: `after`,
+//!   a transaction to resume after (default: only new commits), and
+//!   `workspace`, a workspace IRI to limit the feed to.
+
+use std::net::{SocketAddr, TcpListener, TcpStream};
+use std::time::Duration;
+use tungstenite::handshake::server::{ErrorResponse, Request, Response};
+use tungstenite::http::StatusCode;
+use tungstenite::{Message, WebSocket};
+use super::{changes, token};
+use crate::eavto::DbExecutor;
+
+type Result<T> = std::result::Result<T, Box<dyn std::error::Error>>;
+
+/// Address served unless FOUNDATION_API_ADDRESS says otherwise
+pub const DEFAULT_ADDRESS: &str = "127.0.0.1:4850";
+
+/// Transactions read from the store per message batch
+const CHANGES_BATCH: usize = 100;
+
+/// Idle time after which a ping checks the client is still there
+const KEEPALIVE: Duration = Duration::from_secs(30);
+
+/// Address to serve the API on: FOUNDATION_API_ADDRESS, or the default
+/// (none on mobile); None when the variable is "off"
+pub fn address() -> Option<String> {
+    match std::env::var("FOUNDATION_API_ADDRESS") {
+        Ok(address) if address == "off" => None,
+        Ok(address) => Some(address),
+        // Mobile apps have no local scripts, and lose their sockets in the background
+        Err(_) if cfg!(any(target_os = "android", target_os = "ios")) => None,
+        Err(_) => Some(DEFAULT_ADDRESS.to_string()),
+    }
+}
+
+/// Start serving the API in the background; returns the bound address
+pub fn start(executor: DbExecutor, address: &str) -> std::io::Result<SocketAddr> {
+    let listener = TcpListener::bind(address)?;
+    let bound = listener.local_addr()?;
+
+    std::thread::spawn(move || {
+        for stream in listener.incoming() {
+            let Ok(stream) = stream else { continue };
+            let executor = executor.clone();
+            std::thread::spawn(move || {
+                if let Err(e) = serve(stream, executor) {
+                    println!("⚠️  HTTP API: {}", e);
+                }
+            });
+        }
+    });
+
+    Ok(bound)
+}
+
+/// Parameters of a /changes request
+struct ChangesRequest {
+    token: String,
+    after: Option<i64>,
+    workspace: Option<String>,
+}
+
+/// Serve one connection: authorize the WebSocket handshake, then stream changes
+// ErrorResponse is the error type of tungstenite's handshake callback
+#[allow(clippy::result_large_err)]
+fn serve(stream: TcpStream, executor: DbExecutor) -> Result<()> {
+    let runtime = tokio::runtime::Builder::new_current_thread().enable_time().build()?;
+
+    let mut accepted = None;
+    let mut socket = tungstenite::accept_hdr(stream, |request: &Request, response: Response| {
+        let request = parse_request(request)?;
+        let secret = request.token.clone();
+        runtime.block_on(executor.write(move |conn| {
+            token::authorize(conn, &secret, "changes").map_err(|e| e.to_string())
+        })).map_err(|e| reject(StatusCode::FORBIDDEN, &e))?;
+        accepted = Some(request);
+        Ok(response)
+    }).map_err(|e| e.to_string())?;
+    let Some(request) = accepted else { return Ok(()) };
+
+    match stream_changes(&mut socket, &executor, &runtime, request) {
+        Err(e) if matches!(e.downcast_ref(), Some(tungstenite::Error::ConnectionClosed | tungstenite::Error::AlreadyClosed)) => Ok(()),
+        result => result,
+    }
+}
+
+/// Send each transaction committed after the request's starting point, as it is committed
+fn stream_changes(
+    socket: &mut WebSocket<TcpStream>,
+    executor: &DbExecutor,
+    runtime: &tokio::runtime::Runtime,
+    request: ChangesRequest,
+) -> Result<()> {
+    let mut committed = executor.subscribe();
+    let mut cursor = request.after.unwrap_or_else(|| executor.committed_tx());
+
+    loop {
+        let workspace = request.workspace.clone();
+        let (batch, last_tx) = runtime.block_on(executor.read(None, move |conn| {
+            changes::changes_after(conn, cursor, workspace.as_deref(), CHANGES_BATCH).map_err(|e| e.to_string())
+        }))?;
+        for change in batch {
+            socket.send(Message::Text(serde_json::to_string(&change)?))?;
+        }
+        cursor = last_tx;
+        if cursor < executor.committed_tx() {
+            continue;
+        }
+
+        match runtime.block_on(async { tokio::time::timeout(KEEPALIVE, committed.changed()).await }) {
+            Ok(Ok(())) => {}
+            Ok(Err(_)) => return Ok(()), // The store closed
+            Err(_) => socket.send(Message::Ping(Vec::new()))?,
+        }
+    }
+}
+
+/// Read a handshake request: the path must be /changes, with a token
+#[allow(clippy::result_large_err)]
+fn parse_request(request: &Request) -> std::result::Result<ChangesRequest, ErrorResponse> {
+    if request.uri().path() != "/changes" {
+        return Err(reject(StatusCode::NOT_FOUND, "Unknown endpoint"));
+    }
+
+    let mut token = request.headers().get("authorization")
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "))
+        .map(|value| value.trim().to_string());
+    let mut after = None;
+    let mut workspace = None;
+    for (key, value) in request.uri().query().unwrap_or_default().split('&').filter_map(|pair| pair.split_once('=')) {
+        let value = percent_decode(value);
+        match key {
+            "token" => token = token.or(Some(value)),
+            "after" => after = Some(value.parse().map_err(|_| reject(StatusCode::BAD_REQUEST, "Invalid after"))?),
+            "workspace" => workspace = Some(value),
+            _ => {}
+        }
+    }
+
+    let token = token.ok_or_else(|| reject(StatusCode::UNAUTHORIZED, "Missing API token"))?;
+    Ok(ChangesRequest { token, after, workspace })
+}
+
+fn reject(status: StatusCode, message: &str) -> ErrorResponse {
+    let mut response = ErrorResponse::new(Some(message.to_string()));
+    *response.status_mut() = status;
+    response
+}
+
+/// Decode %XX escapes (and + as space) in a query parameter
+fn percent_decode(value: &str) -> String {
+    let bytes = value.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        let escaped = (bytes[i] == b'%')
+            .then(|| value.get(i + 1..i + 3).and_then(|hex| u8::from_str_radix(hex, 16).ok()))
+            .flatten();
+        match (escaped, bytes[i]) {
+            (Some(byte), _) => {
+                decoded.push(byte);
+                i += 3;
+            }
+            (None, b'+') => {
+                decoded.push(b' ');
+                i += 1;
+            }
+            (None, byte) => {
+                decoded.push(byte);
+                i += 1;
+            }
+        }
+    }
+    String::from_utf8_lossy(&decoded).into_owned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::Scope;
+    use crate::eavto::{store, test_helpers::setup_test_db, Object, Triple};
+
+    fn connect(address: SocketAddr, query: &str) -> tungstenite::Result<WebSocket<TcpStream>> {
+        let stream = TcpStream::connect(address).unwrap();
+        stream.set_read_timeout(Some(Duration::from_secs(10))).unwrap();
+        tungstenite::client(format!("ws://{}/changes{}", address, query), stream)
+            .map(|(socket, _)| socket)
+            .map_err(|e| match e {
+                tungstenite::HandshakeError::Failure(e) => e,
+                tungstenite::HandshakeError::Interrupted(_) => tungstenite::Error::ConnectionClosed,
+            })
+    }
+
+    #[test]
+    fn test_changes_stream() {
+        let conn = setup_test_db();
+        let (reader, _) = token::create(&conn, "reader", &[Scope::Read], None).unwrap();
+        let (writer, _) = token::create(&conn, "writer", &[Scope::Write], None).unwrap();
+        let runtime = tokio::runtime::Builder::new_multi_thread().enable_all().build().unwrap();
+        let executor = runtime.block_on(async { DbExecutor::new(conn) });
+        let address = start(executor.clone(), "127.0.0.1:0").unwrap();
+
+        // Refused without a token, or without the read scope
+        assert!(connect(address, "").is_err());
+        assert!(connect(address, &format!("?token={}", writer)).is_err());
+
+        let mut socket = connect(address, &format!("?token={}&after=0", reader)).unwrap();
+        let tx = runtime.block_on(executor.write(|conn| {
+            store::assert_triples(conn, &[
+                Triple::new("foundation:Ana", "foundation:age", Object::Integer(30)),
+            ], "test").map_err(|e| e.to_string())
+        })).unwrap().value;
+
+        let Message::Text(text) = socket.read().unwrap() else { panic!("change is a text message") };
+        let change: serde_json::Value = serde_json::from_str(&text).unwrap();
+        assert_eq!(change["tx"], tx);
+        assert_eq!(change["asserted"][0]["object"], 30);
+    }
+
+    #[test]
+    fn test_percent_decode() {
+        assert_eq!(percent_decode("foundation%3AWork"), "foundation:Work");
+        assert_eq!(percent_decode("a+b%2"), "a b%2");
+    }
+}
@@ -0,0 +1,203 @@
+//! Personal API Tokens
+//!
+//! Tokens authenticate local scripts to the HTTP API. A token is shown once,
+//! when created; the database keeps only its SHA-256 hash, with the scopes
+//! it grants, an optional expiry and its revocation time.
+
+use rusqlite::{Connection, OptionalExtension, Row};
+use sha2::{Digest, Sha256};
+use std::collections::hash_map::RandomState;
+use std::hash::{BuildHasher, Hasher};
+use super::{required_scope, Scope};
+use crate::eavto::store::now_millis;
+
+type Result<T> = std::result::Result<T, Box<dyn std::error::Error>>;
+
+/// Prefix of every token, so leaked tokens are easy to recognize
+const TOKEN_PREFIX: &str = "fdn_";
+
+/// Token characters kept to tell tokens apart (prefix included)
+const DISPLAY_PREFIX_LEN: usize = 12;
+
+/// A token, without its secret
+#[derive(Debug, Clone, PartialEq)]
+pub struct ApiToken {
+    pub id: i64,
+    pub name: String,
+    pub prefix: String,
+    pub scopes: Vec<Scope>,
+    pub created_at: i64, // Unix ms
+    pub expires_at: Option<i64>,
+    pub revoked_at: Option<i64>,
+    pub last_used_at: Option<i64>,
+}
+
+impl ApiToken {
+    /// Whether the token can be used at a time (Unix ms)
+    pub fn is_active(&self, now: i64) -> bool {
+        self.revoked_at.is_none() && self.expires_at.is_none_or(|expires| expires > now)
+    }
+
+    fn from_row(row: &Row) -> rusqlite::Result<Self> {
+        let scopes: String = row.get("scopes")?;
+        Ok(Self {
+            id: row.get("id")?,
+            name: row.get("name")?,
+            prefix: row.get("prefix")?,
+            scopes: serde_json::from_str::<Vec<String>>(&scopes)
+                .unwrap_or_default()
+                .iter()
+                .filter_map(|s| Scope::parse(s))
+                .collect(),
+            created_at: row.get("created_at")?,
+            expires_at: row.get("expires_at")?,
+            revoked_at: row.get("revoked_at")?,
+            last_used_at: row.get("last_used_at")?,
+        })
+    }
+}
+
+/// Create a token granting scopes until expires_at (Unix ms; None: no expiry)
+/// Returns the token itself, which can't be recovered later, and its record
+pub fn create(conn: &Connection, name: &str, scopes: &[Scope], expires_at: Option<i64>) -> Result<(String, ApiToken)> {
+    let name = name.trim();
+    if name.is_empty() {
+        return Err("A token needs a name".into());
+    }
+    if scopes.is_empty() {
+        return Err("A token needs at least one scope".into());
+    }
+    let now = now_millis();
+    if expires_at.is_some_and(|expires| expires <= now) {
+        return Err("Token expiry is in the past".into());
+    }
+
+    let mut scope_names: Vec<&str> = Vec::new();
+    for scope in scopes {
+        if !scope_names.contains(&scope.as_str()) {
+            scope_names.push(scope.as_str());
+        }
+    }
+
+    let secret = generate();
+    conn.execute(
+        "INSERT INTO api_tokens (name, token_hash, prefix, scopes, created_at, expires_at)
+         VALUES (?, ?, ?, ?, ?, ?)",
+        (name, hash(&secret), &secret[..DISPLAY_PREFIX_LEN], serde_json::to_string(&scope_names)?, now, expires_at),
+    )?;
+    let token = get(conn, conn.last_insert_rowid())?.ok_or("Token was not created")?;
+    Ok((secret, token))
+}
+
+/// All tokens, newest first (revoked and expired ones included)
+pub fn list(conn: &Connection) -> Result<Vec<ApiToken>> {
+    let mut stmt = conn.prepare("SELECT * FROM api_tokens ORDER BY created_at DESC, id DESC")?;
+    let tokens = stmt.query_map([], ApiToken::from_row)?
+        .collect::<rusqlite::Result<Vec<_>>>()?;
+    Ok(tokens)
+}
+
+/// Revoke a token; it can't be used again
+pub fn revoke(conn: &Connection, id: i64) -> Result<()> {
+    let token = get(conn, id)?.ok_or_else(|| format!("Token {} not found", id))?;
+    if token.revoked_at.is_some() {
+        return Err(format!("Token {} is already revoked", id).into());
+    }
+    conn.execute("UPDATE api_tokens SET revoked_at = ? WHERE id = ?", (now_millis(), id))?;
+    Ok(())
+}
+
+/// Check a token presented to the HTTP API for a command, recording its use
+/// Fails when the token is unknown, revoked or expired, or when none of its
+/// scopes covers the command
+pub fn authorize(conn: &Connection, secret: &str, command: &str) -> Result<ApiToken> {
+    let token = conn.query_row(
+        "SELECT * FROM api_tokens WHERE token_hash = ?",
+        [hash(secret)],
+        ApiToken::from_row,
+    ).optional()?.ok_or("Invalid API token")?;
+
+    let now = now_millis();
+    if !token.is_active(now) {
+        return Err(format!("API token {} is revoked or expired", token.prefix).into());
+    }
+    let required = required_scope(command);
+    if !token.scopes.iter().any(|scope| scope.covers(required)) {
+        return Err(format!("API token {} lacks the {} scope for {}", token.prefix, required.as_str(), command).into());
+    }
+
+    conn.execute("UPDATE api_tokens SET last_used_at = ? WHERE id = ?", (now, token.id))?;
+    Ok(ApiToken { last_used_at: Some(now), ..token })
+}
+
+fn get(conn: &Connection, id: i64) -> Result<Option<ApiToken>> {
+    Ok(conn.query_row("SELECT * FROM api_tokens WHERE id = ?", [id], ApiToken::from_row).optional()?)
+}
+
+/// A new random token: the prefix and 64 hex characters
+/// Randomness comes from the OS-seeded keys of std's RandomState
+fn generate() -> String {
+    let mut hasher = Sha256::new();
+    for i in 0..4u64 {
+        let mut state = RandomState::new().build_hasher();
+        state.write_u64(i);
+        state.write_u128(std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_nanos());
+        hasher.update(state.finish().to_le_bytes());
+    }
+    format!("{}{:x}", TOKEN_PREFIX, hasher.finalize())
+}
+
+fn hash(secret: &str) -> String {
+    format!("{:x}", Sha256::digest(secret.as_bytes()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::eavto::test_helpers::setup_test_db;
+
+    #[test]
+    fn test_token_is_stored_hashed() {
+        let conn = setup_test_db();
+        let (secret, token) = create(&conn, "backup script", &[Scope::Read, Scope::Export, Scope::Read], None).unwrap();
+        assert!(secret.starts_with(TOKEN_PREFIX) && secret.len() == TOKEN_PREFIX.len() + 64);
+        assert_eq!(token.scopes, vec![Scope::Read, Scope::Export]);
+        assert!(secret.starts_with(&token.prefix));
+
+        let stored: String = conn.query_row("SELECT token_hash FROM api_tokens", [], |row| row.get(0)).unwrap();
+        assert_ne!(stored, secret);
+        assert_eq!(stored, hash(&secret));
+
+        let (other, _) = create(&conn, "sync", &[Scope::Write], None).unwrap();
+        assert_ne!(other, secret);
+        assert!(create(&conn, " ", &[Scope::Read], None).is_err());
+        assert!(create(&conn, "empty", &[], None).is_err());
+        assert!(create(&conn, "past", &[Scope::Read], Some(1)).is_err());
+    }
+
+    #[test]
+    fn test_authorize_checks_scope_and_revocation() {
+        let conn = setup_test_db();
+        let (secret, token) = create(&conn, "reader", &[Scope::Read], Some(now_millis() + 60_000)).unwrap();
+
+        let used = authorize(&conn, &secret, "entity__search").unwrap();
+        assert!(used.last_used_at.is_some());
+        assert!(authorize(&conn, &secret, "transaction__undo").is_err());
+        assert!(authorize(&conn, &secret, "api__create_token").is_err());
+        assert!(authorize(&conn, "fdn_guess", "entity__search").is_err());
+
+        revoke(&conn, token.id).unwrap();
+        assert!(authorize(&conn, &secret, "entity__search").is_err());
+        assert!(revoke(&conn, token.id).is_err());
+        assert!(!list(&conn).unwrap()[0].is_active(now_millis()));
+
+        // Expired tokens are refused
+        let (admin, admin_token) = create(&conn, "admin", &[Scope::Admin], None).unwrap();
+        assert!(authorize(&conn, &admin, "setup__init").is_ok());
+        conn.execute("UPDATE api_tokens SET expires_at = 1 WHERE id = ?", [admin_token.id]).unwrap();
+        assert!(authorize(&conn, &admin, "setup__init").is_err());
+    }
+}
@@ -0,0 +1,101 @@
+use serde::Serialize;
+use ts_rs::TS;
+use tauri::State;
+
+use crate::api::{token, ApiToken, Scope};
+use crate::eavto::{Committed, DbExecutor};
+
+/// A personal API token, without its secret
+#[derive(Debug, Serialize, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export)]
+pub struct ApiTokenInfo {
+    #[ts(type = "number")]
+    pub id: i64,
+    pub name: String,
+    pub prefix: String, // First characters of the token
+    pub scopes: Vec<String>,
+    #[ts(type = "number")]
+    pub created_at: i64,
+    #[ts(type = "number | null")]
+    pub expires_at: Option<i64>,
+    #[ts(type = "number | null")]
+    pub revoked_at: Option<i64>,
+    #[ts(type = "number | null")]
+    pub last_used_at: Option<i64>,
+    pub active: bool,
+}
+
+impl From<ApiToken> for ApiTokenInfo {
+    fn from(token: ApiToken) -> Self {
+        Self {
+            active: token.is_active(crate::eavto::store::now_millis()),
+            id: token.id,
+            name: token.name,
+            prefix: token.prefix,
+            scopes: token.scopes.iter().map(|s| s.as_str().to_string()).collect(),
+            created_at: token.created_at,
+            expires_at: token.expires_at,
+            revoked_at: token.revoked_at,
+            last_used_at: token.last_used_at,
+        }
+    }
+}
+
+/// A newly created token; the secret is shown only this once
+#[derive(Debug, Serialize, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export)]
+pub struct CreatedApiToken {
+    pub secret: String,
+    pub token: ApiTokenInfo,
+}
+
+/// Create a personal API token for local scripts calling the HTTP API
+/// scopes: "read", "write", "import", "export" or "admin"
+/// expires_at: Unix ms; the token never expires when omitted
+#[tauri::command]
+#[allow(non_snake_case)]
+pub async fn api__create_token(
+    name: String,
+    scopes: Vec<String>,
+    expires_at: Option<i64>,
+    executor: State<'_, DbExecutor>,
+) -> Result<Committed<CreatedApiToken>, String> {
+    let scopes = scopes.iter()
+        .map(|s| Scope::parse(s).ok_or_else(|| format!("Unknown scope: {}", s)))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    executor.write(move |conn| {
+        let (secret, token) = token::create(conn, &name, &scopes, expires_at)
+            .map_err(|e| format!("Failed to create token: {}", e))?;
+        Ok(CreatedApiToken { secret, token: token.into() })
+    }).await
+}
+
+/// List personal API tokens, newest first
+#[tauri::command]
+#[allow(non_snake_case)]
+pub async fn api__list_tokens(
+    min_tx: Option<i64>,
+    executor: State<'_, DbExecutor>,
+) -> Result<Vec<ApiTokenInfo>, String> {
+    executor.read(min_tx, move |conn| {
+        token::list(conn)
+            .map(|tokens| tokens.into_iter().map(Into::into).collect())
+            .map_err(|e| format!("Failed to list tokens: {}", e))
+    }).await
+}
+
+/// Revoke a personal API token
+#[tauri::command]
+#[allow(non_snake_case)]
+pub async fn api__revoke_token(
+    id: i64,
+    executor: State<'_, DbExecutor>,
+) -> Result<Committed<()>, String> {
+    executor.write(move |conn| {
+        token::revoke(conn, id)
+            .map_err(|e| format!("Failed to revoke token {}: {}", id, e))
+    }).await
+}
@@ -0,0 +1,45 @@
+use serde::Serialize;
+use ts_rs::TS;
+use tauri::State;
+use std::path::PathBuf;
+
+use crate::eavto::{Committed, DbExecutor};
+use crate::owl::attachment::{self, AttachmentImport};
+
+#[derive(Debug, Serialize, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export)]
+pub struct AttachmentReport {
+    pub iri: String,
+    pub media_type: String,
+    #[ts(type = "number")]
+    pub characters: usize, // Indexed text length (0: not searchable)
+}
+
+impl From<AttachmentImport> for AttachmentReport {
+    fn from(report: AttachmentImport) -> Self {
+        Self {
+            iri: report.iri,
+            media_type: report.media_type,
+            characters: report.characters,
+        }
+    }
+}
+
+/// Attach a file to an entity; the text of PDF and text files becomes
+/// searchable with entity__search
+/// Attaching the same file again re-indexes its text
+#[tauri::command]
+#[allow(non_snake_case)]
+pub async fn attachment__add(
+    path: String,
+    entity: String,
+    dry_run: Option<bool>,
+    executor: State<'_, DbExecutor>,
+) -> Result<Committed<AttachmentReport>, String> {
+    executor.write_or_preview(dry_run, move |conn| {
+        attachment::add_attachment(conn, &PathBuf::from(&path), &entity, "user-edit")
+            .map(Into::into)
+            .map_err(|e| format!("Failed to attach {}: {}", path, e))
+    }).await
+}
@@ -0,0 +1,78 @@
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+use tauri::State;
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+use crate::eavto::{Committed, DbExecutor};
+use crate::owl::browsing::{self, WebHistoryImport, WebHistoryOptions};
+
+/// How browsing__import reads a history (all optional)
+#[derive(Debug, Clone, Default, Deserialize, TS)]
+#[serde(rename_all = "camelCase", default)]
+#[ts(export)]
+pub struct BrowsingImportOptions {
+    #[ts(optional)]
+    pub raw: Option<bool>, // Also keep every page visit (default: daily aggregates only)
+    #[ts(optional)]
+    pub organizations: Option<HashMap<String, String>>, // Registered domain -> organization name or IRI
+}
+
+impl From<BrowsingImportOptions> for WebHistoryOptions {
+    fn from(input: BrowsingImportOptions) -> Self {
+        Self {
+            raw: input.raw.unwrap_or(false),
+            organizations: input.organizations.unwrap_or_default(),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export)]
+pub struct BrowsingImportReport {
+    #[ts(type = "number")]
+    pub visits: usize,
+    #[ts(type = "number")]
+    pub activities: usize,
+    #[ts(type = "number")]
+    pub updated: usize,
+    #[ts(type = "number")]
+    pub websites: usize,
+    #[ts(type = "number")]
+    pub organizations: usize,
+    #[ts(type = "number")]
+    pub page_visits: usize,
+}
+
+impl From<WebHistoryImport> for BrowsingImportReport {
+    fn from(report: WebHistoryImport) -> Self {
+        Self {
+            visits: report.visits,
+            activities: report.activities,
+            updated: report.updated,
+            websites: report.websites,
+            organizations: report.organizations,
+            page_visits: report.page_visits,
+        }
+    }
+}
+
+/// Import a browser history database (Chrome, Firefox or Safari) as visits
+/// per website and day, linking websites to the organizations operating them
+#[tauri::command]
+#[allow(non_snake_case)]
+pub async fn browsing__import(
+    path: String,
+    options: Option<BrowsingImportOptions>,
+    dry_run: Option<bool>,
+    executor: State<'_, DbExecutor>,
+) -> Result<Committed<BrowsingImportReport>, String> {
+    let options = WebHistoryOptions::from(options.unwrap_or_default());
+
+    executor.write_or_preview(dry_run, move |conn| {
+        browsing::import_web_history(conn, &PathBuf::from(&path), &options)
+            .map(Into::into)
+            .map_err(|e| format!("Failed to import {}: {}", path, e))
+    }).await
+}
@@ -0,0 +1,151 @@
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+use tauri::State;
+
+use crate::eavto::{Annotation, Committed, DbExecutor};
+use crate::owl::bulk::{self, BulkChange, SkippedFact, ValueMapping};
+use super::triple::{to_object, TripleData};
+
+/// Value change applied by bulk__update
+#[derive(Debug, Clone, Deserialize, TS)]
+#[serde(tag = "op", rename_all = "camelCase")]
+#[ts(export)]
+pub enum BulkMapping {
+    /// Replace the values with one value (an IRI unless datatype or language is given)
+    Set {
+        value: String,
+        #[serde(default)]
+        #[ts(optional)]
+        datatype: Option<String>,
+        #[serde(default)]
+        #[ts(optional)]
+        language: Option<String>,
+    },
+    /// Find and replace in string values
+    Replace { find: String, replace: String },
+    /// Convert numeric values between QUDT units (e.g., "unit:MI" to "unit:KiloM")
+    ConvertUnit { from: String, to: String },
+}
+
+impl From<BulkMapping> for ValueMapping {
+    fn from(mapping: BulkMapping) -> Self {
+        match mapping {
+            BulkMapping::Set { value, datatype, language } => ValueMapping::Set(to_object(&value, &datatype, &language)),
+            BulkMapping::Replace { find, replace } => ValueMapping::Replace { find, replace },
+            BulkMapping::ConvertUnit { from, to } => ValueMapping::ConvertUnit { from, to },
+        }
+    }
+}
+
+#[derive(Debug, Serialize, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export)]
+pub struct BulkChangeInfo {
+    pub entity: String,
+    pub before: Vec<TripleData>,
+    pub after: Vec<TripleData>,
+}
+
+impl From<&BulkChange> for BulkChangeInfo {
+    fn from(change: &BulkChange) -> Self {
+        Self {
+            entity: change.entity.clone(),
+            before: change.before.iter().map(Into::into).collect(),
+            after: change.after.iter().map(Into::into).collect(),
+        }
+    }
+}
+
+/// A fact left unchanged, and why (e.g. the new value isn't a valid xsd:date)
+#[derive(Debug, Serialize, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export)]
+pub struct SkippedFactInfo {
+    pub triple: TripleData,
+    pub reason: String,
+}
+
+impl From<&SkippedFact> for SkippedFactInfo {
+    fn from(skipped: &SkippedFact) -> Self {
+        Self {
+            triple: (&skipped.triple).into(),
+            reason: skipped.reason.clone(),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export)]
+pub struct BulkUpdateResult {
+    pub changes: Vec<BulkChangeInfo>,
+    pub skipped: Vec<SkippedFactInfo>,
+    pub applied: bool,
+}
+
+/// Change a property across all instances of a class in one transaction
+/// dry_run: only preview the affected entities and the diff (nothing is written)
+/// message, tags: annotation shown in the history feed
+#[tauri::command]
+#[allow(non_snake_case)]
+pub async fn bulk__update(
+    class: String,
+    property: String,
+    mapping: BulkMapping,
+    dry_run: Option<bool>,
+    message: Option<String>,
+    tags: Option<Vec<String>>,
+    executor: State<'_, DbExecutor>,
+) -> Result<Committed<BulkUpdateResult>, String> {
+    let mapping = ValueMapping::from(mapping);
+    let applied = !dry_run.unwrap_or(false);
+    let annotation = Annotation::new(message, tags);
+
+    executor.write_or_preview(dry_run, move |conn| {
+        let changes = bulk::plan(conn, &class, &property, &mapping)
+            .map_err(|e| format!("Failed to plan update of {}: {}", property, e))?;
+
+        bulk::apply(conn, &changes, "user-edit", &annotation)
+            .map_err(|e| format!("Failed to update {}: {}", property, e))?;
+
+        Ok(BulkUpdateResult {
+            changes: changes.iter().map(Into::into).collect(),
+            skipped: Vec::new(),
+            applied,
+        })
+    }).await
+}
+
+/// Rename a literal value everywhere it appears, in one transaction: every
+/// fact whose value is exactly old_value (of property, if given) gets new_value
+/// Facts whose datatype new_value doesn't fit are left alone and listed in skipped
+/// dry_run: only preview the affected entities and the diff (nothing is written)
+/// message, tags: annotation shown in the history feed
+#[tauri::command]
+#[allow(non_snake_case)]
+pub async fn bulk__replace_literal(
+    property: Option<String>,
+    old_value: String,
+    new_value: String,
+    dry_run: Option<bool>,
+    message: Option<String>,
+    tags: Option<Vec<String>>,
+    executor: State<'_, DbExecutor>,
+) -> Result<Committed<BulkUpdateResult>, String> {
+    let applied = !dry_run.unwrap_or(false);
+    let annotation = Annotation::new(message, tags);
+
+    executor.write_or_preview(dry_run, move |conn| {
+        let (changes, skipped) = bulk::plan_literal_replace(conn, property.as_deref(), &old_value, &new_value)
+            .map_err(|e| format!("Failed to plan renaming \"{}\": {}", old_value, e))?;
+
+        bulk::apply(conn, &changes, "user-edit", &annotation)
+            .map_err(|e| format!("Failed to rename \"{}\": {}", old_value, e))?;
+
+        Ok(BulkUpdateResult {
+            changes: changes.iter().map(Into::into).collect(),
+            skipped: skipped.iter().map(Into::into).collect(),
+            applied,
+        })
+    }).await
+}
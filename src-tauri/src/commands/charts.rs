@@ -0,0 +1,53 @@
+use serde::Serialize;
+use ts_rs::TS;
+use tauri::State;
+
+use crate::eavto::{series::{self, Bucket}, DbExecutor};
+
+/// Time series for plotting, as parallel arrays (one element per bucket)
+#[derive(Debug, Serialize, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export)]
+pub struct ChartSeries {
+    #[ts(type = "number[]")]
+    pub starts: Vec<i64>, // Bucket start (Unix ms, UTC)
+    #[ts(type = "number[]")]
+    pub counts: Vec<usize>,
+    pub min: Vec<f64>,
+    pub max: Vec<f64>,
+    pub avg: Vec<f64>,
+    pub sum: Vec<f64>,
+    pub last: Vec<f64>,
+    pub unit: Option<String>,
+}
+
+/// Aggregate the numeric values of an entity's property into time buckets
+/// bucket: "day" | "week" | "month"
+#[tauri::command]
+#[allow(non_snake_case)]
+pub async fn charts__series(
+    entity: String,
+    property: String,
+    bucket: String,
+    min_tx: Option<i64>,
+    executor: State<'_, DbExecutor>,
+) -> Result<ChartSeries, String> {
+    let bucket = Bucket::from_name(&bucket)
+        .ok_or_else(|| format!("Unsupported bucket: {} (expected day, week or month)", bucket))?;
+
+    executor.read(min_tx, move |conn| {
+        let series = series::series(conn, &entity, &property, bucket)
+            .map_err(|e| format!("Failed to aggregate {} of {}: {}", property, entity, e))?;
+
+        Ok(ChartSeries {
+            starts: series.starts,
+            counts: series.counts,
+            min: series.min,
+            max: series.max,
+            avg: series.avg,
+            sum: series.sum,
+            last: series.last,
+            unit: series.unit,
+        })
+    }).await
+}
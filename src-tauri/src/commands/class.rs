@@ -0,0 +1,185 @@
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+use tauri::State;
+
+use crate::eavto::{Committed, DbExecutor};
+use crate::owl::{Class, Thing, manchester, functional, table::{self, Cell, FacetFilter, TableQuery}};
+
+/// Rows per page when the caller gives no page size
+const DEFAULT_PAGE_SIZE: usize = 50;
+
+#[derive(Debug, Serialize, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export)]
+pub struct AddRestrictionResult {
+    pub node: String,
+    pub expression: String,
+}
+
+/// Export a class's axioms as text
+/// syntax: "manchester" (default) or "functional"
+#[tauri::command]
+#[allow(non_snake_case)]
+pub async fn class__export(
+    class_id: String,
+    syntax: Option<String>,
+    min_tx: Option<i64>,
+    executor: State<'_, DbExecutor>,
+) -> Result<String, String> {
+    executor.read(min_tx, move |conn| {
+        if !Class::new(&class_id).exists(conn).map_err(|e| e.to_string())? {
+            return Err(format!("Class {} not found", class_id));
+        }
+
+        match syntax.as_deref().unwrap_or("manchester") {
+            "manchester" => manchester::export_class(conn, &class_id),
+            "functional" => functional::export_class(conn, &class_id),
+            other => return Err(format!("Unsupported syntax: {}", other)),
+        }.map_err(|e| e.to_string())
+    }).await
+}
+
+/// Add a restriction to a class from a Manchester expression
+/// (e.g., "foundation:hasProcessor some foundation:Processor")
+#[tauri::command]
+#[allow(non_snake_case)]
+pub async fn class__add_restriction(
+    class_id: String,
+    expression: String,
+    dry_run: Option<bool>,
+    executor: State<'_, DbExecutor>,
+) -> Result<Committed<AddRestrictionResult>, String> {
+    executor.write_or_preview(dry_run, move |conn| {
+        let parsed = manchester::parse(&expression).map_err(|e| e.to_string())?;
+
+        let node = Class::new(&class_id)
+            .add_restriction(conn, &parsed, "user-edit")
+            .map_err(|e| e.to_string())?;
+
+        Ok(AddRestrictionResult {
+            node,
+            expression: manchester::render(&parsed),
+        })
+    }).await
+}
+
+/// Instances kept by class__instances: those with one of the values
+#[derive(Debug, Clone, Deserialize, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export)]
+pub struct FacetFilterInput {
+    pub property: String,
+    pub values: Vec<String>, // IRIs or literal values
+}
+
+/// A value of a cell or a facet
+#[derive(Debug, Serialize, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export)]
+pub struct InstanceCell {
+    pub value: String,
+    pub label: String,
+    pub is_iri: bool,
+}
+
+impl From<Cell> for InstanceCell {
+    fn from(cell: Cell) -> Self {
+        Self { value: cell.value, label: cell.label, is_iri: cell.is_iri }
+    }
+}
+
+#[derive(Debug, Serialize, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export)]
+pub struct InstanceRow {
+    pub iri: String,
+    pub label: String,
+    pub icon: Option<String>,
+    pub cells: Vec<Vec<InstanceCell>>, // One list of values per column
+}
+
+#[derive(Debug, Serialize, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export)]
+pub struct InstanceFacetValue {
+    pub value: InstanceCell,
+    #[ts(type = "number")]
+    pub count: usize,
+}
+
+#[derive(Debug, Serialize, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export)]
+pub struct InstanceFacet {
+    pub property: String,
+    pub values: Vec<InstanceFacetValue>, // Most common first
+}
+
+#[derive(Debug, Serialize, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export)]
+pub struct ClassInstances {
+    pub columns: Vec<Thing>,
+    pub rows: Vec<InstanceRow>,
+    #[ts(type = "number")]
+    pub total: usize, // Instances after filtering, over all pages
+    pub facets: Vec<InstanceFacet>, // Values of each column over the filtered instances
+}
+
+/// A page of the instances of a class, subclass members and inferred
+/// members included, with some properties as columns
+/// columns: the properties listed (default: the properties of the class)
+/// sort_by_property: sort by the first value of a property (default: by label);
+/// missing values sort last
+/// facet_filters: keep instances with one of the values of each filter
+#[tauri::command]
+#[allow(non_snake_case, clippy::too_many_arguments)]
+pub async fn class__instances(
+    class_iri: String,
+    page: Option<usize>,
+    page_size: Option<usize>,
+    columns: Option<Vec<String>>,
+    sort_by_property: Option<String>,
+    descending: Option<bool>,
+    facet_filters: Option<Vec<FacetFilterInput>>,
+    min_tx: Option<i64>,
+    executor: State<'_, DbExecutor>,
+) -> Result<ClassInstances, String> {
+    executor.read(min_tx, move |conn| {
+        let query = TableQuery {
+            columns,
+            filters: facet_filters.unwrap_or_default().into_iter()
+                .map(|filter| FacetFilter { property: filter.property, values: filter.values })
+                .collect(),
+            sort_by: sort_by_property,
+            descending: descending.unwrap_or(false),
+            page: page.unwrap_or(0),
+            page_size: page_size.unwrap_or(DEFAULT_PAGE_SIZE),
+        };
+        let instances = table::instances(conn, &class_iri, &query)
+            .map_err(|e| format!("Failed to list the instances of {}: {}", class_iri, e))?;
+
+        Ok(ClassInstances {
+            columns: instances.columns,
+            rows: instances.rows.into_iter()
+                .map(|row| InstanceRow {
+                    iri: row.thing.iri,
+                    label: row.thing.label,
+                    icon: row.thing.icon,
+                    cells: row.cells.into_iter()
+                        .map(|values| values.into_iter().map(Into::into).collect())
+                        .collect(),
+                })
+                .collect(),
+            total: instances.total,
+            facets: instances.facets.into_iter()
+                .map(|facet| InstanceFacet {
+                    property: facet.property,
+                    values: facet.values.into_iter()
+                        .map(|value| InstanceFacetValue { value: value.cell.into(), count: value.count })
+                        .collect(),
+                })
+                .collect(),
+        })
+    }).await
+}
@@ -0,0 +1,194 @@
+use serde::Serialize;
+use ts_rs::TS;
+use tauri::State;
+use std::time::Instant;
+
+use crate::eavto::{pattern, Committed, DbExecutor, Object};
+use crate::owl::console::{self, HistoryEntry, SavedQuery};
+
+/// Rows returned when the console doesn't ask for a limit
+const DEFAULT_LIMIT: usize = 1000;
+
+/// A bound value: an IRI (or "_:" blank node) unless a datatype or language is given
+#[derive(Debug, Serialize, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export)]
+pub struct ConsoleValue {
+    pub value: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub datatype: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub language: Option<String>,
+}
+
+impl From<&Object> for ConsoleValue {
+    fn from(object: &Object) -> Self {
+        let (datatype, language) = match object {
+            Object::Iri(_) | Object::Blank(_) => (None, None),
+            Object::Literal { language: Some(language), .. } => (None, Some(language.clone())),
+            other => (Some(other.datatype().unwrap_or("xsd:string").to_string()), None),
+        };
+        Self {
+            value: object.as_iri().map(|s| s.to_string())
+                .or_else(|| object.as_literal())
+                .unwrap_or_default(),
+            datatype,
+            language,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export)]
+pub struct ConsolePlanStep {
+    pub pattern: String,
+    #[ts(type = "number")]
+    pub estimate: i64,
+    pub joined: Vec<String>,
+}
+
+#[derive(Debug, Serialize, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export)]
+pub struct ConsoleResult {
+    pub variables: Vec<String>,
+    pub rows: Vec<Vec<ConsoleValue>>,
+    pub truncated: bool,
+    #[ts(type = "number")]
+    pub elapsed_ms: u64,
+    pub plan: Vec<ConsolePlanStep>,
+}
+
+#[derive(Debug, Serialize, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export)]
+pub struct ConsoleHistoryEntry {
+    pub query: String,
+    #[ts(type = "number")]
+    pub executed_at: i64,
+    #[ts(type = "number")]
+    pub rows: usize,
+    pub error: Option<String>,
+}
+
+impl From<HistoryEntry> for ConsoleHistoryEntry {
+    fn from(entry: HistoryEntry) -> Self {
+        Self { query: entry.query, executed_at: entry.executed_at, rows: entry.rows, error: entry.error }
+    }
+}
+
+#[derive(Debug, Serialize, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export)]
+pub struct ConsoleFavorite {
+    pub iri: String,
+    pub label: String,
+    pub query: String,
+}
+
+impl From<SavedQuery> for ConsoleFavorite {
+    fn from(saved: SavedQuery) -> Self {
+        Self { iri: saved.iri, label: saved.label, query: saved.query }
+    }
+}
+
+/// Run a pattern query (SPARQL subset) and add it to the console history
+/// limit: maximum rows returned (default 1000)
+#[tauri::command]
+#[allow(non_snake_case)]
+pub async fn console__execute(
+    query: String,
+    limit: Option<usize>,
+    executor: State<'_, DbExecutor>,
+) -> Result<Committed<ConsoleResult>, String> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT);
+
+    executor.write(move |conn| {
+        let started = Instant::now();
+        let result = pattern::execute(conn, &query, Some(limit)).map_err(|e| e.to_string());
+        let elapsed_ms = started.elapsed().as_millis() as u64;
+
+        console::record(conn, HistoryEntry {
+            query: query.trim().to_string(),
+            executed_at: chrono::Utc::now().timestamp_millis(),
+            rows: result.as_ref().map(|r| r.rows.len()).unwrap_or(0),
+            error: result.as_ref().err().cloned(),
+        }).map_err(|e| format!("Failed to record query history: {}", e))?;
+
+        let result = result?;
+        Ok(ConsoleResult {
+            variables: result.variables,
+            rows: result.rows.iter().map(|row| row.iter().map(Into::into).collect()).collect(),
+            truncated: result.truncated,
+            elapsed_ms,
+            plan: result.plan.into_iter()
+                .map(|step| ConsolePlanStep { pattern: step.pattern, estimate: step.estimate, joined: step.joined })
+                .collect(),
+        })
+    }).await
+}
+
+/// Get the console query history, newest first
+#[tauri::command]
+#[allow(non_snake_case)]
+pub async fn console__history(
+    min_tx: Option<i64>,
+    executor: State<'_, DbExecutor>,
+) -> Result<Vec<ConsoleHistoryEntry>, String> {
+    executor.read(min_tx, move |conn| {
+        Ok(console::history(conn)
+            .map_err(|e| e.to_string())?
+            .into_iter()
+            .map(Into::into)
+            .collect())
+    }).await
+}
+
+/// Get the saved (favorite) queries
+#[tauri::command]
+#[allow(non_snake_case)]
+pub async fn console__favorites(
+    min_tx: Option<i64>,
+    executor: State<'_, DbExecutor>,
+) -> Result<Vec<ConsoleFavorite>, String> {
+    executor.read(min_tx, move |conn| {
+        Ok(console::favorites(conn)
+            .map_err(|e| e.to_string())?
+            .into_iter()
+            .map(Into::into)
+            .collect())
+    }).await
+}
+
+/// Save a query as a favorite (a foundation:SavedQuery entity); returns its IRI
+#[tauri::command]
+#[allow(non_snake_case)]
+pub async fn console__save_favorite(
+    label: String,
+    query: String,
+    dry_run: Option<bool>,
+    executor: State<'_, DbExecutor>,
+) -> Result<Committed<String>, String> {
+    executor.write_or_preview(dry_run, move |conn| {
+        console::save_favorite(conn, &label, &query, "user-edit")
+            .map_err(|e| format!("Failed to save query: {}", e))
+    }).await
+}
+
+/// Remove a saved query
+#[tauri::command]
+#[allow(non_snake_case)]
+pub async fn console__remove_favorite(
+    iri: String,
+    dry_run: Option<bool>,
+    executor: State<'_, DbExecutor>,
+) -> Result<Committed<()>, String> {
+    executor.write_or_preview(dry_run, move |conn| {
+        console::remove_favorite(conn, &iri, "user-edit")
+            .map(|_| ())
+            .map_err(|e| format!("Failed to remove {}: {}", iri, e))
+    }).await
+}
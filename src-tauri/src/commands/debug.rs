@@ -0,0 +1,91 @@
+use serde::Serialize;
+use ts_rs::TS;
+use tauri::State;
+
+use crate::eavto::{DbExecutor, indexes::{self, QueryPlan}, journal::{self, JournalEntry}};
+
+/// Writes listed when the caller gives no limit
+const RECENT_WRITES: usize = 100;
+
+/// A write, as journaled
+#[derive(Debug, Serialize, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export)]
+pub struct JournaledWrite {
+    #[ts(type = "number")]
+    pub id: i64,
+    pub command: String,
+    pub args_hash: Option<String>, // SHA-256 of the command's arguments
+    #[ts(type = "number | null")]
+    pub tx: Option<i64>,          // None: nothing written, or failed
+    #[ts(type = "number")]
+    pub duration_ms: i64,
+    pub error: Option<String>,
+    #[ts(type = "number")]
+    pub recorded_at: i64,
+}
+
+impl From<JournalEntry> for JournaledWrite {
+    fn from(entry: JournalEntry) -> Self {
+        Self {
+            id: entry.id,
+            command: entry.command,
+            args_hash: entry.args_hash,
+            tx: entry.tx,
+            duration_ms: entry.duration_ms,
+            error: entry.error,
+            recorded_at: entry.recorded_at,
+        }
+    }
+}
+
+/// How SQLite runs a hot lookup
+#[derive(Debug, Serialize, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export)]
+pub struct LookupPlan {
+    pub query: String, // eavto::query function
+    pub sql: String,
+    pub steps: Vec<String>, // EXPLAIN QUERY PLAN, nested steps indented
+}
+
+impl From<QueryPlan> for LookupPlan {
+    fn from(plan: QueryPlan) -> Self {
+        Self { query: plan.query, sql: plan.sql, steps: plan.steps }
+    }
+}
+
+/// The latest writes, newest first, to trace a data problem back to the
+/// operations that produced it
+#[tauri::command]
+#[allow(non_snake_case)]
+pub async fn debug__recent_writes(
+    limit: Option<usize>,
+    min_tx: Option<i64>,
+    executor: State<'_, DbExecutor>,
+) -> Result<Vec<JournaledWrite>, String> {
+    executor.read(min_tx, move |conn| {
+        Ok(journal::recent(conn, limit.unwrap_or(RECENT_WRITES))
+            .map_err(|e| e.to_string())?
+            .into_iter()
+            .map(Into::into)
+            .collect())
+    }).await
+}
+
+/// Query plans of the lookups behind Class::get and the entity graph, to
+/// check they search the triple indexes rather than scan
+#[tauri::command]
+#[allow(non_snake_case)]
+pub async fn debug__query_plans(
+    min_tx: Option<i64>,
+    executor: State<'_, DbExecutor>,
+) -> Result<Vec<LookupPlan>, String> {
+    executor.read(min_tx, move |conn| {
+        Ok(indexes::explain(conn)
+            .map_err(|e| e.to_string())?
+            .into_iter()
+            .map(Into::into)
+            .collect())
+    }).await
+}
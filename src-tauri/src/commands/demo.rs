@@ -0,0 +1,124 @@
+use rusqlite::Connection;
+use serde::Serialize;
+use std::sync::Mutex;
+use ts_rs::TS;
+use tauri::State;
+
+use crate::eavto::{Committed, DbExecutor};
+
+/// The user's own database while a demo profile is open (None: no demo)
+#[derive(Default)]
+pub struct DemoProfile(Mutex<Option<Connection>>);
+
+impl DemoProfile {
+    /// Whether a demo profile is open
+    pub fn is_active(&self) -> Result<bool, String> {
+        Ok(self.0.lock().map_err(|e| e.to_string())?.is_some())
+    }
+}
+
+/// What demo__load asserted
+#[derive(Debug, Serialize, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export)]
+pub struct DemoLoad {
+    #[ts(type = "number")]
+    pub people: usize,
+    #[ts(type = "number")]
+    pub triples: usize,
+    #[ts(type = "number")]
+    pub replaced: usize,
+}
+
+/// Load a synthetic personal graph (people, visits, files, tasks) for
+/// screenshots and onboarding, replacing demo data loaded before
+/// size: number of people (default 25); the rest scales with it
+#[tauri::command]
+#[allow(non_snake_case)]
+pub async fn demo__load(
+    size: Option<usize>,
+    seed: Option<u64>,
+    executor: State<'_, DbExecutor>,
+) -> Result<Committed<DemoLoad>, String> {
+    #[cfg(feature = "fixtures")]
+    {
+        use crate::fixtures::{self, FixtureConfig};
+
+        let mut config = FixtureConfig::sized(size.unwrap_or(fixtures::DEFAULT_SIZE));
+        config.seed = seed.unwrap_or(config.seed);
+        executor.write(move |conn| {
+            fixtures::load(conn, &config)
+                .map(|report| DemoLoad { people: config.people, triples: report.triples, replaced: report.replaced })
+                .map_err(|e| format!("Failed to load demo data: {}", e))
+        }).await
+    }
+    #[cfg(not(feature = "fixtures"))]
+    {
+        let _ = (size, seed, executor);
+        Err("This build has no demo data (fixtures feature disabled)".to_string())
+    }
+}
+
+/// Open a throwaway demo profile: a new database populated with demo data,
+/// used by every command until demo__reset. The user's database is left
+/// untouched meanwhile.
+/// size, seed: as for demo__load
+#[tauri::command]
+#[allow(non_snake_case)]
+pub async fn demo__start(
+    size: Option<usize>,
+    seed: Option<u64>,
+    executor: State<'_, DbExecutor>,
+    profile: State<'_, DemoProfile>,
+) -> Result<Committed<DemoLoad>, String> {
+    #[cfg(feature = "fixtures")]
+    {
+        use crate::eavto::{get_demo_db_path, initialize_db};
+        use crate::fixtures::{self, FixtureConfig};
+
+        if profile.0.lock().map_err(|e| e.to_string())?.is_some() {
+            return Err("A demo profile is already open".to_string());
+        }
+        let mut config = FixtureConfig::sized(size.unwrap_or(fixtures::DEFAULT_SIZE));
+        config.seed = seed.unwrap_or(config.seed);
+
+        let (conn, report) = tokio::task::spawn_blocking(move || {
+            let path = get_demo_db_path().map_err(|e| format!("{:?}", e))?;
+            let _ = std::fs::remove_file(&path);
+            let mut conn = initialize_db(&path).map_err(|e| format!("Failed to create demo profile: {:?}", e))?;
+            let report = fixtures::load(&mut conn, &config).map_err(|e| format!("Failed to load demo data: {}", e))?;
+            Ok::<_, String>((conn, report))
+        }).await.map_err(|e| e.to_string())??;
+
+        let own = executor.swap(conn).await?;
+        *profile.0.lock().map_err(|e| e.to_string())? = Some(own);
+        Ok(Committed {
+            tx: executor.committed_tx(),
+            value: DemoLoad { people: config.people, triples: report.triples, replaced: 0 },
+            dry_run: None,
+        })
+    }
+    #[cfg(not(feature = "fixtures"))]
+    {
+        let _ = (size, seed, executor, profile);
+        Err("This build has no demo data (fixtures feature disabled)".to_string())
+    }
+}
+
+/// Close the demo profile: switch back to the user's database and delete
+/// the demo one
+#[tauri::command]
+#[allow(non_snake_case)]
+pub async fn demo__reset(
+    executor: State<'_, DbExecutor>,
+    profile: State<'_, DemoProfile>,
+) -> Result<Committed<()>, String> {
+    let own = profile.0.lock().map_err(|e| e.to_string())?.take()
+        .ok_or_else(|| "No demo profile is open".to_string())?;
+    drop(executor.swap(own).await?);
+
+    if let Ok(path) = crate::eavto::get_demo_db_path() {
+        let _ = std::fs::remove_file(path);
+    }
+    Ok(Committed { tx: executor.committed_tx(), value: (), dry_run: None })
+}
@@ -0,0 +1,81 @@
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+use tauri::State;
+
+use crate::eavto::{self, encryption::{self, KeySource}, Committed, DbExecutor};
+use super::demo::DemoProfile;
+
+/// Where the database key comes from
+#[derive(Debug, Clone, Deserialize, TS)]
+#[serde(tag = "mode", rename_all = "camelCase")]
+#[ts(export)]
+pub enum EncryptionKey {
+    /// A passphrase, asked at every launch
+    Passphrase { passphrase: String },
+    /// A random key kept in the OS keychain (unlocks without asking)
+    Keychain,
+}
+
+impl From<EncryptionKey> for KeySource {
+    fn from(key: EncryptionKey) -> Self {
+        match key {
+            EncryptionKey::Passphrase { passphrase } => KeySource::Passphrase(passphrase),
+            EncryptionKey::Keychain => KeySource::Keychain,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export)]
+pub struct EncryptionStatus {
+    pub supported: bool, // Built with the encryption feature
+    pub encrypted: bool,
+}
+
+/// Whether the database is encrypted, and whether this build can encrypt it
+#[tauri::command]
+#[allow(non_snake_case)]
+pub async fn db__encryption_status() -> Result<EncryptionStatus, String> {
+    let path = eavto::get_db_path().map_err(|e| format!("{:?}", e))?;
+    Ok(EncryptionStatus {
+        supported: encryption::is_supported(),
+        encrypted: encryption::is_encrypted(&path),
+    })
+}
+
+/// Unlock the encrypted database with its passphrase (after the
+/// database-locked event); startup then goes on
+#[tauri::command]
+#[allow(non_snake_case)]
+pub async fn db__unlock(passphrase: String) -> Result<(), String> {
+    let path = eavto::get_db_path().map_err(|e| format!("{:?}", e))?;
+    encryption::unlock(&path, &KeySource::Passphrase(passphrase)).map_err(|e| e.to_string())
+}
+
+/// Encrypt the database, change its key, or decrypt it (key omitted)
+#[tauri::command]
+#[allow(non_snake_case)]
+pub async fn db__set_encryption(
+    key: Option<EncryptionKey>,
+    executor: State<'_, DbExecutor>,
+    profile: State<'_, DemoProfile>,
+) -> Result<Committed<EncryptionStatus>, String> {
+    if profile.is_active()? {
+        return Err("Encryption can't be changed while a demo profile is open".to_string());
+    }
+    let path = eavto::get_db_path().map_err(|e| format!("{:?}", e))?;
+    let source = key.map(KeySource::from);
+
+    let status = executor.write(move |conn| {
+        encryption::set_encryption(conn, &path, source.as_ref())
+            .map_err(|e| format!("Failed to set database encryption: {}", e))?;
+        Ok(EncryptionStatus {
+            supported: true,
+            encrypted: encryption::is_encrypted(&path),
+        })
+    }).await?;
+    // Reads were on the file replaced
+    executor.reopen_readers().await?;
+    Ok(status)
+}
@@ -1,9 +1,15 @@
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
 use tauri::State;
 use rusqlite::Connection;
+use std::collections::HashMap;
 
-use crate::eavto::DbExecutor;
-use crate::owl::{Class, Individual, Property};
+use crate::eavto::{Committed, DbExecutor, query};
+use crate::owl::{Class, EntityKind, Individual, Property, PropertyInput};
+
+/// Backlinks returned by entity__get (an imported track can have thousands
+/// of listens); the rest are paged with entity__backlinks
+const BACKLINKS_PAGE: usize = 100;
 
 /// Entity type in OWL ontology
 #[derive(Debug, Serialize, Clone)]
@@ -14,33 +20,41 @@ pub enum EntityType {
 }
 
 /// Search result for entities
-#[derive(Debug, Serialize, serde::Deserialize)]
+#[derive(Debug, Serialize, serde::Deserialize, TS)]
 #[serde(rename_all = "camelCase")]
+#[ts(export)]
 pub struct SearchResult {
     pub id: String,
     pub label: String,
     pub icon: Option<String>,
     #[serde(rename = "type")]
-    pub entity_type: String, // "class" or "individual"
+    pub entity_type: String, // "class", "property", "individual" or "attachment"
+    pub type_iri: Option<String>, // Direct (asserted) type of the entity
+    pub snippet: Option<String>, // Attachments: matching text, terms between [ and ]
+    pub attached_to: Option<String>, // Attachments: the entity the file is attached to
 }
 
 /// Node in the graph (Class or Individual)
-#[derive(Debug, Serialize, Clone)]
+#[derive(Debug, Serialize, Clone, TS)]
 #[serde(rename_all = "camelCase")]
+#[ts(export)]
 pub struct GraphNode {
     pub id: String,
     pub label: String,
     pub icon: Option<String>,
     pub group: u8, // 1 = Class, 6 = Individual, 7 = Literal Value
     #[serde(skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
     pub is_broken_ref: Option<bool>, // true if entity doesn't exist in database
     #[serde(skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
     pub is_literal: Option<bool>, // true if this is a literal value node
 }
 
 /// Link between nodes (ObjectProperty)
-#[derive(Debug, Serialize, Clone)]
+#[derive(Debug, Serialize, Clone, TS)]
 #[serde(rename_all = "camelCase")]
+#[ts(export)]
 pub struct GraphLink {
     pub source: String,
     pub target: String,
@@ -48,31 +62,38 @@ pub struct GraphLink {
 }
 
 /// Complete entity data with its neighborhood
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, TS)]
 #[serde(rename_all = "camelCase")]
+#[ts(export)]
 pub struct EntityData {
     pub id: String,
     pub label: String,
     pub icon: Option<String>,
     pub comment: Option<String>,
+    pub same_as: Vec<String>, // Other IRIs merged into this entity (owl:sameAs)
 
     // RDF semantic data
     pub types: Vec<crate::owl::Thing>, // rdf:type (for individuals)
     pub super_classes: Vec<crate::owl::Thing>, // rdfs:subClassOf (for classes)
     pub sub_classes: Vec<crate::owl::Thing>, // inverse of rdfs:subClassOf (for classes)
     pub instances: Vec<crate::owl::Thing>, // entities with rdf:type pointing to this class
+    pub union_of: Vec<String>, // owl:unionOf members in Manchester syntax (for classes)
+    pub intersection_of: Vec<String>, // owl:intersectionOf members in Manchester syntax (for classes)
 
     // Properties and relationships
     pub properties: Vec<PropertyValue>,
-    pub backlinks: Vec<PropertyValue>, // Properties from other entities pointing to this one
+    pub backlinks: Vec<PropertyValue>, // Properties from other entities pointing to this one (most recent BACKLINKS_PAGE)
+    #[ts(type = "number")]
+    pub backlinks_total: usize, // All backlinks; page through them with entity__backlinks
 
     // Graph visualization data
     pub nodes: Vec<GraphNode>,
     pub links: Vec<GraphLink>,
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, TS)]
 #[serde(rename_all = "camelCase")]
+#[ts(export)]
 pub struct PropertyValue {
     pub property: String,
     pub property_label: String,
@@ -85,46 +106,87 @@ pub struct PropertyValue {
     pub source_class_label: Option<String>,
     pub unit: Option<String>, // QUDT unit IRI (e.g., "unit:GigaBYTE")
     pub unit_label: Option<String>, // QUDT unit label (e.g., "Gigabyte")
+    pub constraints: Vec<String>, // For classes: restrictions on the property, in Manchester syntax (e.g., "foundation:sourceFile exactly 1")
 }
 
-/// Search for entities (classes and individuals) by label
+/// Search for entities (classes, properties and individuals) by label, and
+/// attachments by their text
+/// With a class, only its members are searched, inferred ones included
+/// (instances of subclasses, entities typed by property domains and ranges)
+/// With a kind ("class", "property" or "individual"), only entities of that
+/// kind are searched
 #[tauri::command]
 #[allow(non_snake_case)]
 pub async fn entity__search(
     query: String,
     limit: Option<usize>,
+    class: Option<String>,
+    kind: Option<String>,
+    min_tx: Option<i64>,
     executor: State<'_, DbExecutor>,
-) -> Result<String, String> {
+) -> Result<Vec<SearchResult>, String> {
+    let kind = match kind.as_deref() {
+        Some(kind) => Some(EntityKind::parse(kind).ok_or_else(|| format!("Unknown entity kind: {}", kind))?),
+        None => None,
+    };
+    // Members of a class are individuals
+    let kind = match (&class, kind) {
+        (Some(_), None | Some(EntityKind::Individual)) => Some(EntityKind::Individual),
+        (Some(_), Some(kind)) => return Err(format!("A class filter finds individuals, not {:?}", kind).to_lowercase()),
+        (None, kind) => kind,
+    };
+    let wants = move |wanted: EntityKind| kind.is_none_or(|kind| kind == wanted);
+
     // Use EAVTO executor for async read (won't block UI)
-    executor.read(move |conn| {
+    executor.read(min_tx, move |conn| {
         let limit = limit.unwrap_or(100);
         let mut results = Vec::new();
+        let push = |results: &mut Vec<SearchResult>, found: Vec<crate::owl::SearchResult>, entity_type: &str| {
+            results.extend(found.into_iter().map(|result| SearchResult {
+                id: result.id,
+                label: result.label,
+                icon: result.icon,
+                entity_type: entity_type.to_string(),
+                type_iri: result.type_iri,
+                snippet: None,
+                attached_to: None,
+            }));
+        };
 
         // Search classes using OWL abstraction
-        let class_results = crate::owl::search_classes(conn, &query, limit)
-            .map_err(|e| e.to_string())?;
+        if wants(EntityKind::Class) {
+            push(&mut results, crate::owl::search_classes(conn, &query, limit).map_err(|e| e.to_string())?, "class");
+        }
 
-        for class_result in class_results {
-            results.push(SearchResult {
-                id: class_result.id,
-                label: class_result.label,
-                icon: class_result.icon,
-                entity_type: "class".to_string(),
-            });
+        // Search properties
+        let remaining_limit = limit.saturating_sub(results.len());
+        if remaining_limit > 0 && wants(EntityKind::Property) {
+            push(&mut results, crate::owl::search_properties(conn, &query, remaining_limit).map_err(|e| e.to_string())?, "property");
         }
 
         // Search individuals using OWL abstraction
         let remaining_limit = limit.saturating_sub(results.len());
-        if remaining_limit > 0 {
-            let individual_results = crate::owl::search_individuals(conn, &query, remaining_limit)
+        if remaining_limit > 0 && wants(EntityKind::Individual) {
+            let individual_results = crate::owl::search_individuals_of(conn, &query, remaining_limit, class.as_deref())
                 .map_err(|e| e.to_string())?;
+            push(&mut results, individual_results, "individual");
+        }
 
-            for individual_result in individual_results {
+        // Search the text of attachments
+        let remaining_limit = limit.saturating_sub(results.len());
+        if remaining_limit > 0 && kind.is_none() {
+            let attachment_hits = crate::owl::attachment::search_attachments(conn, &query, remaining_limit)
+                .map_err(|e| e.to_string())?;
+
+            for hit in attachment_hits {
                 results.push(SearchResult {
-                    id: individual_result.id,
-                    label: individual_result.label,
-                    icon: individual_result.icon,
-                    entity_type: "individual".to_string(),
+                    id: hit.iri,
+                    label: hit.label,
+                    icon: Some("attach_file".to_string()),
+                    entity_type: "attachment".to_string(),
+                    type_iri: None,
+                    snippet: Some(hit.snippet),
+                    attached_to: hit.attached_to,
                 });
             }
         }
@@ -132,7 +194,7 @@ pub async fn entity__search(
         // Limit total results
         results.truncate(limit);
 
-        serde_json::to_string(&results).map_err(|e| e.to_string())
+        Ok(results)
     }).await
 }
 
@@ -141,22 +203,150 @@ pub async fn entity__search(
 #[allow(non_snake_case)]
 pub async fn entity__get(
     entity_id: String,
+    min_tx: Option<i64>,
     executor: State<'_, DbExecutor>,
-) -> Result<String, String> {
+) -> Result<EntityData, String> {
     // Use EAVTO executor for async read (won't block UI)
-    executor.read(move |conn| {
-        // Determine entity type by checking what it is
-        let entity_type = determine_entity_type(conn, &entity_id)?;
+    executor.read(min_tx, move |conn| load_entity(conn, &entity_id)).await
+}
 
-        let data = match entity_type {
-            EntityType::Class => get_class_data(conn, &entity_id)?,
-            EntityType::Individual => get_individual_data(conn, &entity_id)?,
+/// A page of an entity's backlinks, most recent first
+#[derive(Debug, Serialize, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export)]
+pub struct BacklinksPage {
+    pub backlinks: Vec<PropertyValue>,
+    #[ts(type = "number")]
+    pub total: usize,
+}
+
+/// Get a page of the entities pointing to an entity (instances, for classes)
+#[tauri::command]
+#[allow(non_snake_case)]
+pub async fn entity__backlinks(
+    entity_id: String,
+    offset: Option<usize>,
+    limit: Option<usize>,
+    min_tx: Option<i64>,
+    executor: State<'_, DbExecutor>,
+) -> Result<BacklinksPage, String> {
+    executor.read(min_tx, move |conn| {
+        let backlinks = match determine_entity_type(conn, &entity_id)? {
+            EntityType::Class => Class::get(conn, &entity_id).map_err(|e| e.to_string())?.backlinks,
+            EntityType::Individual => Individual::get(conn, &entity_id).map_err(|e| e.to_string())?.backlinks,
         };
 
-        serde_json::to_string(&data).map_err(|e| e.to_string())
+        Ok(BacklinksPage {
+            backlinks: backlink_values(conn, &backlinks, offset.unwrap_or(0), limit.unwrap_or(BACKLINKS_PAGE)),
+            total: backlinks.len(),
+        })
+    }).await
+}
+
+/// A property value for entity__create
+#[derive(Debug, Clone, Deserialize, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export)]
+pub struct PropertyValueInput {
+    #[ts(type = "string | number | boolean")]
+    pub value: serde_json::Value, // Entity IRI for object properties
+    #[ts(optional)]
+    pub unit: Option<String>, // QUDT unit of a number; must be the property's unit
+    #[ts(optional)]
+    pub language: Option<String>,
+}
+
+/// Create an individual of a class with a label, icon and property values
+/// (property IRI -> value), validated against the properties the class
+/// declares; returns the new entity
+#[tauri::command]
+#[allow(non_snake_case)]
+pub async fn entity__create(
+    class_id: String,
+    label: String,
+    icon: String,
+    properties: Option<HashMap<String, PropertyValueInput>>,
+    dry_run: Option<bool>,
+    executor: State<'_, DbExecutor>,
+) -> Result<Committed<EntityData>, String> {
+    let values: Vec<PropertyInput> = properties.unwrap_or_default().into_iter()
+        .map(|(property, input)| PropertyInput {
+            property,
+            value: input.value,
+            unit: input.unit,
+            language: input.language,
+        })
+        .collect();
+
+    executor.write_or_preview(dry_run, move |conn| {
+        let individual = Individual::create(conn, &class_id, &label, &icon, &values, "user-edit")
+            .map_err(|e| format!("Failed to create {}: {}", label, e))?;
+        load_entity(conn, &individual.iri)
+    }).await
+}
+
+/// Set a property of an entity, typed by the property's range (or by
+/// `datatype` when it declares none); a functional property's current value
+/// is replaced. Returns the updated entity.
+#[tauri::command]
+#[allow(non_snake_case)]
+pub async fn entity__set_property(
+    entity_id: String,
+    property_iri: String,
+    value: String,
+    datatype: Option<String>,
+    dry_run: Option<bool>,
+    executor: State<'_, DbExecutor>,
+) -> Result<Committed<EntityData>, String> {
+    executor.write_or_preview(dry_run, move |conn| {
+        Individual::new(entity_id.as_str())
+            .set_property(conn, &property_iri, &value, datatype.as_deref(), "user-edit")
+            .map_err(|e| format!("Failed to set {} of {}: {}", property_iri, entity_id, e))?;
+        load_entity(conn, &entity_id)
     }).await
 }
 
+/// What entity__retract removed
+#[derive(Debug, Serialize, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export)]
+pub struct EntityRetraction {
+    #[ts(type = "number")]
+    pub triples: usize,
+    #[ts(type = "number")]
+    pub backlinks: usize,
+}
+
+/// Delete an entity: retract every triple about it and, with
+/// include_backlinks, the triples of other entities pointing to it, as one
+/// transaction (undoable with transaction__undo)
+#[tauri::command]
+#[allow(non_snake_case)]
+pub async fn entity__retract(
+    entity_id: String,
+    include_backlinks: Option<bool>,
+    dry_run: Option<bool>,
+    executor: State<'_, DbExecutor>,
+) -> Result<Committed<EntityRetraction>, String> {
+    executor.write_or_preview(dry_run, move |conn| {
+        let retraction = Individual::new(entity_id.as_str())
+            .retract(conn, include_backlinks.unwrap_or(false), "user-edit")
+            .map_err(|e| format!("Failed to retract {}: {}", entity_id, e))?;
+        Ok(EntityRetraction { triples: retraction.triples, backlinks: retraction.backlinks })
+    }).await
+}
+
+/// Entity data with its neighborhood (shared with sandbox previews)
+pub(crate) fn load_entity(conn: &Connection, entity_id: &str) -> Result<EntityData, String> {
+    // Determine entity type by checking what it is
+    let entity_type = determine_entity_type(conn, entity_id)?;
+
+    match entity_type {
+        EntityType::Class => get_class_data(conn, entity_id),
+        EntityType::Individual => get_individual_data(conn, entity_id),
+    }
+}
+
 fn determine_entity_type(conn: &Connection, entity_id: &str) -> Result<EntityType, String> {
     // Check if it's a class (has rdf:type owl:Class)
     let class = Class::new(entity_id);
@@ -170,6 +360,12 @@ fn determine_entity_type(conn: &Connection, entity_id: &str) -> Result<EntityTyp
         return Ok(EntityType::Individual);
     }
 
+    // An IRI only known through owl:sameAs stands for the individual it aliases
+    let same_as = crate::owl::same_as(conn, entity_id).map_err(|e| e.to_string())?;
+    if same_as.len() > 1 {
+        return Ok(EntityType::Individual);
+    }
+
     Err(format!("Entity {} not found or unknown type", entity_id))
 }
 
@@ -316,6 +512,7 @@ fn get_class_data(conn: &Connection, class_id: &str) -> Result<EntityData, Strin
             source_class_label: None,
             unit: None,
             unit_label: None,
+            constraints: Vec::new(),
         });
     }
 
@@ -333,10 +530,20 @@ fn get_class_data(conn: &Connection, class_id: &str) -> Result<EntityData, Strin
             source_class_label: None,
             unit: None,
             unit_label: None,
+            constraints: Vec::new(),
         });
     }
 
-    for (property_iri, source_class_iri) in &class.properties {
+    // Restricted properties the class doesn't otherwise declare are listed too
+    let mut restricted: Vec<(String, String)> = Vec::new();
+    for restriction in &class.restrictions {
+        let listed = class.properties.iter().chain(&restricted).any(|(p, _)| *p == restriction.property);
+        if !listed {
+            restricted.push((restriction.property.clone(), restriction.source_class.clone()));
+        }
+    }
+
+    for (property_iri, source_class_iri) in class.properties.iter().chain(&restricted) {
         // Get property data using OWL abstraction
         let prop = Property::get(conn, property_iri)
             .map_err(|e| e.to_string())?;
@@ -396,49 +603,31 @@ fn get_class_data(conn: &Connection, class_id: &str) -> Result<EntityData, Strin
             source_class_label,
             unit,
             unit_label,
+            constraints: class.restrictions.iter()
+                .filter(|r| r.property == *property_iri)
+                .map(|r| r.to_manchester())
+                .collect(),
         });
     }
 
     // Process backlinks (instances of this class)
-    let mut backlinks = Vec::new();
-    for (source_entity, property_iri, _value_obj) in &class.backlinks {
-        // Get property metadata
-        let prop_result = Property::get(conn, property_iri);
-        let (property_label, property_comment) = if let Ok(prop) = prop_result {
-            (prop.label.unwrap_or_else(|| property_iri.clone()), prop.comment)
-        } else {
-            (property_iri.clone(), None)
-        };
-
-        // Get source entity info
-        let source_thing = crate::owl::Thing::get(conn, source_entity);
-
-        backlinks.push(PropertyValue {
-            property: property_iri.clone(),
-            property_label,
-            property_comment,
-            value: source_entity.clone(),
-            value_label: Some(source_thing.label),
-            value_icon: source_thing.icon,
-            is_object_property: true,
-            source_class: None,
-            source_class_label: None,
-            unit: None,
-            unit_label: None,
-        });
-    }
+    let backlinks = backlink_values(conn, &class.backlinks, 0, BACKLINKS_PAGE);
 
     Ok(EntityData {
         id: class_id.to_string(),
         label,
         icon,
         comment,
+        same_as: vec![],
         types: class.types.clone(),
         super_classes: class.super_classes.clone(),
         sub_classes: class.sub_classes.clone(),
         instances: vec![],
+        union_of: class.union_of.iter().map(crate::owl::manchester::render).collect(),
+        intersection_of: class.intersection_of.iter().map(crate::owl::manchester::render).collect(),
         properties,
         backlinks,
+        backlinks_total: class.backlinks.len(),
         nodes,
         links,
     })
@@ -448,6 +637,9 @@ fn get_individual_data(conn: &Connection, individual_id: &str) -> Result<EntityD
     // Get complete individual data using OWL abstraction
     let individual = Individual::get(conn, individual_id)
         .map_err(|e| e.to_string())?;
+    // Shown under its canonical IRI when it has owl:sameAs aliases
+    let individual_id = individual.iri.as_str();
+    let members: Vec<&str> = std::iter::once(individual_id).chain(individual.same_as.iter().map(String::as_str)).collect();
 
     let label = individual.label.unwrap_or_else(|| individual_id.to_string());
     let icon = individual.icon;
@@ -526,6 +718,7 @@ fn get_individual_data(conn: &Connection, individual_id: &str) -> Result<EntityD
             source_class_label: None,
             unit,
             unit_label,
+            constraints: Vec::new(),
         });
     }
 
@@ -622,16 +815,18 @@ fn get_individual_data(conn: &Connection, individual_id: &str) -> Result<EntityD
         }
     }
 
-    // Add related individuals via incoming ObjectProperties (backlinks)
+    // Add related individuals via incoming ObjectProperties (backlinks, most recent page)
     // Still need raw query for reverse lookups
-    let backlink_query = "SELECT subject, predicate
+    let backlink_query = format!("SELECT subject, predicate
                           FROM triples
-                          WHERE object = ? AND object_type = 'iri'
+                          WHERE object IN ({}) AND object_type = 'iri'
                           AND predicate != 'rdf:type'
-                          AND retracted = 0";
+                          AND {}
+                          ORDER BY tx DESC
+                          LIMIT {}", vec!["?"; members.len()].join(", "), query::visible("triples"), BACKLINKS_PAGE);
 
-    let mut stmt = conn.prepare(backlink_query).map_err(|e| e.to_string())?;
-    let backlink_rows = stmt.query_map([individual_id], |row| {
+    let mut stmt = conn.prepare(&backlink_query).map_err(|e| e.to_string())?;
+    let backlink_rows = stmt.query_map(rusqlite::params_from_iter(&members), |row| {
         Ok((
             row.get::<_, String>(0)?,
             row.get::<_, String>(1)?,
@@ -640,6 +835,9 @@ fn get_individual_data(conn: &Connection, individual_id: &str) -> Result<EntityD
 
     for row in backlink_rows {
         let (subject, predicate_iri) = row.map_err(|e| e.to_string())?;
+        if !individual.backlinks.iter().any(|(source, property, _)| *source == subject && *property == predicate_iri) {
+            continue; // owl:sameAs between aliases, or a link shown as an inverse property
+        }
 
         if !added_node_ids.contains(&subject) {
             let subject_thing = crate::owl::Thing::get(conn, &subject);
@@ -670,8 +868,41 @@ fn get_individual_data(conn: &Connection, individual_id: &str) -> Result<EntityD
     }
 
     // Process backlinks
-    let mut backlinks = Vec::new();
-    for (source_entity, property_iri, _value_obj) in &individual.backlinks {
+    let backlinks = backlink_values(conn, &individual.backlinks, 0, BACKLINKS_PAGE);
+
+    Ok(EntityData {
+        id: individual_id.to_string(),
+        label,
+        icon,
+        comment,
+        same_as: individual.same_as.clone(),
+        types: individual.types.clone(),
+        super_classes: vec![],
+        sub_classes: vec![],
+        instances: vec![],
+        union_of: vec![],
+        intersection_of: vec![],
+        properties,
+        backlinks,
+        backlinks_total: individual.backlinks.len(),
+        nodes,
+        links,
+    })
+}
+
+/// Display values of a page of backlinks (source entity, property, value)
+fn backlink_values(
+    conn: &Connection,
+    backlinks: &[(String, String, crate::owl::Object)],
+    offset: usize,
+    limit: usize,
+) -> Vec<PropertyValue> {
+    let page: Vec<_> = backlinks.iter().skip(offset).take(limit).collect();
+    let sources: Vec<&str> = page.iter().map(|(source_entity, _, _)| source_entity.as_str()).collect();
+    let source_things = crate::owl::Thing::get_many(conn, &sources);
+
+    let mut values = Vec::new();
+    for ((source_entity, property_iri, _value_obj), source_thing) in page.into_iter().zip(source_things) {
         // Get property metadata
         let prop_result = Property::get(conn, property_iri);
         let (property_label, property_comment) = if let Ok(prop) = prop_result {
@@ -680,10 +911,7 @@ fn get_individual_data(conn: &Connection, individual_id: &str) -> Result<EntityD
             (property_iri.clone(), None)
         };
 
-        // Get source entity info
-        let source_thing = crate::owl::Thing::get(conn, source_entity);
-
-        backlinks.push(PropertyValue {
+        values.push(PropertyValue {
             property: property_iri.clone(),
             property_label,
             property_comment,
@@ -695,21 +923,8 @@ fn get_individual_data(conn: &Connection, individual_id: &str) -> Result<EntityD
             source_class_label: None,
             unit: None,
             unit_label: None,
+            constraints: Vec::new(),
         });
     }
-
-    Ok(EntityData {
-        id: individual_id.to_string(),
-        label,
-        icon,
-        comment,
-        types: individual.types.clone(),
-        super_classes: vec![],
-        sub_classes: vec![],
-        instances: vec![],
-        properties,
-        backlinks,
-        nodes,
-        links,
-    })
-}
\ No newline at end of file
+    values
+}
@@ -0,0 +1,181 @@
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+use tauri::State;
+use std::path::Path;
+
+use crate::eavto::DbExecutor;
+use crate::export::{self, GraphMode, OntologyMetadata, ResultFormat, SnapshotFormat};
+
+/// Header metadata for an exported ontology (all optional)
+#[derive(Debug, Clone, Default, Deserialize, TS)]
+#[serde(rename_all = "camelCase", default)]
+#[ts(export)]
+pub struct OntologyMetadataInput {
+    #[ts(optional)]
+    pub iri: Option<String>,
+    #[ts(optional)]
+    pub version_iri: Option<String>,
+    #[ts(optional)]
+    pub version_info: Option<String>,
+    #[ts(optional)]
+    pub title: Option<String>,
+    #[ts(optional)]
+    pub description: Option<String>,
+    pub creators: Vec<String>,
+    #[ts(optional)]
+    pub license: Option<String>,
+    pub imports: Vec<String>,
+}
+
+impl From<OntologyMetadataInput> for OntologyMetadata {
+    fn from(input: OntologyMetadataInput) -> Self {
+        Self {
+            iri: input.iri,
+            version_iri: input.version_iri,
+            version_info: input.version_info,
+            title: input.title,
+            description: input.description,
+            creators: input.creators,
+            license: input.license,
+            imports: input.imports,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export)]
+pub struct OntologyExport {
+    pub iri: String,
+    pub content: String,
+    pub external_terms: Vec<String>, // Used but defined neither in the file nor the core ontology
+}
+
+/// Export an origin as a standalone Turtle ontology file, ready to share
+/// metadata: header fields; missing ones come from the origin's own owl:Ontology header
+#[tauri::command]
+#[allow(non_snake_case)]
+pub async fn export__origin_as_ontology(
+    origin: String,
+    metadata: Option<OntologyMetadataInput>,
+    min_tx: Option<i64>,
+    executor: State<'_, DbExecutor>,
+) -> Result<OntologyExport, String> {
+    let metadata = OntologyMetadata::from(metadata.unwrap_or_default());
+
+    executor.read(min_tx, move |conn| {
+        let file = export::export_origin_as_ontology(conn, &origin, &metadata)
+            .map_err(|e| format!("Failed to export {}: {}", origin, e))?;
+
+        Ok(OntologyExport {
+            iri: file.iri,
+            content: file.content,
+            external_terms: file.external_terms,
+        })
+    }).await
+}
+
+#[derive(Debug, Serialize, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export)]
+pub struct QueryResultsExport {
+    pub path: String,
+    pub columns: Vec<String>,
+    #[ts(type = "number")]
+    pub rows: usize,
+}
+
+/// Export the results of a pattern query to a CSV or JSON file
+/// format: "csv" | "json"
+#[tauri::command]
+#[allow(non_snake_case)]
+pub async fn export__query_results(
+    query: String,
+    format: String,
+    path: String,
+    min_tx: Option<i64>,
+    executor: State<'_, DbExecutor>,
+) -> Result<QueryResultsExport, String> {
+    let format = ResultFormat::from_name(&format)
+        .ok_or_else(|| format!("Unsupported format: {} (expected csv or json)", format))?;
+
+    executor.read(min_tx, move |conn| {
+        let export = export::export_query_results(conn, &query, format, Path::new(&path))
+            .map_err(|e| format!("Failed to export results to {}: {}", path, e))?;
+
+        Ok(QueryResultsExport { path, columns: export.columns, rows: export.rows })
+    }).await
+}
+
+#[derive(Debug, Serialize, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export)]
+pub struct NQuadsExport {
+    pub content: String,
+    #[ts(type = "number")]
+    pub quads: usize,
+    pub graphs: Vec<String>, // Named graph IRIs, one per origin or transaction
+}
+
+/// Export current triples as N-Quads, each in a named graph for its origin,
+/// so provenance survives in other triple stores
+/// origin: limit the export to one origin; all triples otherwise
+/// graph: "origin" (default) | "transaction" | "stored", what the named graphs stand for
+#[tauri::command]
+#[allow(non_snake_case)]
+pub async fn export__nquads(
+    origin: Option<String>,
+    graph: Option<String>,
+    min_tx: Option<i64>,
+    executor: State<'_, DbExecutor>,
+) -> Result<NQuadsExport, String> {
+    let mode = match graph {
+        Some(graph) => GraphMode::from_name(&graph)
+            .ok_or_else(|| format!("Unsupported graph: {} (expected origin, transaction or stored)", graph))?,
+        None => GraphMode::Origin,
+    };
+
+    executor.read(min_tx, move |conn| {
+        let export = export::export_nquads(conn, origin.as_deref(), mode)
+            .map_err(|e| format!("Failed to export N-Quads: {}", e))?;
+
+        Ok(NQuadsExport { content: export.content, quads: export.quads, graphs: export.graphs })
+    }).await
+}
+
+#[derive(Debug, Serialize, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export)]
+pub struct SnapshotExport {
+    pub path: String,
+    #[ts(type = "number")]
+    pub tx: i64,
+    #[ts(type = "number")]
+    pub triples: usize, // Current as of the transaction
+}
+
+/// Write the store as it was right after a transaction to a new file
+/// format: "sqlite" (default, a database with the history up to the
+/// transaction) | "turtle" (the triples current then)
+#[tauri::command]
+#[allow(non_snake_case)]
+pub async fn export__snapshot(
+    tx_id: i64,
+    path: String,
+    format: Option<String>,
+    min_tx: Option<i64>,
+    executor: State<'_, DbExecutor>,
+) -> Result<SnapshotExport, String> {
+    let format = match format {
+        Some(format) => SnapshotFormat::from_name(&format)
+            .ok_or_else(|| format!("Unsupported format: {} (expected sqlite or turtle)", format))?,
+        None => SnapshotFormat::Sqlite,
+    };
+
+    executor.read(min_tx, move |conn| {
+        let export = export::export_snapshot(conn, tx_id, Path::new(&path), format)
+            .map_err(|e| format!("Failed to export snapshot to {}: {}", path, e))?;
+
+        Ok(SnapshotExport { path, tx: export.tx, triples: export.triples })
+    }).await
+}
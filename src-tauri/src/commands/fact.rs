@@ -0,0 +1,102 @@
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+use tauri::State;
+
+use crate::eavto::{Committed, DbExecutor, QuotedTriple, Triple};
+use crate::owl::fact::{self, FactAnnotations};
+use super::triple::TripleData;
+
+/// What is said about a fact: confidence (0 to 1), a note, and the IRIs
+/// of its sources (prov:wasDerivedFrom)
+#[derive(Debug, Clone, Default, Serialize, Deserialize, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export)]
+pub struct FactAnnotationsData {
+    pub confidence: Option<f64>,
+    pub note: Option<String>,
+    #[serde(default)]
+    pub derived_from: Vec<String>,
+}
+
+impl From<FactAnnotations> for FactAnnotationsData {
+    fn from(annotations: FactAnnotations) -> Self {
+        Self {
+            confidence: annotations.confidence,
+            note: annotations.note,
+            derived_from: annotations.derived_from,
+        }
+    }
+}
+
+impl From<FactAnnotationsData> for FactAnnotations {
+    fn from(data: FactAnnotationsData) -> Self {
+        Self {
+            confidence: data.confidence,
+            note: data.note,
+            derived_from: data.derived_from,
+        }
+    }
+}
+
+/// A fact and what is said about it
+#[derive(Debug, Serialize, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export)]
+pub struct AnnotatedFact {
+    pub fact: TripleData,
+    pub annotations: FactAnnotationsData,
+}
+
+/// Annotations of a fact (the fact needn't be asserted)
+#[tauri::command]
+#[allow(non_snake_case)]
+pub async fn fact__annotations(
+    fact: TripleData,
+    min_tx: Option<i64>,
+    executor: State<'_, DbExecutor>,
+) -> Result<FactAnnotationsData, String> {
+    executor.read(min_tx, move |conn| {
+        fact::annotations(conn, &QuotedTriple::of(&fact.to_triple()))
+            .map(Into::into)
+            .map_err(|e| e.to_string())
+    }).await
+}
+
+/// Facts about an entity that have annotations, with them
+#[tauri::command]
+#[allow(non_snake_case)]
+pub async fn fact__annotated(
+    entity_iri: String,
+    min_tx: Option<i64>,
+    executor: State<'_, DbExecutor>,
+) -> Result<Vec<AnnotatedFact>, String> {
+    executor.read(min_tx, move |conn| {
+        Ok(fact::annotated_facts(conn, &entity_iri)
+            .map_err(|e| e.to_string())?
+            .into_iter()
+            .map(|(quoted, annotations)| AnnotatedFact {
+                fact: TripleData::from(&Triple::new(quoted.subject, quoted.predicate, quoted.object)),
+                annotations: annotations.into(),
+            })
+            .collect())
+    }).await
+}
+
+/// Replace the annotations of a fact; empty annotations remove them
+/// Returns the transaction, or null if nothing changed
+#[tauri::command]
+#[allow(non_snake_case)]
+pub async fn fact__annotate(
+    fact: TripleData,
+    annotations: FactAnnotationsData,
+    dry_run: Option<bool>,
+    executor: State<'_, DbExecutor>,
+) -> Result<Committed<Option<i64>>, String> {
+    let fact = QuotedTriple::of(&fact.to_triple());
+    let annotations = FactAnnotations::from(annotations);
+
+    executor.write_or_preview(dry_run, move |conn| {
+        fact::annotate(conn, &fact, &annotations, "user-edit")
+            .map_err(|e| format!("Failed to annotate fact: {}", e))
+    }).await
+}
@@ -0,0 +1,80 @@
+use serde::Serialize;
+use ts_rs::TS;
+use tauri::State;
+
+use crate::eavto::{Committed, DbExecutor};
+use crate::owl::federation::{self, FederatedResult};
+use super::triple::TripleData;
+
+/// Triples a public SPARQL endpoint returned for a query
+#[derive(Debug, Serialize, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export)]
+pub struct FederatedQueryResult {
+    pub origin: String, // Origin holding the cached triples
+    pub triples: Vec<TripleData>,
+    pub cached: bool,
+    #[ts(type = "number")]
+    pub fetched_at: i64,
+    pub status: String, // "quarantined" until the user approves the origin, then "active"
+}
+
+impl From<FederatedResult> for FederatedQueryResult {
+    fn from(result: FederatedResult) -> Self {
+        Self {
+            triples: result.triples.iter().map(Into::into).collect(),
+            origin: result.origin,
+            cached: result.cached,
+            fetched_at: result.fetched_at,
+            status: result.status.as_str().to_string(),
+        }
+    }
+}
+
+/// Run a CONSTRUCT or DESCRIBE query against a public SPARQL endpoint
+/// (e.g. https://query.wikidata.org/sparql), caching the triples it returns
+/// in a quarantined origin
+/// cache_ttl: seconds a fetched result is reused without asking the endpoint
+/// (default: one day)
+/// When the endpoint can't be reached, the query is queued in the outbox and
+/// its results are cached once it answers
+#[tauri::command]
+#[allow(non_snake_case)]
+pub async fn federated__query(
+    endpoint: String,
+    query: String,
+    cache_ttl: Option<i64>,
+    dry_run: Option<bool>,
+    executor: State<'_, DbExecutor>,
+) -> Result<Committed<FederatedQueryResult>, String> {
+    let cache_ttl = cache_ttl.unwrap_or(federation::DEFAULT_CACHE_TTL);
+    let (cache_endpoint, cache_query) = (endpoint.clone(), query.clone());
+    let cached = executor.read(None, move |conn| {
+        federation::cached(conn, &cache_endpoint, &cache_query, cache_ttl).map_err(|e| e.to_string())
+    }).await?;
+    if let Some(result) = cached {
+        return Ok(Committed { tx: executor.committed_tx(), value: result.into(), dry_run: None });
+    }
+
+    let (fetch_endpoint, fetch_query) = (endpoint.clone(), query.clone());
+    let fetched = tokio::task::spawn_blocking(move || federation::fetch(&fetch_endpoint, &fetch_query))
+        .await
+        .map_err(|e| e.to_string())?;
+    let body = match fetched {
+        Ok(body) => body,
+        Err(e) => {
+            // Offline or unreachable: fetch in the background once it answers
+            let (queue_endpoint, queue_query) = (endpoint.clone(), query.clone());
+            let job = executor.write(move |conn| {
+                federation::queue(conn, &queue_endpoint, &queue_query).map_err(|e| e.to_string())
+            }).await?.value;
+            return Err(format!("{} (queued as outbox job {})", e, job));
+        }
+    };
+
+    executor.write_or_preview(dry_run, move |conn| {
+        federation::store_results(conn, &endpoint, &query, &body)
+            .map(Into::into)
+            .map_err(|e| format!("Failed to cache results from {}: {}", endpoint, e))
+    }).await
+}
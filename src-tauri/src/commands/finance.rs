@@ -0,0 +1,109 @@
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+use tauri::State;
+use std::path::PathBuf;
+
+use crate::eavto::{Committed, DbExecutor};
+use crate::owl::finance::{self, FinanceImport, ImportProfile, StatementFormat};
+
+/// How finance__import reads a statement (all optional; CSV columns are
+/// matched against the header row, case-insensitively)
+#[derive(Debug, Clone, Default, Deserialize, TS)]
+#[serde(rename_all = "camelCase", default)]
+#[ts(export)]
+pub struct FinanceImportProfile {
+    #[ts(optional)]
+    pub format: Option<String>, // "ofx" | "csv" (default: from the file extension)
+    #[ts(optional)]
+    pub currency: Option<String>, // ISO 4217 code, when the file has none
+    #[ts(optional)]
+    pub account: Option<String>,
+    #[ts(optional)]
+    pub delimiter: Option<char>,
+    #[ts(optional)]
+    pub date_column: Option<String>,
+    #[ts(optional)]
+    pub date_format: Option<String>,
+    #[ts(optional)]
+    pub amount_column: Option<String>,
+    #[ts(optional)]
+    pub decimal_comma: Option<bool>,
+    #[ts(optional)]
+    pub locale: Option<String>, // BCP 47 tag ("pt-BR"): parse numbers and dates as written there
+    #[ts(optional)]
+    pub description_column: Option<String>,
+    #[ts(optional)]
+    pub reference_column: Option<String>,
+    #[ts(optional)]
+    pub counterparty_column: Option<String>,
+}
+
+impl TryFrom<FinanceImportProfile> for ImportProfile {
+    type Error = String;
+
+    fn try_from(input: FinanceImportProfile) -> Result<Self, String> {
+        let defaults = ImportProfile::default();
+        let format = match input.format.as_deref().map(str::to_ascii_lowercase).as_deref() {
+            None => None,
+            Some("ofx") => Some(StatementFormat::Ofx),
+            Some("csv") => Some(StatementFormat::Csv),
+            Some(other) => return Err(format!("Unsupported format: {} (expected ofx or csv)", other)),
+        };
+
+        Ok(Self {
+            format,
+            currency: input.currency,
+            account: input.account,
+            delimiter: input.delimiter.unwrap_or(defaults.delimiter),
+            date_column: input.date_column.unwrap_or(defaults.date_column),
+            date_format: input.date_format.unwrap_or(defaults.date_format),
+            amount_column: input.amount_column.unwrap_or(defaults.amount_column),
+            decimal_comma: input.decimal_comma.unwrap_or(defaults.decimal_comma),
+            locale: input.locale,
+            description_column: input.description_column.unwrap_or(defaults.description_column),
+            reference_column: input.reference_column.unwrap_or(defaults.reference_column),
+            counterparty_column: input.counterparty_column.unwrap_or(defaults.counterparty_column),
+        })
+    }
+}
+
+#[derive(Debug, Serialize, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export)]
+pub struct FinanceImportReport {
+    #[ts(type = "number")]
+    pub imported: usize,
+    #[ts(type = "number")]
+    pub duplicates: usize,
+    #[ts(type = "number")]
+    pub organizations: usize,
+}
+
+impl From<FinanceImport> for FinanceImportReport {
+    fn from(report: FinanceImport) -> Self {
+        Self {
+            imported: report.imported,
+            duplicates: report.duplicates,
+            organizations: report.organizations,
+        }
+    }
+}
+
+/// Import a bank statement (OFX or CSV) as FinancialTransaction entities
+/// Transactions already imported (same date, amount and reference) are skipped
+#[tauri::command]
+#[allow(non_snake_case)]
+pub async fn finance__import(
+    path: String,
+    profile: Option<FinanceImportProfile>,
+    dry_run: Option<bool>,
+    executor: State<'_, DbExecutor>,
+) -> Result<Committed<FinanceImportReport>, String> {
+    let profile = ImportProfile::try_from(profile.unwrap_or_default())?;
+
+    executor.write_or_preview(dry_run, move |conn| {
+        finance::import_statement(conn, &PathBuf::from(&path), &profile)
+            .map(Into::into)
+            .map_err(|e| format!("Failed to import {}: {}", path, e))
+    }).await
+}
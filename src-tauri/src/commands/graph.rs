@@ -0,0 +1,132 @@
+use rusqlite::Connection;
+use serde::Serialize;
+use ts_rs::TS;
+use tauri::State;
+
+use crate::eavto::DbExecutor;
+use crate::owl::{neighborhood, path, Class, Thing};
+use super::entity::{GraphLink, GraphNode};
+
+/// Links followed from the entity when the caller gives no depth
+const DEFAULT_DEPTH: usize = 1;
+
+/// Nodes returned at most when the caller gives no limit
+const DEFAULT_MAX_NODES: usize = 200;
+
+/// Links a path may have when the caller gives no maximum
+const DEFAULT_PATH_DEPTH: usize = 6;
+
+/// The graph around an entity
+#[derive(Debug, Serialize, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export)]
+pub struct GraphNeighborhood {
+    pub nodes: Vec<GraphNode>, // The entity first, then by distance
+    pub links: Vec<GraphLink>, // Labeled with the predicate's label
+    pub truncated: bool,       // max_nodes stopped the traversal: expand further nodes to see more
+}
+
+/// How two entities are related
+#[derive(Debug, Serialize, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export)]
+pub struct GraphPath {
+    pub nodes: Vec<GraphNode>, // From the first entity to the second
+    pub links: Vec<GraphLink>, // links[i] joins nodes[i] and nodes[i + 1], source → target as stated
+}
+
+/// The entities within `depth` links of an entity, in either direction,
+/// and the links between them
+/// predicates: only follow these (e.g. ["rdfs:subClassOf"] for a taxonomy)
+/// Expanding a node is another call from it, with depth 1
+#[tauri::command]
+#[allow(non_snake_case)]
+pub async fn graph__neighborhood(
+    entity_id: String,
+    depth: Option<usize>,
+    predicates: Option<Vec<String>>,
+    max_nodes: Option<usize>,
+    min_tx: Option<i64>,
+    executor: State<'_, DbExecutor>,
+) -> Result<GraphNeighborhood, String> {
+    executor.read(min_tx, move |conn| {
+        let graph = neighborhood::neighborhood(
+            conn,
+            &entity_id,
+            depth.unwrap_or(DEFAULT_DEPTH),
+            predicates.as_deref(),
+            max_nodes.unwrap_or(DEFAULT_MAX_NODES),
+        ).map_err(|e| format!("Failed to traverse the graph around {}: {}", entity_id, e))?;
+
+        Ok(GraphNeighborhood {
+            links: graph_links(conn, &graph.links),
+            nodes: graph.nodes.into_iter()
+                .map(|node| graph_node(node.thing, node.is_class))
+                .collect(),
+            truncated: graph.truncated,
+        })
+    }).await
+}
+
+/// The shortest chain of links between two entities, followed in either
+/// direction, or None if they're more than `max_depth` links apart
+/// Paths don't pass through RDF, RDFS, OWL or XSD terms (e.g. owl:Class)
+#[tauri::command]
+#[allow(non_snake_case)]
+pub async fn graph__find_path(
+    from_iri: String,
+    to_iri: String,
+    max_depth: Option<usize>,
+    min_tx: Option<i64>,
+    executor: State<'_, DbExecutor>,
+) -> Result<Option<GraphPath>, String> {
+    executor.read(min_tx, move |conn| {
+        let found = path::find_path(conn, &from_iri, &to_iri, max_depth.unwrap_or(DEFAULT_PATH_DEPTH))
+            .map_err(|e| format!("Failed to find a path from {} to {}: {}", from_iri, to_iri, e))?;
+        let Some(found) = found else {
+            return Ok(None);
+        };
+
+        let iris: Vec<&str> = found.nodes.iter().map(String::as_str).collect();
+        let nodes = Thing::get_many(conn, &iris)
+            .into_iter()
+            .map(|thing| {
+                let is_class = Class::new(&thing.iri).exists(conn).map_err(|e| e.to_string())?;
+                Ok(graph_node(thing, is_class))
+            })
+            .collect::<Result<Vec<_>, String>>()?;
+
+        Ok(Some(GraphPath { nodes, links: graph_links(conn, &found.links) }))
+    }).await
+}
+
+/// Graph node of an entity
+fn graph_node(thing: Thing, is_class: bool) -> GraphNode {
+    GraphNode {
+        id: thing.iri,
+        label: thing.label,
+        icon: thing.icon,
+        group: if is_class { 1 } else { 6 },
+        is_broken_ref: None,
+        is_literal: None,
+    }
+}
+
+/// Graph links, labeled with their predicate's label
+fn graph_links(conn: &Connection, links: &[neighborhood::NeighborLink]) -> Vec<GraphLink> {
+    let mut predicates: Vec<&str> = links.iter().map(|link| link.predicate.as_str()).collect();
+    predicates.sort();
+    predicates.dedup();
+    let labels: std::collections::HashMap<String, String> = Thing::get_many(conn, &predicates)
+        .into_iter()
+        .map(|thing| (thing.iri, thing.label))
+        .collect();
+
+    links.iter()
+        .map(|link| GraphLink {
+            source: link.source.clone(),
+            target: link.target.clone(),
+            label: labels[&link.predicate].clone(),
+        })
+        .collect()
+}
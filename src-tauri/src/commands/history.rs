@@ -0,0 +1,217 @@
+use serde::Serialize;
+use ts_rs::TS;
+use tauri::State;
+
+use crate::eavto::{query, store, Committed, DbExecutor, Transaction};
+use crate::identity::{self, Verification};
+use crate::owl::{changelog, Thing};
+use super::triple::TripleData;
+
+/// Default number of transactions per history page
+const DEFAULT_FEED_LIMIT: usize = 50;
+
+/// Signature check of a transaction
+#[derive(Debug, Serialize, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export)]
+pub struct TransactionVerification {
+    #[ts(type = "number")]
+    pub tx: i64,
+    pub status: String,         // "valid", "invalid" (changed since signed) or "unsigned"
+    pub signer: Option<String>, // Public key (hex) of the signer
+}
+
+/// A transaction in the history feed
+#[derive(Debug, Serialize, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export)]
+pub struct HistoryEntry {
+    #[ts(type = "number")]
+    pub tx: i64,
+    pub origin: String,
+    #[ts(type = "number")]
+    pub created_at: i64, // Unix ms
+    #[ts(type = "number | null")]
+    pub received_at: Option<i64>,
+    pub message: Option<String>,
+    pub tags: Vec<String>,
+    #[ts(type = "number")]
+    pub assertions: i64, // Triples asserted (retractions are not counted)
+}
+
+impl HistoryEntry {
+    fn new(transaction: Transaction, assertions: i64) -> Self {
+        Self {
+            tx: transaction.tx,
+            origin: transaction.origin,
+            created_at: transaction.created_at,
+            received_at: transaction.received_at,
+            message: transaction.message,
+            tags: transaction.tags,
+            assertions,
+        }
+    }
+}
+
+/// Changes between two transactions
+#[derive(Debug, Serialize, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export)]
+pub struct TransactionDiff {
+    #[ts(type = "number")]
+    pub from_tx: i64,
+    #[ts(type = "number")]
+    pub to_tx: i64,
+    pub added: Vec<TripleData>,
+    pub retracted: Vec<TripleData>,
+}
+
+/// A value before or after a change
+#[derive(Debug, Serialize, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export)]
+pub struct ChangeValueData {
+    pub value: String, // IRI or literal value
+    pub label: String, // Label of an IRI, readable form of a literal
+}
+
+impl From<changelog::ChangeValue> for ChangeValueData {
+    fn from(value: changelog::ChangeValue) -> Self {
+        Self { value: value.value, label: value.label }
+    }
+}
+
+/// A change to a property of an entity, ready to show in its timeline
+#[derive(Debug, Serialize, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export)]
+pub struct EntityChange {
+    #[ts(type = "number")]
+    pub tx: i64,
+    #[ts(type = "number")]
+    pub created_at: i64, // Unix ms
+    pub origin: String,
+    pub message: Option<String>,
+    pub property: Thing,
+    pub kind: String, // "added", "removed" or "changed"
+    pub from: Option<ChangeValueData>,
+    pub to: Option<ChangeValueData>,
+    pub description: String, // e.g. "Label changed from Car to Sports car"
+}
+
+/// Recent transactions with their messages and tags, newest first
+/// before: only transactions older than this one (next page)
+/// tag: only transactions with this tag
+#[tauri::command]
+#[allow(non_snake_case)]
+pub async fn history__feed(
+    limit: Option<usize>,
+    before: Option<i64>,
+    tag: Option<String>,
+    min_tx: Option<i64>,
+    executor: State<'_, DbExecutor>,
+) -> Result<Vec<HistoryEntry>, String> {
+    let limit = limit.unwrap_or(DEFAULT_FEED_LIMIT);
+
+    executor.read(min_tx, move |conn| {
+        let transactions = query::get_transactions(conn, before, tag.as_deref(), limit)
+            .map_err(|e| format!("Failed to get history: {}", e))?;
+
+        transactions.into_iter()
+            .map(|transaction| {
+                let assertions = query::count_by_transaction(conn, transaction.tx)
+                    .map_err(|e| format!("Failed to count transaction {}: {}", transaction.tx, e))?;
+                Ok(HistoryEntry::new(transaction, assertions))
+            })
+            .collect()
+    }).await
+}
+
+/// Timeline of an entity: each change to its properties, newest first, with
+/// when and by which origin it was made
+#[tauri::command]
+#[allow(non_snake_case)]
+pub async fn entity__history(
+    entity_id: String,
+    min_tx: Option<i64>,
+    executor: State<'_, DbExecutor>,
+) -> Result<Vec<EntityChange>, String> {
+    executor.read(min_tx, move |conn| {
+        let changes = changelog::entity_history(conn, &entity_id)
+            .map_err(|e| format!("Failed to get history of {}: {}", entity_id, e))?;
+
+        Ok(changes.into_iter()
+            .map(|change| EntityChange {
+                tx: change.tx,
+                created_at: change.created_at,
+                origin: change.origin,
+                message: change.message,
+                property: change.property,
+                kind: change.kind.as_str().to_string(),
+                from: change.from.map(Into::into),
+                to: change.to.map(Into::into),
+                description: change.description,
+            })
+            .collect())
+    }).await
+}
+
+/// Triples added and retracted going from the state after from_tx to the
+/// state after to_tx (from_tx = tx - 1, to_tx = tx: the changes of one transaction)
+#[tauri::command]
+#[allow(non_snake_case)]
+pub async fn history__diff(
+    from_tx: i64,
+    to_tx: i64,
+    min_tx: Option<i64>,
+    executor: State<'_, DbExecutor>,
+) -> Result<TransactionDiff, String> {
+    executor.read(min_tx, move |conn| {
+        let (added, retracted) = query::diff_transactions(conn, from_tx, to_tx)
+            .map_err(|e| format!("Failed to diff transactions {} and {}: {}", from_tx, to_tx, e))?;
+
+        Ok(TransactionDiff {
+            from_tx,
+            to_tx,
+            added: added.iter().map(Into::into).collect(),
+            retracted: retracted.iter().map(Into::into).collect(),
+        })
+    }).await
+}
+
+/// Undo a transaction: retract what it asserted and assert again what it
+/// retracted, as a new transaction
+/// Returns the transaction ID of the undo
+#[tauri::command]
+#[allow(non_snake_case)]
+pub async fn transaction__undo(
+    tx: i64,
+    dry_run: Option<bool>,
+    executor: State<'_, DbExecutor>,
+) -> Result<Committed<i64>, String> {
+    executor.write_or_preview(dry_run, move |conn| {
+        store::undo_transaction(conn, tx, "user-edit")
+            .map_err(|e| format!("Failed to undo transaction {}: {}", tx, e))
+    }).await
+}
+
+/// Verify a transaction's signature: whether what it asserted is still what
+/// its signer signed, and who that is
+#[tauri::command]
+#[allow(non_snake_case)]
+pub async fn transaction__verify(
+    tx: i64,
+    min_tx: Option<i64>,
+    executor: State<'_, DbExecutor>,
+) -> Result<TransactionVerification, String> {
+    executor.read(min_tx, move |conn| {
+        let (status, signer) = match identity::verify_transaction(conn, tx)
+            .map_err(|e| format!("Failed to verify transaction {}: {}", tx, e))?
+        {
+            Verification::Valid { signer } => ("valid", Some(signer)),
+            Verification::Invalid { signer } => ("invalid", Some(signer)),
+            Verification::Unsigned => ("unsigned", None),
+        };
+        Ok(TransactionVerification { tx, status: status.to_string(), signer })
+    }).await
+}
@@ -0,0 +1,37 @@
+use serde::Serialize;
+use ts_rs::TS;
+use tauri::State;
+
+use crate::identity::Identity;
+
+/// A payload signed with the local identity
+#[derive(Debug, Serialize, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export)]
+pub struct SignedPayload {
+    pub public_key: String, // Hex, compressed SEC1
+    pub signature: String,  // Hex, ECDSA r || s over the SHA-256 of the payload
+}
+
+/// Public key of the local identity (secp256k1, hex), as published on
+/// foundation:ThisUser
+#[tauri::command]
+#[allow(non_snake_case)]
+pub async fn identity__get_public_key(
+    identity: State<'_, Identity>,
+) -> Result<String, String> {
+    Ok(identity.public_key())
+}
+
+/// Sign a payload (its UTF-8 bytes) with the local identity's private key
+#[tauri::command]
+#[allow(non_snake_case)]
+pub async fn identity__sign(
+    payload: String,
+    identity: State<'_, Identity>,
+) -> Result<SignedPayload, String> {
+    Ok(SignedPayload {
+        public_key: identity.public_key(),
+        signature: identity.sign(payload.as_bytes()),
+    })
+}
@@ -0,0 +1,292 @@
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+use tauri::{AppHandle, Emitter, State};
+use std::collections::{HashSet, VecDeque};
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use crate::eavto::{Committed, DbExecutor, merge::{self, MergeOptions, MergeReport}, skew::{self, OriginSkew}};
+use crate::turtle::{job::{self, ImportJob}, remote, ImportStats, RdfFormat};
+
+/// What import__foundation_db copies (default: all user origins, current triples)
+#[derive(Debug, Clone, Default, Deserialize, TS)]
+#[serde(rename_all = "camelCase", default)]
+#[ts(export)]
+pub struct FoundationDbImportOptions {
+    #[ts(optional)]
+    pub origins: Option<Vec<String>>,
+    #[ts(optional)]
+    pub entities: Option<Vec<String>>,
+    #[ts(optional)]
+    pub origin_prefix: Option<String>,
+    #[ts(optional)]
+    pub include_history: Option<bool>,
+    #[ts(optional)]
+    pub graph: Option<String>,
+}
+
+impl From<FoundationDbImportOptions> for MergeOptions {
+    fn from(options: FoundationDbImportOptions) -> Self {
+        Self {
+            origins: options.origins,
+            entities: options.entities,
+            origin_prefix: options.origin_prefix,
+            include_history: options.include_history.unwrap_or(false),
+            graph: options.graph,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export)]
+pub struct FoundationDbImportReport {
+    pub origins: Vec<String>,
+    #[ts(type = "number")]
+    pub transactions: i64,
+    #[ts(type = "number")]
+    pub copied: i64,
+    #[ts(type = "number")]
+    pub skipped: i64,
+}
+
+impl From<MergeReport> for FoundationDbImportReport {
+    fn from(report: MergeReport) -> Self {
+        Self {
+            origins: report.origins,
+            transactions: report.transactions,
+            copied: report.copied,
+            skipped: report.skipped,
+        }
+    }
+}
+
+/// What import__from_url did
+#[derive(Debug, Serialize, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export)]
+pub struct UrlImport {
+    pub url: String,
+    pub origin: String,
+    pub etag: Option<String>,
+    pub stats: Option<ImportStats>, // None: not modified since the last download, or failed
+    pub error: Option<String>,       // Set for ontologies it imports (owl:imports) that failed
+    pub dependencies: Vec<UrlImport>, // Ontologies it imports, resolved in turn (flat)
+}
+
+/// Clock skew of the transactions an origin received from another device
+#[derive(Debug, Serialize, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export)]
+pub struct ClockSkew {
+    pub origin: String,
+    #[ts(type = "number")]
+    pub transactions: i64,
+    #[ts(type = "number")]
+    pub ahead: i64, // Claimed after they were received: the device's clock runs fast
+    #[ts(type = "number")]
+    pub max_ahead_ms: i64,
+    #[ts(type = "number")]
+    pub first_claimed: i64, // Unix ms
+    #[ts(type = "number")]
+    pub last_claimed: i64,
+    #[ts(type = "number")]
+    pub last_received: i64,
+}
+
+impl From<OriginSkew> for ClockSkew {
+    fn from(skew: OriginSkew) -> Self {
+        Self {
+            origin: skew.origin,
+            transactions: skew.transactions,
+            ahead: skew.ahead,
+            max_ahead_ms: skew.max_ahead_ms,
+            first_claimed: skew.first_claimed,
+            last_claimed: skew.last_claimed,
+            last_received: skew.last_received,
+        }
+    }
+}
+
+/// Copy origins or entities from another FOUNDATION database file
+#[tauri::command]
+#[allow(non_snake_case)]
+pub async fn import__foundation_db(
+    path: String,
+    options: Option<FoundationDbImportOptions>,
+    dry_run: Option<bool>,
+    executor: State<'_, DbExecutor>,
+) -> Result<Committed<FoundationDbImportReport>, String> {
+    let options = MergeOptions::from(options.unwrap_or_default());
+
+    // The source is attached around the dry run, so it can't go through write_or_preview
+    if dry_run.unwrap_or(false) {
+        let previewed = executor.write(move |conn| {
+            merge::preview_db(conn, &PathBuf::from(&path), &options)
+                .map_err(|e| format!("Failed to preview import of {}: {}", path, e))
+        }).await?;
+        let (report, preview) = previewed.value;
+        return Ok(Committed { tx: previewed.tx, value: report.into(), dry_run: Some((&preview).into()) });
+    }
+
+    executor.write(move |conn| {
+        merge::import_db(conn, &PathBuf::from(&path), &options)
+            .map(Into::into)
+            .map_err(|e| format!("Failed to import {}: {}", path, e))
+    }).await
+}
+
+/// Download an ontology over HTTPS and import it
+/// format: "turtle", "ntriples", "rdfxml" or "jsonld" (default: from the
+/// response's Content-Type, then the URL)
+/// origin: default import:{url}
+/// resolve_imports: also import the ontologies it imports (owl:imports),
+/// and theirs, each into its own origin
+/// An ontology downloaded before is only imported again if it changed (ETag);
+/// its new version replaces the old one
+/// Each download is imported as a job (see ontology__import_file)
+#[tauri::command]
+#[allow(non_snake_case)]
+pub async fn import__from_url(
+    url: String,
+    format: Option<String>,
+    origin: Option<String>,
+    resolve_imports: Option<bool>,
+    dry_run: Option<bool>,
+    app: AppHandle,
+    executor: State<'_, DbExecutor>,
+) -> Result<Committed<UrlImport>, String> {
+    let format = format
+        .map(|name| RdfFormat::parse(&name).ok_or_else(|| format!("Unknown RDF format {}", name)))
+        .transpose()?;
+    let origin = origin.unwrap_or_else(|| format!("import:{}", url));
+
+    let mut imported = download_and_import(&app, &executor, url, format, origin.clone(), dry_run).await?;
+    if resolve_imports.unwrap_or(false) && imported.dry_run.is_none() {
+        imported.value.dependencies = resolve_dependencies(&app, &executor, origin).await?;
+        imported.tx = executor.committed_tx();
+    }
+    Ok(imported)
+}
+
+/// Import the ontologies an origin imports (owl:imports) that the store
+/// doesn't hold yet, and theirs, each from its IRI into its own origin
+#[tauri::command]
+#[allow(non_snake_case)]
+pub async fn import__resolve_imports(
+    origin: String,
+    app: AppHandle,
+    executor: State<'_, DbExecutor>,
+) -> Result<Committed<Vec<UrlImport>>, String> {
+    let dependencies = resolve_dependencies(&app, &executor, origin).await?;
+    Ok(Committed { tx: executor.committed_tx(), value: dependencies, dry_run: None })
+}
+
+/// Cancel an import job (its ID comes with its "import-progress" events);
+/// nothing it imported is kept. Returns false if the job already finished.
+#[tauri::command]
+#[allow(non_snake_case)]
+pub async fn import__cancel(job_id: u64) -> Result<bool, String> {
+    Ok(job::cancel(job_id))
+}
+
+/// Start an import job reporting its progress to the frontend
+/// ("import-progress" events)
+pub(crate) fn start_job(app: &AppHandle, name: &str, total_bytes: Option<u64>) -> Arc<ImportJob> {
+    let app = app.clone();
+    job::start(name, total_bytes, move |progress| {
+        let _ = app.emit("import-progress", progress);
+    })
+}
+
+/// Download an ontology outside the writer, then import it
+async fn download_and_import(
+    app: &AppHandle,
+    executor: &DbExecutor,
+    url: String,
+    format: Option<RdfFormat>,
+    origin: String,
+    dry_run: Option<bool>,
+) -> Result<Committed<UrlImport>, String> {
+    let etag_url = url.clone();
+    let etag = executor.read(None, move |conn| {
+        remote::stored_etag(conn, &etag_url).map_err(|e| format!("{:?}", e))
+    }).await?;
+
+    let download_url = url.clone();
+    let downloaded = tokio::task::spawn_blocking(move || remote::download(&download_url, format, etag.as_deref()))
+        .await
+        .map_err(|e| e.to_string())?
+        .map_err(|e| format!("{:?}", e))?;
+
+    let Some(download) = downloaded else {
+        let value = UrlImport { url, origin, etag: None, stats: None, error: None, dependencies: Vec::new() };
+        return Ok(Committed { tx: executor.committed_tx(), value, dry_run: None });
+    };
+
+    let size = std::fs::metadata(&download.path).ok().map(|metadata| metadata.len());
+    let job = start_job(app, &download.name, size);
+    executor.write_or_preview(dry_run, move |conn| {
+        let stats = remote::import(conn, &download, &origin, Some(&job))
+            .map_err(|e| format!("Failed to import {}: {:?}", download.url, e))?;
+        Ok(UrlImport {
+            url: download.url.clone(),
+            origin,
+            etag: download.etag.clone(),
+            stats: Some(stats),
+            error: None,
+            dependencies: Vec::new(),
+        })
+    }).await
+}
+
+/// Import what an origin imports, breadth first; an ontology is visited
+/// once, so import cycles end. Failures are reported, not fatal.
+async fn resolve_dependencies(app: &AppHandle, executor: &DbExecutor, origin: String) -> Result<Vec<UrlImport>, String> {
+    let mut visited = HashSet::from([origin.clone()]);
+    let mut queue = VecDeque::from([origin]);
+    let mut imported = Vec::new();
+
+    while let Some(origin) = queue.pop_front() {
+        let unresolved = executor.read(None, move |conn| {
+            remote::unresolved_imports(conn, &origin).map_err(|e| format!("{:?}", e))
+        }).await?;
+
+        for url in unresolved {
+            let dependency = format!("import:{}", url);
+            if !visited.insert(dependency.clone()) {
+                continue;
+            }
+            match download_and_import(app, executor, url.clone(), None, dependency.clone(), None).await {
+                Ok(committed) => {
+                    queue.push_back(dependency);
+                    imported.push(committed.value);
+                }
+                Err(e) => imported.push(UrlImport {
+                    url,
+                    origin: dependency,
+                    etag: None,
+                    stats: None,
+                    error: Some(e),
+                    dependencies: Vec::new(),
+                }),
+            }
+        }
+    }
+    Ok(imported)
+}
+
+/// Claimed vs. receive times of the transactions imported from other
+/// devices, per origin, most ahead first
+#[tauri::command]
+#[allow(non_snake_case)]
+pub async fn import__clock_skew(
+    min_tx: Option<i64>,
+    executor: State<'_, DbExecutor>,
+) -> Result<Vec<ClockSkew>, String> {
+    executor.read(min_tx, move |conn| {
+        skew::skew_by_origin(conn)
+            .map(|skews| skews.into_iter().map(Into::into).collect())
+            .map_err(|e| format!("Failed to get clock skew: {}", e))
+    }).await
+}
@@ -0,0 +1,38 @@
+use tauri::State;
+
+use crate::eavto::{Committed, DbExecutor};
+use crate::owl::integrity::{self, PredicatePolicy};
+
+/// How undeclared predicates in user edits are handled:
+/// "lenient" (no check), "strict" (refused) or "auto-declare" (declared as
+/// properties in the user namespace)
+#[tauri::command]
+#[allow(non_snake_case)]
+pub async fn integrity__predicate_policy(
+    min_tx: Option<i64>,
+    executor: State<'_, DbExecutor>,
+) -> Result<String, String> {
+    executor.read(min_tx, move |conn| {
+        integrity::predicate_policy(conn)
+            .map(|policy| policy.as_str().to_string())
+            .map_err(|e| e.to_string())
+    }).await
+}
+
+/// Set the predicate policy ("lenient", "strict" or "auto-declare")
+/// Applies to edits from now on
+#[tauri::command]
+#[allow(non_snake_case)]
+pub async fn integrity__set_predicate_policy(
+    policy: String,
+    executor: State<'_, DbExecutor>,
+) -> Result<Committed<String>, String> {
+    let policy = PredicatePolicy::parse(&policy)
+        .ok_or_else(|| format!("Unknown predicate policy {}", policy))?;
+
+    executor.write(move |conn| {
+        integrity::set_predicate_policy(conn, policy)
+            .map(|_| policy.as_str().to_string())
+            .map_err(|e| format!("Failed to set predicate policy: {}", e))
+    }).await
+}
@@ -0,0 +1,121 @@
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+use tauri::State;
+
+use crate::eavto::{Annotation, Committed, DbExecutor};
+use crate::owl::bulk;
+use crate::owl::lint::{self, Issue, LintKind, Scope};
+use super::bulk::BulkChangeInfo;
+
+/// Part of the user data lint__run checks
+#[derive(Debug, Clone, Deserialize, TS)]
+#[serde(tag = "scope", rename_all = "camelCase")]
+#[ts(export)]
+pub enum LintScope {
+    /// Everything users wrote
+    All,
+    /// Instances of a class (and its subclasses)
+    Class { iri: String },
+    /// One entity
+    Entity { iri: String },
+}
+
+impl From<LintScope> for Scope {
+    fn from(scope: LintScope) -> Self {
+        match scope {
+            LintScope::All => Scope::All,
+            LintScope::Class { iri } => Scope::Class(iri),
+            LintScope::Entity { iri } => Scope::Entity(iri),
+        }
+    }
+}
+
+/// A problem in user data, and how to fix it
+#[derive(Debug, Serialize, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export)]
+pub struct LintIssue {
+    pub kind: String, // "literal-for-iri", "number-as-string", "missing-label", "missing-icon" or "outside-domain"
+    pub entity: String,
+    pub property: Option<String>,
+    pub message: String,
+    pub suggestion: String,
+    pub fix: Option<BulkChangeInfo>, // Set when lint__fix can apply the suggestion
+}
+
+impl From<&Issue> for LintIssue {
+    fn from(issue: &Issue) -> Self {
+        Self {
+            kind: issue.kind.as_str().to_string(),
+            entity: issue.entity.clone(),
+            property: issue.property.clone(),
+            message: issue.message.clone(),
+            suggestion: issue.suggestion.clone(),
+            fix: issue.fix.as_ref().map(Into::into),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export)]
+pub struct LintFixResult {
+    pub fixed: Vec<LintIssue>,
+    pub applied: bool,
+}
+
+/// Check user data for common problems: literals where entities are
+/// expected, numbers stored as strings, user classes without a label or
+/// icon, and properties used outside their domain
+#[tauri::command]
+#[allow(non_snake_case)]
+pub async fn lint__run(
+    scope: Option<LintScope>,
+    min_tx: Option<i64>,
+    executor: State<'_, DbExecutor>,
+) -> Result<Vec<LintIssue>, String> {
+    let scope = Scope::from(scope.unwrap_or(LintScope::All));
+
+    executor.read(min_tx, move |conn| {
+        let issues = lint::run(conn, &scope).map_err(|e| e.to_string())?;
+        Ok(issues.iter().map(Into::into).collect())
+    }).await
+}
+
+/// Apply the fixes lint__run suggests, in one transaction
+/// kinds: only fix issues of these kinds (all fixable kinds if omitted)
+/// dry_run: only preview the fixes (nothing is written)
+#[tauri::command]
+#[allow(non_snake_case)]
+pub async fn lint__fix(
+    scope: Option<LintScope>,
+    kinds: Option<Vec<String>>,
+    dry_run: Option<bool>,
+    executor: State<'_, DbExecutor>,
+) -> Result<Committed<LintFixResult>, String> {
+    let scope = Scope::from(scope.unwrap_or(LintScope::All));
+    let kinds = kinds
+        .map(|kinds| kinds.iter()
+            .map(|kind| LintKind::parse(kind).ok_or_else(|| format!("Unknown lint kind {}", kind)))
+            .collect::<Result<Vec<_>, _>>())
+        .transpose()?;
+    let applied = !dry_run.unwrap_or(false);
+
+    executor.write_or_preview(dry_run, move |conn| {
+        let fixable: Vec<Issue> = lint::run(conn, &scope)
+            .map_err(|e| e.to_string())?
+            .into_iter()
+            .filter(|issue| issue.fix.is_some())
+            .filter(|issue| kinds.as_ref().is_none_or(|kinds| kinds.contains(&issue.kind)))
+            .collect();
+        let changes: Vec<_> = fixable.iter().filter_map(|issue| issue.fix.clone()).collect();
+
+        bulk::apply(conn, &changes, "user-edit", &Annotation::new(Some("Lint fixes".to_string()), None))
+            .map_err(|e| format!("Failed to apply lint fixes: {}", e))?;
+
+        Ok(LintFixResult {
+            fixed: fixable.iter().map(Into::into).collect(),
+            applied,
+        })
+    }).await
+}
@@ -0,0 +1,52 @@
+use serde::Serialize;
+use ts_rs::TS;
+use tauri::State;
+use std::path::PathBuf;
+
+use crate::eavto::{Committed, DbExecutor};
+use crate::owl::listening::{self, ListeningImport};
+
+#[derive(Debug, Serialize, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export)]
+pub struct ListeningImportReport {
+    #[ts(type = "number")]
+    pub imported: usize,
+    #[ts(type = "number")]
+    pub duplicates: usize,
+    #[ts(type = "number")]
+    pub skipped: usize,
+    #[ts(type = "number")]
+    pub tracks: usize,
+    #[ts(type = "number")]
+    pub artists: usize,
+}
+
+impl From<ListeningImport> for ListeningImportReport {
+    fn from(report: ListeningImport) -> Self {
+        Self {
+            imported: report.imported,
+            duplicates: report.duplicates,
+            skipped: report.skipped,
+            tracks: report.tracks,
+            artists: report.artists,
+        }
+    }
+}
+
+/// Import a Spotify (JSON) or Last.fm (JSON or CSV) listening history as
+/// Listen events of Track and Artist entities
+/// Listens already imported (same start time) are skipped
+#[tauri::command]
+#[allow(non_snake_case)]
+pub async fn listening__import(
+    path: String,
+    dry_run: Option<bool>,
+    executor: State<'_, DbExecutor>,
+) -> Result<Committed<ListeningImportReport>, String> {
+    executor.write_or_preview(dry_run, move |conn| {
+        listening::import_listening_history(conn, &PathBuf::from(&path))
+            .map(Into::into)
+            .map_err(|e| format!("Failed to import {}: {}", path, e))
+    }).await
+}
@@ -0,0 +1,81 @@
+use serde::Serialize;
+use ts_rs::TS;
+use tauri::State;
+use std::path::PathBuf;
+
+use crate::eavto::{Committed, DbExecutor};
+use crate::owl::location::{self, LocationImport};
+
+#[derive(Debug, Serialize, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export)]
+pub struct LocationImportReport {
+    #[ts(type = "number")]
+    pub points: usize,
+    #[ts(type = "number")]
+    pub visits: usize,
+    #[ts(type = "number")]
+    pub duplicates: usize,
+    #[ts(type = "number")]
+    pub places: usize,
+}
+
+impl From<LocationImport> for LocationImportReport {
+    fn from(report: LocationImport) -> Self {
+        Self {
+            points: report.points,
+            visits: report.visits,
+            duplicates: report.duplicates,
+            places: report.places,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export)]
+pub struct LocationVisit {
+    pub iri: String,
+    pub place: String,
+    pub place_label: String,
+    #[ts(type = "number")]
+    pub start: i64, // Unix ms
+    #[ts(type = "number")]
+    pub end: i64,
+}
+
+/// Import a Google location history export (Records.json, Semantic Location
+/// History or Timeline) as visits at places
+/// Visits already imported (same place and arrival) are skipped
+#[tauri::command]
+#[allow(non_snake_case)]
+pub async fn location__import(
+    path: String,
+    dry_run: Option<bool>,
+    executor: State<'_, DbExecutor>,
+) -> Result<Committed<LocationImportReport>, String> {
+    executor.write_or_preview(dry_run, move |conn| {
+        location::import_location_history(conn, &PathBuf::from(&path))
+            .map(Into::into)
+            .map_err(|e| format!("Failed to import {}: {}", path, e))
+    }).await
+}
+
+/// Visits overlapping a period (Unix ms), in order of arrival
+#[tauri::command]
+#[allow(non_snake_case)]
+pub async fn location__visits(
+    from: i64,
+    to: i64,
+    min_tx: Option<i64>,
+    executor: State<'_, DbExecutor>,
+) -> Result<Vec<LocationVisit>, String> {
+    executor.read(min_tx, move |conn| {
+        let visits = location::visits(conn, from, to)
+            .map_err(|e| format!("Failed to get visits: {}", e))?;
+
+        Ok(visits.into_iter()
+            .map(|v| LocationVisit { iri: v.iri, place: v.place, place_label: v.place_label, start: v.start, end: v.end })
+            .collect())
+    }).await
+}
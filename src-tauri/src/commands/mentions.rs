@@ -0,0 +1,87 @@
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+use tauri::State;
+
+use crate::eavto::{Committed, DbExecutor};
+use crate::owl::mentions::{self, Mention};
+
+/// An entity named in another entity's text
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export)]
+pub struct MentionInfo {
+    pub source: String,
+    pub target: String,
+    pub property: String,
+    pub label: String,
+}
+
+impl From<Mention> for MentionInfo {
+    fn from(mention: Mention) -> Self {
+        Self {
+            source: mention.source,
+            target: mention.target,
+            property: mention.property,
+            label: mention.label,
+        }
+    }
+}
+
+impl From<MentionInfo> for Mention {
+    fn from(info: MentionInfo) -> Self {
+        Self {
+            source: info.source,
+            target: info.target,
+            property: info.property,
+            label: info.label,
+        }
+    }
+}
+
+/// Propose foundation:mentions links for entity labels found in text
+#[tauri::command]
+#[allow(non_snake_case)]
+pub async fn mentions__propose(
+    min_tx: Option<i64>,
+    executor: State<'_, DbExecutor>,
+) -> Result<Vec<MentionInfo>, String> {
+    executor.read(min_tx, move |conn| {
+        Ok(mentions::propose(conn)
+            .map_err(|e| e.to_string())?
+            .into_iter()
+            .map(Into::into)
+            .collect())
+    }).await
+}
+
+/// Create the given (accepted) mention links
+#[tauri::command]
+#[allow(non_snake_case)]
+pub async fn mentions__link(
+    mentions: Vec<MentionInfo>,
+    dry_run: Option<bool>,
+    executor: State<'_, DbExecutor>,
+) -> Result<Committed<()>, String> {
+    let mentions: Vec<Mention> = mentions.into_iter().map(Into::into).collect();
+
+    executor.write_or_preview(dry_run, move |conn| {
+        mentions::link(conn, &mentions)
+            .map(|_| ())
+            .map_err(|e| format!("Failed to link mentions: {}", e))
+    }).await
+}
+
+/// Link mentions involving entities written since the last run
+/// Call after creating or importing entities; returns the links created
+#[tauri::command]
+#[allow(non_snake_case)]
+pub async fn mentions__link_new(
+    dry_run: Option<bool>,
+    executor: State<'_, DbExecutor>,
+) -> Result<Committed<Vec<MentionInfo>>, String> {
+    executor.write_or_preview(dry_run, move |conn| {
+        mentions::link_new(conn)
+            .map(|linked| linked.into_iter().map(Into::into).collect())
+            .map_err(|e| format!("Failed to link mentions: {}", e))
+    }).await
+}
@@ -0,0 +1,56 @@
+use serde::Serialize;
+use ts_rs::TS;
+use tauri::State;
+use std::path::PathBuf;
+
+use crate::eavto::{Committed, DbExecutor};
+use crate::owl::messaging::{self, MessagingImport};
+
+#[derive(Debug, Serialize, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export)]
+pub struct MessagingImportReport {
+    #[ts(type = "number")]
+    pub messages: usize,
+    #[ts(type = "number")]
+    pub conversations: usize,
+    #[ts(type = "number")]
+    pub activities: usize,
+    #[ts(type = "number")]
+    pub updated: usize,
+    #[ts(type = "number")]
+    pub persons: usize,
+    #[ts(type = "number")]
+    pub bodies: usize,
+}
+
+impl From<MessagingImport> for MessagingImportReport {
+    fn from(report: MessagingImport) -> Self {
+        Self {
+            messages: report.messages,
+            conversations: report.conversations,
+            activities: report.activities,
+            updated: report.updated,
+            persons: report.persons,
+            bodies: report.bodies,
+        }
+    }
+}
+
+/// Import a chat export (WhatsApp .txt or Telegram .json) as conversations
+/// with daily message counts per person
+/// bodies: also keep the message texts (default: counts only)
+#[tauri::command]
+#[allow(non_snake_case)]
+pub async fn messaging__import(
+    path: String,
+    bodies: Option<bool>,
+    dry_run: Option<bool>,
+    executor: State<'_, DbExecutor>,
+) -> Result<Committed<MessagingImportReport>, String> {
+    executor.write_or_preview(dry_run, move |conn| {
+        messaging::import_chat_export(conn, &PathBuf::from(&path), bodies.unwrap_or(false))
+            .map(Into::into)
+            .map_err(|e| format!("Failed to import {}: {}", path, e))
+    }).await
+}
@@ -7,13 +7,104 @@
 // - Commands should use the OWL module API, not direct SQL
 // - Keep commands thin - business logic belongs in OWL module
 // - Each command should have tests using tauri::test::mock_app()
+// - Return typed payloads, not JSON strings: derive ts_rs::TS with #[ts(export)]
+//   so `cargo test` writes the TypeScript definition to src/lib/bindings/
+//   (i64/u64 fields need #[ts(type = "number")], serde sends them as numbers)
+// - Write commands return Committed<T> ({ tx, value }); read commands take an
+//   optional `min_tx` so the frontend can read its own writes (see eavto::executor)
 
 mod setup;
 mod entity;
+mod class;
+mod ontology;
 mod shortcuts;
+mod sandbox;
+mod triple;
+mod quarantine;
+mod workspace;
+mod suggestions;
+mod mentions;
+mod bulk;
+mod import;
+mod export;
+mod console;
+mod charts;
+mod finance;
+mod location;
+mod listening;
+mod browsing;
+mod messaging;
+mod person;
+mod organization;
+mod parts;
+mod attachment;
+mod photos;
+mod history;
+mod palette;
+mod api;
+mod federated;
+mod outbox;
+mod storage;
+mod stats;
+mod encryption;
+mod integrity;
+mod graph;
+mod validation;
+mod identity;
+mod settings;
+mod sync;
+mod lint;
+mod origins;
+mod debug;
+mod demo;
 mod logging;
+mod query;
+mod fact;
 
 pub use setup::*;
 pub use entity::*;
+pub use class::*;
+pub use ontology::*;
 pub use shortcuts::*;
+pub use sandbox::*;
+pub use triple::*;
+pub use quarantine::*;
+pub use workspace::*;
+pub use suggestions::*;
+pub use mentions::*;
+pub use bulk::*;
+pub use import::*;
+pub use export::*;
+pub use console::*;
+pub use charts::*;
+pub use finance::*;
+pub use location::*;
+pub use listening::*;
+pub use browsing::*;
+pub use messaging::*;
+pub use person::*;
+pub use organization::*;
+pub use parts::*;
+pub use attachment::*;
+pub use photos::*;
+pub use history::*;
+pub use palette::*;
+pub use api::*;
+pub use federated::*;
+pub use outbox::*;
+pub use storage::*;
+pub use stats::*;
+pub use encryption::*;
+pub use integrity::*;
+pub use graph::*;
+pub use validation::*;
+pub use identity::*;
+pub use settings::*;
+pub use sync::*;
+pub use lint::*;
+pub use origins::*;
+pub use debug::*;
+pub use demo::*;
 pub use logging::*;
+pub use query::*;
+pub use fact::*;
@@ -0,0 +1,412 @@
+use serde::Serialize;
+use ts_rs::TS;
+use tauri::{AppHandle, State};
+use std::path::PathBuf;
+
+use crate::eavto::{Committed, DbExecutor, query};
+use crate::export::{self, OntologyHeader};
+use crate::turtle::pipeline;
+use crate::owl::{Ontology, OntologyRelease, OntologyUpgrade, license::{self, SourceLicense}, tree::{self, ClassTree}};
+use crate::owl::vocabulary::owl;
+
+/// Default ontology IRI for exports of the FOUNDATION store
+const DEFAULT_ONTOLOGY_IRI: &str = "http://foundation.local/ontology";
+
+#[derive(Debug, Serialize, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export)]
+pub struct SourceLicenseInfo {
+    pub origin: String,
+    pub source: String,
+    pub license: Option<String>,
+    pub license_name: Option<String>, // SPDX-style id when the license is recognized
+}
+
+impl From<SourceLicense> for SourceLicenseInfo {
+    fn from(source: SourceLicense) -> Self {
+        Self {
+            license_name: source.license.as_deref().map(license::short_name),
+            origin: source.origin,
+            source: source.source,
+            license: source.license,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export)]
+pub struct RdfXmlExport {
+    pub content: String,
+    pub sources: Vec<SourceLicenseInfo>,
+    pub warnings: Vec<String>, // License incompatibilities between the exported sources
+}
+
+/// Export triples as Protégé-compatible RDF/XML
+/// origin: limit the export to one origin (e.g., "user-edit"); all triples otherwise
+/// The licenses of the exported sources are listed in the header and checked for conflicts
+#[tauri::command]
+#[allow(non_snake_case)]
+pub async fn ontology__export_rdfxml(
+    origin: Option<String>,
+    ontology_iri: Option<String>,
+    version_iri: Option<String>,
+    imports: Option<Vec<String>>,
+    min_tx: Option<i64>,
+    executor: State<'_, DbExecutor>,
+) -> Result<RdfXmlExport, String> {
+    executor.read(min_tx, move |conn| {
+        let sources = license::source_licenses(conn, origin.as_deref())
+            .map_err(|e| e.to_string())?;
+        let warnings = license::compatibility_warnings(&sources);
+        for warning in &warnings {
+            println!("⚠️  License: {}", warning);
+        }
+
+        let header = OntologyHeader {
+            iri: ontology_iri.unwrap_or_else(|| DEFAULT_ONTOLOGY_IRI.to_string()),
+            version_iri,
+            imports: imports.unwrap_or_default(),
+            sources: sources.clone(),
+        };
+
+        let content = export::export_rdfxml(conn, origin.as_deref(), &header)
+            .map_err(|e| format!("Failed to export RDF/XML: {}", e))?;
+
+        Ok(RdfXmlExport {
+            content,
+            sources: sources.into_iter().map(Into::into).collect(),
+            warnings,
+        })
+    }).await
+}
+
+/// List the sources in the store with their declared licenses
+#[tauri::command]
+#[allow(non_snake_case)]
+pub async fn ontology__list_licenses(
+    min_tx: Option<i64>,
+    executor: State<'_, DbExecutor>,
+) -> Result<Vec<SourceLicenseInfo>, String> {
+    executor.read(min_tx, move |conn| {
+        Ok(license::source_licenses(conn, None)
+            .map_err(|e| e.to_string())?
+            .into_iter()
+            .map(Into::into)
+            .collect())
+    }).await
+}
+
+/// Declare the license of a source (e.g., "import:bfo.owl"), replacing any previous one
+/// license: license IRI (e.g., "http://creativecommons.org/licenses/by/4.0/") or name
+#[tauri::command]
+#[allow(non_snake_case)]
+pub async fn ontology__declare_license(
+    origin: String,
+    license: String,
+    dry_run: Option<bool>,
+    executor: State<'_, DbExecutor>,
+) -> Result<Committed<Vec<String>>, String> {
+    executor.write_or_preview(dry_run, move |conn| {
+        license::declare(conn, &origin, &license)
+            .map_err(|e| format!("Failed to declare license for {}: {}", origin, e))
+    }).await
+}
+
+/// Import an ontology file (Turtle, N-Triples, RDF/XML or JSON-LD, or
+/// their .gz) into the store
+/// Ontology IRIs and version IRIs are kept as entities linked to the source file
+/// license: declared license of the file, when its header doesn't state one
+/// quarantine: hold the import for review (quarantine__list) before queries see it
+/// Runs as an import job: "import-progress" events carry its ID, for import__cancel
+#[tauri::command]
+#[allow(non_snake_case)]
+pub async fn ontology__import_file(
+    file_path: String,
+    license: Option<String>,
+    quarantine: Option<bool>,
+    dry_run: Option<bool>,
+    app: AppHandle,
+    executor: State<'_, DbExecutor>,
+) -> Result<Committed<crate::turtle::ImportStats>, String> {
+    let path = PathBuf::from(&file_path);
+    let file_name = path.file_name()
+        .and_then(|n| n.to_str())
+        .ok_or_else(|| format!("Invalid file path: {}", file_path))?
+        .to_string();
+    let size = std::fs::metadata(&path).ok().map(|metadata| metadata.len());
+    let job = super::import::start_job(&app, &file_name, size);
+
+    executor.write_or_preview(dry_run, move |conn| {
+        let origin = format!("import:{}", file_name);
+
+        // In a savepoint, so a cancelled import leaves nothing behind
+        conn.execute_batch("SAVEPOINT file_import").map_err(|e| e.to_string())?;
+        let imported = (|| {
+            if quarantine.unwrap_or(false) {
+                crate::eavto::quarantine::quarantine(conn, &origin)
+                    .map_err(|e| format!("Failed to quarantine {}: {}", file_name, e))?;
+            }
+
+            let stats = pipeline::import_file_job(conn, &path, None, &origin, Some(&job))
+                .map_err(|e| format!("Failed to import {}: {:?}", file_name, e))?;
+
+            if let Some(license) = license {
+                license::declare(conn, &origin, &license)
+                    .map_err(|e| format!("Failed to declare license for {}: {}", file_name, e))?;
+            }
+            Ok(stats)
+        })();
+
+        let end = if imported.is_ok() { "RELEASE file_import" } else { "ROLLBACK TO file_import; RELEASE file_import" };
+        conn.execute_batch(end).map_err(|e| e.to_string())?;
+        imported
+    }).await
+}
+
+#[derive(Debug, Serialize, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export)]
+pub struct OntologyInfo {
+    pub iri: String,
+    pub title: String, // dc:title / rdfs:label, or the IRI when there is none
+    pub version_iri: Option<String>,
+    pub version_info: Option<String>,
+    pub license: Option<String>,
+    pub creators: Vec<String>,
+    pub imports: Vec<String>,
+    pub source_file: Option<String>,
+    #[ts(type = "number | null")]
+    pub imported_at: Option<i64>,
+    pub origin: Option<String>,
+    #[ts(type = "number")]
+    pub axiom_count: i64,
+}
+
+impl From<Ontology> for OntologyInfo {
+    fn from(ontology: Ontology) -> Self {
+        Self {
+            title: ontology.display_title().to_string(),
+            iri: ontology.iri,
+            version_iri: ontology.version_iri,
+            version_info: ontology.version_info,
+            license: ontology.license,
+            creators: ontology.creators,
+            imports: ontology.imports,
+            source_file: ontology.source_file,
+            imported_at: ontology.imported_at,
+            origin: ontology.origin,
+            axiom_count: ontology.axiom_count,
+        }
+    }
+}
+
+/// List imported ontologies (owl:Ontology headers) with their metadata
+#[tauri::command]
+#[allow(non_snake_case)]
+pub async fn ontology__list_ontologies(
+    min_tx: Option<i64>,
+    executor: State<'_, DbExecutor>,
+) -> Result<Vec<OntologyInfo>, String> {
+    executor.read(min_tx, move |conn| {
+        Ok(Ontology::list(conn)
+            .map_err(|e| e.to_string())?
+            .into_iter()
+            .map(Into::into)
+            .collect())
+    }).await
+}
+
+/// Levels of the class tree when the caller gives no depth
+const DEFAULT_TREE_DEPTH: usize = 2;
+
+/// A class of the hierarchy tree
+#[derive(Debug, Serialize, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export)]
+pub struct ClassTreeNode {
+    pub iri: String,
+    pub label: String,
+    pub icon: Option<String>,
+    #[ts(type = "number")]
+    pub child_count: u64, // Direct subclasses: a node with none listed but some here can expand
+    #[ts(type = "number")]
+    pub instance_count: u64, // Direct instances
+    pub children: Vec<ClassTreeNode>, // By label
+}
+
+impl From<ClassTree> for ClassTreeNode {
+    fn from(tree: ClassTree) -> Self {
+        Self {
+            iri: tree.thing.iri,
+            label: tree.thing.label,
+            icon: tree.thing.icon,
+            child_count: tree.child_count,
+            instance_count: tree.instance_count,
+            children: tree.children.into_iter().map(Into::into).collect(),
+        }
+    }
+}
+
+/// The subclass tree under a class (owl:Thing by default), `depth` levels
+/// deep, with subclass and instance counts per class
+/// Expanding a node is another call from it
+#[tauri::command]
+#[allow(non_snake_case)]
+pub async fn ontology__tree(
+    root_iri: Option<String>,
+    depth: Option<usize>,
+    min_tx: Option<i64>,
+    executor: State<'_, DbExecutor>,
+) -> Result<ClassTreeNode, String> {
+    executor.read(min_tx, move |conn| {
+        let root = root_iri.as_deref().unwrap_or(owl::THING);
+        tree::class_tree(conn, root, depth.unwrap_or(DEFAULT_TREE_DEPTH))
+            .map(Into::into)
+            .map_err(|e| format!("Failed to read the class tree under {}: {}", root, e))
+    }).await
+}
+
+#[derive(Debug, Serialize, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export)]
+pub struct OntologyReleaseInfo {
+    pub iri: String,
+    pub source_file: String,
+    pub checksum: String,
+    pub ontology: Option<String>,
+    pub version_iri: Option<String>,
+    #[ts(type = "number")]
+    pub imported_at: i64,
+    #[ts(type = "number")]
+    pub tx: i64,
+    #[ts(type = "number")]
+    pub triple_count: i64,
+    pub previous_release: Option<String>,
+}
+
+impl From<OntologyRelease> for OntologyReleaseInfo {
+    fn from(release: OntologyRelease) -> Self {
+        Self {
+            iri: release.iri,
+            source_file: release.source_file,
+            checksum: release.checksum,
+            ontology: release.ontology,
+            version_iri: release.version_iri,
+            imported_at: release.imported_at,
+            tx: release.tx,
+            triple_count: release.triple_count,
+            previous_release: release.previous_release,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export)]
+pub struct FactRelease {
+    pub predicate: String,
+    #[ts(type = "number")]
+    pub tx: i64,
+    pub release: Option<OntologyReleaseInfo>,
+}
+
+#[derive(Debug, Serialize, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export)]
+pub struct OntologyUpgradeInfo {
+    pub iri: String,
+    pub source_file: String,
+    pub from_release: Option<String>,
+    pub to_release: String,
+    #[ts(type = "number")]
+    pub added: i64,
+    #[ts(type = "number")]
+    pub retracted: i64,
+    #[ts(type = "number")]
+    pub tx: i64,
+    #[ts(type = "number")]
+    pub imported_at: i64,
+}
+
+impl From<OntologyUpgrade> for OntologyUpgradeInfo {
+    fn from(upgrade: OntologyUpgrade) -> Self {
+        Self {
+            iri: upgrade.iri,
+            source_file: upgrade.source_file,
+            from_release: upgrade.from_release,
+            to_release: upgrade.to_release,
+            added: upgrade.added,
+            retracted: upgrade.retracted,
+            tx: upgrade.tx,
+            imported_at: upgrade.imported_at,
+        }
+    }
+}
+
+/// List recorded ontology releases, optionally for a single file (oldest first)
+#[tauri::command]
+#[allow(non_snake_case)]
+pub async fn ontology__list_releases(
+    source_file: Option<String>,
+    min_tx: Option<i64>,
+    executor: State<'_, DbExecutor>,
+) -> Result<Vec<OntologyReleaseInfo>, String> {
+    executor.read(min_tx, move |conn| {
+        let releases = match source_file {
+            Some(file) => OntologyRelease::for_file(conn, &file),
+            None => OntologyRelease::list(conn),
+        }.map_err(|e| e.to_string())?;
+
+        Ok(releases.into_iter().map(Into::into).collect())
+    }).await
+}
+
+/// Find which ontology release asserted the current facts about an entity
+/// predicate: limit to one property; all properties otherwise
+#[tauri::command]
+#[allow(non_snake_case)]
+pub async fn ontology__release_for_fact(
+    entity_id: String,
+    predicate: Option<String>,
+    min_tx: Option<i64>,
+    executor: State<'_, DbExecutor>,
+) -> Result<Vec<FactRelease>, String> {
+    executor.read(min_tx, move |conn| {
+        let triples = match &predicate {
+            Some(p) => query::get_by_entity_predicate(conn, &entity_id, p),
+            None => query::get_by_entity(conn, &entity_id),
+        }.map_err(|e| e.to_string())?.triples;
+
+        let mut facts = Vec::new();
+        for triple in triples {
+            let release = OntologyRelease::for_transaction(conn, triple.tx)
+                .map_err(|e| e.to_string())?
+                .map(Into::into);
+            facts.push(FactRelease {
+                predicate: triple.predicate,
+                tx: triple.tx,
+                release,
+            });
+        }
+
+        Ok(facts)
+    }).await
+}
+
+/// List the upgrade reports of a core ontology file (oldest first)
+#[tauri::command]
+#[allow(non_snake_case)]
+pub async fn ontology__list_upgrades(
+    source_file: String,
+    min_tx: Option<i64>,
+    executor: State<'_, DbExecutor>,
+) -> Result<Vec<OntologyUpgradeInfo>, String> {
+    executor.read(min_tx, move |conn| {
+        Ok(OntologyUpgrade::for_file(conn, &source_file)
+            .map_err(|e| e.to_string())?
+            .into_iter()
+            .map(Into::into)
+            .collect())
+    }).await
+}
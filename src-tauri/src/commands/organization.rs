@@ -0,0 +1,23 @@
+use tauri::State;
+
+use crate::eavto::DbExecutor;
+use crate::owl::affiliation;
+use super::person::AffiliationEntry;
+
+/// An organization's affiliations overlapping a period (Unix ms, either end
+/// open); pass the same date as from and to for the members at that date
+#[tauri::command]
+#[allow(non_snake_case)]
+pub async fn organization__affiliations(
+    org: String,
+    from: Option<i64>,
+    to: Option<i64>,
+    min_tx: Option<i64>,
+    executor: State<'_, DbExecutor>,
+) -> Result<Vec<AffiliationEntry>, String> {
+    executor.read(min_tx, move |conn| {
+        let affiliations = affiliation::affiliations_with(conn, &org, from, to)
+            .map_err(|e| format!("Failed to get affiliations with {}: {}", org, e))?;
+        Ok(affiliations.into_iter().map(|a| AffiliationEntry::new(conn, a)).collect())
+    }).await
+}
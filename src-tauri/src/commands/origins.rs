@@ -0,0 +1,107 @@
+use serde::Serialize;
+use ts_rs::TS;
+use tauri::State;
+
+use crate::eavto::{query, store, Committed, DbExecutor, origin::{self, OriginStats}};
+
+/// An origin and what it holds, for the provenance dashboard
+#[derive(Debug, Serialize, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export)]
+pub struct OriginInfo {
+    pub name: String,
+    pub description: Option<String>,
+    pub status: String, // "active", "quarantined" or "rejected"
+    #[ts(type = "number")]
+    pub triple_count: i64,
+    #[ts(type = "number | null")]
+    pub first_tx: Option<i64>,
+    #[ts(type = "number | null")]
+    pub last_tx: Option<i64>,
+    #[ts(type = "number | null")]
+    pub first_at: Option<i64>,
+    #[ts(type = "number | null")]
+    pub last_at: Option<i64>,
+}
+
+impl From<OriginStats> for OriginInfo {
+    fn from(stats: OriginStats) -> Self {
+        Self {
+            name: stats.origin.name,
+            description: stats.origin.description,
+            status: stats.status,
+            triple_count: stats.triple_count,
+            first_tx: stats.first_tx,
+            last_tx: stats.last_tx,
+            first_at: stats.first_at,
+            last_at: stats.last_at,
+        }
+    }
+}
+
+/// List origins with their triple counts and first/last transaction
+#[tauri::command]
+#[allow(non_snake_case)]
+pub async fn origins__list(
+    min_tx: Option<i64>,
+    executor: State<'_, DbExecutor>,
+) -> Result<Vec<OriginInfo>, String> {
+    executor.read(min_tx, move |conn| {
+        Ok(origin::list_origin_stats(conn)
+            .map_err(|e| e.to_string())?
+            .into_iter()
+            .map(Into::into)
+            .collect())
+    }).await
+}
+
+/// Rename an origin (past transactions keep the name they were written under)
+#[tauri::command]
+#[allow(non_snake_case)]
+pub async fn origins__rename(
+    name: String,
+    new_name: String,
+    executor: State<'_, DbExecutor>,
+) -> Result<Committed<()>, String> {
+    executor.write(move |conn| {
+        origin::rename(conn, &name, &new_name)
+            .map_err(|e| format!("Failed to rename origin {}: {}", name, e))
+    }).await
+}
+
+/// Set (or clear) the description of an origin
+#[tauri::command]
+#[allow(non_snake_case)]
+pub async fn origins__describe(
+    name: String,
+    description: Option<String>,
+    executor: State<'_, DbExecutor>,
+) -> Result<Committed<()>, String> {
+    executor.write(move |conn| {
+        origin::describe(conn, &name, description.as_deref())
+            .map_err(|e| format!("Failed to describe origin {}: {}", name, e))
+    }).await
+}
+
+/// Forget an import: retract every current triple of an origin in one
+/// transaction (the bundled ontologies can't be forgotten)
+/// Returns the transaction ID (None if the origin held no triples)
+#[tauri::command]
+#[allow(non_snake_case)]
+pub async fn origins__forget(
+    name: String,
+    dry_run: Option<bool>,
+    executor: State<'_, DbExecutor>,
+) -> Result<Committed<Option<i64>>, String> {
+    if query::is_core_origin(&name) {
+        return Err(format!("Origin {} holds the bundled ontologies and can't be forgotten", name));
+    }
+
+    executor.write_or_preview(dry_run, move |conn| {
+        let origin_id = query::get_origin_id(conn, &name)
+            .map_err(|e| e.to_string())?
+            .ok_or_else(|| format!("Origin {} not found", name))?;
+        store::retract_origin(conn, origin_id)
+            .map_err(|e| format!("Failed to forget origin {}: {}", name, e))
+    }).await
+}
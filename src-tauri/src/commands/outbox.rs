@@ -0,0 +1,96 @@
+use serde::Serialize;
+use ts_rs::TS;
+use tauri::State;
+
+use crate::eavto::{Committed, DbExecutor};
+use crate::outbox::{self, Job, JobStatus};
+
+/// Jobs returned by outbox__list when no limit is given
+const DEFAULT_LIMIT: usize = 100;
+
+/// Outbound work waiting for the network
+#[derive(Debug, Serialize, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export)]
+pub struct OutboxJob {
+    #[ts(type = "number")]
+    pub id: i64,
+    pub kind: String,
+    #[ts(type = "unknown")]
+    pub payload: serde_json::Value,
+    pub status: String, // "pending", "done", "failed" or "cancelled"
+    #[ts(type = "number")]
+    pub attempts: i64,
+    #[ts(type = "number")]
+    pub next_attempt_at: i64,
+    pub last_error: Option<String>,
+    #[ts(type = "number")]
+    pub created_at: i64,
+    #[ts(type = "number | null")]
+    pub completed_at: Option<i64>,
+}
+
+impl From<Job> for OutboxJob {
+    fn from(job: Job) -> Self {
+        Self {
+            id: job.id,
+            kind: job.kind,
+            payload: job.payload,
+            status: job.status.as_str().to_string(),
+            attempts: job.attempts,
+            next_attempt_at: job.next_attempt_at,
+            last_error: job.last_error,
+            created_at: job.created_at,
+            completed_at: job.completed_at,
+        }
+    }
+}
+
+/// List outbox jobs, newest first
+/// status: only jobs with this status ("pending", "done", "failed", "cancelled")
+#[tauri::command]
+#[allow(non_snake_case)]
+pub async fn outbox__list(
+    status: Option<String>,
+    limit: Option<usize>,
+    min_tx: Option<i64>,
+    executor: State<'_, DbExecutor>,
+) -> Result<Vec<OutboxJob>, String> {
+    let status = status
+        .map(|s| JobStatus::parse(&s).ok_or_else(|| format!("Unknown job status: {}", s)))
+        .transpose()?;
+
+    executor.read(min_tx, move |conn| {
+        outbox::list(conn, status, limit.unwrap_or(DEFAULT_LIMIT))
+            .map(|jobs| jobs.into_iter().map(Into::into).collect())
+            .map_err(|e| format!("Failed to list outbox: {}", e))
+    }).await
+}
+
+/// Run a failed or cancelled outbox job again
+#[tauri::command]
+#[allow(non_snake_case)]
+pub async fn outbox__retry(
+    id: i64,
+    executor: State<'_, DbExecutor>,
+) -> Result<Committed<OutboxJob>, String> {
+    executor.write(move |conn| {
+        outbox::retry(conn, id)
+            .map(Into::into)
+            .map_err(|e| format!("Failed to retry job {}: {}", id, e))
+    }).await
+}
+
+/// Cancel a pending outbox job
+#[tauri::command]
+#[allow(non_snake_case)]
+pub async fn outbox__cancel(
+    id: i64,
+    executor: State<'_, DbExecutor>,
+) -> Result<Committed<OutboxJob>, String> {
+    executor.write(move |conn| {
+        outbox::cancel(conn, id)
+            .map(Into::into)
+            .map_err(|e| format!("Failed to cancel job {}: {}", id, e))
+    }).await
+}
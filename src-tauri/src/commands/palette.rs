@@ -0,0 +1,80 @@
+use serde::Serialize;
+use ts_rs::TS;
+use tauri::State;
+
+use crate::eavto::{Committed, DbExecutor};
+use crate::owl::palette::{self, ActionKind, PaletteAction};
+
+/// Kind of palette action
+#[derive(Debug, Serialize, TS)]
+#[serde(rename_all = "lowercase")]
+#[ts(export)]
+pub enum PaletteActionKind {
+    Command,
+    Query,
+    Navigate,
+}
+
+/// An action of the command palette
+#[derive(Debug, Serialize, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export)]
+pub struct PaletteActionInfo {
+    pub id: String, // Pass to palette__record when the action is used
+    pub kind: PaletteActionKind,
+    pub label: String,
+    pub target: String, // Tauri command, saved query IRI or entity IRI
+    #[ts(type = "number")]
+    pub uses: i64,
+    #[ts(type = "number | null")]
+    pub last_used: Option<i64>,
+}
+
+impl From<PaletteAction> for PaletteActionInfo {
+    fn from(action: PaletteAction) -> Self {
+        Self {
+            id: action.id,
+            kind: match action.kind {
+                ActionKind::Command => PaletteActionKind::Command,
+                ActionKind::Query => PaletteActionKind::Query,
+                ActionKind::Navigate => PaletteActionKind::Navigate,
+            },
+            label: action.label,
+            target: action.target,
+            uses: action.uses,
+            last_used: action.last_used,
+        }
+    }
+}
+
+/// Actions for the command palette (commands, saved queries, navigation
+/// targets), most used first
+/// context_iri: entity on screen, adding the commands that apply to it and
+/// the entities it links to
+#[tauri::command]
+#[allow(non_snake_case)]
+pub async fn palette__actions(
+    context_iri: Option<String>,
+    min_tx: Option<i64>,
+    executor: State<'_, DbExecutor>,
+) -> Result<Vec<PaletteActionInfo>, String> {
+    executor.read(min_tx, move |conn| {
+        palette::actions(conn, context_iri.as_deref())
+            .map(|actions| actions.into_iter().map(Into::into).collect())
+            .map_err(|e| format!("Failed to get palette actions: {}", e))
+    }).await
+}
+
+/// Count a use of a palette action, so it ranks higher
+#[tauri::command]
+#[allow(non_snake_case)]
+pub async fn palette__record(
+    action_id: String,
+    executor: State<'_, DbExecutor>,
+) -> Result<Committed<()>, String> {
+    executor.write(move |conn| {
+        palette::record_use(conn, &action_id, "user-edit")
+            .map(|_| ())
+            .map_err(|e| format!("Failed to record {}: {}", action_id, e))
+    }).await
+}
@@ -0,0 +1,99 @@
+use serde::Serialize;
+use ts_rs::TS;
+use tauri::State;
+
+use crate::eavto::DbExecutor;
+use crate::owl::{closure::Reached, parthood, Thing};
+
+/// An entity reached through part-whole links
+#[derive(Debug, Serialize, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export)]
+pub struct PartEntry {
+    pub iri: String,
+    pub label: String,
+    pub icon: Option<String>,
+    #[ts(type = "number")]
+    pub depth: usize, // 1: direct part (or whole)
+}
+
+/// A line of a shopping list
+#[derive(Debug, Serialize, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export)]
+pub struct ShoppingListItem {
+    pub product: String,
+    pub product_label: String,
+    pub unit: Option<String>, // QUDT unit IRI; null: a count
+    pub unit_label: Option<String>,
+    pub needed: f64,
+    pub on_hand: f64,
+    pub to_buy: f64,
+}
+
+fn entries(conn: &rusqlite::Connection, reached: Vec<Reached>) -> Vec<PartEntry> {
+    reached.into_iter()
+        .map(|r| {
+            let thing = Thing::get(conn, &r.iri);
+            PartEntry { iri: r.iri, label: thing.label, icon: thing.icon, depth: r.depth }
+        })
+        .collect()
+}
+
+/// All parts of a whole, including parts of parts (nearest first)
+#[tauri::command]
+#[allow(non_snake_case)]
+pub async fn parts__of(
+    whole: String,
+    min_tx: Option<i64>,
+    executor: State<'_, DbExecutor>,
+) -> Result<Vec<PartEntry>, String> {
+    executor.read(min_tx, move |conn| {
+        let parts = parthood::parts(conn, &whole)
+            .map_err(|e| format!("Failed to get parts of {}: {}", whole, e))?;
+        Ok(entries(conn, parts))
+    }).await
+}
+
+/// All wholes a part belongs to, including wholes of wholes (nearest first)
+#[tauri::command]
+#[allow(non_snake_case)]
+pub async fn parts__wholes(
+    part: String,
+    min_tx: Option<i64>,
+    executor: State<'_, DbExecutor>,
+) -> Result<Vec<PartEntry>, String> {
+    executor.read(min_tx, move |conn| {
+        let wholes = parthood::wholes(conn, &part)
+            .map_err(|e| format!("Failed to get wholes of {}: {}", part, e))?;
+        Ok(entries(conn, wholes))
+    }).await
+}
+
+/// What to buy for some wholes (recipes, meal plans, builds): their
+/// ingredients added up per product, less the quantity on hand
+/// A whole listed twice counts twice
+#[tauri::command]
+#[allow(non_snake_case)]
+pub async fn parts__shopping_list(
+    wholes: Vec<String>,
+    min_tx: Option<i64>,
+    executor: State<'_, DbExecutor>,
+) -> Result<Vec<ShoppingListItem>, String> {
+    executor.read(min_tx, move |conn| {
+        let items = parthood::shopping_list(conn, &wholes)
+            .map_err(|e| format!("Failed to build the shopping list: {}", e))?;
+
+        Ok(items.into_iter()
+            .map(|item| ShoppingListItem {
+                product_label: Thing::get(conn, &item.product).label,
+                unit_label: item.unit.as_ref().map(|u| Thing::get(conn, u).label),
+                product: item.product,
+                unit: item.unit,
+                needed: item.needed,
+                on_hand: item.on_hand,
+                to_buy: item.to_buy,
+            })
+            .collect())
+    }).await
+}
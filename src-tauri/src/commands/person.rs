@@ -0,0 +1,76 @@
+use serde::Serialize;
+use ts_rs::TS;
+use tauri::State;
+
+use crate::eavto::{Committed, DbExecutor};
+use crate::owl::{affiliation::{self, Affiliation}, Thing};
+
+/// An affiliation, with labels for display
+#[derive(Debug, Serialize, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export)]
+pub struct AffiliationEntry {
+    pub iri: String,
+    pub kind: String, // foundation:Affiliation, or Employment, Membership, Education
+    pub person: String,
+    pub person_label: String,
+    pub organization: String,
+    pub organization_label: String,
+    pub role: Option<String>,
+    #[ts(type = "number | null")]
+    pub from: Option<i64>, // Unix ms; null: unknown start
+    #[ts(type = "number | null")]
+    pub to: Option<i64>, // Unix ms; null: current
+}
+
+impl AffiliationEntry {
+    pub(crate) fn new(conn: &rusqlite::Connection, affiliation: Affiliation) -> Self {
+        Self {
+            person_label: Thing::get(conn, &affiliation.person).label,
+            organization_label: Thing::get(conn, &affiliation.organization).label,
+            iri: affiliation.iri,
+            kind: affiliation.kind,
+            person: affiliation.person,
+            organization: affiliation.organization,
+            role: affiliation.role,
+            from: affiliation.from,
+            to: affiliation.to,
+        }
+    }
+}
+
+/// Record a person's affiliation with an organization for a period
+/// kind: "foundation:Employment" | "foundation:Membership" | "foundation:Education" (default: plain affiliation)
+/// from, to: Unix ms; leave `to` out for a current affiliation
+#[tauri::command]
+#[allow(non_snake_case, clippy::too_many_arguments)]
+pub async fn person__add_affiliation(
+    person: String,
+    org: String,
+    role: Option<String>,
+    from: Option<i64>,
+    to: Option<i64>,
+    kind: Option<String>,
+    dry_run: Option<bool>,
+    executor: State<'_, DbExecutor>,
+) -> Result<Committed<String>, String> {
+    executor.write_or_preview(dry_run, move |conn| {
+        affiliation::add_affiliation(conn, &person, &org, role.as_deref(), kind.as_deref(), (from, to), "user-edit")
+            .map_err(|e| format!("Failed to add affiliation: {}", e))
+    }).await
+}
+
+/// A person's affiliations as a CV-style timeline (current first)
+#[tauri::command]
+#[allow(non_snake_case)]
+pub async fn person__affiliations(
+    person: String,
+    min_tx: Option<i64>,
+    executor: State<'_, DbExecutor>,
+) -> Result<Vec<AffiliationEntry>, String> {
+    executor.read(min_tx, move |conn| {
+        let affiliations = affiliation::affiliations_of(conn, &person)
+            .map_err(|e| format!("Failed to get affiliations of {}: {}", person, e))?;
+        Ok(affiliations.into_iter().map(|a| AffiliationEntry::new(conn, a)).collect())
+    }).await
+}
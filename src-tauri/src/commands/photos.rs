@@ -0,0 +1,108 @@
+use serde::Serialize;
+use ts_rs::TS;
+use tauri::State;
+use std::path::PathBuf;
+
+use crate::eavto::{Committed, DbExecutor};
+use crate::owl::{photos::{self, PhotoImport}, Thing};
+
+#[derive(Debug, Serialize, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export)]
+pub struct PhotoImportReport {
+    #[ts(type = "number")]
+    pub files: usize,
+    #[ts(type = "number")]
+    pub photos: usize,
+    #[ts(type = "number")]
+    pub copies: usize,
+    #[ts(type = "number")]
+    pub unchanged: usize,
+    #[ts(type = "number")]
+    pub duplicates: usize,
+    #[ts(type = "number")]
+    pub unhashed: usize,
+}
+
+impl From<PhotoImport> for PhotoImportReport {
+    fn from(report: PhotoImport) -> Self {
+        Self {
+            files: report.files,
+            photos: report.photos,
+            copies: report.copies,
+            unchanged: report.unchanged,
+            duplicates: report.duplicates,
+            unhashed: report.unhashed,
+        }
+    }
+}
+
+/// A near-duplicate photo to review
+#[derive(Debug, Serialize, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export)]
+pub struct PhotoDuplicate {
+    pub photo: String,
+    pub photo_label: String,
+    pub original: String,
+    pub original_label: String,
+    #[ts(type = "number | null")]
+    pub distance: Option<u32>, // Differing bits of the perceptual hashes (of 64)
+}
+
+/// Import the photos of a folder and its subfolders
+/// Identical files become one photo; near-duplicates are linked to their
+/// original with foundation:duplicateOf
+#[tauri::command]
+#[allow(non_snake_case)]
+pub async fn photos__import(
+    path: String,
+    dry_run: Option<bool>,
+    executor: State<'_, DbExecutor>,
+) -> Result<Committed<PhotoImportReport>, String> {
+    executor.write_or_preview(dry_run, move |conn| {
+        photos::import_photos(conn, &PathBuf::from(&path))
+            .map(Into::into)
+            .map_err(|e| format!("Failed to import {}: {}", path, e))
+    }).await
+}
+
+/// Near-duplicate photos waiting for review
+#[tauri::command]
+#[allow(non_snake_case)]
+pub async fn photos__duplicates(
+    min_tx: Option<i64>,
+    executor: State<'_, DbExecutor>,
+) -> Result<Vec<PhotoDuplicate>, String> {
+    executor.read(min_tx, move |conn| {
+        let duplicates = photos::duplicates(conn)
+            .map_err(|e| format!("Failed to get duplicates: {}", e))?;
+
+        Ok(duplicates.into_iter()
+            .map(|d| PhotoDuplicate {
+                photo_label: Thing::get(conn, &d.photo).label,
+                original_label: Thing::get(conn, &d.original).label,
+                photo: d.photo,
+                original: d.original,
+                distance: d.distance,
+            })
+            .collect())
+    }).await
+}
+
+/// Settle a near-duplicate: same merges the photo into its original,
+/// otherwise the link is dropped for good
+#[tauri::command]
+#[allow(non_snake_case)]
+pub async fn photos__review_duplicate(
+    photo: String,
+    same: bool,
+    dry_run: Option<bool>,
+    executor: State<'_, DbExecutor>,
+) -> Result<Committed<()>, String> {
+    executor.write_or_preview(dry_run, move |conn| {
+        photos::review_duplicate(conn, &photo, same, "user-edit")
+            .map(|_| ())
+            .map_err(|e| format!("Failed to review {}: {}", photo, e))
+    }).await
+}
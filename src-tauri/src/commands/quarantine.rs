@@ -0,0 +1,79 @@
+use serde::Serialize;
+use ts_rs::TS;
+use tauri::State;
+
+use crate::eavto::{Committed, DbExecutor, quarantine::{self, QuarantineSummary}};
+use super::triple::TripleData;
+
+#[derive(Debug, Serialize, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export)]
+pub struct QuarantineSummaryInfo {
+    pub origin: String,
+    #[ts(type = "number")]
+    pub triple_count: i64,
+    #[ts(type = "number")]
+    pub entity_count: i64,
+    pub classes: Vec<String>,
+    pub samples: Vec<TripleData>,
+    #[ts(type = "number | null")]
+    pub received_at: Option<i64>,
+}
+
+impl From<QuarantineSummary> for QuarantineSummaryInfo {
+    fn from(summary: QuarantineSummary) -> Self {
+        Self {
+            samples: summary.samples.iter().map(Into::into).collect(),
+            origin: summary.origin,
+            triple_count: summary.triple_count,
+            entity_count: summary.entity_count,
+            classes: summary.classes,
+            received_at: summary.received_at,
+        }
+    }
+}
+
+/// List quarantined imports awaiting review, with what they would add
+#[tauri::command]
+#[allow(non_snake_case)]
+pub async fn quarantine__list(
+    min_tx: Option<i64>,
+    executor: State<'_, DbExecutor>,
+) -> Result<Vec<QuarantineSummaryInfo>, String> {
+    executor.read(min_tx, move |conn| {
+        Ok(quarantine::review_queue(conn)
+            .map_err(|e| e.to_string())?
+            .into_iter()
+            .map(Into::into)
+            .collect())
+    }).await
+}
+
+/// Approve a quarantined import, making its triples visible to queries
+#[tauri::command]
+#[allow(non_snake_case)]
+pub async fn quarantine__approve(
+    origin: String,
+    dry_run: Option<bool>,
+    executor: State<'_, DbExecutor>,
+) -> Result<Committed<()>, String> {
+    executor.write_or_preview(dry_run, move |conn| {
+        quarantine::approve(conn, &origin)
+            .map_err(|e| format!("Failed to approve {}: {}", origin, e))
+    }).await
+}
+
+/// Reject a quarantined import, retracting its triples
+#[tauri::command]
+#[allow(non_snake_case)]
+pub async fn quarantine__reject(
+    origin: String,
+    dry_run: Option<bool>,
+    executor: State<'_, DbExecutor>,
+) -> Result<Committed<()>, String> {
+    executor.write_or_preview(dry_run, move |conn| {
+        quarantine::reject(conn, &origin)
+            .map(|_| ())
+            .map_err(|e| format!("Failed to reject {}: {}", origin, e))
+    }).await
+}
@@ -0,0 +1,49 @@
+use tauri::State;
+
+use crate::eavto::{get_by_predicate_range, DbExecutor, ValueRange, XsdType};
+use super::triple::TripleData;
+
+/// Triples of a predicate whose values fall in a range, smallest first
+/// kind: "integer", "number" or "dateTime" (the column compared)
+/// min, max: inclusive bounds, omitted for an open end; dateTime bounds are
+/// xsd:dateTime or xsd:date values
+#[tauri::command]
+#[allow(non_snake_case)]
+pub async fn query__range(
+    predicate: String,
+    kind: String,
+    min: Option<String>,
+    max: Option<String>,
+    min_tx: Option<i64>,
+    executor: State<'_, DbExecutor>,
+) -> Result<Vec<TripleData>, String> {
+    let range = match kind.as_str() {
+        "integer" => ValueRange::Integer(bound(min, parse_integer)?, bound(max, parse_integer)?),
+        "number" => ValueRange::Number(bound(min, parse_number)?, bound(max, parse_number)?),
+        "dateTime" => ValueRange::DateTime(bound(min, parse_moment)?, bound(max, parse_moment)?),
+        other => return Err(format!("Unknown range kind: {} (integer, number or dateTime)", other)),
+    };
+
+    executor.read(min_tx, move |conn| {
+        let result = get_by_predicate_range(conn, &predicate, range).map_err(|e| format!("{:?}", e))?;
+        Ok(result.triples.iter().map(TripleData::from).collect())
+    }).await
+}
+
+fn bound<T>(value: Option<String>, parse: fn(&str) -> Option<T>) -> Result<Option<T>, String> {
+    value
+        .map(|value| parse(value.trim()).ok_or_else(|| format!("Invalid range bound: {}", value)))
+        .transpose()
+}
+
+fn parse_integer(value: &str) -> Option<i64> {
+    value.parse().ok()
+}
+
+fn parse_number(value: &str) -> Option<f64> {
+    value.parse().ok().filter(|n: &f64| n.is_finite())
+}
+
+fn parse_moment(value: &str) -> Option<i64> {
+    XsdType::DateTime.epoch_millis(value).or_else(|| XsdType::Date.epoch_millis(value))
+}
@@ -0,0 +1,143 @@
+use serde::Serialize;
+use ts_rs::TS;
+use tauri::State;
+
+use crate::eavto::{Annotation, Committed, DbExecutor, Sandbox, SandboxRegistry};
+use crate::owl::{integrity, workspace};
+use super::entity::{load_entity, EntityData};
+use super::triple::TripleData;
+
+#[derive(Debug, Serialize, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export)]
+pub struct SandboxInfo {
+    pub handle: String,
+    pub origin: String,
+    #[ts(type = "number")]
+    pub base_tx: i64,
+    pub additions: Vec<TripleData>,
+    pub retractions: Vec<TripleData>,
+}
+
+impl From<&Sandbox> for SandboxInfo {
+    fn from(sandbox: &Sandbox) -> Self {
+        let (additions, retractions) = sandbox.pending();
+        Self {
+            handle: sandbox.id.clone(),
+            origin: sandbox.origin.clone(),
+            base_tx: sandbox.base_tx,
+            additions: additions.iter().map(Into::into).collect(),
+            retractions: retractions.iter().map(Into::into).collect(),
+        }
+    }
+}
+
+/// Begin a what-if session; changes made under the returned handle stay
+/// out of the store until sandbox__commit
+/// origin: origin the changes are committed under (default "user-edit")
+#[tauri::command]
+#[allow(non_snake_case)]
+pub async fn sandbox__begin(
+    origin: Option<String>,
+    executor: State<'_, DbExecutor>,
+    sandboxes: State<'_, SandboxRegistry>,
+) -> Result<SandboxInfo, String> {
+    let origin = origin.unwrap_or_else(|| "user-edit".to_string());
+    let handle = sandboxes.begin(&origin, executor.committed_tx());
+    sandboxes.update(&handle, |sandbox| SandboxInfo::from(&*sandbox))
+}
+
+/// Assert triples in a sandbox
+/// workspace: put the entities the triples describe in this workspace
+#[tauri::command]
+#[allow(non_snake_case)]
+pub async fn sandbox__assert(
+    handle: String,
+    triples: Vec<TripleData>,
+    workspace: Option<String>,
+    sandboxes: State<'_, SandboxRegistry>,
+) -> Result<SandboxInfo, String> {
+    let triples: Vec<_> = triples.iter().map(TripleData::to_triple).collect();
+    let triples = match workspace {
+        Some(workspace) => workspace::annotate(&triples, &workspace),
+        None => triples,
+    };
+
+    sandboxes.update(&handle, |sandbox| {
+        sandbox.assert(triples);
+        SandboxInfo::from(&*sandbox)
+    })
+}
+
+/// Retract triples in a sandbox (matched by subject, predicate and object)
+#[tauri::command]
+#[allow(non_snake_case)]
+pub async fn sandbox__retract(
+    handle: String,
+    triples: Vec<TripleData>,
+    sandboxes: State<'_, SandboxRegistry>,
+) -> Result<SandboxInfo, String> {
+    sandboxes.update(&handle, |sandbox| {
+        sandbox.retract(triples.iter().map(TripleData::to_triple).collect());
+        SandboxInfo::from(&*sandbox)
+    })
+}
+
+/// Get entity data as it would be after committing the sandbox
+/// Runs on the writer, since the overlay is applied and rolled back in the store
+#[tauri::command]
+#[allow(non_snake_case)]
+pub async fn sandbox__entity_get(
+    handle: String,
+    entity_id: String,
+    executor: State<'_, DbExecutor>,
+    sandboxes: State<'_, SandboxRegistry>,
+) -> Result<EntityData, String> {
+    let sandbox = sandboxes.get(&handle)?;
+
+    executor.write(move |conn| {
+        sandbox.with_overlay(conn, |conn| load_entity(conn, &entity_id).map_err(Into::into))
+            .map_err(|e| e.to_string())
+    }).await.map(|committed| committed.value)
+}
+
+/// Make a sandbox's changes permanent and close it
+/// message, tags: annotation shown in the history feed for each transaction
+/// dry_run: preview the commit; the sandbox stays open
+/// Returns the transaction IDs of the committed change sets
+#[tauri::command]
+#[allow(non_snake_case)]
+pub async fn sandbox__commit(
+    handle: String,
+    message: Option<String>,
+    tags: Option<Vec<String>>,
+    dry_run: Option<bool>,
+    executor: State<'_, DbExecutor>,
+    sandboxes: State<'_, SandboxRegistry>,
+) -> Result<Committed<Vec<i64>>, String> {
+    let sandbox = match dry_run {
+        Some(true) => sandboxes.get(&handle)?,
+        _ => sandboxes.close(&handle)?,
+    };
+    let annotation = Annotation::new(message, tags);
+
+    executor.write_or_preview(dry_run, move |conn| {
+        let (additions, _) = sandbox.pending();
+        let declarations = integrity::check_predicates(conn, &additions)
+            .map_err(|e| format!("Failed to commit {}: {}", handle, e))?;
+        let mut sandbox = sandbox;
+        sandbox.assert(declarations);
+        sandbox.commit(conn, &annotation)
+            .map_err(|e| format!("Failed to commit {}: {}", handle, e))
+    }).await
+}
+
+/// Discard a sandbox's changes and close it
+#[tauri::command]
+#[allow(non_snake_case)]
+pub async fn sandbox__discard(
+    handle: String,
+    sandboxes: State<'_, SandboxRegistry>,
+) -> Result<(), String> {
+    sandboxes.close(&handle).map(|_| ())
+}
@@ -0,0 +1,65 @@
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+use tauri::State;
+
+use crate::eavto::{Committed, DbExecutor};
+use crate::owl::startup::{IntegrityCheck, StartupSettings};
+
+/// How the app launches (applies from the next launch)
+#[derive(Debug, Serialize, Deserialize, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export)]
+pub struct StartupSettingsPayload {
+    pub skip_stats: bool,
+    pub defer_collectors: bool,
+    pub integrity_check: String, // "every-launch" or "weekly"
+    pub watch_ontology: bool,
+}
+
+impl From<StartupSettings> for StartupSettingsPayload {
+    fn from(settings: StartupSettings) -> Self {
+        Self {
+            skip_stats: settings.skip_stats,
+            defer_collectors: settings.defer_collectors,
+            integrity_check: settings.integrity_check.as_str().to_string(),
+            watch_ontology: settings.watch_ontology,
+        }
+    }
+}
+
+/// The startup settings
+#[tauri::command]
+#[allow(non_snake_case)]
+pub async fn settings__startup(
+    min_tx: Option<i64>,
+    executor: State<'_, DbExecutor>,
+) -> Result<StartupSettingsPayload, String> {
+    executor.read(min_tx, move |conn| {
+        StartupSettings::load(conn)
+            .map(StartupSettingsPayload::from)
+            .map_err(|e| e.to_string())
+    }).await
+}
+
+/// Set the startup settings: a fast launch (skip stats, defer collectors,
+/// weekly integrity checks) or a thorough one
+#[tauri::command]
+#[allow(non_snake_case)]
+pub async fn settings__set_startup(
+    settings: StartupSettingsPayload,
+    executor: State<'_, DbExecutor>,
+) -> Result<Committed<StartupSettingsPayload>, String> {
+    let settings = StartupSettings {
+        skip_stats: settings.skip_stats,
+        defer_collectors: settings.defer_collectors,
+        integrity_check: IntegrityCheck::parse(&settings.integrity_check)
+            .ok_or_else(|| format!("Unknown integrity check {}", settings.integrity_check))?,
+        watch_ontology: settings.watch_ontology,
+    };
+
+    executor.write(move |conn| {
+        settings.save(conn, "user-edit")
+            .map(|_| StartupSettingsPayload::from(settings))
+            .map_err(|e| format!("Failed to save startup settings: {}", e))
+    }).await
+}
@@ -1,12 +1,14 @@
 use serde::Serialize;
+use ts_rs::TS;
 use tauri::State;
 use rusqlite::Connection;
 
-use crate::eavto::DbExecutor;
+use crate::eavto::{Committed, DbExecutor};
 use crate::owl::{Individual, Object};
 
-#[derive(Debug, Serialize, serde::Deserialize)]
+#[derive(Debug, Serialize, serde::Deserialize, TS)]
 #[serde(rename_all = "camelCase")]
+#[ts(export)]
 pub struct SetupResult {
     pub already_setup: bool,
     pub user: UserInfo,
@@ -14,33 +16,39 @@ pub struct SetupResult {
     pub foundation: FoundationInfo,
 }
 
-#[derive(Debug, Serialize, serde::Deserialize)]
+#[derive(Debug, Serialize, serde::Deserialize, TS)]
 #[serde(rename_all = "camelCase")]
+#[ts(export)]
 pub struct UserInfo {
     pub iri: String,
     pub name: String,
     pub email: Option<String>,
 }
 
-#[derive(Debug, Serialize, serde::Deserialize)]
+#[derive(Debug, Serialize, serde::Deserialize, TS)]
 #[serde(rename_all = "camelCase")]
+#[ts(export)]
 pub struct ProcessorInfo {
     pub iri: String,
     pub model: String,
+    #[ts(type = "number | null")]
     pub cores: Option<i64>,
     pub architecture: String,
 }
 
-#[derive(Debug, Serialize, serde::Deserialize)]
+#[derive(Debug, Serialize, serde::Deserialize, TS)]
 #[serde(rename_all = "camelCase")]
+#[ts(export)]
 pub struct MemoryInfo {
     pub iri: String,
+    #[ts(type = "number")]
     pub capacity_gb: i64,
     pub memory_type: String,
 }
 
-#[derive(Debug, Serialize, serde::Deserialize)]
+#[derive(Debug, Serialize, serde::Deserialize, TS)]
 #[serde(rename_all = "camelCase")]
+#[ts(export)]
 pub struct OperatingSystemInfo {
     pub iri: String,
     pub name: String,
@@ -48,8 +56,9 @@ pub struct OperatingSystemInfo {
     pub kernel: String,
 }
 
-#[derive(Debug, Serialize, serde::Deserialize)]
+#[derive(Debug, Serialize, serde::Deserialize, TS)]
 #[serde(rename_all = "camelCase")]
+#[ts(export)]
 pub struct ComputerInfo {
     pub iri: String,
     pub hostname: String,
@@ -58,16 +67,18 @@ pub struct ComputerInfo {
     pub memory: MemoryInfo,
 }
 
-#[derive(Debug, Serialize, serde::Deserialize)]
+#[derive(Debug, Serialize, serde::Deserialize, TS)]
 #[serde(rename_all = "camelCase")]
+#[ts(export)]
 pub struct SoftwareReleaseInfo {
     pub iri: String,
     pub version_number: String,
     pub license_type: Option<String>,
 }
 
-#[derive(Debug, Serialize, serde::Deserialize)]
+#[derive(Debug, Serialize, serde::Deserialize, TS)]
 #[serde(rename_all = "camelCase")]
+#[ts(export)]
 pub struct FoundationInfo {
     pub iri: String,
     pub release: SoftwareReleaseInfo,
@@ -77,9 +88,10 @@ pub struct FoundationInfo {
 #[tauri::command]
 #[allow(non_snake_case)]
 pub async fn setup__check(
+    min_tx: Option<i64>,
     executor: State<'_, DbExecutor>,
 ) -> Result<bool, String> {
-    executor.read(|conn| {
+    executor.read(min_tx, |conn| {
         let foundation_instance = Individual::new("foundation:ThisFoundationInstance");
         foundation_instance.exists(conn)
             .map_err(|e| format!("Failed to check setup status: {}", e))
@@ -94,9 +106,9 @@ pub async fn setup__init(
     user_name: String,
     email: Option<String>,
     executor: State<'_, DbExecutor>,
-) -> Result<SetupResult, String> {
+) -> Result<Committed<SetupResult>, String> {
     // Setup involves writes, so we use the write executor
-    let result_json = executor.write(move |conn| {
+    executor.write(move |conn| {
 
     // Don't check again - assume caller used setup__check first
 
@@ -300,11 +312,8 @@ pub async fn setup__init(
         },
     };
 
-    serde_json::to_string(&result).map_err(|e| e.to_string())
-    }).await?;
-
-    // Deserialize the result
-    serde_json::from_str(&result_json).map_err(|e| e.to_string())
+    Ok(result)
+    }).await
 }
 
 // REMOVED: get_existing_setup function was only used in tests and doesn't match
@@ -1,7 +1,9 @@
 use serde::{Serialize, Deserialize};
+use ts_rs::TS;
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, TS)]
 #[serde(rename_all = "camelCase")]
+#[ts(export)]
 pub struct KeyboardShortcut {
     pub keys: String,
     pub label: String,
@@ -9,8 +11,9 @@ pub struct KeyboardShortcut {
 
 /// Get all registered keyboard shortcuts
 #[tauri::command]
-pub fn shortcuts__get_all() -> String {
-    let shortcuts = vec![
+#[allow(non_snake_case)]
+pub fn shortcuts__get_all() -> Vec<KeyboardShortcut> {
+    vec![
         KeyboardShortcut {
             keys: "CMD+F".to_string(),
             label: "Search".to_string(),
@@ -23,8 +26,6 @@ pub fn shortcuts__get_all() -> String {
             keys: "CMD+R".to_string(),
             label: "Reload".to_string(),
         },
-    ];
-
-    serde_json::to_string(&shortcuts).unwrap_or_else(|_| "[]".to_string())
+    ]
 }
 
@@ -0,0 +1,75 @@
+use serde::Serialize;
+use ts_rs::TS;
+use tauri::State;
+
+use crate::eavto::{stats, DbExecutor};
+use crate::owl::Thing;
+
+#[derive(Debug, Serialize, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export)]
+pub struct ClassStats {
+    pub iri: String,
+    pub label: String,
+    #[ts(type = "number")]
+    pub instances: u64, // Asserted rdf:type, subclasses not included
+}
+
+#[derive(Debug, Serialize, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export)]
+pub struct PropertyStats {
+    pub iri: String,
+    pub label: String,
+    #[ts(type = "number")]
+    pub uses: u64,
+    #[ts(type = "number")]
+    pub subjects: u64,
+    #[ts(type = "number")]
+    pub distinct_values: u64,
+}
+
+#[derive(Debug, Serialize, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export)]
+pub struct OntologyStats {
+    pub classes: Vec<ClassStats>,       // Most instances first
+    pub properties: Vec<PropertyStats>, // Most used first
+}
+
+/// What's in the store: instances per class, and uses, subjects and
+/// distinct values per property (current triples only)
+#[tauri::command]
+#[allow(non_snake_case)]
+pub async fn stats__ontology(
+    min_tx: Option<i64>,
+    executor: State<'_, DbExecutor>,
+) -> Result<OntologyStats, String> {
+    executor.read(min_tx, move |conn| {
+        let classes = stats::class_counts(conn).map_err(|e| format!("{:?}", e))?;
+        let properties = stats::property_counts(conn).map_err(|e| format!("{:?}", e))?;
+
+        let iris: Vec<&str> = classes.iter().map(|c| c.class.as_str())
+            .chain(properties.iter().map(|p| p.property.as_str()))
+            .collect();
+        let labels: std::collections::HashMap<String, String> = Thing::get_many(conn, &iris)
+            .into_iter()
+            .map(|thing| (thing.iri, thing.label))
+            .collect();
+
+        Ok(OntologyStats {
+            classes: classes.iter()
+                .map(|c| ClassStats { iri: c.class.clone(), label: labels[&c.class].clone(), instances: c.instances })
+                .collect(),
+            properties: properties.iter()
+                .map(|p| PropertyStats {
+                    iri: p.property.clone(),
+                    label: labels[&p.property].clone(),
+                    uses: p.uses,
+                    subjects: p.subjects,
+                    distinct_values: p.distinct_values,
+                })
+                .collect(),
+        })
+    }).await
+}
@@ -0,0 +1,208 @@
+use serde::Serialize;
+use ts_rs::TS;
+use tauri::State;
+
+use crate::eavto::{backfill, blob, connection, stats::{self, StorageUsage}, Committed, DbExecutor, Object};
+
+/// Content of a large literal kept in the blob store
+#[derive(Debug, Serialize, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export)]
+pub struct BlobContent {
+    pub value: String,
+    pub datatype: Option<String>,
+    pub language: Option<String>,
+}
+
+/// A literal whose typed column couldn't be filled: its value isn't valid
+/// for its datatype
+#[derive(Debug, Serialize, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export)]
+pub struct UnfixableLiteral {
+    pub subject: String,
+    pub predicate: String,
+    pub value: String,
+    pub datatype: String,
+    #[ts(type = "number")]
+    pub tx: i64,
+}
+
+/// What a typed column backfill did
+#[derive(Debug, Serialize, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export)]
+pub struct TypedColumnBackfill {
+    #[ts(type = "number")]
+    pub scanned: usize,
+    #[ts(type = "number")]
+    pub fixed: usize,
+    pub unfixable: Vec<UnfixableLiteral>,
+}
+
+/// Size of the database file around a vacuum
+#[derive(Debug, Serialize, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export)]
+pub struct VacuumResult {
+    #[ts(type = "number")]
+    pub bytes_before: i64,
+    #[ts(type = "number")]
+    pub bytes_after: i64,
+}
+
+/// Storage used by an origin or a workspace
+#[derive(Debug, Serialize, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export)]
+pub struct StorageUsageInfo {
+    pub name: String, // Origin name, or workspace IRI
+    #[ts(type = "number")]
+    pub rows: u64,
+    #[ts(type = "number")]
+    pub active_rows: u64,
+    #[ts(type = "number")]
+    pub estimated_bytes: u64,
+    #[ts(type = "number | null")]
+    pub quota_bytes: Option<u64>,
+    pub over_quota: bool,
+}
+
+impl From<StorageUsage> for StorageUsageInfo {
+    fn from(usage: StorageUsage) -> Self {
+        Self {
+            over_quota: usage.over_quota(),
+            name: usage.name,
+            rows: usage.rows,
+            active_rows: usage.active_rows,
+            estimated_bytes: usage.estimated_bytes,
+            quota_bytes: usage.quota_bytes,
+        }
+    }
+}
+
+/// Storage accounting, largest first
+#[derive(Debug, Serialize, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export)]
+pub struct StorageUsageReport {
+    pub origins: Vec<StorageUsageInfo>,
+    pub workspaces: Vec<StorageUsageInfo>,
+}
+
+/// Largest literal kept in the triples table, in bytes (null: no limit)
+/// Larger literals are stored in the blob store, behind a blob: reference
+#[tauri::command]
+#[allow(non_snake_case)]
+pub async fn storage__literal_limit(
+    min_tx: Option<i64>,
+    executor: State<'_, DbExecutor>,
+) -> Result<Option<usize>, String> {
+    executor.read(min_tx, move |conn| {
+        blob::literal_limit(conn).map_err(|e| e.to_string())
+    }).await
+}
+
+/// Set the literal size limit in bytes (0: no limit; null: the default)
+/// Applies to literals written from now on
+#[tauri::command]
+#[allow(non_snake_case)]
+pub async fn storage__set_literal_limit(
+    bytes: Option<usize>,
+    executor: State<'_, DbExecutor>,
+) -> Result<Committed<Option<usize>>, String> {
+    executor.write(move |conn| {
+        blob::set_literal_limit(conn, bytes)
+            .and_then(|_| blob::literal_limit(conn))
+            .map_err(|e| format!("Failed to set literal limit: {}", e))
+    }).await
+}
+
+/// Get the literal a blob: reference stands for
+#[tauri::command]
+#[allow(non_snake_case)]
+pub async fn storage__blob(
+    iri: String,
+    min_tx: Option<i64>,
+    executor: State<'_, DbExecutor>,
+) -> Result<BlobContent, String> {
+    executor.read(min_tx, move |conn| {
+        match blob::get(conn, &iri).map_err(|e| e.to_string())? {
+            Some(Object::Literal { value, datatype, language }) => Ok(BlobContent { value, datatype, language }),
+            _ => Err(format!("Blob {} not found", iri)),
+        }
+    }).await
+}
+
+/// Fill the typed columns (number, integer, boolean, dateTime) of literals
+/// stored without them, and report the literals whose value doesn't parse
+/// With dry_run, only reports what would be filled
+#[tauri::command]
+#[allow(non_snake_case)]
+pub async fn storage__backfill_typed_columns(
+    dry_run: Option<bool>,
+    executor: State<'_, DbExecutor>,
+) -> Result<Committed<TypedColumnBackfill>, String> {
+    executor.write_or_preview(dry_run, move |conn| {
+        let report = backfill::backfill_typed_columns(conn)
+            .map_err(|e| format!("Failed to backfill typed columns: {}", e))?;
+        Ok(TypedColumnBackfill {
+            scanned: report.scanned,
+            fixed: report.fixed,
+            unfixable: report.unfixable.into_iter()
+                .map(|row| UnfixableLiteral {
+                    subject: row.subject,
+                    predicate: row.predicate,
+                    value: row.value,
+                    datatype: row.datatype,
+                    tx: row.tx,
+                })
+                .collect(),
+        })
+    }).await
+}
+
+/// Rows and estimated bytes per origin and per workspace, with the origins'
+/// soft quotas
+#[tauri::command]
+#[allow(non_snake_case)]
+pub async fn storage__usage(
+    min_tx: Option<i64>,
+    executor: State<'_, DbExecutor>,
+) -> Result<StorageUsageReport, String> {
+    executor.read(min_tx, move |conn| {
+        let origins = stats::origin_usage(conn).map_err(|e| format!("{:?}", e))?;
+        let workspaces = stats::workspace_usage(conn).map_err(|e| format!("{:?}", e))?;
+        Ok(StorageUsageReport {
+            origins: origins.into_iter().map(Into::into).collect(),
+            workspaces: workspaces.into_iter().map(Into::into).collect(),
+        })
+    }).await
+}
+
+/// Set the soft quota of an origin in estimated bytes (null: unlimited)
+/// Writes over it still go through; a warning is logged
+#[tauri::command]
+#[allow(non_snake_case)]
+pub async fn storage__set_quota(
+    origin: String,
+    quota_bytes: Option<u64>,
+    executor: State<'_, DbExecutor>,
+) -> Result<Committed<()>, String> {
+    executor.write(move |conn| {
+        stats::set_quota(conn, &origin, quota_bytes)
+            .map_err(|e| format!("Failed to set quota of {}: {:?}", origin, e))
+    }).await
+}
+
+/// Rebuild the database file to reclaim the space of deleted rows and
+/// indexes (the store keeps retracted triples: history isn't removed)
+/// Blocks writes while it runs, which on a large database takes a while
+#[tauri::command]
+#[allow(non_snake_case)]
+pub async fn db__vacuum(executor: State<'_, DbExecutor>) -> Result<Committed<VacuumResult>, String> {
+    executor.write(move |conn| {
+        let report = connection::vacuum(conn).map_err(|e| format!("Failed to vacuum the database: {:?}", e))?;
+        Ok(VacuumResult { bytes_before: report.bytes_before, bytes_after: report.bytes_after })
+    }).await
+}
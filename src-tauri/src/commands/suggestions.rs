@@ -0,0 +1,51 @@
+use serde::Serialize;
+use ts_rs::TS;
+use tauri::State;
+
+use crate::eavto::DbExecutor;
+use crate::owl::suggestions::{self, Suggestion};
+
+#[derive(Debug, Serialize, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export)]
+pub struct SuggestionInfo {
+    pub kind: String, // "create_entity" | "classify_entities" | "define_class"
+    pub message: String,
+    #[ts(type = "number")]
+    pub count: usize,
+    pub entities: Vec<String>,
+    pub predicates: Vec<String>,
+    pub value: Option<String>,
+    pub existing: Option<String>,
+}
+
+impl From<Suggestion> for SuggestionInfo {
+    fn from(suggestion: Suggestion) -> Self {
+        Self {
+            kind: suggestion.kind.as_str().to_string(),
+            message: suggestion.message,
+            count: suggestion.count,
+            entities: suggestion.entities,
+            predicates: suggestion.predicates,
+            value: suggestion.value,
+            existing: suggestion.existing,
+        }
+    }
+}
+
+/// Get modeling suggestions from patterns in user data (repeated values,
+/// untyped entities, properties that keep appearing together)
+#[tauri::command]
+#[allow(non_snake_case)]
+pub async fn suggestions__get(
+    min_tx: Option<i64>,
+    executor: State<'_, DbExecutor>,
+) -> Result<Vec<SuggestionInfo>, String> {
+    executor.read(min_tx, move |conn| {
+        Ok(suggestions::suggest(conn)
+            .map_err(|e| e.to_string())?
+            .into_iter()
+            .map(Into::into)
+            .collect())
+    }).await
+}
@@ -0,0 +1,43 @@
+use tauri::State;
+
+use crate::eavto::DbExecutor;
+use crate::sync::p2p::{self, Peer, SyncStatus};
+
+/// Start syncing with other instances on the local network: announce this
+/// one, find the others and pull the changesets they have
+/// Does nothing if sync is already running
+#[tauri::command]
+#[allow(non_snake_case)]
+pub async fn sync__start(
+    executor: State<'_, DbExecutor>,
+    service: State<'_, p2p::Service>,
+) -> Result<SyncStatus, String> {
+    let name = hostname::get()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_else(|_| "FOUNDATION".to_string());
+    let (executor, service) = (executor.inner().clone(), service.inner().clone());
+
+    tokio::task::spawn_blocking(move || {
+        service.start(executor, &name).map_err(|e| format!("Failed to start sync: {}", e))
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+/// State of sync: whether it runs, on which port, what it received
+#[tauri::command]
+#[allow(non_snake_case)]
+pub async fn sync__status(
+    service: State<'_, p2p::Service>,
+) -> Result<SyncStatus, String> {
+    Ok(service.status())
+}
+
+/// Other instances found on the local network, with how far they were pulled
+#[tauri::command]
+#[allow(non_snake_case)]
+pub async fn sync__peers(
+    service: State<'_, p2p::Service>,
+) -> Result<Vec<Peer>, String> {
+    Ok(service.peers())
+}
@@ -0,0 +1,65 @@
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+
+use crate::eavto::{statement, Object, Triple};
+
+/// A triple as sent to and from the frontend
+/// object is an IRI (or "_:" blank node) unless a datatype or language is given
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export)]
+pub struct TripleData {
+    pub subject: String,
+    pub predicate: String,
+    pub object: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub datatype: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub language: Option<String>,
+}
+
+impl TripleData {
+    pub fn to_triple(&self) -> Triple {
+        Triple::new(&self.subject, &self.predicate, to_object(&self.object, &self.datatype, &self.language))
+    }
+}
+
+/// Object from its frontend form: an IRI (or "_:" blank node, or "<<( s p o )>>"
+/// quoted triple) unless a datatype or language is given
+pub fn to_object(object: &str, datatype: &Option<String>, language: &Option<String>) -> Object {
+    match (datatype, language) {
+        (None, None) if object.starts_with("_:") => Object::Blank(object.to_string()),
+        (None, None) => match statement::parse(object) {
+            Some(quoted) => Object::Triple(Box::new(quoted)),
+            None => Object::Iri(object.to_string()),
+        },
+        (datatype, language) => Object::Literal {
+            value: object.to_string(),
+            datatype: datatype.clone(),
+            language: language.clone(),
+        },
+    }
+}
+
+impl From<&Triple> for TripleData {
+    fn from(triple: &Triple) -> Self {
+        let (datatype, language) = match &triple.object {
+            Object::Iri(_) | Object::Blank(_) | Object::Triple(_) => (None, None),
+            Object::Literal { language: Some(language), .. } => (None, Some(language.clone())),
+            other => (Some(other.datatype().unwrap_or("xsd:string").to_string()), None),
+        };
+        Self {
+            subject: triple.subject.clone(),
+            predicate: triple.predicate.clone(),
+            object: match &triple.object {
+                Object::Triple(quoted) => Some(quoted.term()),
+                object => object.as_iri().map(|s| s.to_string()).or_else(|| object.as_literal()),
+            }
+                .unwrap_or_default(),
+            datatype,
+            language,
+        }
+    }
+}
@@ -0,0 +1,63 @@
+use serde::Serialize;
+use ts_rs::TS;
+use tauri::State;
+
+use crate::eavto::DbExecutor;
+use crate::owl::consistency;
+
+/// Violations per page when the caller gives no limit
+const CONSISTENCY_PAGE: usize = 100;
+
+/// An individual contradicting an axiom of the ontology
+#[derive(Debug, Serialize, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export)]
+pub struct ConsistencyViolation {
+    pub kind: String, // "disjoint-classes", "functional-property" or "asymmetric-property"
+    pub entity: String,
+    pub axiom: String,       // First class of the disjoint pair, or the property
+    pub values: Vec<String>, // Classes typed with, distinct values, or the entity linked back
+    pub message: String,
+}
+
+/// A page of the consistency report
+#[derive(Debug, Serialize, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export)]
+pub struct ConsistencyReport {
+    pub violations: Vec<ConsistencyViolation>,
+    #[ts(type = "number")]
+    pub total: usize,
+}
+
+/// Check the data against the ontology: individuals typed with disjoint
+/// classes, several values of a functional property, and asymmetric
+/// properties linking two individuals both ways
+#[tauri::command]
+#[allow(non_snake_case)]
+pub async fn validation__consistency(
+    offset: Option<usize>,
+    limit: Option<usize>,
+    min_tx: Option<i64>,
+    executor: State<'_, DbExecutor>,
+) -> Result<ConsistencyReport, String> {
+    executor.read(min_tx, move |conn| {
+        let violations = consistency::check(conn).map_err(|e| e.to_string())?;
+        let total = violations.len();
+
+        Ok(ConsistencyReport {
+            violations: violations.into_iter()
+                .skip(offset.unwrap_or(0))
+                .take(limit.unwrap_or(CONSISTENCY_PAGE))
+                .map(|violation| ConsistencyViolation {
+                    kind: violation.kind.as_str().to_string(),
+                    message: violation.message(),
+                    entity: violation.entity,
+                    axiom: violation.axiom,
+                    values: violation.values,
+                })
+                .collect(),
+            total,
+        })
+    }).await
+}
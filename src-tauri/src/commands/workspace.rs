@@ -0,0 +1,68 @@
+use serde::Serialize;
+use ts_rs::TS;
+use tauri::State;
+
+use crate::eavto::{Committed, DbExecutor};
+use crate::owl::workspace;
+
+#[derive(Debug, Serialize, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export)]
+pub struct WorkspaceInfo {
+    pub iri: String,
+    pub label: String,
+    pub active: bool,
+}
+
+/// List workspaces, marking the active one
+#[tauri::command]
+#[allow(non_snake_case)]
+pub async fn workspace__list(
+    min_tx: Option<i64>,
+    executor: State<'_, DbExecutor>,
+) -> Result<Vec<WorkspaceInfo>, String> {
+    executor.read(min_tx, move |conn| {
+        let active = workspace::active(conn).map_err(|e| e.to_string())?;
+        Ok(workspace::list(conn)
+            .map_err(|e| e.to_string())?
+            .into_iter()
+            .map(|w| WorkspaceInfo {
+                active: active.as_deref() == Some(w.iri.as_str()),
+                iri: w.iri,
+                label: w.label,
+            })
+            .collect())
+    }).await
+}
+
+/// Switch the active workspace; queries only show entities in it (or in none)
+/// workspace: workspace IRI, or null to show all workspaces
+#[tauri::command]
+#[allow(non_snake_case)]
+pub async fn workspace__switch(
+    workspace: Option<String>,
+    dry_run: Option<bool>,
+    executor: State<'_, DbExecutor>,
+) -> Result<Committed<()>, String> {
+    executor.write_or_preview(dry_run, move |conn| {
+        workspace::switch(conn, workspace.as_deref())
+            .map_err(|e| format!("Failed to switch workspace: {}", e))
+    }).await
+}
+
+/// Move an entity to a workspace
+/// workspace: workspace IRI, or null to make the entity visible in all workspaces
+#[tauri::command]
+#[allow(non_snake_case)]
+pub async fn workspace__assign(
+    entity_id: String,
+    workspace: Option<String>,
+    dry_run: Option<bool>,
+    executor: State<'_, DbExecutor>,
+) -> Result<Committed<()>, String> {
+    executor.write_or_preview(dry_run, move |conn| {
+        workspace::assign(conn, &entity_id, workspace.as_deref(), "user-edit")
+            .map(|_| ())
+            .map_err(|e| format!("Failed to assign {}: {}", entity_id, e))
+    }).await
+}
@@ -1,131 +1,114 @@
-mod turtle;
-mod namespaces;
 mod commands;
-mod eavto;
-mod owl;
+mod api;
 
-use std::sync::Mutex;
-
-// Triple structure for serialization (maps to triples table)
-#[derive(serde::Serialize)]
-pub struct TripleData {
-    subject: String,
-    predicate: String,
-    object: Option<String>,           // IRI or blank node (if object_type = 'iri' or 'blank')
-    object_value: Option<String>,      // Literal value (if object_type = 'literal')
-    object_type: String,               // 'iri', 'literal', or 'blank'
-    object_datatype: Option<String>,   // Datatype IRI for literals
-    tx: i64,
-    origin_id: i64,
-    retracted: bool,
-}
-
-// Simplified triple structure for UI display
-#[derive(serde::Serialize, serde::Deserialize)]
-pub struct DisplayTriple {
-    a: String,            // attribute (predicate)
-    v: String,            // value (object or object_value)
-    v_type: String,       // value type: 'ref' for IRIs, 'literal' for literals
-    v_label: Option<String>, // optional label for the value (when v_type is 'ref')
-    v_icon: Option<String>, // optional icon for the value (when v_type is 'ref')
-    a_comment: Option<String>, // optional comment for the predicate
-    domain: Option<String>, // optional domain class IRI for the predicate
-    domain_label: Option<String>, // optional label for the domain class
-    domain_icon: Option<String>, // optional icon for the domain class
-}
-
-// Graph structures for ontology visualization
-#[derive(serde::Serialize)]
-pub struct GraphNode {
-    id: String,
-    label: String,
-    group: i32, // 1=Class, 6=Instance
-    icon: Option<String>, // Material Symbols icon name
-}
-
-#[derive(serde::Serialize)]
-pub struct GraphLink {
-    source: String,
-    target: String,
-    label: String,
-}
+// The store, OWL layer and importers live in foundation-core; the app is
+// the commands over them
+use foundation_core::{eavto, owl, turtle, export, outbox, identity, sync};
+#[cfg(feature = "fixtures")]
+use foundation_core::fixtures;
 
-#[derive(serde::Serialize)]
-pub struct GraphData {
-    nodes: Vec<GraphNode>,
-    links: Vec<GraphLink>,
-    central_node_id: String,
-}
+use std::sync::Mutex;
+use std::time::Duration;
 
-// Search result structure
-#[derive(serde::Serialize, serde::Deserialize)]
-pub struct SearchResult {
-    id: String,
-    label: String,
-    definition: Option<String>,
-    group: i32,
-    score: f32,
-}
+/// How long after launch deferred collectors start
+const COLLECTORS_DELAY: Duration = Duration::from_secs(30);
 
-#[derive(serde::Serialize)]
-pub struct NodeStatistics {
-    children_count: i64,
-    backlinks_count: i64,
-    synonyms_count: i64,
-    related_count: i64,
-    examples_count: i64,
-}
+/// Forwards import progress to the frontend as events: "import-progress",
+/// "import-summary" and "import-complete"
+struct ImportEvents(tauri::AppHandle);
 
-// Setup wizard data structures
-#[derive(serde::Serialize, serde::Deserialize)]
-pub struct SetupData {
-    person_name: String,
-    person_email: Option<String>,
-    computer_name: String,
-    computer_processor: Option<String>,
-    computer_memory: Option<i32>,
-}
+impl foundation_core::ImportObserver for ImportEvents {
+    fn progress(&self, progress: foundation_core::ImportProgress) {
+        use tauri::Emitter;
+        let _ = self.0.emit("import-progress", progress);
+    }
 
-#[derive(serde::Serialize)]
-pub struct SystemInfo {
-    hostname: String,
-    os_name: String,
-    os_version: String,
-    cpu_brand: String,
-    cpu_cores: usize,
-    total_memory_gb: f64,
-}
+    fn summary(&self, summary: foundation_core::ImportSummary) {
+        use tauri::Emitter;
+        let _ = self.0.emit("import-summary", summary);
+    }
 
-// Import progress tracking
-#[derive(Clone, serde::Serialize)]
-pub struct ImportProgress {
-    pub stage: String,       // "core", "dtype", "foundation"
-    pub current_file: String, // Nome do arquivo sendo importado
-    pub current: u32,        // Arquivo atual (1-based)
-    pub total: u32,          // Total de arquivos
-    pub triples: u64,        // Total de triples importados até agora
+    fn complete(&self) {
+        use tauri::Emitter;
+        let _ = self.0.emit("import-complete", ());
+    }
 }
 
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
     use tauri::{Manager, Emitter};
 
-    tauri::Builder::default()
+    let builder = tauri::Builder::default();
+
+    // Mobile: run queued work as soon as the app is back in the foreground
+    #[cfg(mobile)]
+    let builder = builder.on_window_event(|_, event| {
+        if let tauri::WindowEvent::Focused(true) = event {
+            outbox::worker::wake();
+        }
+    });
+
+    builder
         .plugin(tauri_plugin_opener::init())
+        .manage(eavto::SandboxRegistry::default())
+        .manage(commands::DemoProfile::default())
+        .manage(sync::p2p::Service::default())
         .setup(|app| {
+            // Mobile: the databases and the ontology files live in the app
+            // sandbox (no Documents directory, no source tree)
+            #[cfg(mobile)]
+            {
+                let data_dir = app.path().app_data_dir()?;
+                let ontology_dir = data_dir.join("core-ontology");
+                turtle::bundled::unpack(&ontology_dir)?;
+                turtle::set_ontology_dir(ontology_dir);
+                eavto::set_data_dir(data_dir);
+            }
+
             // Initialize database with event emission
             let app_handle = app.handle().clone();
 
             std::thread::spawn(move || {
+                // An encrypted database opens with the key in the OS keychain,
+                // or waits for its passphrase (db__unlock)
+                if let Ok(path) = eavto::get_db_path() {
+                    if eavto::encryption::is_encrypted(&path)
+                        && !eavto::encryption::unlock_from_keychain(&path).unwrap_or(false)
+                    {
+                        commands::log_backend(&app_handle, "info", "Database is encrypted, waiting for its passphrase");
+                        let _ = app_handle.emit("database-locked", ());
+                        eavto::encryption::wait_unlocked();
+                    }
+                }
+
+                // Startup settings, read from the database file before it opens
+                let startup = eavto::get_db_path()
+                    .map(|path| owl::startup::StartupSettings::read(&path))
+                    .unwrap_or_default();
+
                 commands::log_backend(&app_handle, "info", "Database initialization starting...");
 
-                match eavto::initialize_with_progress(app_handle.clone()) {
-                    Ok(conn) => {
+                match eavto::initialize_with_progress(&ImportEvents(app_handle.clone())) {
+                    Ok(mut conn) => {
                         println!("Database initialized successfully");
                         commands::log_backend(&app_handle, "info", "Database initialized successfully");
 
-                        // Print database stats
-                        if let Ok(stats) = eavto::get_stats(&conn) {
+                        // Check the database integrity (every launch, or weekly)
+                        let now = chrono::Utc::now().timestamp_millis();
+                        match startup.integrity_check_due(&conn, now) {
+                            Ok(true) => match owl::startup::check_integrity(&conn, now) {
+                                Ok(problems) if problems.is_empty() => commands::log_backend(&app_handle, "info", "Database integrity check passed"),
+                                Ok(problems) => commands::log_backend(&app_handle, "error", &format!("Database integrity check failed: {}", problems.join("; "))),
+                                Err(e) => commands::log_backend(&app_handle, "error", &format!("Database integrity check could not run: {}", e)),
+                            },
+                            Ok(false) => {}
+                            Err(e) => commands::log_backend(&app_handle, "error", &format!("Database integrity check could not run: {}", e)),
+                        }
+
+                        // Print database stats (they count every fact; fast starts skip them)
+                        if startup.skip_stats {
+                            commands::log_backend(&app_handle, "info", "Database stats skipped (fast start)");
+                        } else if let Ok(stats) = eavto::get_stats(&conn) {
                             println!("Database stats:");
                             println!("  Total triples: {}", stats.total_facts);
                             println!("  Active triples: {}", stats.active_facts);
@@ -139,8 +122,55 @@ pub fn run() {
                             commands::log_backend(&app_handle, "info", &stats_msg);
                         }
 
+                        // Local identity: a key pair kept in the OS keychain (created on
+                        // first run), its public key published on foundation:ThisUser
+                        match identity::Identity::load_or_create(&identity::Keychain) {
+                            Ok(local) => {
+                                if let Err(e) = identity::publish(&mut conn, &local) {
+                                    commands::log_backend(&app_handle, "error", &format!("Failed to publish the public key: {}", e));
+                                }
+                                // Sign what this device asserts from now on
+                                identity::set_local(local.clone());
+                                app_handle.manage(local);
+                            }
+                            Err(e) => commands::log_backend(&app_handle, "error", &format!("Identity unavailable: {}", e)),
+                        }
+
                         // Create async executor and store in state
                         let executor = eavto::DbExecutor::new(conn);
+
+                        // Serve the HTTP API to local scripts (FOUNDATION_API_ADDRESS=off disables it)
+                        if let Some(address) = api::server::address() {
+                            match api::server::start(executor.clone(), &address) {
+                                Ok(bound) => commands::log_backend(&app_handle, "info", &format!("HTTP API listening on {}", bound)),
+                                Err(e) => commands::log_backend(&app_handle, "error", &format!("HTTP API failed to start on {}: {}", address, e)),
+                            }
+                        }
+
+                        // Background collectors: run queued outbound work (fetches that
+                        // waited for the network) and, when the setting is on, re-import
+                        // core-ontology files as they are saved. Fast starts defer them
+                        // so the launch has the disk to itself
+                        let collectors_executor = executor.clone();
+                        let reload_handle = app_handle.clone();
+                        let watch_ontology = startup.watch_ontology;
+                        let start_collectors = move || {
+                            outbox::worker::start(collectors_executor.clone());
+                            if watch_ontology {
+                                turtle::watch::start(collectors_executor, move |reload| {
+                                    let _ = reload_handle.emit("ontology-reloaded", reload);
+                                });
+                            }
+                        };
+                        if startup.defer_collectors {
+                            std::thread::spawn(move || {
+                                std::thread::sleep(COLLECTORS_DELAY);
+                                start_collectors();
+                            });
+                        } else {
+                            start_collectors();
+                        }
+
                         app_handle.manage(executor);
 
                         // Emit completion event
@@ -157,16 +187,145 @@ pub fn run() {
 
             Ok(())
         })
-        .invoke_handler(tauri::generate_handler![
-            commands::setup__check,
-            commands::setup__init,
-            commands::entity__get,
-            commands::entity__search,
-            commands::shortcuts__get_all,
-            commands::log_frontend,
-            commands::get_log_file_path_command,
-            commands::clear_logs
-        ])
+        .invoke_handler({
+            let handler = tauri::generate_handler![
+                commands::setup__check,
+                commands::setup__init,
+                commands::entity__get,
+                commands::entity__backlinks,
+                commands::entity__search,
+                commands::entity__create,
+                commands::entity__set_property,
+                commands::entity__retract,
+                commands::graph__neighborhood,
+                commands::graph__find_path,
+                commands::class__export,
+                commands::class__add_restriction,
+                commands::class__instances,
+                commands::ontology__export_rdfxml,
+                commands::ontology__import_file,
+                commands::ontology__list_ontologies,
+                commands::ontology__tree,
+                commands::ontology__list_releases,
+                commands::ontology__release_for_fact,
+                commands::ontology__list_upgrades,
+                commands::ontology__list_licenses,
+                commands::ontology__declare_license,
+                commands::sandbox__begin,
+                commands::sandbox__assert,
+                commands::sandbox__retract,
+                commands::sandbox__entity_get,
+                commands::sandbox__commit,
+                commands::sandbox__discard,
+                commands::quarantine__list,
+                commands::quarantine__approve,
+                commands::quarantine__reject,
+                commands::workspace__list,
+                commands::workspace__switch,
+                commands::workspace__assign,
+                commands::suggestions__get,
+                commands::mentions__propose,
+                commands::mentions__link,
+                commands::mentions__link_new,
+                commands::bulk__update,
+                commands::bulk__replace_literal,
+                commands::import__foundation_db,
+                commands::import__from_url,
+                commands::import__resolve_imports,
+                commands::import__cancel,
+                commands::import__clock_skew,
+                commands::export__origin_as_ontology,
+                commands::export__query_results,
+                commands::export__nquads,
+                commands::export__snapshot,
+                commands::console__execute,
+                commands::console__history,
+                commands::console__favorites,
+                commands::console__save_favorite,
+                commands::console__remove_favorite,
+                commands::charts__series,
+                commands::finance__import,
+                commands::location__import,
+                commands::location__visits,
+                commands::listening__import,
+                commands::browsing__import,
+                commands::messaging__import,
+                commands::person__add_affiliation,
+                commands::person__affiliations,
+                commands::organization__affiliations,
+                commands::parts__of,
+                commands::parts__wholes,
+                commands::parts__shopping_list,
+                commands::attachment__add,
+                commands::photos__import,
+                commands::photos__duplicates,
+                commands::photos__review_duplicate,
+                commands::history__feed,
+                commands::history__diff,
+                commands::entity__history,
+                commands::transaction__undo,
+                commands::transaction__verify,
+                commands::palette__actions,
+                commands::palette__record,
+                commands::api__create_token,
+                commands::api__list_tokens,
+                commands::api__revoke_token,
+                commands::federated__query,
+                commands::outbox__list,
+                commands::outbox__retry,
+                commands::outbox__cancel,
+                commands::storage__literal_limit,
+                commands::storage__set_literal_limit,
+                commands::storage__blob,
+                commands::storage__backfill_typed_columns,
+                commands::storage__usage,
+                commands::stats__ontology,
+                commands::query__range,
+                commands::fact__annotations,
+                commands::fact__annotated,
+                commands::fact__annotate,
+                commands::storage__set_quota,
+                commands::db__vacuum,
+                commands::db__encryption_status,
+                commands::db__unlock,
+                commands::db__set_encryption,
+                commands::integrity__predicate_policy,
+                commands::integrity__set_predicate_policy,
+                commands::validation__consistency,
+                commands::identity__get_public_key,
+                commands::identity__sign,
+                commands::settings__startup,
+                commands::settings__set_startup,
+                commands::sync__start,
+                commands::sync__status,
+                commands::sync__peers,
+                commands::lint__run,
+                commands::lint__fix,
+                commands::origins__list,
+                commands::origins__rename,
+                commands::origins__describe,
+                commands::origins__forget,
+                commands::debug__recent_writes,
+                commands::debug__query_plans,
+                commands::demo__load,
+                commands::demo__start,
+                commands::demo__reset,
+                commands::shortcuts__get_all,
+                commands::log_frontend,
+                commands::get_log_file_path_command,
+                commands::clear_logs
+            ];
+            // Hash the arguments of mutating commands for the write journal (dry
+            // runs are skipped)
+            move |invoke: tauri::ipc::Invoke<tauri::Wry>| {
+                if let tauri::ipc::InvokeBody::Json(args) = invoke.message.payload() {
+                    if api::required_scope(invoke.message.command()) != api::Scope::Read {
+                        eavto::journal::invoked(invoke.message.command(), args);
+                    }
+                }
+                handler(invoke)
+            }
+        })
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
 }
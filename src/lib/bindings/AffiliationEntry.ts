@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * An affiliation, with labels for display
+ */
+export type AffiliationEntry = { iri: string, kind: string, person: string, personLabel: string, organization: string, organizationLabel: string, role: string | null, from: number | null, to: number | null, };
@@ -0,0 +1,8 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { FactAnnotationsData } from "./FactAnnotationsData";
+import type { TripleData } from "./TripleData";
+
+/**
+ * A fact and what is said about it
+ */
+export type AnnotatedFact = { fact: TripleData, annotations: FactAnnotationsData, };
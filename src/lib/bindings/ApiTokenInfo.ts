@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * A personal API token, without its secret
+ */
+export type ApiTokenInfo = { id: number, name: string, prefix: string, scopes: Array<string>, createdAt: number, expiresAt: number | null, revokedAt: number | null, lastUsedAt: number | null, active: boolean, };
@@ -0,0 +1,7 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { PropertyValue } from "./PropertyValue";
+
+/**
+ * A page of an entity's backlinks, most recent first
+ */
+export type BacklinksPage = { backlinks: Array<PropertyValue>, total: number, };
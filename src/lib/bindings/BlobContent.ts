@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Content of a large literal kept in the blob store
+ */
+export type BlobContent = { value: string, datatype: string | null, language: string | null, };
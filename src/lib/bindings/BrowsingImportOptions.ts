@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * How browsing__import reads a history (all optional)
+ */
+export type BrowsingImportOptions = { raw?: boolean, organizations?: { [key in string]?: string }, };
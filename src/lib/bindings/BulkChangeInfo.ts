@@ -0,0 +1,4 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { TripleData } from "./TripleData";
+
+export type BulkChangeInfo = { entity: string, before: Array<TripleData>, after: Array<TripleData>, };
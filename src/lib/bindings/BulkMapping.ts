@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Value change applied by bulk__update
+ */
+export type BulkMapping = { "op": "set", value: string, datatype?: string, language?: string, } | { "op": "replace", find: string, replace: string, } | { "op": "convertUnit", from: string, to: string, };
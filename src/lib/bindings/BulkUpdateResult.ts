@@ -0,0 +1,5 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { BulkChangeInfo } from "./BulkChangeInfo";
+import type { SkippedFactInfo } from "./SkippedFactInfo";
+
+export type BulkUpdateResult = { changes: Array<BulkChangeInfo>, skipped: Array<SkippedFactInfo>, applied: boolean, };
@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Time series for plotting, as parallel arrays (one element per bucket)
+ */
+export type ChartSeries = { starts: number[], counts: number[], min: Array<number>, max: Array<number>, avg: Array<number>, sum: Array<number>, last: Array<number>, unit: string | null, };
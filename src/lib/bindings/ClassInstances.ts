@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { InstanceFacet } from "./InstanceFacet";
+import type { InstanceRow } from "./InstanceRow";
+import type { Thing } from "./Thing";
+
+export type ClassInstances = { columns: Array<Thing>, rows: Array<InstanceRow>, total: number, facets: Array<InstanceFacet>, };
@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * A class of the hierarchy tree
+ */
+export type ClassTreeNode = { iri: string, label: string, icon: string | null, childCount: number, instanceCount: number, children: Array<ClassTreeNode>, };
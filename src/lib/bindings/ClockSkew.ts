@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Clock skew of the transactions an origin received from another device
+ */
+export type ClockSkew = { origin: string, transactions: number, ahead: number, maxAheadMs: number, firstClaimed: number, lastClaimed: number, lastReceived: number, };
@@ -0,0 +1,12 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { DryRunDiff } from "./DryRunDiff";
+
+/**
+ * Result of a write, with the latest transaction committed when it finished
+ * Pass `tx` as `min_tx` to later reads so they observe the write
+ */
+export type Committed<T> = { tx: number, value: T, 
+/**
+ * Set for dry runs: nothing was committed, and this is what would have been
+ */
+dry_run?: DryRunDiff, };
@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { MemoryInfo } from "./MemoryInfo";
+import type { OperatingSystemInfo } from "./OperatingSystemInfo";
+import type { ProcessorInfo } from "./ProcessorInfo";
+
+export type ComputerInfo = { iri: string, hostname: string, operatingSystem: OperatingSystemInfo, processor: ProcessorInfo, memory: MemoryInfo, };
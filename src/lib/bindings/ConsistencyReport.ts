@@ -0,0 +1,7 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { ConsistencyViolation } from "./ConsistencyViolation";
+
+/**
+ * A page of the consistency report
+ */
+export type ConsistencyReport = { violations: Array<ConsistencyViolation>, total: number, };
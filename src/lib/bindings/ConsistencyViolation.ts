@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * An individual contradicting an axiom of the ontology
+ */
+export type ConsistencyViolation = { kind: string, entity: string, axiom: string, values: Array<string>, message: string, };
@@ -0,0 +1,5 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { ConsolePlanStep } from "./ConsolePlanStep";
+import type { ConsoleValue } from "./ConsoleValue";
+
+export type ConsoleResult = { variables: Array<string>, rows: Array<Array<ConsoleValue>>, truncated: boolean, elapsedMs: number, plan: Array<ConsolePlanStep>, };
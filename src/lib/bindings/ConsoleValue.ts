@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * A bound value: an IRI (or "_:" blank node) unless a datatype or language is given
+ */
+export type ConsoleValue = { value: string, datatype?: string, language?: string, };
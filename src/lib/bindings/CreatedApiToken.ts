@@ -0,0 +1,7 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { ApiTokenInfo } from "./ApiTokenInfo";
+
+/**
+ * A newly created token; the secret is shown only this once
+ */
+export type CreatedApiToken = { secret: string, token: ApiTokenInfo, };
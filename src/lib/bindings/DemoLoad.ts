@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * What demo__load asserted
+ */
+export type DemoLoad = { people: number, triples: number, replaced: number, };
@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * A triple in a dry run diff; object is an IRI or a literal's lexical form
+ */
+export type DiffTriple = { subject: string, predicate: string, object: string, };
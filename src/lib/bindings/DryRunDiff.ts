@@ -0,0 +1,8 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { DiffTriple } from "./DiffTriple";
+
+/**
+ * Triples a dry run would have added and retracted: counts, plus the first
+ * few of each
+ */
+export type DryRunDiff = { added: number, retracted: number, addedSample: Array<DiffTriple>, retractedSample: Array<DiffTriple>, };
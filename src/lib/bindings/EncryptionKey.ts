@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Where the database key comes from
+ */
+export type EncryptionKey = { "mode": "passphrase", passphrase: string, } | { "mode": "keychain" };
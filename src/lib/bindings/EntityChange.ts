@@ -0,0 +1,8 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { ChangeValueData } from "./ChangeValueData";
+import type { Thing } from "./Thing";
+
+/**
+ * A change to a property of an entity, ready to show in its timeline
+ */
+export type EntityChange = { tx: number, createdAt: number, origin: string, message: string | null, property: Thing, kind: string, from: ChangeValueData | null, to: ChangeValueData | null, description: string, };
@@ -0,0 +1,10 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { GraphLink } from "./GraphLink";
+import type { GraphNode } from "./GraphNode";
+import type { PropertyValue } from "./PropertyValue";
+import type { Thing } from "./Thing";
+
+/**
+ * Complete entity data with its neighborhood
+ */
+export type EntityData = { id: string, label: string, icon: string | null, comment: string | null, sameAs: Array<string>, types: Array<Thing>, superClasses: Array<Thing>, subClasses: Array<Thing>, instances: Array<Thing>, unionOf: Array<string>, intersectionOf: Array<string>, properties: Array<PropertyValue>, backlinks: Array<PropertyValue>, backlinksTotal: number, nodes: Array<GraphNode>, links: Array<GraphLink>, };
@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Instances kept by class__instances: those with one of the values
+ */
+export type FacetFilterInput = { property: string, values: Array<string>, };
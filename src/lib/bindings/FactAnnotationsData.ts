@@ -0,0 +1,7 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * What is said about a fact: confidence (0 to 1), a note, and the IRIs
+ * of its sources (prov:wasDerivedFrom)
+ */
+export type FactAnnotationsData = { confidence: number | null, note: string | null, derivedFrom: Array<string>, };
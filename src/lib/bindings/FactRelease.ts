@@ -0,0 +1,4 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { OntologyReleaseInfo } from "./OntologyReleaseInfo";
+
+export type FactRelease = { predicate: string, tx: number, release: OntologyReleaseInfo | null, };
@@ -0,0 +1,7 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { TripleData } from "./TripleData";
+
+/**
+ * Triples a public SPARQL endpoint returned for a query
+ */
+export type FederatedQueryResult = { origin: string, triples: Array<TripleData>, cached: boolean, fetchedAt: number, status: string, };
@@ -0,0 +1,7 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * How finance__import reads a statement (all optional; CSV columns are
+ * matched against the header row, case-insensitively)
+ */
+export type FinanceImportProfile = { format?: string, currency?: string, account?: string, delimiter?: string, dateColumn?: string, dateFormat?: string, amountColumn?: string, decimalComma?: boolean, locale?: string, descriptionColumn?: string, referenceColumn?: string, counterpartyColumn?: string, };
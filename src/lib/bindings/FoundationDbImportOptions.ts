@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * What import__foundation_db copies (default: all user origins, current triples)
+ */
+export type FoundationDbImportOptions = { origins?: Array<string>, entities?: Array<string>, originPrefix?: string, includeHistory?: boolean, graph?: string, };
@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Link between nodes (ObjectProperty)
+ */
+export type GraphLink = { source: string, target: string, label: string, };
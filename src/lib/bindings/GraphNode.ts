@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Node in the graph (Class or Individual)
+ */
+export type GraphNode = { id: string, label: string, icon: string | null, group: number, isBrokenRef?: boolean, isLiteral?: boolean, };
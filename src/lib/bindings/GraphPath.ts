@@ -0,0 +1,8 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { GraphLink } from "./GraphLink";
+import type { GraphNode } from "./GraphNode";
+
+/**
+ * How two entities are related
+ */
+export type GraphPath = { nodes: Array<GraphNode>, links: Array<GraphLink>, };
@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * A transaction in the history feed
+ */
+export type HistoryEntry = { tx: number, origin: string, createdAt: number, receivedAt: number | null, message: string | null, tags: Array<string>, assertions: number, };
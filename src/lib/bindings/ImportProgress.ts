@@ -0,0 +1,3 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export type ImportProgress = { stage: string, current_file: string, current: number, total: number, triples: number, job_id: number | null, triples_per_sec: number | null, eta_ms: number | null, };
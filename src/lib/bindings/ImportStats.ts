@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Import statistics
+ */
+export type ImportStats = { file: string, format: string, triples_processed: number, facts_inserted: number, tx_start: number, tx_end: number, };
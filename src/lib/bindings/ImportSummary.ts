@@ -0,0 +1,3 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export type ImportSummary = { run: string | null, files: Array<string>, facts: number, duration_ms: number, warnings: Array<string>, message: string, };
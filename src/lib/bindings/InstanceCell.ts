@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * A value of a cell or a facet
+ */
+export type InstanceCell = { value: string, label: string, isIri: boolean, };
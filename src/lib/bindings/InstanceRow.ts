@@ -0,0 +1,4 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { InstanceCell } from "./InstanceCell";
+
+export type InstanceRow = { iri: string, label: string, icon: string | null, cells: Array<Array<InstanceCell>>, };
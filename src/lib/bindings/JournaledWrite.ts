@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * A write, as journaled
+ */
+export type JournaledWrite = { id: number, command: string, argsHash: string | null, tx: number | null, durationMs: number, error: string | null, recordedAt: number, };
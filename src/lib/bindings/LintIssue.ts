@@ -0,0 +1,7 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { BulkChangeInfo } from "./BulkChangeInfo";
+
+/**
+ * A problem in user data, and how to fix it
+ */
+export type LintIssue = { kind: string, entity: string, property: string | null, message: string, suggestion: string, fix: BulkChangeInfo | null, };
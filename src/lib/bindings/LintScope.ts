@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Part of the user data lint__run checks
+ */
+export type LintScope = { "scope": "all" } | { "scope": "class", iri: string, } | { "scope": "entity", iri: string, };
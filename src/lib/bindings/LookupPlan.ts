@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * How SQLite runs a hot lookup
+ */
+export type LookupPlan = { query: string, sql: string, steps: Array<string>, };
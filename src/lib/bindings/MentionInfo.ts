@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * An entity named in another entity's text
+ */
+export type MentionInfo = { source: string, target: string, property: string, label: string, };
@@ -0,0 +1,3 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export type OntologyInfo = { iri: string, title: string, versionIri: string | null, versionInfo: string | null, license: string | null, creators: Array<string>, imports: Array<string>, sourceFile: string | null, importedAt: number | null, origin: string | null, axiomCount: number, };
@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Header metadata for an exported ontology (all optional)
+ */
+export type OntologyMetadataInput = { iri?: string, versionIri?: string, versionInfo?: string, title?: string, description?: string, creators: Array<string>, license?: string, imports: Array<string>, };
@@ -0,0 +1,3 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export type OntologyReleaseInfo = { iri: string, sourceFile: string, checksum: string, ontology: string | null, versionIri: string | null, importedAt: number, tx: number, tripleCount: number, previousRelease: string | null, };
@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Payload of the "ontology-reloaded" event
+ */
+export type OntologyReload = { file: string, added: number, retracted: number, };
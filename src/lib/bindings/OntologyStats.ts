@@ -0,0 +1,5 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { ClassStats } from "./ClassStats";
+import type { PropertyStats } from "./PropertyStats";
+
+export type OntologyStats = { classes: Array<ClassStats>, properties: Array<PropertyStats>, };
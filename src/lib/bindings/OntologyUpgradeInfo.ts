@@ -0,0 +1,3 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export type OntologyUpgradeInfo = { iri: string, sourceFile: string, fromRelease: string | null, toRelease: string, added: number, retracted: number, tx: number, importedAt: number, };
@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * An origin and what it holds, for the provenance dashboard
+ */
+export type OriginInfo = { name: string, description: string | null, status: string, tripleCount: number, firstTx: number | null, lastTx: number | null, firstAt: number | null, lastAt: number | null, };
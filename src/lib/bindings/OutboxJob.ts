@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Outbound work waiting for the network
+ */
+export type OutboxJob = { id: number, kind: string, payload: unknown, status: string, attempts: number, nextAttemptAt: number, lastError: string | null, createdAt: number, completedAt: number | null, };
@@ -0,0 +1,7 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { PaletteActionKind } from "./PaletteActionKind";
+
+/**
+ * An action of the command palette
+ */
+export type PaletteActionInfo = { id: string, kind: PaletteActionKind, label: string, target: string, uses: number, lastUsed: number | null, };
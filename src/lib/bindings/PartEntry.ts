@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * An entity reached through part-whole links
+ */
+export type PartEntry = { iri: string, label: string, icon: string | null, depth: number, };
@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Another instance found on the network
+ */
+export type Peer = { id: string, name: string, addresses: Array<string>, head: number | null, cursor: number, lastSeen: number, lastSynced: number | null, };
@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * A near-duplicate photo to review
+ */
+export type PhotoDuplicate = { photo: string, photoLabel: string, original: string, originalLabel: string, distance: number | null, };
@@ -0,0 +1,3 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export type PropertyValue = { property: string, propertyLabel: string, propertyComment: string | null, value: string, valueLabel: string | null, valueIcon: string | null, isObjectProperty: boolean, sourceClass: string | null, sourceClassLabel: string | null, unit: string | null, unitLabel: string | null, constraints: Array<string>, };
@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * A property value for entity__create
+ */
+export type PropertyValueInput = { value: string | number | boolean, unit?: string, language?: string, };
@@ -0,0 +1,4 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { TripleData } from "./TripleData";
+
+export type QuarantineSummaryInfo = { origin: string, tripleCount: number, entityCount: number, classes: Array<string>, samples: Array<TripleData>, receivedAt: number | null, };
@@ -0,0 +1,4 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { SourceLicenseInfo } from "./SourceLicenseInfo";
+
+export type RdfXmlExport = { content: string, sources: Array<SourceLicenseInfo>, warnings: Array<string>, };
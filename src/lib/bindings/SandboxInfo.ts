@@ -0,0 +1,4 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { TripleData } from "./TripleData";
+
+export type SandboxInfo = { handle: string, origin: string, baseTx: number, additions: Array<TripleData>, retractions: Array<TripleData>, };
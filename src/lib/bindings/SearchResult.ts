@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Search result for entities
+ */
+export type SearchResult = { id: string, label: string, icon: string | null, type: string, typeIri: string | null, snippet: string | null, attachedTo: string | null, };
@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { ComputerInfo } from "./ComputerInfo";
+import type { FoundationInfo } from "./FoundationInfo";
+import type { UserInfo } from "./UserInfo";
+
+export type SetupResult = { alreadySetup: boolean, user: UserInfo, computer: ComputerInfo, foundation: FoundationInfo, };
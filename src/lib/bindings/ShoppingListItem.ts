@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * A line of a shopping list
+ */
+export type ShoppingListItem = { product: string, productLabel: string, unit: string | null, unitLabel: string | null, needed: number, onHand: number, toBuy: number, };
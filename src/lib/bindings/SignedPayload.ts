@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * A payload signed with the local identity
+ */
+export type SignedPayload = { publicKey: string, signature: string, };
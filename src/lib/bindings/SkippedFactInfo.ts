@@ -0,0 +1,7 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { TripleData } from "./TripleData";
+
+/**
+ * A fact left unchanged, and why (e.g. the new value isn't a valid xsd:date)
+ */
+export type SkippedFactInfo = { triple: TripleData, reason: string, };
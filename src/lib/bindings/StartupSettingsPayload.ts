@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * How the app launches (applies from the next launch)
+ */
+export type StartupSettingsPayload = { skipStats: boolean, deferCollectors: boolean, integrityCheck: string, watchOntology: boolean, };
@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Storage used by an origin or a workspace
+ */
+export type StorageUsageInfo = { name: string, rows: number, activeRows: number, estimatedBytes: number, quotaBytes: number | null, overQuota: boolean, };
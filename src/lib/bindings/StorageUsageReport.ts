@@ -0,0 +1,7 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { StorageUsageInfo } from "./StorageUsageInfo";
+
+/**
+ * Storage accounting, largest first
+ */
+export type StorageUsageReport = { origins: Array<StorageUsageInfo>, workspaces: Array<StorageUsageInfo>, };
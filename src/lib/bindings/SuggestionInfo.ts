@@ -0,0 +1,3 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export type SuggestionInfo = { kind: string, message: string, count: number, entities: Array<string>, predicates: Array<string>, value: string | null, existing: string | null, };
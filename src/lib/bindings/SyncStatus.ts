@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * State of the sync service
+ */
+export type SyncStatus = { running: boolean, peerId: string | null, port: number | null, peers: number, received: number, lastSyncAt: number | null, lastError: string | null, };
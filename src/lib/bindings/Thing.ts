@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Represents owl:Thing - basic entity with metadata only
+ */
+export type Thing = { iri: string, label: string, icon: string | null, };
@@ -0,0 +1,7 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { TripleData } from "./TripleData";
+
+/**
+ * Changes between two transactions
+ */
+export type TransactionDiff = { fromTx: number, toTx: number, added: Array<TripleData>, retracted: Array<TripleData>, };
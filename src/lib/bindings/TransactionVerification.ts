@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Signature check of a transaction
+ */
+export type TransactionVerification = { tx: number, status: string, signer: string | null, };
@@ -0,0 +1,7 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * A triple as sent to and from the frontend
+ * object is an IRI (or "_:" blank node) unless a datatype or language is given
+ */
+export type TripleData = { subject: string, predicate: string, object: string, datatype?: string, language?: string, };
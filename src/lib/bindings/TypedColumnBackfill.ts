@@ -0,0 +1,7 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { UnfixableLiteral } from "./UnfixableLiteral";
+
+/**
+ * What a typed column backfill did
+ */
+export type TypedColumnBackfill = { scanned: number, fixed: number, unfixable: Array<UnfixableLiteral>, };
@@ -0,0 +1,7 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * A literal whose typed column couldn't be filled: its value isn't valid
+ * for its datatype
+ */
+export type UnfixableLiteral = { subject: string, predicate: string, value: string, datatype: string, tx: number, };
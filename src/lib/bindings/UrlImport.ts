@@ -0,0 +1,7 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { ImportStats } from "./ImportStats";
+
+/**
+ * What import__from_url did
+ */
+export type UrlImport = { url: string, origin: string, etag: string | null, stats: ImportStats | null, error: string | null, dependencies: Array<UrlImport>, };